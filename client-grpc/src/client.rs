@@ -27,6 +27,16 @@ cfg_if::cfg_if! {
 impl crate::service::Client<RpcServiceClient<Channel>> for AssetsClient {
     type ReadyRequest = ReadyRequest;
     type ReadyResponse = ReadyResponse;
+    type GroupAvailabilityRequest = GroupAvailabilityRequest;
+    type GroupAvailabilityResponse = GroupAvailabilityResponse;
+    type VertiportWeatherMinimumsRequest = VertiportWeatherMinimumsRequest;
+    type VertiportWeatherMinimumsResponse = VertiportWeatherMinimumsResponse;
+    type RegisterVehicleRequest = RegisterVehicleRequest;
+    type RegisterVehicleResponse = RegisterVehicleResponse;
+    type UpdateVertipadOccupancyRequest = UpdateVertipadOccupancyRequest;
+    type UpdateVertipadOccupancyResponse = UpdateVertipadOccupancyResponse;
+    type SetAssetStatusRequest = SetAssetStatusRequest;
+    type SetAssetStatusResponse = SetAssetStatusResponse;
 
     async fn is_ready(
         &self,
@@ -37,6 +47,60 @@ impl crate::service::Client<RpcServiceClient<Channel>> for AssetsClient {
         grpc_debug!("request: {:?}", request);
         self.get_client().await?.is_ready(request).await
     }
+
+    async fn get_group_availability(
+        &self,
+        request: Self::GroupAvailabilityRequest,
+    ) -> Result<tonic::Response<Self::GroupAvailabilityResponse>, tonic::Status> {
+        grpc_info!("{} client.", self.get_name());
+        grpc_debug!("request: {:?}", request);
+        self.get_client()
+            .await?
+            .get_group_availability(request)
+            .await
+    }
+
+    async fn get_vertiport_weather_minimums(
+        &self,
+        request: Self::VertiportWeatherMinimumsRequest,
+    ) -> Result<tonic::Response<Self::VertiportWeatherMinimumsResponse>, tonic::Status> {
+        grpc_info!("{} client.", self.get_name());
+        grpc_debug!("request: {:?}", request);
+        self.get_client()
+            .await?
+            .get_vertiport_weather_minimums(request)
+            .await
+    }
+
+    async fn register_vehicle(
+        &self,
+        request: Self::RegisterVehicleRequest,
+    ) -> Result<tonic::Response<Self::RegisterVehicleResponse>, tonic::Status> {
+        grpc_info!("{} client.", self.get_name());
+        grpc_debug!("request: {:?}", request);
+        self.get_client().await?.register_vehicle(request).await
+    }
+
+    async fn update_vertipad_occupancy(
+        &self,
+        request: Self::UpdateVertipadOccupancyRequest,
+    ) -> Result<tonic::Response<Self::UpdateVertipadOccupancyResponse>, tonic::Status> {
+        grpc_info!("{} client.", self.get_name());
+        grpc_debug!("request: {:?}", request);
+        self.get_client()
+            .await?
+            .update_vertipad_occupancy(request)
+            .await
+    }
+
+    async fn set_asset_status(
+        &self,
+        request: Self::SetAssetStatusRequest,
+    ) -> Result<tonic::Response<Self::SetAssetStatusResponse>, tonic::Status> {
+        grpc_info!("{} client.", self.get_name());
+        grpc_debug!("request: {:?}", request);
+        self.get_client().await?.set_asset_status(request).await
+    }
 }
 
 #[cfg(feature = "stub_client")]
@@ -44,6 +108,16 @@ impl crate::service::Client<RpcServiceClient<Channel>> for AssetsClient {
 impl crate::service::Client<RpcServiceClient<Channel>> for AssetsClient {
     type ReadyRequest = ReadyRequest;
     type ReadyResponse = ReadyResponse;
+    type GroupAvailabilityRequest = GroupAvailabilityRequest;
+    type GroupAvailabilityResponse = GroupAvailabilityResponse;
+    type VertiportWeatherMinimumsRequest = VertiportWeatherMinimumsRequest;
+    type VertiportWeatherMinimumsResponse = VertiportWeatherMinimumsResponse;
+    type RegisterVehicleRequest = RegisterVehicleRequest;
+    type RegisterVehicleResponse = RegisterVehicleResponse;
+    type UpdateVertipadOccupancyRequest = UpdateVertipadOccupancyRequest;
+    type UpdateVertipadOccupancyResponse = UpdateVertipadOccupancyResponse;
+    type SetAssetStatusRequest = SetAssetStatusRequest;
+    type SetAssetStatusResponse = SetAssetStatusResponse;
 
     async fn is_ready(
         &self,
@@ -53,6 +127,63 @@ impl crate::service::Client<RpcServiceClient<Channel>> for AssetsClient {
         grpc_debug!("(MOCK) request: {:?}", request);
         Ok(tonic::Response::new(ReadyResponse { ready: true }))
     }
+
+    async fn get_group_availability(
+        &self,
+        request: Self::GroupAvailabilityRequest,
+    ) -> Result<tonic::Response<Self::GroupAvailabilityResponse>, tonic::Status> {
+        grpc_warn!("(MOCK) {} client.", self.get_name());
+        grpc_debug!("(MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GroupAvailabilityResponse {
+            constrained: false,
+            available: true,
+            windows: vec![AvailabilityWindow {
+                start: request.from,
+                end: request.to,
+            }],
+        }))
+    }
+
+    async fn get_vertiport_weather_minimums(
+        &self,
+        request: Self::VertiportWeatherMinimumsRequest,
+    ) -> Result<tonic::Response<Self::VertiportWeatherMinimumsResponse>, tonic::Status> {
+        grpc_warn!("(MOCK) {} client.", self.get_name());
+        grpc_debug!("(MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(VertiportWeatherMinimumsResponse {
+            configured: false,
+            minimums: None,
+        }))
+    }
+
+    async fn register_vehicle(
+        &self,
+        request: Self::RegisterVehicleRequest,
+    ) -> Result<tonic::Response<Self::RegisterVehicleResponse>, tonic::Status> {
+        grpc_warn!("(MOCK) {} client.", self.get_name());
+        grpc_debug!("(MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(RegisterVehicleResponse {
+            id: "00000000-0000-0000-0000-000000000000".to_string(),
+        }))
+    }
+
+    async fn update_vertipad_occupancy(
+        &self,
+        request: Self::UpdateVertipadOccupancyRequest,
+    ) -> Result<tonic::Response<Self::UpdateVertipadOccupancyResponse>, tonic::Status> {
+        grpc_warn!("(MOCK) {} client.", self.get_name());
+        grpc_debug!("(MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(UpdateVertipadOccupancyResponse {}))
+    }
+
+    async fn set_asset_status(
+        &self,
+        request: Self::SetAssetStatusRequest,
+    ) -> Result<tonic::Response<Self::SetAssetStatusResponse>, tonic::Status> {
+        grpc_warn!("(MOCK) {} client.", self.get_name());
+        grpc_debug!("(MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(SetAssetStatusResponse {}))
+    }
 }
 
 #[cfg(test)]
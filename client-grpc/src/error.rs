@@ -0,0 +1,123 @@
+//! Typed errors of the assets service
+//!
+//! Errors returned by the server carry a `google.rpc.ErrorInfo` detail with
+//! the [`ErrorReason`] of the error. [`AssetsError`] decodes these details
+//! from a [`tonic::Status`] so callers can match on the reason instead of
+//! parsing the message.
+
+pub use crate::client::ErrorReason;
+
+use std::fmt::{self, Display, Formatter};
+use tonic::{Code, Status};
+use tonic_types::StatusExt;
+
+/// Domain of the `google.rpc.ErrorInfo` details returned by the server
+pub const ERROR_DOMAIN: &str = "svc-assets";
+
+/// An invalid field of a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldViolation {
+    /// Path of the field
+    pub field: String,
+    /// Why the value of the field is invalid
+    pub description: String,
+}
+
+/// An error returned by the assets service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetsError {
+    /// gRPC status code of the error
+    pub code: Code,
+    /// Reason of the error, [`ErrorReason::Unspecified`] if the status did
+    /// not come from the assets service
+    pub reason: ErrorReason,
+    /// Description of the error
+    pub message: String,
+    /// Invalid fields of the request
+    pub field_violations: Vec<FieldViolation>,
+}
+
+impl Display for AssetsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.reason.as_str_name(), self.message)
+    }
+}
+
+impl std::error::Error for AssetsError {}
+
+impl From<&Status> for AssetsError {
+    fn from(status: &Status) -> Self {
+        let details = status.get_error_details();
+        let reason = details
+            .error_info()
+            .filter(|info| info.domain == ERROR_DOMAIN)
+            .and_then(|info| ErrorReason::from_str_name(&info.reason))
+            .unwrap_or(ErrorReason::Unspecified);
+        let field_violations = details
+            .bad_request()
+            .map(|bad_request| {
+                bad_request
+                    .field_violations
+                    .into_iter()
+                    .map(|violation| FieldViolation {
+                        field: violation.field,
+                        description: violation.description,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            code: status.code(),
+            reason,
+            message: status.message().to_string(),
+            field_violations,
+        }
+    }
+}
+
+impl From<Status> for AssetsError {
+    fn from(status: Status) -> Self {
+        Self::from(&status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tonic_types::ErrorDetails;
+
+    #[test]
+    fn test_assets_error_from_status() {
+        let mut details =
+            ErrorDetails::with_error_info("INVALID_ARGUMENT", ERROR_DOMAIN, HashMap::new());
+        details.add_bad_request_violation("asset_id", "must be a valid UUID");
+        let status = Status::with_error_details(
+            Code::InvalidArgument,
+            "asset_id: must be a valid UUID",
+            details,
+        );
+
+        let error = AssetsError::from(status);
+        assert_eq!(error.code, Code::InvalidArgument);
+        assert_eq!(error.reason, ErrorReason::InvalidArgument);
+        assert_eq!(
+            error.field_violations,
+            vec![FieldViolation {
+                field: "asset_id".to_string(),
+                description: "must be a valid UUID".to_string(),
+            }]
+        );
+
+        // details of another domain are ignored
+        let details = ErrorDetails::with_error_info("ASSET_NOT_FOUND", "other", HashMap::new());
+        let status = Status::with_error_details(Code::NotFound, "not found", details);
+        assert_eq!(AssetsError::from(status).reason, ErrorReason::Unspecified);
+
+        let error = AssetsError::from(Status::unavailable("connection refused"));
+        assert_eq!(error.code, Code::Unavailable);
+        assert_eq!(error.reason, ErrorReason::Unspecified);
+        assert!(error.field_violations.is_empty());
+    }
+}
@@ -15,12 +15,274 @@ pub struct ReadyResponse {
     #[prost(bool, tag = "1")]
     pub ready: bool,
 }
+/// Group Availability Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupAvailabilityRequest {
+    /// The UUID of the asset group
+    #[prost(string, tag = "1")]
+    pub group_id: ::prost::alloc::string::String,
+    /// Start of the time range
+    #[prost(message, optional, tag = "2")]
+    pub from: ::core::option::Option<::prost_types::Timestamp>,
+    /// End of the time range
+    #[prost(message, optional, tag = "3")]
+    pub to: ::core::option::Option<::prost_types::Timestamp>,
+}
+/// Window of time
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AvailabilityWindow {
+    /// Start of the window
+    #[prost(message, optional, tag = "1")]
+    pub start: ::core::option::Option<::prost_types::Timestamp>,
+    /// End of the window
+    #[prost(message, optional, tag = "2")]
+    pub end: ::core::option::Option<::prost_types::Timestamp>,
+}
+/// Group Availability Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupAvailabilityResponse {
+    /// True if the group has a schedule constraining its assets
+    #[prost(bool, tag = "1")]
+    pub constrained: bool,
+    /// True if the assets of the group can be used for the whole time range
+    #[prost(bool, tag = "2")]
+    pub available: bool,
+    /// Windows within the time range the assets of the group can be used
+    #[prost(message, repeated, tag = "3")]
+    pub windows: ::prost::alloc::vec::Vec<AvailabilityWindow>,
+}
+/// Vertiport Weather Minimums Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VertiportWeatherMinimumsRequest {
+    /// The UUID of the vertiport
+    #[prost(string, tag = "1")]
+    pub vertiport_id: ::prost::alloc::string::String,
+}
+/// Operational weather minimums of a vertiport
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WeatherMinimums {
+    /// Maximum wind speed, in meters per second
+    #[prost(double, tag = "1")]
+    pub max_wind_speed_mps: f64,
+    /// Minimum visibility, in meters
+    #[prost(double, tag = "2")]
+    pub min_visibility_m: f64,
+    /// Maximum precipitation, in millimeters per hour
+    #[prost(double, tag = "3")]
+    pub max_precipitation_mm_per_hour: f64,
+    /// When the minimums were last changed
+    #[prost(message, optional, tag = "4")]
+    pub updated_at: ::core::option::Option<::prost_types::Timestamp>,
+}
+/// Vertiport Weather Minimums Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VertiportWeatherMinimumsResponse {
+    /// True if weather minimums are configured for the vertiport
+    #[prost(bool, tag = "1")]
+    pub configured: bool,
+    /// The weather minimums of the vertiport, if configured
+    #[prost(message, optional, tag = "2")]
+    pub minimums: ::core::option::Option<WeatherMinimums>,
+}
+/// Register Vehicle Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RegisterVehicleRequest {
+    /// The UUID of the vehicle model
+    #[prost(string, tag = "1")]
+    pub vehicle_model_id: ::prost::alloc::string::String,
+    /// The serial number of the vehicle
+    #[prost(string, tag = "2")]
+    pub serial_number: ::prost::alloc::string::String,
+    /// The registration number of the vehicle
+    #[prost(string, tag = "3")]
+    pub registration_number: ::prost::alloc::string::String,
+    /// Optional description of the vehicle
+    #[prost(string, optional, tag = "4")]
+    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    /// Optional UUID of the asset group of the vehicle
+    #[prost(string, optional, tag = "5")]
+    pub asset_group_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Optional RRULE data string of the vehicle's availability
+    #[prost(string, optional, tag = "6")]
+    pub schedule: ::core::option::Option<::prost::alloc::string::String>,
+    /// Optional UUID of the hangar the vehicle is stored at
+    #[prost(string, optional, tag = "7")]
+    pub hangar_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Optional UUID of the hangar bay the vehicle is stored at
+    #[prost(string, optional, tag = "8")]
+    pub hangar_bay_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Optional timestamp of the last maintenance of the vehicle
+    #[prost(message, optional, tag = "9")]
+    pub last_maintenance: ::core::option::Option<::prost_types::Timestamp>,
+    /// Optional timestamp of the next maintenance of the vehicle
+    #[prost(message, optional, tag = "10")]
+    pub next_maintenance: ::core::option::Option<::prost_types::Timestamp>,
+    /// The UUID of the operator registering and owning the vehicle, counted
+    /// towards its aircraft quota
+    #[prost(string, tag = "11")]
+    pub owner_id: ::prost::alloc::string::String,
+}
+/// Register Vehicle Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RegisterVehicleResponse {
+    /// The UUID of the new vehicle
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+/// Update Vertipad Occupancy Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateVertipadOccupancyRequest {
+    /// The UUID of the vertipad
+    #[prost(string, tag = "1")]
+    pub vertipad_id: ::prost::alloc::string::String,
+    /// True if the vertipad is occupied
+    #[prost(bool, tag = "2")]
+    pub occupied: bool,
+}
+/// Update Vertipad Occupancy Response object
+///
+/// No fields
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateVertipadOccupancyResponse {}
+/// Set Asset Status Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetAssetStatusRequest {
+    /// The UUID of the aircraft
+    #[prost(string, tag = "1")]
+    pub asset_id: ::prost::alloc::string::String,
+    /// The new status of the aircraft
+    #[prost(enumeration = "AssetStatus", tag = "2")]
+    pub status: i32,
+}
+/// Set Asset Status Response object
+///
+/// No fields
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetAssetStatusResponse {}
+/// Status of an asset
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum AssetStatus {
+    /// The asset is available for use
+    Available = 0,
+    /// The asset is unavailable for use
+    Unavailable = 1,
+    /// The asset is only available for emergencies
+    Emergency = 2,
+}
+impl AssetStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            AssetStatus::Available => "ASSET_STATUS_AVAILABLE",
+            AssetStatus::Unavailable => "ASSET_STATUS_UNAVAILABLE",
+            AssetStatus::Emergency => "ASSET_STATUS_EMERGENCY",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ASSET_STATUS_AVAILABLE" => Some(Self::Available),
+            "ASSET_STATUS_UNAVAILABLE" => Some(Self::Unavailable),
+            "ASSET_STATUS_EMERGENCY" => Some(Self::Emergency),
+            _ => None,
+        }
+    }
+}
+/// Reason of an error returned by the service.
+///
+/// Errors carry a `google.rpc.ErrorInfo` detail with the name of the reason
+/// and the `svc-assets` domain, invalid arguments also carry a
+/// `google.rpc.BadRequest` detail listing the invalid fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ErrorReason {
+    /// Unspecified error reason
+    Unspecified = 0,
+    /// The requested asset does not exist
+    AssetNotFound = 1,
+    /// One or more fields of the request are invalid
+    InvalidArgument = 2,
+    /// The asset is owned or held by another party than the caller
+    OwnershipViolation = 3,
+    /// The caller did not present a client certificate
+    PeerUnauthenticated = 4,
+    /// The caller is not allowed to call the method
+    PeerNotAllowed = 5,
+    /// The asset is not in a state allowing the change
+    AssetConflict = 6,
+    /// The storage service is not available
+    StorageUnavailable = 7,
+    /// A call to the storage service timed out
+    StorageTimeout = 8,
+    /// An unexpected internal error occurred
+    Internal = 9,
+    /// The operator reached its quota of assets
+    QuotaExceeded = 10,
+    /// The service is in read-only mode and does not accept changes
+    ReadOnly = 11,
+}
+impl ErrorReason {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ErrorReason::Unspecified => "ERROR_REASON_UNSPECIFIED",
+            ErrorReason::AssetNotFound => "ASSET_NOT_FOUND",
+            ErrorReason::InvalidArgument => "INVALID_ARGUMENT",
+            ErrorReason::OwnershipViolation => "OWNERSHIP_VIOLATION",
+            ErrorReason::PeerUnauthenticated => "PEER_UNAUTHENTICATED",
+            ErrorReason::PeerNotAllowed => "PEER_NOT_ALLOWED",
+            ErrorReason::AssetConflict => "ASSET_CONFLICT",
+            ErrorReason::StorageUnavailable => "STORAGE_UNAVAILABLE",
+            ErrorReason::StorageTimeout => "STORAGE_TIMEOUT",
+            ErrorReason::Internal => "INTERNAL",
+            ErrorReason::QuotaExceeded => "QUOTA_EXCEEDED",
+            ErrorReason::ReadOnly => "READ_ONLY",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ERROR_REASON_UNSPECIFIED" => Some(Self::Unspecified),
+            "ASSET_NOT_FOUND" => Some(Self::AssetNotFound),
+            "INVALID_ARGUMENT" => Some(Self::InvalidArgument),
+            "OWNERSHIP_VIOLATION" => Some(Self::OwnershipViolation),
+            "PEER_UNAUTHENTICATED" => Some(Self::PeerUnauthenticated),
+            "PEER_NOT_ALLOWED" => Some(Self::PeerNotAllowed),
+            "ASSET_CONFLICT" => Some(Self::AssetConflict),
+            "STORAGE_UNAVAILABLE" => Some(Self::StorageUnavailable),
+            "STORAGE_TIMEOUT" => Some(Self::StorageTimeout),
+            "INTERNAL" => Some(Self::Internal),
+            "QUOTA_EXCEEDED" => Some(Self::QuotaExceeded),
+            "READ_ONLY" => Some(Self::ReadOnly),
+            _ => None,
+        }
+    }
+}
 /// Generated client implementations.
 #[cfg(not(tarpaulin_include))]
 pub mod rpc_service_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
     use tonic::codegen::http::Uri;
+    use tonic::codegen::*;
     /// Heartbeat
     #[derive(Debug, Clone)]
     pub struct RpcServiceClient<T> {
@@ -65,9 +327,8 @@ pub mod rpc_service_client {
                     <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
                 >,
             >,
-            <T as tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-            >>::Error: Into<StdError> + Send + Sync,
+            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
+                Into<StdError> + Send + Sync,
         {
             RpcServiceClient::new(InterceptedService::new(inner, interceptor))
         }
@@ -107,19 +368,126 @@ pub mod rpc_service_client {
             &mut self,
             request: impl tonic::IntoRequest<super::ReadyRequest>,
         ) -> std::result::Result<tonic::Response<super::ReadyResponse>, tonic::Status> {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::new(
-                        tonic::Code::Unknown,
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static("/grpc.RpcService/isReady");
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("grpc.RpcService", "isReady"));
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "isReady"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Get the windows the assets of a group can be used, so the scheduler
+        /// can enforce the group schedule
+        pub async fn get_group_availability(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GroupAvailabilityRequest>,
+        ) -> std::result::Result<tonic::Response<super::GroupAvailabilityResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/grpc.RpcService/getGroupAvailability");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "getGroupAvailability"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Get the weather minimums of a vertiport, so the scheduler can decide
+        /// if flights to and from it can go ahead
+        pub async fn get_vertiport_weather_minimums(
+            &mut self,
+            request: impl tonic::IntoRequest<super::VertiportWeatherMinimumsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::VertiportWeatherMinimumsResponse>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/getVertiportWeatherMinimums",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "grpc.RpcService",
+                "getVertiportWeatherMinimums",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Register a vehicle, returns the id of the new vehicle
+        pub async fn register_vehicle(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RegisterVehicleRequest>,
+        ) -> std::result::Result<tonic::Response<super::RegisterVehicleResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/grpc.RpcService/registerVehicle");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "registerVehicle"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Change the occupancy of a vertipad, recorded as a scheduler reservation
+        pub async fn update_vertipad_occupancy(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateVertipadOccupancyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateVertipadOccupancyResponse>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/grpc.RpcService/updateVertipadOccupancy");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "grpc.RpcService",
+                "updateVertipadOccupancy",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Set the status of an aircraft
+        pub async fn set_asset_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetAssetStatusRequest>,
+        ) -> std::result::Result<tonic::Response<super::SetAssetStatusResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/grpc.RpcService/setAssetStatus");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "setAssetStatus"));
             self.inner.unary(req, path, codec).await
         }
     }
@@ -1,6 +1,7 @@
 //! Re-export of used objects
 
 pub use super::client as assets;
+pub use super::error::{AssetsError, ErrorReason};
 pub use super::service::Client as AssetsServiceClient;
 pub use assets::AssetsClient;
 
@@ -11,6 +11,26 @@ where
     type ReadyRequest;
     /// The type expected for ReadyResponse structs.
     type ReadyResponse;
+    /// The type expected for GroupAvailabilityRequest structs.
+    type GroupAvailabilityRequest;
+    /// The type expected for GroupAvailabilityResponse structs.
+    type GroupAvailabilityResponse;
+    /// The type expected for VertiportWeatherMinimumsRequest structs.
+    type VertiportWeatherMinimumsRequest;
+    /// The type expected for VertiportWeatherMinimumsResponse structs.
+    type VertiportWeatherMinimumsResponse;
+    /// The type expected for RegisterVehicleRequest structs.
+    type RegisterVehicleRequest;
+    /// The type expected for RegisterVehicleResponse structs.
+    type RegisterVehicleResponse;
+    /// The type expected for UpdateVertipadOccupancyRequest structs.
+    type UpdateVertipadOccupancyRequest;
+    /// The type expected for UpdateVertipadOccupancyResponse structs.
+    type UpdateVertipadOccupancyResponse;
+    /// The type expected for SetAssetStatusRequest structs.
+    type SetAssetStatusRequest;
+    /// The type expected for SetAssetStatusResponse structs.
+    type SetAssetStatusResponse;
 
     /// Returns a [`tonic::Response`] containing a [`ReadyResponse`](Self::ReadyResponse)
     /// Takes an [`ReadyRequest`](Self::ReadyRequest).
@@ -38,4 +58,198 @@ where
         &self,
         request: Self::ReadyRequest,
     ) -> Result<tonic::Response<Self::ReadyResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a
+    /// [`GroupAvailabilityResponse`](Self::GroupAvailabilityResponse)
+    /// Takes a [`GroupAvailabilityRequest`](Self::GroupAvailabilityRequest).
+    ///
+    /// Used by svc-scheduler to enforce the schedule of an asset group on
+    /// all its assets.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`tonic::Code::InvalidArgument`] if the
+    /// group id or time range is invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use std::time::{Duration, SystemTime};
+    /// use svc_assets_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = AssetsClient::new_client(&host, port, "assets");
+    ///     let from = SystemTime::now();
+    ///     let response = client
+    ///         .get_group_availability(assets::GroupAvailabilityRequest {
+    ///             group_id: "00000000-0000-0000-0000-000000000000".to_string(),
+    ///             from: Some(from.into()),
+    ///             to: Some((from + Duration::from_secs(7200)).into()),
+    ///         })
+    ///         .await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_group_availability(
+        &self,
+        request: Self::GroupAvailabilityRequest,
+    ) -> Result<tonic::Response<Self::GroupAvailabilityResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a
+    /// [`VertiportWeatherMinimumsResponse`](Self::VertiportWeatherMinimumsResponse)
+    /// Takes a [`VertiportWeatherMinimumsRequest`](Self::VertiportWeatherMinimumsRequest).
+    ///
+    /// Used by svc-scheduler for its go/no-go decisions on flights to and
+    /// from the vertiport. `configured` is `false` if no weather minimums are
+    /// configured for the vertiport.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`tonic::Code::InvalidArgument`] if the
+    /// vertiport id is invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_assets_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = AssetsClient::new_client(&host, port, "assets");
+    ///     let response = client
+    ///         .get_vertiport_weather_minimums(assets::VertiportWeatherMinimumsRequest {
+    ///             vertiport_id: "00000000-0000-0000-0000-000000000000".to_string(),
+    ///         })
+    ///         .await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_vertiport_weather_minimums(
+        &self,
+        request: Self::VertiportWeatherMinimumsRequest,
+    ) -> Result<tonic::Response<Self::VertiportWeatherMinimumsResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a
+    /// [`RegisterVehicleResponse`](Self::RegisterVehicleResponse)
+    /// Takes a [`RegisterVehicleRequest`](Self::RegisterVehicleRequest).
+    ///
+    /// Only allowed for trusted services presenting a client certificate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`tonic::Code::Unauthenticated`] or
+    /// [`tonic::Code::PermissionDenied`] if the caller is not allowed to
+    /// register vehicles, [`tonic::Code::InvalidArgument`] if the vehicle
+    /// or its owner is invalid, or [`tonic::Code::ResourceExhausted`] if the
+    /// owner reached its aircraft quota.
+    ///
+    /// Returns [`tonic::Code::Unavailable`] while the service is in read-only
+    /// mode.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_assets_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = AssetsClient::new_client(&host, port, "assets");
+    ///     let response = client
+    ///         .register_vehicle(assets::RegisterVehicleRequest {
+    ///             vehicle_model_id: "00000000-0000-0000-0000-000000000000".to_string(),
+    ///             serial_number: "SN-1".to_string(),
+    ///             registration_number: "N12345".to_string(),
+    ///             owner_id: "00000000-0000-0000-0000-000000000001".to_string(),
+    ///             ..Default::default()
+    ///         })
+    ///         .await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn register_vehicle(
+        &self,
+        request: Self::RegisterVehicleRequest,
+    ) -> Result<tonic::Response<Self::RegisterVehicleResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing an
+    /// [`UpdateVertipadOccupancyResponse`](Self::UpdateVertipadOccupancyResponse)
+    /// Takes an [`UpdateVertipadOccupancyRequest`](Self::UpdateVertipadOccupancyRequest).
+    ///
+    /// Used by svc-scheduler to reserve and release vertipads.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`tonic::Code::Unauthenticated`] or
+    /// [`tonic::Code::PermissionDenied`] if the caller is not allowed to
+    /// change the occupancy, or [`tonic::Code::InvalidArgument`] if the
+    /// vertipad id is invalid.
+    ///
+    /// Returns [`tonic::Code::Unavailable`] while the service is in read-only
+    /// mode.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_assets_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = AssetsClient::new_client(&host, port, "assets");
+    ///     let response = client
+    ///         .update_vertipad_occupancy(assets::UpdateVertipadOccupancyRequest {
+    ///             vertipad_id: "00000000-0000-0000-0000-000000000000".to_string(),
+    ///             occupied: true,
+    ///         })
+    ///         .await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn update_vertipad_occupancy(
+        &self,
+        request: Self::UpdateVertipadOccupancyRequest,
+    ) -> Result<tonic::Response<Self::UpdateVertipadOccupancyResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a
+    /// [`SetAssetStatusResponse`](Self::SetAssetStatusResponse)
+    /// Takes a [`SetAssetStatusRequest`](Self::SetAssetStatusRequest).
+    ///
+    /// Sets the status of an aircraft, for example to ground it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`tonic::Code::Unauthenticated`] or
+    /// [`tonic::Code::PermissionDenied`] if the caller is not allowed to set
+    /// the status, or [`tonic::Code::NotFound`] if the aircraft does not
+    /// exist.
+    ///
+    /// Returns [`tonic::Code::Unavailable`] while the service is in read-only
+    /// mode.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_assets_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = AssetsClient::new_client(&host, port, "assets");
+    ///     let response = client
+    ///         .set_asset_status(assets::SetAssetStatusRequest {
+    ///             asset_id: "00000000-0000-0000-0000-000000000000".to_string(),
+    ///             status: assets::AssetStatus::Unavailable.into(),
+    ///         })
+    ///         .await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn set_asset_status(
+        &self,
+        request: Self::SetAssetStatusRequest,
+    ) -> Result<tonic::Response<Self::SetAssetStatusResponse>, tonic::Status>;
 }
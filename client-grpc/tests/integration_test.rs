@@ -49,4 +49,57 @@ async fn test_client_requests_and_logs() {
             }
         }));
     }
+
+    //test_get_group_availability_request_logs
+    {
+        let from = std::time::SystemTime::now();
+        let result = client
+            .get_group_availability(assets::GroupAvailabilityRequest {
+                group_id: "00000000-0000-0000-0000-000000000000".to_string(),
+                from: Some(from.into()),
+                to: Some((from + std::time::Duration::from_secs(3600)).into()),
+            })
+            .await;
+        println!("{:?}", result);
+        assert!(result.is_ok());
+
+        // Search for the expected log message
+        let expected = get_log_string("get_group_availability", name);
+        println!("expected message: {}", expected);
+        assert!(logger.any(|log| {
+            if log.target().contains("app::") {
+                println!("{}", log.target());
+                let message = log.args();
+                println!("{:?}", message);
+                log.args() == expected
+            } else {
+                false
+            }
+        }));
+    }
+
+    //test_get_vertiport_weather_minimums_request_logs
+    {
+        let result = client
+            .get_vertiport_weather_minimums(assets::VertiportWeatherMinimumsRequest {
+                vertiport_id: "00000000-0000-0000-0000-000000000000".to_string(),
+            })
+            .await;
+        println!("{:?}", result);
+        assert!(result.is_ok());
+
+        // Search for the expected log message
+        let expected = get_log_string("get_vertiport_weather_minimums", name);
+        println!("expected message: {}", expected);
+        assert!(logger.any(|log| {
+            if log.target().contains("app::") {
+                println!("{}", log.target());
+                let message = log.args();
+                println!("{:?}", message);
+                log.args() == expected
+            } else {
+                false
+            }
+        }));
+    }
 }
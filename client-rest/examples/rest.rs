@@ -4,6 +4,7 @@ use hyper::{Body, Client, Method, Request, Response};
 use hyper::{Error, StatusCode};
 use lib_common::grpc::get_endpoint_from_env;
 use lib_common::time::Utc;
+use lib_common::uuid::Uuid;
 use svc_assets_client_rest::types::*;
 
 fn check_body(bytes: &hyper::body::Bytes) -> String {
@@ -64,7 +65,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // POST /assets/aircraft
     {
         let data = vehicle::Data {
-            vehicle_model_id: "Jet".to_string(),
+            vehicle_model_id: Uuid::new_v4().to_string(),
             registration_number: "N2133423".to_string(),
             serial_number: "1234".to_string(),
             description: None,
@@ -0,0 +1,1777 @@
+//! REST client for the svc-assets server
+//!
+//! Provides a method for each endpoint of the REST API. Assets (aircraft,
+//! vertiports, vertipads, operators and asset groups) are returned as
+//! [`serde_json::Value`], all other responses use the structs from
+//! [`types`](crate::types).
+
+use crate::types::*;
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::{self, Display, Formatter};
+
+/// Header used to identify the operator registering assets
+pub const OPERATOR_ID_HEADER: &str = "x-operator-id";
+
+/// Header used to request an API version
+pub const API_VERSION_HEADER: &str = "x-api-version";
+
+/// API version of the payloads used by this client
+pub const API_VERSION: &str = "v1";
+
+/// Content type of JSON Merge Patch requests
+const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+
+/// Content type of JSON Patch requests
+const JSON_PATCH_CONTENT_TYPE: &str = "application/json-patch+json";
+
+/// Content type of asset snapshots
+const SNAPSHOT_CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// Percent-encode a value used as a single path segment.
+fn encode_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Errors returned by the [`AssetsClient`]
+#[derive(Debug)]
+pub enum ClientError {
+    /// The request could not be sent or the response could not be read.
+    Request(reqwest::Error),
+    /// The server rejected the payload.
+    Validation(Vec<FieldError>),
+    /// Some storage objects of a list could not be converted; the list is
+    /// returned with the failed objects.
+    PartialList(PartialList),
+    /// The server returned an unexpected status code.
+    Status {
+        /// The status code returned by the server.
+        status: StatusCode,
+        /// The body of the response.
+        body: String,
+    },
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ClientError::Request(e) => write!(f, "request failed: {e}"),
+            ClientError::Validation(errors) => {
+                write!(f, "payload rejected with {} field errors", errors.len())
+            }
+            ClientError::PartialList(list) => write!(
+                f,
+                "{} objects listed, {} could not be converted",
+                list.items.len(),
+                list.failed.len()
+            ),
+            ClientError::Status { status, body } => write!(f, "status {status}: {body}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        ClientError::Request(e)
+    }
+}
+
+/// Client for the svc-assets REST API
+#[derive(Debug, Clone)]
+pub struct AssetsClient {
+    url: String,
+    http: reqwest::Client,
+    token: Option<String>,
+}
+
+impl AssetsClient {
+    /// Create a client for the server at the provided url
+    /// (for example `http://localhost:8000`).
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_client(url, reqwest::Client::new())
+    }
+
+    /// Create a client for the server at the provided url, using a
+    /// preconfigured [`reqwest::Client`].
+    pub fn with_client(url: impl Into<String>, http: reqwest::Client) -> Self {
+        Self {
+            url: url.into().trim_end_matches('/').to_string(),
+            http,
+            token: None,
+        }
+    }
+
+    /// Send the provided API token as bearer token with every request,
+    /// except for the admin endpoints.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Start a request for the provided path without a token, pinned to
+    /// [`API_VERSION`].
+    fn base_request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http
+            .request(method, format!("{}{}", self.url, path))
+            .header(API_VERSION_HEADER, API_VERSION)
+    }
+
+    /// Start a request for the provided path, adding the API token if set.
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.base_request(method, path);
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Start an admin request using the provided admin token.
+    ///
+    /// If the admin endpoints are served on a separate port, use a client
+    /// created for that port.
+    fn admin(&self, method: Method, path: &str, token: &str) -> RequestBuilder {
+        self.base_request(method, path).bearer_auth(token)
+    }
+
+    /// Start a request, adding the operator header if provided.
+    fn operator(&self, method: Method, path: &str, operator_id: Option<&str>) -> RequestBuilder {
+        let request = self.request(method, path);
+        match operator_id {
+            Some(operator_id) => request.header(OPERATOR_ID_HEADER, operator_id),
+            None => request,
+        }
+    }
+
+    /// Start a registration request, adding the operator header if provided.
+    ///
+    /// The operator becomes the owner of the registered Assets. It is
+    /// required, unless the API token of the client is bound to an operator.
+    fn register(&self, path: &str, operator_id: Option<&str>) -> RequestBuilder {
+        self.operator(Method::POST, path, operator_id)
+    }
+
+    /// Send the request, returning the response if it was successful.
+    async fn send(request: RequestBuilder) -> Result<Response, ClientError> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.text().await?;
+        if status == StatusCode::UNPROCESSABLE_ENTITY {
+            if let Ok(errors) = serde_json::from_str(&body) {
+                return Err(ClientError::Validation(errors));
+            }
+        }
+
+        Err(ClientError::Status { status, body })
+    }
+
+    /// Send the request and deserialize the JSON response.
+    async fn send_json<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, ClientError> {
+        Ok(Self::send(request).await?.json().await?)
+    }
+
+    /// Send a list request and deserialize the JSON response.
+    ///
+    /// A `207 Multi-Status` response, returned by the `report` conversion
+    /// mode when some storage objects could not be converted, results in
+    /// [`ClientError::PartialList`].
+    async fn send_list(request: RequestBuilder) -> Result<Vec<Value>, ClientError> {
+        let response = Self::send(request).await?;
+        if response.status() == StatusCode::MULTI_STATUS {
+            return Err(ClientError::PartialList(response.json().await?));
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Send the request and return the response body as text.
+    async fn send_text(request: RequestBuilder) -> Result<String, ClientError> {
+        Ok(Self::send(request).await?.text().await?)
+    }
+
+    /// Send the request, ignoring the response body.
+    async fn send_empty(request: RequestBuilder) -> Result<(), ClientError> {
+        Self::send(request).await.map(|_| ())
+    }
+
+    /// Send a JSON Merge Patch request.
+    async fn patch(&self, path: &str, patch: &Value) -> Result<UpdateResult, ClientError> {
+        Self::send_json(
+            self.request(Method::PATCH, path)
+                .header(reqwest::header::CONTENT_TYPE, MERGE_PATCH_CONTENT_TYPE)
+                .body(patch.to_string()),
+        )
+        .await
+    }
+
+    /// Send a `HEAD` request, returning `false` if the resource was not found.
+    async fn exists(&self, path: &str) -> Result<bool, ClientError> {
+        match Self::send(self.request(Method::HEAD, path)).await {
+            Ok(_) => Ok(true),
+            Err(ClientError::Status { status, .. }) if status == StatusCode::NOT_FOUND => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Send a JSON body with the provided method, deserializing the response.
+    async fn send_body<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ClientError> {
+        Self::send_json(self.request(method, path).json(body)).await
+    }
+
+    // ------------------------------------------------------------------
+    // Health
+    // ------------------------------------------------------------------
+
+    /// `GET /health`
+    pub async fn health_check(&self) -> Result<HealthStatus, ClientError> {
+        Self::send_json(self.request(Method::GET, "/health")).await
+    }
+
+    // ------------------------------------------------------------------
+    // Admin
+    // ------------------------------------------------------------------
+
+    /// `GET /admin/read-only`
+    pub async fn get_read_only(&self, token: &str) -> Result<ReadOnlyStatus, ClientError> {
+        Self::send_json(self.admin(Method::GET, "/admin/read-only", token)).await
+    }
+
+    /// `PUT /admin/read-only`
+    pub async fn put_read_only(
+        &self,
+        token: &str,
+        enabled: bool,
+    ) -> Result<ReadOnlyStatus, ClientError> {
+        Self::send_json(
+            self.admin(Method::PUT, "/admin/read-only", token)
+                .json(&ReadOnlyStatus { enabled }),
+        )
+        .await
+    }
+
+    /// `GET /admin/audit-log`
+    pub async fn get_audit_log(&self, token: &str) -> Result<AuditLogStatus, ClientError> {
+        Self::send_json(self.admin(Method::GET, "/admin/audit-log", token)).await
+    }
+
+    /// `PUT /admin/audit-log`
+    pub async fn put_audit_log(
+        &self,
+        token: &str,
+        enabled: bool,
+    ) -> Result<AuditLogStatus, ClientError> {
+        Self::send_json(
+            self.admin(Method::PUT, "/admin/audit-log", token)
+                .json(&AuditLogStatus { enabled }),
+        )
+        .await
+    }
+
+    /// `GET /admin/config`
+    pub async fn get_config(&self, token: &str) -> Result<Value, ClientError> {
+        Self::send_json(self.admin(Method::GET, "/admin/config", token)).await
+    }
+
+    /// `GET /admin/features`
+    pub async fn get_features(&self, token: &str) -> Result<Vec<FeatureFlag>, ClientError> {
+        Self::send_json(self.admin(Method::GET, "/admin/features", token)).await
+    }
+
+    /// `POST /admin/cache/flush`
+    pub async fn flush_caches(&self, token: &str) -> Result<(), ClientError> {
+        Self::send_empty(self.admin(Method::POST, "/admin/cache/flush", token)).await
+    }
+
+    /// `GET /admin/grpc`
+    pub async fn get_grpc_connections(
+        &self,
+        token: &str,
+    ) -> Result<Vec<GrpcConnectionStatus>, ClientError> {
+        Self::send_json(self.admin(Method::GET, "/admin/grpc", token)).await
+    }
+
+    /// `POST /admin/grpc/reconnect`
+    pub async fn reconnect_grpc_clients(&self, token: &str) -> Result<(), ClientError> {
+        Self::send_empty(self.admin(Method::POST, "/admin/grpc/reconnect", token)).await
+    }
+
+    /// `PUT /admin/groups/{id}/tenant`
+    pub async fn put_group_tenant(
+        &self,
+        token: &str,
+        id: &str,
+        tenant: Option<String>,
+    ) -> Result<GroupTenant, ClientError> {
+        Self::send_json(
+            self.admin(Method::PUT, &format!("/admin/groups/{id}/tenant"), token)
+                .json(&GroupTenant { tenant }),
+        )
+        .await
+    }
+
+    // ------------------------------------------------------------------
+    // Operators
+    // ------------------------------------------------------------------
+
+    /// `GET /assets/operators/{id}`
+    pub async fn get_operator(&self, id: &str) -> Result<Value, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/operators/{id}"))).await
+    }
+
+    /// `GET /assets/operators/{id}/quota`
+    pub async fn get_operator_quota(&self, id: &str) -> Result<OperatorQuota, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/operators/{id}/quota"))).await
+    }
+
+    /// `GET /assets/operators/{id}/contacts`
+    pub async fn get_operator_contacts(
+        &self,
+        id: &str,
+    ) -> Result<Vec<OperatorContact>, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/operators/{id}/contacts")))
+            .await
+    }
+
+    /// `POST /assets/operators/{id}/contacts`
+    pub async fn add_operator_contact(
+        &self,
+        id: &str,
+        payload: &OperatorContactPayload,
+    ) -> Result<OperatorContact, ClientError> {
+        self.send_body(
+            Method::POST,
+            &format!("/assets/operators/{id}/contacts"),
+            payload,
+        )
+        .await
+    }
+
+    /// `PUT /assets/operators/{id}/contacts/{contact_id}`
+    pub async fn update_operator_contact(
+        &self,
+        id: &str,
+        contact_id: &str,
+        payload: &OperatorContactPayload,
+    ) -> Result<OperatorContact, ClientError> {
+        self.send_body(
+            Method::PUT,
+            &format!("/assets/operators/{id}/contacts/{contact_id}"),
+            payload,
+        )
+        .await
+    }
+
+    /// `DELETE /assets/operators/{id}/contacts/{contact_id}`
+    pub async fn remove_operator_contact(
+        &self,
+        id: &str,
+        contact_id: &str,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(self.request(
+            Method::DELETE,
+            &format!("/assets/operators/{id}/contacts/{contact_id}"),
+        ))
+        .await
+    }
+
+    /// `GET /assets/operators/{id}/notifications`
+    pub async fn get_operator_notifications(
+        &self,
+        id: &str,
+    ) -> Result<Vec<NotificationPreference>, ClientError> {
+        Self::send_json(self.request(
+            Method::GET,
+            &format!("/assets/operators/{id}/notifications"),
+        ))
+        .await
+    }
+
+    /// `PUT /assets/operators/{id}/notifications`
+    pub async fn put_operator_notifications(
+        &self,
+        id: &str,
+        preferences: &[NotificationPreference],
+    ) -> Result<(), ClientError> {
+        Self::send_empty(
+            self.request(
+                Method::PUT,
+                &format!("/assets/operators/{id}/notifications"),
+            )
+            .json(preferences),
+        )
+        .await
+    }
+
+    /// `PUT /assets/operators/{id}/logo`, uploads a PNG or JPEG image as the
+    /// `logo` field of a multipart body and returns the stored logo
+    pub async fn put_operator_logo(
+        &self,
+        id: &str,
+        file_name: &str,
+        content_type: &str,
+        content: Vec<u8>,
+    ) -> Result<OperatorLogo, ClientError> {
+        let part = reqwest::multipart::Part::bytes(content)
+            .file_name(file_name.to_string())
+            .mime_str(content_type)?;
+        Self::send_json(
+            self.request(Method::PUT, &format!("/assets/operators/{id}/logo"))
+                .multipart(reqwest::multipart::Form::new().part("logo", part)),
+        )
+        .await
+    }
+
+    /// `GET /assets/operators/{id}/logo`, returns the content of the logo in
+    /// the requested size
+    pub async fn get_operator_logo(
+        &self,
+        id: &str,
+        size: LogoSize,
+    ) -> Result<Vec<u8>, ClientError> {
+        Ok(Self::send(
+            self.request(Method::GET, &format!("/assets/operators/{id}/logo"))
+                .query(&LogoQuery { size }),
+        )
+        .await?
+        .bytes()
+        .await?
+        .to_vec())
+    }
+
+    /// `DELETE /assets/operators/{id}/logo`
+    pub async fn remove_operator_logo(&self, id: &str) -> Result<(), ClientError> {
+        Self::send_empty(self.request(Method::DELETE, &format!("/assets/operators/{id}/logo")))
+            .await
+    }
+
+    /// `GET /assets/operators/{id}/assets`
+    pub async fn get_all_assets_by_operator(&self, id: &str) -> Result<Vec<String>, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/operators/{id}/assets"))).await
+    }
+
+    /// `GET /assets/operators/{id}/grouped`
+    pub async fn get_all_grouped_assets(&self, id: &str) -> Result<Vec<String>, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/operators/{id}/grouped"))).await
+    }
+
+    /// `GET /assets/operators/{id}/grouped/delegated-to`
+    pub async fn get_all_grouped_assets_delegated_to(
+        &self,
+        id: &str,
+    ) -> Result<Vec<DelegatedAssetGroup>, ClientError> {
+        Self::send_json(self.request(
+            Method::GET,
+            &format!("/assets/operators/{id}/grouped/delegated-to"),
+        ))
+        .await
+    }
+
+    /// `GET /assets/operators/{id}/grouped/delegated-from`
+    pub async fn get_all_grouped_assets_delegated_from(
+        &self,
+        id: &str,
+    ) -> Result<Vec<DelegatedAssetGroup>, ClientError> {
+        Self::send_json(self.request(
+            Method::GET,
+            &format!("/assets/operators/{id}/grouped/delegated-from"),
+        ))
+        .await
+    }
+
+    // ------------------------------------------------------------------
+    // Sync
+    // ------------------------------------------------------------------
+
+    /// `GET /assets/diff`, returns the assets created, updated and deleted since `query.since`
+    pub async fn get_asset_diff(&self, query: &DiffQuery) -> Result<Value, ClientError> {
+        Self::send_json(self.request(Method::GET, "/assets/diff").query(query)).await
+    }
+
+    /// `GET /assets/quality-report`
+    pub async fn get_quality_report(&self) -> Result<QualityReport, ClientError> {
+        Self::send_json(self.request(Method::GET, "/assets/quality-report")).await
+    }
+
+    /// `GET /assets/audit`
+    pub async fn get_audit_changes(&self, query: &AuditQuery) -> Result<AuditChanges, ClientError> {
+        Self::send_json(self.request(Method::GET, "/assets/audit").query(query)).await
+    }
+
+    /// `GET /assets/{type}/{id}/permissions`
+    pub async fn get_asset_permissions(
+        &self,
+        asset_type: PermissionAssetType,
+        id: &str,
+        operator_id: Option<&str>,
+    ) -> Result<AssetPermissions, ClientError> {
+        let collection = match asset_type {
+            PermissionAssetType::Aircraft => "aircraft",
+            PermissionAssetType::Vertiport => "vertiports",
+            PermissionAssetType::Vertipad => "vertipads",
+            PermissionAssetType::Group => "groups",
+        };
+        let path = format!("/assets/{collection}/{id}/permissions");
+        Self::send_json(self.operator(Method::GET, &path, operator_id)).await
+    }
+
+    // ------------------------------------------------------------------
+    // Archive
+    // ------------------------------------------------------------------
+
+    /// `GET /assets/archive`
+    pub async fn get_archive(
+        &self,
+        asset_type: ArchiveAssetType,
+    ) -> Result<Vec<ArchivedAsset>, ClientError> {
+        Self::send_json(
+            self.request(Method::GET, "/assets/archive")
+                .query(&ArchiveQuery { asset_type }),
+        )
+        .await
+    }
+
+    /// `POST /assets/archive/{id}/restore`
+    pub async fn restore_archived_asset(
+        &self,
+        asset_type: ArchiveAssetType,
+        id: &str,
+    ) -> Result<RestoredAsset, ClientError> {
+        Self::send_json(
+            self.request(Method::POST, &format!("/assets/archive/{id}/restore"))
+                .query(&ArchiveQuery { asset_type }),
+        )
+        .await
+    }
+
+    // ------------------------------------------------------------------
+    // Assets of any type
+    // ------------------------------------------------------------------
+
+    /// `GET /assets/{id}`, returns the summary of an asset of any type,
+    /// tagged with its `asset_type`
+    pub async fn get_asset_by_id(&self, id: &str) -> Result<Value, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/{id}"))).await
+    }
+
+    /// `POST /assets/query`, returns the assets of the queried type matching
+    /// the filter
+    pub async fn query_assets(&self, query: &AssetQuery) -> Result<Vec<Value>, ClientError> {
+        Self::send_list(self.request(Method::POST, "/assets/query").json(query)).await
+    }
+
+    // ------------------------------------------------------------------
+    // Notes and attachments
+    // ------------------------------------------------------------------
+
+    /// `GET /assets/{id}/notes`
+    pub async fn get_asset_notes(&self, id: &str) -> Result<Vec<AssetNote>, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/{id}/notes"))).await
+    }
+
+    /// `POST /assets/{id}/notes`, returns the new note
+    pub async fn add_asset_note(
+        &self,
+        id: &str,
+        payload: &AssetNotePayload,
+    ) -> Result<AssetNote, ClientError> {
+        self.send_body(Method::POST, &format!("/assets/{id}/notes"), payload)
+            .await
+    }
+
+    /// `DELETE /assets/{id}/notes/{note_id}`
+    pub async fn remove_asset_note(&self, id: &str, note_id: &str) -> Result<(), ClientError> {
+        Self::send_empty(self.request(Method::DELETE, &format!("/assets/{id}/notes/{note_id}")))
+            .await
+    }
+
+    /// `GET /assets/{id}/attachments`
+    pub async fn get_asset_attachments(
+        &self,
+        id: &str,
+    ) -> Result<Vec<AssetAttachment>, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/{id}/attachments"))).await
+    }
+
+    /// `POST /assets/{id}/attachments`, uploads a file with the provided
+    /// content type and returns the new attachment
+    pub async fn upload_asset_attachment(
+        &self,
+        id: &str,
+        file_name: &str,
+        content_type: &str,
+        content: Vec<u8>,
+    ) -> Result<AssetAttachment, ClientError> {
+        Self::send_json(
+            self.request(Method::POST, &format!("/assets/{id}/attachments"))
+                .query(&AttachmentQuery {
+                    file_name: file_name.to_string(),
+                })
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(content),
+        )
+        .await
+    }
+
+    /// `GET /assets/{id}/attachments/{attachment_id}`, returns the content of the file
+    pub async fn download_asset_attachment(
+        &self,
+        id: &str,
+        attachment_id: &str,
+    ) -> Result<Vec<u8>, ClientError> {
+        Ok(Self::send(self.request(
+            Method::GET,
+            &format!("/assets/{id}/attachments/{attachment_id}"),
+        ))
+        .await?
+        .bytes()
+        .await?
+        .to_vec())
+    }
+
+    /// `DELETE /assets/{id}/attachments/{attachment_id}`
+    pub async fn remove_asset_attachment(
+        &self,
+        id: &str,
+        attachment_id: &str,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(self.request(
+            Method::DELETE,
+            &format!("/assets/{id}/attachments/{attachment_id}"),
+        ))
+        .await
+    }
+
+    // ------------------------------------------------------------------
+    // Aircraft
+    // ------------------------------------------------------------------
+
+    /// `GET /assets/demo/aircraft`
+    pub async fn get_all_aircraft(
+        &self,
+        query: &ListAssetsQuery,
+    ) -> Result<Vec<Value>, ClientError> {
+        Self::send_list(
+            self.request(Method::GET, "/assets/demo/aircraft")
+                .query(query),
+        )
+        .await
+    }
+
+    /// `GET /assets/aircraft/{id}`
+    pub async fn get_aircraft_by_id(&self, id: &str) -> Result<Value, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/aircraft/{id}"))).await
+    }
+
+    /// `HEAD /assets/aircraft/{id}`
+    pub async fn aircraft_exists(&self, id: &str) -> Result<bool, ClientError> {
+        self.exists(&format!("/assets/aircraft/{id}")).await
+    }
+
+    /// `GET /assets/aircraft/{id}/utilization`
+    pub async fn get_aircraft_utilization(
+        &self,
+        id: &str,
+        query: &AvailabilityQuery,
+    ) -> Result<AircraftUtilization, ClientError> {
+        Self::send_json(
+            self.request(Method::GET, &format!("/assets/aircraft/{id}/utilization"))
+                .query(query),
+        )
+        .await
+    }
+
+    /// `POST /assets/aircraft/{id}/assign-hangar`
+    pub async fn assign_aircraft_hangar(
+        &self,
+        id: &str,
+        payload: &AssignHangarPayload,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(
+            self.request(
+                Method::POST,
+                &format!("/assets/aircraft/{id}/assign-hangar"),
+            )
+            .json(payload),
+        )
+        .await
+    }
+
+    /// `POST /assets/aircraft/{id}/hold`
+    pub async fn create_aircraft_hold(
+        &self,
+        id: &str,
+        payload: &MaintenanceHoldPayload,
+    ) -> Result<MaintenanceHold, ClientError> {
+        self.send_body(
+            Method::POST,
+            &format!("/assets/aircraft/{id}/hold"),
+            payload,
+        )
+        .await
+    }
+
+    /// `GET /assets/aircraft/{id}/hold`
+    pub async fn get_aircraft_holds(&self, id: &str) -> Result<Vec<MaintenanceHold>, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/aircraft/{id}/hold"))).await
+    }
+
+    /// `DELETE /assets/aircraft/{id}/hold/{hold_id}`
+    pub async fn cancel_aircraft_hold(&self, id: &str, hold_id: &str) -> Result<(), ClientError> {
+        Self::send_empty(self.request(
+            Method::DELETE,
+            &format!("/assets/aircraft/{id}/hold/{hold_id}"),
+        ))
+        .await
+    }
+
+    /// `GET /assets/maintenance/calendar.ics`, returns the iCalendar feed
+    pub async fn get_maintenance_calendar(
+        &self,
+        query: &MaintenanceCalendarQuery,
+    ) -> Result<String, ClientError> {
+        Self::send_text(
+            self.request(Method::GET, "/assets/maintenance/calendar.ics")
+                .query(query),
+        )
+        .await
+    }
+
+    /// `POST /assets/aircraft/batch-get`
+    pub async fn batch_get_aircraft(
+        &self,
+        payload: &BatchGetPayload,
+    ) -> Result<Value, ClientError> {
+        self.send_body(Method::POST, "/assets/aircraft/batch-get", payload)
+            .await
+    }
+
+    /// `POST /assets/aircraft/status-batch`
+    pub async fn batch_set_aircraft_status(
+        &self,
+        payload: &BatchAircraftStatusPayload,
+    ) -> Result<Vec<AircraftStatusResult>, ClientError> {
+        self.send_body(Method::POST, "/assets/aircraft/status-batch", payload)
+            .await
+    }
+
+    /// `POST /assets/aircraft`, returns the id of the new aircraft
+    pub async fn register_aircraft(
+        &self,
+        data: &vehicle::Data,
+        operator_id: Option<&str>,
+    ) -> Result<String, ClientError> {
+        Self::send_text(self.register("/assets/aircraft", operator_id).json(data)).await
+    }
+
+    /// `POST /assets/aircraft/validate`
+    pub async fn validate_aircraft_registration(
+        &self,
+        data: &vehicle::Data,
+    ) -> Result<vehicle::Data, ClientError> {
+        self.send_body(Method::POST, "/assets/aircraft/validate", data)
+            .await
+    }
+
+    /// `GET /assets/aircraft/check-registration/{registration_number}`
+    pub async fn check_aircraft_registration(
+        &self,
+        registration_number: &str,
+    ) -> Result<RegistrationNumberCheck, ClientError> {
+        let path = format!(
+            "/assets/aircraft/check-registration/{}",
+            encode_segment(registration_number)
+        );
+        Self::send_json(self.request(Method::GET, &path)).await
+    }
+
+    /// `PUT /assets/aircraft/validate`
+    pub async fn validate_aircraft_update(
+        &self,
+        payload: &UpdateAircraftPayload,
+    ) -> Result<UpdateAircraftPayload, ClientError> {
+        self.send_body(Method::PUT, "/assets/aircraft/validate", payload)
+            .await
+    }
+
+    /// `PUT /assets/aircraft`
+    pub async fn update_aircraft(
+        &self,
+        payload: &UpdateAircraftPayload,
+    ) -> Result<UpdateResult, ClientError> {
+        self.send_body(Method::PUT, "/assets/aircraft", payload)
+            .await
+    }
+
+    /// `PATCH /assets/aircraft/{id}`
+    pub async fn patch_aircraft(
+        &self,
+        id: &str,
+        patch: &Value,
+    ) -> Result<UpdateResult, ClientError> {
+        self.patch(&format!("/assets/aircraft/{id}"), patch).await
+    }
+
+    /// `DELETE /assets/aircraft/{id}`
+    pub async fn remove_aircraft(
+        &self,
+        id: &str,
+        query: &RemoveAircraftQuery,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(
+            self.request(Method::DELETE, &format!("/assets/aircraft/{id}"))
+                .query(query),
+        )
+        .await
+    }
+
+    // ------------------------------------------------------------------
+    // Vertiports
+    // ------------------------------------------------------------------
+
+    /// `GET /assets/demo/vertiports`
+    pub async fn get_all_vertiports(
+        &self,
+        query: &ListAssetsQuery,
+    ) -> Result<Vec<Value>, ClientError> {
+        Self::send_list(
+            self.request(Method::GET, "/assets/demo/vertiports")
+                .query(query),
+        )
+        .await
+    }
+
+    /// `GET /assets/vertiports/{id}`
+    pub async fn get_vertiport_by_id(&self, id: &str) -> Result<Value, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/vertiports/{id}"))).await
+    }
+
+    /// `GET /assets/vertiports/{id}?geometry=..`, for example only the
+    /// centroid and bounding box for a map marker
+    pub async fn get_vertiport_by_id_with_geometry(
+        &self,
+        id: &str,
+        geometry: GeometryDetail,
+    ) -> Result<Value, ClientError> {
+        Self::send_json(
+            self.request(Method::GET, &format!("/assets/vertiports/{id}"))
+                .query(&GeometryQuery {
+                    geometry: Some(geometry),
+                }),
+        )
+        .await
+    }
+
+    /// `HEAD /assets/vertiports/{id}`
+    pub async fn vertiport_exists(&self, id: &str) -> Result<bool, ClientError> {
+        self.exists(&format!("/assets/vertiports/{id}")).await
+    }
+
+    /// `GET /assets/vertiports/{id}` with an `Accept-Language` header
+    /// (for example `de-CH, en;q=0.5`)
+    pub async fn get_vertiport_by_id_localized(
+        &self,
+        id: &str,
+        accept_language: &str,
+    ) -> Result<Value, ClientError> {
+        Self::send_json(
+            self.request(Method::GET, &format!("/assets/vertiports/{id}"))
+                .header(reqwest::header::ACCEPT_LANGUAGE, accept_language),
+        )
+        .await
+    }
+
+    /// `POST /assets/vertiports/batch-get`
+    pub async fn batch_get_vertiports(
+        &self,
+        payload: &BatchGetPayload,
+    ) -> Result<Value, ClientError> {
+        self.send_body(Method::POST, "/assets/vertiports/batch-get", payload)
+            .await
+    }
+
+    /// `POST /assets/vertiports`, returns the id of the new vertiport
+    pub async fn register_vertiport(
+        &self,
+        data: &vertiport::Data,
+        query: &RegisterVertiportQuery,
+        operator_id: Option<&str>,
+    ) -> Result<String, ClientError> {
+        Self::send_text(
+            self.register("/assets/vertiports", operator_id)
+                .query(query)
+                .json(data),
+        )
+        .await
+    }
+
+    /// `POST /assets/import/geojson`
+    pub async fn import_geojson(
+        &self,
+        collection: &GeoJsonFeatureCollection,
+        query: &GeoJsonImportQuery,
+        operator_id: Option<&str>,
+    ) -> Result<Vec<ImportedVertiport>, ClientError> {
+        Self::send_json(
+            self.register("/assets/import/geojson", operator_id)
+                .query(query)
+                .json(collection),
+        )
+        .await
+    }
+
+    /// `POST /assets/vertiports/validate`
+    pub async fn validate_vertiport_registration(
+        &self,
+        data: &vertiport::Data,
+    ) -> Result<vertiport::Data, ClientError> {
+        self.send_body(Method::POST, "/assets/vertiports/validate", data)
+            .await
+    }
+
+    /// `PUT /assets/vertiports/validate`
+    pub async fn validate_vertiport_update(
+        &self,
+        payload: &UpdateVertiportPayload,
+    ) -> Result<UpdateVertiportPayload, ClientError> {
+        self.send_body(Method::PUT, "/assets/vertiports/validate", payload)
+            .await
+    }
+
+    /// `PUT /assets/vertiports`
+    pub async fn update_vertiport(
+        &self,
+        payload: &UpdateVertiportPayload,
+    ) -> Result<UpdateResult, ClientError> {
+        self.send_body(Method::PUT, "/assets/vertiports", payload)
+            .await
+    }
+
+    /// `PUT /assets/vertiports/{id}/localization`
+    pub async fn put_vertiport_localization(
+        &self,
+        id: &str,
+        localization: &AssetLocalization,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(
+            self.request(
+                Method::PUT,
+                &format!("/assets/vertiports/{id}/localization"),
+            )
+            .json(localization),
+        )
+        .await
+    }
+
+    /// `GET /assets/vertiports/{id}/ops-status`
+    pub async fn get_vertiport_ops_status(
+        &self,
+        id: &str,
+    ) -> Result<VertiportOpsState, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/vertiports/{id}/ops-status")))
+            .await
+    }
+
+    /// `PUT /assets/vertiports/{id}/ops-status`
+    pub async fn put_vertiport_ops_status(
+        &self,
+        id: &str,
+        payload: &VertiportOpsStatusPayload,
+    ) -> Result<VertiportOpsState, ClientError> {
+        self.send_body(
+            Method::PUT,
+            &format!("/assets/vertiports/{id}/ops-status"),
+            payload,
+        )
+        .await
+    }
+
+    /// `GET /assets/vertiports/{id}/weather-minimums`
+    pub async fn get_vertiport_weather_minimums(
+        &self,
+        id: &str,
+    ) -> Result<VertiportWeatherMinimums, ClientError> {
+        Self::send_json(self.request(
+            Method::GET,
+            &format!("/assets/vertiports/{id}/weather-minimums"),
+        ))
+        .await
+    }
+
+    /// `PUT /assets/vertiports/{id}/weather-minimums`
+    pub async fn put_vertiport_weather_minimums(
+        &self,
+        id: &str,
+        payload: &WeatherMinimumsPayload,
+    ) -> Result<VertiportWeatherMinimums, ClientError> {
+        self.send_body(
+            Method::PUT,
+            &format!("/assets/vertiports/{id}/weather-minimums"),
+            payload,
+        )
+        .await
+    }
+
+    /// `DELETE /assets/vertiports/{id}/weather-minimums`
+    pub async fn remove_vertiport_weather_minimums(&self, id: &str) -> Result<(), ClientError> {
+        Self::send_empty(self.request(
+            Method::DELETE,
+            &format!("/assets/vertiports/{id}/weather-minimums"),
+        ))
+        .await
+    }
+
+    /// `GET /assets/vertiports/typeahead`
+    pub async fn typeahead_vertiports(
+        &self,
+        q: &str,
+        limit: Option<u8>,
+    ) -> Result<Vec<VertiportSuggestion>, ClientError> {
+        Self::send_json(
+            self.request(Method::GET, "/assets/vertiports/typeahead")
+                .query(&VertiportTypeaheadQuery {
+                    q: q.to_string(),
+                    limit,
+                }),
+        )
+        .await
+    }
+
+    /// `GET /assets/vertiports/{id}/vertipads/adjacency`
+    pub async fn get_vertipad_adjacency(
+        &self,
+        id: &str,
+        threshold_m: Option<f64>,
+    ) -> Result<VertipadAdjacency, ClientError> {
+        Self::send_json(
+            self.request(
+                Method::GET,
+                &format!("/assets/vertiports/{id}/vertipads/adjacency"),
+            )
+            .query(&VertipadAdjacencyQuery { threshold_m }),
+        )
+        .await
+    }
+
+    /// `GET /assets/vertiports/{id}/vertipads/by-elevation`
+    pub async fn get_vertipads_by_elevation(&self, id: &str) -> Result<Vec<Value>, ClientError> {
+        Self::send_json(self.request(
+            Method::GET,
+            &format!("/assets/vertiports/{id}/vertipads/by-elevation"),
+        ))
+        .await
+    }
+
+    /// `PATCH /assets/vertiports/{id}`
+    pub async fn patch_vertiport(
+        &self,
+        id: &str,
+        patch: &Value,
+    ) -> Result<UpdateResult, ClientError> {
+        self.patch(&format!("/assets/vertiports/{id}"), patch).await
+    }
+
+    /// `DELETE /assets/vertiports/{id}`
+    pub async fn remove_vertiport(&self, id: &str) -> Result<(), ClientError> {
+        Self::send_empty(self.request(Method::DELETE, &format!("/assets/vertiports/{id}"))).await
+    }
+
+    /// `GET /assets/vertiports/{id}/restrictions?at=..`, the restrictions in
+    /// effect at the time, now if not provided
+    pub async fn get_vertiport_restrictions(
+        &self,
+        id: &str,
+        query: &RestrictionsQuery,
+    ) -> Result<VertiportRestrictions, ClientError> {
+        Self::send_json(
+            self.request(
+                Method::GET,
+                &format!("/assets/vertiports/{id}/restrictions"),
+            )
+            .query(query),
+        )
+        .await
+    }
+
+    /// `GET /assets/vertiports/{id}/restrictions/rules`
+    pub async fn get_vertiport_restriction_rules(
+        &self,
+        id: &str,
+    ) -> Result<Vec<RestrictionRule>, ClientError> {
+        Self::send_json(self.request(
+            Method::GET,
+            &format!("/assets/vertiports/{id}/restrictions/rules"),
+        ))
+        .await
+    }
+
+    /// `GET /assets/vertiports/{id}/restrictions/rules/{rule_id}`
+    pub async fn get_vertiport_restriction_rule(
+        &self,
+        id: &str,
+        rule_id: &str,
+    ) -> Result<RestrictionRule, ClientError> {
+        Self::send_json(self.request(
+            Method::GET,
+            &format!("/assets/vertiports/{id}/restrictions/rules/{rule_id}"),
+        ))
+        .await
+    }
+
+    /// `POST /assets/vertiports/{id}/restrictions/rules`
+    pub async fn add_vertiport_restriction_rule(
+        &self,
+        id: &str,
+        payload: &RestrictionRulePayload,
+    ) -> Result<RestrictionRule, ClientError> {
+        self.send_body(
+            Method::POST,
+            &format!("/assets/vertiports/{id}/restrictions/rules"),
+            payload,
+        )
+        .await
+    }
+
+    /// `PUT /assets/vertiports/{id}/restrictions/rules/{rule_id}`
+    pub async fn update_vertiport_restriction_rule(
+        &self,
+        id: &str,
+        rule_id: &str,
+        payload: &RestrictionRulePayload,
+    ) -> Result<RestrictionRule, ClientError> {
+        self.send_body(
+            Method::PUT,
+            &format!("/assets/vertiports/{id}/restrictions/rules/{rule_id}"),
+            payload,
+        )
+        .await
+    }
+
+    /// `DELETE /assets/vertiports/{id}/restrictions/rules/{rule_id}`
+    pub async fn remove_vertiport_restriction_rule(
+        &self,
+        id: &str,
+        rule_id: &str,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(self.request(
+            Method::DELETE,
+            &format!("/assets/vertiports/{id}/restrictions/rules/{rule_id}"),
+        ))
+        .await
+    }
+
+    // ------------------------------------------------------------------
+    // Vertipads
+    // ------------------------------------------------------------------
+
+    /// `GET /assets/demo/vertipads`
+    pub async fn get_all_vertipads(
+        &self,
+        query: &ListAssetsQuery,
+    ) -> Result<Vec<Value>, ClientError> {
+        Self::send_list(
+            self.request(Method::GET, "/assets/demo/vertipads")
+                .query(query),
+        )
+        .await
+    }
+
+    /// `GET /assets/demo/vertipads?min_weight_kg=..`, only the vertipads
+    /// classified for aircraft of at least that weight
+    pub async fn get_all_vertipads_classified(
+        &self,
+        query: &ListAssetsQuery,
+        classification: &VertipadClassificationQuery,
+    ) -> Result<Vec<Value>, ClientError> {
+        Self::send_list(
+            self.request(Method::GET, "/assets/demo/vertipads")
+                .query(query)
+                .query(classification),
+        )
+        .await
+    }
+
+    /// `GET /assets/demo/vertipads?has_charger=..&min_kw=..`, only the
+    /// vertipads with a matching charger
+    pub async fn get_all_vertipads_with_chargers(
+        &self,
+        query: &ListAssetsQuery,
+        chargers: &VertipadChargerQuery,
+    ) -> Result<Vec<Value>, ClientError> {
+        Self::send_list(
+            self.request(Method::GET, "/assets/demo/vertipads")
+                .query(query)
+                .query(chargers),
+        )
+        .await
+    }
+
+    /// `GET /assets/vertipads/{id}`
+    pub async fn get_vertipad_by_id(&self, id: &str) -> Result<Value, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/vertipads/{id}"))).await
+    }
+
+    /// `HEAD /assets/vertipads/{id}`
+    pub async fn vertipad_exists(&self, id: &str) -> Result<bool, ClientError> {
+        self.exists(&format!("/assets/vertipads/{id}")).await
+    }
+
+    /// `GET /assets/vertipads/{id}` with an `Accept-Language` header
+    /// (for example `de-CH, en;q=0.5`)
+    pub async fn get_vertipad_by_id_localized(
+        &self,
+        id: &str,
+        accept_language: &str,
+    ) -> Result<Value, ClientError> {
+        Self::send_json(
+            self.request(Method::GET, &format!("/assets/vertipads/{id}"))
+                .header(reqwest::header::ACCEPT_LANGUAGE, accept_language),
+        )
+        .await
+    }
+
+    /// `GET /assets/vertipads/{id}/availability`
+    pub async fn get_vertipad_availability(
+        &self,
+        id: &str,
+        query: &AvailabilityQuery,
+    ) -> Result<VertipadAvailability, ClientError> {
+        Self::send_json(
+            self.request(Method::GET, &format!("/assets/vertipads/{id}/availability"))
+                .query(query),
+        )
+        .await
+    }
+
+    /// `GET /assets/vertipads/{id}/occupancy-log`
+    pub async fn get_vertipad_occupancy_log(
+        &self,
+        id: &str,
+        query: &OccupancyLogQuery,
+    ) -> Result<Vec<OccupancyTransition>, ClientError> {
+        Self::send_json(
+            self.request(
+                Method::GET,
+                &format!("/assets/vertipads/{id}/occupancy-log"),
+            )
+            .query(query),
+        )
+        .await
+    }
+
+    /// `POST /assets/vertipads/batch-get`
+    pub async fn batch_get_vertipads(
+        &self,
+        payload: &BatchGetPayload,
+    ) -> Result<Value, ClientError> {
+        self.send_body(Method::POST, "/assets/vertipads/batch-get", payload)
+            .await
+    }
+
+    /// `POST /assets/vertipads`, returns the id of the new vertipad
+    pub async fn register_vertipad(&self, data: &vertipad::Data) -> Result<String, ClientError> {
+        Self::send_text(self.request(Method::POST, "/assets/vertipads").json(data)).await
+    }
+
+    /// `POST /assets/vertipads/validate`
+    pub async fn validate_vertipad_registration(
+        &self,
+        data: &vertipad::Data,
+    ) -> Result<vertipad::Data, ClientError> {
+        self.send_body(Method::POST, "/assets/vertipads/validate", data)
+            .await
+    }
+
+    /// `PUT /assets/vertipads/validate`
+    pub async fn validate_vertipad_update(
+        &self,
+        payload: &UpdateVertipadPayload,
+    ) -> Result<UpdateVertipadPayload, ClientError> {
+        self.send_body(Method::PUT, "/assets/vertipads/validate", payload)
+            .await
+    }
+
+    /// `PUT /assets/vertipads`
+    pub async fn update_vertipad(
+        &self,
+        payload: &UpdateVertipadPayload,
+    ) -> Result<UpdateResult, ClientError> {
+        self.send_body(Method::PUT, "/assets/vertipads", payload)
+            .await
+    }
+
+    /// `PUT /assets/vertipads/{id}/localization`
+    pub async fn put_vertipad_localization(
+        &self,
+        id: &str,
+        localization: &AssetLocalization,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(
+            self.request(Method::PUT, &format!("/assets/vertipads/{id}/localization"))
+                .json(localization),
+        )
+        .await
+    }
+
+    /// `PUT /assets/vertipads/{id}/classification`
+    pub async fn put_vertipad_classification(
+        &self,
+        id: &str,
+        classification: &PadClassification,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(
+            self.request(
+                Method::PUT,
+                &format!("/assets/vertipads/{id}/classification"),
+            )
+            .json(classification),
+        )
+        .await
+    }
+
+    /// `GET /assets/vertipads/{id}/chargers`
+    pub async fn get_vertipad_chargers(&self, id: &str) -> Result<Vec<Charger>, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/vertipads/{id}/chargers")))
+            .await
+    }
+
+    /// `GET /assets/vertipads/{id}/chargers/{charger_id}`
+    pub async fn get_vertipad_charger(
+        &self,
+        id: &str,
+        charger_id: &str,
+    ) -> Result<Charger, ClientError> {
+        Self::send_json(self.request(
+            Method::GET,
+            &format!("/assets/vertipads/{id}/chargers/{charger_id}"),
+        ))
+        .await
+    }
+
+    /// `POST /assets/vertipads/{id}/chargers`
+    pub async fn add_vertipad_charger(
+        &self,
+        id: &str,
+        payload: &ChargerPayload,
+    ) -> Result<Charger, ClientError> {
+        self.send_body(
+            Method::POST,
+            &format!("/assets/vertipads/{id}/chargers"),
+            payload,
+        )
+        .await
+    }
+
+    /// `PUT /assets/vertipads/{id}/chargers/{charger_id}`
+    pub async fn update_vertipad_charger(
+        &self,
+        id: &str,
+        charger_id: &str,
+        payload: &ChargerPayload,
+    ) -> Result<Charger, ClientError> {
+        self.send_body(
+            Method::PUT,
+            &format!("/assets/vertipads/{id}/chargers/{charger_id}"),
+            payload,
+        )
+        .await
+    }
+
+    /// `DELETE /assets/vertipads/{id}/chargers/{charger_id}`
+    pub async fn remove_vertipad_charger(
+        &self,
+        id: &str,
+        charger_id: &str,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(self.request(
+            Method::DELETE,
+            &format!("/assets/vertipads/{id}/chargers/{charger_id}"),
+        ))
+        .await
+    }
+
+    /// `GET /assets/vertipads/{id}/equipment`
+    pub async fn get_vertipad_equipment(&self, id: &str) -> Result<Vec<Equipment>, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/vertipads/{id}/equipment")))
+            .await
+    }
+
+    /// `GET /assets/vertipads/{id}/equipment/{equipment_id}`
+    pub async fn get_vertipad_equipment_by_id(
+        &self,
+        id: &str,
+        equipment_id: &str,
+    ) -> Result<Equipment, ClientError> {
+        Self::send_json(self.request(
+            Method::GET,
+            &format!("/assets/vertipads/{id}/equipment/{equipment_id}"),
+        ))
+        .await
+    }
+
+    /// `POST /assets/vertipads/{id}/equipment`
+    pub async fn add_vertipad_equipment(
+        &self,
+        id: &str,
+        payload: &EquipmentPayload,
+    ) -> Result<Equipment, ClientError> {
+        self.send_body(
+            Method::POST,
+            &format!("/assets/vertipads/{id}/equipment"),
+            payload,
+        )
+        .await
+    }
+
+    /// `PUT /assets/vertipads/{id}/equipment/{equipment_id}`
+    pub async fn update_vertipad_equipment(
+        &self,
+        id: &str,
+        equipment_id: &str,
+        payload: &EquipmentPayload,
+    ) -> Result<Equipment, ClientError> {
+        self.send_body(
+            Method::PUT,
+            &format!("/assets/vertipads/{id}/equipment/{equipment_id}"),
+            payload,
+        )
+        .await
+    }
+
+    /// `DELETE /assets/vertipads/{id}/equipment/{equipment_id}`
+    pub async fn remove_vertipad_equipment(
+        &self,
+        id: &str,
+        equipment_id: &str,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(self.request(
+            Method::DELETE,
+            &format!("/assets/vertipads/{id}/equipment/{equipment_id}"),
+        ))
+        .await
+    }
+
+    /// `PATCH /assets/vertipads/{id}`
+    pub async fn patch_vertipad(
+        &self,
+        id: &str,
+        patch: &Value,
+    ) -> Result<UpdateResult, ClientError> {
+        self.patch(&format!("/assets/vertipads/{id}"), patch).await
+    }
+
+    /// `DELETE /assets/vertipads/{id}`
+    pub async fn remove_vertipad(&self, id: &str) -> Result<(), ClientError> {
+        Self::send_empty(self.request(Method::DELETE, &format!("/assets/vertipads/{id}"))).await
+    }
+
+    // ------------------------------------------------------------------
+    // Snapshots
+    // ------------------------------------------------------------------
+
+    /// `GET /assets/snapshot`, returns the binary snapshot of all assets
+    pub async fn export_snapshot(&self) -> Result<Vec<u8>, ClientError> {
+        Ok(Self::send(self.request(Method::GET, "/assets/snapshot"))
+            .await?
+            .bytes()
+            .await?
+            .to_vec())
+    }
+
+    /// `POST /assets/snapshot`
+    pub async fn import_snapshot(
+        &self,
+        snapshot: Vec<u8>,
+    ) -> Result<SnapshotImportSummary, ClientError> {
+        Self::send_json(
+            self.request(Method::POST, "/assets/snapshot")
+                .header(reqwest::header::CONTENT_TYPE, SNAPSHOT_CONTENT_TYPE)
+                .body(snapshot),
+        )
+        .await
+    }
+
+    // ------------------------------------------------------------------
+    // Regions
+    // ------------------------------------------------------------------
+
+    /// `GET /assets/regions`
+    pub async fn get_regions(&self) -> Result<Vec<Region>, ClientError> {
+        Self::send_json(self.request(Method::GET, "/assets/regions")).await
+    }
+
+    /// `GET /assets/regions/{id}`
+    pub async fn get_region(&self, id: &str) -> Result<Region, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/regions/{id}"))).await
+    }
+
+    /// `POST /assets/regions`
+    pub async fn register_region(&self, payload: &RegionPayload) -> Result<Region, ClientError> {
+        self.send_body(Method::POST, "/assets/regions", payload)
+            .await
+    }
+
+    /// `PUT /assets/regions/{id}`
+    pub async fn update_region(
+        &self,
+        id: &str,
+        payload: &RegionPayload,
+    ) -> Result<Region, ClientError> {
+        self.send_body(Method::PUT, &format!("/assets/regions/{id}"), payload)
+            .await
+    }
+
+    /// `DELETE /assets/regions/{id}`
+    pub async fn remove_region(&self, id: &str) -> Result<(), ClientError> {
+        Self::send_empty(self.request(Method::DELETE, &format!("/assets/regions/{id}"))).await
+    }
+
+    /// `GET /assets/regions/{id}/vertiports`
+    pub async fn get_region_vertiports(&self, id: &str) -> Result<Vec<Value>, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/regions/{id}/vertiports")))
+            .await
+    }
+
+    // ------------------------------------------------------------------
+    // Asset groups
+    // ------------------------------------------------------------------
+
+    /// `GET /assets/groups/{id}`, the assets of the child groups are included
+    /// if `recursive` is set
+    pub async fn get_asset_group_by_id(
+        &self,
+        id: &str,
+        query: &AssetGroupQuery,
+    ) -> Result<Value, ClientError> {
+        Self::send_json(
+            self.request(Method::GET, &format!("/assets/groups/{id}"))
+                .query(query),
+        )
+        .await
+    }
+
+    /// `POST /assets/groups`, returns the id of the new asset group
+    pub async fn register_asset_group(
+        &self,
+        payload: &RegisterAssetGroupPayload,
+    ) -> Result<String, ClientError> {
+        Self::send_text(self.request(Method::POST, "/assets/groups").json(payload)).await
+    }
+
+    /// `PUT /assets/groups/{id}`
+    pub async fn update_asset_group(&self, id: &str, group: &Value) -> Result<String, ClientError> {
+        Self::send_text(
+            self.request(Method::PUT, &format!("/assets/groups/{id}"))
+                .json(group),
+        )
+        .await
+    }
+
+    /// `PATCH /assets/groups/{id}` with a JSON Patch, returns the patched
+    /// asset group
+    pub async fn patch_asset_group(
+        &self,
+        id: &str,
+        operations: &[JsonPatchOperation],
+        operator_id: Option<&str>,
+    ) -> Result<Value, ClientError> {
+        // the content type is kept by `json` if already set
+        Self::send_json(
+            self.operator(Method::PATCH, &format!("/assets/groups/{id}"), operator_id)
+                .header(reqwest::header::CONTENT_TYPE, JSON_PATCH_CONTENT_TYPE)
+                .json(operations),
+        )
+        .await
+    }
+
+    /// `DELETE /assets/groups/{id}`
+    pub async fn remove_asset_group(&self, id: &str) -> Result<String, ClientError> {
+        Self::send_text(self.request(Method::DELETE, &format!("/assets/groups/{id}"))).await
+    }
+
+    /// `PUT /assets/groups/{id}/schedule`
+    pub async fn put_asset_group_schedule(
+        &self,
+        id: &str,
+        payload: &GroupSchedulePayload,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(
+            self.request(Method::PUT, &format!("/assets/groups/{id}/schedule"))
+                .json(payload),
+        )
+        .await
+    }
+
+    /// `PUT /assets/groups/{id}/parent`
+    pub async fn put_asset_group_parent(
+        &self,
+        id: &str,
+        payload: &GroupParentPayload,
+    ) -> Result<(), ClientError> {
+        Self::send_empty(
+            self.request(Method::PUT, &format!("/assets/groups/{id}/parent"))
+                .json(payload),
+        )
+        .await
+    }
+
+    /// `GET /assets/groups/{id}/availability`
+    pub async fn get_asset_group_availability(
+        &self,
+        id: &str,
+        query: &AvailabilityQuery,
+    ) -> Result<GroupAvailability, ClientError> {
+        Self::send_json(
+            self.request(Method::GET, &format!("/assets/groups/{id}/availability"))
+                .query(query),
+        )
+        .await
+    }
+
+    /// `GET /assets/groups/{id}/assets`, the full assets are returned if
+    /// `resolve` is set and the assets of the child groups are included if
+    /// `recursive` is set
+    pub async fn get_asset_group_assets(
+        &self,
+        id: &str,
+        query: &GroupAssetsQuery,
+    ) -> Result<Value, ClientError> {
+        Self::send_json(
+            self.request(Method::GET, &format!("/assets/groups/{id}/assets"))
+                .query(query),
+        )
+        .await
+    }
+
+    /// `PUT /assets/groups/{id}/delegation`, the operator is recorded in the
+    /// delegation audit trail
+    pub async fn put_asset_group_delegation(
+        &self,
+        id: &str,
+        payload: &DelegationPayload,
+        operator_id: Option<&str>,
+    ) -> Result<Delegation, ClientError> {
+        Self::send_json(
+            self.operator(
+                Method::PUT,
+                &format!("/assets/groups/{id}/delegation"),
+                operator_id,
+            )
+            .json(payload),
+        )
+        .await
+    }
+
+    /// `GET /assets/groups/{id}/delegation`
+    pub async fn get_asset_group_delegation(&self, id: &str) -> Result<Delegation, ClientError> {
+        Self::send_json(self.request(Method::GET, &format!("/assets/groups/{id}/delegation"))).await
+    }
+
+    /// `DELETE /assets/groups/{id}/delegation`, returns the revoked delegation
+    pub async fn revoke_asset_group_delegation(
+        &self,
+        id: &str,
+        operator_id: Option<&str>,
+    ) -> Result<Delegation, ClientError> {
+        Self::send_json(self.operator(
+            Method::DELETE,
+            &format!("/assets/groups/{id}/delegation"),
+            operator_id,
+        ))
+        .await
+    }
+
+    /// `GET /assets/groups/{id}/delegation/audit`, most recent first
+    pub async fn get_asset_group_delegation_audit(
+        &self,
+        id: &str,
+    ) -> Result<Vec<DelegationAuditEntry>, ClientError> {
+        Self::send_json(self.request(
+            Method::GET,
+            &format!("/assets/groups/{id}/delegation/audit"),
+        ))
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_url() {
+        let client = AssetsClient::new("http://localhost:8000/");
+        let request = client
+            .request(Method::GET, "/assets/aircraft/1")
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "http://localhost:8000/assets/aircraft/1"
+        );
+
+        let request = client
+            .register("/assets/aircraft", Some("operator"))
+            .build()
+            .unwrap();
+        assert_eq!(request.method(), Method::POST);
+        assert_eq!(request.headers()[OPERATOR_ID_HEADER], "operator");
+    }
+
+    #[test]
+    fn test_client_token() {
+        let client = AssetsClient::new("http://localhost:8000");
+        let request = client.request(Method::GET, "/assets/aircraft/1").build();
+        assert!(!request
+            .unwrap()
+            .headers()
+            .contains_key(reqwest::header::AUTHORIZATION));
+
+        let client = client.with_token("partner");
+        let request = client
+            .request(Method::GET, "/assets/aircraft/1")
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.headers()[reqwest::header::AUTHORIZATION],
+            "Bearer partner"
+        );
+
+        // the admin endpoints use the admin token instead
+        let request = client
+            .admin(Method::GET, "/admin/config", "admin")
+            .build()
+            .unwrap();
+        let authorization: Vec<_> = request
+            .headers()
+            .get_all(reqwest::header::AUTHORIZATION)
+            .iter()
+            .collect();
+        assert_eq!(authorization, vec!["Bearer admin"]);
+    }
+
+    #[test]
+    fn test_encode_segment() {
+        assert_eq!(encode_segment("N12345"), "N12345");
+        assert_eq!(encode_segment("PH-1 A/B"), "PH-1%20A%2FB");
+    }
+}
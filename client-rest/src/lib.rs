@@ -1,6 +1,10 @@
 //! Imports the types used in REST communication with the svc-asset server
+//! and provides a client for its REST API
 
-/// Types for messages to svc-cargo server
-pub mod types {
-    include!("../../openapi/types.rs");
-}
+#[cfg(feature = "client")]
+pub mod client;
+pub mod timestamp;
+pub mod types;
+
+#[cfg(feature = "client")]
+pub use client::{AssetsClient, ClientError};
@@ -0,0 +1,105 @@
+//! RFC 3339 timestamps
+//!
+//! All timestamps of the REST API are [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339)
+//! date-times, for example `2024-01-01T12:00:00Z`, and are returned in UTC.
+//! The fields of the REST types are deserialized with [`deserialize`] and
+//! [`deserialize_option`], so other formats are rejected with the same
+//! message everywhere.
+
+use lib_common::time::{DateTime, Utc};
+use serde::{de, Deserialize, Deserializer};
+
+/// Example of an RFC 3339 timestamp, used in error messages
+pub const EXAMPLE: &str = "2024-01-01T12:00:00Z";
+
+/// Parse an RFC 3339 timestamp, converting it to UTC.
+///
+/// Returns a message describing the expected format if the value is not an
+/// RFC 3339 timestamp.
+pub fn parse(value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| {
+            format!("invalid RFC 3339 timestamp '{value}' ({e}), expected for example '{EXAMPLE}'")
+        })
+}
+
+/// Deserialize an RFC 3339 timestamp
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse(&value).map_err(de::Error::custom)
+}
+
+/// Deserialize an optional RFC 3339 timestamp, `null` is `None`.
+///
+/// Fields using it need `#[serde(default)]` to be optional.
+pub fn deserialize_option<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| parse(&value))
+        .transpose()
+        .map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Payload {
+        #[serde(deserialize_with = "deserialize")]
+        at: DateTime<Utc>,
+        #[serde(default, deserialize_with = "deserialize_option")]
+        until: Option<DateTime<Utc>>,
+    }
+
+    #[test]
+    fn test_parse() {
+        let utc = parse("2024-01-01T12:00:00Z").unwrap();
+        assert_eq!(parse("2024-01-01T14:00:00+02:00").unwrap(), utc);
+        assert_eq!(parse("2024-01-01T12:00:00.000Z").unwrap(), utc);
+
+        for invalid in [
+            "2024-01-01",
+            "12:00:00",
+            "2024-01-01T12:00:00",
+            "1704110400",
+            "",
+        ] {
+            let message = parse(invalid).unwrap_err();
+            assert!(message.starts_with(&format!("invalid RFC 3339 timestamp '{invalid}'")));
+            assert!(message.ends_with(&format!("expected for example '{EXAMPLE}'")));
+        }
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let payload: Payload = serde_json::from_str(r#"{"at": "2024-01-01T12:00:00Z"}"#).unwrap();
+        assert_eq!(payload.until, None);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["at"], EXAMPLE);
+
+        let payload: Payload =
+            serde_json::from_str(r#"{"at": "2024-01-01T12:00:00Z", "until": null}"#).unwrap();
+        assert_eq!(payload.until, None);
+        let payload: Payload = serde_json::from_str(
+            r#"{"at": "2024-01-01T12:00:00Z", "until": "2024-01-02T00:00:00+01:00"}"#,
+        )
+        .unwrap();
+        assert_eq!(payload.until, Some(parse("2024-01-01T23:00:00Z").unwrap()));
+
+        let error = serde_json::from_str::<Payload>(r#"{"at": "2024-01-01"}"#).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("invalid RFC 3339 timestamp '2024-01-01'"));
+        let error =
+            serde_json::from_str::<Payload>(r#"{"at": "2024-01-01T12:00:00Z", "until": "soon"}"#)
+                .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("invalid RFC 3339 timestamp 'soon'"));
+        assert!(serde_json::from_str::<Payload>(r#"{"at": 1704110400}"#).is_err());
+    }
+}
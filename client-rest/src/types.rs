@@ -0,0 +1,1882 @@
+//! Types used in REST communication with the svc-assets server
+
+use crate::timestamp;
+use lib_common::time::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
+
+pub use svc_storage_client_grpc::prelude::{group, vehicle, vertipad, vertiport};
+pub use svc_storage_client_grpc::prelude::{GeoLineStringZ, GeoPointZ, GeoPolygonZ};
+
+/// Status of an Asset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum AssetStatus {
+    /// The asset is available for use.
+    Available,
+    /// The asset is unavailable for use.
+    Unavailable,
+    /// The asset is only available for emergencies.
+    Emergency,
+}
+
+/// A geographical point.
+///
+/// Coordinates are in degrees (WGS84), the altitude is in meters above mean
+/// sea level. The storage field names (`x`, `y`, `z`) are accepted as
+/// aliases.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GeoPoint {
+    /// Longitude of the point, between -180 and 180 degrees.
+    #[serde(alias = "x")]
+    pub longitude: f64,
+    /// Latitude of the point, between -90 and 90 degrees.
+    #[serde(alias = "y")]
+    pub latitude: f64,
+    /// Altitude of the point in meters above mean sea level, between -500
+    /// and 9000 meters. Defaults to 0.
+    #[serde(alias = "z", default)]
+    pub altitude: f64,
+}
+
+/// A line of geographical points.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GeoLineString {
+    /// The points of the line.
+    pub points: Vec<GeoPoint>,
+}
+
+/// A geographical area.
+///
+/// The first ring is the exterior of the area, any other rings are holes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GeoPolygon {
+    /// The closed rings of the area.
+    pub rings: Vec<GeoLineString>,
+}
+
+impl From<GeoPointZ> for GeoPoint {
+    fn from(point: GeoPointZ) -> Self {
+        GeoPoint {
+            longitude: point.x,
+            latitude: point.y,
+            altitude: point.z,
+        }
+    }
+}
+
+impl From<GeoPoint> for GeoPointZ {
+    fn from(point: GeoPoint) -> Self {
+        GeoPointZ {
+            x: point.longitude,
+            y: point.latitude,
+            z: point.altitude,
+        }
+    }
+}
+
+impl From<GeoLineStringZ> for GeoLineString {
+    fn from(line: GeoLineStringZ) -> Self {
+        GeoLineString {
+            points: line.points.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<GeoLineString> for GeoLineStringZ {
+    fn from(line: GeoLineString) -> Self {
+        GeoLineStringZ {
+            points: line.points.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<GeoPolygonZ> for GeoPolygon {
+    fn from(polygon: GeoPolygonZ) -> Self {
+        GeoPolygon {
+            rings: polygon.rings.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<GeoPolygon> for GeoPolygonZ {
+    fn from(polygon: GeoPolygon) -> Self {
+        GeoPolygonZ {
+            rings: polygon.rings.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Request to create or replace a Region.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RegionPayload {
+    /// Name of the Region, for example a city.
+    pub name: String,
+    /// Area of the Region.
+    pub geo_location: GeoPolygon,
+}
+
+/// A named area grouping the Vertiports located in it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Region {
+    /// The UUID of the Region.
+    pub id: String,
+    /// Name of the Region.
+    pub name: String,
+    /// Area of the Region.
+    pub geo_location: GeoPolygon,
+    /// Time the Region was created.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub created_at: DateTime<Utc>,
+    /// Time the Region was last changed.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request to create an Asset Group.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+pub struct RegisterAssetGroupPayload {
+    /// Optional identification name of the asset group
+    pub name: Option<String>,
+    /// The UUID of an Operator, if available.
+    pub owner: String,
+    /// A list of UUIDs of assets.
+    pub assets: Vec<String>,
+}
+
+/// Request to update an Aircraft.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+pub struct UpdateAircraftPayload {
+    /// The UUID v4 of the Aircraft to update.
+    pub id: String,
+    /// The vehicle_model_id UUID v4, can be used to collect additional vehicle_model information.
+    pub vehicle_model_id: Option<String>,
+    /// optional id UUID v4 of the hangar (vertiport) this aircraft is assigned to
+    pub hangar_id: Option<String>,
+    /// optional id UUID v4 of the hangar bay (vertipad) this aircraft is assigned to
+    pub hangar_bay_id: Option<String>,
+    /// The Aircraft's unique serial_number given at the factory.
+    pub serial_number: Option<String>,
+    /// The Aircraft's registration number.
+    ///
+    /// In the US, this is the N number.
+    ///
+    /// This is a unique identifier for
+    /// the aircraft that can be used to look up information about the
+    /// aircraft from national aviation authorities like the FAA.
+    pub registration_number: Option<String>,
+    /// Optional additional description of the Aircraft.
+    pub description: Option<String>,
+    /// The UUID of an AssetGroup, if available.
+    pub asset_group_id: Option<String>,
+    /// Optional RRULE data string to indicate the Aircraft's available days and hours.
+    pub schedule: Option<String>,
+    /// Optional date of Aircraft's last maintenance.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub last_maintenance: Option<DateTime<Utc>>,
+    /// Optional date of Aircraft's next planned maintenance.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub next_maintenance: Option<DateTime<Utc>>,
+    /// Optional `updated_at` of the Aircraft the update is based on.
+    ///
+    /// If provided and the stored Aircraft was updated since, the update is
+    /// rejected with a `409 CONFLICT`.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub expected_updated_at: Option<DateTime<Utc>>,
+    /// List of fields that should be updated.
+    ///
+    /// If any other fields are provided, they will be ignored.
+    pub mask: Vec<String>,
+}
+
+/// Result of an update request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct UpdateResult {
+    /// If the update changed the asset; `false` if all updated fields
+    /// already had the provided values, in which case nothing was written.
+    pub changed: bool,
+}
+
+/// Usage of an Aircraft registration number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RegistrationNumberCheck {
+    /// The normalized registration number.
+    pub registration_number: String,
+    /// Whether the registration number is used by an Aircraft.
+    pub in_use: bool,
+    /// The UUID of the Aircraft using the registration number, if any.
+    pub aircraft_id: Option<String>,
+}
+
+/// Request to update a Vertiport.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+pub struct UpdateVertiportPayload {
+    /// The UUID v4 of the Vertiport to update.
+    pub id: String,
+    /// Identification name of the Vertiport.
+    pub name: Option<String>,
+    /// Additional description of the Vertiport.
+    pub description: Option<String>,
+    /// Geographical area location of the Vertiport.
+    pub geo_location: Option<GeoPolygon>,
+    /// Optional RRULE data string to indicate the Vertiport's available days and hours.
+    pub schedule: Option<String>,
+    /// Optional `updated_at` of the Vertiport the update is based on.
+    ///
+    /// If provided and the stored Vertiport was updated since, the update is
+    /// rejected with a `409 CONFLICT`.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub expected_updated_at: Option<DateTime<Utc>>,
+    /// List of fields that should be updated.
+    ///
+    /// If any other fields are provided, they will be ignored.
+    pub mask: Vec<String>,
+}
+
+/// Request to update a Vertipad.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+pub struct UpdateVertipadPayload {
+    /// The UUID v4 of the Vertipad to update.
+    pub id: String,
+    /// The UUID v4 of the Vertiport the Vertipad is located at.
+    pub vertiport_id: Option<String>,
+    /// Identification name of the Vertipad.
+    pub name: Option<String>,
+    /// Geographical location of the Vertipad.
+    pub geo_location: Option<GeoPoint>,
+    /// Indicates if the Vertipad is in business.
+    pub enabled: Option<bool>,
+    /// Indicates if the Vertipad is currently occupied.
+    pub occupied: Option<bool>,
+    /// Who or what changed `occupied`, defaults to [`OccupancySource::Manual`].
+    #[serde(default)]
+    pub occupancy_source: Option<OccupancySource>,
+    /// Optional RRULE data string to indicate the Vertipad's available days and hours.
+    pub schedule: Option<String>,
+    /// Optional `updated_at` of the Vertipad the update is based on.
+    ///
+    /// If provided and the stored Vertipad was updated since, the update is
+    /// rejected with a `409 CONFLICT`.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub expected_updated_at: Option<DateTime<Utc>>,
+    /// Optional occupancy version of the Vertipad the update is based on,
+    /// the `version` of its last [`OccupancyTransition`] or `0` if it has
+    /// none.
+    ///
+    /// If provided and the occupancy of the Vertipad changed since, the
+    /// update is rejected with a `409 CONFLICT`.
+    #[serde(default)]
+    pub expected_occupancy_version: Option<u64>,
+    /// List of fields that should be updated.
+    ///
+    /// If any other fields are provided, they will be ignored.
+    pub mask: Vec<String>,
+}
+
+/// Who or what changed the occupancy of a Vertipad.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OccupancySource {
+    /// Changed manually, for example by a dispatcher.
+    #[default]
+    Manual,
+    /// Changed by the scheduler for a reservation.
+    Scheduler,
+}
+
+/// A change of the occupancy of a Vertipad.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OccupancyTransition {
+    /// The new occupancy of the Vertipad.
+    pub occupied: bool,
+    /// Who or what changed the occupancy.
+    pub source: OccupancySource,
+    /// When the occupancy was changed.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub timestamp: DateTime<Utc>,
+    /// Occupancy version of the Vertipad after the change, counting its
+    /// transitions.
+    #[serde(default)]
+    pub version: u64,
+}
+
+/// Number of occupancy transitions to get.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, IntoParams)]
+pub struct OccupancyLogQuery {
+    /// Maximum number of transitions to return, most recent first.
+    pub limit: Option<usize>,
+}
+
+/// Validation error for a single payload field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FieldError {
+    /// Name of the payload field the error applies to.
+    pub field: String,
+    /// Description of the problem found.
+    pub message: String,
+}
+
+/// Usage of a single asset quota.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct QuotaUsage {
+    /// Number of assets currently registered.
+    pub used: u32,
+    /// Maximum number of assets allowed, `None` if unlimited.
+    pub limit: Option<u32>,
+}
+
+/// Asset quota usage of an operator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OperatorQuota {
+    /// The UUID of the Operator.
+    pub operator_id: String,
+    /// Aircraft quota usage.
+    pub aircraft: QuotaUsage,
+    /// Vertiport quota usage.
+    pub vertiports: QuotaUsage,
+}
+
+/// Request to add or replace a contact of an Operator.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OperatorContactPayload {
+    /// Optional name of the contact.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Role of the contact within the Operator, for example `maintenance`.
+    pub role: String,
+    /// Email address of the contact.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Phone number of the contact.
+    #[serde(default)]
+    pub phone: Option<String>,
+}
+
+/// A contact of an Operator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OperatorContact {
+    /// The UUID of the contact.
+    pub id: String,
+    /// Optional name of the contact.
+    pub name: Option<String>,
+    /// Role of the contact within the Operator.
+    pub role: String,
+    /// Email address of the contact.
+    pub email: Option<String>,
+    /// Phone number of the contact.
+    pub phone: Option<String>,
+}
+
+/// Event an Operator can be notified of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// The maintenance of an Aircraft is due.
+    MaintenanceDue,
+    /// An AssetGroup was delegated to or from the Operator.
+    Delegation,
+}
+
+/// Channel a notification is sent through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// HTTP POST request to a webhook url.
+    Webhook,
+    /// Email message.
+    Email,
+}
+
+/// Where to send the notifications of an event type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct NotificationPreference {
+    /// The event to notify.
+    pub event: NotificationEvent,
+    /// The channel to send the notification through.
+    pub channel: NotificationChannel,
+    /// Webhook url or email address, depending on the channel.
+    pub target: String,
+}
+
+/// Variant of an Operator logo.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogoSize {
+    /// The uploaded file.
+    #[default]
+    Original,
+    /// PNG fitting in 256x256 pixels.
+    Medium,
+    /// PNG fitting in 64x64 pixels.
+    Small,
+}
+
+/// Variant of the Operator logo to download.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct LogoQuery {
+    /// The variant, `original` if not provided.
+    #[serde(default)]
+    pub size: LogoSize,
+}
+
+/// The logo of an Operator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OperatorLogo {
+    /// The UUID of the Operator.
+    pub operator_id: String,
+    /// Content type of the uploaded file, `image/png` or `image/jpeg`.
+    pub content_type: String,
+    /// Size of the uploaded file in bytes.
+    pub size: u64,
+    /// Width of the uploaded image in pixels.
+    pub width: u32,
+    /// Height of the uploaded image in pixels.
+    pub height: u32,
+    /// Hex encoded SHA-256 hash of the uploaded file.
+    pub sha256: String,
+    /// Path the logo can be downloaded from, relative to the server.
+    pub url: String,
+    /// When the logo was uploaded.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A window of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TimeWindow {
+    /// Start of the window.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub start: DateTime<Utc>,
+    /// End of the window.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub end: DateTime<Utc>,
+}
+
+/// Time range to get the availability for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct AvailabilityQuery {
+    /// Start of the time range.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub from: DateTime<Utc>,
+    /// End of the time range.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub to: DateTime<Utc>,
+}
+
+/// Available time windows of a Vertipad.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct VertipadAvailability {
+    /// The UUID of the Vertipad.
+    pub vertipad_id: String,
+    /// Start of the requested time range.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub from: DateTime<Utc>,
+    /// End of the requested time range.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub to: DateTime<Utc>,
+    /// Windows within the requested time range the Vertipad is available.
+    pub windows: Vec<TimeWindow>,
+}
+
+/// Request to set the schedule of an AssetGroup.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GroupSchedulePayload {
+    /// Schedule constraining all assets of the group, in the same format
+    /// as the vertipad schedule. Removes the schedule if empty or not
+    /// provided.
+    #[serde(default)]
+    pub schedule: Option<String>,
+}
+
+/// Available time windows of an AssetGroup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GroupAvailability {
+    /// The UUID of the AssetGroup.
+    pub group_id: String,
+    /// Start of the requested time range.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub from: DateTime<Utc>,
+    /// End of the requested time range.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub to: DateTime<Utc>,
+    /// True if the group has a schedule constraining its assets.
+    pub constrained: bool,
+    /// Windows within the requested time range the assets of the group can
+    /// be used.
+    pub windows: Vec<TimeWindow>,
+}
+
+/// Options for getting an AssetGroup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct AssetGroupQuery {
+    /// Include the Assets of all child groups, recursively.
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+/// Request to set the parent of an AssetGroup.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GroupParentPayload {
+    /// The UUID of the parent AssetGroup. Makes the group a top level group
+    /// if empty or not provided.
+    #[serde(default)]
+    pub parent_group_id: Option<String>,
+}
+
+/// Options for listing the Assets of an AssetGroup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct GroupAssetsQuery {
+    /// Return the full Assets instead of only their UUIDs.
+    #[serde(default)]
+    pub resolve: bool,
+    /// Include the Assets of all child groups, recursively.
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+/// An Asset of an AssetGroup which could not be resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GroupAssetFailure {
+    /// The UUID of the Asset.
+    pub id: String,
+    /// HTTP status code of the failed lookup.
+    pub status: u16,
+    /// Reason the Asset could not be resolved.
+    pub message: String,
+}
+
+/// Type of an Asset left behind by a failed registration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeftoverAssetType {
+    /// A leftover aircraft.
+    Aircraft,
+    /// A leftover vertiport.
+    Vertiport,
+    /// A leftover vertipad.
+    Vertipad,
+}
+
+/// An Asset created by a registration which failed, and which could not be
+/// removed again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LeftoverAsset {
+    /// The type of the Asset.
+    pub asset_type: LeftoverAssetType,
+    /// The UUID of the Asset.
+    pub id: String,
+}
+
+/// Response of a registration of several Assets which failed after some of
+/// them were created.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct IncompleteRegistration {
+    /// The created Assets which could not be removed again, they have to be
+    /// removed by the client. Empty if all of them were removed.
+    pub leftover: Vec<LeftoverAsset>,
+}
+
+/// Request to assign an Aircraft to a hangar bay.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AssignHangarPayload {
+    /// The UUID of the hangar (Vertiport).
+    pub hangar_id: String,
+    /// The UUID of the hangar bay (a Vertipad of the hangar).
+    pub hangar_bay_id: String,
+}
+
+/// Request to hold an Aircraft for maintenance.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceHoldPayload {
+    /// Start of the hold.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub start: DateTime<Utc>,
+    /// End of the hold.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub end: DateTime<Utc>,
+    /// Optional reason of the hold, for example the planned work.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// A time window an Aircraft is held for maintenance and can not be
+/// scheduled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceHold {
+    /// The UUID of the hold.
+    pub id: String,
+    /// The UUID of the held Aircraft.
+    pub aircraft_id: String,
+    /// Start of the hold.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub start: DateTime<Utc>,
+    /// End of the hold.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub end: DateTime<Utc>,
+    /// Optional reason of the hold.
+    pub reason: Option<String>,
+    /// When the hold was created.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filter of the maintenance calendar.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct MaintenanceCalendarQuery {
+    /// Only export the Aircraft registered by this Operator, all Aircraft
+    /// are exported if not provided.
+    #[serde(default)]
+    pub operator_id: Option<String>,
+}
+
+/// Operational status of a Vertiport, separate from its [`AssetStatus`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum VertiportOpsStatus {
+    /// The vertiport is open for operations.
+    #[default]
+    Open,
+    /// The vertiport is closed, its vertipads are disabled.
+    Closed,
+    /// The vertiport is open with limited operations.
+    LimitedOps,
+    /// The vertiport only accepts emergency operations, its vertipads are
+    /// disabled for regular operations.
+    Emergency,
+}
+
+/// Request to change the operational status of a Vertiport.
+///
+/// The status applies within the effective time window, the vertiport is
+/// `Open` outside of it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct VertiportOpsStatusPayload {
+    /// The operational status.
+    pub status: VertiportOpsStatus,
+    /// When the status becomes effective, immediately if not provided.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub effective_from: Option<DateTime<Utc>>,
+    /// When the status ends, open ended if not provided.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub effective_until: Option<DateTime<Utc>>,
+    /// Optional reason of the status, for example a weather closure.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// The operational status of a Vertiport.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct VertiportOpsState {
+    /// The UUID of the Vertiport.
+    pub vertiport_id: String,
+    /// The operational status within the effective time window.
+    pub status: VertiportOpsStatus,
+    /// When the status becomes effective, `None` if already effective.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub effective_from: Option<DateTime<Utc>>,
+    /// When the status ends, `None` if open ended.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub effective_until: Option<DateTime<Utc>>,
+    /// Optional reason of the status.
+    pub reason: Option<String>,
+    /// The operational status at the time of the request.
+    pub current_status: VertiportOpsStatus,
+    /// When the status was last changed, `None` if it never was.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Operational weather minimums of a Vertiport.
+///
+/// Flights to and from the Vertiport should not go ahead in weather worse
+/// than these limits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WeatherMinimumsPayload {
+    /// Maximum wind speed, in meters per second.
+    pub max_wind_speed_mps: f64,
+    /// Minimum visibility, in meters.
+    pub min_visibility_m: f64,
+    /// Maximum precipitation, in millimeters per hour.
+    pub max_precipitation_mm_per_hour: f64,
+}
+
+/// The operational weather minimums configured for a Vertiport.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct VertiportWeatherMinimums {
+    /// The UUID of the Vertiport.
+    pub vertiport_id: String,
+    /// Maximum wind speed, in meters per second.
+    pub max_wind_speed_mps: f64,
+    /// Minimum visibility, in meters.
+    pub min_visibility_m: f64,
+    /// Maximum precipitation, in millimeters per hour.
+    pub max_precipitation_mm_per_hour: f64,
+    /// When the minimums were last changed.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request to delegate an AssetGroup to another Operator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DelegationPayload {
+    /// The UUID of the Operator the AssetGroup is delegated to.
+    pub delegatee: String,
+    /// When the delegation expires, `None` if it does not expire.
+    ///
+    /// An expired delegation is reverted automatically.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// An active delegation of an AssetGroup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Delegation {
+    /// The UUID of the delegated AssetGroup.
+    pub group_id: String,
+    /// The UUID of the Operator the AssetGroup is delegated to.
+    pub delegatee: String,
+    /// The UUID of the Operator who delegated the AssetGroup, if provided.
+    pub delegated_by: Option<String>,
+    /// When the AssetGroup was delegated.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub delegated_at: DateTime<Utc>,
+    /// When the delegation expires, `None` if it does not expire.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Change of the delegation of an AssetGroup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DelegationAction {
+    /// The AssetGroup was delegated, or the delegation was renewed.
+    Delegated,
+    /// The delegation was revoked.
+    Revoked,
+    /// The delegation expired and was reverted.
+    Expired,
+}
+
+/// Entry of the delegation audit trail of an AssetGroup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DelegationAuditEntry {
+    /// The UUID of the AssetGroup.
+    pub group_id: String,
+    /// The change of the delegation.
+    pub action: DelegationAction,
+    /// The UUID of the Operator the AssetGroup is or was delegated to.
+    pub delegatee: String,
+    /// The UUID of the Operator who made the change, `None` if it was not
+    /// provided or the delegation expired.
+    pub actor: Option<String>,
+    /// When the change was made.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub timestamp: DateTime<Utc>,
+    /// When the delegation expires, for `delegated` entries.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// An AssetGroup delegated to or from an Operator, with its assets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DelegatedAssetGroup {
+    /// The active delegation of the AssetGroup; `delegated_at` and
+    /// `expires_at` are the period the assets are delegated for.
+    pub delegation: Delegation,
+    /// The UUIDs of the assets in the AssetGroup.
+    pub asset_ids: Vec<String>,
+}
+
+/// An operation of a JSON Patch (RFC 6902).
+///
+/// The `path` is a JSON Pointer (RFC 6901) into the patched document, for
+/// example `/assets/-` to append to the assets of an AssetGroup. The `move`
+/// and `copy` operations are not supported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JsonPatchOperation {
+    /// Add the value at the path, inserting it into an array.
+    Add {
+        /// The location to add the value to.
+        path: String,
+        /// The value to add.
+        value: serde_json::Value,
+    },
+    /// Remove the value at the path.
+    Remove {
+        /// The location of the removed value.
+        path: String,
+    },
+    /// Replace the value at the path, which must exist.
+    Replace {
+        /// The location of the replaced value.
+        path: String,
+        /// The new value.
+        value: serde_json::Value,
+    },
+    /// Check that the value at the path equals the value, failing the whole
+    /// patch otherwise.
+    Test {
+        /// The location of the tested value.
+        path: String,
+        /// The expected value.
+        value: serde_json::Value,
+    },
+}
+
+/// Request to get multiple assets by their ids.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+pub struct BatchGetPayload {
+    /// The UUIDs of the assets to get.
+    pub ids: Vec<String>,
+}
+
+/// Request to add a note to an Asset.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AssetNotePayload {
+    /// Text of the note.
+    pub text: String,
+    /// Optional author of the note.
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+/// A free-form note attached to an Asset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AssetNote {
+    /// The UUID of the note.
+    pub id: String,
+    /// The UUID of the Asset.
+    pub asset_id: String,
+    /// Text of the note.
+    pub text: String,
+    /// Optional author of the note.
+    pub author: Option<String>,
+    /// When the note was created.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Name of a file uploaded as attachment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct AttachmentQuery {
+    /// File name of the attachment, for example `certificate.pdf`.
+    pub file_name: String,
+}
+
+/// A file attached to an Asset, like an image or a certificate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AssetAttachment {
+    /// The UUID of the attachment.
+    pub id: String,
+    /// The UUID of the Asset.
+    pub asset_id: String,
+    /// File name of the attachment.
+    pub file_name: String,
+    /// Content type of the attachment, for example `application/pdf`.
+    pub content_type: String,
+    /// Size of the attachment in bytes.
+    pub size: u64,
+    /// When the attachment was uploaded.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Point in time to get the asset changes since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct DiffQuery {
+    /// Changes made at or after this time are returned.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub since: DateTime<Utc>,
+}
+
+/// Whether removed Assets are listed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum IncludeDeleted {
+    /// Only list available Assets.
+    #[default]
+    #[serde(rename = "false")]
+    False,
+    /// List available and removed Assets.
+    #[serde(rename = "true")]
+    True,
+    /// Only list removed Assets, for audits.
+    #[serde(rename = "only")]
+    Only,
+}
+
+/// Time and owner filters of the Asset list endpoints.
+///
+/// All filters are optional, Assets matching all provided filters are
+/// returned.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct ListAssetsQuery {
+    /// Only return Assets created at or after this time.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only return Assets created before this time.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only return Assets updated at or after this time.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub updated_after: Option<DateTime<Utc>>,
+    /// Whether removed Assets are returned, with their `deleted_at` time if
+    /// it is known.
+    #[serde(default)]
+    pub include_deleted: IncludeDeleted,
+    /// Only return Assets owned by the operator with this UUID. A vertipad
+    /// is owned by the owner of its vertiport.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<String>,
+}
+
+/// Type of the Assets searched by an [`AssetQuery`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryAssetType {
+    /// Search aircraft.
+    Aircraft,
+    /// Search vertiports.
+    Vertiport,
+    /// Search vertipads.
+    Vertipad,
+}
+
+/// Comparison of a [`QueryFilter`] condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryOperator {
+    /// The field equals the value.
+    Eq,
+    /// The field differs from the value.
+    Ne,
+    /// The field is less than the value.
+    Lt,
+    /// The field is less than or equal to the value.
+    Le,
+    /// The field is greater than the value.
+    Gt,
+    /// The field is greater than or equal to the value.
+    Ge,
+    /// The field matches the case insensitive pattern of the value, `%`
+    /// matching any characters.
+    Like,
+    /// The field equals one of the values of an array.
+    In,
+    /// The field is not set, the condition takes no value.
+    IsNull,
+    /// The field is set, the condition takes no value.
+    IsNotNull,
+}
+
+/// A node of the filter of an [`AssetQuery`].
+///
+/// A node either combines other nodes, `{"and": [..]}` or `{"or": [..]}`,
+/// or compares a field, `{"field": "name", "op": "eq", "value": "Pad 1"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum QueryFilter {
+    /// Matches the Assets matching all nodes.
+    And {
+        /// The combined nodes.
+        and: Vec<QueryFilter>,
+    },
+    /// Matches the Assets matching any of the nodes.
+    Or {
+        /// The combined nodes.
+        or: Vec<QueryFilter>,
+    },
+    /// Compares a field of the Assets.
+    Condition {
+        /// The compared field, which must be allowed for the queried type.
+        field: String,
+        /// The comparison.
+        op: QueryOperator,
+        /// The compared value: a string, a boolean, an RFC 3339 timestamp
+        /// or an array of them for `in`. Left out for `is_null` and
+        /// `is_not_null`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value: Option<serde_json::Value>,
+    },
+}
+
+/// Search of the Assets of a type matching a filter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AssetQuery {
+    /// Type of the searched Assets.
+    pub asset_type: QueryAssetType,
+    /// The filter of the Assets.
+    pub filter: QueryFilter,
+}
+
+/// Type of an archived Asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveAssetType {
+    /// Archived aircraft.
+    Aircraft,
+    /// Archived vertiports.
+    Vertiport,
+    /// Archived vertipads.
+    Vertipad,
+}
+
+/// Type of the archived Assets to list or restore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct ArchiveQuery {
+    /// Type of the archived Assets.
+    #[serde(rename = "type")]
+    pub asset_type: ArchiveAssetType,
+}
+
+/// Compact entry of an Asset in the archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ArchivedAsset {
+    /// The UUID of the removed Asset.
+    pub id: String,
+    /// Type of the Asset.
+    pub asset_type: ArchiveAssetType,
+    /// Registration number of an aircraft, name of a vertiport or vertipad.
+    pub name: String,
+    /// When the Asset was created.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// An archived Asset registered again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RestoredAsset {
+    /// The UUID of the archived Asset.
+    pub archived_id: String,
+    /// The UUID of the restored Asset.
+    pub id: String,
+}
+
+/// A data quality issue of an Asset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct QualityIssue {
+    /// The UUID of the Asset.
+    pub id: String,
+    /// Type of the Asset.
+    pub asset_type: ArchiveAssetType,
+    /// The field with the issue.
+    pub field: String,
+    /// Description of the issue.
+    pub message: String,
+}
+
+/// Data quality issues of the inventory, grouped by severity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct QualityReport {
+    /// When the inventory was scanned.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub generated_at: DateTime<Utc>,
+    /// Number of Assets scanned.
+    pub scanned: usize,
+    /// Issues making an Asset unusable.
+    pub errors: Vec<QualityIssue>,
+    /// Issues of usable Assets.
+    pub warnings: Vec<QualityIssue>,
+}
+
+/// Storage objects a list endpoint could not convert to Assets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SkippedObjects {
+    /// Number of objects which could not be converted.
+    pub skipped: usize,
+    /// Number of objects returned by storage.
+    pub total: usize,
+}
+
+/// Storage object which could not be converted to an Asset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ConversionFailure {
+    /// Id of the storage object.
+    pub id: String,
+    /// Why the object could not be converted.
+    pub reason: String,
+}
+
+/// `application/problem+json` body of a `207 Multi-Status` list response in
+/// which some storage objects could not be converted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PartialList {
+    /// URI identifying the problem type.
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Summary of the problem type.
+    pub title: String,
+    /// HTTP status code of the response.
+    pub status: u16,
+    /// Explanation of this occurrence of the problem.
+    pub detail: String,
+    /// The Assets which could be converted.
+    pub items: Vec<serde_json::Value>,
+    /// The storage objects which could not be converted.
+    pub failed: Vec<ConversionFailure>,
+}
+
+/// Properties of a GeoJSON vertiport feature.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct GeoJsonProperties {
+    /// Identification name of the Vertiport.
+    pub name: Option<String>,
+    /// Additional description of the Vertiport.
+    pub description: Option<String>,
+}
+
+/// GeoJSON geometry.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GeoJsonGeometry {
+    /// Geometry type, only `Polygon` is supported.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Coordinates of the geometry, as `[longitude, latitude, altitude]` positions.
+    #[schema(value_type = Object)]
+    pub coordinates: serde_json::Value,
+}
+
+/// GeoJSON feature.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GeoJsonFeature {
+    /// Feature type, must be `Feature`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Geometry of the feature.
+    pub geometry: Option<GeoJsonGeometry>,
+    /// Properties of the feature.
+    pub properties: Option<GeoJsonProperties>,
+}
+
+/// GeoJSON feature collection.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GeoJsonFeatureCollection {
+    /// Collection type, must be `FeatureCollection`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Features of the collection.
+    pub features: Vec<GeoJsonFeature>,
+}
+
+/// Placement of automatically created Vertipads within a Vertiport.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PadLayout {
+    /// Vertipads are spread over a grid covering the Vertiport area.
+    #[default]
+    Grid,
+    /// A Vertipad at the centroid of the Vertiport, the others around it.
+    CentroidOffset,
+}
+
+/// Options for a Vertiport registration.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, IntoParams)]
+pub struct RegisterVertiportQuery {
+    /// Number of Vertipads to create within the Vertiport.
+    pub auto_create_pads: Option<u8>,
+    /// Placement of the created Vertipads, defaults to [`PadLayout::Grid`].
+    pub pad_layout: Option<PadLayout>,
+}
+
+/// Options for the Vertipad adjacency of a Vertiport.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, IntoParams)]
+pub struct VertipadAdjacencyQuery {
+    /// Maximum distance in meters between adjacent Vertipads, defaults to the
+    /// configured threshold.
+    pub threshold_m: Option<f64>,
+}
+
+/// A geographical bounding box.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GeoBoundingBox {
+    /// Minimum longitude, in degrees.
+    pub min_longitude: f64,
+    /// Minimum latitude, in degrees.
+    pub min_latitude: f64,
+    /// Maximum longitude, in degrees.
+    pub max_longitude: f64,
+    /// Maximum latitude, in degrees.
+    pub max_latitude: f64,
+}
+
+/// Geometry included in a Vertiport response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GeometryDetail {
+    /// No geometry, without the area, centroid and bounding box.
+    None,
+    /// The centroid and bounding box, without the area.
+    Centroid,
+    /// The area, centroid and bounding box.
+    #[default]
+    Full,
+}
+
+/// Options for the geometry of Vertiport responses.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, IntoParams)]
+pub struct GeometryQuery {
+    /// Geometry included in the response, defaults to
+    /// [`GeometryDetail::Full`].
+    pub geometry: Option<GeometryDetail>,
+}
+
+/// Options of the Vertiport typeahead search.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct VertiportTypeaheadQuery {
+    /// Prefix of the Vertiport name, compared case insensitive.
+    pub q: String,
+    /// Maximum number of suggestions, between 1 and 50, defaults to 10.
+    pub limit: Option<u8>,
+}
+
+/// A Vertiport suggested by the typeahead search.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct VertiportSuggestion {
+    /// The UUID of the Vertiport.
+    pub id: String,
+    /// The name of the Vertiport.
+    pub name: String,
+    /// The centroid of the Vertiport area, if the area is valid.
+    pub centroid: Option<GeoPoint>,
+}
+
+/// Pairwise distances and adjacency of the Vertipads of a Vertiport.
+///
+/// The rows and columns of the matrices follow the order of `vertipad_ids`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct VertipadAdjacency {
+    /// The UUID of the Vertiport.
+    pub vertiport_id: String,
+    /// Maximum distance in meters between adjacent Vertipads.
+    pub threshold_m: f64,
+    /// The UUIDs of the Vertipads, sorted.
+    pub vertipad_ids: Vec<String>,
+    /// Ground distance in meters between each pair of Vertipads.
+    pub distances_m: Vec<Vec<f64>>,
+    /// Whether each pair of Vertipads is within the threshold; a Vertipad is
+    /// not adjacent to itself.
+    pub adjacency: Vec<Vec<bool>>,
+}
+
+/// Options for a GeoJSON import.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, IntoParams)]
+pub struct GeoJsonImportQuery {
+    /// Create a default Vertipad at the centroid of each imported Vertiport.
+    pub create_vertipads: Option<bool>,
+}
+
+/// A Vertiport created by a GeoJSON import.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ImportedVertiport {
+    /// Index of the feature in the imported collection.
+    pub feature_index: usize,
+    /// The UUID of the created Vertiport.
+    pub vertiport_id: String,
+    /// The UUID of the created default Vertipad, if requested.
+    pub vertipad_id: Option<String>,
+}
+
+/// Options for removing an Aircraft.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct RemoveAircraftQuery {
+    /// Remove the Aircraft even if it is assigned to future flights.
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// A flight an Aircraft is assigned to which did not end yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AssignedFlight {
+    /// The UUID of the flight plan.
+    pub flight_plan_id: String,
+    /// The UUID of the departure Vertipad.
+    pub origin_vertipad_id: String,
+    /// The UUID of the arrival Vertipad.
+    pub target_vertipad_id: String,
+    /// Actual or planned departure time of the flight.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub departure: DateTime<Utc>,
+    /// Actual or planned arrival time of the flight.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub arrival: DateTime<Utc>,
+}
+
+/// Criteria selecting the Aircraft of a batch status change.
+///
+/// An Aircraft is selected if it matches all provided criteria.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AircraftStatusFilter {
+    /// The UUID of the hangar the Aircraft is stored in.
+    #[serde(default)]
+    pub hangar_id: Option<String>,
+    /// The UUID of the Asset Group of the Aircraft.
+    #[serde(default)]
+    pub asset_group_id: Option<String>,
+    /// The UUID of the model of the Aircraft.
+    #[serde(default)]
+    pub vehicle_model_id: Option<String>,
+}
+
+/// Request to change the status of multiple Aircraft.
+///
+/// Exactly one of `ids` and `filter` must be provided.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BatchAircraftStatusPayload {
+    /// The UUIDs of the Aircraft.
+    #[serde(default)]
+    pub ids: Option<Vec<String>>,
+    /// Criteria selecting the Aircraft.
+    #[serde(default)]
+    pub filter: Option<AircraftStatusFilter>,
+    /// The status to set.
+    pub status: AssetStatus,
+}
+
+/// Result of the status change of a single Aircraft.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AircraftStatusResult {
+    /// The UUID of the Aircraft.
+    pub id: String,
+    /// HTTP status code of the change, `200` if the status was set.
+    pub status: u16,
+    /// Reason the status could not be set, if any.
+    pub message: Option<String>,
+}
+
+/// Utilization of an Aircraft within a time range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AircraftUtilization {
+    /// The UUID of the Aircraft.
+    pub aircraft_id: String,
+    /// Start of the requested time range.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub from: DateTime<Utc>,
+    /// End of the requested time range.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub to: DateTime<Utc>,
+    /// Hours spent in flight within the time range.
+    pub flight_hours: f64,
+    /// Number of flight cycles (departures) within the time range.
+    pub cycles: u32,
+    /// Hours not spent in flight within the time range.
+    pub idle_hours: f64,
+}
+
+/// Number of assets imported from a snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SnapshotImportSummary {
+    /// Number of vertiports imported.
+    pub vertiports: u32,
+    /// Number of vertipads imported.
+    pub vertipads: u32,
+    /// Number of aircraft imported.
+    pub aircraft: u32,
+}
+
+/// Localized names and descriptions of an Asset.
+///
+/// The maps are keyed by locale, a language tag like `de` or `ja-JP`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AssetLocalization {
+    /// Localized names of the Asset.
+    #[serde(default)]
+    pub names: HashMap<String, String>,
+    /// Localized descriptions of the Asset.
+    #[serde(default)]
+    pub descriptions: HashMap<String, String>,
+}
+
+/// Type of a Vertipad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PadType {
+    /// Final approach and takeoff area.
+    Fato,
+    /// Touchdown and lift-off area.
+    Tlof,
+    /// A touchdown and lift-off area within its final approach and takeoff
+    /// area.
+    FatoTlof,
+}
+
+/// Surface of a Vertipad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PadSurface {
+    /// Concrete surface.
+    Concrete,
+    /// Asphalt surface.
+    Asphalt,
+    /// Steel or aluminium deck, for example on a rooftop.
+    Metal,
+    /// Composite deck.
+    Composite,
+    /// Grass surface.
+    Grass,
+}
+
+/// Classification of a Vertipad, used to schedule aircraft types on the
+/// pads able to take them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PadClassification {
+    /// Type of the Vertipad.
+    pub pad_type: PadType,
+    /// Surface of the Vertipad.
+    pub surface: PadSurface,
+    /// Maximum takeoff weight in kilograms of the aircraft the Vertipad can
+    /// take, between 1 and 20000.
+    pub max_aircraft_weight_kg: f64,
+    /// Length of the Vertipad in meters, between 1 and 500.
+    pub length_m: f64,
+    /// Width of the Vertipad in meters, between 1 and 500.
+    pub width_m: f64,
+}
+
+/// Classification filters of the Vertipad list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, IntoParams)]
+pub struct VertipadClassificationQuery {
+    /// Only return Vertipads classified for aircraft of at least this weight
+    /// in kilograms.
+    pub min_weight_kg: Option<f64>,
+}
+
+/// Connector of a Vertipad charger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChargerConnector {
+    /// Combined Charging System, type 1.
+    Ccs1,
+    /// Combined Charging System, type 2.
+    Ccs2,
+    /// CHAdeMO.
+    Chademo,
+    /// GB/T.
+    Gbt,
+    /// Megawatt Charging System.
+    Mcs,
+}
+
+/// Availability of a Vertipad charger.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChargerAvailability {
+    /// The charger can be used.
+    #[default]
+    Available,
+    /// The charger is charging an aircraft.
+    InUse,
+    /// The charger can not be used, for example during maintenance.
+    OutOfService,
+}
+
+/// Request to add or replace a charger of a Vertipad.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ChargerPayload {
+    /// Connector of the charger.
+    pub connector: ChargerConnector,
+    /// Maximum charging power in kilowatts, between 1 and 5000.
+    pub max_kw: f64,
+    /// Availability of the charger, `available` if not provided.
+    #[serde(default)]
+    pub availability: ChargerAvailability,
+}
+
+/// A charger of a Vertipad.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Charger {
+    /// The UUID of the charger.
+    pub id: String,
+    /// The UUID of the Vertipad of the charger.
+    pub vertipad_id: String,
+    /// Connector of the charger.
+    pub connector: ChargerConnector,
+    /// Maximum charging power in kilowatts.
+    pub max_kw: f64,
+    /// Availability of the charger.
+    pub availability: ChargerAvailability,
+    /// Time the charger was added.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub created_at: DateTime<Utc>,
+    /// Time the charger was last changed.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Charger filters of the Vertipad list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, IntoParams)]
+pub struct VertipadChargerQuery {
+    /// Only return Vertipads with (`true`) or without (`false`) a charger
+    /// which is not out of service.
+    pub has_charger: Option<bool>,
+    /// Only return Vertipads with a charger which is not out of service and
+    /// delivers at least this power in kilowatts.
+    pub min_kw: Option<f64>,
+}
+
+/// Kind of operational equipment of a Vertipad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EquipmentKind {
+    /// Landing and approach lighting.
+    Lighting,
+    /// Sensors, for example pad occupancy or obstacle sensors.
+    Sensor,
+    /// Weather station.
+    WeatherStation,
+}
+
+/// Status of operational equipment of a Vertipad.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EquipmentStatus {
+    /// The equipment works as expected.
+    #[default]
+    Operational,
+    /// The equipment works with limitations.
+    Degraded,
+    /// The equipment does not work.
+    Failed,
+}
+
+/// Request to add or replace operational equipment of a Vertipad.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct EquipmentPayload {
+    /// Kind of the equipment.
+    pub kind: EquipmentKind,
+    /// Name of the equipment, for example `"Approach lights north"`.
+    pub name: String,
+    /// Status of the equipment, `operational` if not provided.
+    #[serde(default)]
+    pub status: EquipmentStatus,
+    /// Time of the last inspection of the equipment, can not be in the
+    /// future.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub last_inspection: Option<DateTime<Utc>>,
+}
+
+/// Operational equipment of a Vertipad.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Equipment {
+    /// The UUID of the equipment.
+    pub id: String,
+    /// The UUID of the Vertipad of the equipment.
+    pub vertipad_id: String,
+    /// Kind of the equipment.
+    pub kind: EquipmentKind,
+    /// Name of the equipment.
+    pub name: String,
+    /// Status of the equipment.
+    pub status: EquipmentStatus,
+    /// Time of the last inspection of the equipment.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub last_inspection: Option<DateTime<Utc>>,
+    /// Time the equipment was added.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub created_at: DateTime<Utc>,
+    /// Time the equipment was last changed.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Kind of a restriction rule of a Vertiport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RestrictionKind {
+    /// No takeoffs or landings while the rule is in effect.
+    Curfew,
+    /// At most `max_movements_per_hour` takeoffs and landings while the
+    /// rule is in effect.
+    NoiseAbatement,
+}
+
+/// Request to add or replace a curfew or noise abatement rule of a
+/// Vertiport.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RestrictionRulePayload {
+    /// Name of the rule, like `night curfew`.
+    pub name: String,
+    /// Kind of the rule.
+    pub kind: RestrictionKind,
+    /// When the rule is in effect, in the schedule format of the Assets:
+    /// `DTSTART` lines with a `DURATION`, optionally followed by `RRULE`
+    /// lines.
+    pub schedule: String,
+    /// Maximum number of takeoffs and landings per hour, required by noise
+    /// abatement rules and not allowed for curfews.
+    #[serde(default)]
+    pub max_movements_per_hour: Option<u32>,
+}
+
+/// A curfew or noise abatement rule of a Vertiport.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RestrictionRule {
+    /// The UUID of the rule.
+    pub id: String,
+    /// The UUID of the Vertiport of the rule.
+    pub vertiport_id: String,
+    /// Name of the rule.
+    pub name: String,
+    /// Kind of the rule.
+    pub kind: RestrictionKind,
+    /// When the rule is in effect.
+    pub schedule: String,
+    /// Maximum number of takeoffs and landings per hour of a noise
+    /// abatement rule.
+    pub max_movements_per_hour: Option<u32>,
+    /// Time the rule was added.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub created_at: DateTime<Utc>,
+    /// Time the rule was last changed.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Time to evaluate the restrictions of a Vertiport at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct RestrictionsQuery {
+    /// The evaluated time, now if not provided.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// A restriction rule in effect at the evaluated time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ActiveRestriction {
+    /// The rule in effect.
+    pub rule: RestrictionRule,
+    /// When the rule stops being in effect, `None` if it is in effect for
+    /// more than 7 days after the evaluated time.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Restrictions of a Vertiport in effect at a time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct VertiportRestrictions {
+    /// The UUID of the Vertiport.
+    pub vertiport_id: String,
+    /// The evaluated time.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub at: DateTime<Utc>,
+    /// Whether a curfew forbids all takeoffs and landings.
+    pub curfew: bool,
+    /// Maximum number of takeoffs and landings per hour: `0` during a
+    /// curfew, the lowest limit of the noise abatement rules in effect
+    /// otherwise, `None` if movements are not limited.
+    pub max_movements_per_hour: Option<u32>,
+    /// The rules in effect, in the order they were added.
+    pub active_rules: Vec<ActiveRestriction>,
+}
+
+/// Read-only maintenance mode of the service.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ReadOnlyStatus {
+    /// Whether requests changing assets are rejected.
+    pub enabled: bool,
+}
+
+/// Audit log of the REST requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AuditLogStatus {
+    /// Whether the REST requests are recorded in the audit log.
+    pub enabled: bool,
+}
+
+/// Tenant of an asset group.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GroupTenant {
+    /// The tenant the asset group belongs to, the default tenant if not set.
+    pub tenant: Option<String>,
+}
+
+/// Filters of the changes made by an actor.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+pub struct AuditQuery {
+    /// The Operator which made the changes, the operator the API token of
+    /// the changes is bound to.
+    pub actor: String,
+    /// Only return changes made at or after this time.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub from: Option<DateTime<Utc>>,
+    /// Only return changes made before this time.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub to: Option<DateTime<Utc>>,
+    /// Maximum number of changes to return, between 1 and 1000, defaults
+    /// to 100.
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Only return changes older than this cursor, the `next_cursor` of the
+    /// previous page.
+    #[serde(default)]
+    pub cursor: Option<u64>,
+}
+
+/// A change of an Asset recorded in the audit trail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AuditChange {
+    /// Sequence number of the change, increasing with every change.
+    pub sequence: u64,
+    /// The Operator which made the change.
+    pub actor: String,
+    /// The tenant of the API token of the change, the default tenant if not
+    /// set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    /// When the change was requested.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub timestamp: DateTime<Utc>,
+    /// HTTP method of the request.
+    pub method: String,
+    /// Path of the request.
+    pub path: String,
+    /// Type of the changed Asset, the path segment after `/assets`, for
+    /// example `aircraft`.
+    pub asset_type: Option<String>,
+    /// The UUID of the changed Asset, if known.
+    pub asset_id: Option<String>,
+    /// HTTP status of the response.
+    pub status: u16,
+}
+
+/// A page of the changes made by an actor, most recent first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AuditChanges {
+    /// The changes of the page.
+    pub changes: Vec<AuditChange>,
+    /// Cursor of the next page, `None` on the last page.
+    pub next_cursor: Option<u64>,
+}
+
+/// Type of the Asset of [`AssetPermissions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionAssetType {
+    /// An aircraft.
+    Aircraft,
+    /// A vertiport.
+    Vertiport,
+    /// A vertipad, owned by the owner of its vertiport.
+    Vertipad,
+    /// An asset group.
+    Group,
+}
+
+/// Relation of an Operator to an Asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetRelation {
+    /// The Operator owns the Asset.
+    Owner,
+    /// The Asset, or the AssetGroup of the aircraft, is delegated to the
+    /// Operator.
+    Delegatee,
+    /// The Asset belongs to another Operator.
+    Other,
+    /// The owner of the Asset is not known, or no Operator was provided.
+    Unknown,
+}
+
+/// What an Operator may do with an Asset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AssetPermissions {
+    /// Type of the Asset.
+    pub asset_type: PermissionAssetType,
+    /// UUID of the Asset.
+    pub asset_id: String,
+    /// UUID of the Operator from the `x-operator-id` header, if provided.
+    pub operator_id: Option<String>,
+    /// Relation of the Operator to the Asset.
+    pub relation: AssetRelation,
+    /// The Operator may read the Asset.
+    pub read: bool,
+    /// The Operator may update the Asset.
+    pub update: bool,
+    /// The Operator may remove the Asset.
+    pub delete: bool,
+    /// The Operator may delegate the Asset, only AssetGroups can be
+    /// delegated.
+    pub delegate: bool,
+}
+
+/// Feature flag of the service.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FeatureFlag {
+    /// Name of the flag, like `groups_v2`.
+    pub name: String,
+    /// Whether the feature is enabled.
+    pub enabled: bool,
+    /// What the flag enables.
+    pub description: String,
+}
+
+/// Startup phase of the service.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupState {
+    /// Waiting for svc-storage to become ready.
+    #[default]
+    WaitingForStorage,
+    /// svc-storage became ready, the service accepts requests.
+    Ready,
+    /// svc-storage did not become ready before the startup timeout.
+    TimedOut,
+}
+
+/// Rolling latency percentiles of the svc-storage calls of a resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct StorageLatency {
+    /// Name of the svc-storage resource, like `vehicle`.
+    pub resource: String,
+    /// Number of recent calls the percentiles are computed over.
+    pub samples: usize,
+    /// Median latency in milliseconds.
+    pub p50_ms: f64,
+    /// 95th percentile latency in milliseconds.
+    pub p95_ms: f64,
+    /// 99th percentile latency in milliseconds.
+    pub p99_ms: f64,
+    /// If a percentile exceeds its configured objective.
+    pub slo_exceeded: bool,
+}
+
+/// Health of the service.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct HealthStatus {
+    /// Startup phase of the service.
+    pub startup: StartupState,
+    /// If the svc-storage latency of a resource exceeds its objectives.
+    ///
+    /// A degraded service still accepts requests.
+    #[serde(default)]
+    pub degraded: bool,
+    /// Latency of the svc-storage calls per resource.
+    #[serde(default)]
+    pub storage_latency: Vec<StorageLatency>,
+}
+
+/// State of a gRPC client connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GrpcConnectionState {
+    /// The connection was not checked yet.
+    Unknown,
+    /// The last readiness check succeeded.
+    Ready,
+    /// The last readiness check failed.
+    Unavailable,
+}
+
+/// Status of the connection of a gRPC client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GrpcConnectionStatus {
+    /// Name of the client.
+    pub name: String,
+    /// State of the connection.
+    pub state: GrpcConnectionState,
+    /// Number of readiness checks failed since the last successful check.
+    pub consecutive_failures: u32,
+    /// Number of times the connection was dropped to reconnect.
+    pub reconnects: u64,
+    /// When the state last changed.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub last_change: Option<DateTime<Utc>>,
+}
@@ -16,9 +16,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .out_dir("../client-grpc/src/")
         .compile(&[proto_file], &[proto_dir])?;
 
-    // Build the Server
+    // Build the Server, including the file descriptor set used by the reflection service
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
     server_config
         .build_client(false)
+        .file_descriptor_set_path(out_dir.join("grpc_descriptor.bin"))
         .compile(&[proto_file], &[proto_dir])?;
 
     println!("cargo:rerun-if-changed={}", proto_file);
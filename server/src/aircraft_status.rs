@@ -0,0 +1,93 @@
+//! # Aircraft Status
+//!
+//! Status of an aircraft set by a trusted service through the
+//! `setAssetStatus` gRPC method, for example the scheduler grounding an
+//! aircraft. The status replaces the
+//! [`AssetStatus`](crate::rest::structs::AssetStatus) reported by the REST
+//! API, but an aircraft with an overdue maintenance or held for maintenance
+//! is still reported `Unavailable`.
+//!
+//! svc-storage does not store an aircraft status, so [`AircraftStatuses`]
+//! keeps it in the [`AIRCRAFT_STATUSES_TABLE`] of the persisted state, see
+//! [`crate::state`]. A grounded aircraft stays grounded after a restart, but
+//! a status set through one replica is not reported by the others.
+
+use crate::rest::structs::AssetStatus;
+use crate::state::{StateError, StateTable};
+
+/// Name of the table of the aircraft statuses in the state directory
+pub const AIRCRAFT_STATUSES_TABLE: &str = "aircraft_statuses";
+
+/// Store of the status per aircraft id.
+///
+/// The default store is kept in memory, [`AircraftStatuses::open`] persists
+/// the statuses.
+#[derive(Debug, Clone, Default)]
+pub struct AircraftStatuses {
+    statuses: StateTable<AssetStatus>,
+}
+
+impl AircraftStatuses {
+    /// Open the statuses persisted in the state directory, kept in memory if
+    /// no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            statuses: StateTable::open(dir, AIRCRAFT_STATUSES_TABLE)?,
+        })
+    }
+
+    /// Set the status of an aircraft.
+    ///
+    /// Setting an aircraft `Available` removes its status, as that is the
+    /// status of an aircraft without one.
+    pub fn set(&self, aircraft_id: &str, status: AssetStatus) -> Result<(), StateError> {
+        match status {
+            AssetStatus::Available => self.statuses.remove(aircraft_id)?,
+            _ => self.statuses.insert(aircraft_id, status)?,
+        };
+        Ok(())
+    }
+
+    /// Get the status of an aircraft, if set
+    pub fn get(&self, aircraft_id: &str) -> Option<AssetStatus> {
+        self.statuses.get(aircraft_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aircraft_statuses() {
+        let statuses = AircraftStatuses::default();
+        assert_eq!(statuses.get("aircraft"), None);
+
+        statuses.set("aircraft", AssetStatus::Emergency).unwrap();
+        assert_eq!(statuses.get("aircraft"), Some(AssetStatus::Emergency));
+        assert_eq!(statuses.get("other"), None);
+
+        statuses.set("aircraft", AssetStatus::Unavailable).unwrap();
+        assert_eq!(statuses.get("aircraft"), Some(AssetStatus::Unavailable));
+
+        statuses.set("aircraft", AssetStatus::Available).unwrap();
+        assert_eq!(statuses.get("aircraft"), None);
+    }
+
+    #[test]
+    fn test_aircraft_statuses_persisted() {
+        let dir = crate::state::test_state_dir();
+
+        let statuses = AircraftStatuses::open(Some(&dir)).unwrap();
+        statuses.set("grounded", AssetStatus::Unavailable).unwrap();
+        statuses.set("cleared", AssetStatus::Emergency).unwrap();
+        statuses.set("cleared", AssetStatus::Available).unwrap();
+
+        // the statuses survive a restart
+        let statuses = AircraftStatuses::open(Some(&dir)).unwrap();
+        assert_eq!(statuses.get("grounded"), Some(AssetStatus::Unavailable));
+        assert_eq!(statuses.get("cleared"), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
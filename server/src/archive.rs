@@ -0,0 +1,203 @@
+//! # Archive
+//!
+//! Assets removed more than `archive_after_days` days ago are listed in the
+//! archive. Removed assets are already excluded from all other queries, the
+//! archive only lists a compact entry per asset so old fleets can be looked
+//! up without cluttering the current listings.
+//!
+//! svc-storage can not undo the removal of an asset, so an archived asset is
+//! restored by registering its data again as a new asset. [`RestoredAssets`]
+//! keeps the id of the restored asset per archived asset in the
+//! [`RESTORED_ASSETS_TABLE`] of the persisted state, see [`crate::state`], so
+//! a restored asset is not listed or restored again after a restart.
+//!
+//! svc-storage does not return the removal time of an asset either, the
+//! assets removed through this service are recorded in the
+//! [`REMOVED_ASSETS_TABLE`] by [`RemovedAssets`]. Assets removed by other
+//! services are reported without a `deleted_at`, which does not change
+//! whether they are archived.
+
+use crate::state::{StateError, StateTable};
+use lib_common::time::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Name of the table of the restored assets in the state directory
+pub const RESTORED_ASSETS_TABLE: &str = "restored_assets";
+
+/// Name of the table of the removal times in the state directory
+pub const REMOVED_ASSETS_TABLE: &str = "removed_assets";
+
+/// Store of the restored assets, the id of the restored asset per archived
+/// asset id.
+///
+/// The archived assets being restored are claimed in memory only, so a
+/// restore interrupted by a restart can be tried again.
+#[derive(Debug, Clone, Default)]
+pub struct RestoredAssets {
+    ids: StateTable<String>,
+    claimed: Arc<Mutex<HashSet<String>>>,
+}
+
+impl RestoredAssets {
+    /// Open the restored assets persisted in the state directory, see
+    /// [`StateTable::open`]
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            ids: StateTable::open(dir, RESTORED_ASSETS_TABLE)?,
+            claimed: Arc::default(),
+        })
+    }
+
+    fn claimed(&self) -> MutexGuard<'_, HashSet<String>> {
+        self.claimed
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Claim an archived asset before restoring it.
+    ///
+    /// Returns `false` if the asset has been restored or is being restored.
+    pub fn claim(&self, archived_id: &str) -> bool {
+        let mut claimed = self.claimed();
+        if claimed.contains(archived_id) || self.ids.get(archived_id).is_some() {
+            return false;
+        }
+
+        claimed.insert(archived_id.to_string())
+    }
+
+    /// Release a claimed archived asset which could not be restored
+    pub fn release(&self, archived_id: &str) {
+        self.claimed().remove(archived_id);
+    }
+
+    /// Record the id of the asset restored from a claimed archived asset.
+    ///
+    /// The asset stays claimed if the id can not be written, so it is not
+    /// restored twice by this instance.
+    pub fn commit(&self, archived_id: &str, id: &str) -> Result<(), StateError> {
+        let mut claimed = self.claimed();
+        self.ids.insert(archived_id, id.to_string())?;
+        claimed.remove(archived_id);
+
+        Ok(())
+    }
+
+    /// Check if an archived asset has been restored or is being restored
+    pub fn contains(&self, archived_id: &str) -> bool {
+        self.claimed().contains(archived_id) || self.ids.get(archived_id).is_some()
+    }
+
+    /// Get the id of the asset restored from an archived asset
+    pub fn restored_id(&self, archived_id: &str) -> Option<String> {
+        self.ids.get(archived_id)
+    }
+}
+
+/// Store of the removal times of the assets removed through this service,
+/// by asset id.
+///
+/// Assets removed by other services have no recorded removal time.
+#[derive(Debug, Clone, Default)]
+pub struct RemovedAssets {
+    times: StateTable<DateTime<Utc>>,
+}
+
+impl RemovedAssets {
+    /// Open the removal times persisted in the state directory, see
+    /// [`StateTable::open`]
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            times: StateTable::open(dir, REMOVED_ASSETS_TABLE)?,
+        })
+    }
+
+    /// Record the removal of an asset.
+    ///
+    /// The asset is removed even if its removal time could not be written,
+    /// so the error is only logged.
+    pub fn record(&self, id: &str, removed_at: DateTime<Utc>) {
+        if let Err(e) = self.times.insert(id, removed_at) {
+            log::warn!(
+                "(RemovedAssets) could not record the removal of {}: {}",
+                id,
+                e
+            );
+        }
+    }
+
+    /// Get the recorded removal time of an asset
+    pub fn removed_at(&self, id: &str) -> Option<DateTime<Utc>> {
+        self.times.get(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::test_state_dir;
+
+    #[test]
+    fn test_removed_assets() {
+        let removed = RemovedAssets::default();
+        assert_eq!(removed.removed_at("asset"), None);
+
+        let now = Utc::now();
+        removed.record("asset", now);
+        assert_eq!(removed.removed_at("asset"), Some(now));
+        assert_eq!(removed.removed_at("other"), None);
+    }
+
+    #[test]
+    fn test_restored_assets() {
+        let restored = RestoredAssets::default();
+        assert!(!restored.contains("archived"));
+
+        assert!(restored.claim("archived"));
+        assert!(!restored.claim("archived"));
+        assert!(restored.contains("archived"));
+        assert_eq!(restored.restored_id("archived"), None);
+
+        restored.release("archived");
+        assert!(!restored.contains("archived"));
+
+        assert!(restored.claim("archived"));
+        restored.commit("archived", "restored").unwrap();
+        assert_eq!(
+            restored.restored_id("archived"),
+            Some("restored".to_string())
+        );
+
+        // a restored asset can not be released
+        restored.release("archived");
+        assert!(restored.contains("archived"));
+        assert!(!restored.claim("archived"));
+    }
+
+    #[test]
+    fn test_archive_persisted() {
+        let dir = test_state_dir();
+
+        let restored = RestoredAssets::open(Some(&dir)).unwrap();
+        assert!(restored.claim("archived"));
+        restored.commit("archived", "restored").unwrap();
+        // an interrupted restore is not persisted
+        assert!(restored.claim("interrupted"));
+        let removed = RemovedAssets::open(Some(&dir)).unwrap();
+        let now = Utc::now();
+        removed.record("asset", now);
+
+        let restored = RestoredAssets::open(Some(&dir)).unwrap();
+        assert_eq!(
+            restored.restored_id("archived"),
+            Some("restored".to_string())
+        );
+        assert!(!restored.claim("archived"));
+        assert!(restored.claim("interrupted"));
+        let removed = RemovedAssets::open(Some(&dir)).unwrap();
+        assert_eq!(removed.removed_at("asset"), Some(now));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,181 @@
+//! # Calendar
+//!
+//! iCalendar ([RFC 5545](https://www.rfc-editor.org/rfc/rfc5545)) feeds,
+//! so maintenance teams can subscribe to the maintenance dates of the fleet
+//! in their calendar application.
+//!
+//! Only the properties used by this service are supported: a calendar of
+//! events with a start, an optional end, a summary and a description.
+
+use lib_common::time::{DateTime, Utc};
+
+/// Content type of a calendar body
+pub const CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/// Identifier of the product which created the calendar
+const PRODUCT_ID: &str = "-//Aetheric//svc-assets//EN";
+
+/// Maximum length of a content line in octets, excluding the line break
+const MAX_LINE_OCTETS: usize = 75;
+
+/// An event of a calendar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarEvent {
+    /// Globally unique identifier of the event, stable across exports so
+    /// calendar applications update the event instead of duplicating it
+    pub uid: String,
+    /// Start of the event
+    pub start: DateTime<Utc>,
+    /// End of the event, the event has no duration if not provided
+    pub end: Option<DateTime<Utc>>,
+    /// Title of the event
+    pub summary: String,
+    /// Details of the event, if any
+    pub description: Option<String>,
+}
+
+/// Format a time as a UTC date-time value
+fn date_time(time: &DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a text value
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Append a content line, folded into lines of at most
+/// [`MAX_LINE_OCTETS`] octets without splitting a character
+fn push_line(calendar: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            calendar.push_str("\r\n ");
+            // the leading space of the continuation counts
+            octets = 1;
+        }
+        calendar.push(c);
+        octets += c.len_utf8();
+    }
+    calendar.push_str("\r\n");
+}
+
+/// Render a calendar with the provided name and events.
+///
+/// `now` is the time the calendar is created, used as the `DTSTAMP` of the
+/// events.
+pub fn render(name: &str, events: &[CalendarEvent], now: DateTime<Utc>) -> String {
+    let mut calendar = String::new();
+    push_line(&mut calendar, "BEGIN:VCALENDAR");
+    push_line(&mut calendar, "VERSION:2.0");
+    push_line(&mut calendar, &format!("PRODID:{PRODUCT_ID}"));
+    push_line(&mut calendar, "CALSCALE:GREGORIAN");
+    push_line(&mut calendar, "METHOD:PUBLISH");
+    push_line(&mut calendar, &format!("X-WR-CALNAME:{}", escape(name)));
+
+    for event in events {
+        push_line(&mut calendar, "BEGIN:VEVENT");
+        push_line(&mut calendar, &format!("UID:{}", escape(&event.uid)));
+        push_line(&mut calendar, &format!("DTSTAMP:{}", date_time(&now)));
+        push_line(
+            &mut calendar,
+            &format!("DTSTART:{}", date_time(&event.start)),
+        );
+        if let Some(end) = &event.end {
+            push_line(&mut calendar, &format!("DTEND:{}", date_time(end)));
+        }
+        push_line(
+            &mut calendar,
+            &format!("SUMMARY:{}", escape(&event.summary)),
+        );
+        if let Some(description) = &event.description {
+            push_line(
+                &mut calendar,
+                &format!("DESCRIPTION:{}", escape(description)),
+            );
+        }
+        push_line(&mut calendar, "END:VEVENT");
+    }
+
+    push_line(&mut calendar, "END:VCALENDAR");
+    calendar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a\\b;c,d\r\ne"), "a\\\\b\\;c\\,d\\ne".to_string());
+    }
+
+    #[test]
+    fn test_push_line() {
+        let mut calendar = String::new();
+        push_line(&mut calendar, &"a".repeat(160));
+        let lines: Vec<&str> = calendar.split("\r\n").collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0].len(), 75);
+        assert!(lines[1].starts_with(' ') && lines[1].len() == 75);
+        assert_eq!(lines[2], format!(" {}", "a".repeat(11)));
+        assert_eq!(lines[3], "");
+
+        // multi-byte characters are not split
+        let mut calendar = String::new();
+        push_line(&mut calendar, &"é".repeat(40));
+        for line in calendar.split("\r\n") {
+            assert!(line.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(
+            calendar.replace("\r\n ", ""),
+            format!("{}\r\n", "é".repeat(40))
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap();
+        let events = vec![
+            CalendarEvent {
+                uid: "maintenance-1@svc-assets".to_string(),
+                start: now + Duration::days(2),
+                end: None,
+                summary: "Maintenance due: N12345".to_string(),
+                description: None,
+            },
+            CalendarEvent {
+                uid: "hold-2@svc-assets".to_string(),
+                start: now,
+                end: Some(now + Duration::hours(4)),
+                summary: "Maintenance hold: N12345".to_string(),
+                description: Some("rotor inspection, phase 2".to_string()),
+            },
+        ];
+
+        let calendar = render("Maintenance", &events, now);
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+        assert!(calendar.contains("X-WR-CALNAME:Maintenance\r\n"));
+        assert!(calendar.contains(
+            "UID:maintenance-1@svc-assets\r\nDTSTAMP:20260301T080000Z\r\n\
+             DTSTART:20260303T080000Z\r\nSUMMARY:Maintenance due: N12345\r\n"
+        ));
+        assert!(calendar.contains("DTSTART:20260301T080000Z\r\nDTEND:20260301T120000Z\r\n"));
+        assert!(calendar.contains("DESCRIPTION:rotor inspection\\, phase 2\r\n"));
+    }
+}
@@ -3,17 +3,60 @@
 //! Define and implement config options for module
 
 use anyhow::Result;
-use config::{ConfigError, Environment};
+use config::{ConfigError, Environment, File};
 use dotenv::dotenv;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+/// Handling of the vertipads of a removed vertiport
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VertiportDeletePolicy {
+    /// Reject removing a vertiport which still has vertipads
+    #[default]
+    Reject,
+    /// Remove the vertipads together with the vertiport
+    Cascade,
+}
+
+/// Handling of vertipad schedules allowing operation while the vertiport is
+/// closed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleConflictMode {
+    /// Reject the schedule update
+    #[default]
+    Strict,
+    /// Accept the schedule update with a warning
+    Lenient,
+}
+
+/// Handling of storage objects which can not be converted by the list
+/// endpoints, see [`crate::rest::api::conversion`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversionMode {
+    /// Leave the object out of the list
+    #[default]
+    Skip,
+    /// Fill the missing fields with defaults and report a warning
+    Lenient,
+    /// Fail the request with a `502 BAD GATEWAY`
+    Strict,
+    /// Leave the object out of the list and report it in a
+    /// `207 Multi-Status` response
+    Report,
+}
 
 /// struct holding configuration options
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config {
     /// port to be used for gRPC server
     pub docker_port_grpc: u16,
     /// port to be used for REST server
     pub docker_port_rest: u16,
+    /// Optional port of the admin endpoints, served on the REST port if not set
+    pub docker_port_admin: Option<u16>,
     /// host of storage server
     pub storage_host_grpc: String,
     /// port of storage server
@@ -27,6 +70,127 @@ pub struct Config {
     /// Full url (including port number) to be allowed as request origin for
     /// REST requests
     pub rest_cors_allowed_origin: String,
+    /// Maximum number of aircraft an operator can register, `0` for unlimited
+    pub max_aircraft_per_operator: u32,
+    /// Maximum number of vertiports an operator can register, `0` for unlimited
+    pub max_vertiports_per_operator: u32,
+    /// Seconds between checks for aircraft with an overdue maintenance, `0` to disable
+    pub maintenance_check_interval_secs: u64,
+    /// Seconds between refreshes of the vertiport typeahead index, `0` to
+    /// disable
+    pub vertiport_index_refresh_secs: u64,
+    /// Timeout in milliseconds of a single svc-storage call, `0` to disable
+    pub storage_call_timeout_ms: u64,
+    /// Timeout in milliseconds of a whole REST request, `0` to disable, see
+    /// [`crate::rest::timeout`]
+    pub rest_request_timeout_ms: u64,
+    /// Objective in milliseconds of the 95th percentile latency of the
+    /// svc-storage calls, `0` to disable, see [`crate::latency`]
+    pub storage_latency_slo_p95_ms: u64,
+    /// Objective in milliseconds of the 99th percentile latency of the
+    /// svc-storage calls, `0` to disable
+    pub storage_latency_slo_p99_ms: u64,
+    /// Seconds to wait for svc-storage at startup before exiting, `0` to
+    /// wait without a timeout, see [`crate::startup`]
+    pub startup_timeout_secs: u64,
+    /// Milliseconds between the first readiness checks at startup, doubled
+    /// after each failed check
+    pub startup_backoff_initial_ms: u64,
+    /// Maximum milliseconds between readiness checks at startup
+    pub startup_backoff_max_ms: u64,
+    /// Maximum size in bytes of a JSON request body
+    pub rest_max_body_bytes: usize,
+    /// Maximum size in bytes of a JSON request body for bulk and import routes
+    pub rest_max_bulk_body_bytes: usize,
+    /// Maximum number of assets returned by a list endpoint, `0` for no
+    /// limit, see [`crate::rest::api::list`]
+    pub rest_list_max_results: usize,
+    /// Number of assets returned by a list endpoint above which a warning
+    /// is returned, `0` to never warn
+    pub rest_list_warn_results: usize,
+    /// Reject requests which change assets, see [`crate::rest::read_only`]
+    pub read_only: bool,
+    /// Record the REST requests in the audit log, see [`crate::rest::audit`]
+    pub audit_log: bool,
+    /// Body fields redacted in the audit log, compared case insensitive
+    pub audit_log_redact_fields: Vec<String>,
+    /// Handling of the vertipads of a removed vertiport
+    pub vertiport_delete_policy: VertiportDeletePolicy,
+    /// Maximum distance in meters between adjacent vertipads of a vertiport
+    pub vertipad_adjacency_threshold_m: f64,
+    /// Maximum distance in meters a vertipad may lie outside the area of its
+    /// vertiport
+    pub vertipad_location_tolerance_m: f64,
+    /// Handling of vertipad schedules conflicting with the schedule of their
+    /// vertiport
+    pub schedule_conflict_mode: ScheduleConflictMode,
+    /// Handling of storage objects the list endpoints can not convert
+    pub conversion_mode: ConversionMode,
+    /// Days after which a removed asset is listed in the archive, see
+    /// [`crate::archive`]
+    pub archive_after_days: u32,
+    /// Serve the asset group schedule and delegation endpoints, see
+    /// [`crate::features`]
+    pub enable_groups_v2: bool,
+    /// Serve the public asset listings under `/assets/demo`
+    pub enable_public_api: bool,
+    /// Reject request bodies with unknown fields, see [`crate::rest::json`]
+    pub strict_validation: bool,
+    /// Optional url of an S3-compatible object store for asset attachments,
+    /// attachments are kept in memory if not set
+    pub object_store_endpoint: Option<String>,
+    /// Bucket of the object store
+    pub object_store_bucket: String,
+    /// Region of the object store
+    pub object_store_region: String,
+    /// Access key of the object store
+    pub object_store_access_key: Option<String>,
+    /// Secret key of the object store
+    #[serde(skip_serializing)]
+    pub object_store_secret_key: Option<String>,
+    /// Bearer token required by the admin endpoints, see [`crate::rest::admin`].
+    ///
+    /// The admin endpoints reject all requests if not set.
+    #[serde(skip_serializing)]
+    pub admin_token: Option<String>,
+    /// Bearer tokens of the REST API with their scopes, like
+    /// `<token>=assets:read assets:write`, see [`crate::rest::auth`].
+    ///
+    /// The REST API does not require a token if not set.
+    #[serde(skip_serializing)]
+    pub api_tokens: Vec<String>,
+    /// Response fields redacted for API tokens without a scope, like
+    /// `<field>=<scope>`, see [`crate::rest::redaction`]
+    pub response_redact_fields: Vec<String>,
+    /// Tenants sharing the service, see [`crate::rest::tenant`]. The service
+    /// is single tenant if empty.
+    pub tenants: Vec<String>,
+    /// Peer identities allowed to call the `registerVehicle` gRPC method,
+    /// see [`crate::grpc::auth`]
+    pub grpc_register_vehicle_peers: Vec<String>,
+    /// Peer identities allowed to call the `updateVertipadOccupancy` gRPC
+    /// method
+    pub grpc_update_vertipad_occupancy_peers: Vec<String>,
+    /// Peer identities allowed to call the `setAssetStatus` gRPC method
+    pub grpc_set_asset_status_peers: Vec<String>,
+    /// Path of the PEM encoded server certificate chain, TLS is enabled if
+    /// set together with `tls_key_path`, see [`crate::tls`]
+    pub tls_cert_path: Option<String>,
+    /// Path of the PEM encoded server private key
+    pub tls_key_path: Option<String>,
+    /// Path of the PEM encoded CA certificates used to verify client
+    /// certificates
+    pub tls_client_ca_path: Option<String>,
+    /// Reject clients without a valid certificate
+    pub tls_client_auth_required: bool,
+    /// Directory of the persisted side tables, see [`crate::state`]. The
+    /// tables are kept in memory and lost on restart if not set.
+    pub state_dir: Option<String>,
+    /// Optional path to a configuration file.
+    ///
+    /// Values in this file take precedence over environment variables and
+    /// can be reloaded at runtime by sending a SIGHUP signal to the process.
+    pub config_file: Option<String>,
 }
 
 impl Default for Config {
@@ -42,12 +206,69 @@ impl Config {
         Config {
             docker_port_grpc: 50051,
             docker_port_rest: 8000,
+            docker_port_admin: None,
             storage_port_grpc: 50051,
             storage_host_grpc: String::from("svc-storage"),
             log_config: String::from("log4rs.yaml"),
             rest_request_limit_per_second: 2,
             rest_concurrency_limit_per_service: 5,
             rest_cors_allowed_origin: String::from("http://localhost:3000"),
+            max_aircraft_per_operator: 0,
+            max_vertiports_per_operator: 0,
+            maintenance_check_interval_secs: 3600,
+            vertiport_index_refresh_secs: 60,
+            storage_call_timeout_ms: 5000,
+            rest_request_timeout_ms: 10000,
+            storage_latency_slo_p95_ms: 1000,
+            storage_latency_slo_p99_ms: 2500,
+            startup_timeout_secs: 300,
+            startup_backoff_initial_ms: 500,
+            startup_backoff_max_ms: 10000,
+            rest_max_body_bytes: 64 * 1024,
+            rest_max_bulk_body_bytes: 16 * 1024 * 1024,
+            rest_list_max_results: 10000,
+            rest_list_warn_results: 1000,
+            read_only: false,
+            audit_log: false,
+            audit_log_redact_fields: [
+                "email",
+                "phone",
+                "password",
+                "token",
+                "secret",
+                "access_key",
+                "secret_key",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            vertiport_delete_policy: VertiportDeletePolicy::Reject,
+            vertipad_adjacency_threshold_m: 30.0,
+            vertipad_location_tolerance_m: 10.0,
+            schedule_conflict_mode: ScheduleConflictMode::Strict,
+            conversion_mode: ConversionMode::Skip,
+            archive_after_days: 30,
+            enable_groups_v2: true,
+            enable_public_api: true,
+            strict_validation: false,
+            object_store_endpoint: None,
+            object_store_bucket: String::from("svc-assets"),
+            object_store_region: String::from("us-east-1"),
+            object_store_access_key: None,
+            object_store_secret_key: None,
+            admin_token: None,
+            api_tokens: vec![],
+            response_redact_fields: vec![],
+            tenants: vec![],
+            grpc_register_vehicle_peers: vec![],
+            grpc_update_vertipad_occupancy_peers: vec![],
+            grpc_set_asset_status_peers: vec![],
+            tls_cert_path: None,
+            tls_key_path: None,
+            tls_client_ca_path: None,
+            tls_client_auth_required: false,
+            state_dir: None,
+            config_file: None,
         }
     }
 
@@ -57,7 +278,7 @@ impl Config {
         dotenv().ok();
         let default_config = Config::default();
 
-        config::Config::builder()
+        let builder = config::Config::builder()
             .set_default("docker_port_grpc", default_config.docker_port_grpc)?
             .set_default("docker_port_rest", default_config.docker_port_rest)?
             .set_default("log_config", default_config.log_config)?
@@ -73,9 +294,352 @@ impl Config {
                 "rest_cors_allowed_origin",
                 default_config.rest_cors_allowed_origin,
             )?
-            .add_source(Environment::default().separator("__"))
-            .build()?
-            .try_deserialize()
+            .set_default(
+                "max_aircraft_per_operator",
+                default_config.max_aircraft_per_operator,
+            )?
+            .set_default(
+                "max_vertiports_per_operator",
+                default_config.max_vertiports_per_operator,
+            )?
+            .set_default(
+                "maintenance_check_interval_secs",
+                default_config.maintenance_check_interval_secs,
+            )?
+            .set_default(
+                "vertiport_index_refresh_secs",
+                default_config.vertiport_index_refresh_secs,
+            )?
+            .set_default(
+                "storage_call_timeout_ms",
+                default_config.storage_call_timeout_ms,
+            )?
+            .set_default(
+                "rest_request_timeout_ms",
+                default_config.rest_request_timeout_ms,
+            )?
+            .set_default(
+                "storage_latency_slo_p95_ms",
+                default_config.storage_latency_slo_p95_ms,
+            )?
+            .set_default(
+                "storage_latency_slo_p99_ms",
+                default_config.storage_latency_slo_p99_ms,
+            )?
+            .set_default("startup_timeout_secs", default_config.startup_timeout_secs)?
+            .set_default(
+                "startup_backoff_initial_ms",
+                default_config.startup_backoff_initial_ms,
+            )?
+            .set_default(
+                "startup_backoff_max_ms",
+                default_config.startup_backoff_max_ms,
+            )?
+            .set_default(
+                "rest_max_body_bytes",
+                default_config.rest_max_body_bytes as u64,
+            )?
+            .set_default(
+                "rest_max_bulk_body_bytes",
+                default_config.rest_max_bulk_body_bytes as u64,
+            )?
+            .set_default(
+                "rest_list_max_results",
+                default_config.rest_list_max_results as u64,
+            )?
+            .set_default(
+                "rest_list_warn_results",
+                default_config.rest_list_warn_results as u64,
+            )?
+            .set_default("read_only", default_config.read_only)?
+            .set_default("audit_log", default_config.audit_log)?
+            .set_default(
+                "audit_log_redact_fields",
+                default_config.audit_log_redact_fields,
+            )?
+            .set_default("vertiport_delete_policy", "reject")?
+            .set_default(
+                "vertipad_adjacency_threshold_m",
+                default_config.vertipad_adjacency_threshold_m,
+            )?
+            .set_default(
+                "vertipad_location_tolerance_m",
+                default_config.vertipad_location_tolerance_m,
+            )?
+            .set_default("schedule_conflict_mode", "strict")?
+            .set_default("conversion_mode", "skip")?
+            .set_default("archive_after_days", default_config.archive_after_days)?
+            .set_default("enable_groups_v2", default_config.enable_groups_v2)?
+            .set_default("enable_public_api", default_config.enable_public_api)?
+            .set_default("strict_validation", default_config.strict_validation)?
+            .set_default("object_store_bucket", default_config.object_store_bucket)?
+            .set_default("object_store_region", default_config.object_store_region)?
+            .set_default("api_tokens", default_config.api_tokens)?
+            .set_default(
+                "response_redact_fields",
+                default_config.response_redact_fields,
+            )?
+            .set_default("tenants", default_config.tenants)?
+            .set_default(
+                "grpc_register_vehicle_peers",
+                default_config.grpc_register_vehicle_peers,
+            )?
+            .set_default(
+                "grpc_update_vertipad_occupancy_peers",
+                default_config.grpc_update_vertipad_occupancy_peers,
+            )?
+            .set_default(
+                "grpc_set_asset_status_peers",
+                default_config.grpc_set_asset_status_peers,
+            )?
+            .set_default(
+                "tls_client_auth_required",
+                default_config.tls_client_auth_required,
+            )?
+            // The API tokens, tenants, gRPC peer identities and redacted
+            // fields are comma separated lists
+            .add_source(
+                Environment::default()
+                    .separator("__")
+                    .list_separator(",")
+                    .with_list_parse_key("api_tokens")
+                    .with_list_parse_key("response_redact_fields")
+                    .with_list_parse_key("tenants")
+                    .with_list_parse_key("grpc_register_vehicle_peers")
+                    .with_list_parse_key("grpc_update_vertipad_occupancy_peers")
+                    .with_list_parse_key("grpc_set_asset_status_peers")
+                    .with_list_parse_key("audit_log_redact_fields"),
+            );
+
+        // The config file (if any) is added last so its values can be changed at runtime
+        let builder = match std::env::var("CONFIG_FILE") {
+            Ok(config_file) => builder.add_source(File::with_name(&config_file).required(false)),
+            Err(_) => builder,
+        };
+
+        builder.build()?.try_deserialize()
+    }
+}
+
+impl Config {
+    /// Get the names of the values which differ from the provided
+    /// configuration and can only be changed with a restart
+    pub fn restart_required(&self, new: &Config) -> Vec<&'static str> {
+        [
+            (
+                "docker_port_grpc",
+                self.docker_port_grpc != new.docker_port_grpc,
+            ),
+            (
+                "docker_port_rest",
+                self.docker_port_rest != new.docker_port_rest,
+            ),
+            (
+                "docker_port_admin",
+                self.docker_port_admin != new.docker_port_admin,
+            ),
+            (
+                "storage_host_grpc",
+                self.storage_host_grpc != new.storage_host_grpc,
+            ),
+            (
+                "storage_port_grpc",
+                self.storage_port_grpc != new.storage_port_grpc,
+            ),
+            ("log_config", self.log_config != new.log_config),
+            (
+                "startup_timeout_secs",
+                self.startup_timeout_secs != new.startup_timeout_secs,
+            ),
+            (
+                "startup_backoff_initial_ms",
+                self.startup_backoff_initial_ms != new.startup_backoff_initial_ms,
+            ),
+            (
+                "startup_backoff_max_ms",
+                self.startup_backoff_max_ms != new.startup_backoff_max_ms,
+            ),
+            (
+                "object_store_endpoint",
+                self.object_store_endpoint != new.object_store_endpoint,
+            ),
+            (
+                "object_store_bucket",
+                self.object_store_bucket != new.object_store_bucket,
+            ),
+            (
+                "object_store_region",
+                self.object_store_region != new.object_store_region,
+            ),
+            (
+                "object_store_access_key",
+                self.object_store_access_key != new.object_store_access_key,
+            ),
+            (
+                "object_store_secret_key",
+                self.object_store_secret_key != new.object_store_secret_key,
+            ),
+            (
+                "grpc_register_vehicle_peers",
+                self.grpc_register_vehicle_peers != new.grpc_register_vehicle_peers,
+            ),
+            (
+                "grpc_update_vertipad_occupancy_peers",
+                self.grpc_update_vertipad_occupancy_peers
+                    != new.grpc_update_vertipad_occupancy_peers,
+            ),
+            (
+                "grpc_set_asset_status_peers",
+                self.grpc_set_asset_status_peers != new.grpc_set_asset_status_peers,
+            ),
+            ("tls_cert_path", self.tls_cert_path != new.tls_cert_path),
+            ("tls_key_path", self.tls_key_path != new.tls_key_path),
+            (
+                "tls_client_ca_path",
+                self.tls_client_ca_path != new.tls_client_ca_path,
+            ),
+            (
+                "tls_client_auth_required",
+                self.tls_client_auth_required != new.tls_client_auth_required,
+            ),
+            ("state_dir", self.state_dir != new.state_dir),
+        ]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect()
+    }
+}
+
+/// Shared handle to the service [`Config`] which can be reloaded at runtime.
+///
+/// Only a subset of the configuration can be changed without a restart,
+/// see [`SharedConfig::apply`].
+#[derive(Debug, Clone)]
+pub struct SharedConfig {
+    inner: Arc<RwLock<Config>>,
+}
+
+impl From<Config> for SharedConfig {
+    fn from(config: Config) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(config)),
+        }
+    }
+}
+
+impl SharedConfig {
+    /// Get a copy of the current configuration
+    pub fn get(&self) -> Config {
+        self.read(|config| config.clone())
+    }
+
+    /// Read a value from the current configuration
+    pub fn read<T>(&self, f: impl FnOnce(&Config) -> T) -> T {
+        match self.inner.read() {
+            Ok(config) => f(&config),
+            Err(poisoned) => f(&poisoned.into_inner()),
+        }
+    }
+
+    /// Apply the reloadable values of the provided configuration.
+    ///
+    /// Reloadable values are the REST rate limit, concurrency limit, CORS
+    /// allowed origin, the operator asset quotas, the maintenance check
+    /// interval, the storage call timeout, the request body size limits, the
+    /// read-only mode, the audit log settings, the vertiport delete policy, the
+    /// vertipad adjacency threshold, the schedule conflict mode, the archive
+    /// age, the feature flags, the admin token, the API tokens and the
+    /// tenants.
+    /// Changes to any other values are ignored with a warning for each
+    /// changed value, see [`Config::restart_required`].
+    pub fn apply(&self, new: Config) {
+        let mut config = match self.inner.write() {
+            Ok(config) => config,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        for field in config.restart_required(&new) {
+            log::warn!(
+                "(SharedConfig::apply) {} can not be changed at runtime, restart required.",
+                field
+            );
+        }
+
+        config.rest_request_limit_per_second = new.rest_request_limit_per_second;
+        config.rest_concurrency_limit_per_service = new.rest_concurrency_limit_per_service;
+        config.rest_cors_allowed_origin = new.rest_cors_allowed_origin;
+        config.max_aircraft_per_operator = new.max_aircraft_per_operator;
+        config.max_vertiports_per_operator = new.max_vertiports_per_operator;
+        config.maintenance_check_interval_secs = new.maintenance_check_interval_secs;
+        config.vertiport_index_refresh_secs = new.vertiport_index_refresh_secs;
+        config.storage_call_timeout_ms = new.storage_call_timeout_ms;
+        config.rest_request_timeout_ms = new.rest_request_timeout_ms;
+        config.storage_latency_slo_p95_ms = new.storage_latency_slo_p95_ms;
+        config.storage_latency_slo_p99_ms = new.storage_latency_slo_p99_ms;
+        config.rest_max_body_bytes = new.rest_max_body_bytes;
+        config.rest_max_bulk_body_bytes = new.rest_max_bulk_body_bytes;
+        config.rest_list_max_results = new.rest_list_max_results;
+        config.rest_list_warn_results = new.rest_list_warn_results;
+        config.read_only = new.read_only;
+        config.audit_log = new.audit_log;
+        config.audit_log_redact_fields = new.audit_log_redact_fields;
+        config.vertiport_delete_policy = new.vertiport_delete_policy;
+        config.vertipad_adjacency_threshold_m = new.vertipad_adjacency_threshold_m;
+        config.vertipad_location_tolerance_m = new.vertipad_location_tolerance_m;
+        config.schedule_conflict_mode = new.schedule_conflict_mode;
+        config.conversion_mode = new.conversion_mode;
+        config.archive_after_days = new.archive_after_days;
+        config.enable_groups_v2 = new.enable_groups_v2;
+        config.enable_public_api = new.enable_public_api;
+        config.strict_validation = new.strict_validation;
+        config.admin_token = new.admin_token;
+        config.api_tokens = new.api_tokens;
+        config.response_redact_fields = new.response_redact_fields;
+        config.tenants = new.tenants;
+        log::info!(
+            "(SharedConfig::apply) configuration reloaded: {}",
+            serde_json::to_string(&*config).unwrap_or_default()
+        );
+    }
+
+    /// Reload the configuration from the config file.
+    ///
+    /// The environment of a running process does not change and the `.env`
+    /// file does not override variables which are already set, so only
+    /// changes to the file at `CONFIG_FILE` are picked up.
+    pub fn reload(&self) -> Result<(), ConfigError> {
+        let new = Config::try_from_env()?;
+        self.apply(new);
+        Ok(())
+    }
+}
+
+/// Reload the [`SharedConfig`] each time a SIGHUP signal is received.
+///
+/// Log levels are reloaded by log4rs itself using the `refresh_rate` of
+/// the log configuration file.
+#[cfg(unix)]
+#[cfg(not(tarpaulin_include))]
+// no_coverage: (Rnever) requires sending signals to the test process
+pub async fn reload_on_sighup(config: SharedConfig) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            log::error!(
+                "(reload_on_sighup) could not register SIGHUP handler: {}",
+                e
+            );
+            return;
+        }
+    };
+
+    while hangup.recv().await.is_some() {
+        log::info!("(reload_on_sighup) SIGHUP received, reloading configuration.");
+        if let Err(e) = config.reload() {
+            log::error!("(reload_on_sighup) could not reload configuration: {}", e);
+        }
     }
 }
 
@@ -100,6 +664,56 @@ mod tests {
             config.rest_cors_allowed_origin,
             String::from("http://localhost:3000")
         );
+        assert_eq!(config.max_aircraft_per_operator, 0);
+        assert_eq!(config.max_vertiports_per_operator, 0);
+        assert_eq!(config.vertiport_index_refresh_secs, 60);
+        assert_eq!(config.storage_call_timeout_ms, 5000);
+        assert_eq!(config.rest_request_timeout_ms, 10000);
+        assert_eq!(config.storage_latency_slo_p95_ms, 1000);
+        assert_eq!(config.storage_latency_slo_p99_ms, 2500);
+        assert_eq!(config.startup_timeout_secs, 300);
+        assert_eq!(config.startup_backoff_initial_ms, 500);
+        assert_eq!(config.startup_backoff_max_ms, 10000);
+        assert_eq!(config.rest_max_body_bytes, 65536);
+        assert_eq!(config.rest_max_bulk_body_bytes, 16777216);
+        assert_eq!(config.rest_list_max_results, 10000);
+        assert_eq!(config.rest_list_warn_results, 1000);
+        assert!(!config.read_only);
+        assert!(!config.audit_log);
+        assert!(config
+            .audit_log_redact_fields
+            .contains(&String::from("email")));
+        assert_eq!(
+            config.vertiport_delete_policy,
+            VertiportDeletePolicy::Reject
+        );
+        assert_eq!(config.vertipad_adjacency_threshold_m, 30.0);
+        assert_eq!(config.vertipad_location_tolerance_m, 10.0);
+        assert_eq!(config.schedule_conflict_mode, ScheduleConflictMode::Strict);
+        assert_eq!(config.conversion_mode, ConversionMode::Skip);
+        assert_eq!(config.archive_after_days, 30);
+        assert!(config.enable_groups_v2);
+        assert!(config.enable_public_api);
+        assert!(!config.strict_validation);
+        assert_eq!(config.object_store_endpoint, None);
+        assert_eq!(config.object_store_bucket, String::from("svc-assets"));
+        assert_eq!(config.object_store_region, String::from("us-east-1"));
+        assert_eq!(config.object_store_access_key, None);
+        assert_eq!(config.object_store_secret_key, None);
+        assert_eq!(config.docker_port_admin, None);
+        assert_eq!(config.admin_token, None);
+        assert!(config.api_tokens.is_empty());
+        assert!(config.response_redact_fields.is_empty());
+        assert!(config.tenants.is_empty());
+        assert!(config.grpc_register_vehicle_peers.is_empty());
+        assert!(config.grpc_update_vertipad_occupancy_peers.is_empty());
+        assert!(config.grpc_set_asset_status_peers.is_empty());
+        assert_eq!(config.tls_cert_path, None);
+        assert_eq!(config.tls_key_path, None);
+        assert_eq!(config.tls_client_ca_path, None);
+        assert!(!config.tls_client_auth_required);
+        assert_eq!(config.state_dir, None);
+        assert_eq!(config.config_file, None);
 
         ut_info!("Success.");
     }
@@ -120,6 +734,53 @@ mod tests {
             "REST_CORS_ALLOWED_ORIGIN",
             "https://allowed.origin.host:443",
         );
+        std::env::set_var("MAX_AIRCRAFT_PER_OPERATOR", "10");
+        std::env::set_var("MAX_VERTIPORTS_PER_OPERATOR", "2");
+        std::env::set_var("VERTIPORT_INDEX_REFRESH_SECS", "0");
+        std::env::set_var("STORAGE_CALL_TIMEOUT_MS", "250");
+        std::env::set_var("REST_REQUEST_TIMEOUT_MS", "2000");
+        std::env::set_var("STORAGE_LATENCY_SLO_P95_MS", "300");
+        std::env::set_var("STORAGE_LATENCY_SLO_P99_MS", "0");
+        std::env::set_var("STARTUP_TIMEOUT_SECS", "0");
+        std::env::set_var("STARTUP_BACKOFF_INITIAL_MS", "100");
+        std::env::set_var("STARTUP_BACKOFF_MAX_MS", "2000");
+        std::env::set_var("REST_MAX_BODY_BYTES", "1024");
+        std::env::set_var("REST_MAX_BULK_BODY_BYTES", "1048576");
+        std::env::set_var("REST_LIST_MAX_RESULTS", "500");
+        std::env::set_var("REST_LIST_WARN_RESULTS", "0");
+        std::env::set_var("READ_ONLY", "true");
+        std::env::set_var("AUDIT_LOG", "true");
+        std::env::set_var("AUDIT_LOG_REDACT_FIELDS", "email,iban");
+        std::env::set_var("VERTIPORT_DELETE_POLICY", "cascade");
+        std::env::set_var("VERTIPAD_ADJACENCY_THRESHOLD_M", "45.5");
+        std::env::set_var("VERTIPAD_LOCATION_TOLERANCE_M", "2.5");
+        std::env::set_var("SCHEDULE_CONFLICT_MODE", "lenient");
+        std::env::set_var("CONVERSION_MODE", "strict");
+        std::env::set_var("ARCHIVE_AFTER_DAYS", "90");
+        std::env::set_var("ENABLE_GROUPS_V2", "false");
+        std::env::set_var("STRICT_VALIDATION", "true");
+        std::env::set_var("OBJECT_STORE_ENDPOINT", "http://minio:9000");
+        std::env::set_var("OBJECT_STORE_BUCKET", "assets");
+        std::env::set_var("OBJECT_STORE_ACCESS_KEY", "access");
+        std::env::set_var("OBJECT_STORE_SECRET_KEY", "secret");
+        std::env::set_var("DOCKER_PORT_ADMIN", "8001");
+        std::env::set_var("ADMIN_TOKEN", "secret");
+        std::env::set_var(
+            "API_TOKENS",
+            "partner=assets:read,ops=assets:read assets:write",
+        );
+        std::env::set_var(
+            "RESPONSE_REDACT_FIELDS",
+            "serial_number=sensitive:read,email=operators:admin",
+        );
+        std::env::set_var("TENANTS", "staging,pilot");
+        std::env::set_var("GRPC_REGISTER_VEHICLE_PEERS", "svc-scheduler,svc-cargo");
+        std::env::set_var("GRPC_SET_ASSET_STATUS_PEERS", "svc-scheduler");
+        std::env::set_var("TLS_CERT_PATH", "/certs/tls.crt");
+        std::env::set_var("TLS_KEY_PATH", "/certs/tls.key");
+        std::env::set_var("TLS_CLIENT_CA_PATH", "/certs/ca.crt");
+        std::env::set_var("TLS_CLIENT_AUTH_REQUIRED", "true");
+        std::env::set_var("STATE_DIR", "/var/lib/svc-assets");
         let config = Config::try_from_env();
         assert!(config.is_ok());
         let config = config.unwrap();
@@ -135,6 +796,142 @@ mod tests {
             config.rest_cors_allowed_origin,
             String::from("https://allowed.origin.host:443")
         );
+        assert_eq!(config.max_aircraft_per_operator, 10);
+        assert_eq!(config.max_vertiports_per_operator, 2);
+        assert_eq!(config.vertiport_index_refresh_secs, 0);
+        assert_eq!(config.storage_call_timeout_ms, 250);
+        assert_eq!(config.rest_request_timeout_ms, 2000);
+        assert_eq!(config.storage_latency_slo_p95_ms, 300);
+        assert_eq!(config.storage_latency_slo_p99_ms, 0);
+        assert_eq!(config.startup_timeout_secs, 0);
+        assert_eq!(config.startup_backoff_initial_ms, 100);
+        assert_eq!(config.startup_backoff_max_ms, 2000);
+        assert_eq!(config.rest_max_body_bytes, 1024);
+        assert_eq!(config.rest_max_bulk_body_bytes, 1048576);
+        assert_eq!(config.rest_list_max_results, 500);
+        assert_eq!(config.rest_list_warn_results, 0);
+        assert!(config.read_only);
+        assert!(config.audit_log);
+        assert_eq!(
+            config.audit_log_redact_fields,
+            vec![String::from("email"), String::from("iban")]
+        );
+        assert_eq!(
+            config.vertiport_delete_policy,
+            VertiportDeletePolicy::Cascade
+        );
+        assert_eq!(config.vertipad_adjacency_threshold_m, 45.5);
+        assert_eq!(config.vertipad_location_tolerance_m, 2.5);
+        assert_eq!(config.schedule_conflict_mode, ScheduleConflictMode::Lenient);
+        assert_eq!(config.conversion_mode, ConversionMode::Strict);
+        assert_eq!(config.archive_after_days, 90);
+        assert!(!config.enable_groups_v2);
+        assert!(config.enable_public_api);
+        assert!(config.strict_validation);
+        assert_eq!(
+            config.object_store_endpoint,
+            Some(String::from("http://minio:9000"))
+        );
+        assert_eq!(config.object_store_bucket, String::from("assets"));
+        assert_eq!(config.object_store_region, String::from("us-east-1"));
+        assert_eq!(config.object_store_access_key, Some(String::from("access")));
+        assert_eq!(config.object_store_secret_key, Some(String::from("secret")));
+        assert_eq!(config.docker_port_admin, Some(8001));
+        assert_eq!(config.admin_token, Some(String::from("secret")));
+        assert_eq!(
+            config.api_tokens,
+            vec![
+                String::from("partner=assets:read"),
+                String::from("ops=assets:read assets:write")
+            ]
+        );
+        assert_eq!(
+            config.response_redact_fields,
+            vec![
+                String::from("serial_number=sensitive:read"),
+                String::from("email=operators:admin")
+            ]
+        );
+        assert_eq!(
+            config.tenants,
+            vec![String::from("staging"), String::from("pilot")]
+        );
+        assert_eq!(
+            config.grpc_register_vehicle_peers,
+            vec![String::from("svc-scheduler"), String::from("svc-cargo")]
+        );
+        assert!(config.grpc_update_vertipad_occupancy_peers.is_empty());
+        assert_eq!(
+            config.grpc_set_asset_status_peers,
+            vec![String::from("svc-scheduler")]
+        );
+        assert_eq!(config.tls_cert_path, Some(String::from("/certs/tls.crt")));
+        assert_eq!(config.tls_key_path, Some(String::from("/certs/tls.key")));
+        assert_eq!(
+            config.tls_client_ca_path,
+            Some(String::from("/certs/ca.crt"))
+        );
+        assert!(config.tls_client_auth_required);
+        assert_eq!(config.state_dir, Some(String::from("/var/lib/svc-assets")));
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_shared_config_apply() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let shared = SharedConfig::from(Config::default());
+
+        let mut new = Config::default();
+        new.docker_port_rest = 1234;
+        new.rest_request_limit_per_second = 10;
+        new.rest_concurrency_limit_per_service = 20;
+        new.rest_cors_allowed_origin = String::from("https://new.origin");
+        shared.apply(new);
+
+        let config = shared.get();
+        assert_eq!(config.docker_port_rest, 8000);
+        assert_eq!(config.rest_request_limit_per_second, 10);
+        assert_eq!(config.rest_concurrency_limit_per_service, 20);
+        assert_eq!(
+            shared.read(|config| config.rest_cors_allowed_origin.clone()),
+            String::from("https://new.origin")
+        );
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_config_restart_required() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let config = Config::default();
+        assert!(config.restart_required(&Config::default()).is_empty());
+
+        let mut new = Config::default();
+        new.docker_port_rest = 1234;
+        new.grpc_set_asset_status_peers = vec![String::from("svc-scheduler")];
+        new.startup_timeout_secs = 10;
+        new.read_only = true;
+        assert_eq!(
+            config.restart_required(&new),
+            vec![
+                "docker_port_rest",
+                "startup_timeout_secs",
+                "grpc_set_asset_status_peers"
+            ]
+        );
+
+        // the values requiring a restart are kept
+        let shared = SharedConfig::from(config);
+        shared.apply(new);
+        let config = shared.get();
+        assert!(config.grpc_set_asset_status_peers.is_empty());
+        assert_eq!(config.startup_timeout_secs, 300);
+        assert!(config.read_only);
 
         ut_info!("Success.");
     }
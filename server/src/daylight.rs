@@ -0,0 +1,196 @@
+//! # Daylight
+//!
+//! Sunrise and sunset times of asset locations, used to find the hours a
+//! vertipad needs working lighting.
+//!
+//! Times are computed with the
+//! [sunrise equation](https://en.wikipedia.org/wiki/Sunrise_equation),
+//! accounting for atmospheric refraction and the size of the sun. The
+//! result is accurate to a few minutes, the terrain and altitude of the
+//! location are ignored.
+
+pub use crate::rest::TimeWindow;
+
+use crate::schedule::{merge_windows, subtract_windows};
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Julian day of the J2000 epoch (2000-01-01 12:00 UTC)
+const J2000: f64 = 2_451_545.0;
+
+/// Julian day of the unix epoch
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+
+/// Number of seconds in a day
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Altitude of the center of the sun at sunrise and sunset, in degrees
+const SUNRISE_ALTITUDE_DEG: f64 = -0.833;
+
+/// Obliquity of the ecliptic, in degrees
+const OBLIQUITY_DEG: f64 = 23.4397;
+
+/// Get the julian day of a time
+fn julian_day(time: DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64 / 1000.0 / SECONDS_PER_DAY + UNIX_EPOCH_JD
+}
+
+/// Get the time of a julian day
+fn from_julian_day(julian_day: f64) -> Option<DateTime<Utc>> {
+    let millis = (julian_day - UNIX_EPOCH_JD) * SECONDS_PER_DAY * 1000.0;
+    Utc.timestamp_millis_opt(millis.round() as i64).single()
+}
+
+/// Get the window from sunrise to sunset of the solar day `day` (days since
+/// J2000) at a location.
+///
+/// Returns [`None`] during the polar night, and more than the whole day
+/// during the midnight sun.
+fn daylight_window(day: f64, longitude: f64, latitude: f64) -> Option<TimeWindow> {
+    let mean_solar_time = day - longitude / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * mean_solar_time).rem_euclid(360.0);
+    let anomaly_rad = anomaly.to_radians();
+    let center = 1.9148 * anomaly_rad.sin()
+        + 0.02 * (2.0 * anomaly_rad).sin()
+        + 0.0003 * (3.0 * anomaly_rad).sin();
+    let ecliptic_longitude = (anomaly + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit = J2000 + mean_solar_time + 0.0053 * anomaly_rad.sin()
+        - 0.0069 * (2.0 * ecliptic_longitude).sin();
+
+    let sin_declination = ecliptic_longitude.sin() * OBLIQUITY_DEG.to_radians().sin();
+    let cos_declination = (1.0 - sin_declination.powi(2)).sqrt();
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = (SUNRISE_ALTITUDE_DEG.to_radians().sin()
+        - latitude.sin() * sin_declination)
+        / (latitude.cos() * cos_declination);
+
+    // the sun does not rise
+    if cos_hour_angle.is_nan() || cos_hour_angle >= 1.0 {
+        return None;
+    }
+
+    // the sun does not set, overlap the neighbouring days so the windows
+    // are merged
+    let half_day = match cos_hour_angle <= -1.0 {
+        true => 0.75,
+        false => cos_hour_angle.acos().to_degrees() / 360.0,
+    };
+
+    Some(TimeWindow {
+        start: from_julian_day(transit - half_day)?,
+        end: from_julian_day(transit + half_day)?,
+    })
+}
+
+/// Get the windows from sunrise to sunset within the provided range at a
+/// location, in degrees
+pub fn daylight_windows(
+    longitude: f64,
+    latitude: f64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<TimeWindow> {
+    if to <= from {
+        return vec![];
+    }
+
+    // solar days are offset by the longitude, so include the days around
+    // the range and clip the windows
+    let first = (julian_day(from) - J2000).floor() as i64 - 1;
+    let last = (julian_day(to) - J2000).ceil() as i64 + 1;
+    let windows = (first..=last)
+        .filter_map(|day| daylight_window(day as f64, longitude, latitude))
+        .map(|window| TimeWindow {
+            start: window.start.max(from),
+            end: window.end.min(to),
+        })
+        .collect();
+
+    merge_windows(windows)
+}
+
+/// Get the windows from sunset to sunrise within the provided range at a
+/// location, in degrees
+pub fn night_windows(
+    longitude: f64,
+    latitude: f64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<TimeWindow> {
+    if to <= from {
+        return vec![];
+    }
+
+    subtract_windows(
+        vec![TimeWindow {
+            start: from,
+            end: to,
+        }],
+        &daylight_windows(longitude, latitude, from, to),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    /// Longitude and latitude of London
+    const LONDON: (f64, f64) = (-0.1278, 51.5074);
+
+    /// Longitude and latitude of a location north of the arctic circle
+    const ARCTIC: (f64, f64) = (18.9553, 69.6492);
+
+    fn time(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn assert_close(actual: DateTime<Utc>, expected: &str) {
+        let difference = (actual - time(expected)).num_seconds().abs();
+        assert!(difference < 300, "{actual} is not close to {expected}");
+    }
+
+    #[test]
+    fn test_daylight_windows() {
+        let (longitude, latitude) = LONDON;
+        let from = time("2024-06-21T00:00:00Z");
+        let windows = daylight_windows(longitude, latitude, from, from + Duration::days(2));
+        assert_eq!(windows.len(), 2);
+        assert_close(windows[0].start, "2024-06-21T03:43:00Z");
+        assert_close(windows[0].end, "2024-06-21T20:21:00Z");
+        assert_close(windows[1].start, "2024-06-22T03:43:00Z");
+
+        let from = time("2024-12-21T00:00:00Z");
+        let windows = daylight_windows(longitude, latitude, from, from + Duration::days(1));
+        assert_eq!(windows.len(), 1);
+        assert_close(windows[0].start, "2024-12-21T08:04:00Z");
+        assert_close(windows[0].end, "2024-12-21T15:54:00Z");
+
+        assert!(daylight_windows(longitude, latitude, from, from).is_empty());
+    }
+
+    #[test]
+    fn test_night_windows() {
+        let (longitude, latitude) = LONDON;
+        let from = time("2024-06-21T12:00:00Z");
+        let windows = night_windows(longitude, latitude, from, from + Duration::days(1));
+        assert_eq!(windows.len(), 1);
+        assert_close(windows[0].start, "2024-06-21T20:21:00Z");
+        assert_close(windows[0].end, "2024-06-22T03:43:00Z");
+
+        // midnight sun and polar night
+        let (longitude, latitude) = ARCTIC;
+        let from = time("2024-06-21T00:00:00Z");
+        assert!(night_windows(longitude, latitude, from, from + Duration::days(1)).is_empty());
+        let from = time("2024-12-21T00:00:00Z");
+        let to = from + Duration::days(1);
+        assert_eq!(
+            night_windows(longitude, latitude, from, to),
+            vec![TimeWindow {
+                start: from,
+                end: to
+            }]
+        );
+    }
+}
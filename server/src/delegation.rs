@@ -0,0 +1,505 @@
+//! # Delegation
+//!
+//! Delegations of asset groups to other operators, with an optional expiry
+//! and an audit trail of who delegated a group, when and to whom.
+//!
+//! A delegation with an `expires_at` is a lease: it is no longer reported
+//! once it expired, and the [`delegation_monitor`] reverts it and records
+//! the expiry in the audit trail.
+//!
+//! svc-storage does not store delegations yet, so [`GroupDelegations`] keeps
+//! them with their audit trail in the [`GROUP_DELEGATIONS_TABLE`] of the
+//! persisted state, see [`crate::state`]. A delegation made through one
+//! replica is not visible to the others, so the delegatee only has access
+//! through the instance which recorded it.
+
+pub use crate::rest::{Delegation, DelegationAction, DelegationAuditEntry, DelegationPayload};
+
+use crate::state::{StateError, StateTable};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
+use tokio::time::{interval, Duration, MissedTickBehavior};
+
+/// Name of the table of the group delegations in the state directory
+pub const GROUP_DELEGATIONS_TABLE: &str = "group_delegations";
+
+/// Maximum number of audit entries kept per asset group
+pub const MAX_DELEGATION_AUDIT_LEN: usize = 100;
+
+/// Interval at which expired delegations are reverted
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Errors returned when changing the delegation of an asset group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DelegationError {
+    /// The delegation expires in the past
+    Expired,
+    /// The group is delegated to the operator with the provided id
+    AlreadyDelegated(String),
+    /// The group is not delegated
+    NotFound,
+    /// The delegations could not be persisted
+    State(StateError),
+}
+
+impl Display for DelegationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DelegationError::Expired => write!(f, "delegation expires in the past"),
+            DelegationError::AlreadyDelegated(id) => {
+                write!(f, "group is already delegated to operator {}", id)
+            }
+            DelegationError::NotFound => write!(f, "group is not delegated"),
+            DelegationError::State(e) => write!(f, "could not persist delegation: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for DelegationError {}
+
+/// Check if the delegation is active at the provided time
+fn is_active(delegation: &Delegation, now: DateTime<Utc>) -> bool {
+    delegation
+        .expires_at
+        .map_or(true, |expires_at| now < expires_at)
+}
+
+/// Active delegation and audit trail of an asset group
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct GroupDelegation {
+    active: Option<Delegation>,
+    audit: VecDeque<DelegationAuditEntry>,
+}
+
+impl GroupDelegation {
+    /// Append an entry to the audit trail
+    fn record(&mut self, entry: DelegationAuditEntry) {
+        if self.audit.len() == MAX_DELEGATION_AUDIT_LEN {
+            self.audit.pop_front();
+        }
+        self.audit.push_back(entry);
+    }
+
+    /// Check if the active delegation expired at the provided time
+    fn expired(&self, now: DateTime<Utc>) -> bool {
+        self.active
+            .as_ref()
+            .map_or(false, |delegation| !is_active(delegation, now))
+    }
+
+    /// Remove the delegation if it expired, recording the expiry
+    fn expire(&mut self, now: DateTime<Utc>) -> Option<DelegationAuditEntry> {
+        if !self.expired(now) {
+            return None;
+        }
+
+        let delegation = self.active.take()?;
+        let expires_at = delegation.expires_at?;
+        let entry = DelegationAuditEntry {
+            group_id: delegation.group_id,
+            action: DelegationAction::Expired,
+            delegatee: delegation.delegatee,
+            actor: None,
+            timestamp: expires_at,
+            expires_at: Some(expires_at),
+        };
+        self.record(entry.clone());
+        Some(entry)
+    }
+}
+
+/// Store of the delegations per asset group id.
+///
+/// The default store is kept in memory, [`GroupDelegations::open`] persists
+/// the delegations and their audit trail.
+#[derive(Debug, Clone, Default)]
+pub struct GroupDelegations {
+    groups: StateTable<GroupDelegation>,
+}
+
+impl GroupDelegations {
+    /// Open the delegations persisted in the state directory, kept in memory
+    /// if no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            groups: StateTable::open(dir, GROUP_DELEGATIONS_TABLE)?,
+        })
+    }
+
+    /// Delegate a group to another operator.
+    ///
+    /// Delegating a group to its current delegatee renews the delegation
+    /// with the new expiry. Only one level of delegation is allowed, so a
+    /// group delegated to another operator has to be revoked first.
+    pub fn delegate(
+        &self,
+        group_id: &str,
+        payload: DelegationPayload,
+        actor: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Result<Delegation, DelegationError> {
+        if payload
+            .expires_at
+            .map_or(false, |expires_at| expires_at <= now)
+        {
+            return Err(DelegationError::Expired);
+        }
+
+        self.groups
+            .update(|groups| {
+                let group = groups.entry(group_id.to_string()).or_default();
+                group.expire(now);
+                if let Some(current) = &group.active {
+                    if current.delegatee != payload.delegatee {
+                        return Err(DelegationError::AlreadyDelegated(current.delegatee.clone()));
+                    }
+                }
+
+                let delegation = Delegation {
+                    group_id: group_id.to_string(),
+                    delegatee: payload.delegatee,
+                    delegated_by: actor.clone(),
+                    delegated_at: now,
+                    expires_at: payload.expires_at,
+                };
+                group.active = Some(delegation.clone());
+                group.record(DelegationAuditEntry {
+                    group_id: group_id.to_string(),
+                    action: DelegationAction::Delegated,
+                    delegatee: delegation.delegatee.clone(),
+                    actor,
+                    timestamp: now,
+                    expires_at: delegation.expires_at,
+                });
+
+                Ok(delegation)
+            })
+            .map_err(DelegationError::State)?
+    }
+
+    /// Revoke the delegation of a group, returning the revoked delegation
+    pub fn revoke(
+        &self,
+        group_id: &str,
+        actor: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Result<Delegation, DelegationError> {
+        let delegated = self
+            .groups
+            .get(group_id)
+            .map_or(false, |group| group.active.is_some());
+        if !delegated {
+            return Err(DelegationError::NotFound);
+        }
+
+        self.groups
+            .update(|groups| {
+                let group = groups.get_mut(group_id).ok_or(DelegationError::NotFound)?;
+                group.expire(now);
+                let delegation = group.active.take().ok_or(DelegationError::NotFound)?;
+
+                group.record(DelegationAuditEntry {
+                    group_id: group_id.to_string(),
+                    action: DelegationAction::Revoked,
+                    delegatee: delegation.delegatee.clone(),
+                    actor,
+                    timestamp: now,
+                    expires_at: None,
+                });
+
+                Ok(delegation)
+            })
+            .map_err(DelegationError::State)?
+    }
+
+    /// Get the delegation of a group, if it is delegated at the provided time
+    pub fn get(&self, group_id: &str, now: DateTime<Utc>) -> Option<Delegation> {
+        self.groups
+            .get(group_id)?
+            .active
+            .filter(|delegation| is_active(delegation, now))
+    }
+
+    /// Get the delegations active at the provided time matching the filter,
+    /// sorted by group id
+    fn active_matching(
+        &self,
+        now: DateTime<Utc>,
+        filter: impl Fn(&Delegation) -> bool,
+    ) -> Vec<Delegation> {
+        let mut delegations: Vec<Delegation> = self.groups.read(|groups| {
+            groups
+                .values()
+                .filter_map(|group| group.active.as_ref())
+                .filter(|delegation| is_active(delegation, now) && filter(delegation))
+                .cloned()
+                .collect()
+        });
+        delegations.sort_by(|a, b| a.group_id.cmp(&b.group_id));
+        delegations
+    }
+
+    /// Get the delegations of the groups delegated to an operator at the
+    /// provided time
+    pub fn delegated_to(&self, operator_id: &str, now: DateTime<Utc>) -> Vec<Delegation> {
+        self.active_matching(now, |delegation| delegation.delegatee == operator_id)
+    }
+
+    /// Get the delegations an operator made to other operators, active at
+    /// the provided time.
+    ///
+    /// Only delegations made with the operator as actor are known, see
+    /// [`Delegation::delegated_by`].
+    pub fn delegated_from(&self, operator_id: &str, now: DateTime<Utc>) -> Vec<Delegation> {
+        self.active_matching(now, |delegation| {
+            delegation.delegated_by.as_deref() == Some(operator_id)
+        })
+    }
+
+    /// Get the audit trail of a group, most recent first
+    pub fn audit(&self, group_id: &str) -> Vec<DelegationAuditEntry> {
+        self.groups
+            .get(group_id)
+            .map(|group| group.audit.into_iter().rev().collect())
+            .unwrap_or_default()
+    }
+
+    /// Revert all delegations expired at the provided time, returning the
+    /// recorded expiries.
+    ///
+    /// The table is only written if a delegation expired.
+    pub fn expire(&self, now: DateTime<Utc>) -> Result<Vec<DelegationAuditEntry>, StateError> {
+        let mut expired: Vec<String> = self.groups.read(|groups| {
+            groups
+                .iter()
+                .filter(|(_, group)| group.expired(now))
+                .map(|(group_id, _)| group_id.clone())
+                .collect()
+        });
+        if expired.is_empty() {
+            return Ok(vec![]);
+        }
+        expired.sort();
+
+        self.groups.update(|groups| {
+            expired
+                .iter()
+                .filter_map(|group_id| groups.get_mut(group_id)?.expire(now))
+                .collect()
+        })
+    }
+}
+
+/// Revert expired delegations at a fixed interval.
+///
+/// Every reverted delegation is recorded in the audit trail and logged as a
+/// delegation event.
+pub async fn delegation_monitor(delegations: GroupDelegations) {
+    log::info!("(delegation_monitor) start.");
+
+    let mut ticker = interval(EXPIRY_CHECK_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+
+        let expired = match delegations.expire(Utc::now()) {
+            Ok(expired) => expired,
+            Err(e) => {
+                log::warn!("(delegation_monitor) could not revert expired delegations: {e}");
+                continue;
+            }
+        };
+
+        for entry in expired {
+            log::info!(
+                "(delegation_monitor) delegation of group {} to operator {} expired at {}, reverted.",
+                entry.group_id,
+                entry.delegatee,
+                entry.timestamp
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn payload(delegatee: &str, expires_at: Option<DateTime<Utc>>) -> DelegationPayload {
+        DelegationPayload {
+            delegatee: delegatee.to_string(),
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_group_delegations() {
+        let delegations = GroupDelegations::default();
+        let id = "group";
+        let owner = Some("owner".to_string());
+        let now = Utc::now();
+        let hours = |hours: i64| now + Duration::hours(hours);
+
+        assert_eq!(
+            delegations.delegate(id, payload("a", Some(hours(-1))), owner.clone(), now),
+            Err(DelegationError::Expired)
+        );
+
+        let delegation = delegations
+            .delegate(id, payload("a", Some(hours(1))), owner.clone(), now)
+            .unwrap();
+        assert_eq!(delegation.delegated_by, owner);
+        assert_eq!(delegations.get(id, now), Some(delegation));
+        assert_eq!(
+            delegations.delegate(id, payload("b", None), owner.clone(), now),
+            Err(DelegationError::AlreadyDelegated("a".to_string()))
+        );
+
+        // renew the lease
+        delegations
+            .delegate(id, payload("a", Some(hours(2))), owner.clone(), now)
+            .unwrap();
+        assert!(delegations.get(id, hours(1)).is_some());
+        assert!(delegations.get(id, hours(2)).is_none());
+
+        // an expired lease does not block a new delegation
+        let delegation = delegations
+            .delegate(id, payload("b", None), owner.clone(), hours(3))
+            .unwrap();
+        assert_eq!(delegation.delegatee, "b");
+
+        let revoked = delegations.revoke(id, owner.clone(), hours(4)).unwrap();
+        assert_eq!(revoked.delegatee, "b");
+        assert_eq!(
+            delegations.revoke(id, owner, hours(4)),
+            Err(DelegationError::NotFound)
+        );
+
+        let actions: Vec<DelegationAction> = delegations
+            .audit(id)
+            .into_iter()
+            .map(|entry| entry.action)
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                DelegationAction::Revoked,
+                DelegationAction::Delegated,
+                DelegationAction::Expired,
+                DelegationAction::Delegated,
+                DelegationAction::Delegated,
+            ]
+        );
+        let expired = &delegations.audit(id)[2];
+        assert_eq!(expired.delegatee, "a");
+        assert_eq!(expired.actor, None);
+        assert_eq!(expired.timestamp, hours(2));
+    }
+
+    #[test]
+    fn test_group_delegations_persisted() {
+        let dir = crate::state::test_state_dir();
+        let now = Utc::now();
+        let owner = Some("owner".to_string());
+
+        let delegations = GroupDelegations::open(Some(&dir)).unwrap();
+        let delegation = delegations
+            .delegate("group", payload("a", None), owner.clone(), now)
+            .unwrap();
+        delegations
+            .delegate("revoked", payload("b", None), owner.clone(), now)
+            .unwrap();
+        delegations.revoke("revoked", owner, now).unwrap();
+
+        // the delegations and their audit trail survive a restart
+        let delegations = GroupDelegations::open(Some(&dir)).unwrap();
+        assert_eq!(delegations.get("group", now), Some(delegation));
+        assert_eq!(delegations.get("revoked", now), None);
+        assert_eq!(delegations.audit("revoked").len(), 2);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_delegations_by_operator() {
+        let delegations = GroupDelegations::default();
+        let owner = Some("owner".to_string());
+        let now = Utc::now();
+        let hours = |hours: i64| now + Duration::hours(hours);
+
+        delegations
+            .delegate("b", payload("partner", None), owner.clone(), now)
+            .unwrap();
+        delegations
+            .delegate("a", payload("partner", Some(hours(1))), owner.clone(), now)
+            .unwrap();
+        delegations
+            .delegate("c", payload("other", None), None, now)
+            .unwrap();
+
+        let groups = |delegations: Vec<Delegation>| -> Vec<String> {
+            delegations
+                .into_iter()
+                .map(|delegation| delegation.group_id)
+                .collect()
+        };
+        assert_eq!(groups(delegations.delegated_to("partner", now)), ["a", "b"]);
+        assert_eq!(groups(delegations.delegated_to("partner", hours(1))), ["b"]);
+        assert_eq!(
+            groups(delegations.delegated_to("owner", now)),
+            Vec::<String>::new()
+        );
+        assert_eq!(groups(delegations.delegated_from("owner", now)), ["a", "b"]);
+        assert_eq!(
+            groups(delegations.delegated_from("other", now)),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_expire_delegations() {
+        let delegations = GroupDelegations::default();
+        let now = Utc::now();
+
+        delegations
+            .delegate(
+                "lease",
+                payload("a", Some(now + Duration::minutes(5))),
+                None,
+                now,
+            )
+            .unwrap();
+        delegations
+            .delegate("permanent", payload("a", None), None, now)
+            .unwrap();
+
+        assert!(delegations.expire(now).unwrap().is_empty());
+
+        let expired = delegations.expire(now + Duration::minutes(5)).unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].group_id, "lease");
+        assert_eq!(expired[0].action, DelegationAction::Expired);
+        assert!(delegations.get("lease", now).is_none());
+        assert!(delegations.get("permanent", now).is_some());
+
+        assert!(delegations
+            .expire(now + Duration::minutes(10))
+            .unwrap()
+            .is_empty());
+        assert_eq!(delegations.audit("lease").len(), 2);
+
+        for _ in 0..MAX_DELEGATION_AUDIT_LEN {
+            delegations
+                .delegate("permanent", payload("a", None), None, now)
+                .unwrap();
+        }
+        assert_eq!(
+            delegations.audit("permanent").len(),
+            MAX_DELEGATION_AUDIT_LEN
+        );
+    }
+}
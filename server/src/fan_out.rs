@@ -0,0 +1,124 @@
+//! # Fan Out
+//!
+//! Helpers to issue independent svc-storage calls concurrently.
+//!
+//! Every call is bounded by the `storage_call_timeout_ms` configuration, so a
+//! single slow dependency fails its own call with
+//! [`tonic::Code::DeadlineExceeded`] instead of holding up the whole request.
+//! Combine the calls with [`tokio::join!`] or [`futures::future::join_all`].
+
+use crate::config::SharedConfig;
+use std::future::Future;
+use std::time::Duration;
+use tonic::Status;
+
+/// Get the call timeout from the configured milliseconds, `None` if disabled
+pub fn call_timeout(timeout_ms: u64) -> Option<Duration> {
+    match timeout_ms {
+        0 => None,
+        ms => Some(Duration::from_millis(ms)),
+    }
+}
+
+/// Run a call, failing with [`Status::deadline_exceeded`] if it does not
+/// complete within the timeout.
+pub async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    call: impl Future<Output = Result<T, Status>>,
+) -> Result<T, Status> {
+    let Some(timeout) = timeout else {
+        return call.await;
+    };
+
+    tokio::time::timeout(timeout, call)
+        .await
+        .unwrap_or_else(|_| {
+            Err(Status::deadline_exceeded(format!(
+                "call did not complete within {:?}",
+                timeout
+            )))
+        })
+}
+
+/// Applies the configured timeout to the svc-storage calls of a request.
+///
+/// The timeout is read from the [`SharedConfig`] for each call, so it can be
+/// changed at runtime.
+#[derive(Debug, Clone)]
+pub struct FanOut {
+    config: SharedConfig,
+}
+
+impl FanOut {
+    /// Create a new [`FanOut`] using the provided configuration
+    pub fn new(config: SharedConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get the current call timeout, `None` if disabled
+    pub fn timeout(&self) -> Option<Duration> {
+        call_timeout(self.config.read(|config| config.storage_call_timeout_ms))
+    }
+
+    /// Run a call with the current call timeout
+    pub async fn call<T>(
+        &self,
+        call: impl Future<Output = Result<T, Status>>,
+    ) -> Result<T, Status> {
+        with_timeout(self.timeout(), call).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use std::time::Instant;
+
+    async fn slow(delay: Duration) -> Result<Duration, Status> {
+        tokio::time::sleep(delay).await;
+        Ok(delay)
+    }
+
+    #[test]
+    fn test_call_timeout() {
+        assert_eq!(call_timeout(0), None);
+        assert_eq!(call_timeout(250), Some(Duration::from_millis(250)));
+    }
+
+    #[tokio::test]
+    async fn test_with_timeout() {
+        let timeout = Some(Duration::from_millis(50));
+        let fast = Duration::from_millis(1);
+        assert_eq!(with_timeout(timeout, slow(fast)).await.unwrap(), fast);
+
+        let error = with_timeout(timeout, slow(Duration::from_secs(5)))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::DeadlineExceeded);
+
+        // disabled
+        let delay = Duration::from_millis(60);
+        assert_eq!(with_timeout(None, slow(delay)).await.unwrap(), delay);
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_concurrent() {
+        let mut config = Config::default();
+        config.storage_call_timeout_ms = 200;
+        let fan_out = FanOut::new(config.into());
+
+        let start = Instant::now();
+        let (a, b, c) = tokio::join!(
+            fan_out.call(slow(Duration::from_millis(100))),
+            fan_out.call(slow(Duration::from_millis(100))),
+            fan_out.call(slow(Duration::from_secs(5))),
+        );
+
+        // calls run concurrently and the slow call is cut off at the timeout
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(a.is_ok());
+        assert!(b.is_ok());
+        assert_eq!(c.unwrap_err().code(), tonic::Code::DeadlineExceeded);
+    }
+}
@@ -0,0 +1,188 @@
+//! # Features
+//!
+//! Feature flags, so the bigger subsystems of the service can be rolled out
+//! gradually. The flags are read from the [`Config`](crate::Config)
+//! (`ENABLE_GROUPS_V2`, `ENABLE_PUBLIC_API` and `STRICT_VALIDATION`) for each
+//! request, so they can be changed with a configuration reload.
+//!
+//! Routes of a disabled feature answer `404 NOT_FOUND`, see
+//! [`ApiRoute::feature`](crate::rest::routes::ApiRoute::feature).
+
+pub use crate::rest::FeatureFlag;
+
+use crate::config::{Config, SharedConfig};
+use axum::{
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Feature which can be enabled or disabled with a flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// Schedules and delegations of asset groups
+    GroupsV2,
+    /// Public asset listings under `/assets/demo`
+    PublicApi,
+    /// Rejection of request bodies with unknown fields
+    StrictValidation,
+}
+
+impl Feature {
+    /// All features, in the order they are listed
+    pub const ALL: [Feature; 3] = [
+        Feature::GroupsV2,
+        Feature::PublicApi,
+        Feature::StrictValidation,
+    ];
+
+    /// Name of the flag of the feature
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feature::GroupsV2 => "groups_v2",
+            Feature::PublicApi => "public_api",
+            Feature::StrictValidation => "strict_validation",
+        }
+    }
+
+    /// Description of what the flag enables
+    pub fn description(&self) -> &'static str {
+        match self {
+            Feature::GroupsV2 => "asset group schedules, availability and delegations",
+            Feature::PublicApi => "public asset listings under /assets/demo",
+            Feature::StrictValidation => "rejection of request bodies with unknown fields",
+        }
+    }
+
+    /// Check if the feature is enabled in a configuration
+    pub fn is_enabled_in(&self, config: &Config) -> bool {
+        match self {
+            Feature::GroupsV2 => config.enable_groups_v2,
+            Feature::PublicApi => config.enable_public_api,
+            Feature::StrictValidation => config.strict_validation,
+        }
+    }
+}
+
+/// Feature flags of the current configuration
+#[derive(Debug, Clone)]
+pub struct Features {
+    config: SharedConfig,
+}
+
+impl Features {
+    /// Create new [`Features`] using the provided configuration
+    pub fn new(config: SharedConfig) -> Self {
+        Self { config }
+    }
+
+    /// Check if a feature is enabled
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.config.read(|config| feature.is_enabled_in(config))
+    }
+
+    /// Get the flags of all features
+    pub fn flags(&self) -> Vec<FeatureFlag> {
+        self.config.read(|config| {
+            Feature::ALL
+                .iter()
+                .map(|feature| FeatureFlag {
+                    name: feature.name().to_string(),
+                    enabled: feature.is_enabled_in(config),
+                    description: feature.description().to_string(),
+                })
+                .collect()
+        })
+    }
+}
+
+/// Middleware function answering `404 NOT_FOUND` for the routes of a
+/// disabled feature.
+///
+/// The [`Features`] are taken from the request extensions.
+pub async fn require<B>(feature: Feature, req: Request<B>, next: Next<B>) -> Response {
+    let Some(features) = req.extensions().get::<Features>() else {
+        log::error!("(require) Features extension missing.");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    if !features.is_enabled(feature) {
+        log::info!(
+            "(require) rejected {} {}: feature {} is disabled.",
+            req.method(),
+            req.uri().path(),
+            feature.name()
+        );
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing, Extension, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_features() {
+        let mut config = Config::default();
+        config.enable_groups_v2 = false;
+        let features = Features::new(SharedConfig::from(config));
+
+        assert!(!features.is_enabled(Feature::GroupsV2));
+        assert!(features.is_enabled(Feature::PublicApi));
+        assert!(!features.is_enabled(Feature::StrictValidation));
+
+        let flags = features.flags();
+        let names: Vec<&str> = flags.iter().map(|flag| flag.name.as_str()).collect();
+        assert_eq!(names, vec!["groups_v2", "public_api", "strict_validation"]);
+        assert!(!flags[0].enabled);
+        assert!(flags[1].enabled);
+    }
+
+    #[tokio::test]
+    async fn test_require() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let mut config = Config::default();
+        config.enable_public_api = false;
+        let shared = SharedConfig::from(config);
+        let app = Router::new()
+            .route(
+                "/groups",
+                routing::get(|| async { "ok" }).layer(middleware::from_fn(
+                    |req: Request<Body>, next: Next<Body>| require(Feature::GroupsV2, req, next),
+                )),
+            )
+            .route(
+                "/demo",
+                routing::get(|| async { "ok" }).layer(middleware::from_fn(
+                    |req: Request<Body>, next: Next<Body>| require(Feature::PublicApi, req, next),
+                )),
+            )
+            .layer(Extension(Features::new(shared.clone())));
+        let status = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                app.oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap()
+                    .status()
+            }
+        };
+
+        assert_eq!(status("/groups").await, StatusCode::OK);
+        assert_eq!(status("/demo").await, StatusCode::NOT_FOUND);
+
+        // flags are read for each request
+        let mut config = shared.get();
+        config.enable_public_api = true;
+        shared.apply(config);
+        assert_eq!(status("/demo").await, StatusCode::OK);
+
+        ut_info!("success");
+    }
+}
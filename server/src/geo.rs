@@ -0,0 +1,538 @@
+//! # Geo
+//!
+//! Geometry helpers for asset locations.
+//!
+//! Coordinates are handled as planar `x` (longitude) and `y` (latitude)
+//! values, which is accurate enough for areas the size of a vertiport.
+//! Distances in meters are computed on a sphere, see [`distance_m`].
+
+use svc_storage_client_grpc::prelude::{GeoLineStringZ, GeoPointZ, GeoPolygonZ};
+
+/// Number of times a layout is refined before giving up
+const MAX_GRID_REFINEMENT: usize = 4;
+
+/// Mean radius of the earth in meters
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// Get the ground distance in meters between two points, using the
+/// haversine formula. The altitude (`z`) of the points is ignored.
+pub fn distance_m(a: &GeoPointZ, b: &GeoPointZ) -> f64 {
+    let (lat_a, lat_b) = (a.y.to_radians(), b.y.to_radians());
+    let d_lat = lat_b - lat_a;
+    let d_lon = (b.x - a.x).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * h.sqrt().min(1.0).asin()
+}
+
+/// Get the centroid of a polygon, using its exterior (first) ring.
+///
+/// The altitude (`z`) of the centroid is the average altitude of the ring.
+/// Returns [`None`] if the polygon has no rings or an exterior ring without area.
+pub fn polygon_centroid(polygon: &GeoPolygonZ) -> Option<GeoPointZ> {
+    let ring = polygon.rings.first()?;
+    let points = &ring.points;
+    if points.len() < 3 {
+        return None;
+    }
+
+    let mut area = 0.0;
+    let mut x = 0.0;
+    let mut y = 0.0;
+    for (current, next) in points.iter().zip(points.iter().cycle().skip(1)) {
+        let cross = current.x * next.y - next.x * current.y;
+        area += cross;
+        x += (current.x + next.x) * cross;
+        y += (current.y + next.y) * cross;
+    }
+
+    if area.abs() < f64::EPSILON {
+        return None;
+    }
+
+    area /= 2.0;
+    Some(GeoPointZ {
+        x: x / (6.0 * area),
+        y: y / (6.0 * area),
+        z: points.iter().map(|point| point.z).sum::<f64>() / points.len() as f64,
+    })
+}
+
+/// Get the bounding box (`min_x`, `min_y`, `max_x`, `max_y`) of the
+/// exterior (first) ring of a polygon.
+///
+/// Returns [`None`] if the polygon has no rings or an empty exterior ring.
+pub fn polygon_bbox(polygon: &GeoPolygonZ) -> Option<(f64, f64, f64, f64)> {
+    bounds(polygon).map(|(bbox, _)| bbox)
+}
+
+/// Check if a point lies within a polygon.
+///
+/// The point must be inside the exterior (first) ring and outside all other
+/// rings (holes). Points on an edge may be reported either way.
+pub fn point_in_polygon(point: &GeoPointZ, polygon: &GeoPolygonZ) -> bool {
+    let mut rings = polygon.rings.iter().map(|ring| point_in_ring(point, ring));
+    rings.next().unwrap_or(false) && !rings.any(|inside| inside)
+}
+
+/// Check if a point lies within a ring, using ray casting.
+fn point_in_ring(point: &GeoPointZ, ring: &GeoLineStringZ) -> bool {
+    let points = &ring.points;
+    let mut inside = false;
+    for (current, next) in points.iter().zip(points.iter().cycle().skip(1)) {
+        if (current.y > point.y) != (next.y > point.y)
+            && point.x
+                < (next.x - current.x) * (point.y - current.y) / (next.y - current.y) + current.x
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Check if two polygons intersect, using their exterior (first) rings.
+///
+/// Polygons intersect if their edges cross or one lies within the other.
+/// Holes are ignored, so a polygon within a hole of the other is reported
+/// as intersecting. Polygons without rings never intersect.
+pub fn polygons_intersect(a: &GeoPolygonZ, b: &GeoPolygonZ) -> bool {
+    let (Some(ring_a), Some(ring_b)) = (a.rings.first(), b.rings.first()) else {
+        return false;
+    };
+
+    let edges = |ring: &GeoLineStringZ| {
+        ring.points
+            .windows(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect::<Vec<_>>()
+    };
+    let edges_b = edges(ring_b);
+    let crossing = edges(ring_a).iter().any(|(p1, p2)| {
+        edges_b
+            .iter()
+            .any(|(q1, q2)| segments_intersect(p1, p2, q1, q2))
+    });
+
+    crossing
+        || ring_a
+            .points
+            .first()
+            .map_or(false, |point| point_in_ring(point, ring_b))
+        || ring_b
+            .points
+            .first()
+            .map_or(false, |point| point_in_ring(point, ring_a))
+}
+
+/// Check if the segments `p1`-`p2` and `q1`-`q2` intersect, including
+/// touching segments.
+fn segments_intersect(p1: &GeoPointZ, p2: &GeoPointZ, q1: &GeoPointZ, q2: &GeoPointZ) -> bool {
+    let cross = |a: &GeoPointZ, b: &GeoPointZ, c: &GeoPointZ| {
+        (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+    };
+    let on_segment = |a: &GeoPointZ, b: &GeoPointZ, c: &GeoPointZ| {
+        c.x >= a.x.min(b.x) && c.x <= a.x.max(b.x) && c.y >= a.y.min(b.y) && c.y <= a.y.max(b.y)
+    };
+
+    let d1 = cross(q1, q2, p1);
+    let d2 = cross(q1, q2, p2);
+    let d3 = cross(p1, p2, q1);
+    let d4 = cross(p1, p2, q2);
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+
+    (d1 == 0.0 && on_segment(q1, q2, p1))
+        || (d2 == 0.0 && on_segment(q1, q2, p2))
+        || (d3 == 0.0 && on_segment(p1, p2, q1))
+        || (d4 == 0.0 && on_segment(p1, p2, q2))
+}
+
+/// Get the ground distance in meters between a point and a polygon, `0` if
+/// the point lies within the polygon.
+///
+/// Outside the polygon the distance is the distance to the nearest edge of
+/// any ring, measured on a plane around the point; the altitude (`z`) is
+/// ignored. Returns [`None`] if the polygon has no rings.
+pub fn distance_to_polygon_m(point: &GeoPointZ, polygon: &GeoPolygonZ) -> Option<f64> {
+    polygon.rings.first()?;
+    if point_in_polygon(point, polygon) {
+        return Some(0.0);
+    }
+
+    let m_per_degree = EARTH_RADIUS_M.to_radians();
+    let scale_x = m_per_degree * point.y.to_radians().cos();
+    let project = |other: &GeoPointZ| {
+        (
+            (other.x - point.x) * scale_x,
+            (other.y - point.y) * m_per_degree,
+        )
+    };
+
+    polygon
+        .rings
+        .iter()
+        .flat_map(|ring| ring.points.windows(2))
+        .map(|pair| {
+            let ((ax, ay), (bx, by)) = (project(&pair[0]), project(&pair[1]));
+            let (dx, dy) = (bx - ax, by - ay);
+            let length = dx * dx + dy * dy;
+            let t = match length > 0.0 {
+                true => (-(ax * dx + ay * dy) / length).clamp(0.0, 1.0),
+                false => 0.0,
+            };
+            (ax + t * dx).hypot(ay + t * dy)
+        })
+        .reduce(f64::min)
+}
+
+/// Get the bounding box (`min_x`, `min_y`, `max_x`, `max_y`) and the average
+/// altitude of the exterior ring of a polygon.
+fn bounds(polygon: &GeoPolygonZ) -> Option<((f64, f64, f64, f64), f64)> {
+    let points = &polygon.rings.first()?.points;
+    let first = points.first()?;
+    let bounds = points.iter().fold(
+        (first.x, first.y, first.x, first.y),
+        |(min_x, min_y, max_x, max_y), point| {
+            (
+                min_x.min(point.x),
+                min_y.min(point.y),
+                max_x.max(point.x),
+                max_y.max(point.y),
+            )
+        },
+    );
+    let z = points.iter().map(|point| point.z).sum::<f64>() / points.len() as f64;
+    Some((bounds, z))
+}
+
+/// Lay out `count` points on a grid within a polygon.
+///
+/// The bounding box of the exterior ring is divided into square grids of
+/// increasing size until enough cell centers lie within the polygon; the
+/// points are spread evenly over these cell centers. Returns [`None`] if
+/// the polygon can not fit the points.
+pub fn grid_points(polygon: &GeoPolygonZ, count: usize) -> Option<Vec<GeoPointZ>> {
+    if count == 0 {
+        return Some(vec![]);
+    }
+
+    let ((min_x, min_y, max_x, max_y), z) = bounds(polygon)?;
+    let min_size = (count as f64).sqrt().ceil() as usize;
+    for size in min_size..=min_size * MAX_GRID_REFINEMENT {
+        let cells: Vec<GeoPointZ> = (0..size * size)
+            .map(|index| GeoPointZ {
+                x: min_x + (max_x - min_x) * ((index % size) as f64 + 0.5) / size as f64,
+                y: min_y + (max_y - min_y) * ((index / size) as f64 + 0.5) / size as f64,
+                z,
+            })
+            .filter(|point| point_in_polygon(point, polygon))
+            .collect();
+
+        if cells.len() >= count {
+            return Some(
+                (0..count)
+                    .map(|index| &cells[index * cells.len() / count])
+                    .map(|cell| GeoPointZ {
+                        x: cell.x,
+                        y: cell.y,
+                        z,
+                    })
+                    .collect(),
+            );
+        }
+    }
+
+    None
+}
+
+/// Lay out `count` points around the centroid of a polygon.
+///
+/// The first point is the centroid, the others are spread evenly over a
+/// circle around it. The radius of the circle is reduced until all points
+/// lie within the polygon. Returns [`None`] if the polygon can not fit the
+/// points.
+pub fn centroid_offset_points(polygon: &GeoPolygonZ, count: usize) -> Option<Vec<GeoPointZ>> {
+    if count == 0 {
+        return Some(vec![]);
+    }
+
+    let centroid = polygon_centroid(polygon)?;
+    if !point_in_polygon(&centroid, polygon) {
+        return None;
+    }
+
+    let ((min_x, min_y, max_x, max_y), _) = bounds(polygon)?;
+    let mut radius = (max_x - min_x).min(max_y - min_y) / 4.0;
+    for _ in 0..MAX_GRID_REFINEMENT {
+        let offsets = count - 1;
+        let points: Vec<GeoPointZ> = (0..count)
+            .map(|index| {
+                let (dx, dy) = match index {
+                    0 => (0.0, 0.0),
+                    _ => {
+                        let angle =
+                            2.0 * std::f64::consts::PI * (index - 1) as f64 / offsets as f64;
+                        (radius * angle.cos(), radius * angle.sin())
+                    }
+                };
+                GeoPointZ {
+                    x: centroid.x + dx,
+                    y: centroid.y + dy,
+                    z: centroid.z,
+                }
+            })
+            .collect();
+
+        if points.iter().all(|point| point_in_polygon(point, polygon)) {
+            return Some(points);
+        }
+
+        radius /= 2.0;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::{GeoPoint, GeoPolygon};
+
+    fn point(x: f64, y: f64) -> GeoPointZ {
+        GeoPointZ { x, y, z: 10.0 }
+    }
+
+    #[test]
+    fn test_polygon_centroid() {
+        let polygon = GeoPolygonZ {
+            rings: vec![GeoLineStringZ {
+                points: vec![
+                    point(4.0, 52.0),
+                    point(4.2, 52.0),
+                    point(4.2, 52.2),
+                    point(4.0, 52.2),
+                    point(4.0, 52.0),
+                ],
+            }],
+        };
+
+        let centroid = polygon_centroid(&polygon).unwrap();
+        assert!((centroid.x - 4.1).abs() < 1e-9);
+        assert!((centroid.y - 52.1).abs() < 1e-9);
+        assert!((centroid.z - 10.0).abs() < 1e-9);
+
+        assert!(polygon_centroid(&GeoPolygonZ { rings: vec![] }).is_none());
+        let line = GeoPolygonZ {
+            rings: vec![GeoLineStringZ {
+                points: vec![point(4.0, 52.0), point(4.1, 52.1), point(4.0, 52.0)],
+            }],
+        };
+        assert!(polygon_centroid(&line).is_none());
+    }
+
+    #[test]
+    fn test_distance_m() {
+        assert_eq!(distance_m(&point(4.1, 52.1), &point(4.1, 52.1)), 0.0);
+
+        // a degree of latitude is about 111.2 km
+        let distance = distance_m(&point(4.1, 52.0), &point(4.1, 53.0));
+        assert!((distance - 111_195.0).abs() < 1.0);
+
+        // a degree of longitude shrinks with the latitude
+        let distance = distance_m(&point(4.0, 52.0), &point(5.0, 52.0));
+        assert!((distance - 68_458.0).abs() < 10.0);
+        assert_eq!(
+            distance,
+            distance_m(
+                &point(5.0, 52.0),
+                &GeoPointZ {
+                    x: 4.0,
+                    y: 52.0,
+                    z: 0.0
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_geo_dto_conversion() {
+        let polygon = GeoPolygonZ {
+            rings: vec![GeoLineStringZ {
+                points: vec![point(4.0, 52.0), point(4.2, 52.0), point(4.0, 52.0)],
+            }],
+        };
+
+        let dto = GeoPolygon::from(polygon.clone());
+        assert_eq!(dto.rings[0].points[1].longitude, 4.2);
+        assert_eq!(dto.rings[0].points[1].latitude, 52.0);
+        assert_eq!(dto.rings[0].points[1].altitude, 10.0);
+        assert_eq!(GeoPolygonZ::from(dto), polygon);
+
+        // storage field names are accepted, the altitude is optional
+        let dto: GeoPoint = serde_json::from_str(r#"{"x": 4.9, "y": 52.3}"#).unwrap();
+        assert_eq!(
+            GeoPointZ::from(dto),
+            GeoPointZ {
+                x: 4.9,
+                y: 52.3,
+                z: 0.0
+            }
+        );
+        let json = serde_json::to_value(dto).unwrap();
+        assert_eq!(json["longitude"], 4.9);
+        assert_eq!(json["latitude"], 52.3);
+    }
+
+    fn square() -> GeoPolygonZ {
+        GeoPolygonZ {
+            rings: vec![GeoLineStringZ {
+                points: vec![
+                    point(4.0, 52.0),
+                    point(4.2, 52.0),
+                    point(4.2, 52.2),
+                    point(4.0, 52.2),
+                    point(4.0, 52.0),
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_polygon_bbox() {
+        assert_eq!(polygon_bbox(&square()), Some((4.0, 52.0, 4.2, 52.2)));
+        assert_eq!(
+            polygon_bbox(&GeoPolygonZ {
+                rings: vec![GeoLineStringZ {
+                    points: vec![point(4.1, 52.3), point(3.9, 52.4), point(4.0, 52.1)],
+                }],
+            }),
+            Some((3.9, 52.1, 4.1, 52.4))
+        );
+        assert_eq!(polygon_bbox(&GeoPolygonZ { rings: vec![] }), None);
+        assert_eq!(
+            polygon_bbox(&GeoPolygonZ {
+                rings: vec![GeoLineStringZ { points: vec![] }],
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        let mut polygon = square();
+        assert!(point_in_polygon(&point(4.1, 52.1), &polygon));
+        assert!(!point_in_polygon(&point(4.3, 52.1), &polygon));
+
+        polygon.rings.push(GeoLineStringZ {
+            points: vec![
+                point(4.05, 52.05),
+                point(4.15, 52.05),
+                point(4.15, 52.15),
+                point(4.05, 52.15),
+                point(4.05, 52.05),
+            ],
+        });
+        assert!(!point_in_polygon(&point(4.1, 52.1), &polygon));
+        assert!(point_in_polygon(&point(4.01, 52.01), &polygon));
+        assert!(!point_in_polygon(
+            &point(4.1, 52.1),
+            &GeoPolygonZ { rings: vec![] }
+        ));
+    }
+
+    #[test]
+    fn test_distance_to_polygon_m() {
+        let mut polygon = square();
+        assert_eq!(
+            distance_to_polygon_m(&point(4.1, 52.1), &polygon),
+            Some(0.0)
+        );
+
+        // a thousandth degree of longitude at 52.1 degrees latitude
+        let distance = distance_to_polygon_m(&point(4.201, 52.1), &polygon).unwrap();
+        assert!((distance - 68.3).abs() < 0.5);
+
+        // nearest to a corner
+        let distance = distance_to_polygon_m(&point(4.201, 52.201), &polygon).unwrap();
+        assert!((distance - 68.3_f64.hypot(111.2)).abs() < 0.5);
+
+        // within a hole
+        polygon.rings.push(GeoLineStringZ {
+            points: vec![
+                point(4.05, 52.05),
+                point(4.15, 52.05),
+                point(4.15, 52.15),
+                point(4.05, 52.15),
+                point(4.05, 52.05),
+            ],
+        });
+        let distance = distance_to_polygon_m(&point(4.1, 52.149), &polygon).unwrap();
+        assert!((distance - 111.2).abs() < 0.5);
+
+        assert_eq!(
+            distance_to_polygon_m(&point(4.1, 52.1), &GeoPolygonZ { rings: vec![] }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_polygons_intersect() {
+        let polygon = square();
+        let offset = |dx: f64, dy: f64, size: f64| GeoPolygonZ {
+            rings: vec![GeoLineStringZ {
+                points: vec![
+                    point(4.0 + dx, 52.0 + dy),
+                    point(4.0 + dx + size, 52.0 + dy),
+                    point(4.0 + dx + size, 52.0 + dy + size),
+                    point(4.0 + dx, 52.0 + dy + size),
+                    point(4.0 + dx, 52.0 + dy),
+                ],
+            }],
+        };
+
+        // overlapping edges
+        assert!(polygons_intersect(&polygon, &offset(0.1, 0.1, 0.2)));
+        // contained, both ways
+        assert!(polygons_intersect(&polygon, &offset(0.05, 0.05, 0.1)));
+        assert!(polygons_intersect(&offset(0.05, 0.05, 0.1), &polygon));
+        // touching corners
+        assert!(polygons_intersect(&polygon, &offset(0.2, 0.2, 0.1)));
+        // disjoint
+        assert!(!polygons_intersect(&polygon, &offset(0.3, 0.0, 0.1)));
+        assert!(!polygons_intersect(
+            &polygon,
+            &GeoPolygonZ { rings: vec![] }
+        ));
+    }
+
+    #[test]
+    fn test_grid_points() {
+        let polygon = square();
+        let points = grid_points(&polygon, 4).unwrap();
+        assert_eq!(points.len(), 4);
+        assert!(points
+            .iter()
+            .all(|point| point_in_polygon(point, &polygon) && point.z == 10.0));
+        assert!((points[0].x - 4.05).abs() < 1e-9);
+        assert!((points[0].y - 52.05).abs() < 1e-9);
+
+        assert_eq!(grid_points(&polygon, 0), Some(vec![]));
+        assert!(grid_points(&GeoPolygonZ { rings: vec![] }, 1).is_none());
+    }
+
+    #[test]
+    fn test_centroid_offset_points() {
+        let polygon = square();
+        let points = centroid_offset_points(&polygon, 5).unwrap();
+        assert_eq!(points.len(), 5);
+        assert!((points[0].x - 4.1).abs() < 1e-9);
+        assert!((points[0].y - 52.1).abs() < 1e-9);
+        assert!(points.iter().all(|point| point_in_polygon(point, &polygon)));
+
+        assert_eq!(centroid_offset_points(&polygon, 1).unwrap().len(), 1);
+        assert!(centroid_offset_points(&GeoPolygonZ { rings: vec![] }, 2).is_none());
+    }
+}
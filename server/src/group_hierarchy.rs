@@ -0,0 +1,281 @@
+//! # Group Hierarchy
+//!
+//! Asset groups can contain child groups, so large operators can organize
+//! their fleets by region, base and squad. The parent of a group is stored
+//! in svc-storage as the `parent_group_id` of the group. Each group has at
+//! most one parent group; the hierarchy can not contain cycles and is
+//! limited to [`MAX_GROUP_DEPTH`] levels.
+//!
+//! svc-storage can not check a move and write it atomically, so concurrent
+//! moves of related groups can still create a cycle. Walks through the
+//! hierarchy stop after [`MAX_GROUP_DEPTH`] levels, so such a cycle can not
+//! make a request loop.
+
+use crate::repo::GroupRepository;
+use futures::future::join_all;
+use std::fmt::{self, Display, Formatter};
+use svc_storage_client_grpc::prelude::{group, AdvancedSearchFilter, FieldMask};
+use tonic::{Code, Status};
+
+/// Maximum number of levels of the group hierarchy, including the top level
+pub const MAX_GROUP_DEPTH: usize = 8;
+
+/// Errors returned when changing the parent of an asset group
+#[derive(Debug, Clone)]
+pub enum HierarchyError {
+    /// The parent group does not exist
+    ParentNotFound,
+    /// The group would be its own ancestor
+    Cycle,
+    /// The hierarchy would have more than [`MAX_GROUP_DEPTH`] levels
+    TooDeep,
+    /// A svc-storage call failed
+    Storage(Status),
+}
+
+impl Display for HierarchyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HierarchyError::ParentNotFound => write!(f, "parent group not found"),
+            HierarchyError::Cycle => write!(f, "group can not be a child of itself"),
+            HierarchyError::TooDeep => write!(
+                f,
+                "group hierarchy can not have more than {} levels",
+                MAX_GROUP_DEPTH
+            ),
+            HierarchyError::Storage(e) => write!(f, "storage error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for HierarchyError {}
+
+impl From<Status> for HierarchyError {
+    fn from(status: Status) -> Self {
+        HierarchyError::Storage(status)
+    }
+}
+
+/// Get the id of the parent of a stored group, if it has one
+pub fn parent_of(object: &group::Object) -> Option<String> {
+    object
+        .data
+        .as_ref()
+        .and_then(|data| data.parent_group_id.clone())
+        .filter(|id| !id.is_empty())
+}
+
+/// Get the direct children of a group, sorted by id
+pub async fn children(groups: &dyn GroupRepository, group_id: &str) -> Result<Vec<String>, Status> {
+    let filter =
+        AdvancedSearchFilter::search_equals("parent_group_id".to_string(), group_id.to_string())
+            .and_is_null("deleted_at".to_string());
+
+    let mut children: Vec<String> = groups
+        .search(filter)
+        .await?
+        .into_iter()
+        .filter(|object| parent_of(object).as_deref() == Some(group_id))
+        .map(|object| object.id)
+        .collect();
+    children.sort();
+
+    Ok(children)
+}
+
+/// Get the groups below a group level by level, at most
+/// [`MAX_GROUP_DEPTH`] levels.
+async fn levels(groups: &dyn GroupRepository, group_id: &str) -> Result<Vec<Vec<String>>, Status> {
+    let mut seen = vec![group_id.to_string()];
+    let mut levels = vec![];
+    let mut level = children(groups, group_id).await?;
+    while !level.is_empty() && levels.len() < MAX_GROUP_DEPTH {
+        seen.extend(level.iter().cloned());
+        let mut next = vec![];
+        for ids in join_all(level.iter().map(|id| children(groups, id))).await {
+            next.extend(ids?.into_iter().filter(|id| !seen.contains(id)));
+        }
+        levels.push(level);
+        level = next;
+    }
+
+    Ok(levels)
+}
+
+/// Get all groups below a group, level by level
+pub async fn descendants(
+    groups: &dyn GroupRepository,
+    group_id: &str,
+) -> Result<Vec<String>, Status> {
+    Ok(levels(groups, group_id).await?.concat())
+}
+
+/// Check if a group can be moved below a parent group, returning the error
+/// if it can not
+pub async fn check_parent(
+    groups: &dyn GroupRepository,
+    group_id: &str,
+    parent_id: &str,
+) -> Result<(), HierarchyError> {
+    // the level of the group below the parent and its ancestors
+    let mut depth = 1;
+    let mut ancestor = Some(parent_id.to_string());
+    while let Some(id) = ancestor {
+        if id == group_id {
+            return Err(HierarchyError::Cycle);
+        }
+        depth += 1;
+        if depth > MAX_GROUP_DEPTH {
+            return Err(HierarchyError::TooDeep);
+        }
+
+        ancestor = match groups.get_by_id(id.clone()).await {
+            Ok(object) => parent_of(&object),
+            Err(e) if e.code() == Code::NotFound && id == parent_id => {
+                return Err(HierarchyError::ParentNotFound)
+            }
+            // a removed ancestor ends the hierarchy
+            Err(e) if e.code() == Code::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+    }
+
+    let height = levels(groups, group_id).await?.len();
+    match depth + height > MAX_GROUP_DEPTH {
+        true => Err(HierarchyError::TooDeep),
+        false => Ok(()),
+    }
+}
+
+/// Set the parent of a stored group, `None` making it a top level group
+pub async fn set_parent(
+    groups: &dyn GroupRepository,
+    object: group::Object,
+    parent_id: Option<String>,
+) -> Result<(), HierarchyError> {
+    if let Some(parent_id) = &parent_id {
+        check_parent(groups, &object.id, parent_id).await?;
+    }
+
+    let data = group::Data {
+        parent_group_id: parent_id,
+        ..object.data.unwrap_or_default()
+    };
+    groups
+        .update(group::UpdateObject {
+            id: object.id,
+            data: Some(data),
+            mask: Some(FieldMask {
+                paths: vec!["parent_group_id".to_string()],
+            }),
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::memory::MemoryRepository;
+    use std::collections::HashMap;
+
+    /// Create a repository with groups by id and parent id
+    fn hierarchy(groups: &[(&str, Option<&str>)]) -> MemoryRepository<group::Data> {
+        MemoryRepository::with_objects(
+            groups
+                .iter()
+                .map(|(id, parent)| {
+                    let data = group::Data {
+                        parent_group_id: parent.map(str::to_string),
+                        ..Default::default()
+                    };
+                    (id.to_string(), data)
+                })
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_group_hierarchy() {
+        let groups = hierarchy(&[
+            ("region", None),
+            ("base", Some("region")),
+            ("squad-b", Some("base")),
+            ("squad-a", Some("base")),
+            ("other", Some("region")),
+        ]);
+
+        assert_eq!(
+            children(&groups, "region").await.unwrap(),
+            vec!["base", "other"]
+        );
+        assert_eq!(
+            descendants(&groups, "region").await.unwrap(),
+            vec!["base", "other", "squad-a", "squad-b"]
+        );
+        assert!(descendants(&groups, "squad-a").await.unwrap().is_empty());
+
+        let other = groups.get_by_id("other".to_string()).await.unwrap();
+        assert_eq!(parent_of(&other), Some("region".to_string()));
+        set_parent(&groups, other, None).await.unwrap();
+        let other = groups.get_by_id("other".to_string()).await.unwrap();
+        assert_eq!(parent_of(&other), None);
+        assert_eq!(children(&groups, "region").await.unwrap(), vec!["base"]);
+    }
+
+    #[tokio::test]
+    async fn test_group_hierarchy_cycles() {
+        let groups = hierarchy(&[
+            ("region", None),
+            ("base", Some("region")),
+            ("squad", Some("base")),
+        ]);
+
+        for (group_id, parent_id) in [("region", "squad"), ("region", "region"), ("base", "squad")]
+        {
+            let error = check_parent(&groups, group_id, parent_id)
+                .await
+                .unwrap_err();
+            assert!(matches!(error, HierarchyError::Cycle));
+        }
+        let error = check_parent(&groups, "base", "unknown").await.unwrap_err();
+        assert!(matches!(error, HierarchyError::ParentNotFound));
+
+        // moving a group up the hierarchy is allowed
+        assert!(check_parent(&groups, "squad", "region").await.is_ok());
+
+        let error = check_parent(
+            &MemoryRepository::<group::Data>::unavailable(),
+            "base",
+            "region",
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, HierarchyError::Storage(_)));
+    }
+
+    #[tokio::test]
+    async fn test_group_hierarchy_depth() {
+        let ids: Vec<String> = (0..MAX_GROUP_DEPTH)
+            .map(|level| level.to_string())
+            .collect();
+        let mut levels: Vec<(&str, Option<&str>)> = vec![(ids[0].as_str(), None)];
+        levels.extend(
+            ids.windows(2)
+                .map(|pair| (pair[1].as_str(), Some(pair[0].as_str()))),
+        );
+        levels.push(("top", None));
+        levels.push(("child", Some("top")));
+        let groups = hierarchy(&levels);
+
+        let deepest = &ids[MAX_GROUP_DEPTH - 1];
+        let error = check_parent(&groups, "top", deepest).await.unwrap_err();
+        assert!(matches!(error, HierarchyError::TooDeep));
+
+        // a subtree can not be moved below a group if it would be too deep
+        let error = check_parent(&groups, "top", "6").await.unwrap_err();
+        assert!(matches!(error, HierarchyError::TooDeep));
+        assert!(check_parent(&groups, "top", "5").await.is_ok());
+    }
+}
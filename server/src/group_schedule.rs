@@ -0,0 +1,219 @@
+//! # Group Schedule
+//!
+//! Schedules constraining all assets of an asset group, for example a fleet
+//! delegated to another operator only on weekdays.
+//!
+//! svc-storage does not store a schedule for groups, so [`GroupSchedules`]
+//! keeps the schedules in the [`GROUP_SCHEDULES_TABLE`] of the persisted
+//! state, see [`crate::state`]. The same store is used by the REST API to
+//! set and evaluate the schedules and by the gRPC API to let svc-scheduler
+//! enforce them. A schedule set on one replica is not enforced through the
+//! others.
+
+use crate::schedule::{OccurrenceBudget, Schedule, ScheduleError, TimeWindow};
+use crate::state::{StateError, StateTable};
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::{self, Display, Formatter};
+
+/// Name of the table of the group schedules in the state directory
+pub const GROUP_SCHEDULES_TABLE: &str = "group_schedules";
+
+/// Maximum time range, in days, the availability of a group can be requested for
+pub const MAX_GROUP_AVAILABILITY_RANGE_DAYS: i64 = 31;
+
+/// Check if a requested time range is valid
+pub fn is_valid_range(from: DateTime<Utc>, to: DateTime<Utc>) -> bool {
+    from < to && to - from <= Duration::days(MAX_GROUP_AVAILABILITY_RANGE_DAYS)
+}
+
+/// Availability of a group within a time range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupWindows {
+    /// True if the group has a schedule constraining its assets
+    pub constrained: bool,
+    /// Windows the assets of the group can be used
+    pub windows: Vec<TimeWindow>,
+}
+
+impl GroupWindows {
+    /// Check if the provided window is fully covered by the available windows
+    pub fn covers(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        self.windows
+            .iter()
+            .any(|window| window.start <= start && end <= window.end)
+    }
+}
+
+/// Errors returned when setting the schedule of a group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupScheduleError {
+    /// The schedule is invalid
+    Schedule(ScheduleError),
+    /// The schedule could not be persisted
+    State(StateError),
+}
+
+impl Display for GroupScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupScheduleError::Schedule(e) => write!(f, "{e}"),
+            GroupScheduleError::State(e) => write!(f, "could not persist schedule: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for GroupScheduleError {}
+
+/// Store of the schedules per asset group id.
+///
+/// The default store is kept in memory, [`GroupSchedules::open`] persists
+/// the schedules. Only valid schedules are stored, they are parsed again
+/// when evaluated.
+#[derive(Debug, Clone, Default)]
+pub struct GroupSchedules {
+    schedules: StateTable<String>,
+}
+
+impl GroupSchedules {
+    /// Open the schedules persisted in the state directory, kept in memory
+    /// if no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            schedules: StateTable::open(dir, GROUP_SCHEDULES_TABLE)?,
+        })
+    }
+
+    /// Set the schedule of a group, an empty schedule removes it
+    pub fn set(&self, group_id: &str, schedule: Option<String>) -> Result<(), GroupScheduleError> {
+        let result = match schedule.filter(|schedule| !schedule.trim().is_empty()) {
+            Some(schedule) => {
+                schedule
+                    .parse::<Schedule>()
+                    .map_err(GroupScheduleError::Schedule)?;
+                self.schedules.insert(group_id, schedule)
+            }
+            None => self.schedules.remove(group_id),
+        };
+
+        result.map(|_| ()).map_err(GroupScheduleError::State)
+    }
+
+    /// Get the schedule of a group
+    pub fn get(&self, group_id: &str) -> Option<String> {
+        self.schedules.get(group_id)
+    }
+
+    /// Get the windows the assets of a group can be used.
+    ///
+    /// A group without a schedule does not constrain its assets, so it is
+    /// available for the whole range.
+    pub fn availability(
+        &self,
+        group_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<GroupWindows, ScheduleError> {
+        Ok(match self.get(group_id) {
+            Some(schedule) => GroupWindows {
+                constrained: true,
+                windows: schedule.parse::<Schedule>()?.windows(
+                    from,
+                    to,
+                    &mut OccurrenceBudget::default(),
+                )?,
+            },
+            None => GroupWindows {
+                constrained: false,
+                windows: vec![TimeWindow {
+                    start: from,
+                    end: to,
+                }],
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Weekdays only
+    const SCHEDULE: &str = "DTSTART:20221017T000000Z;DURATION:PT24H\n\
+        RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR";
+
+    #[test]
+    fn test_group_schedules() {
+        let schedules = GroupSchedules::default();
+        let id = "group";
+        // Saturday to Monday
+        let from = Utc.with_ymd_and_hms(2022, 10, 22, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2022, 10, 25, 0, 0, 0).unwrap();
+
+        let availability = schedules.availability(id, from, to).unwrap();
+        assert!(!availability.constrained);
+        assert!(availability.covers(from, to));
+
+        assert!(schedules
+            .set(id, Some("RRULE:FREQ=DAILY".to_string()))
+            .is_err());
+        assert!(schedules.get(id).is_none());
+
+        schedules.set(id, Some(SCHEDULE.to_string())).unwrap();
+        assert_eq!(schedules.get(id), Some(SCHEDULE.to_string()));
+
+        let monday = Utc.with_ymd_and_hms(2022, 10, 24, 0, 0, 0).unwrap();
+        let availability = schedules.availability(id, from, to).unwrap();
+        assert!(availability.constrained);
+        assert_eq!(
+            availability.windows,
+            vec![TimeWindow {
+                start: monday,
+                end: to,
+            }]
+        );
+        assert!(availability.covers(monday, monday + Duration::hours(2)));
+        assert!(!availability.covers(from, monday + Duration::hours(2)));
+
+        schedules.set(id, Some(" ".to_string())).unwrap();
+        assert!(schedules.get(id).is_none());
+    }
+
+    #[test]
+    fn test_group_schedules_persisted() {
+        let dir = crate::state::test_state_dir();
+        let from = Utc.with_ymd_and_hms(2022, 10, 22, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2022, 10, 25, 0, 0, 0).unwrap();
+
+        let schedules = GroupSchedules::open(Some(&dir)).unwrap();
+        schedules.set("group", Some(SCHEDULE.to_string())).unwrap();
+        schedules
+            .set("removed", Some(SCHEDULE.to_string()))
+            .unwrap();
+        schedules.set("removed", None).unwrap();
+
+        // the group is still constrained after a restart
+        let schedules = GroupSchedules::open(Some(&dir)).unwrap();
+        assert_eq!(schedules.get("group"), Some(SCHEDULE.to_string()));
+        assert!(
+            schedules
+                .availability("group", from, to)
+                .unwrap()
+                .constrained
+        );
+        assert!(schedules.get("removed").is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_valid_range() {
+        let from = Utc::now();
+        assert!(is_valid_range(from, from + Duration::days(1)));
+        assert!(!is_valid_range(from, from));
+        assert!(!is_valid_range(
+            from,
+            from + Duration::days(MAX_GROUP_AVAILABILITY_RANGE_DAYS + 1)
+        ));
+    }
+}
@@ -0,0 +1,232 @@
+//! gRPC authorization
+//!
+//! The mutation methods of the gRPC server are only allowed for trusted
+//! services. A caller is identified by the client certificate it presented
+//! during the mTLS handshake: the common name and the DNS subject alternative
+//! names of the certificate are its identities. Each method has its own list
+//! of allowed identities in the [`Config`], a method with an empty list is
+//! rejected for all callers.
+//!
+//! Without mTLS the caller presents no certificate, so all mutations are
+//! rejected.
+
+use super::error::{ErrorReason, GrpcError};
+use crate::Config;
+
+use openssl::nid::Nid;
+use openssl::x509::X509;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use tonic::transport::Certificate;
+use tonic::{Request, Status};
+
+/// gRPC methods which change assets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mutation {
+    /// `registerVehicle`
+    RegisterVehicle,
+    /// `updateVertipadOccupancy`
+    UpdateVertipadOccupancy,
+    /// `setAssetStatus`
+    SetAssetStatus,
+}
+
+impl Display for Mutation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Mutation::RegisterVehicle => write!(f, "registerVehicle"),
+            Mutation::UpdateVertipadOccupancy => write!(f, "updateVertipadOccupancy"),
+            Mutation::SetAssetStatus => write!(f, "setAssetStatus"),
+        }
+    }
+}
+
+/// Get the identities of the peer certificate, the first certificate of the
+/// chain presented by the caller.
+///
+/// Certificates which can not be parsed have no identities.
+pub fn peer_identities(certs: &[Certificate]) -> Vec<String> {
+    let Some(cert) = certs.first() else {
+        return vec![];
+    };
+
+    let cert = X509::from_der(cert.get_ref()).or_else(|_| X509::from_pem(cert.get_ref()));
+    let cert = match cert {
+        Ok(cert) => cert,
+        Err(e) => {
+            grpc_warn!("could not parse peer certificate: {}", e);
+            return vec![];
+        }
+    };
+
+    let common_names = cert
+        .subject_name()
+        .entries_by_nid(Nid::COMMONNAME)
+        .filter_map(|entry| entry.data().as_utf8().ok())
+        .map(|name| name.to_string());
+    let dns_names = cert
+        .subject_alt_names()
+        .into_iter()
+        .flatten()
+        .filter_map(|name| name.dnsname().map(str::to_string));
+
+    common_names.chain(dns_names).collect()
+}
+
+/// Peer identities allowed to call each [`Mutation`]
+#[derive(Debug, Clone, Default)]
+pub struct PeerAuthorization {
+    peers: HashMap<Mutation, Vec<String>>,
+}
+
+impl From<&Config> for PeerAuthorization {
+    fn from(config: &Config) -> Self {
+        Self {
+            peers: HashMap::from([
+                (
+                    Mutation::RegisterVehicle,
+                    config.grpc_register_vehicle_peers.clone(),
+                ),
+                (
+                    Mutation::UpdateVertipadOccupancy,
+                    config.grpc_update_vertipad_occupancy_peers.clone(),
+                ),
+                (
+                    Mutation::SetAssetStatus,
+                    config.grpc_set_asset_status_peers.clone(),
+                ),
+            ]),
+        }
+    }
+}
+
+impl PeerAuthorization {
+    /// Check if a caller with the provided identities may call the
+    /// mutation, returning the allowed identity.
+    ///
+    /// Returns `Unauthenticated` if the caller has no identity and
+    /// `PermissionDenied` if none of its identities is allowed.
+    pub fn check(&self, mutation: Mutation, identities: &[String]) -> Result<String, Status> {
+        if identities.is_empty() {
+            grpc_warn!("{} rejected, no peer certificate.", mutation);
+            return Err(GrpcError::new(
+                ErrorReason::PeerUnauthenticated,
+                "a client certificate is required for mutations",
+            )
+            .into());
+        }
+
+        let allowed = self
+            .peers
+            .get(&mutation)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        identities
+            .iter()
+            .find(|identity| allowed.contains(identity))
+            .cloned()
+            .ok_or_else(|| {
+                grpc_warn!("{} rejected for peer {:?}.", mutation, identities);
+                GrpcError::new(
+                    ErrorReason::PeerNotAllowed,
+                    format!("peer is not allowed to call {mutation}"),
+                )
+                .into()
+            })
+    }
+
+    /// Check if the caller of the request may call the mutation, returning
+    /// the allowed identity.
+    pub fn authorize<T>(&self, mutation: Mutation, request: &Request<T>) -> Result<String, Status> {
+        let identities = request
+            .peer_certs()
+            .map(|certs| peer_identities(&certs))
+            .unwrap_or_default();
+        self.check(mutation, &identities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::asn1::Asn1Time;
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+    use openssl::x509::extension::SubjectAlternativeName;
+    use openssl::x509::X509NameBuilder;
+
+    fn certificate(common_name: &str, dns_name: &str) -> Certificate {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, common_name)
+            .unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        let san = SubjectAlternativeName::new()
+            .dns(dns_name)
+            .build(&builder.x509v3_context(None, None))
+            .unwrap();
+        builder.append_extension(san).unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+
+        Certificate::from_pem(builder.build().to_pem().unwrap())
+    }
+
+    #[test]
+    fn test_peer_identities() {
+        let cert = certificate("svc-scheduler", "svc-scheduler.arrow.svc");
+        assert_eq!(
+            peer_identities(&[cert]),
+            vec![
+                "svc-scheduler".to_string(),
+                "svc-scheduler.arrow.svc".to_string()
+            ]
+        );
+        assert!(peer_identities(&[]).is_empty());
+        assert!(peer_identities(&[Certificate::from_pem("invalid")]).is_empty());
+    }
+
+    #[test]
+    fn test_peer_authorization() {
+        let mut config = Config::default();
+        config.grpc_update_vertipad_occupancy_peers = vec!["svc-scheduler".to_string()];
+        let auth = PeerAuthorization::from(&config);
+        let scheduler = vec!["svc-scheduler".to_string()];
+
+        assert_eq!(
+            auth.check(Mutation::UpdateVertipadOccupancy, &scheduler)
+                .unwrap(),
+            "svc-scheduler"
+        );
+        assert_eq!(
+            auth.check(Mutation::SetAssetStatus, &scheduler)
+                .unwrap_err()
+                .code(),
+            tonic::Code::PermissionDenied
+        );
+        assert_eq!(
+            auth.check(Mutation::UpdateVertipadOccupancy, &[])
+                .unwrap_err()
+                .code(),
+            tonic::Code::Unauthenticated
+        );
+
+        // without mTLS a request has no peer certificate
+        let error = auth
+            .authorize(Mutation::UpdateVertipadOccupancy, &Request::new(()))
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unauthenticated);
+    }
+}
@@ -1,12 +1,110 @@
 //! gRPC client helpers implementation
+//!
+//! A single [`GrpcClients`] is created at startup and shared by the REST and
+//! gRPC servers. The svc-storage clients connect lazily and share their
+//! channel between clones, so cloning the clients for each request does not
+//! open new connections.
+//!
+//! Each readiness check records the state of the connections. A client
+//! failing the check is invalidated, so its channel is re-established on the
+//! next call instead of reusing a broken connection.
 
-use svc_storage_client_grpc::prelude::Clients;
+pub use crate::rest::api::rest_types::{GrpcConnectionState, GrpcConnectionStatus};
+
+use crate::fan_out::{call_timeout, with_timeout};
+use crate::latency::StorageLatencies;
+use lib_common::time::Utc;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+use svc_storage_client_grpc::prelude::{Client as _, Clients, ReadyRequest};
+use svc_storage_client_grpc::simple_service::Client;
+
+/// Names of the svc-storage clients used by this service
+const STORAGE_CLIENTS: [&str; 3] = ["vehicle", "vertiport", "vertipad"];
+
+/// Status of a client which was not checked yet
+fn unknown(name: &str) -> GrpcConnectionStatus {
+    GrpcConnectionStatus {
+        name: name.to_string(),
+        state: GrpcConnectionState::Unknown,
+        consecutive_failures: 0,
+        reconnects: 0,
+        last_change: None,
+    }
+}
+
+/// Connection status per client name
+#[derive(Debug, Clone, Default)]
+struct ConnectionStates {
+    states: Arc<RwLock<BTreeMap<String, GrpcConnectionStatus>>>,
+}
+
+impl ConnectionStates {
+    fn read(&self) -> RwLockReadGuard<'_, BTreeMap<String, GrpcConnectionStatus>> {
+        self.states
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, BTreeMap<String, GrpcConnectionStatus>> {
+        self.states
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Record the result of a readiness check
+    fn record(&self, name: &str, ready: bool) {
+        let mut states = self.write();
+        let status = states
+            .entry(name.to_string())
+            .or_insert_with(|| unknown(name));
+
+        let state = match ready {
+            true => GrpcConnectionState::Ready,
+            false => GrpcConnectionState::Unavailable,
+        };
+        if status.state != state {
+            status.state = state;
+            status.last_change = Some(Utc::now());
+        }
+
+        match ready {
+            true => status.consecutive_failures = 0,
+            false => status.consecutive_failures += 1,
+        }
+    }
+
+    /// Record a dropped connection
+    fn record_reconnect(&self, name: &str) {
+        self.write()
+            .entry(name.to_string())
+            .or_insert_with(|| unknown(name))
+            .reconnects += 1;
+    }
+
+    /// Get the status of all clients, ordered by name
+    fn list(&self) -> Vec<GrpcConnectionStatus> {
+        let states = self.read();
+        STORAGE_CLIENTS
+            .iter()
+            .map(|name| states.get(*name).cloned().unwrap_or_else(|| unknown(name)))
+            .collect()
+    }
+}
 
 /// Struct to hold all gRPC client connections
 #[derive(Clone, Debug)]
 pub struct GrpcClients {
     /// Svc-Storage clients
     pub storage: Clients,
+    /// Timeout of the readiness checks and of the storage calls of the
+    /// repositories, `None` if disabled
+    pub call_timeout: Option<Duration>,
+    /// State of the connections, shared between clones
+    connections: ConnectionStates,
+    /// Latency of the svc-storage calls, shared between clones
+    latencies: StorageLatencies,
 }
 
 impl GrpcClients {
@@ -16,14 +114,76 @@ impl GrpcClients {
 
         GrpcClients {
             storage: storage_clients,
+            call_timeout: call_timeout(config.storage_call_timeout_ms),
+            connections: ConnectionStates::default(),
+            latencies: StorageLatencies::default(),
+        }
+    }
+
+    /// Check if the svc-storage clients used by this service are ready.
+    ///
+    /// The clients are checked concurrently. Returns `false` if any of the
+    /// clients can not be reached. Clients which can not be reached are
+    /// invalidated, so they reconnect on their next call.
+    pub async fn storage_ready(&self) -> bool {
+        let (storage, timeout) = (&self.storage, self.call_timeout);
+        let (vehicle, vertiport, vertipad) = tokio::join!(
+            with_timeout(timeout, storage.vehicle.is_ready(ReadyRequest {})),
+            with_timeout(timeout, storage.vertiport.is_ready(ReadyRequest {})),
+            with_timeout(timeout, storage.vertipad.is_ready(ReadyRequest {})),
+        );
+
+        let mut ready = true;
+        for (name, error) in [
+            ("vehicle", vehicle.err()),
+            ("vertiport", vertiport.err()),
+            ("vertipad", vertipad.err()),
+        ] {
+            self.connections.record(name, error.is_none());
+            if let Some(e) = error {
+                grpc_warn!("svc-storage {} unavailable: {}", name, e);
+                self.invalidate(name).await;
+                ready = false;
+            }
+        }
+
+        ready
+    }
+
+    /// Drop the connections of all svc-storage clients.
+    ///
+    /// The clients reconnect on their next call.
+    pub async fn reconnect(&self) {
+        grpc_info!("reconnecting svc-storage clients.");
+        for name in STORAGE_CLIENTS {
+            self.invalidate(name).await;
+        }
+    }
+
+    /// Get the state of the svc-storage connections
+    pub fn connection_status(&self) -> Vec<GrpcConnectionStatus> {
+        self.connections.list()
+    }
+
+    /// Get the latency of the svc-storage calls, see [`crate::latency`]
+    pub fn storage_latencies(&self) -> &StorageLatencies {
+        &self.latencies
+    }
+
+    /// Drop the connection of a client, so it reconnects on its next call
+    async fn invalidate(&self, name: &str) {
+        self.connections.record_reconnect(name);
+        match name {
+            "vehicle" => self.storage.vehicle.invalidate().await,
+            "vertiport" => self.storage.vertiport.invalidate().await,
+            "vertipad" => self.storage.vertipad.invalidate().await,
+            _ => grpc_warn!("unknown client {}.", name),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use svc_storage_client_grpc::prelude::Client;
-
     use super::*;
 
     #[tokio::test]
@@ -48,4 +208,68 @@ mod tests {
 
         ut_info!("Success.");
     }
+
+    #[tokio::test]
+    async fn test_grpc_clients_storage_ready() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let config = crate::Config::default();
+        let clients = GrpcClients::default(config);
+        assert!(clients.storage_ready().await);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_grpc_clients_connection_status() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let clients = GrpcClients::default(crate::Config::default());
+        let status = clients.connection_status();
+        assert_eq!(status.len(), STORAGE_CLIENTS.len());
+        assert!(status
+            .iter()
+            .all(|status| status.state == GrpcConnectionState::Unknown));
+
+        // clones share the connection state
+        assert!(clients.clone().storage_ready().await);
+        let status = clients.connection_status();
+        assert!(status
+            .iter()
+            .all(|status| status.state == GrpcConnectionState::Ready));
+
+        clients.reconnect().await;
+        assert!(clients
+            .connection_status()
+            .iter()
+            .all(|status| status.reconnects == 1));
+        assert!(clients.storage_ready().await);
+
+        ut_info!("Success.");
+    }
+
+    #[test]
+    fn test_connection_states_record() {
+        let states = ConnectionStates::default();
+        states.record("vehicle", true);
+        let status = &states.list()[0];
+        assert_eq!(status.state, GrpcConnectionState::Ready);
+        assert!(status.last_change.is_some());
+
+        states.record("vehicle", false);
+        states.record("vehicle", false);
+        states.record_reconnect("vehicle");
+        let status = &states.list()[0];
+        assert_eq!(status.state, GrpcConnectionState::Unavailable);
+        assert_eq!(status.consecutive_failures, 2);
+        assert_eq!(status.reconnects, 1);
+
+        states.record("vehicle", true);
+        let status = &states.list()[0];
+        assert_eq!(status.state, GrpcConnectionState::Ready);
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(status.reconnects, 1);
+    }
 }
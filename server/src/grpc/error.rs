@@ -0,0 +1,211 @@
+//! gRPC errors
+//!
+//! Errors returned by the gRPC server carry a `google.rpc.ErrorInfo` detail
+//! with the [`ErrorReason`] of the error in the [`ERROR_DOMAIN`], so clients
+//! can handle them without parsing the message. Invalid arguments also carry
+//! a `google.rpc.BadRequest` detail listing the invalid fields.
+//!
+//! Errors of the REST handlers shared with the gRPC server and errors of
+//! svc-storage are mapped to a [`GrpcError`] here, so all methods report the
+//! same reason for the same problem.
+
+pub use super::server::grpc_server::ErrorReason;
+
+use crate::rest::api::validation::ValidationError;
+use crate::rest::json::Json;
+use crate::rest::FieldError;
+use hyper::StatusCode;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use tonic::{Code, Status};
+use tonic_types::{ErrorDetails, StatusExt};
+
+/// Domain of the `google.rpc.ErrorInfo` details
+pub const ERROR_DOMAIN: &str = "svc-assets";
+
+impl ErrorReason {
+    /// Get the gRPC status code of errors with this reason
+    pub fn code(&self) -> Code {
+        match self {
+            ErrorReason::Unspecified | ErrorReason::Internal => Code::Internal,
+            ErrorReason::AssetNotFound => Code::NotFound,
+            ErrorReason::InvalidArgument => Code::InvalidArgument,
+            ErrorReason::OwnershipViolation | ErrorReason::PeerNotAllowed => Code::PermissionDenied,
+            ErrorReason::PeerUnauthenticated => Code::Unauthenticated,
+            ErrorReason::AssetConflict => Code::FailedPrecondition,
+            ErrorReason::StorageUnavailable | ErrorReason::ReadOnly => Code::Unavailable,
+            ErrorReason::StorageTimeout => Code::DeadlineExceeded,
+            ErrorReason::QuotaExceeded => Code::ResourceExhausted,
+        }
+    }
+}
+
+/// An error returned by the gRPC server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrpcError {
+    /// Reason of the error
+    pub reason: ErrorReason,
+    /// Description of the error
+    pub message: String,
+    /// Invalid fields of the request
+    pub field_errors: Vec<FieldError>,
+}
+
+impl GrpcError {
+    /// Create a new error
+    pub fn new(reason: ErrorReason, message: impl Into<String>) -> Self {
+        Self {
+            reason,
+            message: message.into(),
+            field_errors: vec![],
+        }
+    }
+
+    /// Create an [`ErrorReason::InvalidArgument`] error for a single field
+    pub fn invalid_field(field: &str, message: &str) -> Self {
+        Self::from_field_errors(vec![FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        }])
+    }
+
+    /// Create an [`ErrorReason::InvalidArgument`] error for the provided
+    /// fields
+    pub fn from_field_errors(field_errors: Vec<FieldError>) -> Self {
+        let message = field_errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.message))
+            .collect::<Vec<String>>()
+            .join("; ");
+
+        Self {
+            reason: ErrorReason::InvalidArgument,
+            message,
+            field_errors,
+        }
+    }
+
+    /// Get the gRPC status code of the error
+    pub fn code(&self) -> Code {
+        self.reason.code()
+    }
+}
+
+impl Display for GrpcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.reason.as_str_name(), self.message)
+    }
+}
+
+impl std::error::Error for GrpcError {}
+
+impl From<GrpcError> for Status {
+    fn from(error: GrpcError) -> Self {
+        let mut details =
+            ErrorDetails::with_error_info(error.reason.as_str_name(), ERROR_DOMAIN, HashMap::new());
+        for field_error in error.field_errors.iter() {
+            details.add_bad_request_violation(&field_error.field, &field_error.message);
+        }
+
+        Status::with_error_details(error.code(), error.message, details)
+    }
+}
+
+/// Map an error returned by svc-storage
+impl From<Status> for GrpcError {
+    fn from(status: Status) -> Self {
+        let reason = match status.code() {
+            Code::NotFound => ErrorReason::AssetNotFound,
+            Code::InvalidArgument => ErrorReason::InvalidArgument,
+            Code::Unavailable => ErrorReason::StorageUnavailable,
+            Code::DeadlineExceeded => ErrorReason::StorageTimeout,
+            _ => ErrorReason::Internal,
+        };
+
+        Self::new(reason, status.message())
+    }
+}
+
+/// Map the status code and field errors returned by a REST handler
+impl From<ValidationError> for GrpcError {
+    fn from((status, Json(field_errors)): ValidationError) -> Self {
+        let reason = match status {
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+                ErrorReason::InvalidArgument
+            }
+            StatusCode::NOT_FOUND => ErrorReason::AssetNotFound,
+            StatusCode::FORBIDDEN => ErrorReason::OwnershipViolation,
+            StatusCode::CONFLICT => ErrorReason::AssetConflict,
+            StatusCode::SERVICE_UNAVAILABLE => ErrorReason::StorageUnavailable,
+            StatusCode::GATEWAY_TIMEOUT => ErrorReason::StorageTimeout,
+            _ => ErrorReason::Internal,
+        };
+
+        match (reason, field_errors.is_empty()) {
+            (ErrorReason::InvalidArgument, false) => Self::from_field_errors(field_errors),
+            _ => Self::new(reason, status.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grpc_error_details() {
+        let status = Status::from(GrpcError::invalid_field("asset_id", "must be a valid UUID"));
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(status.message(), "asset_id: must be a valid UUID");
+
+        let details = status.get_error_details();
+        let info = details.error_info().unwrap();
+        assert_eq!(info.reason, "INVALID_ARGUMENT");
+        assert_eq!(info.domain, ERROR_DOMAIN);
+        let violations = details.bad_request().unwrap().field_violations;
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].field, "asset_id");
+        assert_eq!(violations[0].description, "must be a valid UUID");
+
+        let status = Status::from(GrpcError::new(
+            ErrorReason::AssetNotFound,
+            "no aircraft with id",
+        ));
+        assert_eq!(status.code(), Code::NotFound);
+        let details = status.get_error_details();
+        assert_eq!(details.error_info().unwrap().reason, "ASSET_NOT_FOUND");
+        assert!(details.bad_request().is_none());
+    }
+
+    #[test]
+    fn test_grpc_error_mapping() {
+        let error = GrpcError::from(Status::not_found("no object"));
+        assert_eq!(error.reason, ErrorReason::AssetNotFound);
+        assert_eq!(error.message, "no object");
+        assert_eq!(
+            GrpcError::from(Status::deadline_exceeded("timeout")).reason,
+            ErrorReason::StorageTimeout
+        );
+        assert_eq!(
+            GrpcError::from(Status::unknown("unknown")).reason,
+            ErrorReason::Internal
+        );
+
+        let error = GrpcError::from((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(vec![FieldError {
+                field: "registration_number".to_string(),
+                message: "already in use".to_string(),
+            }]),
+        ));
+        assert_eq!(error.reason, ErrorReason::InvalidArgument);
+        assert_eq!(error.field_errors.len(), 1);
+        assert_eq!(error.message, "registration_number: already in use");
+
+        let error = GrpcError::from((StatusCode::FORBIDDEN, Json(vec![])));
+        assert_eq!(error.reason, ErrorReason::OwnershipViolation);
+        assert_eq!(error.code(), Code::PermissionDenied);
+        let error = GrpcError::from((StatusCode::CONFLICT, Json(vec![])));
+        assert_eq!(error.code(), Code::FailedPrecondition);
+    }
+}
@@ -0,0 +1,140 @@
+//! gRPC health reporting
+//!
+//! Besides the default service (`""`) and the `RpcService` of this server,
+//! the health service reports separate service names, so probes can check a
+//! single part of the service:
+//! - [`REST_SERVICE`]: the REST server serves requests and can reach its
+//!   storage
+//! - [`STORAGE_LINK_SERVICE`]: the svc-storage clients are ready
+//!
+//! The statuses are updated every [`HEALTH_CHECK_INTERVAL`] by
+//! [`health_monitor`].
+
+use super::server::{RpcServiceServer, ServerImpl};
+use std::time::Duration;
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+
+/// Interval between dependency checks used to update the health service
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Health service name of the REST server
+pub const REST_SERVICE: &str = "assets.rest";
+
+/// Health service name of the connection to svc-storage
+pub const STORAGE_LINK_SERVICE: &str = "assets.storage-link";
+
+/// Serving status of the reported services, all not serving by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HealthStatuses {
+    /// The gRPC server is ready, used for the default service as well
+    pub rpc: bool,
+    /// The REST server is serving and ready
+    pub rest: bool,
+    /// The svc-storage clients are ready
+    pub storage_link: bool,
+}
+
+/// Convert a readiness to the reported [`ServingStatus`]
+fn serving_status(serving: bool) -> ServingStatus {
+    match serving {
+        true => ServingStatus::Serving,
+        false => ServingStatus::NotServing,
+    }
+}
+
+impl ServerImpl {
+    /// Check the dependencies of the reported services.
+    ///
+    /// svc-storage is checked once and used for all services; the servers
+    /// are not ready before the startup phase completed.
+    pub async fn health_statuses(&self) -> HealthStatuses {
+        let storage_link = match &self.grpc_clients {
+            Some(grpc_clients) => grpc_clients.storage_ready().await,
+            None => true,
+        };
+        let rpc = storage_link && self.shared.startup.is_ready();
+
+        HealthStatuses {
+            rpc,
+            rest: rpc && self.shared.rest_serving.is_serving(),
+            storage_link,
+        }
+    }
+}
+
+/// Set the statuses of all reported services
+pub async fn report(reporter: &mut HealthReporter, statuses: HealthStatuses) {
+    reporter
+        .set_service_status("", serving_status(statuses.rpc))
+        .await;
+    match statuses.rpc {
+        true => reporter.set_serving::<RpcServiceServer<ServerImpl>>().await,
+        false => {
+            reporter
+                .set_not_serving::<RpcServiceServer<ServerImpl>>()
+                .await
+        }
+    }
+    reporter
+        .set_service_status(REST_SERVICE, serving_status(statuses.rest))
+        .await;
+    reporter
+        .set_service_status(STORAGE_LINK_SERVICE, serving_status(statuses.storage_link))
+        .await;
+}
+
+/// Keep the reported statuses in line with the dependencies of the server,
+/// until the task is aborted
+pub async fn health_monitor(imp: ServerImpl, mut reporter: HealthReporter) {
+    let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+    let mut last = None;
+    loop {
+        interval.tick().await;
+        let statuses = imp.health_statuses().await;
+        if last != Some(statuses) {
+            grpc_info!("health statuses changed: {:?}.", statuses);
+            last = Some(statuses);
+        }
+        report(&mut reporter, statuses).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::client::GrpcClients;
+    use crate::rest::api::rest_types::StartupState;
+    use crate::Config;
+
+    #[tokio::test]
+    async fn test_health_statuses() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let imp = ServerImpl {
+            grpc_clients: Some(GrpcClients::default(Config::default())),
+            ..Default::default()
+        };
+        let statuses = imp.health_statuses().await;
+        assert_eq!(
+            statuses,
+            HealthStatuses {
+                rpc: false,
+                rest: false,
+                storage_link: true,
+            }
+        );
+
+        imp.shared.startup.set(StartupState::Ready);
+        assert!(imp.health_statuses().await.rpc);
+        assert!(!imp.health_statuses().await.rest);
+
+        let serving = imp.shared.rest_serving.start();
+        assert!(imp.health_statuses().await.rest);
+        drop(serving);
+        assert!(!imp.health_statuses().await.rest);
+
+        ut_info!("Success.");
+    }
+}
@@ -3,5 +3,8 @@
 
 #[macro_use]
 pub mod macros;
+pub mod auth;
 pub mod client;
+pub mod error;
+pub mod health;
 pub mod server;
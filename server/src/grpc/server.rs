@@ -3,21 +3,375 @@
 pub mod grpc_server {
     #![allow(unused_qualifications, missing_docs)]
     tonic::include_proto!("grpc");
+
+    /// Encoded file descriptor set, used by the reflection service
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("grpc_descriptor");
 }
 pub use grpc_server::rpc_service_server::{RpcService, RpcServiceServer};
-pub use grpc_server::{ReadyRequest, ReadyResponse};
+pub use grpc_server::{
+    AvailabilityWindow, GroupAvailabilityRequest, GroupAvailabilityResponse, ReadyRequest,
+    ReadyResponse, RegisterVehicleRequest, RegisterVehicleResponse, SetAssetStatusRequest,
+    SetAssetStatusResponse, UpdateVertipadOccupancyRequest, UpdateVertipadOccupancyResponse,
+    VertiportWeatherMinimumsRequest, VertiportWeatherMinimumsResponse,
+};
 
+#[cfg(not(feature = "stub_server"))]
+use super::auth::Mutation;
+use super::auth::PeerAuthorization;
+use super::client::GrpcClients;
+use super::error::{ErrorReason, GrpcError};
+use super::health::{self, HealthStatuses};
+use crate::group_schedule::{is_valid_range, MAX_GROUP_AVAILABILITY_RANGE_DAYS};
+use crate::repo::Repositories;
+use crate::rest::api::aircraft::check_vehicle_data;
+use crate::rest::api::vertipad::{apply_vertipad_update, VertipadChecks};
+use crate::rest::json::Json;
+use crate::rest::quota::{OperatorQuotas, QuotaKind};
+use crate::rest::read_only::ReadOnlyMode;
+use crate::rest::server::RestComponents;
+use crate::rest::structs::AssetStatus;
+use crate::rest::tenant::Tenancy;
+use crate::rest::{OccupancySource, UpdateVertipadPayload};
+use crate::shared::SharedState;
 use crate::shutdown_signal;
-use crate::Config;
+use crate::tls::{Protocol, ServerTls};
 
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+use lib_common::uuid::to_uuid;
 use std::fmt::Debug;
 use std::net::SocketAddr;
+use std::time::{Duration, SystemTime};
+use svc_storage_client_grpc::prelude::vehicle;
+use tokio::net::TcpListener;
 use tonic::transport::Server;
 use tonic::{Request, Response, Status};
 
 /// struct to implement the gRPC server functions
-#[derive(Debug, Default, Copy, Clone)]
-pub struct ServerImpl {}
+#[derive(Debug, Default, Clone)]
+pub struct ServerImpl {
+    /// gRPC clients of our dependencies, used for readiness checks.
+    ///
+    /// If not provided, the server reports ready once the startup phase
+    /// completed.
+    pub grpc_clients: Option<GrpcClients>,
+
+    /// Storage used by the mutation methods.
+    ///
+    /// If not provided, mutations fail with `Unavailable`.
+    pub repositories: Option<Repositories>,
+
+    /// In memory state shared with the REST server.
+    pub shared: SharedState,
+
+    /// Operator quotas shared with the REST server, with the owners of the
+    /// assets.
+    ///
+    /// If not provided, vehicles can not be registered.
+    pub operator_quotas: Option<OperatorQuotas>,
+
+    /// Peers allowed to call the mutation methods.
+    pub authorization: PeerAuthorization,
+
+    /// Read-only maintenance mode shared with the REST server.
+    ///
+    /// If not provided, mutations are always accepted.
+    pub read_only: Option<ReadOnlyMode>,
+
+    /// Tenants of the service. Trusted services are not bound to a tenant,
+    /// so the mutations only reach and register assets of the default
+    /// tenant.
+    ///
+    /// If not provided, the mutations reach all assets.
+    pub tenancy: Option<Tenancy>,
+}
+
+impl From<grpc_server::AssetStatus> for AssetStatus {
+    fn from(status: grpc_server::AssetStatus) -> Self {
+        match status {
+            grpc_server::AssetStatus::Available => AssetStatus::Available,
+            grpc_server::AssetStatus::Unavailable => AssetStatus::Unavailable,
+            grpc_server::AssetStatus::Emergency => AssetStatus::Emergency,
+        }
+    }
+}
+
+/// Convert a required protobuf timestamp to a [`DateTime`]
+fn to_date_time(
+    field: &str,
+    timestamp: Option<prost_types::Timestamp>,
+) -> Result<DateTime<Utc>, Status> {
+    let timestamp = timestamp.ok_or_else(|| GrpcError::invalid_field(field, "is required"))?;
+    SystemTime::try_from(timestamp)
+        .map(DateTime::<Utc>::from)
+        .map_err(|e| GrpcError::invalid_field(field, &format!("invalid timestamp: {e}")).into())
+}
+
+/// Convert an optional protobuf timestamp to a [`DateTime`]
+fn to_optional_date_time(
+    field: &str,
+    timestamp: Option<prost_types::Timestamp>,
+) -> Result<Option<DateTime<Utc>>, Status> {
+    timestamp
+        .map(|timestamp| to_date_time(field, Some(timestamp)))
+        .transpose()
+}
+
+/// Convert a [`DateTime`] to a protobuf timestamp
+fn to_timestamp(date: DateTime<Utc>) -> prost_types::Timestamp {
+    SystemTime::from(date).into()
+}
+
+impl ServerImpl {
+    /// Check if the dependencies of this server are available.
+    ///
+    /// The server is not ready before the startup phase completed.
+    pub async fn dependencies_ready(&self) -> bool {
+        if !self.shared.startup.is_ready() {
+            return false;
+        }
+
+        match &self.grpc_clients {
+            Some(grpc_clients) => grpc_clients.storage_ready().await,
+            None => true,
+        }
+    }
+
+    /// Evaluate the schedule of an asset group for the requested time range
+    pub fn group_availability(
+        &self,
+        request: GroupAvailabilityRequest,
+    ) -> Result<GroupAvailabilityResponse, Status> {
+        let group_id = to_uuid(&request.group_id)
+            .ok_or_else(|| GrpcError::invalid_field("group_id", "must be a valid UUID"))?
+            .to_string();
+        let from = to_date_time("from", request.from)?;
+        let to = to_date_time("to", request.to)?;
+        if !is_valid_range(from, to) {
+            return Err(GrpcError::invalid_field(
+                "to",
+                &format!(
+                    "must be after 'from' and the range can not exceed {} days",
+                    MAX_GROUP_AVAILABILITY_RANGE_DAYS
+                ),
+            )
+            .into());
+        }
+
+        let availability = self
+            .shared
+            .group_schedules
+            .availability(&group_id, from, to)
+            .map_err(|e| GrpcError::new(ErrorReason::Internal, e.to_string()))?;
+        Ok(GroupAvailabilityResponse {
+            constrained: availability.constrained,
+            available: availability.covers(from, to),
+            windows: availability
+                .windows
+                .into_iter()
+                .map(|window| AvailabilityWindow {
+                    start: Some(to_timestamp(window.start)),
+                    end: Some(to_timestamp(window.end)),
+                })
+                .collect(),
+        })
+    }
+
+    /// Get the weather minimums configured for a vertiport
+    pub fn vertiport_weather_minimums(
+        &self,
+        request: VertiportWeatherMinimumsRequest,
+    ) -> Result<VertiportWeatherMinimumsResponse, Status> {
+        let vertiport_id = to_uuid(&request.vertiport_id)
+            .ok_or_else(|| GrpcError::invalid_field("vertiport_id", "must be a valid UUID"))?
+            .to_string();
+
+        let minimums = self
+            .shared
+            .weather_minimums
+            .get(&vertiport_id)
+            .map(|minimums| grpc_server::WeatherMinimums {
+                max_wind_speed_mps: minimums.max_wind_speed_mps,
+                min_visibility_m: minimums.min_visibility_m,
+                max_precipitation_mm_per_hour: minimums.max_precipitation_mm_per_hour,
+                updated_at: Some(to_timestamp(minimums.updated_at)),
+            });
+        Ok(VertiportWeatherMinimumsResponse {
+            configured: minimums.is_some(),
+            minimums,
+        })
+    }
+
+    /// Get the repositories used by the mutation methods
+    fn repositories(&self) -> Result<&Repositories, Status> {
+        self.repositories.as_ref().ok_or_else(|| {
+            GrpcError::new(ErrorReason::StorageUnavailable, "storage is not available").into()
+        })
+    }
+
+    /// Get the repositories of a mutation, limited to the default tenant.
+    ///
+    /// Returns `Unavailable` while the service is in read-only mode, like the
+    /// REST API.
+    fn mutation_repositories(&self) -> Result<Repositories, Status> {
+        if self
+            .read_only
+            .as_ref()
+            .map_or(false, ReadOnlyMode::is_enabled)
+        {
+            return Err(GrpcError::new(
+                ErrorReason::ReadOnly,
+                "service is in read-only mode for maintenance, changes are not accepted",
+            )
+            .into());
+        }
+
+        let repos = self.repositories()?;
+        Ok(match &self.tenancy {
+            Some(tenancy) => tenancy.repositories(repos, None),
+            None => repos.clone(),
+        })
+    }
+
+    /// Get the operator quotas used to register assets
+    fn operator_quotas(&self) -> Result<&OperatorQuotas, Status> {
+        self.operator_quotas.as_ref().ok_or_else(|| {
+            GrpcError::new(ErrorReason::Internal, "operator quotas are not available").into()
+        })
+    }
+
+    /// Validate and store a new vehicle, returning its id.
+    ///
+    /// The `owner_id` of the request becomes the owner of the vehicle, like
+    /// the operator of a `POST /assets/aircraft` request, and the vehicle
+    /// counts towards its aircraft quota.
+    pub async fn insert_vehicle(
+        &self,
+        request: RegisterVehicleRequest,
+    ) -> Result<RegisterVehicleResponse, Status> {
+        let repos = self.mutation_repositories()?;
+        let quotas = self.operator_quotas()?;
+        let owner_id = to_uuid(&request.owner_id)
+            .ok_or_else(|| GrpcError::invalid_field("owner_id", "must be a valid UUID"))?
+            .to_string();
+        let data = vehicle::Data {
+            vehicle_model_id: request.vehicle_model_id,
+            serial_number: request.serial_number,
+            registration_number: request.registration_number,
+            description: request.description,
+            asset_group_id: request.asset_group_id,
+            schedule: request.schedule,
+            hangar_id: request.hangar_id,
+            hangar_bay_id: request.hangar_bay_id,
+            last_maintenance: to_optional_date_time("last_maintenance", request.last_maintenance)?
+                .map(Into::into),
+            next_maintenance: to_optional_date_time("next_maintenance", request.next_maintenance)?
+                .map(Into::into),
+            created_at: None,
+            updated_at: None,
+        };
+
+        let data = check_vehicle_data(&repos, data)
+            .await
+            .map_err(GrpcError::from)?;
+        let reservation = quotas
+            .reserve(&repos, &owner_id, QuotaKind::Aircraft)
+            .await
+            .map_err(|status| match status {
+                StatusCode::FORBIDDEN => GrpcError::new(
+                    ErrorReason::QuotaExceeded,
+                    format!("operator {owner_id} reached its aircraft quota"),
+                ),
+                status => GrpcError::from((status, Json(vec![]))),
+            })?;
+        let id = repos
+            .aircraft
+            .insert(data)
+            .await
+            .map_err(GrpcError::from)?
+            .id;
+
+        // a vehicle without its owner would not count towards the quota
+        if let Err(e) = reservation.commit(&id) {
+            grpc_error!("could not record the owner of vehicle {id}: {e}");
+            if let Err(e) = repos.aircraft.delete(id.clone()).await {
+                grpc_error!("could not remove vehicle {id} without owner: {e}");
+            }
+            return Err(GrpcError::new(
+                ErrorReason::Internal,
+                "the owner of the vehicle could not be recorded",
+            )
+            .into());
+        }
+
+        Ok(RegisterVehicleResponse { id })
+    }
+
+    /// Change the occupancy of a vertipad on behalf of the scheduler
+    pub async fn set_vertipad_occupancy(
+        &self,
+        request: UpdateVertipadOccupancyRequest,
+    ) -> Result<UpdateVertipadOccupancyResponse, Status> {
+        let repos = self.mutation_repositories()?;
+        let payload = UpdateVertipadPayload {
+            id: request.vertipad_id,
+            vertiport_id: None,
+            name: None,
+            geo_location: None,
+            enabled: None,
+            occupied: Some(request.occupied),
+            occupancy_source: Some(OccupancySource::Scheduler),
+            schedule: None,
+            expected_updated_at: None,
+            expected_occupancy_version: None,
+            mask: vec!["occupied".to_string()],
+        };
+
+        // Only the occupancy changes, the location and schedule are not checked
+        apply_vertipad_update(
+            &repos,
+            &self.shared.occupancy_log,
+            payload,
+            VertipadChecks::default(),
+        )
+        .await
+        .map_err(GrpcError::from)?;
+
+        Ok(UpdateVertipadOccupancyResponse {})
+    }
+
+    /// Set the status of an aircraft
+    pub async fn set_aircraft_status(
+        &self,
+        request: SetAssetStatusRequest,
+    ) -> Result<SetAssetStatusResponse, Status> {
+        let repos = self.mutation_repositories()?;
+        let id = to_uuid(&request.asset_id)
+            .ok_or_else(|| GrpcError::invalid_field("asset_id", "must be a valid UUID"))?
+            .to_string();
+        let status: AssetStatus = grpc_server::AssetStatus::try_from(request.status)
+            .map_err(|_| GrpcError::invalid_field("status", "unknown asset status"))?
+            .into();
+
+        // only aircraft have a status set by other services
+        repos
+            .aircraft
+            .get_by_id(id.clone())
+            .await
+            .map_err(GrpcError::from)?;
+        self.shared
+            .aircraft_statuses
+            .set(&id, status)
+            .map_err(|e| {
+                GrpcError::new(
+                    ErrorReason::Internal,
+                    format!("could not persist the status: {e}"),
+                )
+            })?;
+
+        Ok(SetAssetStatusResponse {})
+    }
+}
 
 #[cfg(not(feature = "stub_server"))]
 #[tonic::async_trait]
@@ -30,25 +384,140 @@ impl RpcService for ServerImpl {
         // only show is_ready calls if log level is debug. This will be called 5times per second by the health checks.
         grpc_debug!("assets server.");
         grpc_debug!("request: {:?}", request);
-        let response = ReadyResponse { ready: true };
+        let ready = self.dependencies_ready().await;
+        if !ready {
+            grpc_warn!("one or more dependencies unavailable.");
+        }
+
+        let response = ReadyResponse { ready };
+        Ok(Response::new(response))
+    }
+
+    /// Returns the windows the assets of a group can be used
+    async fn get_group_availability(
+        &self,
+        request: Request<GroupAvailabilityRequest>,
+    ) -> Result<Response<GroupAvailabilityResponse>, Status> {
+        grpc_info!("assets server.");
+        grpc_debug!("request: {:?}", request);
+        self.group_availability(request.into_inner())
+            .map(Response::new)
+            .map_err(|e| {
+                grpc_warn!("invalid group availability request: {}", e.message());
+                e
+            })
+    }
+
+    /// Returns the weather minimums of a vertiport
+    async fn get_vertiport_weather_minimums(
+        &self,
+        request: Request<VertiportWeatherMinimumsRequest>,
+    ) -> Result<Response<VertiportWeatherMinimumsResponse>, Status> {
+        grpc_info!("assets server.");
+        grpc_debug!("request: {:?}", request);
+        self.vertiport_weather_minimums(request.into_inner())
+            .map(Response::new)
+            .map_err(|e| {
+                grpc_warn!("invalid weather minimums request: {}", e.message());
+                e
+            })
+    }
+
+    /// Registers a new vehicle, returning its id
+    async fn register_vehicle(
+        &self,
+        request: Request<RegisterVehicleRequest>,
+    ) -> Result<Response<RegisterVehicleResponse>, Status> {
+        grpc_info!("assets server.");
+        grpc_debug!("request: {:?}", request);
+        let peer = self
+            .authorization
+            .authorize(Mutation::RegisterVehicle, &request)?;
+        let response = self
+            .insert_vehicle(request.into_inner())
+            .await
+            .map_err(|e| {
+                grpc_warn!("could not register vehicle: {}", e.message());
+                e
+            })?;
+
+        grpc_info!("vehicle {} registered by {}.", response.id, peer);
+        Ok(Response::new(response))
+    }
+
+    /// Changes the occupancy of a vertipad on behalf of the scheduler
+    async fn update_vertipad_occupancy(
+        &self,
+        request: Request<UpdateVertipadOccupancyRequest>,
+    ) -> Result<Response<UpdateVertipadOccupancyResponse>, Status> {
+        grpc_info!("assets server.");
+        grpc_debug!("request: {:?}", request);
+        let peer = self
+            .authorization
+            .authorize(Mutation::UpdateVertipadOccupancy, &request)?;
+        let request = request.into_inner();
+        let vertipad_id = request.vertipad_id.clone();
+        let response = self.set_vertipad_occupancy(request).await.map_err(|e| {
+            grpc_warn!("could not update vertipad occupancy: {}", e.message());
+            e
+        })?;
+
+        grpc_info!("vertipad {} occupancy updated by {}.", vertipad_id, peer);
+        Ok(Response::new(response))
+    }
+
+    /// Sets the status of an aircraft
+    async fn set_asset_status(
+        &self,
+        request: Request<SetAssetStatusRequest>,
+    ) -> Result<Response<SetAssetStatusResponse>, Status> {
+        grpc_info!("assets server.");
+        grpc_debug!("request: {:?}", request);
+        let peer = self
+            .authorization
+            .authorize(Mutation::SetAssetStatus, &request)?;
+        let request = request.into_inner();
+        let asset_id = request.asset_id.clone();
+        let response = self.set_aircraft_status(request).await.map_err(|e| {
+            grpc_warn!("could not set asset status: {}", e.message());
+            e
+        })?;
+
+        grpc_info!("asset {} status set by {}.", asset_id, peer);
         Ok(Response::new(response))
     }
 }
 
 /// Starts the grpc servers for this microservice using the provided configuration
 ///
+/// The [`RestComponents`] are shared with the REST server, so both servers
+/// use the same [`SharedState`], [`GrpcClients`] and operator quotas.
+/// The mutation methods are only allowed for the peers configured per method,
+/// identified by their client certificate when mTLS is configured, see
+/// [`crate::tls`].
+///
 /// # Example:
 /// ```
+/// use svc_assets::config::SharedConfig;
+/// use svc_assets::grpc::client::GrpcClients;
 /// use svc_assets::grpc::server::grpc_server;
+/// use svc_assets::rest::server::RestComponents;
+/// use svc_assets::shared::SharedState;
 /// use svc_assets::Config;
-/// async fn example() -> Result<(), tokio::task::JoinError> {
-///     let config = Config::default();
-///     tokio::spawn(grpc_server(config, None)).await;
+/// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+///     let config = SharedConfig::from(Config::default());
+///     let grpc_clients = GrpcClients::default(config.get());
+///     let components = RestComponents::new(config, SharedState::default(), grpc_clients)?;
+///     tokio::spawn(grpc_server(components, None)).await?;
 ///     Ok(())
 /// }
 /// ```
-pub async fn grpc_server(config: Config, shutdown_rx: Option<tokio::sync::oneshot::Receiver<()>>) {
+pub async fn grpc_server(
+    components: RestComponents,
+    shutdown_rx: Option<tokio::sync::oneshot::Receiver<()>>,
+) {
     grpc_debug!("entry.");
+    let config = components.config.get();
 
     // Grpc Server
     let grpc_port = config.docker_port_grpc;
@@ -60,25 +529,83 @@ pub async fn grpc_server(config: Config, shutdown_rx: Option<tokio::sync::onesho
         }
     };
 
-    let imp = ServerImpl::default();
+    // TLS termination, the certificates are reloaded when the files change
+    let tls = match ServerTls::from_config(&config, Protocol::Http2) {
+        Ok(tls) => tls,
+        Err(e) => {
+            grpc_error!("invalid TLS configuration: {}", e);
+            return;
+        }
+    };
+    let listener = match &tls {
+        None => None,
+        Some(_) => match TcpListener::bind(full_grpc_addr).await {
+            Ok(listener) => Some(listener),
+            Err(e) => {
+                grpc_error!("could not bind {}: {}", full_grpc_addr, e);
+                return;
+            }
+        },
+    };
+
+    let imp = ServerImpl {
+        repositories: Some(components.repositories),
+        grpc_clients: Some(components.grpc_clients),
+        shared: components.shared,
+        operator_quotas: Some(components.operator_quotas),
+        authorization: PeerAuthorization::from(&config),
+        read_only: Some(components.read_only),
+        tenancy: Some(Tenancy::new(
+            components.config.clone(),
+            components.tenant_assets,
+        )),
+    };
     let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
-    health_reporter
-        .set_serving::<RpcServiceServer<ServerImpl>>()
-        .await;
+    health::report(&mut health_reporter, HealthStatuses::default()).await;
+
+    // Keep the health statuses in line with the availability of our dependencies
+    let health_task = tokio::spawn(health::health_monitor(imp.clone(), health_reporter));
+
+    let reflection_service = match tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(grpc_server::FILE_DESCRIPTOR_SET)
+        .build()
+    {
+        Ok(service) => service,
+        Err(e) => {
+            grpc_error!("Failed to build gRPC reflection service: {}", e);
+            health_task.abort();
+            return;
+        }
+    };
 
     //start server
     grpc_info!("Starting gRPC services on: {}.", full_grpc_addr);
-    match Server::builder()
+    let router = Server::builder()
         .add_service(health_service)
-        .add_service(RpcServiceServer::new(imp))
-        .serve_with_shutdown(full_grpc_addr, shutdown_signal("grpc", shutdown_rx))
-        .await
-    {
+        .add_service(reflection_service)
+        .add_service(RpcServiceServer::new(imp));
+    let signal = shutdown_signal("grpc", shutdown_rx);
+    let tls_watch = tls.clone().map(|tls| tokio::spawn(tls.watch()));
+    let result = match (&tls, listener) {
+        (Some(tls), Some(listener)) => {
+            grpc_info!("TLS enabled.");
+            router
+                .serve_with_incoming_shutdown(tls.incoming(listener), signal)
+                .await
+        }
+        _ => router.serve_with_shutdown(full_grpc_addr, signal).await,
+    };
+    match result {
         Ok(_) => grpc_info!("gRPC server running at: {}.", full_grpc_addr),
         Err(e) => {
             grpc_error!("could not start gRPC server: {}", e);
         }
     };
+
+    health_task.abort();
+    if let Some(tls_watch) = tls_watch {
+        tls_watch.abort();
+    }
 }
 
 #[cfg(feature = "stub_server")]
@@ -93,11 +620,68 @@ impl RpcService for ServerImpl {
         let response = ReadyResponse { ready: true };
         Ok(Response::new(response))
     }
+
+    async fn get_group_availability(
+        &self,
+        request: Request<GroupAvailabilityRequest>,
+    ) -> Result<Response<GroupAvailabilityResponse>, Status> {
+        grpc_warn!("(MOCK) assets server.");
+        grpc_debug!("(MOCK) request: {:?}", request);
+        self.group_availability(request.into_inner())
+            .map(Response::new)
+    }
+
+    async fn get_vertiport_weather_minimums(
+        &self,
+        request: Request<VertiportWeatherMinimumsRequest>,
+    ) -> Result<Response<VertiportWeatherMinimumsResponse>, Status> {
+        grpc_warn!("(MOCK) assets server.");
+        grpc_debug!("(MOCK) request: {:?}", request);
+        self.vertiport_weather_minimums(request.into_inner())
+            .map(Response::new)
+    }
+
+    async fn register_vehicle(
+        &self,
+        request: Request<RegisterVehicleRequest>,
+    ) -> Result<Response<RegisterVehicleResponse>, Status> {
+        grpc_warn!("(MOCK) assets server.");
+        grpc_debug!("(MOCK) request: {:?}", request);
+        let response = RegisterVehicleResponse {
+            id: lib_common::uuid::Uuid::new_v4().to_string(),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn update_vertipad_occupancy(
+        &self,
+        request: Request<UpdateVertipadOccupancyRequest>,
+    ) -> Result<Response<UpdateVertipadOccupancyResponse>, Status> {
+        grpc_warn!("(MOCK) assets server.");
+        grpc_debug!("(MOCK) request: {:?}", request);
+        Ok(Response::new(UpdateVertipadOccupancyResponse {}))
+    }
+
+    async fn set_asset_status(
+        &self,
+        request: Request<SetAssetStatusRequest>,
+    ) -> Result<Response<SetAssetStatusResponse>, Status> {
+        grpc_warn!("(MOCK) assets server.");
+        grpc_debug!("(MOCK) request: {:?}", request);
+        Ok(Response::new(SetAssetStatusResponse {}))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::SharedConfig;
+    use crate::repo::tenant::TenantAssets;
+    use crate::rest::api::rest_types::StartupState;
+    use crate::rest::quota::AssetOwners;
+    use crate::weather_minimums::WeatherMinimumsPayload;
+    use crate::Config;
+    use svc_storage_client_grpc::prelude::vertipad;
 
     #[tokio::test]
     async fn test_grpc_server_is_ready() {
@@ -105,6 +689,7 @@ mod tests {
         ut_info!("Start.");
 
         let imp = ServerImpl::default();
+        imp.shared.startup.set(StartupState::Ready);
         let result = imp.is_ready(Request::new(ReadyRequest {})).await;
         assert!(result.is_ok());
         let result: ReadyResponse = result.unwrap().into_inner();
@@ -113,6 +698,448 @@ mod tests {
         ut_info!("Success.");
     }
 
+    #[tokio::test]
+    async fn test_grpc_server_dependencies_ready() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let imp = ServerImpl::default();
+        assert!(!imp.dependencies_ready().await);
+        imp.shared.startup.set(StartupState::Ready);
+        assert!(imp.dependencies_ready().await);
+
+        let imp = ServerImpl {
+            grpc_clients: Some(GrpcClients::default(Config::default())),
+            shared: imp.shared.clone(),
+            ..Default::default()
+        };
+        assert!(imp.dependencies_ready().await);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_grpc_server_group_availability() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let imp = ServerImpl::default();
+        let group_id = lib_common::uuid::Uuid::new_v4().to_string();
+        let from = SystemTime::now();
+        let request = |hours: u64| GroupAvailabilityRequest {
+            group_id: group_id.clone(),
+            from: Some(from.into()),
+            to: Some((from + Duration::from_secs(hours * 3600)).into()),
+        };
+
+        // not constrained without a schedule
+        let response = imp
+            .get_group_availability(Request::new(request(2)))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!response.constrained);
+        assert!(response.available);
+        assert_eq!(response.windows.len(), 1);
+
+        // a schedule in the past never makes the group available
+        imp.shared
+            .group_schedules
+            .set(
+                &group_id,
+                Some("DTSTART:20221020T000000Z;DURATION:PT1H".to_string()),
+            )
+            .unwrap();
+        let response = imp.group_availability(request(2)).unwrap();
+        assert!(response.constrained);
+        assert!(!response.available);
+        assert!(response.windows.is_empty());
+
+        let error = imp.group_availability(request(0)).unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        let error = imp
+            .group_availability(GroupAvailabilityRequest {
+                group_id: "invalid".to_string(),
+                ..request(2)
+            })
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_grpc_server_vertiport_weather_minimums() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let imp = ServerImpl::default();
+        let vertiport_id = lib_common::uuid::Uuid::new_v4().to_string();
+        let request = || VertiportWeatherMinimumsRequest {
+            vertiport_id: vertiport_id.clone(),
+        };
+
+        let response = imp
+            .get_vertiport_weather_minimums(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!response.configured);
+        assert_eq!(response.minimums, None);
+
+        let now = Utc::now();
+        imp.shared
+            .weather_minimums
+            .set(
+                &vertiport_id,
+                WeatherMinimumsPayload {
+                    max_wind_speed_mps: 12.5,
+                    min_visibility_m: 1500.0,
+                    max_precipitation_mm_per_hour: 2.0,
+                },
+                now,
+            )
+            .unwrap();
+        let response = imp.vertiport_weather_minimums(request()).unwrap();
+        assert!(response.configured);
+        let minimums = response.minimums.unwrap();
+        assert_eq!(minimums.max_wind_speed_mps, 12.5);
+        assert_eq!(minimums.min_visibility_m, 1500.0);
+        assert_eq!(minimums.max_precipitation_mm_per_hour, 2.0);
+        assert_eq!(minimums.updated_at, Some(to_timestamp(now)));
+
+        let error = imp
+            .vertiport_weather_minimums(VertiportWeatherMinimumsRequest {
+                vertiport_id: "invalid".to_string(),
+            })
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        ut_info!("Success.");
+    }
+
+    fn mutation_server() -> ServerImpl {
+        let mut config = Config::default();
+        config.max_aircraft_per_operator = 2;
+        ServerImpl {
+            repositories: Some(Repositories::memory()),
+            operator_quotas: Some(OperatorQuotas::new(
+                SharedConfig::from(config),
+                AssetOwners::default(),
+            )),
+            ..Default::default()
+        }
+    }
+
+    #[cfg(not(feature = "stub_server"))]
+    #[tokio::test]
+    async fn test_grpc_server_mutations_unauthenticated() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        // requests without a peer certificate are rejected
+        let imp = mutation_server();
+        let error = imp
+            .set_asset_status(Request::new(SetAssetStatusRequest {
+                asset_id: lib_common::uuid::Uuid::new_v4().to_string(),
+                status: grpc_server::AssetStatus::Emergency.into(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unauthenticated);
+
+        // mutations need storage
+        let error = ServerImpl::default()
+            .set_aircraft_status(SetAssetStatusRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unavailable);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_grpc_server_insert_vehicle() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let imp = mutation_server();
+        let owner_id = lib_common::uuid::Uuid::new_v4().to_string();
+        let mut request = RegisterVehicleRequest {
+            vehicle_model_id: lib_common::uuid::Uuid::new_v4().to_string(),
+            serial_number: "SN-1".to_string(),
+            registration_number: "N12345".to_string(),
+            last_maintenance: Some(SystemTime::now().into()),
+            owner_id: owner_id.clone(),
+            ..Default::default()
+        };
+
+        let response = imp.insert_vehicle(request.clone()).await.unwrap();
+        let stored = imp
+            .repositories()
+            .unwrap()
+            .aircraft
+            .get_by_id(response.id.clone())
+            .await
+            .unwrap();
+        assert_eq!(stored.data.unwrap().registration_number, "N12345");
+
+        // the owner is recorded like for REST registrations
+        let quotas = imp.operator_quotas().unwrap();
+        assert_eq!(
+            quotas.owner(QuotaKind::Aircraft, &response.id),
+            Some(owner_id.clone())
+        );
+
+        // an owner is required
+        let error = imp
+            .insert_vehicle(RegisterVehicleRequest {
+                owner_id: String::new(),
+                registration_number: "N00001".to_string(),
+                ..request.clone()
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+        assert!(error.message().contains("owner_id"));
+
+        // the registrations count towards the quota of the owner
+        imp.insert_vehicle(RegisterVehicleRequest {
+            registration_number: "N00002".to_string(),
+            ..request.clone()
+        })
+        .await
+        .unwrap();
+        let error = imp
+            .insert_vehicle(RegisterVehicleRequest {
+                registration_number: "N00003".to_string(),
+                ..request.clone()
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::ResourceExhausted);
+
+        // vehicles can not be registered without the quotas
+        let error = ServerImpl {
+            operator_quotas: None,
+            ..imp.clone()
+        }
+        .insert_vehicle(RegisterVehicleRequest {
+            registration_number: "N00004".to_string(),
+            ..request.clone()
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Internal);
+
+        // registration numbers are unique
+        let error = imp.insert_vehicle(request.clone()).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        request.registration_number = "N54321".to_string();
+        request.vehicle_model_id = "invalid".to_string();
+        let error = imp.insert_vehicle(request).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+        assert!(error.message().contains("vehicle_model_id"));
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_grpc_server_set_vertipad_occupancy() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let imp = mutation_server();
+        let mut data = vertipad::mock::get_data_obj();
+        data.occupied = false;
+        let id = imp
+            .repositories()
+            .unwrap()
+            .vertipads
+            .insert(data)
+            .await
+            .unwrap()
+            .id;
+
+        imp.set_vertipad_occupancy(UpdateVertipadOccupancyRequest {
+            vertipad_id: id.clone(),
+            occupied: true,
+        })
+        .await
+        .unwrap();
+        let stored = imp
+            .repositories()
+            .unwrap()
+            .vertipads
+            .get_by_id(id.clone())
+            .await
+            .unwrap();
+        assert!(stored.data.unwrap().occupied);
+        assert!(imp.shared.occupancy_log.is_reserved(&id));
+
+        let error = imp
+            .set_vertipad_occupancy(UpdateVertipadOccupancyRequest {
+                vertipad_id: "invalid".to_string(),
+                occupied: true,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_grpc_server_set_aircraft_status() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let imp = mutation_server();
+        let id = imp
+            .repositories()
+            .unwrap()
+            .aircraft
+            .insert(vehicle::mock::get_data_obj())
+            .await
+            .unwrap()
+            .id;
+        let request = |asset_id: &str, status: i32| SetAssetStatusRequest {
+            asset_id: asset_id.to_string(),
+            status,
+        };
+
+        imp.set_aircraft_status(request(&id, grpc_server::AssetStatus::Emergency.into()))
+            .await
+            .unwrap();
+        assert_eq!(
+            imp.shared.aircraft_statuses.get(&id),
+            Some(AssetStatus::Emergency)
+        );
+
+        let error = imp.set_aircraft_status(request(&id, 42)).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        let unknown = lib_common::uuid::Uuid::new_v4().to_string();
+        let error = imp
+            .set_aircraft_status(request(
+                &unknown,
+                grpc_server::AssetStatus::Unavailable.into(),
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::NotFound);
+        assert_eq!(imp.shared.aircraft_statuses.get(&unknown), None);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_grpc_server_mutations_read_only() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let config = SharedConfig::from(Config::default());
+        let read_only = ReadOnlyMode::new(config);
+        let imp = ServerImpl {
+            read_only: Some(read_only.clone()),
+            ..mutation_server()
+        };
+        let id = imp
+            .repositories()
+            .unwrap()
+            .aircraft
+            .insert(vehicle::mock::get_data_obj())
+            .await
+            .unwrap()
+            .id;
+        let request = SetAssetStatusRequest {
+            asset_id: id.clone(),
+            status: grpc_server::AssetStatus::Unavailable.into(),
+        };
+
+        imp.set_aircraft_status(request.clone()).await.unwrap();
+
+        // changes are rejected like through REST
+        read_only.set(true);
+        let error = imp
+            .set_aircraft_status(SetAssetStatusRequest {
+                status: grpc_server::AssetStatus::Emergency.into(),
+                ..request
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unavailable);
+        assert_eq!(
+            imp.shared.aircraft_statuses.get(&id),
+            Some(AssetStatus::Unavailable)
+        );
+        let error = imp
+            .set_vertipad_occupancy(UpdateVertipadOccupancyRequest {
+                vertipad_id: lib_common::uuid::Uuid::new_v4().to_string(),
+                occupied: true,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unavailable);
+        let error = imp
+            .insert_vehicle(RegisterVehicleRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unavailable);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_grpc_server_mutations_tenant() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let mut config = Config::default();
+        config.tenants = vec!["pilot".to_string()];
+        let assets = TenantAssets::default();
+        let imp = ServerImpl {
+            tenancy: Some(Tenancy::new(config.into(), assets.clone())),
+            ..mutation_server()
+        };
+        let repos = imp.repositories().unwrap().clone();
+        let pilot_id = repos
+            .for_tenant(Some("pilot".to_string()), assets.clone())
+            .aircraft
+            .insert(vehicle::mock::get_data_obj())
+            .await
+            .unwrap()
+            .id;
+
+        // the assets of other tenants are not found
+        let error = imp
+            .set_aircraft_status(SetAssetStatusRequest {
+                asset_id: pilot_id.clone(),
+                status: grpc_server::AssetStatus::Emergency.into(),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::NotFound);
+        assert_eq!(imp.shared.aircraft_statuses.get(&pilot_id), None);
+
+        // registered vehicles belong to the default tenant
+        let response = imp
+            .insert_vehicle(RegisterVehicleRequest {
+                vehicle_model_id: lib_common::uuid::Uuid::new_v4().to_string(),
+                serial_number: "SN-1".to_string(),
+                registration_number: "N12345".to_string(),
+                owner_id: lib_common::uuid::Uuid::new_v4().to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(assets.belongs_to(&response.id, None));
+
+        ut_info!("Success.");
+    }
+
     #[tokio::test]
     async fn test_grpc_server_start_and_shutdown() {
         use tokio::time::{sleep, Duration};
@@ -124,7 +1151,13 @@ mod tests {
         let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
 
         // Start the grpc server
-        tokio::spawn(grpc_server(config, Some(shutdown_rx)));
+        let components = RestComponents::new(
+            SharedConfig::from(config.clone()),
+            SharedState::default(),
+            GrpcClients::default(config),
+        )
+        .unwrap();
+        tokio::spawn(grpc_server(components, Some(shutdown_rx)));
 
         // Give the server time to get through the startup sequence (and thus code)
         sleep(Duration::from_secs(1)).await;
@@ -0,0 +1,257 @@
+//! # Maintenance Holds
+//!
+//! Time windows an aircraft is held for maintenance and can not be
+//! scheduled, so maintenance crews no longer have to coordinate with
+//! dispatch out of band.
+//!
+//! svc-storage does not store holds, so [`MaintenanceHolds`] keeps them in
+//! the [`MAINTENANCE_HOLDS_TABLE`] of the persisted state, see
+//! [`crate::state`]. The holds survive a restart, but are only seen by the
+//! instance which recorded them. Holds which ended are dropped from the
+//! table when another hold is added to the aircraft. The REST API reports an
+//! aircraft with an active hold as
+//! [`Unavailable`](crate::rest::structs::AssetStatus::Unavailable).
+
+pub use crate::rest::{MaintenanceHold, MaintenanceHoldPayload};
+
+use crate::state::{StateError, StateTable};
+use chrono::{DateTime, Duration, Utc};
+use lib_common::uuid::Uuid;
+use std::fmt::{self, Display, Formatter};
+
+/// Maximum duration, in days, of a single hold
+pub const MAX_HOLD_DURATION_DAYS: i64 = 90;
+
+/// Name of the table of the holds in the state directory
+pub const MAINTENANCE_HOLDS_TABLE: &str = "maintenance_holds";
+
+/// Errors returned when changing the holds of an aircraft
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HoldError {
+    /// The end is not after the start, or the hold is too long
+    InvalidRange,
+    /// The hold ends in the past
+    Ended,
+    /// The hold overlaps the hold with the provided id
+    Overlap(String),
+    /// The hold does not exist
+    NotFound,
+    /// The holds could not be persisted
+    State(StateError),
+}
+
+impl Display for HoldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HoldError::InvalidRange => write!(
+                f,
+                "'end' must be after 'start' and the hold can not exceed {} days",
+                MAX_HOLD_DURATION_DAYS
+            ),
+            HoldError::Ended => write!(f, "hold ends in the past"),
+            HoldError::Overlap(id) => write!(f, "hold overlaps hold {}", id),
+            HoldError::NotFound => write!(f, "hold not found"),
+            HoldError::State(e) => write!(f, "could not persist holds: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HoldError {}
+
+/// Store of the maintenance holds per aircraft id.
+///
+/// The default store is kept in memory, [`MaintenanceHolds::open`] persists
+/// the holds.
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceHolds {
+    holds: StateTable<Vec<MaintenanceHold>>,
+}
+
+impl MaintenanceHolds {
+    /// Open the holds persisted in the state directory, kept in memory if no
+    /// directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            holds: StateTable::open(dir, MAINTENANCE_HOLDS_TABLE)?,
+        })
+    }
+
+    /// Hold an aircraft for maintenance.
+    ///
+    /// Holds which ended before `now` are dropped, a new hold can not
+    /// overlap any of the remaining holds of the aircraft.
+    pub fn add(
+        &self,
+        aircraft_id: &str,
+        payload: MaintenanceHoldPayload,
+        now: DateTime<Utc>,
+    ) -> Result<MaintenanceHold, HoldError> {
+        if payload.end <= payload.start
+            || payload.end - payload.start > Duration::days(MAX_HOLD_DURATION_DAYS)
+        {
+            return Err(HoldError::InvalidRange);
+        }
+
+        if payload.end <= now {
+            return Err(HoldError::Ended);
+        }
+
+        if let Some(hold) = self
+            .list(aircraft_id, now)
+            .into_iter()
+            .find(|hold| hold.start < payload.end && payload.start < hold.end)
+        {
+            return Err(HoldError::Overlap(hold.id));
+        }
+
+        let hold = MaintenanceHold {
+            id: Uuid::new_v4().to_string(),
+            aircraft_id: aircraft_id.to_string(),
+            start: payload.start,
+            end: payload.end,
+            reason: payload.reason,
+            created_at: now,
+        };
+        self.holds
+            .update(|entries| {
+                // a concurrent hold may have been added since the check
+                let holds = entries.entry(aircraft_id.to_string()).or_default();
+                holds.retain(|hold| hold.end > now);
+                if let Some(other) = holds
+                    .iter()
+                    .find(|other| other.start < hold.end && hold.start < other.end)
+                {
+                    return Err(HoldError::Overlap(other.id.clone()));
+                }
+
+                holds.push(hold.clone());
+                holds.sort_by_key(|hold| hold.start);
+                Ok(hold)
+            })
+            .map_err(HoldError::State)?
+    }
+
+    /// Get the holds of an aircraft ending after `now`, ordered by start
+    pub fn list(&self, aircraft_id: &str, now: DateTime<Utc>) -> Vec<MaintenanceHold> {
+        self.holds
+            .get(aircraft_id)
+            .map(|holds| holds.into_iter().filter(|hold| hold.end > now).collect())
+            .unwrap_or_default()
+    }
+
+    /// Cancel a hold of an aircraft, returning the cancelled hold
+    pub fn cancel(&self, aircraft_id: &str, hold_id: &str) -> Result<MaintenanceHold, HoldError> {
+        let found = self.holds.read(|entries| {
+            entries
+                .get(aircraft_id)
+                .map_or(false, |holds| holds.iter().any(|hold| hold.id == hold_id))
+        });
+        if !found {
+            return Err(HoldError::NotFound);
+        }
+
+        self.holds
+            .update(|entries| {
+                let holds = entries.get_mut(aircraft_id).ok_or(HoldError::NotFound)?;
+                let index = holds
+                    .iter()
+                    .position(|hold| hold.id == hold_id)
+                    .ok_or(HoldError::NotFound)?;
+                let hold = holds.remove(index);
+                if holds.is_empty() {
+                    entries.remove(aircraft_id);
+                }
+                Ok(hold)
+            })
+            .map_err(HoldError::State)?
+    }
+
+    /// Check if the aircraft is held for maintenance at the provided time
+    pub fn is_held(&self, aircraft_id: &str, at: DateTime<Utc>) -> bool {
+        self.holds.read(|entries| {
+            entries
+                .get(aircraft_id)
+                .map(|holds| holds.iter().any(|hold| hold.start <= at && at < hold.end))
+                .unwrap_or(false)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(now: DateTime<Utc>, start: i64, end: i64) -> MaintenanceHoldPayload {
+        MaintenanceHoldPayload {
+            start: now + Duration::hours(start),
+            end: now + Duration::hours(end),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_maintenance_holds() {
+        let holds = MaintenanceHolds::default();
+        let id = "aircraft";
+        let now = Utc::now();
+
+        assert_eq!(
+            holds.add(id, payload(now, 2, 1), now),
+            Err(HoldError::InvalidRange)
+        );
+        assert_eq!(
+            holds.add(id, payload(now, 0, MAX_HOLD_DURATION_DAYS * 24 + 1), now),
+            Err(HoldError::InvalidRange)
+        );
+        assert_eq!(
+            holds.add(id, payload(now, -2, -1), now),
+            Err(HoldError::Ended)
+        );
+
+        let active = holds.add(id, payload(now, -1, 1), now).unwrap();
+        let planned = holds.add(id, payload(now, 4, 6), now).unwrap();
+        assert_eq!(
+            holds.add(id, payload(now, 5, 8), now),
+            Err(HoldError::Overlap(planned.id.clone()))
+        );
+        // adjacent holds do not overlap
+        holds.add(id, payload(now, 1, 2), now).unwrap();
+
+        assert!(holds.is_held(id, now));
+        assert!(!holds.is_held(id, now + Duration::hours(3)));
+        assert!(holds.is_held(id, now + Duration::hours(4)));
+        assert!(!holds.is_held("other", now));
+
+        let list = holds.list(id, now);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[0], active);
+        assert_eq!(
+            holds.list(id, now + Duration::hours(5)),
+            vec![planned.clone()]
+        );
+
+        assert_eq!(holds.cancel(id, &active.id), Ok(active.clone()));
+        assert_eq!(holds.cancel(id, &active.id), Err(HoldError::NotFound));
+        assert_eq!(holds.cancel("other", &planned.id), Err(HoldError::NotFound));
+        assert!(!holds.is_held(id, now));
+    }
+
+    #[test]
+    fn test_maintenance_holds_persisted() {
+        let dir = crate::state::test_state_dir();
+        let id = "aircraft";
+        let now = Utc::now();
+
+        let holds = MaintenanceHolds::open(Some(&dir)).unwrap();
+        let active = holds.add(id, payload(now, -1, 1), now).unwrap();
+        let cancelled = holds.add(id, payload(now, 2, 3), now).unwrap();
+        holds.cancel(id, &cancelled.id).unwrap();
+
+        // the holds are read back after a restart
+        let holds = MaintenanceHolds::open(Some(&dir)).unwrap();
+        assert!(holds.is_held(id, now));
+        assert_eq!(holds.list(id, now), vec![active]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,213 @@
+//! # Storage Latency
+//!
+//! Rolling latency percentiles of the svc-storage calls, per resource.
+//!
+//! The repositories backed by svc-storage record the duration of each call.
+//! The health check reports the percentiles of the most recent calls and
+//! flags the service as degraded when a percentile exceeds its configured
+//! service level objective (SLO), so a slow svc-storage is noticed before
+//! calls start to time out.
+
+pub use crate::rest::api::rest_types::StorageLatency;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::{Duration, Instant};
+use tonic::Status;
+
+/// Number of most recent calls per resource the percentiles are computed
+/// over
+pub const LATENCY_WINDOW: usize = 1000;
+
+/// Latency objectives of the svc-storage calls, `None` if disabled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LatencySlo {
+    /// Maximum 95th percentile
+    pub p95: Option<Duration>,
+    /// Maximum 99th percentile
+    pub p99: Option<Duration>,
+}
+
+impl LatencySlo {
+    /// Get the objectives from the configured milliseconds, `0` disables an
+    /// objective
+    pub fn from_ms(p95_ms: u64, p99_ms: u64) -> Self {
+        let slo = |ms| match ms {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        };
+
+        Self {
+            p95: slo(p95_ms),
+            p99: slo(p99_ms),
+        }
+    }
+}
+
+/// Get the percentile of sorted durations, using the nearest rank
+fn percentile(sorted: &[Duration], percentile: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+
+    let rank = ((sorted.len() * percentile + 99) / 100).max(1);
+    sorted[rank - 1]
+}
+
+/// Convert a duration to fractional milliseconds
+fn as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Recent call durations per resource, shared between clones
+#[derive(Debug, Clone, Default)]
+pub struct StorageLatencies {
+    samples: Arc<RwLock<BTreeMap<String, VecDeque<Duration>>>>,
+}
+
+impl StorageLatencies {
+    fn read(&self) -> RwLockReadGuard<'_, BTreeMap<String, VecDeque<Duration>>> {
+        self.samples
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, BTreeMap<String, VecDeque<Duration>>> {
+        self.samples
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Record the duration of a call, dropping the oldest duration of the
+    /// resource once the window is full
+    pub fn record(&self, resource: &str, duration: Duration) {
+        let mut samples = self.write();
+        let durations = samples.entry(resource.to_string()).or_default();
+        if durations.len() == LATENCY_WINDOW {
+            durations.pop_front();
+        }
+        durations.push_back(duration);
+    }
+
+    /// Run a call, recording its duration for the resource.
+    ///
+    /// Failed calls are recorded as well, a call failing slowly is as
+    /// noticeable as a slow success.
+    pub async fn time<T>(
+        &self,
+        resource: &str,
+        call: impl std::future::Future<Output = Result<T, Status>>,
+    ) -> Result<T, Status> {
+        let start = Instant::now();
+        let result = call.await;
+        self.record(resource, start.elapsed());
+        result
+    }
+
+    /// Get the latency percentiles of the resources with recorded calls,
+    /// ordered by resource
+    pub fn report(&self, slo: LatencySlo) -> Vec<StorageLatency> {
+        self.read()
+            .iter()
+            .map(|(resource, durations)| {
+                let mut sorted: Vec<Duration> = durations.iter().copied().collect();
+                sorted.sort_unstable();
+
+                let (p50, p95, p99) = (
+                    percentile(&sorted, 50),
+                    percentile(&sorted, 95),
+                    percentile(&sorted, 99),
+                );
+                let slo_exceeded = slo.p95.map_or(false, |max| p95 > max)
+                    || slo.p99.map_or(false, |max| p99 > max);
+
+                StorageLatency {
+                    resource: resource.clone(),
+                    samples: sorted.len(),
+                    p50_ms: as_ms(p50),
+                    p95_ms: as_ms(p95),
+                    p99_ms: as_ms(p99),
+                    slo_exceeded,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&sorted, 50), Duration::from_millis(50));
+        assert_eq!(percentile(&sorted, 95), Duration::from_millis(95));
+        assert_eq!(percentile(&sorted, 99), Duration::from_millis(99));
+        assert_eq!(percentile(&sorted[..1], 99), Duration::from_millis(1));
+        assert_eq!(percentile(&[], 50), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_latency_slo_from_ms() {
+        assert_eq!(LatencySlo::from_ms(0, 0), LatencySlo::default());
+        assert_eq!(
+            LatencySlo::from_ms(500, 0),
+            LatencySlo {
+                p95: Some(Duration::from_millis(500)),
+                p99: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_storage_latencies_report() {
+        let latencies = StorageLatencies::default();
+        assert!(latencies.report(LatencySlo::default()).is_empty());
+
+        for ms in 1..=100 {
+            latencies.record("vehicle", Duration::from_millis(ms));
+            latencies.record("vertipad", Duration::from_millis(1));
+        }
+
+        let slo = LatencySlo::from_ms(90, 0);
+        let report = latencies.report(slo);
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].resource, "vehicle");
+        assert_eq!(report[0].samples, 100);
+        assert_eq!(report[0].p50_ms, 50.0);
+        assert_eq!(report[0].p95_ms, 95.0);
+        assert!(report[0].slo_exceeded);
+        assert_eq!(report[1].resource, "vertipad");
+        assert!(!report[1].slo_exceeded);
+
+        // disabled objectives are never exceeded
+        assert!(!latencies.report(LatencySlo::default())[0].slo_exceeded);
+    }
+
+    #[test]
+    fn test_storage_latencies_window() {
+        let latencies = StorageLatencies::default();
+        for _ in 0..LATENCY_WINDOW {
+            latencies.record("vehicle", Duration::from_secs(1));
+        }
+        for _ in 0..LATENCY_WINDOW {
+            latencies.record("vehicle", Duration::from_millis(1));
+        }
+
+        // the slow calls left the window
+        let report = latencies.report(LatencySlo::default());
+        assert_eq!(report[0].samples, LATENCY_WINDOW);
+        assert_eq!(report[0].p99_ms, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_storage_latencies_time() {
+        let latencies = StorageLatencies::default();
+        let result = latencies
+            .time("vertiport", async { Err::<(), _>(Status::internal("")) })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(latencies.report(LatencySlo::default())[0].samples, 1);
+    }
+}
@@ -4,8 +4,36 @@
 #[macro_use]
 pub mod test_util;
 
+pub mod aircraft_status;
+pub mod archive;
+pub mod calendar;
 pub mod config;
+pub mod daylight;
+pub mod delegation;
+pub mod fan_out;
+pub mod features;
+pub mod geo;
+pub mod group_hierarchy;
+pub mod group_schedule;
 pub mod grpc;
+pub mod hold;
+pub mod latency;
+pub mod maintenance;
+pub mod object_store;
+pub mod ops_status;
+pub mod repo;
+pub mod saga;
+pub mod schedule;
+pub mod seed;
+pub mod shared;
+pub mod snapshot;
+pub mod startup;
+pub mod state;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod tls;
+pub mod typeahead;
+pub mod weather_minimums;
 
 pub use crate::config::Config;
 
@@ -19,6 +47,15 @@ pub struct Cli {
     /// Target file to write the OpenAPI Spec
     #[arg(long)]
     pub openapi: Option<String>,
+
+    /// Prepare the OpenAPI Spec for the client SDK generator of a language,
+    /// see [`rest::sdk`]
+    #[arg(long, value_enum, requires = "openapi")]
+    pub sdk: Option<rest::sdk::SdkLanguage>,
+
+    /// Manifest file of assets to register at startup, see [`seed`]
+    #[arg(long)]
+    pub seed: Option<String>,
 }
 
 /// Tokio signal handler that will wait for a user to press CTRL+C.
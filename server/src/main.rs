@@ -3,7 +3,8 @@
 use grpc::server::grpc_server;
 use lib_common::logger::load_logger_config_from_file;
 use log::info;
-use rest::{generate_openapi_spec, server::rest_server, ApiDoc};
+use rest::{generate_openapi_spec, sdk::generate_sdk_spec, server::rest_server, ApiDoc};
+use std::path::Path;
 use svc_assets::*;
 
 #[tokio::main]
@@ -22,17 +23,72 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("(main) Server startup.");
 
     // Allow option to only generate the spec file to a given location
-    // use `make rust-openapi` to generate the OpenAPI specification
+    // use `make rust-openapi` to generate the OpenAPI specification, add
+    // `--sdk <language>` to prepare it for a client SDK generator
     let args = Cli::parse();
     if let Some(target) = args.openapi {
-        return generate_openapi_spec::<ApiDoc>(&target).map_err(|e| e.into());
+        return match args.sdk {
+            Some(language) => generate_sdk_spec::<ApiDoc>(&target, language),
+            None => generate_openapi_spec::<ApiDoc>(&target),
+        }
+        .map_err(|e| e.into());
     }
 
+    // Read the seed manifest before starting the servers, so an invalid
+    // manifest is reported right away
+    let seed_manifest = args
+        .seed
+        .map(|path| svc_assets::seed::read_manifest(Path::new(&path)))
+        .transpose()?;
+
+    // Allow reloading parts of the configuration at runtime
+    let shared_config = svc_assets::config::SharedConfig::from(config.clone());
+    #[cfg(unix)]
+    tokio::spawn(svc_assets::config::reload_on_sighup(shared_config.clone()));
+
+    // Asset group schedules, vertipad occupancy and aircraft statuses are
+    // changed through both servers
+    let shared = svc_assets::shared::SharedState::open(config.state_dir.as_deref())
+        .map_err(|e| format!("could not open the persisted state: {}", e))?;
+
+    // The svc-storage connections are shared by both servers
+    let grpc_clients = svc_assets::grpc::client::GrpcClients::default(config.clone());
+
+    // The persisted state and the operator quotas are shared by both servers
+    let components = svc_assets::rest::server::RestComponents::new(
+        shared_config.clone(),
+        shared.clone(),
+        grpc_clients.clone(),
+    )
+    .map_err(|e| format!("could not open the persisted state: {}", e))?;
+
     // REST Server
-    tokio::spawn(rest_server(config.clone(), None));
+    tokio::spawn(rest_server(components.clone(), None));
 
     // GRPC Server
-    tokio::spawn(grpc_server(config, None)).await?;
+    let startup = shared.startup.clone();
+    let startup_settings = svc_assets::startup::StartupSettings::from(&config);
+    let grpc = tokio::spawn(grpc_server(components, None));
+
+    // Both servers report ready once svc-storage is ready, exit if it does
+    // not become ready in time so the service is restarted
+    match svc_assets::startup::wait_for_storage(&startup_settings, &startup, &grpc_clients).await {
+        Ok(attempts) => info!("(main) svc-storage ready after {} checks.", attempts),
+        Err(e) => {
+            log::error!("(main) startup failed: {}", e);
+            log::logger().flush();
+            return Err(e.into());
+        }
+    }
+
+    // Register the missing assets of the seed manifest
+    if let Some(manifest) = seed_manifest {
+        let repos = svc_assets::repo::Repositories::grpc(&grpc_clients);
+        let checks = svc_assets::rest::api::vertipad::VertipadChecks::from_config(&shared_config);
+        svc_assets::seed::seed(&repos, &manifest, checks).await;
+    }
+
+    grpc.await?;
 
     info!("(main) Server shutdown.");
 
@@ -0,0 +1,233 @@
+//! # Maintenance
+//!
+//! Periodic check for aircraft with an overdue maintenance.
+//!
+//! svc-storage does not store a status for aircraft, so the aircraft with a
+//! `next_maintenance` in the past are kept in memory by [`OverdueAircraft`].
+//! The REST API reports these aircraft as
+//! [`Unavailable`](crate::rest::structs::AssetStatus::Unavailable). The set
+//! is rebuilt from svc-storage by each check, so nothing is lost on restart
+//! beyond the first check, which runs when the monitor starts. Each replica
+//! runs its own checks and may briefly disagree with the others.
+
+use crate::config::SharedConfig;
+use crate::repo::Repositories;
+use lib_common::time::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use svc_storage_client_grpc::prelude::vehicle;
+use svc_storage_client_grpc::prelude::*;
+use tokio::time::{interval_at, Duration, Instant, MissedTickBehavior};
+
+/// Interval at which a disabled check re-reads its configuration
+const DISABLED_RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// In memory store of the aircraft with an overdue maintenance
+#[derive(Debug, Clone, Default)]
+pub struct OverdueAircraft {
+    ids: Arc<RwLock<HashSet<String>>>,
+}
+
+impl OverdueAircraft {
+    /// Check if the maintenance of the aircraft is overdue
+    pub fn contains(&self, aircraft_id: &str) -> bool {
+        match self.ids.read() {
+            Ok(ids) => ids.contains(aircraft_id),
+            Err(poisoned) => poisoned.into_inner().contains(aircraft_id),
+        }
+    }
+
+    /// Replace the overdue aircraft, returning the ids which were not overdue before
+    fn replace(&self, overdue: HashSet<String>) -> Vec<String> {
+        let mut ids = match self.ids.write() {
+            Ok(ids) => ids,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let mut new: Vec<String> = overdue.difference(&ids).cloned().collect();
+        new.sort();
+        *ids = overdue;
+        new
+    }
+}
+
+/// Result of a maintenance check
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaintenanceSummary {
+    /// Number of aircraft checked
+    pub checked: usize,
+    /// Number of aircraft with an overdue maintenance
+    pub overdue: usize,
+    /// Aircraft which became overdue since the previous check
+    pub new: Vec<String>,
+}
+
+/// Check if the next maintenance of the aircraft is in the past
+pub fn is_overdue(data: &vehicle::Data, now: DateTime<Utc>) -> bool {
+    data.next_maintenance
+        .clone()
+        .map(|next| DateTime::<Utc>::from(next) < now)
+        .unwrap_or(false)
+}
+
+/// Scan all aircraft for an overdue maintenance and update the [`OverdueAircraft`].
+pub async fn check_maintenance(
+    repos: &Repositories,
+    overdue: &OverdueAircraft,
+    now: DateTime<Utc>,
+) -> Result<MaintenanceSummary, tonic::Status> {
+    let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+    let aircraft = repos.aircraft.search(filter).await?;
+
+    let ids: HashSet<String> = aircraft
+        .iter()
+        .filter(|object| {
+            object
+                .data
+                .as_ref()
+                .map(|data| is_overdue(data, now))
+                .unwrap_or(false)
+        })
+        .map(|object| object.id.clone())
+        .collect();
+
+    let count = ids.len();
+    Ok(MaintenanceSummary {
+        checked: aircraft.len(),
+        overdue: count,
+        new: overdue.replace(ids),
+    })
+}
+
+/// Get the configured interval between maintenance checks, `None` if disabled
+fn check_interval(config: &SharedConfig) -> Option<Duration> {
+    match config.read(|config| config.maintenance_check_interval_secs) {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+/// Run the maintenance check at the configured interval.
+///
+/// The interval is read from the [`SharedConfig`], so it can be changed
+/// (or the check disabled) at runtime.
+pub async fn maintenance_monitor(
+    config: SharedConfig,
+    repos: Repositories,
+    overdue: OverdueAircraft,
+) {
+    log::info!("(maintenance_monitor) start.");
+
+    let mut current = check_interval(&config);
+    let mut ticker = interval_at(Instant::now(), current.unwrap_or(DISABLED_RECHECK_INTERVAL));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+
+        let configured = check_interval(&config);
+        if configured != current {
+            log::info!(
+                "(maintenance_monitor) check interval changed to {:?}.",
+                configured
+            );
+            current = configured;
+            let period = current.unwrap_or(DISABLED_RECHECK_INTERVAL);
+            ticker = interval_at(Instant::now() + period, period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        }
+
+        if current.is_none() {
+            continue;
+        }
+
+        match check_maintenance(&repos, &overdue, Utc::now()).await {
+            Ok(summary) => {
+                for id in &summary.new {
+                    log::warn!(
+                        "(maintenance_monitor) maintenance of aircraft {} is overdue, marked unavailable.",
+                        id
+                    );
+                }
+                log::info!(
+                    "(maintenance_monitor) checked {} aircraft, {} overdue ({} new).",
+                    summary.checked,
+                    summary.overdue,
+                    summary.new.len()
+                );
+            }
+            Err(e) => log::error!("(maintenance_monitor) could not check aircraft: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn data(next_maintenance: Option<DateTime<Utc>>) -> vehicle::Data {
+        vehicle::Data {
+            next_maintenance: next_maintenance.map(|date| date.into()),
+            ..vehicle::mock::get_data_obj()
+        }
+    }
+
+    #[test]
+    fn test_is_overdue() {
+        let now = Utc::now();
+        assert!(is_overdue(&data(Some(now - Duration::hours(1))), now));
+        assert!(!is_overdue(&data(Some(now + Duration::hours(1))), now));
+        assert!(!is_overdue(&data(None), now));
+    }
+
+    #[tokio::test]
+    async fn test_check_maintenance() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let now = Utc::now();
+        let repos = Repositories::memory();
+        let overdue_id = repos
+            .aircraft
+            .insert(data(Some(now - Duration::days(1))))
+            .await
+            .unwrap()
+            .id;
+        repos
+            .aircraft
+            .insert(data(Some(now + Duration::days(1))))
+            .await
+            .unwrap();
+
+        let overdue = OverdueAircraft::default();
+        let summary = check_maintenance(&repos, &overdue, now).await.unwrap();
+        assert_eq!(
+            summary,
+            MaintenanceSummary {
+                checked: 2,
+                overdue: 1,
+                new: vec![overdue_id.clone()],
+            }
+        );
+        assert!(overdue.contains(&overdue_id));
+
+        // already known overdue aircraft are not reported again
+        let summary = check_maintenance(&repos, &overdue, now).await.unwrap();
+        assert!(summary.new.is_empty());
+
+        // all aircraft are overdue later on
+        let summary = check_maintenance(&repos, &overdue, now + Duration::days(2))
+            .await
+            .unwrap();
+        assert_eq!(summary.overdue, 2);
+        assert_eq!(summary.new.len(), 1);
+
+        let error = check_maintenance(&Repositories::unavailable(), &overdue, now)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unavailable);
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,80 @@
+//! In memory object store
+//!
+//! Used if no object store endpoint is configured. Objects are lost when the
+//! service is restarted.
+
+use super::{ObjectStore, ObjectStoreError};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// [`ObjectStore`] keeping the objects in memory
+#[derive(Debug, Clone, Default)]
+pub struct MemoryObjectStore {
+    objects: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+}
+
+impl MemoryObjectStore {
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Vec<u8>>> {
+        self.objects
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, Vec<u8>>> {
+        self.objects
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[tonic::async_trait]
+impl ObjectStore for MemoryObjectStore {
+    async fn put(
+        &self,
+        key: &str,
+        _content_type: &str,
+        body: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        self.write().insert(key.to_string(), body);
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        self.read()
+            .get(key)
+            .cloned()
+            .ok_or(ObjectStoreError::NotFound)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), ObjectStoreError> {
+        self.write()
+            .remove(key)
+            .map(|_| ())
+            .ok_or(ObjectStoreError::NotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_object_store() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let store = MemoryObjectStore::default();
+        assert_eq!(store.get("a/b").await, Err(ObjectStoreError::NotFound));
+
+        store.put("a/b", "image/png", vec![1, 2, 3]).await.unwrap();
+        assert_eq!(store.get("a/b").await.unwrap(), vec![1, 2, 3]);
+
+        store.put("a/b", "image/png", vec![4]).await.unwrap();
+        assert_eq!(store.get("a/b").await.unwrap(), vec![4]);
+
+        store.delete("a/b").await.unwrap();
+        assert_eq!(store.delete("a/b").await, Err(ObjectStoreError::NotFound));
+
+        ut_info!("success");
+    }
+}
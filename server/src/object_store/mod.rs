@@ -0,0 +1,110 @@
+//! Object stores
+//! provides storage of binary objects, like the files attached to assets
+//!
+//! Objects are stored in an S3-compatible object store if
+//! `object_store_endpoint` is configured (see [`s3`]), in memory otherwise
+//! (see [`memory`]).
+
+pub mod memory;
+pub mod s3;
+
+use crate::config::Config;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Errors returned by an [`ObjectStore`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectStoreError {
+    /// The object does not exist
+    NotFound,
+    /// The object store could not be reached or returned an error
+    Unavailable(String),
+}
+
+impl std::error::Error for ObjectStoreError {}
+
+impl Display for ObjectStoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectStoreError::NotFound => write!(f, "object not found"),
+            ObjectStoreError::Unavailable(e) => write!(f, "object store unavailable: {e}"),
+        }
+    }
+}
+
+/// Storage of binary objects by key
+#[tonic::async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Store an object, replacing the object with the same key.
+    async fn put(
+        &self,
+        key: &str,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> Result<(), ObjectStoreError>;
+    /// Get the content of an object.
+    async fn get(&self, key: &str) -> Result<Vec<u8>, ObjectStoreError>;
+    /// Remove an object.
+    async fn delete(&self, key: &str) -> Result<(), ObjectStoreError>;
+}
+
+/// Create the object store for the provided configuration.
+///
+/// Falls back to the in memory store if no `object_store_endpoint` is
+/// configured or the S3 client could not be created.
+pub fn object_store(config: &Config) -> Arc<dyn ObjectStore> {
+    let Some(endpoint) = &config.object_store_endpoint else {
+        log::warn!(
+            "(object_store) no object store endpoint configured, objects are kept in memory."
+        );
+        return Arc::new(memory::MemoryObjectStore::default());
+    };
+
+    match s3::S3ObjectStore::new(
+        endpoint,
+        &config.object_store_bucket,
+        &config.object_store_region,
+        config
+            .object_store_access_key
+            .as_deref()
+            .unwrap_or_default(),
+        config
+            .object_store_secret_key
+            .as_deref()
+            .unwrap_or_default(),
+    ) {
+        Ok(store) => Arc::new(store),
+        Err(e) => {
+            log::error!(
+                "(object_store) could not create object store client, objects are kept in memory: {}",
+                e
+            );
+            Arc::new(memory::MemoryObjectStore::default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_object_store_from_config() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let mut config = Config::default();
+        let store = object_store(&config);
+        store
+            .put("key", "text/plain", b"content".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(store.get("key").await.unwrap(), b"content".to_vec());
+
+        config.object_store_endpoint = Some("not a url".to_string());
+        let store = object_store(&config);
+        assert_eq!(store.get("key").await, Err(ObjectStoreError::NotFound));
+
+        ut_info!("success");
+    }
+}
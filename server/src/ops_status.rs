@@ -0,0 +1,305 @@
+//! # Vertiport Operational Status
+//!
+//! The operational status of a vertiport (`Open`, `Closed`, `LimitedOps` or
+//! `Emergency`), separate from its generic
+//! [`AssetStatus`](crate::rest::structs::AssetStatus). A status applies
+//! within an effective time window, the vertiport is `Open` outside of it.
+//!
+//! A `Closed` or `Emergency` vertiport disables its vertipads for regular
+//! operations, so a weather closure no longer requires updating every pad.
+//! The vertipads themselves are not changed in svc-storage, the REST API
+//! reports them disabled and unavailable within the window.
+//!
+//! svc-storage does not store the operational status, so
+//! [`VertiportOpsStatuses`] keeps it in the [`VERTIPORT_OPS_STATUSES_TABLE`]
+//! of the persisted state, see [`crate::state`]. A closed vertiport stays
+//! closed after a restart, but a status set on one replica does not disable
+//! the vertipads reported by the others.
+
+pub use crate::rest::{
+    TimeWindow, VertiportOpsState, VertiportOpsStatus, VertiportOpsStatusPayload,
+};
+
+use crate::state::{StateError, StateTable};
+use chrono::{DateTime, Utc};
+use std::fmt::{self, Display, Formatter};
+
+/// Name of the table of the operational statuses in the state directory
+pub const VERTIPORT_OPS_STATUSES_TABLE: &str = "vertiport_ops_statuses";
+
+/// Errors returned when changing the operational status of a vertiport
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpsStatusError {
+    /// The end of the effective window is not after its start
+    InvalidRange,
+    /// The effective window ends in the past
+    Ended,
+    /// The status could not be persisted
+    State(StateError),
+}
+
+impl Display for OpsStatusError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            OpsStatusError::InvalidRange => {
+                write!(f, "'effective_until' must be after 'effective_from'")
+            }
+            OpsStatusError::Ended => write!(f, "status ends in the past"),
+            OpsStatusError::State(e) => write!(f, "could not persist the status: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for OpsStatusError {}
+
+/// Check if the status disables the vertipads of the vertiport
+pub fn disables_vertipads(status: VertiportOpsStatus) -> bool {
+    matches!(
+        status,
+        VertiportOpsStatus::Closed | VertiportOpsStatus::Emergency
+    )
+}
+
+/// Check if the state is effective at the provided time
+fn is_effective(state: &VertiportOpsState, at: DateTime<Utc>) -> bool {
+    state.effective_from.map_or(true, |from| from <= at)
+        && state.effective_until.map_or(true, |until| at < until)
+}
+
+/// Store of the operational status per vertiport id.
+///
+/// The default store is kept in memory, [`VertiportOpsStatuses::open`]
+/// persists the statuses.
+#[derive(Debug, Clone, Default)]
+pub struct VertiportOpsStatuses {
+    states: StateTable<VertiportOpsState>,
+}
+
+impl VertiportOpsStatuses {
+    /// Open the statuses persisted in the state directory, kept in memory if
+    /// no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            states: StateTable::open(dir, VERTIPORT_OPS_STATUSES_TABLE)?,
+        })
+    }
+
+    /// Set the operational status of a vertiport, replacing the previous
+    /// status.
+    pub fn set(
+        &self,
+        vertiport_id: &str,
+        payload: VertiportOpsStatusPayload,
+        now: DateTime<Utc>,
+    ) -> Result<VertiportOpsState, OpsStatusError> {
+        if let Some(until) = payload.effective_until {
+            if until <= payload.effective_from.unwrap_or(now) {
+                return Err(OpsStatusError::InvalidRange);
+            }
+
+            if until <= now {
+                return Err(OpsStatusError::Ended);
+            }
+        }
+
+        let mut state = VertiportOpsState {
+            vertiport_id: vertiport_id.to_string(),
+            status: payload.status,
+            // A status effective in the past is effective immediately
+            effective_from: payload.effective_from.filter(|from| *from > now),
+            effective_until: payload.effective_until,
+            reason: payload.reason,
+            current_status: VertiportOpsStatus::Open,
+            updated_at: Some(now),
+        };
+        self.states
+            .insert(vertiport_id, state.clone())
+            .map_err(OpsStatusError::State)?;
+
+        state.current_status = self.status_at(vertiport_id, now);
+        Ok(state)
+    }
+
+    /// Get the operational status of a vertiport, `Open` if it was never
+    /// changed.
+    pub fn get(&self, vertiport_id: &str, now: DateTime<Utc>) -> VertiportOpsState {
+        let mut state = self
+            .states
+            .get(vertiport_id)
+            .unwrap_or_else(|| VertiportOpsState {
+                vertiport_id: vertiport_id.to_string(),
+                status: VertiportOpsStatus::Open,
+                effective_from: None,
+                effective_until: None,
+                reason: None,
+                current_status: VertiportOpsStatus::Open,
+                updated_at: None,
+            });
+        state.current_status = self.status_at(vertiport_id, now);
+        state
+    }
+
+    /// Remove the operational status of a removed vertiport.
+    ///
+    /// The vertiport is removed even if its status could not be written, so
+    /// the error is only logged.
+    pub fn remove(&self, vertiport_id: &str) {
+        if let Err(e) = self.states.remove(vertiport_id) {
+            log::warn!(
+                "(VertiportOpsStatuses) could not remove the status of {}: {}",
+                vertiport_id,
+                e
+            );
+        }
+    }
+
+    /// Get the operational status of a vertiport at the provided time
+    pub fn status_at(&self, vertiport_id: &str, at: DateTime<Utc>) -> VertiportOpsStatus {
+        self.states
+            .get(vertiport_id)
+            .filter(|state| is_effective(state, at))
+            .map(|state| state.status)
+            .unwrap_or_default()
+    }
+
+    /// Check if the vertipads of a vertiport are disabled at the provided time
+    pub fn disables_vertipads(&self, vertiport_id: &str, at: DateTime<Utc>) -> bool {
+        disables_vertipads(self.status_at(vertiport_id, at))
+    }
+
+    /// Get the window within the provided range the vertipads of a
+    /// vertiport are disabled, if any.
+    pub fn disabled_window(
+        &self,
+        vertiport_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Option<TimeWindow> {
+        self.states
+            .get(vertiport_id)
+            .filter(|state| disables_vertipads(state.status))
+            .map(|state| TimeWindow {
+                start: state.effective_from.map_or(from, |start| start.max(from)),
+                end: state.effective_until.map_or(to, |end| end.min(to)),
+            })
+            .filter(|window| window.end > window.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn payload(
+        status: VertiportOpsStatus,
+        from: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> VertiportOpsStatusPayload {
+        VertiportOpsStatusPayload {
+            status,
+            effective_from: from,
+            effective_until: until,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_vertiport_ops_statuses() {
+        let statuses = VertiportOpsStatuses::default();
+        let id = "vertiport";
+        let now = Utc::now();
+        let hours = |hours: i64| now + Duration::hours(hours);
+
+        assert_eq!(
+            statuses.get(id, now).current_status,
+            VertiportOpsStatus::Open
+        );
+        assert_eq!(statuses.get(id, now).updated_at, None);
+
+        assert_eq!(
+            statuses.set(
+                id,
+                payload(VertiportOpsStatus::Closed, Some(hours(2)), Some(hours(1))),
+                now
+            ),
+            Err(OpsStatusError::InvalidRange)
+        );
+        assert_eq!(
+            statuses.set(
+                id,
+                payload(VertiportOpsStatus::Closed, Some(hours(-2)), Some(hours(-1))),
+                now
+            ),
+            Err(OpsStatusError::Ended)
+        );
+
+        let state = statuses
+            .set(
+                id,
+                payload(VertiportOpsStatus::Closed, Some(hours(-1)), Some(hours(2))),
+                now,
+            )
+            .unwrap();
+        assert_eq!(state.effective_from, None);
+        assert_eq!(state.current_status, VertiportOpsStatus::Closed);
+        assert!(statuses.disables_vertipads(id, now));
+        assert!(!statuses.disables_vertipads(id, hours(2)));
+        assert!(!statuses.disables_vertipads("other", now));
+        assert_eq!(
+            statuses.disabled_window(id, hours(1), hours(4)),
+            Some(TimeWindow {
+                start: hours(1),
+                end: hours(2),
+            })
+        );
+        assert_eq!(statuses.disabled_window(id, hours(3), hours(4)), None);
+
+        let state = statuses
+            .set(
+                id,
+                payload(VertiportOpsStatus::LimitedOps, Some(hours(1)), None),
+                now,
+            )
+            .unwrap();
+        assert_eq!(state.current_status, VertiportOpsStatus::Open);
+        assert_eq!(
+            statuses.status_at(id, hours(10)),
+            VertiportOpsStatus::LimitedOps
+        );
+        assert!(!statuses.disables_vertipads(id, hours(10)));
+        assert_eq!(statuses.disabled_window(id, now, hours(4)), None);
+    }
+
+    #[test]
+    fn test_vertiport_ops_statuses_persisted() {
+        let dir = crate::state::test_state_dir();
+        let now = Utc::now();
+        let until = now + Duration::hours(2);
+
+        let statuses = VertiportOpsStatuses::open(Some(&dir)).unwrap();
+        statuses
+            .set(
+                "closed",
+                payload(VertiportOpsStatus::Closed, None, Some(until)),
+                now,
+            )
+            .unwrap();
+        statuses
+            .set(
+                "removed",
+                payload(VertiportOpsStatus::Emergency, None, None),
+                now,
+            )
+            .unwrap();
+        statuses.remove("removed");
+
+        // a closed vertiport stays closed after a restart
+        let statuses = VertiportOpsStatuses::open(Some(&dir)).unwrap();
+        assert!(statuses.disables_vertipads("closed", now));
+        assert_eq!(statuses.get("closed", now).effective_until, Some(until));
+        assert_eq!(statuses.status_at("removed", now), VertiportOpsStatus::Open);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,153 @@
+//! Repositories backed by the svc-storage gRPC clients
+
+use super::{
+    AircraftRepository, FlightPlanRepository, GroupRepository, VertipadRepository,
+    VertiportRepository,
+};
+use crate::fan_out::with_timeout;
+use crate::grpc::client::GrpcClients;
+use crate::latency::StorageLatencies;
+use duplicate::duplicate_item;
+use std::time::Duration;
+use svc_storage_client_grpc::prelude::*;
+use svc_storage_client_grpc::prelude::{flight_plan, group, vehicle, vertipad, vertiport};
+use tonic::Status;
+
+/// Repository using the svc-storage gRPC clients, recording the latency of
+/// each call.
+///
+/// Each call is cut off with [`Status::deadline_exceeded`] after the
+/// `storage_call_timeout_ms` of the clients, so a hung storage connection can
+/// not hold a request indefinitely.
+#[derive(Clone, Debug)]
+pub struct GrpcRepository {
+    clients: Clients,
+    latencies: StorageLatencies,
+    timeout: Option<Duration>,
+}
+
+impl GrpcRepository {
+    /// Create a repository using the provided clients
+    pub fn new(grpc_clients: &GrpcClients) -> Self {
+        Self {
+            clients: grpc_clients.storage.clone(),
+            latencies: grpc_clients.storage_latencies().clone(),
+            timeout: grpc_clients.call_timeout,
+        }
+    }
+}
+
+#[duplicate_item(
+    repository              resource    client;
+    [AircraftRepository]    [vehicle]   [vehicle];
+    [VertiportRepository]   [vertiport] [vertiport];
+    [VertipadRepository]    [vertipad]  [vertipad];
+)]
+#[tonic::async_trait]
+impl repository for GrpcRepository {
+    async fn get_by_id(&self, id: String) -> Result<resource::Object, Status> {
+        let call = with_timeout(self.timeout, self.clients.client.get_by_id(Id { id }));
+        Ok(self
+            .latencies
+            .time(stringify!(client), call)
+            .await?
+            .into_inner())
+    }
+
+    async fn search(&self, filter: AdvancedSearchFilter) -> Result<Vec<resource::Object>, Status> {
+        let call = with_timeout(self.timeout, self.clients.client.search(filter));
+        Ok(self
+            .latencies
+            .time(stringify!(client), call)
+            .await?
+            .into_inner()
+            .list)
+    }
+
+    async fn insert(&self, data: resource::Data) -> Result<resource::Object, Status> {
+        let call = with_timeout(self.timeout, self.clients.client.insert(data));
+        self.latencies
+            .time(stringify!(client), call)
+            .await?
+            .into_inner()
+            .object
+            .ok_or_else(|| Status::internal("insert response did not contain an object"))
+    }
+
+    async fn update(&self, object: resource::UpdateObject) -> Result<(), Status> {
+        let call = with_timeout(self.timeout, self.clients.client.update(object));
+        self.latencies
+            .time(stringify!(client), call)
+            .await
+            .map(|_| ())
+    }
+
+    async fn delete(&self, id: String) -> Result<(), Status> {
+        let call = with_timeout(self.timeout, self.clients.client.delete(Id { id }));
+        self.latencies
+            .time(stringify!(client), call)
+            .await
+            .map(|_| ())
+    }
+}
+
+#[tonic::async_trait]
+impl GroupRepository for GrpcRepository {
+    async fn get_by_id(&self, id: String) -> Result<group::Object, Status> {
+        let call = with_timeout(self.timeout, self.clients.group.get_by_id(Id { id }));
+        Ok(self.latencies.time("group", call).await?.into_inner())
+    }
+
+    async fn search(&self, filter: AdvancedSearchFilter) -> Result<Vec<group::Object>, Status> {
+        let call = with_timeout(self.timeout, self.clients.group.search(filter));
+        Ok(self.latencies.time("group", call).await?.into_inner().list)
+    }
+
+    async fn update(&self, object: group::UpdateObject) -> Result<(), Status> {
+        let call = with_timeout(self.timeout, self.clients.group.update(object));
+        self.latencies.time("group", call).await.map(|_| ())
+    }
+}
+
+#[tonic::async_trait]
+impl FlightPlanRepository for GrpcRepository {
+    async fn search(
+        &self,
+        filter: AdvancedSearchFilter,
+    ) -> Result<Vec<flight_plan::Object>, Status> {
+        let call = with_timeout(self.timeout, self.clients.flight_plan.search(filter));
+        Ok(self
+            .latencies
+            .time("flight_plan", call)
+            .await?
+            .into_inner()
+            .list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::Repositories;
+
+    #[tokio::test]
+    async fn test_grpc_repository() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let config = crate::config::Config::default();
+        let repos = Repositories::grpc(&GrpcClients::default(config));
+
+        let object = repos
+            .vertiports
+            .insert(vertiport::mock::get_data_obj())
+            .await
+            .unwrap();
+        let found = repos.vertiports.get_by_id(object.id.clone()).await.unwrap();
+        assert_eq!(found.id, object.id);
+
+        repos.vertiports.delete(object.id).await.unwrap();
+
+        ut_info!("Success.");
+    }
+}
@@ -0,0 +1,239 @@
+//! In-memory repositories, used for handler tests
+//!
+//! Search filters are not applied, all stored objects are returned. Updates
+//! replace the stored data as a whole, ignoring the update mask.
+
+use super::{
+    AircraftRepository, FlightPlanRepository, GroupRepository, Repositories, VertipadRepository,
+    VertiportRepository,
+};
+use duplicate::duplicate_item;
+use lib_common::uuid::Uuid;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use svc_storage_client_grpc::prelude::{
+    flight_plan, group, vehicle, vertipad, vertiport, AdvancedSearchFilter,
+};
+use tonic::Status;
+
+/// Repository storing objects in memory
+#[derive(Debug, Default)]
+pub struct MemoryRepository<T> {
+    objects: Mutex<HashMap<String, T>>,
+    unavailable: bool,
+}
+
+impl<T> MemoryRepository<T> {
+    /// Create a repository failing every request with `Unavailable`
+    pub fn unavailable() -> Self {
+        Self {
+            objects: Mutex::new(HashMap::new()),
+            unavailable: true,
+        }
+    }
+
+    /// Create a repository containing the provided objects by id
+    pub fn with_objects(objects: HashMap<String, T>) -> Self {
+        Self {
+            objects: Mutex::new(objects),
+            unavailable: false,
+        }
+    }
+
+    /// Return an error if the repository is set to be unavailable.
+    fn check_available(&self) -> Result<(), Status> {
+        match self.unavailable {
+            true => Err(Status::unavailable("memory repository unavailable")),
+            false => Ok(()),
+        }
+    }
+
+    /// Get the stored objects, recovering from a poisoned lock.
+    fn objects(&self) -> std::sync::MutexGuard<'_, HashMap<String, T>> {
+        self.objects
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[duplicate_item(
+    repository              resource;
+    [AircraftRepository]    [vehicle];
+    [VertiportRepository]   [vertiport];
+    [VertipadRepository]    [vertipad];
+)]
+#[tonic::async_trait]
+impl repository for MemoryRepository<resource::Data> {
+    async fn get_by_id(&self, id: String) -> Result<resource::Object, Status> {
+        self.check_available()?;
+        let data = self
+            .objects()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| Status::not_found(format!("no object with id {id}")))?;
+        Ok(resource::Object {
+            id,
+            data: Some(data),
+        })
+    }
+
+    async fn search(&self, _filter: AdvancedSearchFilter) -> Result<Vec<resource::Object>, Status> {
+        self.check_available()?;
+        Ok(self
+            .objects()
+            .iter()
+            .map(|(id, data)| resource::Object {
+                id: id.clone(),
+                data: Some(data.clone()),
+            })
+            .collect())
+    }
+
+    async fn insert(&self, mut data: resource::Data) -> Result<resource::Object, Status> {
+        self.check_available()?;
+        let now = lib_common::time::Utc::now();
+        data.created_at = Some(now.into());
+        data.updated_at = Some(now.into());
+
+        let id = Uuid::new_v4().to_string();
+        self.objects().insert(id.clone(), data.clone());
+        Ok(resource::Object {
+            id,
+            data: Some(data),
+        })
+    }
+
+    async fn update(&self, object: resource::UpdateObject) -> Result<(), Status> {
+        self.check_available()?;
+        let mut objects = self.objects();
+        let stored = objects
+            .get_mut(&object.id)
+            .ok_or_else(|| Status::not_found(format!("no object with id {}", object.id)))?;
+        if let Some(mut data) = object.data {
+            data.updated_at = Some(lib_common::time::Utc::now().into());
+            *stored = data;
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, id: String) -> Result<(), Status> {
+        self.check_available()?;
+        self.objects()
+            .remove(&id)
+            .map(|_| ())
+            .ok_or_else(|| Status::not_found(format!("no object with id {id}")))
+    }
+}
+
+#[tonic::async_trait]
+impl GroupRepository for MemoryRepository<group::Data> {
+    async fn get_by_id(&self, id: String) -> Result<group::Object, Status> {
+        self.check_available()?;
+        let data = self
+            .objects()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| Status::not_found(format!("no object with id {id}")))?;
+        Ok(group::Object {
+            id,
+            data: Some(data),
+        })
+    }
+
+    async fn search(&self, _filter: AdvancedSearchFilter) -> Result<Vec<group::Object>, Status> {
+        self.check_available()?;
+        Ok(self
+            .objects()
+            .iter()
+            .map(|(id, data)| group::Object {
+                id: id.clone(),
+                data: Some(data.clone()),
+            })
+            .collect())
+    }
+
+    async fn update(&self, object: group::UpdateObject) -> Result<(), Status> {
+        self.check_available()?;
+        let mut objects = self.objects();
+        let stored = objects
+            .get_mut(&object.id)
+            .ok_or_else(|| Status::not_found(format!("no object with id {}", object.id)))?;
+        if let Some(data) = object.data {
+            *stored = data;
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl FlightPlanRepository for MemoryRepository<flight_plan::Data> {
+    async fn search(
+        &self,
+        _filter: AdvancedSearchFilter,
+    ) -> Result<Vec<flight_plan::Object>, Status> {
+        self.check_available()?;
+        Ok(self
+            .objects()
+            .iter()
+            .map(|(id, data)| flight_plan::Object {
+                id: id.clone(),
+                data: Some(data.clone()),
+            })
+            .collect())
+    }
+}
+
+impl Repositories {
+    /// Create empty in-memory repositories
+    pub fn memory() -> Self {
+        Self {
+            aircraft: Arc::new(MemoryRepository::<vehicle::Data>::default()),
+            vertiports: Arc::new(MemoryRepository::<vertiport::Data>::default()),
+            vertipads: Arc::new(MemoryRepository::<vertipad::Data>::default()),
+            groups: Arc::new(MemoryRepository::<group::Data>::default()),
+            flight_plans: Arc::new(MemoryRepository::<flight_plan::Data>::default()),
+        }
+    }
+
+    /// Create in-memory repositories failing every request with `Unavailable`
+    pub fn unavailable() -> Self {
+        Self {
+            aircraft: Arc::new(MemoryRepository::<vehicle::Data>::unavailable()),
+            vertiports: Arc::new(MemoryRepository::<vertiport::Data>::unavailable()),
+            vertipads: Arc::new(MemoryRepository::<vertipad::Data>::unavailable()),
+            groups: Arc::new(MemoryRepository::<group::Data>::unavailable()),
+            flight_plans: Arc::new(MemoryRepository::<flight_plan::Data>::unavailable()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_repository() {
+        let repos = Repositories::memory();
+
+        let object = repos
+            .vertipads
+            .insert(vertipad::mock::get_data_obj())
+            .await
+            .unwrap();
+        assert!(object.data.as_ref().unwrap().created_at.is_some());
+
+        let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+        assert_eq!(repos.vertipads.search(filter).await.unwrap().len(), 1);
+
+        repos.vertipads.delete(object.id.clone()).await.unwrap();
+        let error = repos.vertipads.get_by_id(object.id).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::NotFound);
+
+        let error = Repositories::unavailable()
+            .aircraft
+            .get_by_id(Uuid::new_v4().to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unavailable);
+    }
+}
@@ -0,0 +1,127 @@
+//! Repositories
+//! provides storage access for the REST handlers
+//!
+//! Handlers only depend on the repository traits, so the storage backend can
+//! be swapped without changing them. The [`Repositories`] struct is added to
+//! the REST server as an [`axum::Extension`].
+
+pub mod grpc;
+#[cfg(any(test, feature = "test_util"))]
+pub mod memory;
+pub mod tenant;
+
+use crate::grpc::client::GrpcClients;
+use std::sync::Arc;
+use svc_storage_client_grpc::prelude::{
+    flight_plan, group, vehicle, vertipad, vertiport, AdvancedSearchFilter,
+};
+use tonic::Status;
+
+/// Storage access for aircraft ([`vehicle`] objects)
+#[tonic::async_trait]
+pub trait AircraftRepository: Send + Sync {
+    /// Get the aircraft with the provided id.
+    async fn get_by_id(&self, id: String) -> Result<vehicle::Object, Status>;
+    /// Get all aircraft matching the filter.
+    async fn search(&self, filter: AdvancedSearchFilter) -> Result<Vec<vehicle::Object>, Status>;
+    /// Insert a new aircraft, returning the stored object.
+    async fn insert(&self, data: vehicle::Data) -> Result<vehicle::Object, Status>;
+    /// Update the fields of an aircraft listed in the object's mask.
+    async fn update(&self, object: vehicle::UpdateObject) -> Result<(), Status>;
+    /// Remove the aircraft with the provided id.
+    async fn delete(&self, id: String) -> Result<(), Status>;
+}
+
+/// Storage access for [`vertiport`] objects
+#[tonic::async_trait]
+pub trait VertiportRepository: Send + Sync {
+    /// Get the vertiport with the provided id.
+    async fn get_by_id(&self, id: String) -> Result<vertiport::Object, Status>;
+    /// Get all vertiports matching the filter.
+    async fn search(&self, filter: AdvancedSearchFilter) -> Result<Vec<vertiport::Object>, Status>;
+    /// Insert a new vertiport, returning the stored object.
+    async fn insert(&self, data: vertiport::Data) -> Result<vertiport::Object, Status>;
+    /// Update the fields of a vertiport listed in the object's mask.
+    async fn update(&self, object: vertiport::UpdateObject) -> Result<(), Status>;
+    /// Remove the vertiport with the provided id.
+    async fn delete(&self, id: String) -> Result<(), Status>;
+}
+
+/// Storage access for [`vertipad`] objects
+#[tonic::async_trait]
+pub trait VertipadRepository: Send + Sync {
+    /// Get the vertipad with the provided id.
+    async fn get_by_id(&self, id: String) -> Result<vertipad::Object, Status>;
+    /// Get all vertipads matching the filter.
+    async fn search(&self, filter: AdvancedSearchFilter) -> Result<Vec<vertipad::Object>, Status>;
+    /// Insert a new vertipad, returning the stored object.
+    async fn insert(&self, data: vertipad::Data) -> Result<vertipad::Object, Status>;
+    /// Update the fields of a vertipad listed in the object's mask.
+    async fn update(&self, object: vertipad::UpdateObject) -> Result<(), Status>;
+    /// Remove the vertipad with the provided id.
+    async fn delete(&self, id: String) -> Result<(), Status>;
+}
+
+/// Storage access for asset [`group`] objects
+///
+/// Asset groups are not registered by this service yet, so they can only be
+/// looked up, patched and moved within the group hierarchy.
+#[tonic::async_trait]
+pub trait GroupRepository: Send + Sync {
+    /// Get the asset group with the provided id.
+    async fn get_by_id(&self, id: String) -> Result<group::Object, Status>;
+    /// Get all asset groups matching the filter.
+    async fn search(&self, filter: AdvancedSearchFilter) -> Result<Vec<group::Object>, Status>;
+    /// Update the fields of an asset group listed in the object's mask.
+    async fn update(&self, object: group::UpdateObject) -> Result<(), Status>;
+}
+
+/// Read access to the [`flight_plan`] objects of the aircraft
+///
+/// The flight plans are the ones svc-scheduler stores in svc-storage for the
+/// itineraries it books. They are read from svc-storage because the
+/// svc-scheduler gRPC API only books and cancels itineraries, it has no query
+/// for the flights of an aircraft.
+#[tonic::async_trait]
+pub trait FlightPlanRepository: Send + Sync {
+    /// Get all flight plans matching the filter.
+    async fn search(
+        &self,
+        filter: AdvancedSearchFilter,
+    ) -> Result<Vec<flight_plan::Object>, Status>;
+}
+
+/// Repositories used by the REST handlers and the gRPC server
+#[derive(Clone)]
+pub struct Repositories {
+    /// Aircraft storage
+    pub aircraft: Arc<dyn AircraftRepository>,
+    /// Vertiport storage
+    pub vertiports: Arc<dyn VertiportRepository>,
+    /// Vertipad storage
+    pub vertipads: Arc<dyn VertipadRepository>,
+    /// Asset group storage
+    pub groups: Arc<dyn GroupRepository>,
+    /// Flight plan storage
+    pub flight_plans: Arc<dyn FlightPlanRepository>,
+}
+
+impl Repositories {
+    /// Create repositories backed by svc-storage
+    pub fn grpc(grpc_clients: &GrpcClients) -> Self {
+        let repository = Arc::new(grpc::GrpcRepository::new(grpc_clients));
+        Self {
+            aircraft: repository.clone(),
+            vertiports: repository.clone(),
+            vertipads: repository.clone(),
+            groups: repository.clone(),
+            flight_plans: repository,
+        }
+    }
+}
+
+impl std::fmt::Debug for Repositories {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Repositories").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,502 @@
+//! Tenant scoped repositories
+//!
+//! svc-storage does not store a tenant per asset, so the tenant of the
+//! aircraft, vertiports and vertipads registered through a tenant scoped
+//! repository is kept in the [`ASSET_TENANTS_TABLE`] of the persisted state
+//! by [`TenantAssets`], see [`crate::state`]. Assets without a tenant belong
+//! to the default tenant. An asset whose tenant can not be written is
+//! removed again, so it does not end up in the default tenant.
+//!
+//! A [`TenantRepository`] only returns, updates and removes the assets of its
+//! own tenant; the assets of other tenants are not found. Its searches pass
+//! the ids of the assets of the tenant to svc-storage, so the result limits
+//! of a search apply to the assets of the tenant. Asset groups are
+//! created outside of this service, so they belong to the default tenant
+//! until they are assigned to a tenant through the admin endpoint
+//! `PUT /admin/groups/{id}/tenant`. Flight plans are limited to the ones of
+//! the aircraft of the tenant.
+
+use super::{
+    AircraftRepository, FlightPlanRepository, GroupRepository, Repositories, VertipadRepository,
+    VertiportRepository,
+};
+use crate::state::{StateError, StateTable};
+use duplicate::duplicate_item;
+use std::sync::Arc;
+use svc_storage_client_grpc::prelude::{
+    flight_plan, group, vehicle, vertipad, vertiport, AdvancedSearchFilter,
+};
+use tonic::Status;
+
+/// Name of the table of the asset tenants in the state directory
+pub const ASSET_TENANTS_TABLE: &str = "asset_tenants";
+
+/// Persisted tenant of each asset and asset group id
+#[derive(Debug, Clone, Default)]
+pub struct TenantAssets {
+    tenants: StateTable<String>,
+}
+
+impl TenantAssets {
+    /// Open the tenants persisted in the state directory, see
+    /// [`StateTable::open`]
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            tenants: StateTable::open(dir, ASSET_TENANTS_TABLE)?,
+        })
+    }
+
+    /// Get the tenant of an asset, `None` for the default tenant
+    pub fn tenant_of(&self, asset_id: &str) -> Option<String> {
+        self.tenants.get(asset_id)
+    }
+
+    /// Get the ids of the assets of a tenant. For the default tenant, the ids
+    /// of the assets of all other tenants.
+    fn ids(&self, tenant: Option<&str>) -> Vec<String> {
+        self.tenants.read(|tenants| {
+            tenants
+                .iter()
+                .filter(|(_, asset_tenant)| tenant.map_or(true, |tenant| *asset_tenant == tenant))
+                .map(|(id, _)| id.clone())
+                .collect()
+        })
+    }
+
+    /// Check if an asset belongs to a tenant, `None` being the default tenant
+    pub fn belongs_to(&self, asset_id: &str, tenant: Option<&str>) -> bool {
+        self.tenants.get(asset_id).as_deref() == tenant
+    }
+
+    /// Assign an asset to a tenant, `None` being the default tenant
+    pub fn assign(&self, asset_id: &str, tenant: Option<&str>) -> Result<(), StateError> {
+        match tenant {
+            Some(tenant) => self.tenants.insert(asset_id, tenant.to_string())?,
+            None => self.tenants.remove(asset_id)?,
+        };
+        Ok(())
+    }
+}
+
+/// Repository limited to the assets of a single tenant
+#[derive(Clone)]
+pub struct TenantRepository {
+    inner: Repositories,
+    tenant: Option<String>,
+    assets: TenantAssets,
+}
+
+impl TenantRepository {
+    /// Create a repository for a tenant, `None` being the default tenant
+    pub fn new(inner: Repositories, tenant: Option<String>, assets: TenantAssets) -> Self {
+        Self {
+            inner,
+            tenant,
+            assets,
+        }
+    }
+
+    /// Return `NotFound` if the asset belongs to another tenant.
+    fn check_tenant(&self, id: &str) -> Result<(), Status> {
+        match self.assets.belongs_to(id, self.tenant.as_deref()) {
+            true => Ok(()),
+            false => Err(Status::not_found(format!("no object with id {id}"))),
+        }
+    }
+
+    /// Limit a search to the assets of the tenant, `None` if the tenant has
+    /// no assets.
+    ///
+    /// The search is limited to the ids of the assets of the tenant in
+    /// `column`, or for the default tenant excludes the assets of the other
+    /// tenants. The constraint is part of the storage search, so the result
+    /// limits of the search only count the assets of the tenant.
+    fn scope(&self, filter: AdvancedSearchFilter, column: &str) -> Option<AdvancedSearchFilter> {
+        let ids = self.assets.ids(self.tenant.as_deref());
+        if ids.is_empty() {
+            return self.tenant.is_none().then_some(filter);
+        }
+
+        let column = column.to_string();
+        let scoped = match (self.tenant.is_some(), filter.filters.is_empty()) {
+            (true, false) => filter.and_in(column, ids),
+            (false, false) => filter.and_not_in(column, ids),
+            (true, true) => AdvancedSearchFilter {
+                filters: AdvancedSearchFilter::search_in(column, ids).filters,
+                ..filter
+            },
+            (false, true) => AdvancedSearchFilter {
+                filters: AdvancedSearchFilter::search_not_in(column, ids).filters,
+                ..filter
+            },
+        };
+
+        Some(scoped)
+    }
+
+    /// Keep the objects of the tenant, for the repositories which do not
+    /// apply search filters
+    fn retain_own<T>(&self, objects: &mut Vec<T>, id: impl Fn(&T) -> Option<&str>) {
+        objects.retain(|object| {
+            id(object).map_or(false, |id| {
+                self.assets.belongs_to(id, self.tenant.as_deref())
+            })
+        });
+    }
+}
+
+#[duplicate_item(
+    repository              resource    field           id_column;
+    [AircraftRepository]    [vehicle]   [aircraft]      ["vehicle_id"];
+    [VertiportRepository]   [vertiport] [vertiports]    ["vertiport_id"];
+    [VertipadRepository]    [vertipad]  [vertipads]     ["vertipad_id"];
+)]
+#[tonic::async_trait]
+impl repository for TenantRepository {
+    async fn get_by_id(&self, id: String) -> Result<resource::Object, Status> {
+        self.check_tenant(&id)?;
+        self.inner.field.get_by_id(id).await
+    }
+
+    async fn search(&self, filter: AdvancedSearchFilter) -> Result<Vec<resource::Object>, Status> {
+        let Some(filter) = self.scope(filter, id_column) else {
+            return Ok(vec![]);
+        };
+        let mut objects = self.inner.field.search(filter).await?;
+        self.retain_own(&mut objects, |object| Some(&object.id));
+        Ok(objects)
+    }
+
+    async fn insert(&self, data: resource::Data) -> Result<resource::Object, Status> {
+        let object = self.inner.field.insert(data).await?;
+        if let Err(e) = self.assets.assign(&object.id, self.tenant.as_deref()) {
+            // the asset would belong to the default tenant otherwise
+            if let Err(delete_error) = self.inner.field.delete(object.id.clone()).await {
+                log::error!(
+                    "(tenant) could not remove asset {} without a tenant: {}",
+                    object.id,
+                    delete_error
+                );
+            }
+            return Err(Status::internal(format!(
+                "could not persist the tenant of {}: {e}",
+                object.id
+            )));
+        }
+        Ok(object)
+    }
+
+    async fn update(&self, object: resource::UpdateObject) -> Result<(), Status> {
+        self.check_tenant(&object.id)?;
+        self.inner.field.update(object).await
+    }
+
+    async fn delete(&self, id: String) -> Result<(), Status> {
+        self.check_tenant(&id)?;
+        self.inner.field.delete(id).await
+    }
+}
+
+#[tonic::async_trait]
+impl GroupRepository for TenantRepository {
+    async fn get_by_id(&self, id: String) -> Result<group::Object, Status> {
+        self.check_tenant(&id)?;
+        self.inner.groups.get_by_id(id).await
+    }
+
+    async fn search(&self, filter: AdvancedSearchFilter) -> Result<Vec<group::Object>, Status> {
+        let Some(filter) = self.scope(filter, "group_id") else {
+            return Ok(vec![]);
+        };
+        let mut objects = self.inner.groups.search(filter).await?;
+        self.retain_own(&mut objects, |object| Some(&object.id));
+        Ok(objects)
+    }
+
+    async fn update(&self, object: group::UpdateObject) -> Result<(), Status> {
+        self.check_tenant(&object.id)?;
+        self.inner.groups.update(object).await
+    }
+}
+
+#[tonic::async_trait]
+impl FlightPlanRepository for TenantRepository {
+    async fn search(
+        &self,
+        filter: AdvancedSearchFilter,
+    ) -> Result<Vec<flight_plan::Object>, Status> {
+        let Some(filter) = self.scope(filter, "vehicle_id") else {
+            return Ok(vec![]);
+        };
+        let mut objects = self.inner.flight_plans.search(filter).await?;
+        self.retain_own(&mut objects, |object| {
+            object.data.as_ref().map(|data| data.vehicle_id.as_str())
+        });
+        Ok(objects)
+    }
+}
+
+impl Repositories {
+    /// Limit the repositories to the assets of a tenant, `None` being the
+    /// default tenant
+    pub fn for_tenant(&self, tenant: Option<String>, assets: TenantAssets) -> Self {
+        let repository = Arc::new(TenantRepository::new(self.clone(), tenant, assets));
+        Self {
+            aircraft: repository.clone(),
+            vertiports: repository.clone(),
+            vertipads: repository.clone(),
+            groups: repository.clone(),
+            flight_plans: repository,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::memory::MemoryRepository;
+    use crate::testing::AircraftDataBuilder;
+    use lib_common::uuid::Uuid;
+    use std::collections::HashMap;
+    use tonic::Code;
+
+    #[tokio::test]
+    async fn test_tenant_repository() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let repos = Repositories::memory();
+        let assets = TenantAssets::default();
+        let pilot = repos.for_tenant(Some("pilot".to_string()), assets.clone());
+        let staging = repos.for_tenant(Some("staging".to_string()), assets.clone());
+        let default = repos.for_tenant(None, assets.clone());
+
+        let pilot_id = pilot
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let default_id = default
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        assert_eq!(assets.tenant_of(&pilot_id), Some("pilot".to_string()));
+        assert_eq!(assets.tenant_of(&default_id), None);
+
+        pilot.aircraft.get_by_id(pilot_id.clone()).await.unwrap();
+        let error = staging
+            .aircraft
+            .get_by_id(pilot_id.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+        let error = pilot
+            .aircraft
+            .get_by_id(default_id.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+
+        let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+        let found = pilot.aircraft.search(filter.clone()).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, pilot_id);
+        assert!(staging
+            .aircraft
+            .search(filter.clone())
+            .await
+            .unwrap()
+            .is_empty());
+        let found = default.aircraft.search(filter).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, default_id);
+
+        let error = staging.aircraft.delete(pilot_id.clone()).await.unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+        pilot.aircraft.delete(pilot_id.clone()).await.unwrap();
+        assert!(repos.aircraft.get_by_id(pilot_id).await.is_err());
+
+        ut_info!("Success.");
+    }
+
+    #[test]
+    fn test_tenant_scope() {
+        let assets = TenantAssets::default();
+        let tenant = |tenant: Option<&str>| {
+            TenantRepository::new(
+                Repositories::memory(),
+                tenant.map(str::to_string),
+                assets.clone(),
+            )
+        };
+        let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+
+        // a tenant without assets finds nothing, the default tenant everything
+        assert_eq!(
+            tenant(Some("pilot")).scope(filter.clone(), "vehicle_id"),
+            None
+        );
+        assert_eq!(
+            tenant(None).scope(filter.clone(), "vehicle_id"),
+            Some(filter.clone())
+        );
+
+        // the ids of the tenant are part of the search
+        assets.assign("aircraft", Some("pilot")).unwrap();
+        let scoped = tenant(Some("pilot"))
+            .scope(filter.clone(), "vehicle_id")
+            .unwrap();
+        assert_eq!(scoped.filters.len(), 2);
+        assert_eq!(scoped.results_per_page, filter.results_per_page);
+        let scoped = tenant(None).scope(filter.clone(), "vehicle_id").unwrap();
+        assert_eq!(scoped.filters.len(), 2);
+
+        let unfiltered = AdvancedSearchFilter {
+            results_per_page: 11,
+            ..Default::default()
+        };
+        let scoped = tenant(Some("pilot"))
+            .scope(unfiltered, "vehicle_id")
+            .unwrap();
+        assert_eq!(scoped.filters.len(), 1);
+        assert_eq!(scoped.results_per_page, 11);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_groups() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let group_id = Uuid::new_v4().to_string();
+        let repos = Repositories {
+            groups: Arc::new(MemoryRepository::with_objects(HashMap::from([(
+                group_id.clone(),
+                group::Data::default(),
+            )]))),
+            ..Repositories::memory()
+        };
+        let assets = TenantAssets::default();
+        let pilot = repos.for_tenant(Some("pilot".to_string()), assets.clone());
+        let default = repos.for_tenant(None, assets.clone());
+        let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+
+        // groups belong to the default tenant until they are assigned
+        default.groups.get_by_id(group_id.clone()).await.unwrap();
+        let error = pilot.groups.get_by_id(group_id.clone()).await.unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+        assert!(pilot
+            .groups
+            .search(filter.clone())
+            .await
+            .unwrap()
+            .is_empty());
+
+        assets.assign(&group_id, Some("pilot")).unwrap();
+        pilot.groups.get_by_id(group_id.clone()).await.unwrap();
+        assert_eq!(pilot.groups.search(filter.clone()).await.unwrap().len(), 1);
+        let error = default
+            .groups
+            .get_by_id(group_id.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+        assert!(default.groups.search(filter).await.unwrap().is_empty());
+        let error = default
+            .groups
+            .update(group::UpdateObject {
+                id: group_id.clone(),
+                data: Some(group::Data::default()),
+                mask: None,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+
+        assets.assign(&group_id, None).unwrap();
+        assert_eq!(assets.tenant_of(&group_id), None);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_tenant_flight_plans() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let assets = TenantAssets::default();
+        let repos = Repositories::memory();
+        let pilot_id = repos
+            .for_tenant(Some("pilot".to_string()), assets.clone())
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let repos = Repositories {
+            flight_plans: Arc::new(MemoryRepository::with_objects(HashMap::from([(
+                "flight".to_string(),
+                flight_plan::Data {
+                    vehicle_id: pilot_id,
+                    ..Default::default()
+                },
+            )]))),
+            ..repos
+        };
+
+        // the flights of an aircraft of another tenant are not found
+        let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+        let pilot = repos.for_tenant(Some("pilot".to_string()), assets.clone());
+        assert_eq!(
+            pilot
+                .flight_plans
+                .search(filter.clone())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        let default = repos.for_tenant(None, assets);
+        assert!(default
+            .flight_plans
+            .search(filter)
+            .await
+            .unwrap()
+            .is_empty());
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_tenant_assets_persisted() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let dir = crate::state::test_state_dir();
+        let repos = Repositories::memory();
+        let assets = TenantAssets::open(Some(&dir)).unwrap();
+        let pilot_id = repos
+            .for_tenant(Some("pilot".to_string()), assets)
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+
+        // after a restart the aircraft still belongs to its tenant
+        let assets = TenantAssets::open(Some(&dir)).unwrap();
+        assert_eq!(assets.tenant_of(&pilot_id), Some("pilot".to_string()));
+        let error = repos
+            .for_tenant(None, assets)
+            .aircraft
+            .get_by_id(pilot_id)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+
+        std::fs::remove_dir_all(dir).unwrap();
+        ut_info!("Success.");
+    }
+}
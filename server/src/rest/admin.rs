@@ -0,0 +1,158 @@
+//! Admin router
+//!
+//! Operational endpoints (read-only and audit log toggles, cache flush, configuration dump,
+//! feature flags, the state and reconnection of the gRPC clients and the tenants of the
+//! asset groups) under `/admin`. The
+//! endpoints are not versioned and require the `ADMIN_TOKEN` as bearer token.
+//! They are served on the REST port, or on `DOCKER_PORT_ADMIN` if configured
+//! so they can be kept off the public network.
+
+use super::api;
+use super::routes::{mount, ApiRoute};
+use crate::config::SharedConfig;
+use axum::{
+    http::{header::AUTHORIZATION, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Router,
+};
+
+/// Scheme of the admin `Authorization` header
+pub const BEARER_PREFIX: &str = "Bearer ";
+
+/// Get the route table of the admin endpoints.
+pub fn admin_route_table() -> Vec<ApiRoute> {
+    vec![
+        ApiRoute::get("/admin/read-only", api::admin::get_read_only),
+        ApiRoute::put("/admin/read-only", api::admin::put_read_only),
+        ApiRoute::get("/admin/audit-log", api::admin::get_audit_log),
+        ApiRoute::put("/admin/audit-log", api::admin::put_audit_log),
+        ApiRoute::get("/admin/config", api::admin::get_config),
+        ApiRoute::get("/admin/features", api::admin::get_features),
+        ApiRoute::post("/admin/cache/flush", api::admin::flush_caches),
+        ApiRoute::get("/admin/grpc", api::admin::get_grpc_connections),
+        ApiRoute::post("/admin/grpc/reconnect", api::admin::reconnect_grpc_clients),
+        ApiRoute::put("/admin/groups/:id/tenant", api::admin::put_group_tenant),
+    ]
+}
+
+/// Get the admin routes.
+pub fn admin_routes() -> Router {
+    mount(admin_route_table())
+}
+
+/// Authorization of the admin endpoints using the `admin_token` of the [`SharedConfig`]
+#[derive(Debug, Clone)]
+pub struct AdminAuth {
+    config: SharedConfig,
+}
+
+impl AdminAuth {
+    /// Create a new authorization for the provided configuration
+    pub fn new(config: SharedConfig) -> Self {
+        Self { config }
+    }
+
+    /// Middleware function rejecting requests without a valid admin token.
+    ///
+    /// Returns `UNAUTHORIZED` if the token is missing or invalid, and
+    /// `FORBIDDEN` if no admin token is configured.
+    pub async fn authorize<B>(self, req: Request<B>, next: Next<B>) -> Response {
+        let Some(expected) = self.config.read(|config| config.admin_token.clone()) else {
+            rest_warn!("admin request rejected: no admin token configured.");
+            return (
+                StatusCode::FORBIDDEN,
+                "(server) admin endpoints are disabled.".to_string(),
+            )
+                .into_response();
+        };
+
+        let token = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix(BEARER_PREFIX));
+
+        match token {
+            Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => {
+                next.run(req).await
+            }
+            _ => {
+                rest_warn!("admin request rejected: invalid admin token.");
+                StatusCode::UNAUTHORIZED.into_response()
+            }
+        }
+    }
+}
+
+/// Compare two byte strings without returning early on the first difference
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use axum::{body::Body, middleware, routing};
+    use tower::ServiceExt;
+
+    async fn request(token: Option<&str>, authorization: Option<&str>) -> StatusCode {
+        let mut config = Config::default();
+        config.admin_token = token.map(String::from);
+        let auth = AdminAuth::new(SharedConfig::from(config));
+
+        let app = Router::new()
+            .route("/admin/test", routing::get(|| async { "ok" }))
+            .layer(middleware::from_fn(
+                move |req: Request<Body>, next: Next<Body>| auth.clone().authorize(req, next),
+            ));
+
+        let mut req = Request::builder().uri("/admin/test");
+        if let Some(authorization) = authorization {
+            req = req.header(AUTHORIZATION, authorization);
+        }
+
+        app.oneshot(req.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_admin_auth() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        assert_eq!(
+            request(Some("secret"), Some("Bearer secret")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            request(Some("secret"), Some("Bearer wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            request(Some("secret"), Some("secret")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            request(Some("secret"), None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            request(None, Some("Bearer secret")).await,
+            StatusCode::FORBIDDEN
+        );
+
+        ut_info!("success");
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(constant_time_eq(b"", b""));
+    }
+}
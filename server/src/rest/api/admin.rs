@@ -0,0 +1,443 @@
+//! Handlers for operational tasks
+
+/// Types Used in REST Messages
+pub use super::rest_types::{
+    AuditLogStatus, FeatureFlag, GroupTenant, GrpcConnectionStatus, ReadOnlyStatus,
+};
+
+use super::errors::storage_error_status;
+use crate::config::{Config, SharedConfig};
+use crate::features::Features;
+use crate::grpc::client::GrpcClients;
+use crate::maintenance::{check_maintenance, OverdueAircraft};
+use crate::repo::tenant::TenantAssets;
+use crate::repo::Repositories;
+use crate::rest::audit::AuditLog;
+use crate::rest::json::Json;
+use crate::rest::read_only::ReadOnlyMode;
+use axum::extract::Path;
+use axum::Extension;
+use hyper::StatusCode;
+use lib_common::time::Utc;
+
+/// Get the read-only maintenance mode of the service.
+#[utoipa::path(
+    get,
+    path = "/admin/read-only",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Current read-only mode", body = ReadOnlyStatus),
+    )
+)]
+pub async fn get_read_only(Extension(read_only): Extension<ReadOnlyMode>) -> Json<ReadOnlyStatus> {
+    rest_debug!("entry.");
+    Json(ReadOnlyStatus {
+        enabled: read_only.is_enabled(),
+    })
+}
+
+/// Enable or disable the read-only maintenance mode of the service.
+///
+/// While enabled, requests changing assets are rejected with a
+/// `503 SERVICE_UNAVAILABLE`. The mode overrides the `READ_ONLY`
+/// configuration until the service restarts.
+#[utoipa::path(
+    put,
+    path = "/admin/read-only",
+    tag = "svc-assets",
+    request_body = ReadOnlyStatus,
+    responses(
+        (status = 200, description = "Read-only mode updated; the new mode is returned", body = ReadOnlyStatus),
+        (status = 422, description = "Request body is invalid format"),
+    )
+)]
+pub async fn put_read_only(
+    Extension(read_only): Extension<ReadOnlyMode>,
+    Json(payload): Json<ReadOnlyStatus>,
+) -> Json<ReadOnlyStatus> {
+    rest_warn!("read-only mode set to {}.", payload.enabled);
+    read_only.set(payload.enabled);
+    Json(ReadOnlyStatus {
+        enabled: read_only.is_enabled(),
+    })
+}
+
+/// Get the state of the audit log of the REST requests.
+#[utoipa::path(
+    get,
+    path = "/admin/audit-log",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Current audit log state", body = AuditLogStatus),
+    )
+)]
+pub async fn get_audit_log(Extension(audit_log): Extension<AuditLog>) -> Json<AuditLogStatus> {
+    rest_debug!("entry.");
+    Json(AuditLogStatus {
+        enabled: audit_log.is_enabled(),
+    })
+}
+
+/// Enable or disable the audit log of the REST requests.
+///
+/// The state overrides the `AUDIT_LOG` configuration until the service
+/// restarts.
+#[utoipa::path(
+    put,
+    path = "/admin/audit-log",
+    tag = "svc-assets",
+    request_body = AuditLogStatus,
+    responses(
+        (status = 200, description = "Audit log updated; the new state is returned", body = AuditLogStatus),
+        (status = 422, description = "Request body is invalid format"),
+    )
+)]
+pub async fn put_audit_log(
+    Extension(audit_log): Extension<AuditLog>,
+    Json(payload): Json<AuditLogStatus>,
+) -> Json<AuditLogStatus> {
+    rest_warn!("audit log set to {}.", payload.enabled);
+    audit_log.set(payload.enabled);
+    Json(AuditLogStatus {
+        enabled: audit_log.is_enabled(),
+    })
+}
+
+/// Get the current configuration of the service.
+///
+/// The admin token is not included.
+#[utoipa::path(
+    get,
+    path = "/admin/config",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Current configuration", body = Object),
+    )
+)]
+pub async fn get_config(Extension(config): Extension<SharedConfig>) -> Json<Config> {
+    rest_debug!("entry.");
+    Json(config.get())
+}
+
+/// Get the feature flags of the service.
+///
+/// The flags are read from the configuration, so they change with a
+/// configuration reload.
+#[utoipa::path(
+    get,
+    path = "/admin/features",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Feature flags", body = [FeatureFlag]),
+    )
+)]
+pub async fn get_features(Extension(features): Extension<Features>) -> Json<Vec<FeatureFlag>> {
+    rest_debug!("entry.");
+    Json(features.flags())
+}
+
+/// Flush the in memory caches of the service.
+///
+/// Derived data (the aircraft with an overdue maintenance) is rebuilt from
+/// svc-storage instead of waiting for the next periodic check.
+#[utoipa::path(
+    post,
+    path = "/admin/cache/flush",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Caches rebuilt"),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+    )
+)]
+pub async fn flush_caches(
+    Extension(repos): Extension<Repositories>,
+    Extension(overdue): Extension<OverdueAircraft>,
+) -> Result<(), StatusCode> {
+    rest_info!("entry.");
+
+    let summary = check_maintenance(&repos, &overdue, Utc::now())
+        .await
+        .map_err(|e| {
+            rest_error!("could not rebuild overdue aircraft: {e}.");
+            storage_error_status(&e)
+        })?;
+
+    rest_info!(
+        "caches flushed, {} of {} aircraft overdue.",
+        summary.overdue,
+        summary.checked
+    );
+    Ok(())
+}
+
+/// Get the state of the svc-storage connections.
+#[utoipa::path(
+    get,
+    path = "/admin/grpc",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "State of the svc-storage connections", body = [GrpcConnectionStatus]),
+    )
+)]
+pub async fn get_grpc_connections(
+    Extension(grpc_clients): Extension<GrpcClients>,
+) -> Json<Vec<GrpcConnectionStatus>> {
+    rest_debug!("entry.");
+    Json(grpc_clients.connection_status())
+}
+
+/// Drop the connections of the svc-storage clients and reconnect.
+///
+/// Returns `SERVICE_UNAVAILABLE` if svc-storage can not be reached after
+/// reconnecting.
+#[utoipa::path(
+    post,
+    path = "/admin/grpc/reconnect",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Clients reconnected"),
+        (status = 503, description = "svc-storage can not be reached"),
+    )
+)]
+pub async fn reconnect_grpc_clients(
+    Extension(grpc_clients): Extension<GrpcClients>,
+) -> Result<(), StatusCode> {
+    rest_warn!("reconnecting svc-storage clients.");
+    grpc_clients.reconnect().await;
+
+    match grpc_clients.storage_ready().await {
+        true => Ok(()),
+        false => {
+            rest_error!("svc-storage unavailable after reconnecting.");
+            Err(StatusCode::SERVICE_UNAVAILABLE)
+        }
+    }
+}
+
+/// Assign an asset group to a tenant.
+///
+/// Asset groups are created outside of this service, so they belong to the
+/// default tenant until they are assigned to a configured tenant, see
+/// [`crate::repo::tenant`]. Without a `tenant` the group belongs to the
+/// default tenant again.
+#[utoipa::path(
+    put,
+    path = "/admin/groups/{id}/tenant",
+    tag = "svc-assets",
+    request_body = GroupTenant,
+    responses(
+        (status = 200, description = "Tenant of the asset group updated", body = GroupTenant),
+        (status = 404, description = "Asset group not found in database"),
+        (status = 422, description = "The tenant is not configured"),
+        (status = 500, description = "The tenant could not be persisted"),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    ),
+    params(
+        ("id" = String, Path, description = "Asset group id"),
+    )
+)]
+pub async fn put_group_tenant(
+    Extension(config): Extension<SharedConfig>,
+    Extension(repos): Extension<Repositories>,
+    Extension(tenants): Extension<TenantAssets>,
+    Path(id): Path<String>,
+    Json(payload): Json<GroupTenant>,
+) -> Result<Json<GroupTenant>, StatusCode> {
+    rest_info!(
+        "asset group {} assigned to tenant {:?}.",
+        id,
+        payload.tenant
+    );
+
+    if let Some(tenant) = &payload.tenant {
+        if !config.read(|config| config.tenants.contains(tenant)) {
+            rest_warn!("unknown tenant '{}'.", tenant);
+            return Err(StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+
+    repos.groups.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not get asset group {}: {e}.", id);
+        storage_error_status(&e)
+    })?;
+
+    tenants
+        .assign(&id, payload.tenant.as_deref())
+        .map_err(|e| {
+            rest_error!("could not persist the tenant of asset group {}: {e}.", id);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(GroupTenant {
+        tenant: tenants.tenant_of(&id),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::memory::MemoryRepository;
+    use lib_common::uuid::Uuid;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use svc_storage_client_grpc::prelude::group;
+
+    #[tokio::test]
+    async fn test_read_only() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let read_only = Extension(ReadOnlyMode::new(SharedConfig::from(Config::default())));
+
+        let Json(status) = get_read_only(read_only.clone()).await;
+        assert!(!status.enabled);
+
+        let Json(status) =
+            put_read_only(read_only.clone(), Json(ReadOnlyStatus { enabled: true })).await;
+        assert!(status.enabled);
+
+        let Json(status) = get_read_only(read_only).await;
+        assert!(status.enabled);
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_audit_log() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let audit_log = Extension(AuditLog::new(SharedConfig::from(Config::default())));
+
+        let Json(status) = get_audit_log(audit_log.clone()).await;
+        assert!(!status.enabled);
+
+        let Json(status) =
+            put_audit_log(audit_log.clone(), Json(AuditLogStatus { enabled: true })).await;
+        assert!(status.enabled);
+
+        let Json(status) = get_audit_log(audit_log).await;
+        assert!(status.enabled);
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_get_config() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let mut config = Config::default();
+        config.admin_token = Some("secret".to_string());
+        config.api_tokens = vec!["secret=assets:read".to_string()];
+        let Json(config) = get_config(Extension(SharedConfig::from(config))).await;
+
+        let json = serde_json::to_value(config).unwrap();
+        assert_eq!(json["docker_port_rest"], 8000);
+        assert!(json.get("admin_token").is_none());
+        assert!(json.get("api_tokens").is_none());
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_get_features() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let mut config = Config::default();
+        config.strict_validation = true;
+        let features = Features::new(SharedConfig::from(config));
+        let Json(flags) = get_features(Extension(features)).await;
+
+        assert_eq!(flags.len(), 3);
+        assert!(flags
+            .iter()
+            .any(|flag| flag.name == "strict_validation" && flag.enabled));
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_flush_caches() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let overdue = OverdueAircraft::default();
+        flush_caches(
+            Extension(Repositories::memory()),
+            Extension(overdue.clone()),
+        )
+        .await
+        .unwrap();
+
+        let error = flush_caches(Extension(Repositories::unavailable()), Extension(overdue))
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::SERVICE_UNAVAILABLE);
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_grpc_connections() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let grpc_clients = Extension(GrpcClients::default(Config::default()));
+        reconnect_grpc_clients(grpc_clients.clone()).await.unwrap();
+
+        let Json(status) = get_grpc_connections(grpc_clients).await;
+        assert_eq!(status.len(), 3);
+        assert!(status.iter().all(|status| status.reconnects == 1));
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_put_group_tenant() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let group_id = Uuid::new_v4().to_string();
+        let repos = Repositories {
+            groups: Arc::new(MemoryRepository::with_objects(HashMap::from([(
+                group_id.clone(),
+                group::Data::default(),
+            )]))),
+            ..Repositories::memory()
+        };
+        let mut config = Config::default();
+        config.tenants = vec!["pilot".to_string()];
+        let config = SharedConfig::from(config);
+        let tenants = TenantAssets::default();
+        let put = |id: &str, tenant: Option<&str>| {
+            put_group_tenant(
+                Extension(config.clone()),
+                Extension(repos.clone()),
+                Extension(tenants.clone()),
+                Path(id.to_string()),
+                Json(GroupTenant {
+                    tenant: tenant.map(str::to_string),
+                }),
+            )
+        };
+
+        let Json(assigned) = put(&group_id, Some("pilot")).await.unwrap();
+        assert_eq!(assigned.tenant, Some("pilot".to_string()));
+        assert_eq!(tenants.tenant_of(&group_id), Some("pilot".to_string()));
+
+        let error = put(&group_id, Some("staging")).await.unwrap_err();
+        assert_eq!(error, StatusCode::UNPROCESSABLE_ENTITY);
+        let error = put(&Uuid::new_v4().to_string(), Some("pilot"))
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        let Json(assigned) = put(&group_id, None).await.unwrap();
+        assert_eq!(assigned.tenant, None);
+
+        ut_info!("success");
+    }
+}
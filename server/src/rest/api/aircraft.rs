@@ -1,14 +1,70 @@
 //! Handlers for actions on aircraft assets
 
-pub use super::rest_types::UpdateAircraftPayload;
-use crate::grpc::client::GrpcClients;
+use super::batch::{batch_get, parse_batch_ids, AircraftBatch, BatchGetPayload};
+use super::conversion::{convert_objects, ListError, Listed, PartialList, SkippedObjects};
+use super::errors::storage_error_status;
+use super::group::check_group_assignment;
+use super::list::{list_filter, matches_list_query, owner_filter, ListAssetsQuery, ListLimits};
+use super::merge_patch::merge_patch_payload;
+use super::methods::{asset_headers, AssetHeaders};
+use super::rest_types::timestamp;
+pub use super::rest_types::{
+    AircraftStatusFilter, AircraftStatusResult, AircraftUtilization, AssignHangarPayload,
+    AssignedFlight, AvailabilityQuery, BatchAircraftStatusPayload, MaintenanceHold,
+    MaintenanceHoldPayload, RemoveAircraftQuery, UpdateAircraftPayload,
+};
+use super::validation::*;
+use crate::aircraft_status::AircraftStatuses;
+use crate::archive::RemovedAssets;
+use crate::config::SharedConfig;
+use crate::delegation::GroupDelegations;
+use crate::fan_out::FanOut;
+use crate::hold::{HoldError, MaintenanceHolds};
+use crate::maintenance::OverdueAircraft;
+use crate::repo::Repositories;
+use crate::rest::json::Json;
+use crate::rest::quota::{
+    operator_id_from_headers, owner_id_from_headers, OperatorQuotas, QuotaKind,
+};
 use crate::rest::structs::{AssetStatus, Basics};
-use axum::{extract::Path, Extension, Json};
+use crate::schedule::{merge_windows, TimeWindow};
+use axum::{
+    extract::{Path, Query},
+    http::HeaderMap,
+    Extension,
+};
+use futures::future::join_all;
 use hyper::StatusCode;
 use lib_common::time::{DateTime, Utc};
 use lib_common::uuid::to_uuid;
-use svc_storage_client_grpc::prelude::vehicle;
 use svc_storage_client_grpc::prelude::*;
+use svc_storage_client_grpc::prelude::{flight_plan, vehicle};
+
+/// Maximum time range, in days, the utilization can be requested for
+const MAX_UTILIZATION_RANGE_DAYS: i64 = 366;
+
+/// Fields of a [`Aircraft`] which can be changed with a merge patch
+const AIRCRAFT_PATCH_FIELDS: &[&str] = &[
+    "vehicle_model_id",
+    "hangar_id",
+    "hangar_bay_id",
+    "serial_number",
+    "registration_number",
+    "description",
+    "asset_group_id",
+    "schedule",
+    "last_maintenance",
+    "next_maintenance",
+];
+
+/// Fields of a [`Aircraft`] which can be removed with a merge patch
+const AIRCRAFT_NULLABLE_FIELDS: &[&str] = &[
+    "hangar_id",
+    "hangar_bay_id",
+    "description",
+    "asset_group_id",
+    "schedule",
+];
 
 /// A struct representing an aircraft.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema, IntoParams)]
@@ -48,9 +104,11 @@ pub struct Aircraft {
     pub max_range_km: f64,
 
     /// The date of the aircraft's last maintenance.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
     pub last_maintenance: Option<DateTime<Utc>>,
 
     /// The date of the aircraft's next planned maintenance.
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
     pub next_maintenance: Option<DateTime<Utc>>,
 
     /// The hangar ID where the aircraft is stored.
@@ -73,6 +131,36 @@ impl Aircraft {
             ),
         }
     }
+
+    /// Report the status set for the aircraft through gRPC, if any.
+    ///
+    /// Apply before [`Aircraft::apply_maintenance_status`], so an aircraft
+    /// held for maintenance is still reported unavailable.
+    pub fn apply_status(mut self, statuses: &AircraftStatuses) -> Self {
+        if let Some(status) = statuses.get(&self.basics.id) {
+            self.basics.status = status;
+        }
+        self
+    }
+
+    /// Add the operator owning the aircraft, if it is known.
+    pub fn apply_owner(mut self, quotas: &OperatorQuotas) -> Self {
+        self.basics.owner_id = quotas.owner(QuotaKind::Aircraft, &self.basics.id);
+        self
+    }
+
+    /// Mark the aircraft unavailable if its maintenance is overdue or it
+    /// is currently held for maintenance.
+    pub fn apply_maintenance_status(
+        mut self,
+        overdue: &OverdueAircraft,
+        holds: &MaintenanceHolds,
+    ) -> Self {
+        if overdue.contains(&self.basics.id) || holds.is_held(&self.basics.id, Utc::now()) {
+            self.basics.status = AssetStatus::Unavailable;
+        }
+        self
+    }
 }
 
 impl TryFrom<vehicle::Object> for Aircraft {
@@ -97,6 +185,8 @@ impl TryFrom<vehicle::Object> for Aircraft {
                     .updated_at
                     .ok_or("(try_from) updated_at field empty but expected.")?
                     .into(),
+                deleted_at: None,
+                owner_id: None,
                 whitelist: Vec::new(),
                 status: AssetStatus::Available,
             },
@@ -114,50 +204,369 @@ impl TryFrom<vehicle::Object> for Aircraft {
     }
 }
 
+/// Validate and normalize a [`vehicle::Data`] object.
+///
+/// Only the payload itself is checked, storage side checks are done
+/// separately by the handlers.
+pub fn validate_vehicle_data(mut data: vehicle::Data) -> Result<vehicle::Data, Vec<FieldError>> {
+    normalize_string(&mut data.vehicle_model_id);
+    normalize_string(&mut data.serial_number);
+    normalize_string(&mut data.registration_number);
+    normalize_optional_string(&mut data.description);
+    normalize_optional_string(&mut data.hangar_id);
+    normalize_optional_string(&mut data.hangar_bay_id);
+    normalize_optional_string(&mut data.asset_group_id);
+    normalize_optional_string(&mut data.schedule);
+
+    let mut errors = vec![];
+    check_uuid(&mut errors, "vehicle_model_id", &data.vehicle_model_id);
+    check_not_empty(&mut errors, "serial_number", &data.serial_number);
+    check_not_empty(
+        &mut errors,
+        "registration_number",
+        &data.registration_number,
+    );
+    check_optional_uuid(&mut errors, "hangar_id", &data.hangar_id);
+    check_optional_uuid(&mut errors, "hangar_bay_id", &data.hangar_bay_id);
+    check_optional_uuid(&mut errors, "asset_group_id", &data.asset_group_id);
+
+    if let (Some(last), Some(next)) = (&data.last_maintenance, &data.next_maintenance) {
+        let last: DateTime<Utc> = last.clone().into();
+        let next: DateTime<Utc> = next.clone().into();
+        if next < last {
+            errors.push(field_error(
+                "next_maintenance",
+                "must not be before last_maintenance",
+            ));
+        }
+    }
+
+    into_result(data, errors)
+}
+
+/// Validate and normalize an [`UpdateAircraftPayload`].
+///
+/// Only the payload itself is checked, storage side checks are done
+/// separately by the handlers.
+pub fn validate_update_aircraft_payload(
+    mut payload: UpdateAircraftPayload,
+) -> Result<UpdateAircraftPayload, Vec<FieldError>> {
+    normalize_string(&mut payload.id);
+    normalize_optional_string(&mut payload.description);
+    normalize_optional_string(&mut payload.hangar_id);
+    normalize_optional_string(&mut payload.hangar_bay_id);
+    normalize_optional_string(&mut payload.asset_group_id);
+    normalize_optional_string(&mut payload.schedule);
+
+    let mut errors = vec![];
+    check_uuid(&mut errors, "id", &payload.id);
+    check_optional_uuid(&mut errors, "vehicle_model_id", &payload.vehicle_model_id);
+    check_optional_uuid(&mut errors, "hangar_id", &payload.hangar_id);
+    check_optional_uuid(&mut errors, "hangar_bay_id", &payload.hangar_bay_id);
+    check_optional_uuid(&mut errors, "asset_group_id", &payload.asset_group_id);
+
+    if let Some(serial_number) = payload.serial_number.as_mut() {
+        normalize_string(serial_number);
+        check_not_empty(&mut errors, "serial_number", serial_number);
+    }
+
+    if let Some(registration_number) = payload.registration_number.as_mut() {
+        normalize_string(registration_number);
+        check_not_empty(&mut errors, "registration_number", registration_number);
+    }
+
+    if let (Some(last), Some(next)) = (&payload.last_maintenance, &payload.next_maintenance) {
+        if next < last {
+            errors.push(field_error(
+                "next_maintenance",
+                "must not be before last_maintenance",
+            ));
+        }
+    }
+
+    into_result(payload, errors)
+}
+
+/// Find the aircraft using the registration number.
+///
+/// The aircraft with the provided `id` (if any) is excluded from the search.
+async fn find_registration_number(
+    repos: &Repositories,
+    registration_number: &str,
+    id: Option<&str>,
+) -> Result<Option<String>, StatusCode> {
+    let filter = AdvancedSearchFilter::search_equals(
+        "registration_number".to_string(),
+        registration_number.to_string(),
+    )
+    .and_is_null("deleted_at".to_string());
+
+    let aircraft_id = repos
+        .aircraft
+        .search(filter)
+        .await
+        .map_err(|e| {
+            rest_error!("could not search vehicles: {e}.");
+            storage_error_status(&e)
+        })?
+        .into_iter()
+        .filter(|object| Some(object.id.as_str()) != id)
+        .find(|object| {
+            object
+                .data
+                .as_ref()
+                .map(|data| data.registration_number == registration_number)
+                .unwrap_or(false)
+        })
+        .map(|object| object.id);
+
+    Ok(aircraft_id)
+}
+
+/// Check if the registration number is already in use by another aircraft.
+///
+/// The aircraft with the provided `id` (if any) is excluded from the check.
+async fn check_registration_number(
+    repos: &Repositories,
+    registration_number: &str,
+    id: Option<&str>,
+) -> Result<Option<FieldError>, StatusCode> {
+    let in_use = find_registration_number(repos, registration_number, id)
+        .await?
+        .is_some();
+
+    Ok(in_use.then(|| field_error("registration_number", "already in use")))
+}
+
+/// Run payload validation and storage side checks for a [`vehicle::Data`] object.
+///
+/// Also used by the gRPC server to register vehicles.
+pub async fn check_vehicle_data(
+    repos: &Repositories,
+    data: vehicle::Data,
+) -> Result<vehicle::Data, ValidationError> {
+    let data = validate_vehicle_data(data).map_err(unprocessable)?;
+
+    if let Some(error) = check_registration_number(repos, &data.registration_number, None)
+        .await
+        .map_err(|status| (status, Json(vec![])))?
+    {
+        return Err(unprocessable(vec![error]));
+    }
+
+    Ok(data)
+}
+
+/// Run payload validation and storage side checks for an [`UpdateAircraftPayload`].
+///
+/// A masked `asset_group_id` is checked with [`check_group_assignment`] for
+/// the operator making the update.
+async fn check_update_aircraft_payload(
+    repos: &Repositories,
+    delegations: &GroupDelegations,
+    quotas: &OperatorQuotas,
+    operator_id: Option<&str>,
+    payload: UpdateAircraftPayload,
+) -> Result<UpdateAircraftPayload, ValidationError> {
+    let payload = validate_update_aircraft_payload(payload).map_err(unprocessable)?;
+
+    let mut errors = vec![];
+    if let Some(registration_number) = &payload.registration_number {
+        errors.extend(
+            check_registration_number(repos, registration_number, Some(&payload.id))
+                .await
+                .map_err(|status| (status, Json(vec![])))?,
+        );
+    }
+
+    let group_masked = payload.mask.iter().any(|field| field == "asset_group_id");
+    if let (true, Some(group_id)) = (group_masked, &payload.asset_group_id) {
+        errors.extend(
+            check_group_assignment(repos, delegations, quotas, group_id, operator_id)
+                .await
+                .map_err(|status| (status, Json(vec![])))?,
+        );
+    }
+
+    into_result(payload, errors).map_err(unprocessable)
+}
+
+/// Validate an [`Aircraft`] registration payload without persisting it.
+///
+/// Returns the normalized payload as it would be registered.
+#[utoipa::path(
+    post,
+    path = "/assets/aircraft/validate",
+    tag = "svc-assets",
+    request_body=vehicle::Data,
+    responses(
+        (status = 200, description = "Payload is valid; the normalized payload is returned", body = vehicle::Data),
+        (status = 422, description = "Payload is invalid; a list of field errors is returned", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    )
+)]
+pub async fn validate_aircraft_registration(
+    Extension(repos): Extension<Repositories>,
+    Json(payload): Json<vehicle::Data>,
+) -> Result<Json<vehicle::Data>, ValidationError> {
+    rest_info!("entry.");
+    rest_debug!("Payload: {:?}", &payload);
+
+    let data = check_vehicle_data(&repos, payload).await?;
+    Ok(Json(data))
+}
+
+/// Validate an [`Aircraft`] update payload without persisting it.
+///
+/// Returns the normalized payload as it would be applied.
+#[utoipa::path(
+    put,
+    path = "/assets/aircraft/validate",
+    tag = "svc-assets",
+    request_body=UpdateAircraftPayload,
+    responses(
+        (status = 200, description = "Payload is valid; the normalized payload is returned", body = UpdateAircraftPayload),
+        (status = 400, description = "Invalid `x-operator-id` header, or no operator while assigning an asset group"),
+        (status = 422, description = "Payload is invalid; a list of field errors is returned", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    )
+)]
+pub async fn validate_aircraft_update(
+    Extension(repos): Extension<Repositories>,
+    Extension(delegations): Extension<GroupDelegations>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateAircraftPayload>,
+) -> Result<Json<UpdateAircraftPayload>, ValidationError> {
+    rest_info!("entry [{}].", payload.id);
+    rest_debug!("Payload: {:?}", &payload);
+
+    let operator_id =
+        operator_id_from_headers(&headers).map_err(|status| (status, Json(vec![])))?;
+    let payload = check_update_aircraft_payload(
+        &repos,
+        &delegations,
+        &quotas,
+        operator_id.as_deref(),
+        payload,
+    )
+    .await?;
+    Ok(Json(payload))
+}
+
+/// Check if a registration number is already in use.
+///
+/// Lets clients validate a registration number before registering an
+/// [`Aircraft`], the id of the aircraft using it is returned if any.
+#[utoipa::path(
+    get,
+    path = "/assets/aircraft/check-registration/{registration_number}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Usage of the registration number", body = RegistrationNumberCheck),
+        (status = 400, description = "Registration number is empty"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("registration_number" = String, Path, description = "Registration number to check"),
+    )
+)]
+pub async fn check_aircraft_registration(
+    Extension(repos): Extension<Repositories>,
+    Path(mut registration_number): Path<String>,
+) -> Result<Json<RegistrationNumberCheck>, StatusCode> {
+    rest_info!("entry [{}].", registration_number);
+
+    normalize_string(&mut registration_number);
+    if registration_number.is_empty() {
+        rest_error!("empty registration number.");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let aircraft_id = find_registration_number(&repos, &registration_number, None).await?;
+    Ok(Json(RegistrationNumberCheck {
+        registration_number,
+        in_use: aircraft_id.is_some(),
+        aircraft_id,
+    }))
+}
+
 /// Update/modify an [`Aircraft`] in the database.
 ///
-/// This will update the aircraft's information.
+/// This will update the aircraft's information. The aircraft is not written
+/// if the masked fields already have the provided values.
+///
+/// A masked `asset_group_id` requires an operator in the `x-operator-id`
+/// header, and must reference an existing asset group owned by or delegated
+/// to that operator, see [`check_group_assignment`]. A masked
+/// `hangar_bay_id` must reference a bay of the hangar of the aircraft which
+/// no other aircraft is assigned to, as for [`assign_aircraft_hangar`].
 #[utoipa::path(
     put,
     path = "/assets/aircraft",
     tag = "svc-assets",
     request_body=UpdateAircraftPayload,
     responses(
-        (status = 200, description = "Aircraft updated in database; a UUID is returned", body = String),
-        (status = 422, description = "Request body is invalid format"),
-        (status = 503, description = "Could not connect to other microservice dependencies")
+        (status = 200, description = "Aircraft updated in database, `changed` is `false` if nothing differed", body = UpdateResult),
+        (status = 400, description = "Invalid aircraft id or `x-operator-id` header, or no operator while assigning an asset group"),
+        (status = 409, description = "Aircraft was updated since `expected_updated_at`, or the hangar bay is occupied by another aircraft"),
+        (status = 422, description = "Request body is invalid format, the asset group does not exist, has no known owner or belongs to another operator, or the hangar bay does not exist or is not a bay of the hangar; a list of field errors is returned", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
     )
 )]
 pub async fn update_aircraft(
-    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+    Extension(delegations): Extension<GroupDelegations>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateAircraftPayload>,
-) -> Result<(), StatusCode> {
+) -> Result<Json<UpdateResult>, ValidationError> {
     rest_info!("entry [{}].", payload.id);
     rest_debug!("Payload: {:?}", &payload);
 
     let id = to_uuid(&payload.id)
         .ok_or_else(|| {
             rest_error!("Invalid aircraft id.");
-            StatusCode::BAD_REQUEST
+            (StatusCode::BAD_REQUEST, Json(vec![]))
         })? // Check if the aircraft_id is a valid UUID
         .to_string();
-
-    let mut vehicle_data = grpc_clients
-        .storage
-        .vehicle
-        .get_by_id(Id { id: id.clone() })
-        .await
+    let operator_id =
+        operator_id_from_headers(&headers).map_err(|status| (status, Json(vec![])))?;
+
+    // The registration number and asset group checks do not depend on the
+    // stored aircraft
+    let (payload, vehicle) = tokio::join!(
+        check_update_aircraft_payload(
+            &repos,
+            &delegations,
+            &quotas,
+            operator_id.as_deref(),
+            payload
+        ),
+        fan_out.call(repos.aircraft.get_by_id(id.clone())),
+    );
+    let payload = payload?;
+
+    let mut vehicle_data = vehicle
         .map_err(|e| {
             rest_error!("could not retrieve vehicles: {e}.");
-            StatusCode::NOT_FOUND
+            (storage_error_status(&e), Json(vec![]))
         })?
-        .into_inner()
         .data
         .ok_or_else(|| {
             rest_error!("vehicle data is missing.");
-            StatusCode::INTERNAL_SERVER_ERROR
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
         })?;
 
+    check_expected_updated_at(
+        payload.expected_updated_at,
+        vehicle_data.updated_at.clone().map(Into::into),
+    )
+    .map_err(|status| (status, Json(vec![])))?;
+
+    let stored = vehicle_data.clone();
     vehicle_data.hangar_id = payload.hangar_id;
     vehicle_data.hangar_bay_id = payload.hangar_bay_id;
     vehicle_data.description = payload.description;
@@ -183,6 +592,27 @@ pub async fn update_aircraft(
         vehicle_data.next_maintenance = Some(date.into());
     }
 
+    if !masked_fields_changed(&stored, &vehicle_data, &payload.mask) {
+        return Ok(Json(unchanged()));
+    }
+
+    // A hangar bay is checked as by the hangar assignment, in the hangar the
+    // aircraft is in after the update
+    let bay_masked = payload.mask.iter().any(|field| field == "hangar_bay_id");
+    if let (true, Some(hangar_bay_id)) = (bay_masked, vehicle_data.hangar_bay_id.as_deref()) {
+        let hangar_id = match payload.mask.iter().any(|field| field == "hangar_id") {
+            true => vehicle_data.hangar_id.as_deref(),
+            false => stored.hangar_id.as_deref(),
+        };
+        let Some(hangar_id) = hangar_id else {
+            return Err(unprocessable(vec![field_error(
+                "hangar_bay_id",
+                "requires a hangar",
+            )]));
+        };
+        check_hangar_assignment(&repos, &id, hangar_id, hangar_bay_id).await?;
+    }
+
     let object = vehicle::UpdateObject {
         id: id.clone(),
         data: Some(vehicle_data),
@@ -191,61 +621,118 @@ pub async fn update_aircraft(
         }),
     };
 
-    grpc_clients
-        .storage
-        .vehicle
-        .update(object)
-        .await
-        .map_err(|e| {
-            rest_error!("could not update vehicle: {e}.");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+    repos.aircraft.update(object).await.map_err(|e| {
+        rest_error!("could not update vehicle: {e}.");
+        (storage_error_status(&e), Json(vec![]))
+    })?;
 
     rest_info!("successfully updated aircraft.");
-    Ok(())
+    Ok(Json(UpdateResult { changed: true }))
+}
+
+/// Get the flights of an aircraft which did not end yet, ordered by
+/// departure.
+///
+/// Cancelled, draft and finished flight plans are ignored.
+pub fn assigned_flights(
+    aircraft_id: &str,
+    plans: Vec<flight_plan::Object>,
+    now: DateTime<Utc>,
+) -> Vec<AssignedFlight> {
+    let mut flights: Vec<AssignedFlight> = plans
+        .into_iter()
+        .filter_map(|object| {
+            let plan = object.data?;
+            if plan.vehicle_id != aircraft_id
+                || plan.flight_status == flight_plan::FlightStatus::Finished as i32
+            {
+                return None;
+            }
+
+            let window = flight_window(&plan)?;
+            (window.end > now).then(|| AssignedFlight {
+                flight_plan_id: object.id,
+                origin_vertipad_id: plan.origin_vertipad_id,
+                target_vertipad_id: plan.target_vertipad_id,
+                departure: window.start,
+                arrival: window.end,
+            })
+        })
+        .collect();
+
+    flights.sort_by_key(|flight| flight.departure);
+    flights
 }
 
 /// Remove a [`Aircraft`] from the database.
+///
+/// Aircraft assigned to flights which did not end yet are only removed if
+/// `force` is set. The flights are the flight plans svc-scheduler stored
+/// for its itineraries, see [`FlightPlanRepository`](crate::repo::FlightPlanRepository).
 #[utoipa::path(
     delete,
     path = "/assets/aircraft/{id}",
     tag = "svc-assets",
     responses(
         (status = 200, description = "Aircraft removed from database; a UUID is returned", body = String),
+        (status = 400, description = "Invalid aircraft id"),
+        (status = 409, description = "Aircraft is assigned to future flights; the flights are returned", body = [AssignedFlight]),
         (status = 503, description = "Could not connect to other microservice dependencies")
     ),
     params(
         ("id" = String, Path, description = "Aircraft id"),
+        RemoveAircraftQuery,
     )
 )]
 pub async fn remove_aircraft(
-    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(repos): Extension<Repositories>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Extension(removed): Extension<RemovedAssets>,
     Path(id): Path<String>,
-) -> Result<(), StatusCode> {
+    Query(query): Query<RemoveAircraftQuery>,
+) -> Result<(), (StatusCode, Json<Vec<AssignedFlight>>)> {
     rest_info!("entry [{}].", &id);
+    rest_debug!("Query: {:?}", query);
 
     let id = to_uuid(&id)
         .ok_or_else(|| {
             rest_error!("Invalid aircraft id.");
-            StatusCode::BAD_REQUEST
+            (StatusCode::BAD_REQUEST, Json(vec![]))
         })? // Check if the aircraft_id is a valid UUID (v4)
         .to_string();
 
-    grpc_clients
-        .storage
-        .vehicle
-        .delete(Id { id })
-        .await
-        .map_err(|e| {
-            rest_error!("could not remove aircraft {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+    if !query.force {
+        let now = Utc::now();
+        let filter = aircraft_flight_plans_filter(&id, now, None);
+        let plans = repos.flight_plans.search(filter).await.map_err(|e| {
+            rest_error!("could not retrieve flight plans: {e}");
+            (storage_error_status(&e), Json(vec![]))
         })?;
 
+        let flights = assigned_flights(&id, plans, now);
+        if !flights.is_empty() {
+            rest_warn!(
+                "aircraft {id} can not be removed, it is assigned to {} future flights.",
+                flights.len()
+            );
+            return Err((StatusCode::CONFLICT, Json(flights)));
+        }
+    }
+
+    repos.aircraft.delete(id.clone()).await.map_err(|e| {
+        rest_error!("could not remove aircraft {e}");
+        (storage_error_status(&e), Json(vec![]))
+    })?;
+    removed.record(&id, Utc::now());
+    quotas.release(QuotaKind::Aircraft, &id);
+
     rest_info!("successfully removed aircraft.");
     Ok(())
 }
 
 /// Register an [`Aircraft`] in the database.
+///
+/// The operator registering the aircraft becomes its owner.
 #[utoipa::path(
     post,
     path = "/assets/aircraft",
@@ -253,34 +740,53 @@ pub async fn remove_aircraft(
     request_body=vehicle::Data,
     responses(
         (status = 200, description = "Aircraft registered in database; a UUID is returned", body = String),
-        (status = 422, description = "Request body is invalid format"),
+        (status = 400, description = "Missing or invalid operator id header"),
+        (status = 403, description = "Operator reached its aircraft quota"),
+        (status = 422, description = "Request body is invalid format or the registration number is in use; a list of field errors is returned", body = [FieldError]),
+        (status = 500, description = "The owner of the aircraft could not be recorded"),
         (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("x-operator-id" = String, Header, description = "UUID of the operator registering and owning the aircraft; set from the API token if it is bound to an operator"),
     )
 )]
 pub async fn register_aircraft(
-    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(repos): Extension<Repositories>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    headers: HeaderMap,
     Json(payload): Json<vehicle::Data>,
-) -> Result<String, StatusCode> {
+) -> Result<String, ValidationError> {
     rest_info!("entry.");
     rest_debug!("Payload: {:?}", &payload);
 
-    let id = grpc_clients
-        .storage
-        .vehicle
+    let operator_id = owner_id_from_headers(&headers).map_err(|status| (status, Json(vec![])))?;
+
+    let payload = check_vehicle_data(&repos, payload).await?;
+
+    let reservation = quotas
+        .reserve(&repos, &operator_id, QuotaKind::Aircraft)
+        .await
+        .map_err(|status| (status, Json(vec![])))?;
+
+    let id = repos
+        .aircraft
         .insert(payload)
         .await
         .map_err(|e| {
             rest_error!("could not insert vehicle: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-        .into_inner()
-        .object
-        .ok_or_else(|| {
-            rest_error!("vehicle insertion failed.");
-            StatusCode::INTERNAL_SERVER_ERROR
+            (storage_error_status(&e), Json(vec![]))
         })?
         .id;
 
+    // an aircraft without its owner would not count towards the quota
+    if let Err(e) = reservation.commit(&id) {
+        rest_error!("could not record the owner of aircraft {id}: {e}");
+        if let Err(e) = repos.aircraft.delete(id.clone()).await {
+            rest_error!("could not remove aircraft {id} without owner: {e}");
+        }
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, Json(vec![])));
+    }
+
     rest_info!("registration success.");
     rest_debug!("new aircraft: {:?}", id);
 
@@ -292,33 +798,66 @@ pub async fn register_aircraft(
     path = "/assets/demo/aircraft",
     tag = "svc-assets",
     responses(
-        (status = 200, description = "Assets successfully found", body = [Aircraft]),
+        (status = 200, description = "Assets successfully found; the `x-dropped-records` header counts the stored objects which could not be converted, a `Warning` header reports a list larger than `rest_list_warn_results`", body = [Aircraft]),
+        (status = 207, description = "Stored objects could not be converted in report conversion mode; the converted assets are returned with the failed objects", body = PartialList, content_type = "application/problem+json"),
+        (status = 400, description = "Invalid time filters"),
+        (status = 422, description = "More assets match the query than `rest_list_max_results`", body = [FieldError]),
+        (status = 502, description = "Stored objects could not be converted in strict conversion mode", body = SkippedObjects),
         (status = 503, description = "Could not connect to other microservice dependencies")
     ),
+    params(ListAssetsQuery)
 )]
-/// Get all aircraft from the database.
+/// Get all aircraft from the database, optionally filtered by their
+/// creation and update times and by their owner.
 pub async fn get_all_aircraft(
-    Extension(grpc_clients): Extension<GrpcClients>,
-) -> Result<Json<Vec<Aircraft>>, StatusCode> {
+    Extension(repos): Extension<Repositories>,
+    Extension(overdue): Extension<OverdueAircraft>,
+    Extension(holds): Extension<MaintenanceHolds>,
+    Extension(statuses): Extension<AircraftStatuses>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(removed): Extension<RemovedAssets>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Query(query): Query<ListAssetsQuery>,
+) -> Result<Listed<Aircraft>, ListError> {
     rest_info!("entry.");
+    rest_debug!("Query: {:?}", query);
+
+    let limits = config.read(ListLimits::from_config);
+    let filter = owner_filter(&query, list_filter(&query)?, "vehicle_id", |owner_id| {
+        quotas.assets(owner_id, QuotaKind::Aircraft)
+    });
+    let objects = match filter {
+        Some(filter) => repos
+            .aircraft
+            .search(limits.limit_filter(filter))
+            .await
+            .map_err(|e| {
+                rest_error!("could not retrieve vehicles: {e}.");
+                storage_error_status(&e)
+            })?,
+        None => vec![],
+    };
+    limits.check(objects.len())?;
 
-    let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
-    let assets = grpc_clients
-        .storage
-        .vehicle
-        .search(filter)
-        .await
-        .map_err(|e| {
-            rest_error!("could not retrieve vehicles: {e}.");
-            StatusCode::NOT_FOUND
-        })?
-        .into_inner()
-        .list
+    let mut listed: Listed<Aircraft> =
+        convert_objects(objects, config.read(|c| c.conversion_mode))?;
+    listed.assets = listed
+        .assets
         .into_iter()
-        .filter_map(|object| object.try_into().ok())
+        .map(|aircraft| aircraft.apply_owner(&quotas))
+        .filter(|aircraft| matches_list_query(&query, aircraft))
+        .map(|aircraft| {
+            aircraft
+                .apply_status(&statuses)
+                .apply_maintenance_status(&overdue, &holds)
+        })
         .collect();
+    for aircraft in &mut listed.assets {
+        aircraft.basics.deleted_at = removed.removed_at(&aircraft.basics.id);
+    }
 
-    Ok(Json(assets))
+    limits.warn(&mut listed);
+    Ok(listed)
 }
 
 /// Get an [`Aircraft`] by its id.
@@ -337,7 +876,11 @@ pub async fn get_all_aircraft(
     )
 )]
 pub async fn get_aircraft_by_id(
-    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(repos): Extension<Repositories>,
+    Extension(overdue): Extension<OverdueAircraft>,
+    Extension(holds): Extension<MaintenanceHolds>,
+    Extension(statuses): Extension<AircraftStatuses>,
+    Extension(quotas): Extension<OperatorQuotas>,
     Path(aircraft_id): Path<String>,
 ) -> Result<Json<Aircraft>, StatusCode> {
     rest_info!("entry [{}].", aircraft_id);
@@ -348,319 +891,2836 @@ pub async fn get_aircraft_by_id(
         })?
         .to_string(); // Check if the aircraft_id is a valid UUID (v4
 
-    let aircraft: Aircraft = grpc_clients
-        .storage
-        .vehicle
-        .get_by_id(Id { id })
+    let aircraft: Aircraft = repos
+        .aircraft
+        .get_by_id(id)
         .await
         .map_err(|e| {
             rest_error!("could not retrieve aircraft: {e}");
-            StatusCode::NOT_FOUND
+            storage_error_status(&e)
         })?
-        .into_inner()
         .try_into()
         .map_err(|e| {
             rest_error!("could not convert vehicle::Object to Aircraft: {e}");
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    Ok(Json(aircraft))
+    Ok(Json(
+        aircraft
+            .apply_owner(&quotas)
+            .apply_status(&statuses)
+            .apply_maintenance_status(&overdue, &holds),
+    ))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::rest::structs::AssetsInfo;
-    use lib_common::logger::get_log_handle;
-    use lib_common::uuid::Uuid;
+/// Check if a [`Aircraft`] exists.
+///
+/// Returns the `ETag` and `Last-Modified` headers of the aircraft without a
+/// body.
+#[utoipa::path(
+    head,
+    path = "/assets/aircraft/{id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Aircraft {id} exists"),
+        (status = 404, description = "Aircraft not found in database"),
+        (status = 400, description = "Invalid aircraft id"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Aircraft id"),
+    )
+)]
+pub async fn head_aircraft_by_id(
+    Extension(repos): Extension<Repositories>,
+    Path(aircraft_id): Path<String>,
+) -> Result<AssetHeaders, StatusCode> {
+    rest_info!("entry [{}].", aircraft_id);
+    let id = to_uuid(&aircraft_id)
+        .ok_or_else(|| {
+            rest_error!("Invalid aircraft id: {}", aircraft_id);
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
 
-    #[test]
-    fn test_aircraft_from_vehicle_object() {
-        let expected_vehicle_id = Uuid::new_v4();
-        let mut vehicle_data: vehicle::Data = vehicle::mock::get_data_obj();
-        let mut vehicle_obj = vehicle::Object {
-            id: expected_vehicle_id.clone().to_string(),
-            data: Some(vehicle_data.clone()),
-        };
-        let aircraft_result = vehicle_obj.clone().try_into();
-        assert!(aircraft_result.is_ok());
-        let aircraft: Aircraft = aircraft_result.unwrap();
+    let aircraft: Aircraft = repos
+        .aircraft
+        .get_by_id(id)
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve aircraft: {e}");
+            storage_error_status(&e)
+        })?
+        .try_into()
+        .map_err(|e| {
+            rest_error!("could not convert aircraft: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
-        assert_eq!(aircraft.id(), Ok(expected_vehicle_id));
-        assert!(aircraft.name().len() > 0);
-        assert_eq!(
-            aircraft.created_at(),
-            vehicle_data
-                .created_at
-                .clone()
-                .expect("mock didn't return a created_at date.")
-                .into()
-        );
-        assert_eq!(
-            aircraft.updated_at(),
-            vehicle_data
-                .updated_at
-                .clone()
-                .expect("mock didn't return a updated_at date.")
-                .into()
-        );
-        assert_eq!(aircraft.is_grouped(), false);
-        assert_eq!(aircraft.is_public(), true);
-        assert_eq!(aircraft.status(), AssetStatus::Available);
+    Ok(asset_headers(aircraft.basics.updated_at))
+}
 
-        vehicle_obj.data = None;
-        let result: Result<Aircraft, String> = vehicle_obj.clone().try_into();
-        assert_eq!(result.unwrap_err(), "(try_from) vehicle data is missing");
+/// Get multiple [`Aircraft`]s by their ids.
+///
+/// The aircraft are fetched concurrently. Ids which could not be found are
+/// returned in the `missing` list, objects which could not be converted in
+/// the `failed` list.
+#[utoipa::path(
+    post,
+    path = "/assets/aircraft/batch-get",
+    tag = "svc-assets",
+    request_body = BatchGetPayload,
+    responses(
+        (status = 200, description = "Aircrafts found and ids missing from database", body = AircraftBatch),
+        (status = 400, description = "Invalid ids or too many ids requested"),
+        (status = 502, description = "Stored objects could not be converted in strict conversion mode", body = SkippedObjects),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    )
+)]
+pub async fn batch_get_aircraft(
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+    Extension(overdue): Extension<OverdueAircraft>,
+    Extension(holds): Extension<MaintenanceHolds>,
+    Extension(statuses): Extension<AircraftStatuses>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Json(payload): Json<BatchGetPayload>,
+) -> Result<Json<AircraftBatch>, ListError> {
+    rest_info!("entry.");
+    rest_debug!("Payload: {:?}", &payload);
 
-        vehicle_data.updated_at = None;
-        vehicle_obj.data = Some(vehicle_data.clone());
-        let result: Result<Aircraft, String> = vehicle_obj.clone().try_into();
-        assert_eq!(
-            result.unwrap_err(),
-            "(try_from) updated_at field empty but expected."
-        );
+    let ids = parse_batch_ids(payload)?;
+    let mode = config.read(|c| c.conversion_mode);
+    let mut response: AircraftBatch = batch_get(ids, mode, |id| {
+        let repos = repos.clone();
+        let fan_out = fan_out.clone();
+        async move {
+            match fan_out.call(repos.aircraft.get_by_id(id)).await {
+                Ok(object) => Ok(Some(object)),
+                Err(e) => match storage_error_status(&e) {
+                    StatusCode::NOT_FOUND => Ok(None),
+                    status => {
+                        rest_error!("could not retrieve aircraft: {e}");
+                        Err(status)
+                    }
+                },
+            }
+        }
+    })
+    .await?;
+    response.found = response
+        .found
+        .into_iter()
+        .map(|aircraft| {
+            aircraft
+                .apply_owner(&quotas)
+                .apply_status(&statuses)
+                .apply_maintenance_status(&overdue, &holds)
+        })
+        .collect();
 
-        vehicle_data.created_at = None;
-        vehicle_data.updated_at = Some(Utc::now().into());
+    rest_debug!(
+        "found {}, missing {}, failed {}.",
+        response.found.len(),
+        response.missing.len(),
+        response.failed.len()
+    );
+    Ok(Json(response))
+}
+
+/// Maximum number of aircraft changed by a single batch status change
+pub const MAX_STATUS_BATCH_SIZE: usize = 500;
+
+/// The aircraft selected by a [`BatchAircraftStatusPayload`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatusBatchTarget {
+    /// The aircraft with the provided ids, without duplicates
+    Ids(Vec<String>),
+    /// The aircraft matching all criteria of the filter
+    Filter(AircraftStatusFilter),
+}
+
+/// Validate a batch status change, normalizing the ids.
+///
+/// Exactly one of `ids` and `filter` must be provided. A filter must contain
+/// at least one criterion, so a single request can not change the status of
+/// all aircraft.
+pub fn validate_status_batch(
+    payload: &BatchAircraftStatusPayload,
+) -> Result<StatusBatchTarget, Vec<FieldError>> {
+    let mut errors = vec![];
+    match (&payload.ids, &payload.filter) {
+        (Some(ids), None) => {
+            if ids.is_empty() || ids.len() > MAX_STATUS_BATCH_SIZE {
+                errors.push(field_error(
+                    "ids",
+                    &format!("must contain between 1 and {MAX_STATUS_BATCH_SIZE} ids"),
+                ));
+            }
+
+            let mut unique: Vec<String> = Vec::with_capacity(ids.len());
+            for (index, id) in ids.iter().enumerate() {
+                match to_uuid(id) {
+                    Some(id) if !unique.contains(&id.to_string()) => unique.push(id.to_string()),
+                    Some(_) => (),
+                    None => errors.push(field_error(
+                        &format!("ids[{index}]"),
+                        "must be a valid UUID",
+                    )),
+                }
+            }
+
+            into_result(StatusBatchTarget::Ids(unique), errors)
+        }
+        (None, Some(filter)) => {
+            let criteria = [
+                ("filter.hangar_id", &filter.hangar_id),
+                ("filter.asset_group_id", &filter.asset_group_id),
+                ("filter.vehicle_model_id", &filter.vehicle_model_id),
+            ];
+            if criteria.iter().all(|(_, value)| value.is_none()) {
+                errors.push(field_error("filter", "must contain at least one criterion"));
+            }
+            for (field, value) in criteria {
+                if let Some(value) = value {
+                    check_uuid(&mut errors, field, value);
+                }
+            }
+
+            into_result(StatusBatchTarget::Filter(filter.clone()), errors)
+        }
+        _ => Err(vec![field_error(
+            "ids",
+            "exactly one of ids and filter must be provided",
+        )]),
+    }
+}
+
+/// Check if an aircraft matches all criteria of a status filter
+pub fn matches_status_filter(filter: &AircraftStatusFilter, aircraft: &Aircraft) -> bool {
+    filter
+        .hangar_id
+        .as_ref()
+        .map_or(true, |id| aircraft.hangar_id.as_ref() == Some(id))
+        && filter
+            .asset_group_id
+            .as_ref()
+            .map_or(true, |id| aircraft.basics.group_id.as_ref() == Some(id))
+        && filter
+            .vehicle_model_id
+            .as_ref()
+            .map_or(true, |id| &aircraft.model == id)
+}
+
+/// Get the ids of the aircraft matching a status filter.
+///
+/// Returns `UNPROCESSABLE_ENTITY` if more than [`MAX_STATUS_BATCH_SIZE`]
+/// aircraft match.
+async fn status_filter_ids(
+    repos: &Repositories,
+    filter: &AircraftStatusFilter,
+) -> Result<Vec<String>, ValidationError> {
+    let mut search = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+    if let Some(id) = &filter.hangar_id {
+        search = search.and_equals("hangar_id".to_string(), id.clone());
+    }
+    if let Some(id) = &filter.asset_group_id {
+        search = search.and_equals("asset_group_id".to_string(), id.clone());
+    }
+    if let Some(id) = &filter.vehicle_model_id {
+        search = search.and_equals("vehicle_model_id".to_string(), id.clone());
+    }
+
+    let ids: Vec<String> = repos
+        .aircraft
+        .search(search)
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve aircraft: {e}.");
+            (storage_error_status(&e), Json(vec![]))
+        })?
+        .into_iter()
+        .filter_map(|object| Aircraft::try_from(object).ok())
+        .filter(|aircraft| matches_status_filter(filter, aircraft))
+        .map(|aircraft| aircraft.basics.id)
+        .collect();
+
+    if ids.len() > MAX_STATUS_BATCH_SIZE {
+        rest_info!("status filter matches {} aircraft.", ids.len());
+        return Err(unprocessable(vec![field_error(
+            "filter",
+            &format!("matches more than {MAX_STATUS_BATCH_SIZE} aircraft"),
+        )]));
+    }
+
+    Ok(ids)
+}
+
+/// Set the status of an aircraft, returning its result in a status batch
+fn set_batch_status(
+    statuses: &AircraftStatuses,
+    id: String,
+    status: AssetStatus,
+) -> AircraftStatusResult {
+    match statuses.set(&id, status) {
+        Ok(()) => AircraftStatusResult {
+            id,
+            status: StatusCode::OK.as_u16(),
+            message: None,
+        },
+        Err(e) => {
+            rest_error!("could not persist the status of aircraft [{}]: {e}", id);
+            AircraftStatusResult {
+                id,
+                status: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                message: Some(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Set the status of multiple [`Aircraft`]s, selected by their ids or by a
+/// filter.
+///
+/// The aircraft selected by id are looked up concurrently; the result of
+/// each aircraft is returned in the order of the selection, so a missing
+/// aircraft does not fail the other changes. The statuses are persisted,
+/// see [`crate::aircraft_status`].
+#[utoipa::path(
+    post,
+    path = "/assets/aircraft/status-batch",
+    tag = "svc-assets",
+    request_body = BatchAircraftStatusPayload,
+    responses(
+        (status = 200, description = "Result of the status change per aircraft", body = [AircraftStatusResult]),
+        (status = 422, description = "Invalid selection or too many aircraft selected", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    )
+)]
+pub async fn batch_set_aircraft_status(
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+    Extension(statuses): Extension<AircraftStatuses>,
+    Json(payload): Json<BatchAircraftStatusPayload>,
+) -> Result<Json<Vec<AircraftStatusResult>>, ValidationError> {
+    rest_info!("entry.");
+    rest_debug!("Payload: {:?}", &payload);
+
+    let status: AssetStatus = payload.status.into();
+    let results = match validate_status_batch(&payload).map_err(unprocessable)? {
+        StatusBatchTarget::Filter(filter) => status_filter_ids(&repos, &filter)
+            .await?
+            .into_iter()
+            .map(|id| set_batch_status(&statuses, id, status))
+            .collect(),
+        StatusBatchTarget::Ids(ids) => {
+            join_all(ids.into_iter().map(|id| {
+                let repos = repos.clone();
+                let fan_out = fan_out.clone();
+                let statuses = statuses.clone();
+                async move {
+                    match fan_out.call(repos.aircraft.get_by_id(id.clone())).await {
+                        Ok(_) => set_batch_status(&statuses, id, status),
+                        Err(e) => {
+                            rest_warn!("could not set status of aircraft [{}]: {e}", id);
+                            AircraftStatusResult {
+                                id,
+                                status: storage_error_status(&e).as_u16(),
+                                message: Some(e.message().to_string()),
+                            }
+                        }
+                    }
+                }
+            }))
+            .await
+        }
+    };
+
+    rest_info!(
+        "set status {:?} of {} aircraft.",
+        status,
+        results
+            .iter()
+            .filter(|result| result.status == StatusCode::OK.as_u16())
+            .count()
+    );
+    Ok(Json(results))
+}
+
+/// Update/modify a [`Aircraft`] in the database using a JSON Merge Patch (RFC 7396).
+///
+/// Only the fields present in the patch are updated, fields set to `null`
+/// are removed.
+#[utoipa::path(
+    patch,
+    path = "/assets/aircraft/{id}",
+    tag = "svc-assets",
+    request_body(content = Object, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "Aircraft updated in database, `changed` is `false` if nothing differed", body = UpdateResult),
+        (status = 400, description = "Invalid aircraft id or `x-operator-id` header, or no operator while assigning an asset group"),
+        (status = 404, description = "Aircraft not found in database"),
+        (status = 409, description = "Hangar bay occupied by another aircraft"),
+        (status = 422, description = "Patch can not be applied, or the asset group or hangar bay can not be assigned; a list of field errors is returned", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    ),
+    params(
+        ("id" = String, Path, description = "Aircraft id"),
+    )
+)]
+pub async fn patch_aircraft(
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+    Extension(delegations): Extension<GroupDelegations>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(patch): Json<serde_json::Value>,
+) -> Result<Json<UpdateResult>, ValidationError> {
+    rest_info!("entry [{}].", id);
+    rest_debug!("Patch: {:?}", &patch);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid aircraft id.");
+            (StatusCode::BAD_REQUEST, Json(vec![]))
+        })?
+        .to_string();
+
+    let payload: UpdateAircraftPayload =
+        merge_patch_payload(&id, patch, AIRCRAFT_PATCH_FIELDS, AIRCRAFT_NULLABLE_FIELDS)
+            .map_err(unprocessable)?;
+
+    update_aircraft(
+        Extension(repos),
+        Extension(fan_out),
+        Extension(delegations),
+        Extension(quotas),
+        headers,
+        Json(payload),
+    )
+    .await
+}
+
+/// Longest delay of a flight past its planned timeslots, in hours, accounted
+/// for when searching the flight plans of a time range
+const FLIGHT_DELAY_MARGIN_HOURS: i64 = 24;
+
+/// Filter the flight plans of an aircraft which may overlap the range from
+/// `from` to `to`, or which did not end before `from` if there is no `to`.
+///
+/// The actual times of a flight are only known once it departed, so the
+/// planned timeslots are compared with a margin of
+/// [`FLIGHT_DELAY_MARGIN_HOURS`]. The flight windows are compared with the
+/// range again once the plans are retrieved.
+pub fn aircraft_flight_plans_filter(
+    aircraft_id: &str,
+    from: DateTime<Utc>,
+    to: Option<DateTime<Utc>>,
+) -> AdvancedSearchFilter {
+    let margin = chrono::Duration::hours(FLIGHT_DELAY_MARGIN_HOURS);
+    let filter =
+        AdvancedSearchFilter::search_equals("vehicle_id".to_string(), aircraft_id.to_string())
+            .and_is_null("deleted_at".to_string())
+            .and_greater(
+                "target_timeslot_end".to_string(),
+                (from - margin).to_rfc3339(),
+            );
+
+    match to {
+        Some(to) => filter.and_less(
+            "origin_timeslot_start".to_string(),
+            (to + margin).to_rfc3339(),
+        ),
+        None => filter,
+    }
+}
+
+/// Get the flight window of a flight plan.
+///
+/// Uses the actual departure and arrival times if known, the planned
+/// timeslots otherwise. Returns [`None`] for cancelled or draft flight plans.
+fn flight_window(plan: &flight_plan::Data) -> Option<TimeWindow> {
+    if plan.flight_status == flight_plan::FlightStatus::Cancelled as i32
+        || plan.flight_status == flight_plan::FlightStatus::Draft as i32
+    {
+        return None;
+    }
+
+    let start = plan
+        .actual_departure_time
+        .clone()
+        .or_else(|| plan.origin_timeslot_start.clone())?;
+    let end = plan
+        .actual_arrival_time
+        .clone()
+        .or_else(|| plan.target_timeslot_end.clone())?;
+
+    let window = TimeWindow {
+        start: start.into(),
+        end: end.into(),
+    };
+    (window.end > window.start).then_some(window)
+}
+
+/// Compute the utilization of an aircraft within the provided range.
+///
+/// Overlapping flights are only counted once for the flight hours, a cycle
+/// is counted for each flight departing within the range.
+pub fn compute_utilization(
+    aircraft_id: &str,
+    plans: &[flight_plan::Data],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> AircraftUtilization {
+    let flights: Vec<TimeWindow> = plans.iter().filter_map(flight_window).collect();
+
+    let cycles = flights
+        .iter()
+        .filter(|flight| flight.start >= from && flight.start < to)
+        .count() as u32;
+
+    let clipped = flights
+        .into_iter()
+        .filter(|flight| flight.end > from && flight.start < to)
+        .map(|flight| TimeWindow {
+            start: flight.start.max(from),
+            end: flight.end.min(to),
+        })
+        .collect();
+
+    let flight_seconds: i64 = merge_windows(clipped)
+        .iter()
+        .map(|flight| (flight.end - flight.start).num_seconds())
+        .sum();
+    let total_seconds = (to - from).num_seconds();
+
+    AircraftUtilization {
+        aircraft_id: aircraft_id.to_string(),
+        from,
+        to,
+        flight_hours: flight_seconds as f64 / 3600.0,
+        cycles,
+        idle_hours: (total_seconds - flight_seconds) as f64 / 3600.0,
+    }
+}
+
+/// Get the utilization of an [`Aircraft`] within a time range.
+///
+/// Computes flight hours, cycles and idle time from the aircraft's flight
+/// plans overlapping the range.
+#[utoipa::path(
+    get,
+    path = "/assets/aircraft/{id}/utilization",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Utilization of aircraft {id}", body = AircraftUtilization),
+        (status = 400, description = "Invalid aircraft id or time range"),
+        (status = 404, description = "Aircraft not found in database"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Aircraft id"),
+        AvailabilityQuery,
+    )
+)]
+pub async fn get_aircraft_utilization(
+    Extension(repos): Extension<Repositories>,
+    Path(aircraft_id): Path<String>,
+    Query(query): Query<AvailabilityQuery>,
+) -> Result<Json<AircraftUtilization>, StatusCode> {
+    rest_info!("entry [{}].", aircraft_id);
+    rest_debug!("Query: {:?}", query);
+
+    let id = to_uuid(&aircraft_id)
+        .ok_or_else(|| {
+            rest_error!("Invalid aircraft id.");
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    if query.to <= query.from
+        || query.to - query.from > chrono::Duration::days(MAX_UTILIZATION_RANGE_DAYS)
+    {
+        rest_error!(
+            "invalid time range, 'to' must be after 'from' and the range can not exceed {} days.",
+            MAX_UTILIZATION_RANGE_DAYS
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    // Make sure the aircraft exists
+    repos.aircraft.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not retrieve aircraft: {e}");
+        storage_error_status(&e)
+    })?;
+
+    let filter = aircraft_flight_plans_filter(&id, query.from, Some(query.to));
+    let plans: Vec<flight_plan::Data> = repos
+        .flight_plans
+        .search(filter)
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve flight plans: {e}");
+            storage_error_status(&e)
+        })?
+        .into_iter()
+        .filter_map(|object| object.data)
+        // The filter might not be applied by all storage backends
+        .filter(|data| data.vehicle_id == id)
+        .collect();
+
+    rest_debug!("found {} flight plans.", plans.len());
+    Ok(Json(compute_utilization(&id, &plans, query.from, query.to)))
+}
+
+/// Validate and normalize a hangar assignment payload.
+pub fn validate_assign_hangar_payload(
+    mut payload: AssignHangarPayload,
+) -> Result<AssignHangarPayload, Vec<FieldError>> {
+    let mut errors = vec![];
+    normalize_string(&mut payload.hangar_id);
+    normalize_string(&mut payload.hangar_bay_id);
+    check_uuid(&mut errors, "hangar_id", &payload.hangar_id);
+    check_uuid(&mut errors, "hangar_bay_id", &payload.hangar_bay_id);
+
+    into_result(payload, errors)
+}
+
+/// Check if a hangar bay exists and belongs to the hangar.
+async fn check_hangar_bay(
+    repos: &Repositories,
+    hangar_id: &str,
+    hangar_bay_id: &str,
+) -> Result<Option<FieldError>, StatusCode> {
+    let bay = match repos.vertipads.get_by_id(hangar_bay_id.to_string()).await {
+        Ok(bay) => bay,
+        Err(e) if e.code() == tonic::Code::NotFound => {
+            return Ok(Some(field_error(
+                "hangar_bay_id",
+                "hangar bay does not exist",
+            )));
+        }
+        Err(e) => {
+            rest_error!("could not retrieve hangar bay: {e}");
+            return Err(storage_error_status(&e));
+        }
+    };
+
+    let in_hangar = bay
+        .data
+        .map(|data| data.vertiport_id == hangar_id)
+        .unwrap_or(false);
+    Ok((!in_hangar).then(|| {
+        field_error(
+            "hangar_bay_id",
+            &format!("is not a bay of hangar {hangar_id}"),
+        )
+    }))
+}
+
+/// Get the id of another aircraft assigned to a hangar bay, if any.
+async fn hangar_bay_occupant(
+    repos: &Repositories,
+    aircraft_id: &str,
+    hangar_bay_id: &str,
+) -> Result<Option<String>, StatusCode> {
+    let filter =
+        AdvancedSearchFilter::search_equals("hangar_bay_id".to_string(), hangar_bay_id.to_string())
+            .and_is_null("deleted_at".to_string());
+
+    let occupant = repos
+        .aircraft
+        .search(filter)
+        .await
+        .map_err(|e| {
+            rest_error!("could not search vehicles: {e}.");
+            storage_error_status(&e)
+        })?
+        .into_iter()
+        // The filter might not be applied by all storage backends
+        .find(|object| {
+            object.id != aircraft_id
+                && object.data.as_ref().map_or(false, |data| {
+                    data.hangar_bay_id.as_deref() == Some(hangar_bay_id)
+                })
+        })
+        .map(|object| object.id);
+
+    Ok(occupant)
+}
+
+/// Check if an aircraft can be assigned to a hangar bay.
+///
+/// The bay must exist and belong to the hangar, and a bay holds a single
+/// aircraft, so the assignment conflicts with another aircraft assigned to
+/// the bay.
+async fn check_hangar_assignment(
+    repos: &Repositories,
+    aircraft_id: &str,
+    hangar_id: &str,
+    hangar_bay_id: &str,
+) -> Result<(), ValidationError> {
+    if let Some(error) = check_hangar_bay(repos, hangar_id, hangar_bay_id)
+        .await
+        .map_err(|status| (status, Json(vec![])))?
+    {
+        return Err(unprocessable(vec![error]));
+    }
+
+    if let Some(occupant) = hangar_bay_occupant(repos, aircraft_id, hangar_bay_id)
+        .await
+        .map_err(|status| (status, Json(vec![])))?
+    {
+        rest_warn!(
+            "hangar bay {} is occupied by aircraft {}.",
+            hangar_bay_id,
+            occupant
+        );
+        return Err((
+            StatusCode::CONFLICT,
+            Json(vec![field_error(
+                "hangar_bay_id",
+                &format!("occupied by aircraft {occupant}"),
+            )]),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Assign an [`Aircraft`] to a hangar bay.
+///
+/// The hangar is a vertiport and the bay one of its vertipads. A bay holds a
+/// single aircraft, so the assignment is rejected if another aircraft is
+/// assigned to the bay.
+#[utoipa::path(
+    post,
+    path = "/assets/aircraft/{id}/assign-hangar",
+    tag = "svc-assets",
+    request_body = AssignHangarPayload,
+    responses(
+        (status = 200, description = "Aircraft assigned to the hangar bay"),
+        (status = 400, description = "Invalid aircraft id"),
+        (status = 404, description = "Aircraft not found in database"),
+        (status = 409, description = "Hangar bay occupied by another aircraft; the error names the aircraft", body = [FieldError]),
+        (status = 422, description = "Invalid ids, or the hangar bay does not exist or is not a bay of the hangar", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Aircraft id"),
+    )
+)]
+pub async fn assign_aircraft_hangar(
+    Extension(repos): Extension<Repositories>,
+    Path(id): Path<String>,
+    Json(payload): Json<AssignHangarPayload>,
+) -> Result<(), ValidationError> {
+    rest_info!("entry [{}].", id);
+    rest_debug!("Payload: {:?}", &payload);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid aircraft id.");
+            (StatusCode::BAD_REQUEST, Json(vec![]))
+        })?
+        .to_string();
+    let payload = validate_assign_hangar_payload(payload).map_err(unprocessable)?;
+
+    let mut vehicle_data = repos
+        .aircraft
+        .get_by_id(id.clone())
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve aircraft: {e}");
+            (storage_error_status(&e), Json(vec![]))
+        })?
+        .data
+        .ok_or_else(|| {
+            rest_error!("vehicle data is missing.");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+        })?;
+
+    check_hangar_assignment(&repos, &id, &payload.hangar_id, &payload.hangar_bay_id).await?;
+
+    vehicle_data.hangar_id = Some(payload.hangar_id.clone());
+    vehicle_data.hangar_bay_id = Some(payload.hangar_bay_id.clone());
+    let object = vehicle::UpdateObject {
+        id: id.clone(),
+        data: Some(vehicle_data),
+        mask: Some(FieldMask {
+            paths: vec!["hangar_id".to_string(), "hangar_bay_id".to_string()],
+        }),
+    };
+
+    repos.aircraft.update(object).await.map_err(|e| {
+        rest_error!("could not update vehicle: {e}.");
+        (storage_error_status(&e), Json(vec![]))
+    })?;
+
+    rest_info!(
+        "aircraft assigned to hangar bay {} of hangar {}.",
+        payload.hangar_bay_id,
+        payload.hangar_id
+    );
+    Ok(())
+}
+
+/// Hold an [`Aircraft`] for maintenance.
+///
+/// The aircraft is reported `Unavailable` while the hold is active. Holds
+/// are persisted by this service, see [`crate::hold`].
+#[utoipa::path(
+    post,
+    path = "/assets/aircraft/{id}/hold",
+    tag = "svc-assets",
+    request_body = MaintenanceHoldPayload,
+    responses(
+        (status = 200, description = "Aircraft held for maintenance; the hold is returned", body = MaintenanceHold),
+        (status = 400, description = "Invalid aircraft id"),
+        (status = 404, description = "Aircraft not found in database"),
+        (status = 409, description = "Hold overlaps an existing hold of the aircraft", body = [FieldError]),
+        (status = 422, description = "Invalid time range", body = [FieldError]),
+        (status = 500, description = "The hold could not be persisted"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Aircraft id"),
+    )
+)]
+pub async fn create_aircraft_hold(
+    Extension(repos): Extension<Repositories>,
+    Extension(holds): Extension<MaintenanceHolds>,
+    Path(id): Path<String>,
+    Json(mut payload): Json<MaintenanceHoldPayload>,
+) -> Result<Json<MaintenanceHold>, ValidationError> {
+    rest_info!("entry [{}].", id);
+    rest_debug!("Payload: {:?}", &payload);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid aircraft id.");
+            (StatusCode::BAD_REQUEST, Json(vec![]))
+        })?
+        .to_string();
+    normalize_optional_string(&mut payload.reason);
+
+    // Make sure the aircraft exists
+    repos.aircraft.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not retrieve aircraft: {e}");
+        (storage_error_status(&e), Json(vec![]))
+    })?;
+
+    let hold = holds.add(&id, payload, Utc::now()).map_err(|e| {
+        rest_error!("could not hold aircraft: {e}");
+        match e {
+            HoldError::Overlap(_) => (
+                StatusCode::CONFLICT,
+                Json(vec![field_error("start", &e.to_string())]),
+            ),
+            HoldError::State(_) => (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![])),
+            _ => unprocessable(vec![field_error("end", &e.to_string())]),
+        }
+    })?;
+
+    rest_info!("aircraft held for maintenance [{}].", hold.id);
+    Ok(Json(hold))
+}
+
+/// Get the maintenance holds of an [`Aircraft`] which did not end yet.
+#[utoipa::path(
+    get,
+    path = "/assets/aircraft/{id}/hold",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Holds of aircraft {id}, ordered by start", body = [MaintenanceHold]),
+        (status = 400, description = "Invalid aircraft id"),
+    ),
+    params(
+        ("id" = String, Path, description = "Aircraft id"),
+    )
+)]
+pub async fn get_aircraft_holds(
+    Extension(holds): Extension<MaintenanceHolds>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<MaintenanceHold>>, StatusCode> {
+    rest_info!("entry [{}].", id);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid aircraft id.");
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    Ok(Json(holds.list(&id, Utc::now())))
+}
+
+/// Cancel a maintenance hold of an [`Aircraft`].
+#[utoipa::path(
+    delete,
+    path = "/assets/aircraft/{id}/hold/{hold_id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Hold cancelled"),
+        (status = 400, description = "Invalid aircraft or hold id"),
+        (status = 404, description = "Hold not found"),
+        (status = 500, description = "The cancellation could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Aircraft id"),
+        ("hold_id" = String, Path, description = "Hold id"),
+    )
+)]
+pub async fn cancel_aircraft_hold(
+    Extension(holds): Extension<MaintenanceHolds>,
+    Path((id, hold_id)): Path<(String, String)>,
+) -> Result<(), StatusCode> {
+    rest_info!("entry [{}] [{}].", id, hold_id);
+
+    let (Some(id), Some(hold_id)) = (to_uuid(&id), to_uuid(&hold_id)) else {
+        rest_error!("Invalid aircraft or hold id.");
+        return Err(StatusCode::BAD_REQUEST);
+    };
+
+    holds
+        .cancel(&id.to_string(), &hold_id.to_string())
+        .map_err(|e| {
+            rest_error!("could not cancel hold: {e}");
+            match e {
+                HoldError::State(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                _ => StatusCode::NOT_FOUND,
+            }
+        })?;
+
+    rest_info!("successfully cancelled hold.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operator_quotas() -> Extension<OperatorQuotas> {
+        Extension(OperatorQuotas::new(
+            crate::config::Config::default().into(),
+            AssetOwners::default(),
+        ))
+    }
+
+    /// Headers of a registration by a new operator
+    fn operator_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            OPERATOR_ID_HEADER,
+            Uuid::new_v4().to_string().parse().unwrap(),
+        );
+        headers
+    }
+
+    fn overdue_aircraft() -> Extension<OverdueAircraft> {
+        Extension(OverdueAircraft::default())
+    }
+
+    fn maintenance_holds() -> Extension<MaintenanceHolds> {
+        Extension(MaintenanceHolds::default())
+    }
+
+    fn shared_config() -> Extension<SharedConfig> {
+        Extension(crate::config::Config::default().into())
+    }
+
+    fn aircraft_statuses() -> Extension<AircraftStatuses> {
+        Extension(AircraftStatuses::default())
+    }
+
+    fn fan_out() -> Extension<FanOut> {
+        Extension(FanOut::new(crate::Config::default().into()))
+    }
+
+    fn group_delegations() -> Extension<GroupDelegations> {
+        Extension(GroupDelegations::default())
+    }
+    use crate::grpc::client::GrpcClients;
+    use crate::repo::memory::MemoryRepository;
+    use crate::rest::quota::{AssetOwners, OPERATOR_ID_HEADER};
+    use crate::rest::structs::AssetsInfo;
+    use crate::testing::{AircraftDataBuilder, VertipadDataBuilder, VertiportDataBuilder};
+    use lib_common::logger::get_log_handle;
+    use lib_common::uuid::Uuid;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_aircraft_from_vehicle_object() {
+        let expected_vehicle_id = Uuid::new_v4();
+        let mut vehicle_data: vehicle::Data = vehicle::mock::get_data_obj();
+        let mut vehicle_obj = vehicle::Object {
+            id: expected_vehicle_id.clone().to_string(),
+            data: Some(vehicle_data.clone()),
+        };
+        let aircraft_result = vehicle_obj.clone().try_into();
+        assert!(aircraft_result.is_ok());
+        let aircraft: Aircraft = aircraft_result.unwrap();
+
+        assert_eq!(aircraft.id(), Ok(expected_vehicle_id));
+        assert!(aircraft.name().len() > 0);
+        assert_eq!(
+            aircraft.created_at(),
+            vehicle_data
+                .created_at
+                .clone()
+                .expect("mock didn't return a created_at date.")
+                .into()
+        );
+        assert_eq!(
+            aircraft.updated_at(),
+            vehicle_data
+                .updated_at
+                .clone()
+                .expect("mock didn't return a updated_at date.")
+                .into()
+        );
+        assert_eq!(aircraft.is_grouped(), false);
+        assert_eq!(aircraft.is_public(), true);
+        assert_eq!(aircraft.status(), AssetStatus::Available);
+
+        vehicle_obj.data = None;
+        let result: Result<Aircraft, String> = vehicle_obj.clone().try_into();
+        assert_eq!(result.unwrap_err(), "(try_from) vehicle data is missing");
+
+        vehicle_data.updated_at = None;
+        vehicle_obj.data = Some(vehicle_data.clone());
+        let result: Result<Aircraft, String> = vehicle_obj.clone().try_into();
+        assert_eq!(
+            result.unwrap_err(),
+            "(try_from) updated_at field empty but expected."
+        );
+
+        vehicle_data.created_at = None;
+        vehicle_data.updated_at = Some(Utc::now().into());
         vehicle_obj.data = Some(vehicle_data.clone());
         let result: Result<Aircraft, String> = vehicle_obj.clone().try_into();
         assert_eq!(
-            result.unwrap_err(),
-            "(try_from) created_at field empty but expected."
+            result.unwrap_err(),
+            "(try_from) created_at field empty but expected."
+        );
+    }
+
+    #[test]
+    fn test_aircraft_basics_trait_methods() {
+        let aircraft = vehicle::mock::get_data_obj();
+        let basics = Basics {
+            id: Uuid::new_v4().to_string(),
+            name: Some("Test asset".to_string()),
+            group_id: Some(Uuid::new_v4().to_string()),
+            created_at: aircraft
+                .created_at
+                .expect("mock didn't return a created_at date.")
+                .into(),
+            updated_at: aircraft
+                .updated_at
+                .expect("mock didn't return a updated_at date.")
+                .into(),
+            deleted_at: None,
+            owner_id: None,
+            whitelist: vec![Uuid::new_v4().to_string()],
+            status: AssetStatus::Available,
+        };
+        let mut aircraft = Aircraft {
+            basics: basics.clone(),
+            manufacturer: "Airbus".to_string(),
+            model: "737-800".to_string(),
+            serial_number: aircraft.serial_number,
+            registration_number: aircraft.registration_number,
+            description: aircraft.description,
+            max_payload_kg: 1000.0,
+            max_range_km: 1000.0,
+            last_maintenance: None,
+            next_maintenance: None,
+            hangar_id: None,
+        };
+
+        assert_eq!(aircraft.id().unwrap(), Uuid::parse_str(&basics.id).unwrap());
+        assert_eq!(aircraft.name(), basics.name.clone().unwrap());
+        assert_eq!(aircraft.created_at(), basics.created_at);
+        assert_eq!(aircraft.updated_at(), basics.updated_at);
+        assert_eq!(aircraft.is_grouped(), true);
+        assert_eq!(aircraft.is_public(), false);
+        assert_eq!(aircraft.status(), basics.status);
+
+        let manufacturer = "Airbus";
+        let model = "A320";
+        aircraft.basics.name = None;
+        aircraft.basics.group_id = None;
+        aircraft.manufacturer = manufacturer.to_string();
+        aircraft.model = model.to_string();
+        assert_eq!(
+            aircraft.full_name(),
+            format!(
+                "{} {} {}",
+                manufacturer, model, aircraft.registration_number
+            )
+        );
+        assert_eq!(aircraft.group_id(), Ok(None));
+        assert_eq!(aircraft.is_grouped(), false);
+    }
+
+    #[tokio::test]
+    async fn test_get_all_aircraft() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        let payload = AircraftDataBuilder::new().build();
+
+        let id = register_aircraft(
+            Extension(repos.clone()),
+            operator_quotas(),
+            operator_headers(),
+            Json(payload),
+        )
+        .await
+        .unwrap();
+
+        ut_info!("Success: {:#?}", id);
+
+        let result = get_all_aircraft(
+            Extension(repos),
+            overdue_aircraft(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            shared_config(),
+            Extension(RemovedAssets::default()),
+            operator_quotas(),
+            Query(ListAssetsQuery::default()),
+        )
+        .await
+        .unwrap();
+        ut_info!("Success: {:#?}", result.assets);
+        // assert!(!result.0.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_all_aircraft_by_owner() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let repos = Repositories::memory();
+        let quotas = operator_quotas();
+        let headers = operator_headers();
+        let owner_id = operator_id_from_headers(&headers).unwrap();
+        let id = register_aircraft(
+            Extension(repos.clone()),
+            quotas.clone(),
+            headers,
+            Json(AircraftDataBuilder::new().build()),
+        )
+        .await
+        .unwrap();
+        register_aircraft(
+            Extension(repos.clone()),
+            quotas.clone(),
+            operator_headers(),
+            Json(AircraftDataBuilder::new().build()),
+        )
+        .await
+        .unwrap();
+        // created outside of this service, without a known owner
+        repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap();
+
+        let list = |owner_id: Option<String>| {
+            get_all_aircraft(
+                Extension(repos.clone()),
+                overdue_aircraft(),
+                maintenance_holds(),
+                aircraft_statuses(),
+                shared_config(),
+                Extension(RemovedAssets::default()),
+                quotas.clone(),
+                Query(ListAssetsQuery {
+                    owner_id,
+                    ..Default::default()
+                }),
+            )
+        };
+
+        let all = list(None).await.unwrap().assets;
+        assert_eq!(all.len(), 3);
+        assert_eq!(
+            all.iter()
+                .filter(|aircraft| aircraft.basics.owner_id.is_none())
+                .count(),
+            1
+        );
+
+        let owned = list(owner_id.clone()).await.unwrap().assets;
+        assert_eq!(owned.len(), 1);
+        assert_eq!(owned[0].basics.id, id);
+        assert_eq!(owned[0].basics.owner_id, owner_id);
+
+        let none = list(Some(Uuid::new_v4().to_string())).await.unwrap().assets;
+        assert!(none.is_empty());
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_get_aircraft_by_id() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        // invalid ID
+        let id = "invalid";
+        let result = get_aircraft_by_id(
+            Extension(repos.clone()),
+            overdue_aircraft(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            operator_quotas(),
+            Path(id.to_string()),
+        )
+        .await;
+        assert_eq!(result.unwrap_err(), StatusCode::BAD_REQUEST);
+
+        // Valid ID, but doesn't exist
+        let id = Uuid::new_v4();
+        let error = get_aircraft_by_id(
+            Extension(repos.clone()),
+            overdue_aircraft(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            operator_quotas(),
+            Path(id.to_string()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        let data = AircraftDataBuilder::new()
+            .hangar(Uuid::new_v4().to_string(), Uuid::new_v4().to_string())
+            .description(Uuid::new_v4().to_string())
+            .asset_group_id(Uuid::new_v4().to_string())
+            .timestamps(Utc::now(), Utc::now())
+            .build();
+
+        let quotas = operator_quotas();
+        let headers = operator_headers();
+        let id = register_aircraft(
+            Extension(repos.clone()),
+            quotas.clone(),
+            headers.clone(),
+            Json(data.clone()),
+        )
+        .await
+        .unwrap();
+
+        let mut expected: Aircraft = vehicle::Object {
+            id: id.clone(),
+            data: Some(data.clone()),
+        }
+        .try_into()
+        .unwrap();
+        expected.basics.owner_id = operator_id_from_headers(&headers).unwrap();
+
+        let result = get_aircraft_by_id(
+            Extension(repos.clone()),
+            overdue_aircraft(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            quotas,
+            Path(id.clone()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.0, expected);
+    }
+
+    #[test]
+    fn test_validate_status_batch() {
+        let id = Uuid::new_v4().to_string();
+        let payload = |ids, filter| BatchAircraftStatusPayload {
+            ids,
+            filter,
+            status: crate::rest::api::rest_types::AssetStatus::Unavailable,
+        };
+
+        assert_eq!(
+            validate_status_batch(&payload(Some(vec![id.clone(), id.clone()]), None)),
+            Ok(StatusBatchTarget::Ids(vec![id.clone()]))
+        );
+        let errors = validate_status_batch(&payload(
+            Some(vec![id.clone(), "invalid".to_string()]),
+            None,
+        ))
+        .unwrap_err();
+        assert_eq!(errors[0].field, "ids[1]");
+        assert!(validate_status_batch(&payload(Some(vec![]), None)).is_err());
+
+        let filter = AircraftStatusFilter {
+            vehicle_model_id: Some(id.clone()),
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_status_batch(&payload(None, Some(filter.clone()))),
+            Ok(StatusBatchTarget::Filter(filter.clone()))
+        );
+        let errors = validate_status_batch(&payload(None, Some(AircraftStatusFilter::default())))
+            .unwrap_err();
+        assert_eq!(errors[0].field, "filter");
+
+        // exactly one of ids and filter
+        assert!(validate_status_batch(&payload(None, None)).is_err());
+        assert!(validate_status_batch(&payload(Some(vec![id]), Some(filter))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_batch_set_aircraft_status() {
+        let repos = Repositories::memory();
+        let model_id = Uuid::new_v4().to_string();
+        let mut ids = vec![];
+        for vehicle_model_id in [
+            model_id.clone(),
+            model_id.clone(),
+            Uuid::new_v4().to_string(),
+        ] {
+            let data = AircraftDataBuilder::new()
+                .vehicle_model_id(vehicle_model_id)
+                .build();
+            ids.push(repos.aircraft.insert(data).await.unwrap().id);
+        }
+
+        // by filter
+        let statuses = AircraftStatuses::default();
+        let results = batch_set_aircraft_status(
+            Extension(repos.clone()),
+            fan_out(),
+            Extension(statuses.clone()),
+            Json(BatchAircraftStatusPayload {
+                ids: None,
+                filter: Some(AircraftStatusFilter {
+                    vehicle_model_id: Some(model_id),
+                    ..Default::default()
+                }),
+                status: crate::rest::api::rest_types::AssetStatus::Unavailable,
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(results.len(), 2);
+        assert_eq!(statuses.get(&ids[0]), Some(AssetStatus::Unavailable));
+        assert_eq!(statuses.get(&ids[1]), Some(AssetStatus::Unavailable));
+        assert_eq!(statuses.get(&ids[2]), None);
+
+        // by ids, a missing aircraft does not fail the others
+        let missing = Uuid::new_v4().to_string();
+        let results = batch_set_aircraft_status(
+            Extension(repos.clone()),
+            fan_out(),
+            Extension(statuses.clone()),
+            Json(BatchAircraftStatusPayload {
+                ids: Some(vec![ids[2].clone(), missing.clone()]),
+                filter: None,
+                status: crate::rest::api::rest_types::AssetStatus::Emergency,
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(results[0].id, ids[2]);
+        assert_eq!(results[0].status, StatusCode::OK.as_u16());
+        assert_eq!(results[1].id, missing);
+        assert_eq!(results[1].status, StatusCode::NOT_FOUND.as_u16());
+        assert_eq!(statuses.get(&ids[2]), Some(AssetStatus::Emergency));
+    }
+
+    #[tokio::test]
+    async fn test_head_aircraft_by_id() {
+        let repos = Repositories::memory();
+        let object = repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap();
+        let aircraft = Aircraft::try_from(object.clone()).unwrap();
+
+        let headers = head_aircraft_by_id(Extension(repos.clone()), Path(object.id))
+            .await
+            .unwrap();
+        assert_eq!(headers, asset_headers(aircraft.basics.updated_at));
+
+        assert_eq!(
+            head_aircraft_by_id(Extension(repos.clone()), Path(Uuid::new_v4().to_string()))
+                .await
+                .unwrap_err(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            head_aircraft_by_id(Extension(repos), Path("invalid".to_string()))
+                .await
+                .unwrap_err(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[tokio::test]
+    async fn test_register_aircraft() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let payload = AircraftDataBuilder::new().build();
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+        register_aircraft(
+            Extension(repos.clone()),
+            operator_quotas(),
+            operator_headers(),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap();
+
+        // the operator registering the aircraft is its owner
+        let (error, _) = register_aircraft(
+            Extension(repos),
+            operator_quotas(),
+            HeaderMap::new(),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_check_aircraft_registration() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let repos = Repositories::memory();
+        let mut data = vehicle::mock::get_data_obj();
+        data.registration_number = "N12345".to_string();
+        let id = repos.aircraft.insert(data).await.unwrap().id;
+
+        let Json(check) =
+            check_aircraft_registration(Extension(repos.clone()), Path(" N12345 ".to_string()))
+                .await
+                .unwrap();
+        assert_eq!(
+            check,
+            RegistrationNumberCheck {
+                registration_number: "N12345".to_string(),
+                in_use: true,
+                aircraft_id: Some(id),
+            }
         );
+
+        let Json(check) =
+            check_aircraft_registration(Extension(repos.clone()), Path("N54321".to_string()))
+                .await
+                .unwrap();
+        assert!(!check.in_use);
+        assert_eq!(check.aircraft_id, None);
+
+        let error = check_aircraft_registration(Extension(repos), Path(" ".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_register_aircraft_quota() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let mut config = crate::config::Config::default();
+        config.max_aircraft_per_operator = 1;
+        let quotas = OperatorQuotas::new(config.clone().into(), AssetOwners::default());
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        let operator_id = Uuid::new_v4().to_string();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            crate::rest::quota::OPERATOR_ID_HEADER,
+            operator_id.parse().unwrap(),
+        );
+
+        let mut payload = vehicle::mock::get_data_obj();
+        payload.vehicle_model_id = Uuid::new_v4().to_string();
+        payload.registration_number = Uuid::new_v4().to_string();
+        let id = register_aircraft(
+            Extension(repos.clone()),
+            Extension(quotas.clone()),
+            headers.clone(),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap();
+        let quota = quotas.get(&repos, &operator_id).await.unwrap();
+        assert_eq!(quota.aircraft.used, 1);
+
+        // an aircraft needs an owner
+        payload.registration_number = Uuid::new_v4().to_string();
+        let (error, _) = register_aircraft(
+            Extension(repos.clone()),
+            Extension(quotas.clone()),
+            HeaderMap::new(),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        // second aircraft exceeds the quota
+        payload.registration_number = Uuid::new_v4().to_string();
+        let (error, _) = register_aircraft(
+            Extension(repos.clone()),
+            Extension(quotas.clone()),
+            headers.clone(),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::FORBIDDEN);
+
+        // removing the aircraft frees the quota
+        remove_aircraft(
+            Extension(repos.clone()),
+            Extension(quotas.clone()),
+            Extension(RemovedAssets::default()),
+            Path(id),
+            Query(RemoveAircraftQuery::default()),
+        )
+        .await
+        .unwrap();
+        let quota = quotas.get(&repos, &operator_id).await.unwrap();
+        assert_eq!(quota.aircraft.used, 0);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_aircraft_memory_repository() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let repos = Repositories::memory();
+        let mut payload = vehicle::mock::get_data_obj();
+        payload.vehicle_model_id = Uuid::new_v4().to_string();
+        payload.registration_number = Uuid::new_v4().to_string();
+        let id = register_aircraft(
+            Extension(repos.clone()),
+            operator_quotas(),
+            operator_headers(),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap();
+
+        let aircraft = get_aircraft_by_id(
+            Extension(repos.clone()),
+            overdue_aircraft(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            operator_quotas(),
+            Path(id.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(aircraft.registration_number, payload.registration_number);
+
+        // registration number is already in use
+        let (error, Json(errors)) = register_aircraft(
+            Extension(repos.clone()),
+            operator_quotas(),
+            operator_headers(),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "registration_number");
+
+        remove_aircraft(
+            Extension(repos.clone()),
+            operator_quotas(),
+            Extension(RemovedAssets::default()),
+            Path(id.clone()),
+            Query(RemoveAircraftQuery::default()),
+        )
+        .await
+        .unwrap();
+        let error = get_aircraft_by_id(
+            Extension(repos),
+            overdue_aircraft(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            operator_quotas(),
+            Path(id.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        let error = get_aircraft_by_id(
+            Extension(Repositories::unavailable()),
+            overdue_aircraft(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            operator_quotas(),
+            Path(id),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::SERVICE_UNAVAILABLE);
+
+        ut_info!("Success.");
     }
 
     #[test]
-    fn test_aircraft_basics_trait_methods() {
-        let aircraft = vehicle::mock::get_data_obj();
-        let basics = Basics {
+    fn test_validate_vehicle_data() {
+        let mut data = vehicle::mock::get_data_obj();
+        data.vehicle_model_id = Uuid::new_v4().to_string();
+        data.hangar_id = None;
+        data.hangar_bay_id = None;
+        data.asset_group_id = None;
+        data.last_maintenance = None;
+        data.next_maintenance = None;
+        data.registration_number = format!(" {} ", data.registration_number);
+
+        let normalized = validate_vehicle_data(data.clone()).unwrap();
+        assert_eq!(
+            normalized.registration_number,
+            data.registration_number.trim()
+        );
+
+        data.vehicle_model_id = "invalid".to_string();
+        data.serial_number = "".to_string();
+        data.hangar_id = Some("invalid".to_string());
+        data.last_maintenance = Some(Utc::now().into());
+        data.next_maintenance = Some((Utc::now() - chrono::Duration::days(1)).into());
+        let errors = validate_vehicle_data(data).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "vehicle_model_id",
+                "serial_number",
+                "hangar_id",
+                "next_maintenance"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_aircraft_registration() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        let payload = AircraftDataBuilder::new()
+            .description(" description ")
+            .build();
+
+        let result =
+            validate_aircraft_registration(Extension(repos.clone()), Json(payload.clone()))
+                .await
+                .unwrap();
+        assert_eq!(result.0.description, Some("description".to_string()));
+
+        // Registration number in use
+        register_aircraft(
+            Extension(repos.clone()),
+            operator_quotas(),
+            operator_headers(),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap();
+        let (status, errors) =
+            validate_aircraft_registration(Extension(repos.clone()), Json(payload.clone()))
+                .await
+                .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "registration_number");
+
+        let (error, Json(errors)) = register_aircraft(
+            Extension(repos),
+            operator_quotas(),
+            operator_headers(),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "registration_number");
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_validate_aircraft_update() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        let payload = UpdateAircraftPayload {
+            id: "invalid".to_string(),
+            hangar_id: None,
+            hangar_bay_id: None,
+            vehicle_model_id: Some("invalid".to_string()),
+            serial_number: None,
+            registration_number: None,
+            description: None,
+            asset_group_id: None,
+            schedule: None,
+            last_maintenance: None,
+            next_maintenance: None,
+            expected_updated_at: None,
+            mask: vec![],
+        };
+
+        let (status, errors) = validate_aircraft_update(
+            Extension(repos),
+            group_delegations(),
+            operator_quotas(),
+            HeaderMap::new(),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0.len(), 2);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_update_aircraft() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let mut payload = UpdateAircraftPayload {
             id: Uuid::new_v4().to_string(),
-            name: Some("Test asset".to_string()),
-            group_id: Some(Uuid::new_v4().to_string()),
-            created_at: aircraft
-                .created_at
-                .expect("mock didn't return a created_at date.")
-                .into(),
-            updated_at: aircraft
-                .updated_at
-                .expect("mock didn't return a updated_at date.")
-                .into(),
-            whitelist: vec![Uuid::new_v4().to_string()],
-            status: AssetStatus::Available,
+            hangar_id: Some(Uuid::new_v4().to_string()),
+            hangar_bay_id: Some(Uuid::new_v4().to_string()),
+            vehicle_model_id: Some(Uuid::new_v4().to_string()),
+            serial_number: Some(Uuid::new_v4().to_string()),
+            registration_number: Some(Uuid::new_v4().to_string()),
+            description: Some(Uuid::new_v4().to_string()),
+            asset_group_id: Some(Uuid::new_v4().to_string()),
+            schedule: Some(Uuid::new_v4().to_string()),
+            last_maintenance: None,
+            next_maintenance: None,
+            expected_updated_at: None,
+            mask: vec![],
         };
-        let mut aircraft = Aircraft {
-            basics: basics.clone(),
-            manufacturer: "Airbus".to_string(),
-            model: "737-800".to_string(),
-            serial_number: aircraft.serial_number,
-            registration_number: aircraft.registration_number,
-            description: aircraft.description,
-            max_payload_kg: 1000.0,
-            max_range_km: 1000.0,
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        // invalid ID
+        payload.id = "invalid".to_string();
+        let error = update_aircraft(
+            Extension(repos.clone()),
+            fan_out(),
+            group_delegations(),
+            operator_quotas(),
+            HeaderMap::new(),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.0, StatusCode::BAD_REQUEST);
+
+        // Valid ID, but doesn't exist
+        payload.id = Uuid::new_v4().to_string();
+        let error = update_aircraft(
+            Extension(repos.clone()),
+            fan_out(),
+            group_delegations(),
+            operator_quotas(),
+            HeaderMap::new(),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.0, StatusCode::NOT_FOUND);
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_update_aircraft_unchanged() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let repos = Repositories::memory();
+        let data = AircraftDataBuilder::new()
+            .description("Cargo aircraft")
+            .build();
+        let object = repos.aircraft.insert(data).await.unwrap();
+        let (id, updated_at) = (object.id, object.data.unwrap().updated_at);
+        let mut payload = UpdateAircraftPayload {
+            id: id.clone(),
+            hangar_id: None,
+            hangar_bay_id: None,
+            vehicle_model_id: None,
+            serial_number: None,
+            registration_number: None,
+            description: Some("Cargo aircraft".to_string()),
+            asset_group_id: None,
+            schedule: None,
+            last_maintenance: None,
+            next_maintenance: None,
+            expected_updated_at: None,
+            mask: vec!["description".to_string()],
+        };
+
+        // resubmitting the stored values does not write the aircraft
+        let Json(result) = update_aircraft(
+            Extension(repos.clone()),
+            fan_out(),
+            group_delegations(),
+            operator_quotas(),
+            HeaderMap::new(),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap();
+        assert!(!result.changed);
+        let stored = repos
+            .aircraft
+            .get_by_id(id.clone())
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(stored.updated_at, updated_at);
+
+        payload.description = Some("Passenger aircraft".to_string());
+        let Json(result) = update_aircraft(
+            Extension(repos.clone()),
+            fan_out(),
+            group_delegations(),
+            operator_quotas(),
+            HeaderMap::new(),
+            Json(payload),
+        )
+        .await
+        .unwrap();
+        assert!(result.changed);
+        let stored = repos.aircraft.get_by_id(id).await.unwrap().data.unwrap();
+        assert_eq!(stored.description, Some("Passenger aircraft".to_string()));
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_update_aircraft_asset_group() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let group_id = Uuid::new_v4().to_string();
+        let repos = Repositories {
+            groups: Arc::new(MemoryRepository::with_objects(HashMap::from([(
+                group_id.clone(),
+                group::Data {
+                    name: "Fleet".to_string(),
+                    ..Default::default()
+                },
+            )]))),
+            ..Repositories::memory()
+        };
+        let quotas = operator_quotas();
+        let owner = Uuid::new_v4().to_string();
+        let member = AircraftDataBuilder::new()
+            .asset_group_id(group_id.clone())
+            .build();
+        let member_id = repos.aircraft.insert(member).await.unwrap().id;
+        quotas
+            .reserve(&repos, &owner, QuotaKind::Aircraft)
+            .await
+            .unwrap()
+            .commit(&member_id)
+            .unwrap();
+
+        let id = repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let payload = |asset_group_id: String| UpdateAircraftPayload {
+            id: id.clone(),
+            hangar_id: None,
+            hangar_bay_id: None,
+            vehicle_model_id: None,
+            serial_number: None,
+            registration_number: None,
+            description: None,
+            asset_group_id: Some(asset_group_id),
+            schedule: None,
+            last_maintenance: None,
+            next_maintenance: None,
+            expected_updated_at: None,
+            mask: vec!["asset_group_id".to_string()],
+        };
+        let headers = |operator_id: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(OPERATOR_ID_HEADER, operator_id.parse().unwrap());
+            headers
+        };
+
+        for (asset_group_id, headers, status, fields) in [
+            (
+                group_id.clone(),
+                HeaderMap::new(),
+                StatusCode::BAD_REQUEST,
+                vec![],
+            ),
+            (
+                Uuid::new_v4().to_string(),
+                headers(&owner),
+                StatusCode::UNPROCESSABLE_ENTITY,
+                vec!["asset_group_id"],
+            ),
+            (
+                group_id.clone(),
+                headers(&Uuid::new_v4().to_string()),
+                StatusCode::UNPROCESSABLE_ENTITY,
+                vec!["asset_group_id"],
+            ),
+        ] {
+            let (error, Json(errors)) = update_aircraft(
+                Extension(repos.clone()),
+                fan_out(),
+                group_delegations(),
+                quotas.clone(),
+                headers,
+                Json(payload(asset_group_id)),
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(error, status);
+            let error_fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+            assert_eq!(error_fields, fields);
+        }
+
+        let Json(result) = update_aircraft(
+            Extension(repos.clone()),
+            fan_out(),
+            group_delegations(),
+            quotas,
+            headers(&owner),
+            Json(payload(group_id.clone())),
+        )
+        .await
+        .unwrap();
+        assert!(result.changed);
+        let stored = repos.aircraft.get_by_id(id).await.unwrap().data.unwrap();
+        assert_eq!(stored.asset_group_id, Some(group_id));
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_update_aircraft_hangar_bay() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let repos = Repositories::memory();
+        let hangar_id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let hangar_bay_id = repos
+            .vertipads
+            .insert(VertipadDataBuilder::new().vertiport_id(&hangar_id).build())
+            .await
+            .unwrap()
+            .id;
+        let occupant = repos
+            .aircraft
+            .insert(
+                AircraftDataBuilder::new()
+                    .hangar(&hangar_id, &hangar_bay_id)
+                    .build(),
+            )
+            .await
+            .unwrap()
+            .id;
+        let id = repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let payload = |hangar_bay_id: String| UpdateAircraftPayload {
+            id: id.clone(),
+            hangar_id: Some(hangar_id.clone()),
+            hangar_bay_id: Some(hangar_bay_id),
+            vehicle_model_id: None,
+            serial_number: None,
+            registration_number: None,
+            description: None,
+            asset_group_id: None,
+            schedule: None,
             last_maintenance: None,
             next_maintenance: None,
-            hangar_id: None,
+            expected_updated_at: None,
+            mask: vec!["hangar_id".to_string(), "hangar_bay_id".to_string()],
         };
 
-        assert_eq!(aircraft.id().unwrap(), Uuid::parse_str(&basics.id).unwrap());
-        assert_eq!(aircraft.name(), basics.name.clone().unwrap());
-        assert_eq!(aircraft.created_at(), basics.created_at);
-        assert_eq!(aircraft.updated_at(), basics.updated_at);
-        assert_eq!(aircraft.is_grouped(), true);
-        assert_eq!(aircraft.is_public(), false);
-        assert_eq!(aircraft.status(), basics.status);
-
-        let manufacturer = "Airbus";
-        let model = "A320";
-        aircraft.basics.name = None;
-        aircraft.basics.group_id = None;
-        aircraft.manufacturer = manufacturer.to_string();
-        aircraft.model = model.to_string();
-        assert_eq!(
-            aircraft.full_name(),
-            format!(
-                "{} {} {}",
-                manufacturer, model, aircraft.registration_number
+        for (hangar_bay_id, status) in [
+            (hangar_bay_id.clone(), StatusCode::CONFLICT),
+            (Uuid::new_v4().to_string(), StatusCode::UNPROCESSABLE_ENTITY),
+        ] {
+            let (error, Json(errors)) = update_aircraft(
+                Extension(repos.clone()),
+                fan_out(),
+                group_delegations(),
+                operator_quotas(),
+                HeaderMap::new(),
+                Json(payload(hangar_bay_id)),
             )
-        );
-        assert_eq!(aircraft.group_id(), Ok(None));
-        assert_eq!(aircraft.is_grouped(), false);
+            .await
+            .unwrap_err();
+            assert_eq!(error, status);
+            assert_eq!(errors[0].field, "hangar_bay_id");
+        }
+
+        // the bay is free once its occupant left
+        repos.aircraft.delete(occupant).await.unwrap();
+        let Json(result) = update_aircraft(
+            Extension(repos.clone()),
+            fan_out(),
+            group_delegations(),
+            operator_quotas(),
+            HeaderMap::new(),
+            Json(payload(hangar_bay_id.clone())),
+        )
+        .await
+        .unwrap();
+        assert!(result.changed);
+        let stored = repos.aircraft.get_by_id(id).await.unwrap().data.unwrap();
+        assert_eq!(stored.hangar_bay_id, Some(hangar_bay_id));
+        ut_info!("Success.");
     }
 
     #[tokio::test]
-    async fn test_get_all_aircraft() {
+    async fn test_remove_aircraft() {
         get_log_handle().await;
         ut_info!("Start.");
 
         let config = crate::config::Config::default();
         let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
 
-        let payload = vehicle::Data {
-            hangar_id: Some(Uuid::new_v4().to_string()),
-            hangar_bay_id: Some(Uuid::new_v4().to_string()),
-            vehicle_model_id: Uuid::new_v4().to_string(),
-            serial_number: Uuid::new_v4().to_string(),
-            registration_number: Uuid::new_v4().to_string(),
-            description: Some(Uuid::new_v4().to_string()),
-            asset_group_id: Some(Uuid::new_v4().to_string()),
-            schedule: Some(Uuid::new_v4().to_string()),
-            last_maintenance: None,
-            next_maintenance: None,
-            created_at: None,
-            updated_at: None,
+        // Invalid ID
+        let id = "invalid";
+        let result = remove_aircraft(
+            Extension(repos.clone()),
+            operator_quotas(),
+            Extension(RemovedAssets::default()),
+            Path(id.to_string()),
+            Query(RemoveAircraftQuery::default()),
+        )
+        .await;
+        assert_eq!(result.unwrap_err().0, StatusCode::BAD_REQUEST);
+
+        // Valid ID
+        let id = Uuid::new_v4();
+        remove_aircraft(
+            Extension(repos.clone()),
+            operator_quotas(),
+            Extension(RemovedAssets::default()),
+            Path(id.to_string()),
+            Query(RemoveAircraftQuery::default()),
+        )
+        .await
+        .unwrap();
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_remove_aircraft_assigned_flights() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let repos = Repositories::memory();
+        let id = repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let later = |hours: i64| Some((Utc::now() + chrono::Duration::hours(hours)).into());
+        let repos = Repositories {
+            flight_plans: Arc::new(MemoryRepository::with_objects(HashMap::from([(
+                "flight".to_string(),
+                flight_plan::Data {
+                    vehicle_id: id.clone(),
+                    origin_timeslot_start: later(1),
+                    target_timeslot_end: later(2),
+                    ..Default::default()
+                },
+            )]))),
+            ..repos
         };
 
-        let id = register_aircraft(Extension(grpc_clients.clone()), Json(payload))
+        let (status, Json(flights)) = remove_aircraft(
+            Extension(repos.clone()),
+            operator_quotas(),
+            Extension(RemovedAssets::default()),
+            Path(id.clone()),
+            Query(RemoveAircraftQuery::default()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(flights.len(), 1);
+        assert_eq!(flights[0].flight_plan_id, "flight");
+
+        let (status, _) = remove_aircraft(
+            Extension(Repositories {
+                flight_plans: Repositories::unavailable().flight_plans,
+                ..repos.clone()
+            }),
+            operator_quotas(),
+            Extension(RemovedAssets::default()),
+            Path(id.clone()),
+            Query(RemoveAircraftQuery::default()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        remove_aircraft(
+            Extension(repos.clone()),
+            operator_quotas(),
+            Extension(RemovedAssets::default()),
+            Path(id.clone()),
+            Query(RemoveAircraftQuery { force: true }),
+        )
+        .await
+        .unwrap();
+        assert!(repos.aircraft.get_by_id(id).await.is_err());
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_batch_get_aircraft() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        let mut data = vehicle::mock::get_data_obj();
+        data.registration_number = Uuid::new_v4().to_string();
+        let id = grpc_clients
+            .storage
+            .vehicle
+            .insert(data)
             .await
-            .unwrap();
+            .unwrap()
+            .into_inner()
+            .object
+            .unwrap()
+            .id;
+
+        // invalid payload
+        let error = batch_get_aircraft(
+            Extension(repos.clone()),
+            fan_out(),
+            overdue_aircraft(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            shared_config(),
+            operator_quotas(),
+            Json(BatchGetPayload {
+                ids: vec!["invalid".to_string()],
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, ListError::Status(StatusCode::BAD_REQUEST));
+
+        let missing_id = Uuid::new_v4().to_string();
+        let response = batch_get_aircraft(
+            Extension(repos),
+            fan_out(),
+            overdue_aircraft(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            shared_config(),
+            operator_quotas(),
+            Json(BatchGetPayload {
+                ids: vec![id.clone(), missing_id.clone()],
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.found.len(), 1);
+        assert_eq!(response.found[0].basics.id, id);
+        assert_eq!(response.missing, vec![missing_id]);
 
-        ut_info!("Success: {:#?}", id);
+        ut_info!("success");
+    }
 
-        let result = get_all_aircraft(Extension(grpc_clients)).await.unwrap();
-        ut_info!("Success: {:#?}", result);
-        // assert!(!result.0.is_empty());
+    #[tokio::test]
+    async fn test_patch_aircraft() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        let mut data = vehicle::mock::get_data_obj();
+        data.registration_number = Uuid::new_v4().to_string();
+        let id = grpc_clients
+            .storage
+            .vehicle
+            .insert(data)
+            .await
+            .unwrap()
+            .into_inner()
+            .object
+            .unwrap()
+            .id;
+
+        // unknown fields can not be patched
+        let (error, Json(errors)) = patch_aircraft(
+            Extension(repos.clone()),
+            fan_out(),
+            group_delegations(),
+            operator_quotas(),
+            Path(id.clone()),
+            HeaderMap::new(),
+            Json(serde_json::json!({ "created_at": null })),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "created_at");
+
+        let description = Uuid::new_v4().to_string();
+        patch_aircraft(
+            Extension(repos.clone()),
+            fan_out(),
+            group_delegations(),
+            operator_quotas(),
+            Path(id.clone()),
+            HeaderMap::new(),
+            Json(serde_json::json!({ "description": description })),
+        )
+        .await
+        .unwrap();
+
+        let aircraft = get_aircraft_by_id(
+            Extension(repos),
+            overdue_aircraft(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            operator_quotas(),
+            Path(id),
+        )
+        .await
+        .unwrap();
+        assert_eq!(aircraft.description, Some(description));
+
+        ut_info!("Success.");
+    }
+
+    #[test]
+    fn test_compute_utilization() {
+        let from: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+        let to = from + chrono::Duration::hours(10);
+        let hours = |hours: i64| Some((from + chrono::Duration::hours(hours)).into());
+
+        let plans = vec![
+            // planned flight of 2 hours
+            flight_plan::Data {
+                origin_timeslot_start: hours(1),
+                target_timeslot_end: hours(3),
+                ..Default::default()
+            },
+            // actual times take precedence, overlaps the first flight
+            flight_plan::Data {
+                origin_timeslot_start: hours(5),
+                target_timeslot_end: hours(6),
+                actual_departure_time: hours(2),
+                actual_arrival_time: hours(4),
+                ..Default::default()
+            },
+            // started before the range
+            flight_plan::Data {
+                origin_timeslot_start: hours(-1),
+                target_timeslot_end: hours(1),
+                ..Default::default()
+            },
+            // cancelled
+            flight_plan::Data {
+                origin_timeslot_start: hours(6),
+                target_timeslot_end: hours(8),
+                flight_status: flight_plan::FlightStatus::Cancelled as i32,
+                ..Default::default()
+            },
+        ];
+
+        let utilization = compute_utilization("id", &plans, from, to);
+        assert_eq!(utilization.cycles, 2);
+        assert!((utilization.flight_hours - 4.0).abs() < f64::EPSILON);
+        assert!((utilization.idle_hours - 6.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_assigned_flights() {
+        let now: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+        let hours = |hours: i64| Some((now + chrono::Duration::hours(hours)).into());
+        let plan = |id: &str, vehicle_id: &str, start: i64, status| flight_plan::Object {
+            id: id.to_string(),
+            data: Some(flight_plan::Data {
+                vehicle_id: vehicle_id.to_string(),
+                origin_timeslot_start: hours(start),
+                target_timeslot_end: hours(start + 1),
+                flight_status: status as i32,
+                ..Default::default()
+            }),
+        };
+
+        let plans = vec![
+            plan("later", "aircraft", 5, flight_plan::FlightStatus::Ready),
+            // still in flight
+            plan(
+                "in-flight",
+                "aircraft",
+                0,
+                flight_plan::FlightStatus::InFlight,
+            ),
+            plan("ended", "aircraft", -2, flight_plan::FlightStatus::Ready),
+            plan(
+                "finished",
+                "aircraft",
+                2,
+                flight_plan::FlightStatus::Finished,
+            ),
+            plan(
+                "cancelled",
+                "aircraft",
+                2,
+                flight_plan::FlightStatus::Cancelled,
+            ),
+            plan("other", "other", 2, flight_plan::FlightStatus::Ready),
+        ];
+
+        let flights = assigned_flights("aircraft", plans, now);
+        let ids: Vec<&str> = flights
+            .iter()
+            .map(|flight| flight.flight_plan_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["in-flight", "later"]);
+        assert_eq!(flights[1].departure, now + chrono::Duration::hours(5));
     }
 
     #[tokio::test]
-    async fn test_get_aircraft_by_id() {
+    async fn test_get_aircraft_utilization() {
         get_log_handle().await;
         ut_info!("Start.");
 
         let config = crate::config::Config::default();
         let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+        let from = Utc::now();
+        let query = AvailabilityQuery {
+            from,
+            to: from + chrono::Duration::days(1),
+        };
 
-        // invalid ID
-        let id = "invalid";
-        let result =
-            get_aircraft_by_id(Extension(grpc_clients.clone()), Path(id.to_string())).await;
-        assert_eq!(result.unwrap_err(), StatusCode::BAD_REQUEST);
+        let error = get_aircraft_utilization(
+            Extension(repos.clone()),
+            Path("invalid".to_string()),
+            Query(query),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
 
-        // Valid ID, but doesn't exist
-        let id = Uuid::new_v4();
-        let error = get_aircraft_by_id(Extension(grpc_clients.clone()), Path(id.to_string()))
+        let error = get_aircraft_utilization(
+            Extension(repos.clone()),
+            Path(Uuid::new_v4().to_string()),
+            Query(AvailabilityQuery { from, to: from }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        let mut data = vehicle::mock::get_data_obj();
+        data.registration_number = Uuid::new_v4().to_string();
+        let id = grpc_clients
+            .storage
+            .vehicle
+            .insert(data)
             .await
-            .unwrap_err();
-        assert_eq!(error, StatusCode::NOT_FOUND);
+            .unwrap()
+            .into_inner()
+            .object
+            .unwrap()
+            .id;
+
+        let utilization =
+            get_aircraft_utilization(Extension(repos), Path(id.clone()), Query(query))
+                .await
+                .unwrap();
+        assert_eq!(utilization.aircraft_id, id);
+        assert!(utilization.idle_hours <= 24.0);
 
-        let data = vehicle::Data {
-            hangar_id: Some(Uuid::new_v4().to_string()),
-            hangar_bay_id: Some(Uuid::new_v4().to_string()),
-            vehicle_model_id: Uuid::new_v4().to_string(),
-            serial_number: Uuid::new_v4().to_string(),
-            registration_number: Uuid::new_v4().to_string(),
-            description: Some(Uuid::new_v4().to_string()),
-            asset_group_id: Some(Uuid::new_v4().to_string()),
-            schedule: Some(Uuid::new_v4().to_string()),
-            last_maintenance: None,
-            next_maintenance: None,
-            created_at: Some(Utc::now().into()),
-            updated_at: Some(Utc::now().into()),
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_get_aircraft_utilization_flight_plans() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let from: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+        let hours = |hours: i64| Some((from + chrono::Duration::hours(hours)).into());
+        let query = AvailabilityQuery {
+            from,
+            to: from + chrono::Duration::hours(10),
         };
 
-        let id = register_aircraft(Extension(grpc_clients.clone()), Json(data.clone()))
+        let repos = Repositories::memory();
+        let id = repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
             .await
-            .unwrap();
+            .unwrap()
+            .id;
+        let plan = |vehicle_id: &str| flight_plan::Data {
+            vehicle_id: vehicle_id.to_string(),
+            origin_timeslot_start: hours(1),
+            target_timeslot_end: hours(3),
+            ..Default::default()
+        };
+        let repos = Repositories {
+            flight_plans: Arc::new(MemoryRepository::with_objects(HashMap::from([
+                ("flight".to_string(), plan(&id)),
+                // the memory repository does not apply the filter
+                ("other".to_string(), plan("other")),
+            ]))),
+            ..repos
+        };
 
-        let expected: Aircraft = vehicle::Object {
-            id: id.clone(),
-            data: Some(data.clone()),
-        }
-        .try_into()
+        let utilization =
+            get_aircraft_utilization(Extension(repos.clone()), Path(id.clone()), Query(query))
+                .await
+                .unwrap();
+        assert_eq!(utilization.cycles, 1);
+        assert!((utilization.flight_hours - 2.0).abs() < f64::EPSILON);
+
+        let repos = Repositories {
+            flight_plans: Repositories::unavailable().flight_plans,
+            ..repos
+        };
+        let error = get_aircraft_utilization(Extension(repos), Path(id), Query(query))
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::SERVICE_UNAVAILABLE);
+
+        ut_info!("Success.");
+    }
+
+    #[test]
+    fn test_aircraft_flight_plans_filter() {
+        let fields = |filter: AdvancedSearchFilter| {
+            filter
+                .filters
+                .into_iter()
+                .map(|condition| condition.search_field)
+                .collect::<Vec<String>>()
+        };
+        let now = Utc::now();
+        assert_eq!(
+            fields(aircraft_flight_plans_filter("id", now, Some(now))),
+            vec![
+                "vehicle_id",
+                "deleted_at",
+                "target_timeslot_end",
+                "origin_timeslot_start"
+            ]
+        );
+        assert_eq!(
+            fields(aircraft_flight_plans_filter("id", now, None)),
+            vec!["vehicle_id", "deleted_at", "target_timeslot_end"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overdue_aircraft_unavailable() {
+        get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let mut data = vehicle::mock::get_data_obj();
+        data.next_maintenance = Some((Utc::now() - chrono::Duration::days(1)).into());
+        let id = repos.aircraft.insert(data).await.unwrap().id;
+
+        let overdue = overdue_aircraft();
+        let aircraft = get_aircraft_by_id(
+            Extension(repos.clone()),
+            overdue.clone(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            operator_quotas(),
+            Path(id.clone()),
+        )
+        .await
         .unwrap();
+        assert_eq!(aircraft.basics.status, AssetStatus::Available);
 
-        let result = get_aircraft_by_id(Extension(grpc_clients.clone()), Path(id.clone()))
+        crate::maintenance::check_maintenance(&repos, &overdue, Utc::now())
             .await
             .unwrap();
+        let aircraft = get_aircraft_by_id(
+            Extension(repos.clone()),
+            overdue.clone(),
+            maintenance_holds(),
+            aircraft_statuses(),
+            operator_quotas(),
+            Path(id),
+        )
+        .await
+        .unwrap();
+        assert_eq!(aircraft.basics.status, AssetStatus::Unavailable);
+
+        let all = get_all_aircraft(
+            Extension(repos),
+            overdue,
+            maintenance_holds(),
+            aircraft_statuses(),
+            shared_config(),
+            Extension(RemovedAssets::default()),
+            operator_quotas(),
+            Query(ListAssetsQuery::default()),
+        )
+        .await
+        .unwrap()
+        .assets;
+        assert_eq!(all[0].basics.status, AssetStatus::Unavailable);
 
-        assert_eq!(result.0, expected);
+        ut_info!("success");
     }
 
     #[tokio::test]
-    async fn test_register_aircraft() {
+    async fn test_assign_aircraft_hangar() {
         get_log_handle().await;
-        ut_info!("Start.");
+        ut_info!("start");
 
-        let payload = vehicle::Data {
-            hangar_id: Some(Uuid::new_v4().to_string()),
-            hangar_bay_id: Some(Uuid::new_v4().to_string()),
-            vehicle_model_id: Uuid::new_v4().to_string(),
-            serial_number: Uuid::new_v4().to_string(),
-            registration_number: Uuid::new_v4().to_string(),
-            description: Some(Uuid::new_v4().to_string()),
-            asset_group_id: Some(Uuid::new_v4().to_string()),
-            schedule: Some(Uuid::new_v4().to_string()),
-            last_maintenance: None,
-            next_maintenance: None,
-            created_at: None,
-            updated_at: None,
+        let repos = Repositories::memory();
+        let hangar_id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let hangar_bay_id = repos
+            .vertipads
+            .insert(VertipadDataBuilder::new().vertiport_id(&hangar_id).build())
+            .await
+            .unwrap()
+            .id;
+        let first = repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let second = repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let payload = AssignHangarPayload {
+            hangar_id: hangar_id.clone(),
+            hangar_bay_id: hangar_bay_id.clone(),
         };
 
-        let config = crate::config::Config::default();
-        let grpc_clients = GrpcClients::default(config);
-        register_aircraft(Extension(grpc_clients), Json(payload))
+        assign_aircraft_hangar(
+            Extension(repos.clone()),
+            Path(first.clone()),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap();
+        let data = repos
+            .aircraft
+            .get_by_id(first.clone())
             .await
+            .unwrap()
+            .data
             .unwrap();
+        assert_eq!(data.hangar_id, Some(hangar_id.clone()));
+        assert_eq!(data.hangar_bay_id, Some(hangar_bay_id.clone()));
+
+        // reassigning the same aircraft is not a conflict
+        assign_aircraft_hangar(
+            Extension(repos.clone()),
+            Path(first.clone()),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap();
 
-        ut_info!("Success.");
+        let (status, Json(errors)) = assign_aircraft_hangar(
+            Extension(repos.clone()),
+            Path(second.clone()),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(errors[0].field, "hangar_bay_id");
+        assert!(errors[0].message.contains(&first));
+
+        let (status, Json(errors)) = assign_aircraft_hangar(
+            Extension(repos.clone()),
+            Path(second.clone()),
+            Json(AssignHangarPayload {
+                hangar_bay_id: Uuid::new_v4().to_string(),
+                ..payload.clone()
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "hangar_bay_id");
+
+        let (status, _) = assign_aircraft_hangar(
+            Extension(repos.clone()),
+            Path(second),
+            Json(AssignHangarPayload {
+                hangar_id: Uuid::new_v4().to_string(),
+                ..payload.clone()
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let (status, _) = assign_aircraft_hangar(
+            Extension(repos.clone()),
+            Path(Uuid::new_v4().to_string()),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        ut_info!("success");
     }
 
     #[tokio::test]
-    async fn test_update_aircraft() {
+    async fn test_aircraft_holds() {
         get_log_handle().await;
-        ut_info!("Start.");
+        ut_info!("start");
 
-        let mut payload = UpdateAircraftPayload {
-            id: Uuid::new_v4().to_string(),
-            hangar_id: Some(Uuid::new_v4().to_string()),
-            hangar_bay_id: Some(Uuid::new_v4().to_string()),
-            vehicle_model_id: Some(Uuid::new_v4().to_string()),
-            serial_number: Some(Uuid::new_v4().to_string()),
-            registration_number: Some(Uuid::new_v4().to_string()),
-            description: Some(Uuid::new_v4().to_string()),
-            asset_group_id: Some(Uuid::new_v4().to_string()),
-            schedule: Some(Uuid::new_v4().to_string()),
-            last_maintenance: None,
-            next_maintenance: None,
-            mask: vec![],
+        let repos = Repositories::memory();
+        let holds = maintenance_holds();
+        let id = repos
+            .aircraft
+            .insert(vehicle::mock::get_data_obj())
+            .await
+            .unwrap()
+            .id;
+        let now = Utc::now();
+        let payload = MaintenanceHoldPayload {
+            start: now - chrono::Duration::hours(1),
+            end: now + chrono::Duration::hours(1),
+            reason: Some(" rotor inspection ".to_string()),
         };
 
-        let config = crate::config::Config::default();
-        let grpc_clients = GrpcClients::default(config);
+        let (status, _) = create_aircraft_hold(
+            Extension(repos.clone()),
+            holds.clone(),
+            Path(Uuid::new_v4().to_string()),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, Json(errors)) = create_aircraft_hold(
+            Extension(repos.clone()),
+            holds.clone(),
+            Path(id.clone()),
+            Json(MaintenanceHoldPayload {
+                end: payload.start,
+                ..payload.clone()
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "end");
+
+        let Json(hold) = create_aircraft_hold(
+            Extension(repos.clone()),
+            holds.clone(),
+            Path(id.clone()),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(hold.reason, Some("rotor inspection".to_string()));
+
+        let (status, _) = create_aircraft_hold(
+            Extension(repos.clone()),
+            holds.clone(),
+            Path(id.clone()),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // the held aircraft is unavailable
+        let aircraft = get_aircraft_by_id(
+            Extension(repos.clone()),
+            overdue_aircraft(),
+            holds.clone(),
+            aircraft_statuses(),
+            operator_quotas(),
+            Path(id.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(aircraft.basics.status, AssetStatus::Unavailable);
 
-        // invalid ID
-        payload.id = "invalid".to_string();
-        let error = update_aircraft(Extension(grpc_clients.clone()), Json(payload.clone()))
+        let Json(list) = get_aircraft_holds(holds.clone(), Path(id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(list, vec![hold.clone()]);
+
+        let error = cancel_aircraft_hold(holds.clone(), Path((id.clone(), "invalid".to_string())))
             .await
             .unwrap_err();
         assert_eq!(error, StatusCode::BAD_REQUEST);
 
-        // Valid ID, but doesn't exist
-        payload.id = Uuid::new_v4().to_string();
-        let error = update_aircraft(Extension(grpc_clients.clone()), Json(payload.clone()))
+        cancel_aircraft_hold(holds.clone(), Path((id.clone(), hold.id.clone())))
+            .await
+            .unwrap();
+        let error = cancel_aircraft_hold(holds.clone(), Path((id.clone(), hold.id)))
             .await
             .unwrap_err();
         assert_eq!(error, StatusCode::NOT_FOUND);
-        ut_info!("Success.");
-    }
-
-    #[tokio::test]
-    async fn test_remove_aircraft() {
-        get_log_handle().await;
-        ut_info!("Start.");
 
-        let config = crate::config::Config::default();
-        let grpc_clients = GrpcClients::default(config);
+        let aircraft = get_aircraft_by_id(
+            Extension(repos),
+            overdue_aircraft(),
+            holds,
+            aircraft_statuses(),
+            operator_quotas(),
+            Path(id),
+        )
+        .await
+        .unwrap();
+        assert_eq!(aircraft.basics.status, AssetStatus::Available);
 
-        // Invalid ID
-        let id = "invalid";
-        let result = remove_aircraft(Extension(grpc_clients.clone()), Path(id.to_string())).await;
-        assert_eq!(result.unwrap_err(), StatusCode::BAD_REQUEST);
+        ut_info!("success");
+    }
 
-        // Valid ID
-        let id = Uuid::new_v4();
-        remove_aircraft(Extension(grpc_clients.clone()), Path(id.to_string()))
+    #[tokio::test]
+    async fn test_aircraft_status() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let statuses = aircraft_statuses();
+        let holds = maintenance_holds();
+        let id = repos
+            .aircraft
+            .insert(vehicle::mock::get_data_obj())
             .await
+            .unwrap()
+            .id;
+
+        statuses.set(&id, AssetStatus::Emergency).unwrap();
+        let aircraft = get_aircraft_by_id(
+            Extension(repos.clone()),
+            overdue_aircraft(),
+            holds.clone(),
+            statuses.clone(),
+            operator_quotas(),
+            Path(id.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(aircraft.basics.status, AssetStatus::Emergency);
+
+        // a hold takes precedence over the status
+        let now = Utc::now();
+        holds
+            .add(
+                &id,
+                MaintenanceHoldPayload {
+                    start: now - chrono::Duration::hours(1),
+                    end: now + chrono::Duration::hours(1),
+                    reason: None,
+                },
+                now,
+            )
             .unwrap();
-        ut_info!("Success.");
+        let all = get_all_aircraft(
+            Extension(repos),
+            overdue_aircraft(),
+            holds,
+            statuses,
+            shared_config(),
+            Extension(RemovedAssets::default()),
+            operator_quotas(),
+            Query(ListAssetsQuery::default()),
+        )
+        .await
+        .unwrap()
+        .assets;
+        assert_eq!(all[0].basics.status, AssetStatus::Unavailable);
+
+        ut_info!("success");
     }
 }
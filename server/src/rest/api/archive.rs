@@ -0,0 +1,452 @@
+//! Handlers for the archive of removed assets.
+
+pub use super::rest_types::{ArchiveAssetType, ArchiveQuery, ArchivedAsset, RestoredAsset};
+
+use super::aircraft::check_vehicle_data;
+use super::errors::storage_error_status;
+use super::validation::*;
+use super::vertipad::{check_vertipad_data, VertipadChecks};
+use super::vertiport::validate_vertiport_data;
+use crate::archive::RestoredAssets;
+use crate::config::SharedConfig;
+use crate::repo::Repositories;
+use crate::rest::json::Json;
+use axum::{
+    extract::{Path, Query},
+    Extension,
+};
+use chrono::{DateTime, Duration, Utc};
+use hyper::StatusCode;
+use lib_common::uuid::to_uuid;
+use svc_storage_client_grpc::prelude::*;
+use svc_storage_client_grpc::prelude::{vehicle, vertipad, vertiport};
+use tonic::Status;
+
+/// Stored data of an archived asset
+enum ArchivedData {
+    Aircraft(vehicle::Data),
+    Vertiport(vertiport::Data),
+    Vertipad(vertipad::Data),
+}
+
+/// Get the time before which removed assets are archived
+fn archive_cutoff(config: &SharedConfig, now: DateTime<Utc>) -> DateTime<Utc> {
+    let days = config.read(|config| config.archive_after_days);
+    now - Duration::days(days.into())
+}
+
+/// Create the archive entry of a removed asset
+fn entry(
+    id: String,
+    asset_type: ArchiveAssetType,
+    name: &str,
+    created_at: Option<DateTime<Utc>>,
+) -> ArchivedAsset {
+    ArchivedAsset {
+        id,
+        asset_type,
+        name: name.to_string(),
+        created_at,
+    }
+}
+
+/// Get the assets of a type removed before the cutoff, together with their
+/// stored data
+async fn archived(
+    repos: &Repositories,
+    asset_type: ArchiveAssetType,
+    cutoff: DateTime<Utc>,
+) -> Result<Vec<(ArchivedAsset, ArchivedData)>, Status> {
+    let filter =
+        AdvancedSearchFilter::search_less_or_equal("deleted_at".to_string(), cutoff.to_rfc3339());
+
+    let archived = match asset_type {
+        ArchiveAssetType::Aircraft => repos
+            .aircraft
+            .search(filter)
+            .await?
+            .into_iter()
+            .filter_map(|object| {
+                let data = object.data?;
+                let entry = entry(
+                    object.id,
+                    asset_type,
+                    &data.registration_number,
+                    data.created_at.clone().map(Into::into),
+                );
+                Some((entry, ArchivedData::Aircraft(data)))
+            })
+            .collect(),
+        ArchiveAssetType::Vertiport => repos
+            .vertiports
+            .search(filter)
+            .await?
+            .into_iter()
+            .filter_map(|object| {
+                let data = object.data?;
+                let entry = entry(
+                    object.id,
+                    asset_type,
+                    &data.name,
+                    data.created_at.clone().map(Into::into),
+                );
+                Some((entry, ArchivedData::Vertiport(data)))
+            })
+            .collect(),
+        ArchiveAssetType::Vertipad => repos
+            .vertipads
+            .search(filter)
+            .await?
+            .into_iter()
+            .filter_map(|object| {
+                let data = object.data?;
+                let entry = entry(
+                    object.id,
+                    asset_type,
+                    &data.name,
+                    data.created_at.clone().map(Into::into),
+                );
+                Some((entry, ArchivedData::Vertipad(data)))
+            })
+            .collect(),
+    };
+
+    Ok(archived)
+}
+
+/// List the assets of a type removed more than `archive_after_days` ago.
+///
+/// Archived assets which have been restored are not listed.
+#[utoipa::path(
+    get,
+    path = "/assets/archive",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Archived assets of the requested type", body = [ArchivedAsset]),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    ),
+    params(ArchiveQuery)
+)]
+pub async fn get_archive(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(restored): Extension<RestoredAssets>,
+    Query(query): Query<ArchiveQuery>,
+) -> Result<Json<Vec<ArchivedAsset>>, StatusCode> {
+    rest_info!("entry [{:?}].", query.asset_type);
+
+    let cutoff = archive_cutoff(&config, Utc::now());
+    let assets = archived(&repos, query.asset_type, cutoff)
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve archived assets: {e}.");
+            storage_error_status(&e)
+        })?
+        .into_iter()
+        .map(|(asset, _)| asset)
+        .filter(|asset| !restored.contains(&asset.id))
+        .collect();
+
+    Ok(Json(assets))
+}
+
+/// Register the data of an archived asset as a new asset, returning its id
+async fn restore(
+    repos: &Repositories,
+    asset_type: ArchiveAssetType,
+    id: &str,
+    cutoff: DateTime<Utc>,
+    checks: VertipadChecks,
+) -> Result<String, ValidationError> {
+    let storage_error = |e: Status| {
+        rest_error!("could not restore archived asset: {e}.");
+        (storage_error_status(&e), Json(vec![]))
+    };
+
+    let data = archived(repos, asset_type, cutoff)
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .find(|(asset, _)| asset.id == id)
+        .map(|(_, data)| data)
+        .ok_or_else(|| {
+            rest_warn!("no archived {:?} with id {}.", asset_type, id);
+            (StatusCode::NOT_FOUND, Json(vec![]))
+        })?;
+
+    // The timestamps of the restored asset are set by svc-storage
+    let object = match data {
+        ArchivedData::Aircraft(data) => {
+            let data = vehicle::Data {
+                created_at: None,
+                updated_at: None,
+                ..data
+            };
+            let data = check_vehicle_data(repos, data).await?;
+            repos.aircraft.insert(data).await.map_err(storage_error)?.id
+        }
+        ArchivedData::Vertiport(data) => {
+            let data = vertiport::Data {
+                created_at: None,
+                updated_at: None,
+                ..data
+            };
+            let data = validate_vertiport_data(data).map_err(unprocessable)?;
+            repos
+                .vertiports
+                .insert(data)
+                .await
+                .map_err(storage_error)?
+                .id
+        }
+        ArchivedData::Vertipad(data) => {
+            let data = vertipad::Data {
+                created_at: None,
+                updated_at: None,
+                ..data
+            };
+            let data = check_vertipad_data(repos, data, checks).await?;
+            repos
+                .vertipads
+                .insert(data)
+                .await
+                .map_err(storage_error)?
+                .id
+        }
+    };
+
+    Ok(object)
+}
+
+/// Restore an archived asset.
+///
+/// svc-storage can not undo the removal of an asset, so the data of the
+/// archived asset is registered again as a new asset. The registration
+/// checks apply; a vertipad can only be restored while its vertiport exists.
+#[utoipa::path(
+    post,
+    path = "/assets/archive/{id}/restore",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Archived asset registered again", body = RestoredAsset),
+        (status = 400, description = "Invalid asset id"),
+        (status = 404, description = "No archived asset of the requested type with id {id}"),
+        (status = 409, description = "The archived asset has already been restored"),
+        (status = 422, description = "The archived asset can not be registered again", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    ),
+    params(
+        ("id" = String, Path, description = "Archived asset id"),
+        ArchiveQuery
+    )
+)]
+pub async fn restore_archived_asset(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(restored): Extension<RestoredAssets>,
+    Path(id): Path<String>,
+    Query(query): Query<ArchiveQuery>,
+) -> Result<Json<RestoredAsset>, ValidationError> {
+    rest_info!("entry [{:?} {}].", query.asset_type, id);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid asset id: {}", id);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(vec![field_error("id", "must be a valid UUID")]),
+            )
+        })?
+        .to_string();
+
+    if !restored.claim(&id) {
+        rest_warn!(
+            "archived asset {} already restored as {:?}.",
+            id,
+            restored.restored_id(&id)
+        );
+        return Err((
+            StatusCode::CONFLICT,
+            Json(vec![field_error("id", "already restored")]),
+        ));
+    }
+
+    let cutoff = archive_cutoff(&config, Utc::now());
+    let checks = VertipadChecks::from_config(&config);
+    match restore(&repos, query.asset_type, &id, cutoff, checks).await {
+        Ok(restored_id) => {
+            if let Err(e) = restored.commit(&id, &restored_id) {
+                rest_error!("could not record the restore of {}: {}", id, e);
+            }
+            rest_info!("archived asset {} restored as {}.", id, restored_id);
+            Ok(Json(RestoredAsset {
+                archived_id: id,
+                id: restored_id,
+            }))
+        }
+        Err(e) => {
+            restored.release(&id);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{AircraftDataBuilder, VertipadDataBuilder, VertiportDataBuilder};
+    use lib_common::uuid::Uuid;
+
+    fn config() -> Extension<SharedConfig> {
+        Extension(crate::Config::default().into())
+    }
+
+    fn query(asset_type: ArchiveAssetType) -> Query<ArchiveQuery> {
+        Query(ArchiveQuery { asset_type })
+    }
+
+    #[test]
+    fn test_archive_cutoff() {
+        let now = Utc::now();
+        let mut config = crate::Config::default();
+        config.archive_after_days = 7;
+        assert_eq!(
+            archive_cutoff(&SharedConfig::from(config), now),
+            now - Duration::days(7)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_archive() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        // The in-memory repositories do not apply filters, so all stored
+        // assets are listed as archived
+        let repos = Repositories::memory();
+        let data = AircraftDataBuilder::new().build();
+        let id = repos.aircraft.insert(data.clone()).await.unwrap().id;
+        let restored = RestoredAssets::default();
+
+        let assets = get_archive(
+            Extension(repos.clone()),
+            config(),
+            Extension(restored.clone()),
+            query(ArchiveAssetType::Aircraft),
+        )
+        .await
+        .unwrap();
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].id, id);
+        assert_eq!(assets[0].asset_type, ArchiveAssetType::Aircraft);
+        assert_eq!(assets[0].name, data.registration_number);
+        assert!(assets[0].created_at.is_some());
+
+        let assets = get_archive(
+            Extension(repos.clone()),
+            config(),
+            Extension(restored.clone()),
+            query(ArchiveAssetType::Vertiport),
+        )
+        .await
+        .unwrap();
+        assert!(assets.is_empty());
+
+        // restored assets are not listed
+        restored.claim(&id);
+        restored.commit(&id, &Uuid::new_v4().to_string()).unwrap();
+        let assets = get_archive(
+            Extension(repos),
+            config(),
+            Extension(restored),
+            query(ArchiveAssetType::Aircraft),
+        )
+        .await
+        .unwrap();
+        assert!(assets.is_empty());
+
+        let error = get_archive(
+            Extension(Repositories::unavailable()),
+            config(),
+            Extension(RestoredAssets::default()),
+            query(ArchiveAssetType::Aircraft),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::SERVICE_UNAVAILABLE);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_restore_archived_asset() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let repos = Repositories::memory();
+        let restored = RestoredAssets::default();
+        let vertiport_id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let restore = |id: String, asset_type: ArchiveAssetType| {
+            restore_archived_asset(
+                Extension(repos.clone()),
+                config(),
+                Extension(restored.clone()),
+                Path(id),
+                query(asset_type),
+            )
+        };
+
+        // Invalid id
+        let (status, _) = restore("invalid".to_string(), ArchiveAssetType::Vertiport)
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Not archived
+        let (status, _) = restore(Uuid::new_v4().to_string(), ArchiveAssetType::Vertiport)
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let result = restore(vertiport_id.clone(), ArchiveAssetType::Vertiport)
+            .await
+            .unwrap();
+        assert_eq!(result.archived_id, vertiport_id);
+        assert_ne!(result.id, vertiport_id);
+        assert!(repos.vertiports.get_by_id(result.id.clone()).await.is_ok());
+        assert_eq!(restored.restored_id(&vertiport_id), Some(result.id.clone()));
+
+        // Already restored
+        let (status, _) = restore(vertiport_id.clone(), ArchiveAssetType::Vertiport)
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // A vertipad can only be restored while its vertiport exists
+        let vertipad_id = repos
+            .vertipads
+            .insert(
+                VertipadDataBuilder::new()
+                    .vertiport_id(Uuid::new_v4().to_string())
+                    .build(),
+            )
+            .await
+            .unwrap()
+            .id;
+        let (status, errors) = restore(vertipad_id.clone(), ArchiveAssetType::Vertipad)
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "vertiport_id");
+        assert!(!restored.contains(&vertipad_id));
+
+        ut_info!("Success.");
+    }
+}
@@ -0,0 +1,502 @@
+//! Handlers for assets of any type
+
+use super::aircraft::Aircraft;
+use super::errors::storage_error_status;
+use super::rest_types::timestamp;
+use super::vertipad::Vertipad;
+use super::vertiport::Vertiport;
+use crate::aircraft_status::AircraftStatuses;
+use crate::fan_out::FanOut;
+use crate::hold::MaintenanceHolds;
+use crate::maintenance::OverdueAircraft;
+use crate::ops_status::VertiportOpsStatuses;
+use crate::repo::Repositories;
+use crate::rest::json::Json;
+use crate::rest::structs::AssetStatus;
+use axum::{extract::Path, Extension};
+use duplicate::duplicate_item;
+use hyper::StatusCode;
+use lib_common::time::{DateTime, Utc};
+use lib_common::uuid::to_uuid;
+use serde::{Deserialize, Serialize};
+use svc_storage_client_grpc::prelude::group;
+use tonic::{Code, Status};
+use utoipa::ToSchema;
+
+/// Summary of an [`Aircraft`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AircraftSummary {
+    /// UUID of the aircraft.
+    pub id: String,
+    /// The name of the aircraft.
+    pub name: Option<String>,
+    /// The status of the aircraft.
+    pub status: AssetStatus,
+    /// The aircraft's registration number.
+    pub registration_number: String,
+    /// The aircraft's model.
+    pub model: String,
+    /// When the aircraft was created.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub created_at: DateTime<Utc>,
+    /// When the aircraft was last updated.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub updated_at: DateTime<Utc>,
+    /// When the aircraft was removed, if known.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "timestamp::deserialize_option"
+    )]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Summary of a [`Vertiport`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct VertiportSummary {
+    /// UUID of the vertiport.
+    pub id: String,
+    /// The name of the vertiport.
+    pub name: Option<String>,
+    /// The status of the vertiport.
+    pub status: AssetStatus,
+    /// A description of the vertiport.
+    pub description: String,
+    /// When the vertiport was created.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub created_at: DateTime<Utc>,
+    /// When the vertiport was last updated.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub updated_at: DateTime<Utc>,
+    /// When the vertiport was removed, if known.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "timestamp::deserialize_option"
+    )]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Summary of a [`Vertipad`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct VertipadSummary {
+    /// UUID of the vertipad.
+    pub id: String,
+    /// The name of the vertipad.
+    pub name: Option<String>,
+    /// The status of the vertipad.
+    pub status: AssetStatus,
+    /// The vertiport ID that the vertipad belongs to.
+    pub vertiport_id: String,
+    /// Whether the vertipad is enabled.
+    pub enabled: bool,
+    /// When the vertipad was created.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub created_at: DateTime<Utc>,
+    /// When the vertipad was last updated.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub updated_at: DateTime<Utc>,
+    /// When the vertipad was removed, if known.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "timestamp::deserialize_option"
+    )]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Summary of an asset group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GroupSummary {
+    /// UUID of the asset group.
+    pub id: String,
+    /// The name of the asset group.
+    pub name: Option<String>,
+}
+
+/// Summary of an asset of any type, tagged with the `asset_type`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "asset_type", rename_all = "snake_case")]
+pub enum AssetSummary {
+    /// The id belongs to an aircraft.
+    Aircraft(AircraftSummary),
+    /// The id belongs to a vertiport.
+    Vertiport(VertiportSummary),
+    /// The id belongs to a vertipad.
+    Vertipad(VertipadSummary),
+    /// The id belongs to an asset group.
+    Group(GroupSummary),
+}
+
+/// An asset of any type, tagged with the `asset_type`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "asset_type", rename_all = "snake_case")]
+pub enum ResolvedAsset {
+    /// The id belongs to an aircraft.
+    Aircraft(Aircraft),
+    /// The id belongs to a vertiport.
+    Vertiport(Vertiport),
+    /// The id belongs to a vertipad.
+    Vertipad(Vertipad),
+}
+
+impl From<Aircraft> for AircraftSummary {
+    fn from(aircraft: Aircraft) -> Self {
+        Self {
+            id: aircraft.basics.id,
+            name: aircraft.basics.name,
+            status: aircraft.basics.status,
+            created_at: aircraft.basics.created_at,
+            updated_at: aircraft.basics.updated_at,
+            deleted_at: aircraft.basics.deleted_at,
+            registration_number: aircraft.registration_number,
+            model: aircraft.model,
+        }
+    }
+}
+
+impl From<Vertiport> for VertiportSummary {
+    fn from(vertiport: Vertiport) -> Self {
+        Self {
+            id: vertiport.basics.id,
+            name: vertiport.basics.name,
+            status: vertiport.basics.status,
+            created_at: vertiport.basics.created_at,
+            updated_at: vertiport.basics.updated_at,
+            deleted_at: vertiport.basics.deleted_at,
+            description: vertiport.description,
+        }
+    }
+}
+
+impl From<Vertipad> for VertipadSummary {
+    fn from(vertipad: Vertipad) -> Self {
+        Self {
+            id: vertipad.basics.id,
+            name: vertipad.basics.name,
+            status: vertipad.basics.status,
+            created_at: vertipad.basics.created_at,
+            updated_at: vertipad.basics.updated_at,
+            deleted_at: vertipad.basics.deleted_at,
+            vertiport_id: vertipad.vertiport_id,
+            enabled: vertipad.enabled,
+        }
+    }
+}
+
+impl From<group::Object> for GroupSummary {
+    fn from(object: group::Object) -> Self {
+        Self {
+            id: object.id,
+            name: object
+                .data
+                .map(|data| data.name)
+                .filter(|name| !name.is_empty()),
+        }
+    }
+}
+
+/// Map a `NotFound` lookup result to `None`
+fn found<T>(result: Result<T, Status>) -> Result<Option<T>, Status> {
+    match result {
+        Ok(object) => Ok(Some(object)),
+        Err(e) if e.code() == Code::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[duplicate_item(
+    lookup              repository      asset_type;
+    [lookup_aircraft]   [aircraft]      [Aircraft];
+    [lookup_vertiport]  [vertiports]    [Vertiport];
+    [lookup_vertipad]   [vertipads]     [Vertipad];
+)]
+/// Look up the id in a single repository, `None` if it does not exist.
+async fn lookup(
+    repos: &Repositories,
+    fan_out: &FanOut,
+    id: &str,
+) -> Result<Option<asset_type>, Status> {
+    found(
+        fan_out
+            .call(repos.repository.get_by_id(id.to_string()))
+            .await,
+    )?
+    .map(|object| {
+        asset_type::try_from(object).map_err(|e| {
+            rest_error!("could not convert asset [{}]: {e}", id);
+            Status::internal(e)
+        })
+    })
+    .transpose()
+}
+
+/// Pick the first asset found.
+///
+/// If no asset was found, the first lookup error is returned, or `None` if
+/// all lookups succeeded.
+fn first_found<T>(lookups: Vec<Result<Option<T>, Status>>) -> Result<Option<T>, Status> {
+    let mut error = None;
+    for lookup in lookups {
+        match lookup {
+            Ok(Some(asset)) => return Ok(Some(asset)),
+            Ok(None) => (),
+            Err(e) => {
+                rest_error!("could not look up asset: {e}");
+                error.get_or_insert(e);
+            }
+        }
+    }
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(None),
+    }
+}
+
+/// Pick the first asset found.
+///
+/// If no asset was found, the first lookup error is returned, or `NOT_FOUND`
+/// if all lookups succeeded.
+fn resolve(lookups: Vec<Result<Option<AssetSummary>, Status>>) -> Result<AssetSummary, StatusCode> {
+    first_found(lookups)
+        .map_err(|e| storage_error_status(&e))?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Look up an aircraft, vertiport or vertipad of which the type is unknown.
+///
+/// The id is looked up concurrently in the aircraft, vertiports and
+/// vertipads, the current statuses are applied to the asset found. Returns
+/// `None` if the id does not exist.
+pub async fn resolve_asset(
+    repos: &Repositories,
+    fan_out: &FanOut,
+    overdue: &OverdueAircraft,
+    holds: &MaintenanceHolds,
+    statuses: &AircraftStatuses,
+    ops_statuses: &VertiportOpsStatuses,
+    id: &str,
+) -> Result<Option<ResolvedAsset>, Status> {
+    let (aircraft, vertiport, vertipad) = tokio::join!(
+        lookup_aircraft(repos, fan_out, id),
+        lookup_vertiport(repos, fan_out, id),
+        lookup_vertipad(repos, fan_out, id),
+    );
+
+    first_found(vec![
+        aircraft.map(|aircraft| {
+            aircraft.map(|aircraft| {
+                ResolvedAsset::Aircraft(
+                    aircraft
+                        .apply_status(statuses)
+                        .apply_maintenance_status(overdue, holds),
+                )
+            })
+        }),
+        vertiport.map(|vertiport| vertiport.map(ResolvedAsset::Vertiport)),
+        vertipad.map(|vertipad| {
+            vertipad
+                .map(|vertipad| ResolvedAsset::Vertipad(vertipad.apply_ops_status(ops_statuses)))
+        }),
+    ])
+}
+
+/// Get the summary of an asset of any type by its id.
+///
+/// The id is looked up concurrently in the aircraft, vertiports, vertipads
+/// and asset groups, so tooling does not have to know the type of the asset.
+/// The summary is tagged with the `asset_type`.
+#[utoipa::path(
+    get,
+    path = "/assets/{id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Summary of asset {id}", body = AssetSummary),
+        (status = 400, description = "Invalid asset id"),
+        (status = 404, description = "No asset with id {id}"),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    ),
+    params(
+        ("id" = String, Path, description = "Asset id"),
+    )
+)]
+pub async fn get_asset_by_id(
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+    Extension(overdue): Extension<OverdueAircraft>,
+    Extension(holds): Extension<MaintenanceHolds>,
+    Extension(statuses): Extension<AircraftStatuses>,
+    Extension(ops_statuses): Extension<VertiportOpsStatuses>,
+    Path(id): Path<String>,
+) -> Result<Json<AssetSummary>, StatusCode> {
+    rest_info!("entry [{}].", id);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid asset id: {}", id);
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    let (aircraft, vertiport, vertipad, group) = tokio::join!(
+        lookup_aircraft(&repos, &fan_out, &id),
+        lookup_vertiport(&repos, &fan_out, &id),
+        lookup_vertipad(&repos, &fan_out, &id),
+        fan_out.call(repos.groups.get_by_id(id.clone())),
+    );
+
+    let summary = resolve(vec![
+        aircraft.map(|aircraft| {
+            aircraft.map(|aircraft| {
+                AssetSummary::Aircraft(
+                    aircraft
+                        .apply_status(&statuses)
+                        .apply_maintenance_status(&overdue, &holds)
+                        .into(),
+                )
+            })
+        }),
+        vertiport
+            .map(|vertiport| vertiport.map(|vertiport| AssetSummary::Vertiport(vertiport.into()))),
+        vertipad.map(|vertipad| {
+            vertipad.map(|vertipad| {
+                AssetSummary::Vertipad(vertipad.apply_ops_status(&ops_statuses).into())
+            })
+        }),
+        found(group).map(|group| group.map(|group| AssetSummary::Group(group.into()))),
+    ])?;
+
+    Ok(Json(summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::repo::memory::MemoryRepository;
+    use lib_common::uuid::Uuid;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use svc_storage_client_grpc::prelude::{vehicle, vertipad, vertiport};
+
+    async fn get(repos: Repositories, id: String) -> Result<Json<AssetSummary>, StatusCode> {
+        get_asset_by_id(
+            Extension(repos),
+            Extension(FanOut::new(Config::default().into())),
+            Extension(OverdueAircraft::default()),
+            Extension(MaintenanceHolds::default()),
+            Extension(AircraftStatuses::default()),
+            Extension(VertiportOpsStatuses::default()),
+            Path(id),
+        )
+        .await
+    }
+
+    #[test]
+    fn test_resolve() {
+        let group = || {
+            Ok(Some(AssetSummary::Group(GroupSummary {
+                id: "group".to_string(),
+                name: None,
+            })))
+        };
+
+        assert!(matches!(
+            resolve(vec![Ok(None), Err(Status::unavailable("")), group()]),
+            Ok(AssetSummary::Group(_))
+        ));
+        assert_eq!(
+            resolve(vec![Ok(None), Err(Status::unavailable("")), Ok(None)]),
+            Err(StatusCode::SERVICE_UNAVAILABLE)
+        );
+        assert_eq!(
+            resolve(vec![Ok(None), Ok(None)]),
+            Err(StatusCode::NOT_FOUND)
+        );
+    }
+
+    #[test]
+    fn test_asset_summary_serialization() {
+        let summary = AssetSummary::Group(GroupSummary {
+            id: "group".to_string(),
+            name: Some("Fleet".to_string()),
+        });
+        assert_eq!(
+            serde_json::to_value(summary).unwrap(),
+            serde_json::json!({"asset_type": "group", "id": "group", "name": "Fleet"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_asset_by_id() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let group_id = Uuid::new_v4().to_string();
+        let repos = Repositories {
+            groups: Arc::new(MemoryRepository::with_objects(HashMap::from([(
+                group_id.clone(),
+                group::Data {
+                    name: "Fleet".to_string(),
+                    ..Default::default()
+                },
+            )]))),
+            ..Repositories::memory()
+        };
+
+        let aircraft = repos
+            .aircraft
+            .insert(vehicle::mock::get_data_obj())
+            .await
+            .unwrap();
+        let vertiport = repos
+            .vertiports
+            .insert(vertiport::mock::get_data_obj())
+            .await
+            .unwrap();
+        let mut vertipad = vertipad::mock::get_data_obj();
+        vertipad.vertiport_id = vertiport.id.clone();
+        let vertipad = repos.vertipads.insert(vertipad).await.unwrap();
+
+        let Json(summary) = get(repos.clone(), aircraft.id.clone()).await.unwrap();
+        match summary {
+            AssetSummary::Aircraft(summary) => assert_eq!(summary.id, aircraft.id),
+            other => panic!("expected an aircraft, got {other:?}"),
+        }
+
+        let Json(summary) = get(repos.clone(), vertiport.id.clone()).await.unwrap();
+        assert!(matches!(summary, AssetSummary::Vertiport(_)));
+
+        let Json(summary) = get(repos.clone(), vertipad.id.clone()).await.unwrap();
+        match summary {
+            AssetSummary::Vertipad(summary) => assert_eq!(summary.vertiport_id, vertiport.id),
+            other => panic!("expected a vertipad, got {other:?}"),
+        }
+
+        let Json(summary) = get(repos.clone(), group_id.clone()).await.unwrap();
+        assert_eq!(
+            summary,
+            AssetSummary::Group(GroupSummary {
+                id: group_id,
+                name: Some("Fleet".to_string()),
+            })
+        );
+
+        let error = get(repos.clone(), Uuid::new_v4().to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        let error = get(repos, "invalid".to_string()).await.unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        let error = get(Repositories::unavailable(), Uuid::new_v4().to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::SERVICE_UNAVAILABLE);
+
+        ut_info!("success");
+    }
+}
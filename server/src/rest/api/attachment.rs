@@ -0,0 +1,518 @@
+//! Handlers for the notes and attachments of assets
+
+pub use super::rest_types::{AssetAttachment, AssetNote, AssetNotePayload, AttachmentQuery};
+
+use super::validation::{field_error, unprocessable, ValidationError};
+use crate::object_store::ObjectStoreError;
+use crate::rest::attachments::{
+    is_allowed_content_type, validate_file_name, validate_note, AssetAttachments, AttachmentError,
+    MAX_ATTACHMENTS_PER_ASSET, MAX_NOTES_PER_ASSET,
+};
+use crate::rest::json::{BodyLimit, BodyLimits, FieldError, Json};
+use axum::{
+    extract::{BodyStream, Path, Query},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use futures::StreamExt;
+use hyper::StatusCode;
+use lib_common::uuid::to_uuid;
+
+/// Check if the provided asset id is a valid UUID
+fn asset_id(id: &str) -> Result<String, StatusCode> {
+    to_uuid(id).map(|id| id.to_string()).ok_or_else(|| {
+        rest_error!("Invalid asset id: {}", id);
+        StatusCode::BAD_REQUEST
+    })
+}
+
+/// Map an [`AttachmentError`] to a status code
+fn attachment_error_status(error: &AttachmentError) -> StatusCode {
+    match error {
+        AttachmentError::NotFound | AttachmentError::Store(ObjectStoreError::NotFound) => {
+            StatusCode::NOT_FOUND
+        }
+        AttachmentError::TooMany => StatusCode::UNPROCESSABLE_ENTITY,
+        AttachmentError::Store(ObjectStoreError::Unavailable(_)) => StatusCode::SERVICE_UNAVAILABLE,
+        AttachmentError::State(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Get the notes of an asset.
+///
+/// Notes are persisted by this service, see [`crate::rest::attachments`].
+#[utoipa::path(
+    get,
+    path = "/assets/{id}/notes",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Notes of asset {id}", body = [AssetNote]),
+        (status = 400, description = "Invalid asset id"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset id"),
+    )
+)]
+pub async fn get_asset_notes(
+    Extension(attachments): Extension<AssetAttachments>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<AssetNote>>, StatusCode> {
+    rest_info!("{}", id);
+    let id = asset_id(&id)?;
+
+    Ok(Json(attachments.notes(&id)))
+}
+
+/// Add a note to an asset.
+#[utoipa::path(
+    post,
+    path = "/assets/{id}/notes",
+    tag = "svc-assets",
+    request_body = AssetNotePayload,
+    responses(
+        (status = 200, description = "Note added; the new note is returned", body = AssetNote),
+        (status = 400, description = "Invalid asset id"),
+        (status = 422, description = "Invalid note or too many notes", body = [FieldError]),
+        (status = 500, description = "The note could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset id"),
+    )
+)]
+pub async fn add_asset_note(
+    Extension(attachments): Extension<AssetAttachments>,
+    Path(id): Path<String>,
+    Json(payload): Json<AssetNotePayload>,
+) -> Result<Json<AssetNote>, ValidationError> {
+    rest_info!("{}", id);
+    let id = asset_id(&id).map_err(|status| (status, Json(vec![])))?;
+    let payload = validate_note(payload).map_err(unprocessable)?;
+
+    let note = attachments.add_note(&id, payload).map_err(|e| match e {
+        AttachmentError::TooMany => {
+            rest_error!("asset {} reached the maximum number of notes.", id);
+            unprocessable(vec![field_error(
+                "notes",
+                &format!("can not contain more than {MAX_NOTES_PER_ASSET} notes"),
+            )])
+        }
+        e => {
+            rest_error!("could not add note to asset {}: {:?}", id, e);
+            (attachment_error_status(&e), Json(vec![]))
+        }
+    })?;
+
+    rest_info!("added note [{}].", note.id);
+    Ok(Json(note))
+}
+
+/// Remove a note of an asset.
+#[utoipa::path(
+    delete,
+    path = "/assets/{id}/notes/{note_id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Note removed"),
+        (status = 400, description = "Invalid asset id"),
+        (status = 404, description = "Note not found"),
+        (status = 500, description = "The removal could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset id"),
+        ("note_id" = String, Path, description = "Note id"),
+    )
+)]
+pub async fn remove_asset_note(
+    Extension(attachments): Extension<AssetAttachments>,
+    Path((id, note_id)): Path<(String, String)>,
+) -> Result<(), StatusCode> {
+    rest_info!("{} [{}]", id, note_id);
+    let id = asset_id(&id)?;
+
+    attachments.remove_note(&id, &note_id).map_err(|e| {
+        rest_error!("could not remove note {} of asset {}: {:?}", note_id, id, e);
+        attachment_error_status(&e)
+    })
+}
+
+/// Get the attachments of an asset.
+///
+/// Only the metadata of the attachments is returned, the files can be
+/// downloaded separately.
+#[utoipa::path(
+    get,
+    path = "/assets/{id}/attachments",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Attachments of asset {id}", body = [AssetAttachment]),
+        (status = 400, description = "Invalid asset id"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset id"),
+    )
+)]
+pub async fn get_asset_attachments(
+    Extension(attachments): Extension<AssetAttachments>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<AssetAttachment>>, StatusCode> {
+    rest_info!("{}", id);
+    let id = asset_id(&id)?;
+
+    Ok(Json(attachments.attachments(&id)))
+}
+
+/// Upload a file and attach it to an asset.
+///
+/// The request body is the content of the file, the `Content-Type` header
+/// its type. Images, PDFs and plain text files up to
+/// `rest_max_bulk_body_bytes` can be attached.
+#[utoipa::path(
+    post,
+    path = "/assets/{id}/attachments",
+    tag = "svc-assets",
+    request_body(content = Vec<u8>, content_type = "application/pdf"),
+    responses(
+        (status = 200, description = "File attached; the new attachment is returned", body = AssetAttachment),
+        (status = 400, description = "Invalid asset id"),
+        (status = 413, description = "File too large", body = [FieldError]),
+        (status = 415, description = "Unsupported content type", body = [FieldError]),
+        (status = 422, description = "Invalid file name or too many attachments", body = [FieldError]),
+        (status = 500, description = "The attachment could not be persisted"),
+        (status = 503, description = "Could not connect to the object store"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset id"),
+        AttachmentQuery,
+    )
+)]
+pub async fn upload_asset_attachment(
+    Extension(attachments): Extension<AssetAttachments>,
+    Extension(limits): Extension<BodyLimits>,
+    Path(id): Path<String>,
+    Query(query): Query<AttachmentQuery>,
+    headers: HeaderMap,
+    mut body: BodyStream,
+) -> Result<Json<AssetAttachment>, ValidationError> {
+    rest_info!("{} [{}]", id, query.file_name);
+    let id = asset_id(&id).map_err(|status| (status, Json(vec![])))?;
+    let file_name = validate_file_name(&query.file_name).map_err(unprocessable)?;
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if !is_allowed_content_type(&content_type) {
+        rest_warn!("unsupported attachment content type: {}", content_type);
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json(vec![field_error(
+                "content_type",
+                "must be an image, a PDF or a plain text file",
+            )]),
+        ));
+    }
+
+    let max_bytes = limits.max_bytes(BodyLimit::Bulk);
+    let mut content: Vec<u8> = vec![];
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.map_err(|e| {
+            rest_error!("could not read attachment: {e}.");
+            (StatusCode::BAD_REQUEST, Json(vec![]))
+        })?;
+        if content.len() + chunk.len() > max_bytes {
+            rest_warn!("attachment exceeds {} bytes.", max_bytes);
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Json(vec![field_error(
+                    "body",
+                    &format!("file exceeds the limit of {max_bytes} bytes"),
+                )]),
+            ));
+        }
+        content.extend_from_slice(&chunk);
+    }
+
+    let attachment = attachments
+        .add_attachment(&id, file_name, content_type, content)
+        .await
+        .map_err(|e| match e {
+            AttachmentError::TooMany => {
+                rest_error!("asset {} reached the maximum number of attachments.", id);
+                unprocessable(vec![field_error(
+                    "attachments",
+                    &format!("can not contain more than {MAX_ATTACHMENTS_PER_ASSET} attachments"),
+                )])
+            }
+            e => {
+                rest_error!("could not store attachment of asset {}: {:?}", id, e);
+                (attachment_error_status(&e), Json(vec![]))
+            }
+        })?;
+
+    rest_info!("added attachment [{}].", attachment.id);
+    Ok(Json(attachment))
+}
+
+/// Download an attachment of an asset.
+#[utoipa::path(
+    get,
+    path = "/assets/{id}/attachments/{attachment_id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Content of the attached file", content_type = "application/octet-stream", body = Vec<u8>),
+        (status = 400, description = "Invalid asset id"),
+        (status = 404, description = "Attachment not found"),
+        (status = 503, description = "Could not connect to the object store"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset id"),
+        ("attachment_id" = String, Path, description = "Attachment id"),
+    )
+)]
+pub async fn download_asset_attachment(
+    Extension(attachments): Extension<AssetAttachments>,
+    Path((id, attachment_id)): Path<(String, String)>,
+) -> Result<Response, StatusCode> {
+    rest_info!("{} [{}]", id, attachment_id);
+    let id = asset_id(&id)?;
+
+    let (attachment, content) = attachments
+        .download(&id, &attachment_id)
+        .await
+        .map_err(|e| {
+            rest_error!(
+                "could not get attachment {} of asset {}: {:?}",
+                attachment_id,
+                id,
+                e
+            );
+            attachment_error_status(&e)
+        })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", attachment.file_name),
+            ),
+        ],
+        content,
+    )
+        .into_response())
+}
+
+/// Remove an attachment of an asset together with its file.
+#[utoipa::path(
+    delete,
+    path = "/assets/{id}/attachments/{attachment_id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Attachment removed"),
+        (status = 400, description = "Invalid asset id"),
+        (status = 404, description = "Attachment not found"),
+        (status = 500, description = "The removal could not be persisted"),
+        (status = 503, description = "Could not connect to the object store"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset id"),
+        ("attachment_id" = String, Path, description = "Attachment id"),
+    )
+)]
+pub async fn remove_asset_attachment(
+    Extension(attachments): Extension<AssetAttachments>,
+    Path((id, attachment_id)): Path<(String, String)>,
+) -> Result<(), StatusCode> {
+    rest_info!("{} [{}]", id, attachment_id);
+    let id = asset_id(&id)?;
+
+    attachments
+        .remove_attachment(&id, &attachment_id)
+        .await
+        .map_err(|e| {
+            rest_error!(
+                "could not remove attachment {} of asset {}: {:?}",
+                attachment_id,
+                id,
+                e
+            );
+            attachment_error_status(&e)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use axum::extract::{FromRequest, RequestParts};
+    use lib_common::uuid::Uuid;
+
+    async fn body(content: &'static [u8]) -> BodyStream {
+        let request = hyper::Request::new(hyper::Body::from(content));
+        BodyStream::from_request(&mut RequestParts::new(request))
+            .await
+            .unwrap()
+    }
+
+    fn content_type(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, value.parse().unwrap());
+        headers
+    }
+
+    fn limits(max_bytes: usize) -> BodyLimits {
+        let mut config = Config::default();
+        config.rest_max_bulk_body_bytes = max_bytes;
+        BodyLimits::new(config.into())
+    }
+
+    fn query(file_name: &str) -> Query<AttachmentQuery> {
+        Query(AttachmentQuery {
+            file_name: file_name.to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_asset_notes() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let attachments = AssetAttachments::default();
+        let id = Uuid::new_v4().to_string();
+
+        let Json(note) = add_asset_note(
+            Extension(attachments.clone()),
+            Path(id.clone()),
+            Json(AssetNotePayload {
+                text: "Replaced rotor blade".to_string(),
+                author: None,
+            }),
+        )
+        .await
+        .unwrap();
+
+        let Json(notes) = get_asset_notes(Extension(attachments.clone()), Path(id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(notes, vec![note.clone()]);
+
+        let (status, _) = add_asset_note(
+            Extension(attachments.clone()),
+            Path(id.clone()),
+            Json(AssetNotePayload::default()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        remove_asset_note(
+            Extension(attachments.clone()),
+            Path((id.clone(), note.id.clone())),
+        )
+        .await
+        .unwrap();
+        let status = remove_asset_note(Extension(attachments.clone()), Path((id, note.id)))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let status = get_asset_notes(Extension(attachments), Path("invalid".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_asset_attachments() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let attachments = AssetAttachments::default();
+        let id = Uuid::new_v4().to_string();
+
+        let Json(attachment) = upload_asset_attachment(
+            Extension(attachments.clone()),
+            Extension(limits(1024)),
+            Path(id.clone()),
+            query("certificate.pdf"),
+            content_type("application/pdf"),
+            body(b"%PDF-1.7").await,
+        )
+        .await
+        .unwrap();
+        assert_eq!(attachment.file_name, "certificate.pdf");
+        assert_eq!(attachment.size, 8);
+
+        let Json(list) = get_asset_attachments(Extension(attachments.clone()), Path(id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(list, vec![attachment.clone()]);
+
+        let response = download_asset_attachment(
+            Extension(attachments.clone()),
+            Path((id.clone(), attachment.id.clone())),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"certificate.pdf\""
+        );
+        let content = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(&content[..], b"%PDF-1.7");
+
+        let (status, _) = upload_asset_attachment(
+            Extension(attachments.clone()),
+            Extension(limits(1024)),
+            Path(id.clone()),
+            query("page.html"),
+            content_type("text/html"),
+            body(b"<html></html>").await,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let (status, _) = upload_asset_attachment(
+            Extension(attachments.clone()),
+            Extension(limits(4)),
+            Path(id.clone()),
+            query("image.png"),
+            content_type("image/png"),
+            body(b"too large").await,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+        let (status, _) = upload_asset_attachment(
+            Extension(attachments.clone()),
+            Extension(limits(1024)),
+            Path(id.clone()),
+            query("../image.png"),
+            content_type("image/png"),
+            body(b"").await,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        remove_asset_attachment(
+            Extension(attachments.clone()),
+            Path((id.clone(), attachment.id.clone())),
+        )
+        .await
+        .unwrap();
+        let status = download_asset_attachment(Extension(attachments), Path((id, attachment.id)))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        ut_info!("success");
+    }
+}
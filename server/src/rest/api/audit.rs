@@ -0,0 +1,180 @@
+//! Handlers for the audit trail of the asset changes.
+
+pub use super::rest_types::{AuditChange, AuditChanges, AuditQuery};
+
+use crate::rest::audit::AuditLog;
+use crate::rest::auth::TokenTenant;
+use crate::rest::json::Json;
+use axum::{extract::Query, Extension};
+use hyper::StatusCode;
+
+/// Default number of changes returned
+const DEFAULT_AUDIT_LIMIT: usize = 100;
+
+/// Maximum number of changes returned
+const MAX_AUDIT_LIMIT: usize = 1000;
+
+/// Get the changes made by an operator across all asset types, most recent
+/// first.
+///
+/// Changes are recorded while the audit log is enabled, see
+/// [`crate::rest::audit`]. Only the changes made in the tenant of the API
+/// token are returned.
+#[utoipa::path(
+    get,
+    path = "/assets/audit",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Changes made by the actor", body = AuditChanges),
+        (status = 400, description = "Missing actor, invalid limit or time range"),
+        (status = 401, description = "Missing or invalid API token"),
+        (status = 403, description = "The API token requires the audit:admin scope"),
+        (status = 500, description = "The audit trail could not be read"),
+    ),
+    params(
+        AuditQuery,
+    )
+)]
+pub async fn get_audit_changes(
+    Extension(audit_log): Extension<AuditLog>,
+    tenant: Option<Extension<TokenTenant>>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<AuditChanges>, StatusCode> {
+    rest_info!("entry [{}].", query.actor);
+    rest_debug!("Query: {:?}", query);
+
+    if query.actor.trim().is_empty() {
+        rest_error!("no actor provided.");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_AUDIT_LIMIT);
+    if !(1..=MAX_AUDIT_LIMIT).contains(&limit) {
+        rest_error!("invalid limit {limit}, must be between 1 and {MAX_AUDIT_LIMIT}.");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if to <= from {
+            rest_error!("invalid time range, 'to' must be after 'from'.");
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    if !audit_log.is_enabled() {
+        rest_warn!("the audit log is disabled, new changes are not recorded.");
+    }
+
+    let tenant = tenant.map(|Extension(TokenTenant(tenant))| tenant);
+    let changes = audit_log
+        .changes(&query, tenant.as_deref(), limit)
+        .map_err(|e| {
+            rest_error!("could not read the audit trail: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SharedConfig;
+    use crate::Config;
+    use chrono::Duration;
+    use lib_common::time::Utc;
+
+    fn change(actor: &str) -> AuditChange {
+        AuditChange {
+            sequence: 0,
+            actor: actor.to_string(),
+            tenant: None,
+            timestamp: Utc::now(),
+            method: "DELETE".to_string(),
+            path: "/assets/aircraft/00000000-0000-4000-8000-000000000001".to_string(),
+            asset_type: Some("aircraft".to_string()),
+            asset_id: Some("00000000-0000-4000-8000-000000000001".to_string()),
+            status: 200,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_audit_changes() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let audit_log = AuditLog::new(SharedConfig::from(Config::default()));
+        for actor in ["alice", "bob", "alice", "alice"] {
+            audit_log.push_change(change(actor));
+        }
+        let query = |actor: &str, limit: Option<usize>| AuditQuery {
+            actor: actor.to_string(),
+            limit,
+            ..Default::default()
+        };
+
+        let Json(page) = get_audit_changes(
+            Extension(audit_log.clone()),
+            None,
+            Query(query("alice", Some(2))),
+        )
+        .await
+        .unwrap();
+        let sequences: Vec<u64> = page.changes.iter().map(|c| c.sequence).collect();
+        assert_eq!(sequences, vec![4, 3]);
+        assert_eq!(page.next_cursor, Some(3));
+
+        let mut next = query("alice", Some(2));
+        next.cursor = page.next_cursor;
+        let Json(page) = get_audit_changes(Extension(audit_log.clone()), None, Query(next))
+            .await
+            .unwrap();
+        let sequences: Vec<u64> = page.changes.iter().map(|c| c.sequence).collect();
+        assert_eq!(sequences, vec![1]);
+        assert_eq!(page.next_cursor, None);
+
+        let Json(page) = get_audit_changes(
+            Extension(audit_log.clone()),
+            None,
+            Query(query("bob", None)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.changes.len(), 1);
+
+        // the changes of another tenant are not returned
+        let mut staging = change("alice");
+        staging.tenant = Some("staging".to_string());
+        audit_log.push_change(staging);
+        let Json(page) = get_audit_changes(
+            Extension(audit_log.clone()),
+            Some(Extension(TokenTenant("staging".to_string()))),
+            Query(query("alice", None)),
+        )
+        .await
+        .unwrap();
+        let sequences: Vec<u64> = page.changes.iter().map(|c| c.sequence).collect();
+        assert_eq!(sequences, vec![5]);
+
+        // invalid queries
+        for invalid in [
+            query(" ", None),
+            query("alice", Some(0)),
+            query("alice", Some(1001)),
+        ] {
+            let error = get_audit_changes(Extension(audit_log.clone()), None, Query(invalid))
+                .await
+                .unwrap_err();
+            assert_eq!(error, StatusCode::BAD_REQUEST);
+        }
+        let mut range = query("alice", None);
+        range.from = Some(Utc::now());
+        range.to = range.from.map(|from| from - Duration::hours(1));
+        let error = get_audit_changes(Extension(audit_log), None, Query(range))
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,206 @@
+//! Helpers for fetching multiple assets by id in a single request.
+
+pub use super::rest_types::BatchGetPayload;
+
+use super::aircraft::Aircraft;
+use super::conversion::{
+    convert_objects, ConversionFailure, ConversionMode, FillMissing, ListError,
+};
+use super::vertipad::Vertipad;
+use super::vertiport::Vertiport;
+use futures::future::join_all;
+use hyper::StatusCode;
+use lib_common::uuid::to_uuid;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use utoipa::ToSchema;
+
+/// Maximum number of ids accepted in a single batch request
+pub const MAX_BATCH_SIZE: usize = 50;
+
+/// Result of a batch get request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[aliases(AircraftBatch = BatchGetResponse<Aircraft>, VertiportBatch = BatchGetResponse<Vertiport>, VertipadBatch = BatchGetResponse<Vertipad>)]
+pub struct BatchGetResponse<T> {
+    /// The objects found, in the order they were requested.
+    pub found: Vec<T>,
+    /// The requested ids which could not be found.
+    pub missing: Vec<String>,
+    /// The requested objects which could not be converted, see
+    /// [`ConversionMode`].
+    pub failed: Vec<ConversionFailure>,
+}
+
+/// Validate the requested ids, removing duplicates while keeping the order.
+///
+/// Returns `BAD_REQUEST` if no ids, too many ids or invalid UUIDs are provided.
+pub fn parse_batch_ids(payload: BatchGetPayload) -> Result<Vec<String>, StatusCode> {
+    if payload.ids.is_empty() || payload.ids.len() > MAX_BATCH_SIZE {
+        rest_error!(
+            "batch requests must contain between 1 and {} ids, got {}.",
+            MAX_BATCH_SIZE,
+            payload.ids.len()
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut ids: Vec<String> = Vec::with_capacity(payload.ids.len());
+    for id in payload.ids.iter() {
+        let id = to_uuid(id)
+            .ok_or_else(|| {
+                rest_error!("Invalid id: {}", id);
+                StatusCode::BAD_REQUEST
+            })?
+            .to_string();
+
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Fetch all ids concurrently using the provided function and convert the
+/// objects found like the list endpoints.
+///
+/// The function should return [`None`] for ids that could not be found.
+/// Objects which can not be converted are reported in `failed`, or fail the
+/// request in [`ConversionMode::Strict`].
+pub async fn batch_get<O, T, F, Fut>(
+    ids: Vec<String>,
+    mode: ConversionMode,
+    get: F,
+) -> Result<BatchGetResponse<T>, ListError>
+where
+    O: FillMissing,
+    T: TryFrom<O, Error = String>,
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Option<O>, StatusCode>>,
+{
+    let results = join_all(ids.iter().cloned().map(get)).await;
+
+    let mut objects = vec![];
+    let mut missing = vec![];
+    for (id, result) in ids.into_iter().zip(results) {
+        match result? {
+            Some(object) => objects.push(object),
+            None => missing.push(id),
+        }
+    }
+
+    let listed = convert_objects(objects, mode)?;
+    Ok(BatchGetResponse {
+        found: listed.assets,
+        missing,
+        failed: listed.dropped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::VertiportDataBuilder;
+    use lib_common::time::Utc;
+    use lib_common::uuid::Uuid;
+    use svc_storage_client_grpc::prelude::vertiport;
+
+    #[test]
+    fn test_parse_batch_ids() {
+        let id = Uuid::new_v4().to_string();
+        let ids = parse_batch_ids(BatchGetPayload {
+            ids: vec![id.clone(), id.clone()],
+        })
+        .unwrap();
+        assert_eq!(ids, vec![id]);
+
+        let error = parse_batch_ids(BatchGetPayload { ids: vec![] }).unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        let error = parse_batch_ids(BatchGetPayload {
+            ids: vec!["invalid".to_string()],
+        })
+        .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        let error = parse_batch_ids(BatchGetPayload {
+            ids: (0..=MAX_BATCH_SIZE)
+                .map(|_| Uuid::new_v4().to_string())
+                .collect(),
+        })
+        .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+    }
+
+    fn object(id: &str) -> Result<Option<vertiport::Object>, StatusCode> {
+        let now = Utc::now();
+        let data = match id {
+            "2" => return Ok(None),
+            "4" => None,
+            _ => Some(VertiportDataBuilder::new().timestamps(now, now).build()),
+        };
+        Ok(Some(vertiport::Object {
+            id: id.to_string(),
+            data,
+        }))
+    }
+
+    fn found_ids(response: &BatchGetResponse<Vertiport>) -> Vec<&str> {
+        response
+            .found
+            .iter()
+            .map(|vertiport| vertiport.basics.id.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_batch_get() {
+        let ids = vec!["1".to_string(), "2".to_string(), "3".to_string()];
+        let response: BatchGetResponse<Vertiport> =
+            batch_get(ids.clone(), ConversionMode::Skip, |id| async move {
+                object(&id)
+            })
+            .await
+            .unwrap();
+        assert_eq!(found_ids(&response), vec!["1", "3"]);
+        assert_eq!(response.missing, vec!["2".to_string()]);
+        assert!(response.failed.is_empty());
+
+        let error = batch_get::<_, Vertiport, _, _>(ids, ConversionMode::Skip, |id| async move {
+            match id.as_str() {
+                "3" => Err(StatusCode::SERVICE_UNAVAILABLE),
+                _ => object(&id),
+            }
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(error, ListError::Status(StatusCode::SERVICE_UNAVAILABLE));
+    }
+
+    #[tokio::test]
+    async fn test_batch_get_unconvertible() {
+        let ids = vec!["1".to_string(), "4".to_string()];
+        let response: BatchGetResponse<Vertiport> =
+            batch_get(ids.clone(), ConversionMode::Report, |id| async move {
+                object(&id)
+            })
+            .await
+            .unwrap();
+        assert_eq!(found_ids(&response), vec!["1"]);
+        assert!(response.missing.is_empty());
+        assert_eq!(
+            response.failed,
+            vec![ConversionFailure {
+                id: "4".to_string(),
+                reason: "(try_from) vertiport data is missing".to_string(),
+            }]
+        );
+
+        let error = batch_get::<_, Vertiport, _, _>(ids, ConversionMode::Strict, |id| async move {
+            object(&id)
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(error.status(), StatusCode::BAD_GATEWAY);
+    }
+}
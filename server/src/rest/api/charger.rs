@@ -0,0 +1,359 @@
+//! Handlers for the vertipad charger API endpoints.
+
+pub use super::rest_types::{
+    Charger, ChargerAvailability, ChargerConnector, ChargerPayload, VertipadChargerQuery,
+};
+
+use super::errors::storage_error_status;
+use super::validation::*;
+
+use crate::repo::Repositories;
+use crate::rest::chargers::{ChargerError, VertipadChargers, MAX_CHARGERS_PER_VERTIPAD};
+use crate::rest::json::Json;
+use axum::{extract::Path, Extension};
+use chrono::Utc;
+use hyper::StatusCode;
+use lib_common::uuid::to_uuid;
+
+/// Maximum charging power in kilowatts of a charger
+const MAX_CHARGER_KW: f64 = 5000.0;
+
+/// Validate a charger payload.
+pub fn validate_charger(payload: ChargerPayload) -> Result<ChargerPayload, Vec<FieldError>> {
+    let mut errors = vec![];
+    if !payload.max_kw.is_finite() || !(1.0..=MAX_CHARGER_KW).contains(&payload.max_kw) {
+        errors.push(field_error(
+            "max_kw",
+            &format!("must be between 1 and {MAX_CHARGER_KW}"),
+        ));
+    }
+
+    into_result(payload, errors)
+}
+
+/// Parse a vertipad or charger id, returning `BAD_REQUEST` if it is not a
+/// valid UUID.
+fn parse_id(kind: &str, id: &str) -> Result<String, StatusCode> {
+    to_uuid(id).map(|id| id.to_string()).ok_or_else(|| {
+        rest_error!("Invalid {} id: {}", kind, id);
+        StatusCode::BAD_REQUEST
+    })
+}
+
+/// Map an error of an existing charger change to its status code.
+fn charger_error_status(vertipad_id: &str, charger_id: &str, e: ChargerError) -> StatusCode {
+    match e {
+        ChargerError::State(e) => {
+            rest_error!("could not persist charger {}: {e}", charger_id);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        _ => {
+            rest_info!(
+                "charger {} of vertipad {} not found.",
+                charger_id,
+                vertipad_id
+            );
+            StatusCode::NOT_FOUND
+        }
+    }
+}
+
+/// Check that a vertipad exists in storage.
+async fn check_vertipad(repos: &Repositories, vertipad_id: &str) -> Result<(), StatusCode> {
+    repos
+        .vertipads
+        .get_by_id(vertipad_id.to_string())
+        .await
+        .map(|_| ())
+        .map_err(|e| {
+            rest_error!("could not get vertipad: {e}");
+            storage_error_status(&e)
+        })
+}
+
+/// Get the chargers of a vertipad.
+#[utoipa::path(
+    get,
+    path = "/assets/vertipads/{id}/chargers",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Chargers of vertipad {id}, in the order they were added", body = [Charger]),
+        (status = 400, description = "Invalid vertipad id"),
+        (status = 404, description = "Vertipad not found in database"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+    )
+)]
+pub async fn get_vertipad_chargers(
+    Extension(repos): Extension<Repositories>,
+    Extension(chargers): Extension<VertipadChargers>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<Charger>>, StatusCode> {
+    rest_info!("entry [{}].", id);
+    let id = parse_id("vertipad", &id)?;
+    check_vertipad(&repos, &id).await?;
+
+    Ok(Json(chargers.list(&id)))
+}
+
+/// Add a charger to a vertipad.
+///
+/// The charger is persisted by this instance, see [`crate::rest::chargers`].
+#[utoipa::path(
+    post,
+    path = "/assets/vertipads/{id}/chargers",
+    tag = "svc-assets",
+    request_body = ChargerPayload,
+    responses(
+        (status = 200, description = "Charger added; the new charger is returned", body = Charger),
+        (status = 400, description = "Invalid vertipad id"),
+        (status = 404, description = "Vertipad not found in database"),
+        (status = 422, description = "Invalid charger or too many chargers", body = [FieldError]),
+        (status = 500, description = "The charger could not be persisted"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+    )
+)]
+pub async fn add_vertipad_charger(
+    Extension(repos): Extension<Repositories>,
+    Extension(chargers): Extension<VertipadChargers>,
+    Path(id): Path<String>,
+    Json(payload): Json<ChargerPayload>,
+) -> Result<Json<Charger>, ValidationError> {
+    rest_info!("entry [{}].", id);
+    let id = parse_id("vertipad", &id).map_err(|status| (status, Json(vec![])))?;
+    let payload = validate_charger(payload).map_err(unprocessable)?;
+    check_vertipad(&repos, &id)
+        .await
+        .map_err(|status| (status, Json(vec![])))?;
+
+    let charger = chargers
+        .add(&id, payload, Utc::now())
+        .map_err(|e| match e {
+            ChargerError::State(e) => {
+                rest_error!("could not persist charger: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+            }
+            _ => {
+                rest_error!("reached the maximum number of chargers of vertipad {}.", id);
+                unprocessable(vec![field_error(
+                    "chargers",
+                    &format!("can not contain more than {MAX_CHARGERS_PER_VERTIPAD} chargers"),
+                )])
+            }
+        })?;
+
+    rest_info!("added charger [{}] to vertipad [{}].", charger.id, id);
+    Ok(Json(charger))
+}
+
+/// Get a charger of a vertipad by its id.
+#[utoipa::path(
+    get,
+    path = "/assets/vertipads/{id}/chargers/{charger_id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Charger {charger_id} found", body = Charger),
+        (status = 400, description = "Invalid vertipad or charger id"),
+        (status = 404, description = "Charger not found"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+        ("charger_id" = String, Path, description = "Charger id"),
+    )
+)]
+pub async fn get_vertipad_charger(
+    Extension(chargers): Extension<VertipadChargers>,
+    Path((id, charger_id)): Path<(String, String)>,
+) -> Result<Json<Charger>, StatusCode> {
+    rest_info!("entry [{}] [{}].", id, charger_id);
+    let id = parse_id("vertipad", &id)?;
+    let charger_id = parse_id("charger", &charger_id)?;
+
+    chargers.get(&id, &charger_id).map(Json).ok_or_else(|| {
+        rest_info!("charger {} of vertipad {} not found.", charger_id, id);
+        StatusCode::NOT_FOUND
+    })
+}
+
+/// Replace the connector, power and availability of a charger.
+#[utoipa::path(
+    put,
+    path = "/assets/vertipads/{id}/chargers/{charger_id}",
+    tag = "svc-assets",
+    request_body = ChargerPayload,
+    responses(
+        (status = 200, description = "Charger updated; the updated charger is returned", body = Charger),
+        (status = 400, description = "Invalid vertipad or charger id"),
+        (status = 404, description = "Charger not found"),
+        (status = 422, description = "Invalid charger", body = [FieldError]),
+        (status = 500, description = "The charger could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+        ("charger_id" = String, Path, description = "Charger id"),
+    )
+)]
+pub async fn update_vertipad_charger(
+    Extension(chargers): Extension<VertipadChargers>,
+    Path((id, charger_id)): Path<(String, String)>,
+    Json(payload): Json<ChargerPayload>,
+) -> Result<Json<Charger>, ValidationError> {
+    rest_info!("entry [{}] [{}].", id, charger_id);
+    let id = parse_id("vertipad", &id).map_err(|status| (status, Json(vec![])))?;
+    let charger_id = parse_id("charger", &charger_id).map_err(|status| (status, Json(vec![])))?;
+    let payload = validate_charger(payload).map_err(unprocessable)?;
+
+    let charger = chargers
+        .update(&id, &charger_id, payload, Utc::now())
+        .map_err(|e| (charger_error_status(&id, &charger_id, e), Json(vec![])))?;
+
+    Ok(Json(charger))
+}
+
+/// Remove a charger of a vertipad.
+#[utoipa::path(
+    delete,
+    path = "/assets/vertipads/{id}/chargers/{charger_id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Charger removed"),
+        (status = 400, description = "Invalid vertipad or charger id"),
+        (status = 404, description = "Charger not found"),
+        (status = 500, description = "The charger could not be removed"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+        ("charger_id" = String, Path, description = "Charger id"),
+    )
+)]
+pub async fn remove_vertipad_charger(
+    Extension(chargers): Extension<VertipadChargers>,
+    Path((id, charger_id)): Path<(String, String)>,
+) -> Result<(), StatusCode> {
+    rest_info!("entry [{}] [{}].", id, charger_id);
+    let id = parse_id("vertipad", &id)?;
+    let charger_id = parse_id("charger", &charger_id)?;
+
+    chargers
+        .remove(&id, &charger_id)
+        .map_err(|e| charger_error_status(&id, &charger_id, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{VertipadDataBuilder, VertiportDataBuilder};
+    use lib_common::uuid::Uuid;
+
+    fn payload(max_kw: f64) -> ChargerPayload {
+        ChargerPayload {
+            connector: ChargerConnector::Mcs,
+            max_kw,
+            availability: ChargerAvailability::Available,
+        }
+    }
+
+    #[test]
+    fn test_validate_charger() {
+        assert_eq!(validate_charger(payload(150.0)), Ok(payload(150.0)));
+        for max_kw in [0.0, -1.0, 5001.0, f64::NAN] {
+            let errors = validate_charger(payload(max_kw)).unwrap_err();
+            assert_eq!(errors[0].field, "max_kw");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vertipad_chargers() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let vertiport_id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let vertipad_id = repos
+            .vertipads
+            .insert(
+                VertipadDataBuilder::new()
+                    .vertiport_id(vertiport_id)
+                    .build(),
+            )
+            .await
+            .unwrap()
+            .id;
+        let chargers = VertipadChargers::default();
+        let ext = || (Extension(repos.clone()), Extension(chargers.clone()));
+
+        let (r, c) = ext();
+        let Json(charger) =
+            add_vertipad_charger(r, c, Path(vertipad_id.clone()), Json(payload(350.0)))
+                .await
+                .unwrap();
+        assert_eq!(charger.vertipad_id, vertipad_id);
+
+        let (r, c) = ext();
+        let Json(list) = get_vertipad_chargers(r, c, Path(vertipad_id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(list, vec![charger.clone()]);
+
+        let ids = || Path((vertipad_id.clone(), charger.id.clone()));
+        let Json(found) = get_vertipad_charger(Extension(chargers.clone()), ids())
+            .await
+            .unwrap();
+        assert_eq!(found, charger);
+
+        let mut changed = payload(150.0);
+        changed.availability = ChargerAvailability::OutOfService;
+        let Json(updated) =
+            update_vertipad_charger(Extension(chargers.clone()), ids(), Json(changed))
+                .await
+                .unwrap();
+        assert_eq!(updated.max_kw, 150.0);
+        assert_eq!(updated.availability, ChargerAvailability::OutOfService);
+
+        // invalid payloads and ids
+        let (status, errors) =
+            update_vertipad_charger(Extension(chargers.clone()), ids(), Json(payload(0.0)))
+                .await
+                .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "max_kw");
+
+        let (r, c) = ext();
+        let (status, _) =
+            add_vertipad_charger(r, c, Path("invalid".to_string()), Json(payload(50.0)))
+                .await
+                .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (r, c) = ext();
+        let (status, _) =
+            add_vertipad_charger(r, c, Path(Uuid::new_v4().to_string()), Json(payload(50.0)))
+                .await
+                .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        remove_vertipad_charger(Extension(chargers.clone()), ids())
+            .await
+            .unwrap();
+        let error = get_vertipad_charger(Extension(chargers.clone()), ids())
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+        let error = remove_vertipad_charger(Extension(chargers.clone()), ids())
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,486 @@
+//! Conversion of the storage objects returned by the list endpoints.
+//!
+//! A storage object without timestamps or a location can not be converted
+//! to an asset. The [`ConversionMode`] configuration decides what the list
+//! endpoints do with such an object:
+//! * [`ConversionMode::Skip`] leaves it out of the list
+//! * [`ConversionMode::Lenient`] fills the missing fields with defaults and
+//!   reports a `Warning` header for the object
+//! * [`ConversionMode::Strict`] fails the request with a
+//!   `502 BAD GATEWAY`, reporting how many objects could not be converted
+//! * [`ConversionMode::Report`] leaves it out of the list and responds with
+//!   a `207 Multi-Status` [`PartialList`] naming the failed objects and the
+//!   reasons next to the converted assets
+//!
+//! Objects left out of a list are counted in the [`DROPPED_RECORDS_HEADER`]
+//! of the response and logged with their ids, so a short list can be told
+//! apart from corrupted data.
+
+pub use super::rest_types::{ConversionFailure, PartialList, SkippedObjects};
+pub use crate::config::ConversionMode;
+
+use super::validation::field_error;
+use crate::rest::json::Json;
+use axum::http::{header, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use hyper::StatusCode;
+use lib_common::time::{DateTime, Utc};
+use serde::Serialize;
+use std::time::UNIX_EPOCH;
+use svc_storage_client_grpc::prelude::{vehicle, vertipad, vertiport, GeoPointZ, GeoPolygonZ};
+
+/// Header with the number of objects left out of a list because they could
+/// not be converted
+pub const DROPPED_RECORDS_HEADER: &str = "x-dropped-records";
+
+/// Content type of a [`PartialList`] response
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Problem type of a [`PartialList`] response
+pub const PARTIAL_LIST_PROBLEM_TYPE: &str = "urn:svc-assets:problem:unconvertible-objects";
+
+/// Query parameters narrowing down a list, suggested when a list is too
+/// large
+const LIST_FILTERS: &str = "created_after, created_before or updated_after";
+
+/// Storage objects of which missing fields can be filled with defaults
+pub trait FillMissing {
+    /// Get the id of the object
+    fn object_id(&self) -> &str;
+
+    /// Fill the missing fields with defaults, returning the names of the
+    /// filled fields
+    fn fill_missing(&mut self) -> Vec<&'static str>;
+}
+
+/// Fill missing timestamps, using the other timestamp if available and the
+/// unix epoch otherwise
+fn fill_timestamps<T: Clone + From<DateTime<Utc>>>(
+    created_at: &mut Option<T>,
+    updated_at: &mut Option<T>,
+    filled: &mut Vec<&'static str>,
+) {
+    if created_at.is_none() {
+        *created_at = Some(
+            updated_at
+                .clone()
+                .unwrap_or_else(|| DateTime::<Utc>::from(UNIX_EPOCH).into()),
+        );
+        filled.push("created_at");
+    }
+    if updated_at.is_none() {
+        *updated_at = created_at.clone();
+        filled.push("updated_at");
+    }
+}
+
+impl FillMissing for vehicle::Object {
+    fn object_id(&self) -> &str {
+        &self.id
+    }
+
+    fn fill_missing(&mut self) -> Vec<&'static str> {
+        let mut filled = vec![];
+        if let Some(data) = self.data.as_mut() {
+            fill_timestamps(&mut data.created_at, &mut data.updated_at, &mut filled);
+        }
+        filled
+    }
+}
+
+impl FillMissing for vertiport::Object {
+    fn object_id(&self) -> &str {
+        &self.id
+    }
+
+    fn fill_missing(&mut self) -> Vec<&'static str> {
+        let mut filled = vec![];
+        if let Some(data) = self.data.as_mut() {
+            fill_timestamps(&mut data.created_at, &mut data.updated_at, &mut filled);
+            if data.geo_location.is_none() {
+                data.geo_location = Some(GeoPolygonZ { rings: vec![] });
+                filled.push("geo_location");
+            }
+        }
+        filled
+    }
+}
+
+impl FillMissing for vertipad::Object {
+    fn object_id(&self) -> &str {
+        &self.id
+    }
+
+    fn fill_missing(&mut self) -> Vec<&'static str> {
+        let mut filled = vec![];
+        if let Some(data) = self.data.as_mut() {
+            fill_timestamps(&mut data.created_at, &mut data.updated_at, &mut filled);
+            if data.geo_location.is_none() {
+                data.geo_location = Some(GeoPointZ {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 0.0,
+                });
+                filled.push("geo_location");
+            }
+        }
+        filled
+    }
+}
+
+/// An object converted with defaults for its missing fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionWarning {
+    /// Id of the object
+    pub id: String,
+    /// Fields filled with a default
+    pub fields: Vec<&'static str>,
+}
+
+impl ConversionWarning {
+    /// Get the value of the `Warning` header reporting this warning
+    pub fn header_value(&self) -> String {
+        format!(
+            "199 svc-assets \"{} converted with defaults for {}\"",
+            self.id,
+            self.fields.join(", ")
+        )
+    }
+}
+
+/// Error of a list endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListError {
+    /// The request failed with the status code
+    Status(StatusCode),
+    /// Objects could not be converted in [`ConversionMode::Strict`]
+    Unconvertible(SkippedObjects),
+    /// More objects match the query than the provided maximum, see
+    /// [`super::list::ListLimits`]
+    TooMany(usize),
+}
+
+impl ListError {
+    /// Get the status code of the error
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ListError::Status(status) => *status,
+            ListError::Unconvertible(_) => StatusCode::BAD_GATEWAY,
+            ListError::TooMany(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+impl From<StatusCode> for ListError {
+    fn from(status: StatusCode) -> Self {
+        ListError::Status(status)
+    }
+}
+
+impl IntoResponse for ListError {
+    fn into_response(self) -> Response {
+        match self {
+            ListError::Status(status) => status.into_response(),
+            ListError::Unconvertible(skipped) => {
+                (StatusCode::BAD_GATEWAY, Json(skipped)).into_response()
+            }
+            ListError::TooMany(max) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(vec![field_error(
+                    "query",
+                    &format!(
+                        "more than {max} assets match the query, narrow it down with the {LIST_FILTERS} filters"
+                    ),
+                )]),
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// Assets returned by a list endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listed<T> {
+    /// The assets
+    pub assets: Vec<T>,
+    /// Objects converted with defaults for their missing fields
+    pub warnings: Vec<ConversionWarning>,
+    /// Objects which could not be converted
+    pub dropped: Vec<ConversionFailure>,
+    /// Respond with a [`PartialList`] if objects could not be converted,
+    /// see [`ConversionMode::Report`]
+    pub report: bool,
+    /// Warning threshold exceeded by the number of assets, see
+    /// [`super::list::ListLimits`]
+    pub exceeded_warning: Option<usize>,
+}
+
+impl<T: Serialize> Listed<T> {
+    /// Get the `207 Multi-Status` body reporting the dropped objects
+    pub fn partial_list(&self) -> PartialList {
+        PartialList {
+            problem_type: PARTIAL_LIST_PROBLEM_TYPE.to_string(),
+            title: "Stored objects could not be converted".to_string(),
+            status: StatusCode::MULTI_STATUS.as_u16(),
+            detail: format!(
+                "{} objects listed, {} could not be converted",
+                self.assets.len(),
+                self.dropped.len()
+            ),
+            items: self
+                .assets
+                .iter()
+                .filter_map(|asset| serde_json::to_value(asset).ok())
+                .collect(),
+            failed: self.dropped.clone(),
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Listed<T> {
+    fn into_response(self) -> Response {
+        let mut response = match self.report && !self.dropped.is_empty() {
+            true => (
+                StatusCode::MULTI_STATUS,
+                [(header::CONTENT_TYPE, PROBLEM_CONTENT_TYPE)],
+                Json(self.partial_list()),
+            )
+                .into_response(),
+            false => Json(self.assets).into_response(),
+        };
+        response.headers_mut().insert(
+            DROPPED_RECORDS_HEADER,
+            HeaderValue::from(self.dropped.len()),
+        );
+        for warning in self.warnings.iter() {
+            if let Ok(value) = HeaderValue::from_str(&warning.header_value()) {
+                response.headers_mut().append(header::WARNING, value);
+            }
+        }
+        if let Some(threshold) = self.exceeded_warning {
+            let warning = format!(
+                "299 svc-assets \"more than {threshold} assets listed, narrow the list down with the {LIST_FILTERS} filters\""
+            );
+            if let Ok(value) = HeaderValue::from_str(&warning) {
+                response.headers_mut().append(header::WARNING, value);
+            }
+        }
+        response
+    }
+}
+
+/// Convert the storage objects of a list endpoint.
+///
+/// Objects which can not be converted are logged and handled according to
+/// the `mode`.
+pub fn convert_objects<O, T>(objects: Vec<O>, mode: ConversionMode) -> Result<Listed<T>, ListError>
+where
+    O: FillMissing,
+    T: TryFrom<O, Error = String>,
+{
+    let total = objects.len();
+    let mut listed = Listed {
+        assets: Vec::with_capacity(total),
+        warnings: vec![],
+        dropped: vec![],
+        report: mode == ConversionMode::Report,
+        exceeded_warning: None,
+    };
+    for mut object in objects {
+        let id = object.object_id().to_string();
+        let filled = match mode {
+            ConversionMode::Lenient => object.fill_missing(),
+            ConversionMode::Skip | ConversionMode::Strict | ConversionMode::Report => vec![],
+        };
+
+        match T::try_from(object) {
+            Ok(asset) => {
+                if !filled.is_empty() {
+                    rest_warn!("converted object [{}] with defaults for {:?}.", id, filled);
+                    listed
+                        .warnings
+                        .push(ConversionWarning { id, fields: filled });
+                }
+                listed.assets.push(asset);
+            }
+            Err(e) => {
+                rest_debug!("could not convert object [{}]: {e}", id);
+                listed.dropped.push(ConversionFailure { id, reason: e });
+            }
+        }
+    }
+
+    if listed.dropped.is_empty() {
+        return Ok(listed);
+    }
+
+    let skipped = listed.dropped.len();
+    let ids: Vec<&str> = listed
+        .dropped
+        .iter()
+        .map(|failure| failure.id.as_str())
+        .collect();
+    if mode == ConversionMode::Strict {
+        rest_error!(
+            "could not convert {} of {} objects: {:?}.",
+            skipped,
+            total,
+            ids
+        );
+        return Err(ListError::Unconvertible(SkippedObjects { skipped, total }));
+    }
+
+    rest_warn!(
+        "dropped {} of {} objects which could not be converted: {:?}.",
+        skipped,
+        total,
+        ids
+    );
+
+    Ok(listed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::api::vertiport::Vertiport;
+    use crate::testing::VertiportDataBuilder;
+
+    fn dropped_ids<T>(listed: &Listed<T>) -> Vec<&str> {
+        listed
+            .dropped
+            .iter()
+            .map(|failure| failure.id.as_str())
+            .collect()
+    }
+
+    fn objects() -> Vec<vertiport::Object> {
+        let now = Utc::now();
+        let valid = VertiportDataBuilder::new().timestamps(now, now).build();
+        let mut missing = valid.clone();
+        missing.created_at = None;
+        missing.geo_location = None;
+
+        vec![
+            vertiport::Object {
+                id: "valid".to_string(),
+                data: Some(valid),
+            },
+            vertiport::Object {
+                id: "missing".to_string(),
+                data: Some(missing),
+            },
+            vertiport::Object {
+                id: "empty".to_string(),
+                data: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_convert_objects() {
+        let listed: Listed<Vertiport> = convert_objects(objects(), ConversionMode::Skip).unwrap();
+        assert_eq!(listed.assets.len(), 1);
+        assert!(listed.warnings.is_empty());
+        assert_eq!(dropped_ids(&listed), vec!["missing", "empty"]);
+        assert!(!listed.report);
+        assert_eq!(
+            listed.dropped[1].reason,
+            "(try_from) vertiport data is missing"
+        );
+
+        let listed: Listed<Vertiport> =
+            convert_objects(objects(), ConversionMode::Lenient).unwrap();
+        assert_eq!(listed.assets.len(), 2);
+        assert_eq!(dropped_ids(&listed), vec!["empty"]);
+        assert_eq!(
+            listed.warnings,
+            vec![ConversionWarning {
+                id: "missing".to_string(),
+                fields: vec!["created_at", "geo_location"],
+            }]
+        );
+        let filled = &listed.assets[1];
+        assert_eq!(filled.basics.created_at, filled.basics.updated_at);
+        assert!(filled.geo_location.as_ref().unwrap().rings.is_empty());
+        assert_eq!(filled.centroid, None);
+        assert_eq!(filled.bbox, None);
+
+        let error = convert_objects::<_, Vertiport>(objects(), ConversionMode::Strict).unwrap_err();
+        assert_eq!(
+            error,
+            ListError::Unconvertible(SkippedObjects {
+                skipped: 2,
+                total: 3
+            })
+        );
+        assert_eq!(error.status(), StatusCode::BAD_GATEWAY);
+
+        let listed: Listed<Vertiport> = convert_objects(objects(), ConversionMode::Report).unwrap();
+        assert_eq!(listed.assets.len(), 1);
+        assert!(listed.warnings.is_empty());
+        assert_eq!(dropped_ids(&listed), vec!["missing", "empty"]);
+        assert!(listed.report);
+    }
+
+    #[test]
+    fn test_listed_response() {
+        let response = Listed {
+            assets: vec![1, 2],
+            warnings: vec![ConversionWarning {
+                id: "id".to_string(),
+                fields: vec!["created_at"],
+            }],
+            dropped: vec![ConversionFailure {
+                id: "dropped".to_string(),
+                reason: "data is missing".to_string(),
+            }],
+            report: false,
+            exceeded_warning: None,
+        }
+        .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[DROPPED_RECORDS_HEADER], "1");
+        assert_eq!(
+            response.headers()[header::WARNING],
+            "199 svc-assets \"id converted with defaults for created_at\""
+        );
+    }
+    #[tokio::test]
+    async fn test_partial_list_response() {
+        let listed = Listed {
+            assets: vec![1, 2],
+            warnings: vec![],
+            dropped: vec![],
+            report: true,
+            exceeded_warning: None,
+        };
+        // lists without failures keep the plain response
+        let response = listed.clone().into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let failure = ConversionFailure {
+            id: "dropped".to_string(),
+            reason: "data is missing".to_string(),
+        };
+        let response = Listed {
+            dropped: vec![failure.clone()],
+            ..listed
+        }
+        .into_response();
+        assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            PROBLEM_CONTENT_TYPE
+        );
+        assert_eq!(response.headers()[DROPPED_RECORDS_HEADER], "1");
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let list: PartialList = serde_json::from_slice(&body).unwrap();
+        assert_eq!(list.problem_type, PARTIAL_LIST_PROBLEM_TYPE);
+        assert_eq!(list.status, 207);
+        assert_eq!(list.detail, "2 objects listed, 1 could not be converted");
+        assert_eq!(list.items, vec![serde_json::json!(1), serde_json::json!(2)]);
+        assert_eq!(list.failed, vec![failure]);
+    }
+}
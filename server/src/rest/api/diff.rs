@@ -0,0 +1,287 @@
+//! Handlers for incremental synchronization of the asset inventory.
+
+pub use super::rest_types::DiffQuery;
+
+use super::aircraft::Aircraft;
+use super::errors::storage_error_status;
+use super::rest_types::timestamp;
+use super::vertipad::Vertipad;
+use super::vertiport::Vertiport;
+use crate::hold::MaintenanceHolds;
+use crate::maintenance::OverdueAircraft;
+use crate::repo::Repositories;
+use crate::rest::json::Json;
+use crate::rest::structs::Basics;
+use axum::{extract::Query, Extension};
+use duplicate::duplicate_item;
+use hyper::StatusCode;
+use lib_common::time::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use svc_storage_client_grpc::prelude::AdvancedSearchFilter;
+use utoipa::ToSchema;
+
+/// Changes of a single asset type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[aliases(AircraftChanges = AssetChanges<Aircraft>, VertiportChanges = AssetChanges<Vertiport>, VertipadChanges = AssetChanges<Vertipad>)]
+pub struct AssetChanges<T> {
+    /// Assets created since the requested time.
+    pub created: Vec<T>,
+    /// Assets created before, and updated since the requested time.
+    pub updated: Vec<T>,
+    /// The ids of the assets deleted since the requested time.
+    pub deleted: Vec<String>,
+}
+
+/// Changes of the asset inventory since a point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AssetDiff {
+    /// The requested time.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub since: DateTime<Utc>,
+    /// Time the changes were collected; use as `since` of the next request.
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub until: DateTime<Utc>,
+    /// Changes of the aircraft.
+    pub aircraft: AircraftChanges,
+    /// Changes of the vertiports.
+    pub vertiports: VertiportChanges,
+    /// Changes of the vertipads.
+    pub vertipads: VertipadChanges,
+}
+
+/// Split changed assets into created and updated assets.
+///
+/// Assets which did not change since `since` are dropped. Deleted ids of
+/// assets which are still available are dropped as well.
+fn split_changes<T>(
+    since: DateTime<Utc>,
+    changed: Vec<T>,
+    deleted: Vec<String>,
+    basics: impl Fn(&T) -> &Basics,
+) -> AssetChanges<T> {
+    let mut changes = AssetChanges {
+        created: vec![],
+        updated: vec![],
+        deleted: vec![],
+    };
+
+    for asset in changed {
+        let asset_basics = basics(&asset);
+        if asset_basics.created_at >= since {
+            changes.created.push(asset);
+        } else if asset_basics.updated_at >= since {
+            changes.updated.push(asset);
+        }
+    }
+
+    changes.deleted = deleted
+        .into_iter()
+        .filter(|id| {
+            !changes
+                .created
+                .iter()
+                .chain(changes.updated.iter())
+                .any(|asset| &basics(asset).id == id)
+        })
+        .collect();
+
+    changes
+}
+
+#[duplicate_item(
+    changes                 repository      asset_type;
+    [aircraft_changes]      [aircraft]      [Aircraft];
+    [vertiport_changes]     [vertiports]    [Vertiport];
+    [vertipad_changes]      [vertipads]     [Vertipad];
+)]
+/// Get the changes of a single asset type since the provided time.
+async fn changes(
+    repos: &Repositories,
+    since: DateTime<Utc>,
+) -> Result<AssetChanges<asset_type>, StatusCode> {
+    let timestamp = since.to_rfc3339();
+
+    // Created assets have an `updated_at` equal to their `created_at`
+    let filter =
+        AdvancedSearchFilter::search_greater_or_equal("updated_at".to_string(), timestamp.clone())
+            .and_is_null("deleted_at".to_string());
+    let changed = repos
+        .repository
+        .search(filter)
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve changed assets: {e}.");
+            storage_error_status(&e)
+        })?
+        .into_iter()
+        .filter_map(|object| asset_type::try_from(object).ok())
+        .collect();
+
+    let filter = AdvancedSearchFilter::search_greater_or_equal("deleted_at".to_string(), timestamp);
+    let deleted = repos
+        .repository
+        .search(filter)
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve deleted assets: {e}.");
+            storage_error_status(&e)
+        })?
+        .into_iter()
+        .map(|object| object.id)
+        .collect();
+
+    Ok(split_changes(since, changed, deleted, |asset| {
+        &asset.basics
+    }))
+}
+
+/// Get the assets created, updated and deleted since a point in time.
+///
+/// Allows consumers to keep a copy of the asset inventory in sync without
+/// getting all assets. The `until` of the response can be used as `since`
+/// of the next request.
+#[utoipa::path(
+    get,
+    path = "/assets/diff",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Changes since the requested time", body = AssetDiff),
+        (status = 400, description = "Requested time is in the future"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(DiffQuery)
+)]
+pub async fn get_asset_diff(
+    Extension(repos): Extension<Repositories>,
+    Extension(overdue): Extension<OverdueAircraft>,
+    Extension(holds): Extension<MaintenanceHolds>,
+    Query(query): Query<DiffQuery>,
+) -> Result<Json<AssetDiff>, StatusCode> {
+    rest_info!("entry [{}].", query.since);
+
+    let until = Utc::now();
+    if query.since > until {
+        rest_error!("'since' can not be in the future: {}.", query.since);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let (aircraft, vertiports, vertipads) = tokio::try_join!(
+        aircraft_changes(&repos, query.since),
+        vertiport_changes(&repos, query.since),
+        vertipad_changes(&repos, query.since),
+    )?;
+
+    let status = |aircraft: Aircraft| aircraft.apply_maintenance_status(&overdue, &holds);
+    let aircraft = AssetChanges {
+        created: aircraft.created.into_iter().map(status).collect(),
+        updated: aircraft.updated.into_iter().map(status).collect(),
+        deleted: aircraft.deleted,
+    };
+
+    Ok(Json(AssetDiff {
+        since: query.since,
+        until,
+        aircraft,
+        vertiports,
+        vertipads,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use svc_storage_client_grpc::prelude::vertiport;
+
+    fn basics(id: &str, created_at: DateTime<Utc>, updated_at: DateTime<Utc>) -> Basics {
+        Basics {
+            id: id.to_string(),
+            name: None,
+            group_id: None,
+            created_at,
+            updated_at,
+            deleted_at: None,
+            owner_id: None,
+            whitelist: vec![],
+            status: crate::rest::structs::AssetStatus::Available,
+        }
+    }
+
+    #[test]
+    fn test_split_changes() {
+        let since = Utc::now();
+        let before = since - Duration::hours(1);
+        let after = since + Duration::hours(1);
+
+        let changes = split_changes(
+            since,
+            vec![
+                basics("created", since, since),
+                basics("updated", before, after),
+                basics("unchanged", before, before),
+            ],
+            vec!["deleted".to_string(), "created".to_string()],
+            |basics| basics,
+        );
+
+        assert_eq!(changes.created.len(), 1);
+        assert_eq!(changes.created[0].id, "created");
+        assert_eq!(changes.updated.len(), 1);
+        assert_eq!(changes.updated[0].id, "updated");
+        assert_eq!(changes.deleted, vec!["deleted".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_get_asset_diff() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let since = Utc::now() - Duration::seconds(1);
+        let id = repos
+            .vertiports
+            .insert(vertiport::mock::get_data_obj())
+            .await
+            .unwrap()
+            .id;
+
+        let Json(diff) = get_asset_diff(
+            Extension(repos.clone()),
+            Extension(OverdueAircraft::default()),
+            Extension(MaintenanceHolds::default()),
+            Query(DiffQuery { since }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(diff.since, since);
+        assert_eq!(diff.vertiports.created.len(), 1);
+        assert_eq!(diff.vertiports.created[0].basics.id, id);
+        assert!(diff.vertiports.updated.is_empty());
+        assert!(diff.vertiports.deleted.is_empty());
+        assert!(diff.aircraft.created.is_empty());
+
+        let error = get_asset_diff(
+            Extension(repos.clone()),
+            Extension(OverdueAircraft::default()),
+            Extension(MaintenanceHolds::default()),
+            Query(DiffQuery {
+                since: Utc::now() + Duration::hours(1),
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        let error = get_asset_diff(
+            Extension(Repositories::unavailable()),
+            Extension(OverdueAircraft::default()),
+            Extension(MaintenanceHolds::default()),
+            Query(DiffQuery { since }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::SERVICE_UNAVAILABLE);
+
+        ut_info!("success");
+    }
+}
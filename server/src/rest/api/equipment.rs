@@ -0,0 +1,400 @@
+//! Handlers for the vertipad equipment API endpoints.
+
+pub use super::rest_types::{Equipment, EquipmentKind, EquipmentPayload, EquipmentStatus};
+
+use super::errors::storage_error_status;
+use super::validation::*;
+
+use crate::repo::Repositories;
+use crate::rest::equipment::{EquipmentError, VertipadEquipment, MAX_EQUIPMENT_PER_VERTIPAD};
+use crate::rest::json::Json;
+use axum::{extract::Path, Extension};
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+use lib_common::uuid::to_uuid;
+
+/// Validate and normalize an equipment payload.
+///
+/// The last inspection can not be after `now`.
+pub fn validate_equipment(
+    mut payload: EquipmentPayload,
+    now: DateTime<Utc>,
+) -> Result<EquipmentPayload, Vec<FieldError>> {
+    let mut errors = vec![];
+    normalize_string(&mut payload.name);
+    check_not_empty(&mut errors, "name", &payload.name);
+    if payload
+        .last_inspection
+        .map(|last_inspection| last_inspection > now)
+        .unwrap_or(false)
+    {
+        errors.push(field_error("last_inspection", "can not be in the future"));
+    }
+
+    into_result(payload, errors)
+}
+
+/// Parse a vertipad or equipment id, returning `BAD_REQUEST` if it is not a
+/// valid UUID.
+fn parse_id(kind: &str, id: &str) -> Result<String, StatusCode> {
+    to_uuid(id).map(|id| id.to_string()).ok_or_else(|| {
+        rest_error!("Invalid {} id: {}", kind, id);
+        StatusCode::BAD_REQUEST
+    })
+}
+
+/// Map an error of an existing equipment change to its status code.
+fn equipment_error_status(vertipad_id: &str, equipment_id: &str, e: EquipmentError) -> StatusCode {
+    match e {
+        EquipmentError::State(e) => {
+            rest_error!("could not persist equipment {}: {e}", equipment_id);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        _ => {
+            rest_info!(
+                "equipment {} of vertipad {} not found.",
+                equipment_id,
+                vertipad_id
+            );
+            StatusCode::NOT_FOUND
+        }
+    }
+}
+
+/// Check that a vertipad exists in storage.
+async fn check_vertipad(repos: &Repositories, vertipad_id: &str) -> Result<(), StatusCode> {
+    repos
+        .vertipads
+        .get_by_id(vertipad_id.to_string())
+        .await
+        .map(|_| ())
+        .map_err(|e| {
+            rest_error!("could not get vertipad: {e}");
+            storage_error_status(&e)
+        })
+}
+
+/// Get the equipment of a vertipad.
+#[utoipa::path(
+    get,
+    path = "/assets/vertipads/{id}/equipment",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Equipment of vertipad {id}, in the order it was added", body = [Equipment]),
+        (status = 400, description = "Invalid vertipad id"),
+        (status = 404, description = "Vertipad not found in database"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+    )
+)]
+pub async fn get_vertipad_equipment(
+    Extension(repos): Extension<Repositories>,
+    Extension(equipment): Extension<VertipadEquipment>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<Equipment>>, StatusCode> {
+    rest_info!("entry [{}].", id);
+    let id = parse_id("vertipad", &id)?;
+    check_vertipad(&repos, &id).await?;
+
+    Ok(Json(equipment.list(&id)))
+}
+
+/// Add equipment to a vertipad.
+///
+/// The equipment is not stored in svc-storage but persisted by this
+/// instance, see [`crate::rest::equipment`].
+#[utoipa::path(
+    post,
+    path = "/assets/vertipads/{id}/equipment",
+    tag = "svc-assets",
+    request_body = EquipmentPayload,
+    responses(
+        (status = 200, description = "Equipment added; the new equipment is returned", body = Equipment),
+        (status = 400, description = "Invalid vertipad id"),
+        (status = 404, description = "Vertipad not found in database"),
+        (status = 422, description = "Invalid equipment or too much equipment", body = [FieldError]),
+        (status = 500, description = "The equipment could not be persisted"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+    )
+)]
+pub async fn add_vertipad_equipment(
+    Extension(repos): Extension<Repositories>,
+    Extension(equipment): Extension<VertipadEquipment>,
+    Path(id): Path<String>,
+    Json(payload): Json<EquipmentPayload>,
+) -> Result<Json<Equipment>, ValidationError> {
+    rest_info!("entry [{}].", id);
+    let id = parse_id("vertipad", &id).map_err(|status| (status, Json(vec![])))?;
+    let now = Utc::now();
+    let payload = validate_equipment(payload, now).map_err(unprocessable)?;
+    check_vertipad(&repos, &id)
+        .await
+        .map_err(|status| (status, Json(vec![])))?;
+
+    let added = equipment.add(&id, payload, now).map_err(|e| match e {
+        EquipmentError::State(e) => {
+            rest_error!("could not persist equipment: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+        }
+        _ => {
+            rest_error!(
+                "reached the maximum number of equipment of vertipad {}.",
+                id
+            );
+            unprocessable(vec![field_error(
+                "equipment",
+                &format!("can not contain more than {MAX_EQUIPMENT_PER_VERTIPAD} records"),
+            )])
+        }
+    })?;
+
+    rest_info!("added equipment [{}] to vertipad [{}].", added.id, id);
+    Ok(Json(added))
+}
+
+/// Get equipment of a vertipad by its id.
+#[utoipa::path(
+    get,
+    path = "/assets/vertipads/{id}/equipment/{equipment_id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Equipment {equipment_id} found", body = Equipment),
+        (status = 400, description = "Invalid vertipad or equipment id"),
+        (status = 404, description = "Equipment not found"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+        ("equipment_id" = String, Path, description = "Equipment id"),
+    )
+)]
+pub async fn get_vertipad_equipment_by_id(
+    Extension(equipment): Extension<VertipadEquipment>,
+    Path((id, equipment_id)): Path<(String, String)>,
+) -> Result<Json<Equipment>, StatusCode> {
+    rest_info!("entry [{}] [{}].", id, equipment_id);
+    let id = parse_id("vertipad", &id)?;
+    let equipment_id = parse_id("equipment", &equipment_id)?;
+
+    equipment.get(&id, &equipment_id).map(Json).ok_or_else(|| {
+        rest_info!("equipment {} of vertipad {} not found.", equipment_id, id);
+        StatusCode::NOT_FOUND
+    })
+}
+
+/// Replace the kind, name, status and last inspection of equipment.
+#[utoipa::path(
+    put,
+    path = "/assets/vertipads/{id}/equipment/{equipment_id}",
+    tag = "svc-assets",
+    request_body = EquipmentPayload,
+    responses(
+        (status = 200, description = "Equipment updated; the updated equipment is returned", body = Equipment),
+        (status = 400, description = "Invalid vertipad or equipment id"),
+        (status = 404, description = "Equipment not found"),
+        (status = 422, description = "Invalid equipment", body = [FieldError]),
+        (status = 500, description = "The equipment could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+        ("equipment_id" = String, Path, description = "Equipment id"),
+    )
+)]
+pub async fn update_vertipad_equipment(
+    Extension(equipment): Extension<VertipadEquipment>,
+    Path((id, equipment_id)): Path<(String, String)>,
+    Json(payload): Json<EquipmentPayload>,
+) -> Result<Json<Equipment>, ValidationError> {
+    rest_info!("entry [{}] [{}].", id, equipment_id);
+    let id = parse_id("vertipad", &id).map_err(|status| (status, Json(vec![])))?;
+    let equipment_id =
+        parse_id("equipment", &equipment_id).map_err(|status| (status, Json(vec![])))?;
+    let now = Utc::now();
+    let payload = validate_equipment(payload, now).map_err(unprocessable)?;
+
+    let updated = equipment
+        .update(&id, &equipment_id, payload, now)
+        .map_err(|e| (equipment_error_status(&id, &equipment_id, e), Json(vec![])))?;
+
+    if updated.kind == EquipmentKind::Lighting && updated.status == EquipmentStatus::Failed {
+        rest_warn!(
+            "lighting [{}] of vertipad [{}] failed, the vertipad is unavailable at night.",
+            updated.id,
+            id
+        );
+    }
+
+    Ok(Json(updated))
+}
+
+/// Remove equipment of a vertipad.
+#[utoipa::path(
+    delete,
+    path = "/assets/vertipads/{id}/equipment/{equipment_id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Equipment removed"),
+        (status = 400, description = "Invalid vertipad or equipment id"),
+        (status = 404, description = "Equipment not found"),
+        (status = 500, description = "The equipment could not be removed"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+        ("equipment_id" = String, Path, description = "Equipment id"),
+    )
+)]
+pub async fn remove_vertipad_equipment(
+    Extension(equipment): Extension<VertipadEquipment>,
+    Path((id, equipment_id)): Path<(String, String)>,
+) -> Result<(), StatusCode> {
+    rest_info!("entry [{}] [{}].", id, equipment_id);
+    let id = parse_id("vertipad", &id)?;
+    let equipment_id = parse_id("equipment", &equipment_id)?;
+
+    equipment
+        .remove(&id, &equipment_id)
+        .map_err(|e| equipment_error_status(&id, &equipment_id, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{VertipadDataBuilder, VertiportDataBuilder};
+    use chrono::Duration;
+    use lib_common::uuid::Uuid;
+
+    fn payload(status: EquipmentStatus) -> EquipmentPayload {
+        EquipmentPayload {
+            kind: EquipmentKind::Lighting,
+            name: "Approach lights".to_string(),
+            status,
+            last_inspection: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_equipment() {
+        let now = Utc::now();
+        let mut valid = payload(EquipmentStatus::Operational);
+        valid.name = " Approach lights ".to_string();
+        valid.last_inspection = Some(now);
+        let validated = validate_equipment(valid, now).unwrap();
+        assert_eq!(validated.name, "Approach lights");
+
+        let mut invalid = payload(EquipmentStatus::Operational);
+        invalid.name = " ".to_string();
+        invalid.last_inspection = Some(now + Duration::days(1));
+        let errors = validate_equipment(invalid, now).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["name", "last_inspection"]);
+    }
+
+    #[tokio::test]
+    async fn test_vertipad_equipment() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let vertiport_id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let vertipad_id = repos
+            .vertipads
+            .insert(
+                VertipadDataBuilder::new()
+                    .vertiport_id(vertiport_id)
+                    .build(),
+            )
+            .await
+            .unwrap()
+            .id;
+        let equipment = VertipadEquipment::default();
+        let ext = || (Extension(repos.clone()), Extension(equipment.clone()));
+
+        let (r, e) = ext();
+        let Json(added) = add_vertipad_equipment(
+            r,
+            e,
+            Path(vertipad_id.clone()),
+            Json(payload(EquipmentStatus::Operational)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(added.vertipad_id, vertipad_id);
+
+        let (r, e) = ext();
+        let Json(list) = get_vertipad_equipment(r, e, Path(vertipad_id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(list, vec![added.clone()]);
+
+        let ids = || Path((vertipad_id.clone(), added.id.clone()));
+        let Json(found) = get_vertipad_equipment_by_id(Extension(equipment.clone()), ids())
+            .await
+            .unwrap();
+        assert_eq!(found, added);
+
+        let Json(updated) = update_vertipad_equipment(
+            Extension(equipment.clone()),
+            ids(),
+            Json(payload(EquipmentStatus::Failed)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.status, EquipmentStatus::Failed);
+        assert!(equipment.lighting_failed(&vertipad_id));
+
+        // invalid payloads and ids
+        let mut invalid = payload(EquipmentStatus::Operational);
+        invalid.name = String::new();
+        let (status, errors) =
+            update_vertipad_equipment(Extension(equipment.clone()), ids(), Json(invalid))
+                .await
+                .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "name");
+
+        let (r, e) = ext();
+        let (status, _) = add_vertipad_equipment(
+            r,
+            e,
+            Path("invalid".to_string()),
+            Json(payload(EquipmentStatus::Operational)),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (r, e) = ext();
+        let (status, _) = add_vertipad_equipment(
+            r,
+            e,
+            Path(Uuid::new_v4().to_string()),
+            Json(payload(EquipmentStatus::Operational)),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        remove_vertipad_equipment(Extension(equipment.clone()), ids())
+            .await
+            .unwrap();
+        let error = get_vertipad_equipment_by_id(Extension(equipment.clone()), ids())
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+        let error = remove_vertipad_equipment(Extension(equipment.clone()), ids())
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,141 @@
+//! Mapping of dependency errors to REST responses.
+
+pub use super::rest_types::{IncompleteRegistration, LeftoverAsset, LeftoverAssetType};
+
+use super::validation::{FieldError, ValidationError};
+use crate::rest::json::Json;
+use crate::saga::Created;
+use axum::response::{IntoResponse, Response};
+use hyper::StatusCode;
+use tonic::{Code, Status};
+
+/// Map an error returned by svc-storage to the matching [`StatusCode`].
+///
+/// * `NotFound` results in `NOT_FOUND`
+/// * `Unavailable` results in `SERVICE_UNAVAILABLE`
+/// * `DeadlineExceeded` results in `GATEWAY_TIMEOUT`
+/// * any other error results in `INTERNAL_SERVER_ERROR`
+pub fn storage_error_status(status: &Status) -> StatusCode {
+    match status.code() {
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Error of an endpoint registering several assets in a
+/// [`Saga`](crate::saga::Saga)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistrationError {
+    /// The request failed with the status code before any asset was created
+    Status(StatusCode),
+    /// The request is invalid, the field errors are returned
+    Invalid(StatusCode, Vec<FieldError>),
+    /// The request failed with the status code after assets were created,
+    /// the assets which could not be removed again are returned
+    Incomplete(StatusCode, Vec<Created>),
+}
+
+impl RegistrationError {
+    /// Get the status code of the error
+    pub fn status(&self) -> StatusCode {
+        match self {
+            RegistrationError::Status(status)
+            | RegistrationError::Invalid(status, _)
+            | RegistrationError::Incomplete(status, _) => *status,
+        }
+    }
+}
+
+impl From<StatusCode> for RegistrationError {
+    fn from(status: StatusCode) -> Self {
+        RegistrationError::Status(status)
+    }
+}
+
+impl From<ValidationError> for RegistrationError {
+    fn from((status, Json(errors)): ValidationError) -> Self {
+        RegistrationError::Invalid(status, errors)
+    }
+}
+
+impl From<Created> for LeftoverAsset {
+    fn from(created: Created) -> Self {
+        let (asset_type, id) = match created {
+            Created::Aircraft(id) => (LeftoverAssetType::Aircraft, id),
+            Created::Vertiport(id) => (LeftoverAssetType::Vertiport, id),
+            Created::Vertipad(id) => (LeftoverAssetType::Vertipad, id),
+        };
+        LeftoverAsset { asset_type, id }
+    }
+}
+
+impl IntoResponse for RegistrationError {
+    fn into_response(self) -> Response {
+        match self {
+            RegistrationError::Status(status) => status.into_response(),
+            RegistrationError::Invalid(status, errors) => (status, Json(errors)).into_response(),
+            RegistrationError::Incomplete(status, leftover) => (
+                status,
+                Json(IncompleteRegistration {
+                    leftover: leftover.into_iter().map(LeftoverAsset::from).collect(),
+                }),
+            )
+                .into_response(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_error_status() {
+        assert_eq!(
+            storage_error_status(&Status::not_found("not found")),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            storage_error_status(&Status::unavailable("unavailable")),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            storage_error_status(&Status::deadline_exceeded("timeout")),
+            StatusCode::GATEWAY_TIMEOUT
+        );
+        assert_eq!(
+            storage_error_status(&Status::internal("internal")),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(
+            storage_error_status(&Status::invalid_argument("invalid")),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[tokio::test]
+    async fn test_registration_error_response() {
+        let error = RegistrationError::from(StatusCode::FORBIDDEN);
+        assert_eq!(error.status(), StatusCode::FORBIDDEN);
+        assert_eq!(error.into_response().status(), StatusCode::FORBIDDEN);
+
+        let error = RegistrationError::Incomplete(
+            StatusCode::SERVICE_UNAVAILABLE,
+            vec![Created::Vertipad("pad".to_string())],
+        );
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let registration: IncompleteRegistration = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            registration.leftover,
+            vec![LeftoverAsset {
+                asset_type: LeftoverAssetType::Vertipad,
+                id: "pad".to_string(),
+            }]
+        );
+    }
+}
@@ -2,10 +2,40 @@
 
 pub use super::rest_types::*;
 
-use axum::{extract::Path, Extension, Json};
+use super::asset::{resolve_asset, ResolvedAsset};
+use super::errors::storage_error_status;
+use super::json_patch::{apply_json_patch, JsonPatchError, JsonPatchOperation};
+use super::validation::{
+    check_optional_uuid, check_uuid, field_error, into_result, normalize_optional_string,
+    normalize_string, unprocessable, FieldError, ValidationError,
+};
+use axum::{
+    extract::{Path, Query},
+    http::HeaderMap,
+    Extension,
+};
+use chrono::Utc;
+use futures::future::join_all;
 use hyper::StatusCode;
+use lib_common::uuid::to_uuid;
+use serde::{Deserialize, Serialize};
+use svc_storage_client_grpc::prelude::{group, vehicle, AdvancedSearchFilter, FieldMask};
+use utoipa::ToSchema;
 
+use crate::aircraft_status::AircraftStatuses;
+use crate::delegation::{DelegationError, GroupDelegations, MAX_DELEGATION_AUDIT_LEN};
+use crate::fan_out::FanOut;
+use crate::group_hierarchy::{self, HierarchyError};
+use crate::group_schedule::{
+    is_valid_range, GroupScheduleError, GroupSchedules, MAX_GROUP_AVAILABILITY_RANGE_DAYS,
+};
 use crate::grpc::client::GrpcClients;
+use crate::hold::MaintenanceHolds;
+use crate::maintenance::OverdueAircraft;
+use crate::ops_status::VertiportOpsStatuses;
+use crate::repo::Repositories;
+use crate::rest::json::Json;
+use crate::rest::quota::{operator_id_from_headers, OperatorQuotas, QuotaKind};
 use crate::rest::structs::AssetGroup;
 
 /// Register an [`AssetGroup`](crate::rest::structs::AssetGroup) in the database.
@@ -67,8 +97,8 @@ pub async fn register_asset_group(
 )]
 pub async fn update_asset_group(
     Extension(mut _grpc_clients): Extension<GrpcClients>,
-    Json(payload): Json<AssetGroup>,
     Path(_id): Path<String>,
+    Json(payload): Json<AssetGroup>,
 ) -> Result<String, (StatusCode, String)> {
     rest_info!("with payload: {:?}", &payload);
     Err((StatusCode::NOT_IMPLEMENTED, "Not implemented".to_string()))
@@ -120,6 +150,11 @@ pub async fn remove_asset_group(
 }
 
 /// Get an [`AssetGroup`](crate::rest::structs::AssetGroup) by its id.
+///
+/// The group is read from svc-storage and combined with the ids of its
+/// assets, its active delegation and its parent and child groups.
+/// svc-storage does not link groups to operators yet, so the `owner` is not
+/// known. With `recursive=true` the assets of all child groups are included.
 #[utoipa::path(
     get,
     path = "/assets/groups/{id}",
@@ -128,34 +163,972 @@ pub async fn remove_asset_group(
         (status = 200, description = "Asset group {id} found from database", body = AssetGroup),
         (status = 404, description = "Asset group not found in database"),
         (status = 400, description = "Invalid asset group id"),
-        (status = 503, description = "Could not connect to other microservice dependencies")
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
     ),
     params(
         ("id" = String, Path, description = "Asset group id"),
+        AssetGroupQuery,
     )
 )]
 pub async fn get_asset_group_by_id(
-    Extension(mut _grpc_clients): Extension<GrpcClients>,
-    Path(asset_group_id): Path<String>,
-) -> Result<Json<AssetGroup>, (StatusCode, String)> {
-    rest_info!("{}", asset_group_id);
-
-    // let _asset_group_id = to_uuid(&asset_group_id).ok_or_else(|| {
-    //     rest_error!("Invalid asset group id.");
-    //     (
-    //         StatusCode::BAD_REQUEST,
-    //         "Invalid asset group id".to_string(),
-    //     )
-    // })?;
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+    Extension(delegations): Extension<GroupDelegations>,
+    Path(id): Path<String>,
+    Query(query): Query<AssetGroupQuery>,
+) -> Result<Json<AssetGroup>, StatusCode> {
+    rest_info!("entry [{}].", &id);
 
-    Err((StatusCode::NOT_IMPLEMENTED, "Not implemented".to_string()))
+    let id = group_id(&id)?;
+    let object = fan_out
+        .call(repos.groups.get_by_id(id.clone()))
+        .await
+        .map_err(|e| {
+            rest_error!("could not get asset group: {e}");
+            storage_error_status(&e)
+        })?;
+
+    let assets = match query.recursive {
+        true => hierarchy_asset_ids(&repos, &id).await?,
+        false => group_asset_ids(&repos, &id).await?,
+    };
+    let child_group_ids = fan_out
+        .call(group_hierarchy::children(repos.groups.as_ref(), &id))
+        .await
+        .map_err(|e| {
+            rest_error!("could not get child asset groups: {e}");
+            storage_error_status(&e)
+        })?;
+    let delegatee = delegations
+        .get(&id, Utc::now())
+        .map(|delegation| delegation.delegatee);
+
+    Ok(Json(AssetGroup {
+        parent_group_id: group_hierarchy::parent_of(&object),
+        id,
+        name: object
+            .data
+            .map(|data| data.name)
+            .filter(|name| !name.is_empty()),
+        owner: None,
+        created_at: None,
+        updated_at: None,
+        delegatee,
+        assets,
+        child_group_ids,
+    }))
+}
+
+/// Set or remove the parent of an
+/// [`AssetGroup`](crate::rest::structs::AssetGroup).
+///
+/// The parent group must exist, can not be the group itself or one of its
+/// child groups, and the hierarchy can not have more than
+/// [`MAX_GROUP_DEPTH`](crate::group_hierarchy::MAX_GROUP_DEPTH) levels.
+#[utoipa::path(
+    put,
+    path = "/assets/groups/{id}/parent",
+    tag = "svc-assets",
+    request_body = GroupParentPayload,
+    responses(
+        (status = 200, description = "Asset group parent updated"),
+        (status = 400, description = "Invalid asset group id"),
+        (status = 404, description = "Asset group not found in database"),
+        (status = 422, description = "Invalid parent group", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    ),
+    params(
+        ("id" = String, Path, description = "Asset group id"),
+    )
+)]
+pub async fn put_asset_group_parent(
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+    Path(id): Path<String>,
+    Json(payload): Json<GroupParentPayload>,
+) -> Result<(), ValidationError> {
+    rest_info!("entry [{}].", &id);
+
+    let id = group_id(&id).map_err(|status| (status, Json(vec![])))?;
+    let mut parent_group_id = payload.parent_group_id;
+    normalize_optional_string(&mut parent_group_id);
+    let mut errors = vec![];
+    check_optional_uuid(&mut errors, "parent_group_id", &parent_group_id);
+    let parent_group_id = into_result(parent_group_id, errors)
+        .map_err(unprocessable)?
+        .and_then(|parent_id| to_uuid(&parent_id))
+        .map(|parent_id| parent_id.to_string());
+
+    let object = fan_out
+        .call(repos.groups.get_by_id(id.clone()))
+        .await
+        .map_err(|e| {
+            rest_error!("could not get asset group: {e}");
+            (storage_error_status(&e), Json(vec![]))
+        })?;
+
+    group_hierarchy::set_parent(repos.groups.as_ref(), object, parent_group_id)
+        .await
+        .map_err(|e| match e {
+            HierarchyError::Storage(e) => {
+                rest_error!("could not change the parent of asset group: {e}");
+                (storage_error_status(&e), Json(vec![]))
+            }
+            e => {
+                rest_error!("invalid parent asset group: {e}");
+                unprocessable(vec![field_error("parent_group_id", &e.to_string())])
+            }
+        })?;
+
+    rest_info!("successfully updated asset group parent.");
+    Ok(())
+}
+
+/// Fields of an [`AssetGroup`](crate::rest::structs::AssetGroup) which can be
+/// changed with a JSON Patch
+const GROUP_PATCH_FIELDS: &[&str] = &["name", "assets", "delegatee"];
+
+/// An asset group after applying a JSON Patch
+#[derive(Debug, Deserialize)]
+struct GroupPatchDocument {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    delegatee: Option<String>,
+    assets: Vec<String>,
+}
+
+/// Validate a patched asset group, normalizing the asset and delegatee ids.
+fn validate_group_patch(
+    mut document: GroupPatchDocument,
+) -> Result<GroupPatchDocument, Vec<FieldError>> {
+    let mut errors = vec![];
+    normalize_optional_string(&mut document.name);
+    normalize_optional_string(&mut document.delegatee);
+    check_optional_uuid(&mut errors, "delegatee", &document.delegatee);
+    document.delegatee = document
+        .delegatee
+        .map(|id| to_uuid(&id).map(|id| id.to_string()).unwrap_or(id));
+
+    let mut assets: Vec<String> = vec![];
+    for asset_id in document.assets {
+        match to_uuid(&asset_id).map(|id| id.to_string()) {
+            Some(id) if assets.contains(&id) => {
+                errors.push(field_error("assets", &format!("contains {id} twice")))
+            }
+            Some(id) => assets.push(id),
+            None => errors.push(field_error(
+                "assets",
+                &format!("{asset_id} is not a valid UUID"),
+            )),
+        }
+    }
+    document.assets = assets;
+
+    into_result(document, errors)
+}
+
+/// Get an aircraft changing its membership in a patched group.
+async fn member_aircraft(
+    repos: &Repositories,
+    id: &str,
+) -> Result<vehicle::Object, ValidationError> {
+    repos.aircraft.get_by_id(id.to_string()).await.map_err(|e| {
+        rest_error!("could not get aircraft {}: {e}", id);
+        match e.code() {
+            tonic::Code::NotFound => unprocessable(vec![field_error(
+                "assets",
+                &format!("aircraft {id} not found"),
+            )]),
+            _ => (storage_error_status(&e), Json(vec![])),
+        }
+    })
+}
+
+/// Set the asset group of an aircraft, `None` removing it from its group.
+async fn set_aircraft_group(
+    repos: &Repositories,
+    object: vehicle::Object,
+    group_id: Option<String>,
+) -> Result<(), ValidationError> {
+    let mut data = object.data.unwrap_or_default();
+    data.asset_group_id = group_id;
+    let object = vehicle::UpdateObject {
+        id: object.id,
+        data: Some(data),
+        mask: Some(FieldMask {
+            paths: vec!["asset_group_id".to_string()],
+        }),
+    };
+
+    repos.aircraft.update(object).await.map_err(|e| {
+        rest_error!("could not update the asset group of an aircraft: {e}");
+        (storage_error_status(&e), Json(vec![]))
+    })
+}
+
+/// Change the name, assets or delegatee of an
+/// [`AssetGroup`](crate::rest::structs::AssetGroup) with a JSON Patch (RFC
+/// 6902).
+///
+/// The patch is applied to the group as returned by
+/// [`get_asset_group_by_id`], limited to the `name`, `assets` and `delegatee`
+/// fields; single aircraft can be added with an `add` to `/assets/-` and
+/// removed with a `remove` of `/assets/{index}`. A `test` operation which
+/// does not match, for example because the group changed since it was read,
+/// results in a `409 CONFLICT` and nothing is changed, as does adding an
+/// aircraft which belongs to another group. The parent group is changed with
+/// [`put_asset_group_parent`].
+///
+/// All changes are validated before any is made, but svc-storage can not
+/// change the group and its aircraft atomically: if a storage call fails,
+/// the changes made before it are kept. A delegation set by a patch does not
+/// expire; the operator making the change is taken from the `x-operator-id`
+/// header and recorded in the delegation audit trail.
+#[utoipa::path(
+    patch,
+    path = "/assets/groups/{id}",
+    tag = "svc-assets",
+    request_body(content = [JsonPatchOperation], content_type = "application/json-patch+json"),
+    responses(
+        (status = 200, description = "Asset group patched; the patched group is returned", body = AssetGroup),
+        (status = 400, description = "Invalid asset group id or operator header"),
+        (status = 404, description = "Asset group not found in database"),
+        (status = 409, description = "A test operation failed or an aircraft belongs to another group", body = [FieldError]),
+        (status = 422, description = "Patch can not be applied; a list of field errors is returned", body = [FieldError]),
+        (status = 500, description = "The delegation could not be persisted"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Asset group id"),
+        ("x-operator-id" = Option<String>, Header, description = "UUID of the operator changing the delegation, recorded in the audit trail"),
+    )
+)]
+pub async fn patch_asset_group(
+    Extension(repos): Extension<Repositories>,
+    Extension(delegations): Extension<GroupDelegations>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(operations): Json<Vec<JsonPatchOperation>>,
+) -> Result<Json<AssetGroup>, ValidationError> {
+    rest_info!("entry [{}].", &id);
+    rest_debug!("Operations: {:?}", &operations);
+
+    let id = group_id(&id).map_err(|status| (status, Json(vec![])))?;
+    let actor = operator_id_from_headers(&headers).map_err(|status| (status, Json(vec![])))?;
+
+    let object = repos.groups.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not get asset group: {e}");
+        (storage_error_status(&e), Json(vec![]))
+    })?;
+    let parent_group_id = group_hierarchy::parent_of(&object);
+    let data = object.data.unwrap_or_default();
+    let name = Some(data.name.clone()).filter(|name| !name.is_empty());
+    let assets = group_asset_ids(&repos, &id)
+        .await
+        .map_err(|status| (status, Json(vec![])))?;
+    let delegatee = delegations
+        .get(&id, Utc::now())
+        .map(|delegation| delegation.delegatee);
+
+    let current = serde_json::json!({
+        "name": name,
+        "delegatee": delegatee,
+        "assets": assets,
+    });
+    let patched = apply_json_patch(&current, &operations, GROUP_PATCH_FIELDS).map_err(|e| {
+        rest_info!("could not apply the patch to asset group {}: {:?}", id, e);
+        match e {
+            JsonPatchError::Invalid(errors) => unprocessable(errors),
+            JsonPatchError::TestFailed(error) => (StatusCode::CONFLICT, Json(vec![error])),
+        }
+    })?;
+    let patched: GroupPatchDocument = serde_json::from_value(patched)
+        .map_err(|e| unprocessable(vec![field_error("body", &e.to_string())]))?;
+    let patched = validate_group_patch(patched).map_err(unprocessable)?;
+
+    // check the aircraft joining and leaving the group before changing any
+    let mut joining = vec![];
+    for asset_id in patched.assets.iter().filter(|id| !assets.contains(id)) {
+        let aircraft = member_aircraft(&repos, asset_id).await?;
+        if let Some(other) = aircraft
+            .data
+            .as_ref()
+            .and_then(|data| data.asset_group_id.clone())
+            .filter(|other| *other != id)
+        {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(vec![field_error(
+                    "assets",
+                    &format!("aircraft {asset_id} belongs to asset group {other}"),
+                )]),
+            ));
+        }
+        joining.push(aircraft);
+    }
+    let mut leaving = vec![];
+    for asset_id in assets.iter().filter(|id| !patched.assets.contains(id)) {
+        leaving.push(member_aircraft(&repos, asset_id).await?);
+    }
+
+    if patched.name != name {
+        let object = group::UpdateObject {
+            id: id.clone(),
+            data: Some(group::Data {
+                name: patched.name.clone().unwrap_or_default(),
+                ..data
+            }),
+            mask: Some(FieldMask {
+                paths: vec!["name".to_string()],
+            }),
+        };
+        repos.groups.update(object).await.map_err(|e| {
+            rest_error!("could not update asset group: {e}");
+            (storage_error_status(&e), Json(vec![]))
+        })?;
+    }
+
+    for aircraft in joining {
+        set_aircraft_group(&repos, aircraft, Some(id.clone())).await?;
+    }
+    for aircraft in leaving {
+        set_aircraft_group(&repos, aircraft, None).await?;
+    }
+
+    if patched.delegatee != delegatee {
+        let now = Utc::now();
+        if delegatee.is_some() {
+            // the delegation could have expired in the meantime
+            let _ = delegations.revoke(&id, actor.clone(), now);
+        }
+        if let Some(delegatee) = &patched.delegatee {
+            let payload = DelegationPayload {
+                delegatee: delegatee.clone(),
+                expires_at: None,
+            };
+            delegations
+                .delegate(&id, payload, actor, now)
+                .map_err(|e| {
+                    rest_error!("could not delegate asset group: {e}");
+                    match e {
+                        DelegationError::State(_) => {
+                            (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+                        }
+                        _ => (
+                            StatusCode::CONFLICT,
+                            Json(vec![field_error("delegatee", &e.to_string())]),
+                        ),
+                    }
+                })?;
+        }
+    }
+
+    rest_info!("asset group {} patched.", id);
+    let child_group_ids = group_hierarchy::children(repos.groups.as_ref(), &id)
+        .await
+        .map_err(|e| {
+            rest_error!("could not get child asset groups: {e}");
+            (storage_error_status(&e), Json(vec![]))
+        })?;
+    Ok(Json(AssetGroup {
+        id,
+        name: patched.name,
+        owner: None,
+        created_at: None,
+        updated_at: None,
+        delegatee: patched.delegatee,
+        assets: patched.assets,
+        parent_group_id,
+        child_group_ids,
+    }))
+}
+
+/// Set the schedule constraining all assets of an
+/// [`AssetGroup`](crate::rest::structs::AssetGroup).
+///
+/// The schedule is persisted and enforced by this instance, see
+/// [`crate::group_schedule`].
+#[utoipa::path(
+    put,
+    path = "/assets/groups/{id}/schedule",
+    tag = "svc-assets",
+    request_body = GroupSchedulePayload,
+    responses(
+        (status = 200, description = "Asset group schedule updated"),
+        (status = 400, description = "Invalid asset group id"),
+        (status = 422, description = "Invalid schedule", body = [FieldError]),
+        (status = 500, description = "The schedule could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset group id"),
+    )
+)]
+pub async fn put_asset_group_schedule(
+    Extension(schedules): Extension<GroupSchedules>,
+    Path(id): Path<String>,
+    Json(payload): Json<GroupSchedulePayload>,
+) -> Result<(), ValidationError> {
+    rest_info!("entry [{}].", &id);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid asset group id: {}", &id);
+            (StatusCode::BAD_REQUEST, Json(vec![]))
+        })?
+        .to_string();
+
+    schedules.set(&id, payload.schedule).map_err(|e| match e {
+        GroupScheduleError::Schedule(e) => {
+            rest_error!("invalid asset group schedule: {e}");
+            unprocessable(vec![field_error("schedule", &e.to_string())])
+        }
+        GroupScheduleError::State(e) => {
+            rest_error!("could not persist asset group schedule: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+        }
+    })?;
+
+    rest_info!("successfully updated asset group schedule.");
+    Ok(())
+}
+
+/// Get the time windows the assets of an
+/// [`AssetGroup`](crate::rest::structs::AssetGroup) can be used.
+///
+/// A group without a schedule does not constrain its assets.
+#[utoipa::path(
+    get,
+    path = "/assets/groups/{id}/availability",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Availability of asset group {id}", body = GroupAvailability),
+        (status = 400, description = "Invalid asset group id or time range"),
+        (status = 500, description = "Asset group schedule could not be expanded"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset group id"),
+        AvailabilityQuery,
+    )
+)]
+pub async fn get_asset_group_availability(
+    Extension(schedules): Extension<GroupSchedules>,
+    Path(id): Path<String>,
+    Query(query): Query<AvailabilityQuery>,
+) -> Result<Json<GroupAvailability>, StatusCode> {
+    rest_info!("entry [{}].", &id);
+    rest_debug!("Query: {:?}", query);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid asset group id: {}", &id);
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    if !is_valid_range(query.from, query.to) {
+        rest_error!(
+            "invalid time range, 'to' must be after 'from' and the range can not exceed {} days.",
+            MAX_GROUP_AVAILABILITY_RANGE_DAYS
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let availability = schedules
+        .availability(&id, query.from, query.to)
+        .map_err(|e| {
+            rest_error!("could not expand asset group schedule: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(GroupAvailability {
+        group_id: id,
+        from: query.from,
+        to: query.to,
+        constrained: availability.constrained,
+        windows: availability.windows,
+    }))
+}
+
+/// Assets of an [`AssetGroup`](crate::rest::structs::AssetGroup).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GroupAssets {
+    /// The UUID of the asset group.
+    pub group_id: String,
+    /// The UUIDs of the assets in the group.
+    pub asset_ids: Vec<String>,
+    /// The assets in the group, in the order of `asset_ids`, if resolved.
+    /// Assets which could not be resolved are listed in `failed`.
+    pub assets: Option<Vec<ResolvedAsset>>,
+    /// The assets which could not be resolved.
+    pub failed: Vec<GroupAssetFailure>,
+}
+
+/// Get the ids of the assets in an asset group.
+///
+/// Aircraft are the only assets svc-storage links to a group.
+pub async fn group_asset_ids(
+    repos: &Repositories,
+    group_id: &str,
+) -> Result<Vec<String>, StatusCode> {
+    let filter =
+        AdvancedSearchFilter::search_equals("asset_group_id".to_string(), group_id.to_string())
+            .and_is_null("deleted_at".to_string());
+
+    let ids = repos
+        .aircraft
+        .search(filter)
+        .await
+        .map_err(|e| {
+            rest_error!("could not search aircraft: {e}.");
+            storage_error_status(&e)
+        })?
+        .into_iter()
+        .filter(|object| {
+            object
+                .data
+                .as_ref()
+                .map(|data| data.asset_group_id.as_deref() == Some(group_id))
+                .unwrap_or(false)
+        })
+        .map(|object| object.id)
+        .collect();
+
+    Ok(ids)
+}
+
+/// Get the operators owning an asset group according to the persisted
+/// owners of its aircraft.
+///
+/// svc-storage does not link groups to operators yet, so the owners of a
+/// group are the operators which registered its aircraft through this
+/// service, as recorded in the `asset_owners` table (see
+/// [`crate::rest::quota`]). The owners are not known if the list is empty.
+pub async fn group_aircraft_owners(
+    repos: &Repositories,
+    quotas: &OperatorQuotas,
+    group_id: &str,
+) -> Result<Vec<String>, StatusCode> {
+    let mut owners: Vec<String> = group_asset_ids(repos, group_id)
+        .await?
+        .iter()
+        .filter_map(|asset_id| quotas.owner(QuotaKind::Aircraft, asset_id))
+        .collect();
+    owners.sort();
+    owners.dedup();
+
+    Ok(owners)
+}
+
+/// Get the operators known to own an asset group.
+///
+/// The owners are the ones of [`group_aircraft_owners`] and the operator
+/// which delegated the group, through the `x-operator-id` header. The
+/// owners are not known if the list is empty.
+pub async fn group_owners(
+    repos: &Repositories,
+    delegations: &GroupDelegations,
+    quotas: &OperatorQuotas,
+    group_id: &str,
+) -> Result<Vec<String>, StatusCode> {
+    let mut owners = group_aircraft_owners(repos, quotas, group_id).await?;
+    owners.extend(
+        delegations
+            .get(group_id, Utc::now())
+            .and_then(|delegation| delegation.delegated_by),
+    );
+    owners.sort();
+    owners.dedup();
+
+    Ok(owners)
+}
+
+/// Check if an operator may assign an aircraft to an asset group.
+///
+/// An operator is required and the group must exist. An operator may assign
+/// aircraft to a group it owns according to the persisted owners of the
+/// group's aircraft (see [`group_aircraft_owners`]), or which is delegated
+/// to it. The operator which delegated a group is kept in memory only, so it
+/// does not count as an owner here. A group without a known owner is
+/// rejected.
+///
+/// Returns `BAD_REQUEST` without an operator, and a [`FieldError`] for the
+/// `asset_group_id` field if the assignment is not allowed.
+pub async fn check_group_assignment(
+    repos: &Repositories,
+    delegations: &GroupDelegations,
+    quotas: &OperatorQuotas,
+    group_id: &str,
+    operator_id: Option<&str>,
+) -> Result<Option<FieldError>, StatusCode> {
+    let Some(operator_id) = operator_id else {
+        rest_warn!(
+            "an operator is required to assign aircraft to asset group [{}].",
+            group_id
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    };
+
+    match repos.groups.get_by_id(group_id.to_string()).await {
+        Ok(_) => (),
+        Err(e) if e.code() == tonic::Code::NotFound => {
+            return Ok(Some(field_error(
+                "asset_group_id",
+                "asset group does not exist",
+            )));
+        }
+        Err(e) => {
+            rest_error!("could not get asset group: {e}");
+            return Err(storage_error_status(&e));
+        }
+    }
+
+    let delegated = delegations
+        .get(group_id, Utc::now())
+        .map_or(false, |delegation| delegation.delegatee == operator_id);
+    if delegated {
+        return Ok(None);
+    }
+
+    let owners = group_aircraft_owners(repos, quotas, group_id).await?;
+    if owners.iter().any(|owner| owner == operator_id) {
+        return Ok(None);
+    }
+
+    rest_warn!(
+        "operator [{}] may not assign aircraft to asset group [{}], owners: {:?}.",
+        operator_id,
+        group_id,
+        owners
+    );
+    let message = match owners.is_empty() {
+        true => "the owner of the asset group is not known",
+        false => "asset group belongs to another operator",
+    };
+    Ok(Some(field_error("asset_group_id", message)))
+}
+
+/// Get the assets of an [`AssetGroup`](crate::rest::structs::AssetGroup).
+///
+/// With `recursive=true` the assets of all child groups are included. With
+/// `resolve=true` the full assets are returned, tagged with their
+/// `asset_type`, instead of only their ids. The assets are fetched
+/// concurrently; assets which could not be fetched are reported in `failed`
+/// instead of failing the whole request.
+#[utoipa::path(
+    get,
+    path = "/assets/groups/{id}/assets",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Assets of asset group {id}", body = GroupAssets),
+        (status = 400, description = "Invalid asset group id"),
+        (status = 404, description = "Asset group not found in database"),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    ),
+    params(
+        ("id" = String, Path, description = "Asset group id"),
+        GroupAssetsQuery,
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_asset_group_assets(
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+    Extension(overdue): Extension<OverdueAircraft>,
+    Extension(holds): Extension<MaintenanceHolds>,
+    Extension(statuses): Extension<AircraftStatuses>,
+    Extension(ops_statuses): Extension<VertiportOpsStatuses>,
+    Path(id): Path<String>,
+    Query(query): Query<GroupAssetsQuery>,
+) -> Result<Json<GroupAssets>, StatusCode> {
+    rest_info!("entry [{}].", &id);
+
+    let id = group_id(&id)?;
+    fan_out
+        .call(repos.groups.get_by_id(id.clone()))
+        .await
+        .map_err(|e| {
+            rest_error!("could not get asset group: {e}");
+            storage_error_status(&e)
+        })?;
+
+    let asset_ids = match query.recursive {
+        true => hierarchy_asset_ids(&repos, &id).await?,
+        false => group_asset_ids(&repos, &id).await?,
+    };
+    if !query.resolve {
+        return Ok(Json(GroupAssets {
+            group_id: id,
+            asset_ids,
+            assets: None,
+            failed: vec![],
+        }));
+    }
+
+    let results = join_all(asset_ids.iter().map(|asset_id| {
+        resolve_asset(
+            &repos,
+            &fan_out,
+            &overdue,
+            &holds,
+            &statuses,
+            &ops_statuses,
+            asset_id,
+        )
+    }))
+    .await;
+
+    let mut assets = vec![];
+    let mut failed = vec![];
+    for (asset_id, result) in asset_ids.iter().zip(results) {
+        match result {
+            Ok(Some(asset)) => assets.push(asset),
+            Ok(None) => failed.push(GroupAssetFailure {
+                id: asset_id.clone(),
+                status: StatusCode::NOT_FOUND.as_u16(),
+                message: "asset not found".to_string(),
+            }),
+            Err(e) => {
+                rest_warn!("could not resolve asset [{}]: {e}", asset_id);
+                failed.push(GroupAssetFailure {
+                    id: asset_id.clone(),
+                    status: storage_error_status(&e).as_u16(),
+                    message: e.message().to_string(),
+                })
+            }
+        }
+    }
+
+    Ok(Json(GroupAssets {
+        group_id: id,
+        asset_ids,
+        assets: Some(assets),
+        failed,
+    }))
+}
+
+/// Parse an asset group id, returning `BAD_REQUEST` if it is not a UUID.
+fn group_id(id: &str) -> Result<String, StatusCode> {
+    to_uuid(id).map(|id| id.to_string()).ok_or_else(|| {
+        rest_error!("Invalid asset group id: {}", id);
+        StatusCode::BAD_REQUEST
+    })
+}
+
+/// Get the ids of the assets in an asset group and in all of its child
+/// groups, recursively.
+///
+/// The assets of the group come first, followed by the assets of the child
+/// groups level by level.
+async fn hierarchy_asset_ids(
+    repos: &Repositories,
+    group_id: &str,
+) -> Result<Vec<String>, StatusCode> {
+    let mut ids = group_asset_ids(repos, group_id).await?;
+    let descendants = group_hierarchy::descendants(repos.groups.as_ref(), group_id)
+        .await
+        .map_err(|e| {
+            rest_error!("could not get child asset groups: {e}");
+            storage_error_status(&e)
+        })?;
+    let results = join_all(
+        descendants
+            .iter()
+            .map(|child_id| group_asset_ids(repos, child_id)),
+    )
+    .await;
+    for child_ids in results {
+        for id in child_ids? {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Delegate an [`AssetGroup`](crate::rest::structs::AssetGroup) to another
+/// operator.
+///
+/// The operator making the change is taken from the `x-operator-id` header
+/// and recorded in the audit trail. A delegation with an `expires_at` is
+/// reverted automatically once it expired. Delegating a group to its current
+/// delegatee renews the delegation. Delegations are persisted by this
+/// instance, see [`crate::delegation`].
+#[utoipa::path(
+    put,
+    path = "/assets/groups/{id}/delegation",
+    tag = "svc-assets",
+    request_body = DelegationPayload,
+    responses(
+        (status = 200, description = "Asset group delegated; the delegation is returned", body = Delegation),
+        (status = 400, description = "Invalid asset group id or operator header"),
+        (status = 409, description = "Asset group is delegated to another operator", body = [FieldError]),
+        (status = 422, description = "Invalid delegatee or expiry", body = [FieldError]),
+        (status = 500, description = "The delegation could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset group id"),
+        ("x-operator-id" = Option<String>, Header, description = "UUID of the operator delegating the asset group, recorded in the audit trail"),
+    )
+)]
+pub async fn put_asset_group_delegation(
+    Extension(delegations): Extension<GroupDelegations>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(mut payload): Json<DelegationPayload>,
+) -> Result<Json<Delegation>, ValidationError> {
+    rest_info!("entry [{}].", &id);
+    rest_debug!("Payload: {:?}", &payload);
+
+    let id = group_id(&id).map_err(|status| (status, Json(vec![])))?;
+    let actor = operator_id_from_headers(&headers).map_err(|status| (status, Json(vec![])))?;
+
+    normalize_string(&mut payload.delegatee);
+    let mut errors = vec![];
+    check_uuid(&mut errors, "delegatee", &payload.delegatee);
+    let mut payload = into_result(payload, errors).map_err(unprocessable)?;
+    if let Some(delegatee) = to_uuid(&payload.delegatee) {
+        payload.delegatee = delegatee.to_string();
+    }
+
+    let delegation = delegations
+        .delegate(&id, payload, actor, Utc::now())
+        .map_err(|e| {
+            rest_error!("could not delegate asset group: {e}");
+            match e {
+                DelegationError::AlreadyDelegated(_) => (
+                    StatusCode::CONFLICT,
+                    Json(vec![field_error("delegatee", &e.to_string())]),
+                ),
+                DelegationError::State(_) => (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![])),
+                _ => unprocessable(vec![field_error("expires_at", &e.to_string())]),
+            }
+        })?;
+
+    rest_info!(
+        "asset group {} delegated to operator {}.",
+        id,
+        delegation.delegatee
+    );
+    Ok(Json(delegation))
+}
+
+/// Get the active delegation of an
+/// [`AssetGroup`](crate::rest::structs::AssetGroup).
+#[utoipa::path(
+    get,
+    path = "/assets/groups/{id}/delegation",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Delegation of asset group {id}", body = Delegation),
+        (status = 400, description = "Invalid asset group id"),
+        (status = 404, description = "Asset group is not delegated"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset group id"),
+    )
+)]
+pub async fn get_asset_group_delegation(
+    Extension(delegations): Extension<GroupDelegations>,
+    Path(id): Path<String>,
+) -> Result<Json<Delegation>, StatusCode> {
+    rest_info!("entry [{}].", &id);
+
+    let id = group_id(&id)?;
+    delegations
+        .get(&id, Utc::now())
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Revoke the delegation of an
+/// [`AssetGroup`](crate::rest::structs::AssetGroup).
+///
+/// The operator revoking the delegation is taken from the `x-operator-id`
+/// header and recorded in the audit trail.
+#[utoipa::path(
+    delete,
+    path = "/assets/groups/{id}/delegation",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Delegation revoked; the revoked delegation is returned", body = Delegation),
+        (status = 400, description = "Invalid asset group id or operator header"),
+        (status = 404, description = "Asset group is not delegated"),
+        (status = 500, description = "The revocation could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset group id"),
+        ("x-operator-id" = Option<String>, Header, description = "UUID of the operator revoking the delegation, recorded in the audit trail"),
+    )
+)]
+pub async fn revoke_asset_group_delegation(
+    Extension(delegations): Extension<GroupDelegations>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Delegation>, StatusCode> {
+    rest_info!("entry [{}].", &id);
+
+    let id = group_id(&id)?;
+    let actor = operator_id_from_headers(&headers)?;
+    let delegation = delegations.revoke(&id, actor, Utc::now()).map_err(|e| {
+        rest_error!("could not revoke asset group delegation: {e}");
+        match e {
+            DelegationError::State(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::NOT_FOUND,
+        }
+    })?;
+
+    rest_info!(
+        "delegation of asset group {} to operator {} revoked.",
+        id,
+        delegation.delegatee
+    );
+    Ok(Json(delegation))
+}
+
+/// Get the delegation audit trail of an
+/// [`AssetGroup`](crate::rest::structs::AssetGroup), most recent first.
+///
+/// At most [`MAX_DELEGATION_AUDIT_LEN`] entries are kept per group.
+#[utoipa::path(
+    get,
+    path = "/assets/groups/{id}/delegation/audit",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Delegation audit trail of asset group {id}", body = [DelegationAuditEntry]),
+        (status = 400, description = "Invalid asset group id"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset group id"),
+    )
+)]
+pub async fn get_asset_group_delegation_audit(
+    Extension(delegations): Extension<GroupDelegations>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<DelegationAuditEntry>>, StatusCode> {
+    rest_info!("entry [{}].", &id);
+
+    let id = group_id(&id)?;
+    Ok(Json(delegations.audit(&id)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::repo::memory::MemoryRepository;
+    use crate::rest::quota::AssetOwners;
+    use crate::testing::AircraftDataBuilder;
+    use axum::http::HeaderValue;
+    use chrono::{Duration, TimeZone};
     use lib_common::logger::get_log_handle;
     use lib_common::uuid::Uuid;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use svc_storage_client_grpc::prelude::{group, vehicle};
 
     #[tokio::test]
     async fn test_register_asset_group() {
@@ -183,19 +1156,21 @@ mod tests {
         let payload = AssetGroup {
             id: Uuid::new_v4().to_string(),
             name: Some("Test Group".to_string()),
-            owner: "Test Owner".to_string(),
+            owner: Some("Test Owner".to_string()),
             created_at: None,
             updated_at: None,
             delegatee: None,
             assets: vec![],
+            parent_group_id: None,
+            child_group_ids: vec![],
         };
 
         let config = crate::config::Config::default();
         let grpc_clients = GrpcClients::default(config);
         let result = update_asset_group(
             Extension(grpc_clients),
-            Json(payload),
             Path(Uuid::new_v4().to_string()),
+            Json(payload),
         )
         .await;
         assert!(result.is_err());
@@ -217,17 +1192,674 @@ mod tests {
         assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
     }
 
+    /// Create repositories with groups by id and parent id
+    fn groups(groups: &[(&str, Option<&str>)]) -> Repositories {
+        let groups = groups
+            .iter()
+            .map(|(id, parent)| {
+                let data = group::Data {
+                    name: format!("Group {id}"),
+                    parent_group_id: parent.map(str::to_string),
+                    ..Default::default()
+                };
+                (id.to_string(), data)
+            })
+            .collect::<HashMap<_, _>>();
+
+        Repositories {
+            groups: Arc::new(MemoryRepository::with_objects(groups)),
+            ..Repositories::memory()
+        }
+    }
+
+    /// Add an aircraft to an asset group, returning its id
+    async fn add_aircraft(repos: &Repositories, group_id: &str) -> String {
+        let data = vehicle::Data {
+            asset_group_id: Some(group_id.to_string()),
+            ..vehicle::mock::get_data_obj()
+        };
+        repos.aircraft.insert(data).await.unwrap().id
+    }
+
     #[tokio::test]
     async fn test_get_asset_group_by_id() {
         get_log_handle().await;
         ut_info!("Start.");
 
-        let config = crate::config::Config::default();
-        let grpc_clients = GrpcClients::default(config);
-        let result =
-            get_asset_group_by_id(Extension(grpc_clients), Path(Uuid::new_v4().to_string())).await;
-        assert!(result.is_err());
-        let (status, _) = result.unwrap_err();
-        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+        let (region, base, squad) = (
+            Uuid::new_v4().to_string(),
+            Uuid::new_v4().to_string(),
+            Uuid::new_v4().to_string(),
+        );
+        let repos = groups(&[
+            (region.as_str(), None),
+            (base.as_str(), Some(region.as_str())),
+            (squad.as_str(), Some(base.as_str())),
+        ]);
+        let region_aircraft = add_aircraft(&repos, &region).await;
+        let squad_aircraft = add_aircraft(&repos, &squad).await;
+
+        let delegations = GroupDelegations::default();
+        let delegatee = Uuid::new_v4().to_string();
+        delegations
+            .delegate(
+                &base,
+                DelegationPayload {
+                    delegatee: delegatee.clone(),
+                    expires_at: None,
+                },
+                None,
+                Utc::now(),
+            )
+            .unwrap();
+
+        let get = |repos: Repositories, id: String, recursive: bool| {
+            get_asset_group_by_id(
+                Extension(repos),
+                Extension(FanOut::new(crate::Config::default().into())),
+                Extension(delegations.clone()),
+                Path(id),
+                Query(AssetGroupQuery { recursive }),
+            )
+        };
+
+        let Json(group) = get(repos.clone(), base.clone(), false).await.unwrap();
+        assert_eq!(group.id, base);
+        assert_eq!(group.name, Some(format!("Group {base}")));
+        assert_eq!(group.owner, None);
+        assert_eq!(group.delegatee, Some(delegatee));
+        assert_eq!(group.parent_group_id, Some(region.clone()));
+        assert_eq!(group.child_group_ids, vec![squad.clone()]);
+        assert!(group.assets.is_empty());
+
+        let Json(group) = get(repos.clone(), region.clone(), true).await.unwrap();
+        assert_eq!(group.delegatee, None);
+        assert_eq!(group.parent_group_id, None);
+        assert_eq!(group.assets, vec![region_aircraft, squad_aircraft]);
+
+        let error = get(repos.clone(), Uuid::new_v4().to_string(), false)
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+        let error = get(repos, "invalid".to_string(), false).await.unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+        let error = get(Repositories::unavailable(), region, false)
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::SERVICE_UNAVAILABLE);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_put_asset_group_parent() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let (region, base) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+        let repos = groups(&[
+            (region.as_str(), None),
+            (base.as_str(), Some(region.as_str())),
+        ]);
+
+        let put = |repos: Repositories, id: String, parent_group_id: Option<String>| {
+            put_asset_group_parent(
+                Extension(repos),
+                Extension(FanOut::new(crate::Config::default().into())),
+                Path(id),
+                Json(GroupParentPayload { parent_group_id }),
+            )
+        };
+
+        // a group can not be below its own child group
+        let (status, Json(errors)) = put(repos.clone(), region.clone(), Some(base.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "parent_group_id");
+
+        for parent_group_id in [Uuid::new_v4().to_string(), "invalid".to_string()] {
+            let (status, Json(errors)) = put(repos.clone(), base.clone(), Some(parent_group_id))
+                .await
+                .unwrap_err();
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+            assert_eq!(errors[0].field, "parent_group_id");
+        }
+
+        let (status, _) = put(repos.clone(), Uuid::new_v4().to_string(), None)
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = put(repos.clone(), "invalid".to_string(), None)
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // an empty parent makes the group a top level group
+        put(repos.clone(), base.clone(), Some(" ".to_string()))
+            .await
+            .unwrap();
+        let object = repos.groups.get_by_id(base.clone()).await.unwrap();
+        assert_eq!(group_hierarchy::parent_of(&object), None);
+
+        put(repos.clone(), region.clone(), Some(base.clone()))
+            .await
+            .unwrap();
+        let object = repos.groups.get_by_id(region).await.unwrap();
+        assert_eq!(group_hierarchy::parent_of(&object), Some(base));
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_patch_asset_group() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let group_id = Uuid::new_v4().to_string();
+        let other_group_id = Uuid::new_v4().to_string();
+        let repos = Repositories {
+            groups: Arc::new(MemoryRepository::with_objects(HashMap::from([(
+                group_id.clone(),
+                group::Data {
+                    name: "Fleet".to_string(),
+                    ..Default::default()
+                },
+            )]))),
+            ..Repositories::memory()
+        };
+        let insert = |group: Option<&str>| {
+            let mut builder = AircraftDataBuilder::new();
+            if let Some(group) = group {
+                builder = builder.asset_group_id(group.to_string());
+            }
+            let repos = repos.clone();
+            async move { repos.aircraft.insert(builder.build()).await.unwrap().id }
+        };
+        let member = insert(Some(&group_id)).await;
+        let joining = insert(None).await;
+        let elsewhere = insert(Some(&other_group_id)).await;
+
+        let delegations = GroupDelegations::default();
+        let actor = Uuid::new_v4().to_string();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-operator-id", HeaderValue::from_str(&actor).unwrap());
+        let patch = |id: &str, operations: serde_json::Value| {
+            patch_asset_group(
+                Extension(repos.clone()),
+                Extension(delegations.clone()),
+                Path(id.to_string()),
+                headers.clone(),
+                Json(serde_json::from_value(operations).unwrap()),
+            )
+        };
+
+        // add one member and remove another, rename and delegate the group
+        let delegatee = Uuid::new_v4().to_string();
+        let Json(group) = patch(
+            &group_id,
+            serde_json::json!([
+                { "op": "test", "path": "/assets/0", "value": member },
+                { "op": "remove", "path": "/assets/0" },
+                { "op": "add", "path": "/assets/-", "value": joining },
+                { "op": "replace", "path": "/name", "value": "Night Fleet" },
+                { "op": "add", "path": "/delegatee", "value": delegatee },
+            ]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(group.name, Some("Night Fleet".to_string()));
+        assert_eq!(group.assets, vec![joining.clone()]);
+        assert_eq!(group.delegatee, Some(delegatee.clone()));
+
+        let stored = repos.groups.get_by_id(group_id.clone()).await.unwrap();
+        assert_eq!(stored.data.unwrap().name, "Night Fleet");
+        let group_of = |id: String| {
+            let repos = repos.clone();
+            async move {
+                repos
+                    .aircraft
+                    .get_by_id(id)
+                    .await
+                    .unwrap()
+                    .data
+                    .unwrap()
+                    .asset_group_id
+            }
+        };
+        assert_eq!(group_of(joining.clone()).await, Some(group_id.clone()));
+        assert_eq!(group_of(member.clone()).await, None);
+        let delegation = delegations.get(&group_id, Utc::now()).unwrap();
+        assert_eq!(delegation.delegatee, delegatee);
+        assert_eq!(delegation.delegated_by, Some(actor));
+
+        // a failed test changes nothing
+        let (status, Json(errors)) = patch(
+            &group_id,
+            serde_json::json!([
+                { "op": "test", "path": "/assets/0", "value": member },
+                { "op": "remove", "path": "/delegatee" },
+            ]),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(errors[0].field, "/assets/0");
+        assert!(delegations.get(&group_id, Utc::now()).is_some());
+
+        // aircraft of another group can not be added
+        let (status, Json(errors)) = patch(
+            &group_id,
+            serde_json::json!([{ "op": "add", "path": "/assets/-", "value": elsewhere }]),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(errors[0].field, "assets");
+        assert_eq!(group_of(elsewhere).await, Some(other_group_id));
+
+        // invalid patches
+        for operations in [
+            serde_json::json!([{ "op": "replace", "path": "/owner", "value": "x" }]),
+            serde_json::json!([{ "op": "remove", "path": "/assets" }]),
+            serde_json::json!([{ "op": "add", "path": "/assets/-", "value": "invalid" }]),
+            serde_json::json!([{ "op": "add", "path": "/assets/-", "value": joining }]),
+            serde_json::json!([{ "op": "add", "path": "/assets/-", "value": Uuid::new_v4() }]),
+        ] {
+            let (status, _) = patch(&group_id, operations).await.unwrap_err();
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        }
+        assert_eq!(group_of(joining).await, Some(group_id.clone()));
+
+        let (status, _) = patch(&Uuid::new_v4().to_string(), serde_json::json!([]))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = patch("invalid", serde_json::json!([])).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_asset_group_schedule() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let schedules = GroupSchedules::default();
+        let id = Uuid::new_v4().to_string();
+        let from = Utc.with_ymd_and_hms(2022, 10, 22, 0, 0, 0).unwrap();
+        let query = AvailabilityQuery {
+            from,
+            to: from + Duration::days(3),
+        };
+
+        let (status, _) = put_asset_group_schedule(
+            Extension(schedules.clone()),
+            Path("invalid".to_string()),
+            Json(GroupSchedulePayload::default()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, Json(errors)) = put_asset_group_schedule(
+            Extension(schedules.clone()),
+            Path(id.clone()),
+            Json(GroupSchedulePayload {
+                schedule: Some("invalid".to_string()),
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "schedule");
+
+        // not constrained without a schedule
+        let Json(availability) = get_asset_group_availability(
+            Extension(schedules.clone()),
+            Path(id.clone()),
+            Query(query),
+        )
+        .await
+        .unwrap();
+        assert!(!availability.constrained);
+        assert_eq!(availability.windows.len(), 1);
+
+        // weekdays only
+        put_asset_group_schedule(
+            Extension(schedules.clone()),
+            Path(id.clone()),
+            Json(GroupSchedulePayload {
+                schedule: Some(
+                    "DTSTART:20221017T000000Z;DURATION:PT24H\n\
+                    RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR"
+                        .to_string(),
+                ),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let Json(availability) = get_asset_group_availability(
+            Extension(schedules.clone()),
+            Path(id.clone()),
+            Query(query),
+        )
+        .await
+        .unwrap();
+        assert_eq!(availability.group_id, id);
+        assert!(availability.constrained);
+        assert_eq!(
+            availability.windows,
+            vec![TimeWindow {
+                start: from + Duration::days(2),
+                end: query.to,
+            }]
+        );
+
+        let error = get_asset_group_availability(
+            Extension(schedules),
+            Path(id),
+            Query(AvailabilityQuery {
+                from,
+                to: from + Duration::days(MAX_GROUP_AVAILABILITY_RANGE_DAYS + 1),
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_asset_group_delegation() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let delegations = GroupDelegations::default();
+        let id = Uuid::new_v4().to_string();
+        let delegatee = Uuid::new_v4().to_string();
+        let operator = Uuid::new_v4().to_string();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            crate::rest::quota::OPERATOR_ID_HEADER,
+            HeaderValue::from_str(&operator).unwrap(),
+        );
+
+        let (status, Json(errors)) = put_asset_group_delegation(
+            Extension(delegations.clone()),
+            Path(id.clone()),
+            headers.clone(),
+            Json(DelegationPayload {
+                delegatee: "invalid".to_string(),
+                expires_at: None,
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "delegatee");
+
+        let (status, Json(errors)) = put_asset_group_delegation(
+            Extension(delegations.clone()),
+            Path(id.clone()),
+            headers.clone(),
+            Json(DelegationPayload {
+                delegatee: delegatee.clone(),
+                expires_at: Some(Utc::now() - Duration::hours(1)),
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "expires_at");
+
+        let error = get_asset_group_delegation(Extension(delegations.clone()), Path(id.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        let Json(delegation) = put_asset_group_delegation(
+            Extension(delegations.clone()),
+            Path(id.clone()),
+            headers.clone(),
+            Json(DelegationPayload {
+                delegatee: format!(" {} ", delegatee),
+                expires_at: Some(Utc::now() + Duration::hours(1)),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(delegation.delegatee, delegatee);
+        assert_eq!(delegation.delegated_by, Some(operator.clone()));
+
+        let (status, _) = put_asset_group_delegation(
+            Extension(delegations.clone()),
+            Path(id.clone()),
+            headers.clone(),
+            Json(DelegationPayload {
+                delegatee: Uuid::new_v4().to_string(),
+                expires_at: None,
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let Json(current) =
+            get_asset_group_delegation(Extension(delegations.clone()), Path(id.clone()))
+                .await
+                .unwrap();
+        assert_eq!(current, delegation);
+
+        let Json(revoked) = revoke_asset_group_delegation(
+            Extension(delegations.clone()),
+            Path(id.clone()),
+            headers.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(revoked, delegation);
+
+        let error = revoke_asset_group_delegation(
+            Extension(delegations.clone()),
+            Path(id.clone()),
+            headers,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        let Json(audit) = get_asset_group_delegation_audit(Extension(delegations), Path(id))
+            .await
+            .unwrap();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[0].action, DelegationAction::Revoked);
+        assert_eq!(audit[0].actor, Some(operator));
+        assert_eq!(audit[1].action, DelegationAction::Delegated);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_get_asset_group_assets() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let (group_id, child_id) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+        let repos = groups(&[
+            (group_id.as_str(), None),
+            (child_id.as_str(), Some(group_id.as_str())),
+        ]);
+        let mut ids = vec![];
+        for _ in 0..2 {
+            let data = AircraftDataBuilder::new()
+                .asset_group_id(group_id.clone())
+                .build();
+            ids.push(repos.aircraft.insert(data).await.unwrap().id);
+        }
+        // aircraft of child groups are only included recursively
+        let data = AircraftDataBuilder::new()
+            .asset_group_id(child_id.clone())
+            .build();
+        let child_aircraft = repos.aircraft.insert(data).await.unwrap().id;
+
+        let get_recursive = |repos: Repositories, id: String, resolve: bool, recursive: bool| {
+            get_asset_group_assets(
+                Extension(repos),
+                Extension(FanOut::new(crate::Config::default().into())),
+                Extension(OverdueAircraft::default()),
+                Extension(MaintenanceHolds::default()),
+                Extension(AircraftStatuses::default()),
+                Extension(VertiportOpsStatuses::default()),
+                Path(id),
+                Query(GroupAssetsQuery { resolve, recursive }),
+            )
+        };
+        let get = |repos: Repositories, id: String, resolve: bool| {
+            get_recursive(repos, id, resolve, false)
+        };
+
+        let Json(assets) = get(repos.clone(), group_id.clone(), false).await.unwrap();
+        assert_eq!(assets.group_id, group_id);
+        assert_eq!(assets.asset_ids.len(), 2);
+        assert!(ids.iter().all(|id| assets.asset_ids.contains(id)));
+        assert_eq!(assets.assets, None);
+
+        let Json(assets) = get_recursive(repos.clone(), group_id.clone(), false, true)
+            .await
+            .unwrap();
+        assert_eq!(assets.asset_ids.len(), 3);
+        assert_eq!(assets.asset_ids[2], child_aircraft);
+
+        let Json(assets) = get(repos.clone(), group_id.clone(), true).await.unwrap();
+        let resolved = assets.assets.unwrap();
+        assert_eq!(resolved.len(), 2);
+        for (asset, id) in resolved.iter().zip(assets.asset_ids.iter()) {
+            match asset {
+                ResolvedAsset::Aircraft(aircraft) => assert_eq!(&aircraft.basics.id, id),
+                other => panic!("expected an aircraft, got {other:?}"),
+            }
+        }
+        assert!(assets.failed.is_empty());
+
+        let error = get(repos.clone(), Uuid::new_v4().to_string(), true)
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+        let error = get(repos, "invalid".to_string(), true).await.unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+        let error = get(Repositories::unavailable(), group_id, true)
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::SERVICE_UNAVAILABLE);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_check_group_assignment() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let group_id = Uuid::new_v4().to_string();
+        let repos = Repositories {
+            groups: Arc::new(MemoryRepository::with_objects(HashMap::from([(
+                group_id.clone(),
+                group::Data {
+                    name: "Fleet".to_string(),
+                    ..Default::default()
+                },
+            )]))),
+            ..Repositories::memory()
+        };
+        let delegations = GroupDelegations::default();
+        let quotas = OperatorQuotas::new(crate::Config::default().into(), AssetOwners::default());
+        let (owner, other) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+        let check = |group_id: String, operator_id: Option<String>| {
+            let (repos, delegations, quotas) = (repos.clone(), delegations.clone(), quotas.clone());
+            async move {
+                check_group_assignment(
+                    &repos,
+                    &delegations,
+                    &quotas,
+                    &group_id,
+                    operator_id.as_deref(),
+                )
+                .await
+                .unwrap()
+                .map(|error| error.message)
+            }
+        };
+
+        // an operator is required, unknown groups and groups without a known
+        // owner are rejected
+        let error = check_group_assignment(&repos, &delegations, &quotas, &group_id, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            check(Uuid::new_v4().to_string(), Some(other.clone()))
+                .await
+                .as_deref(),
+            Some("asset group does not exist")
+        );
+        assert_eq!(
+            check(group_id.clone(), Some(other.clone()))
+                .await
+                .as_deref(),
+            Some("the owner of the asset group is not known")
+        );
+
+        // the group contains an aircraft registered by the owner
+        let data = AircraftDataBuilder::new()
+            .asset_group_id(group_id.clone())
+            .build();
+        let aircraft_id = repos.aircraft.insert(data).await.unwrap().id;
+        quotas
+            .reserve(&repos, &owner, QuotaKind::Aircraft)
+            .await
+            .unwrap()
+            .commit(&aircraft_id)
+            .unwrap();
+        assert_eq!(check(group_id.clone(), Some(owner.clone())).await, None);
+        assert_eq!(
+            check(group_id.clone(), Some(other.clone()))
+                .await
+                .as_deref(),
+            Some("asset group belongs to another operator")
+        );
+
+        // delegated groups can be assigned to by the delegatee
+        let payload = DelegationPayload {
+            delegatee: other.clone(),
+            expires_at: None,
+        };
+        delegations
+            .delegate(&group_id, payload, Some(owner.clone()), Utc::now())
+            .unwrap();
+        assert_eq!(check(group_id.clone(), Some(other)).await, None);
+        assert_eq!(check(group_id.clone(), Some(owner)).await, None);
+        assert_eq!(
+            check(group_id, Some(Uuid::new_v4().to_string()))
+                .await
+                .as_deref(),
+            Some("asset group belongs to another operator")
+        );
+
+        let error = check_group_assignment(
+            &Repositories::unavailable(),
+            &delegations,
+            &quotas,
+            &Uuid::new_v4().to_string(),
+            Some(&Uuid::new_v4().to_string()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::SERVICE_UNAVAILABLE);
+
+        ut_info!("Success.");
     }
 }
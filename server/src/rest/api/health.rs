@@ -1,72 +1,90 @@
 //! Health check REST endpoint
 
+pub use super::rest_types::{HealthStatus, StartupState, StorageLatency};
+
+use crate::config::SharedConfig;
+use crate::fan_out::FanOut;
 use crate::grpc::client::GrpcClients;
+use crate::latency::LatencySlo;
+use crate::rest::json::Json;
+use crate::startup::Startup;
 use axum::Extension;
 use hyper::StatusCode;
 use svc_storage_client_grpc::prelude::ReadyRequest;
 use svc_storage_client_grpc::simple_service::Client;
 
-/// Health check for load balancing
+/// Health check for load balancing.
+///
+/// The service is unhealthy until the startup phase completed, see
+/// [`crate::startup`]. The body reports the latency of the svc-storage calls;
+/// the service is degraded, but still healthy, if a latency exceeds its
+/// objective, see [`crate::latency`].
 #[utoipa::path(
     get,
     path = "/health",
     tag = "svc-assets",
     responses(
-        (status = 200, description = "Service is healthy, all dependencies running."),
-        (status = 503, description = "Service is unhealthy, one or more dependencies unavailable.")
+        (status = 200, description = "Service is healthy, all dependencies running. The service might be degraded by slow svc-storage calls.", body = HealthStatus),
+        (status = 503, description = "Service is starting or unhealthy, one or more dependencies unavailable.", body = HealthStatus)
     )
 )]
 pub async fn health_check(
     Extension(grpc_clients): Extension<GrpcClients>,
-) -> Result<(), StatusCode> {
+    Extension(fan_out): Extension<FanOut>,
+    Extension(startup): Extension<Startup>,
+    Extension(config): Extension<SharedConfig>,
+) -> (StatusCode, Json<HealthStatus>) {
     rest_debug!("entry.");
 
-    let mut ok = true;
-
-    if grpc_clients
-        .storage
-        .vertiport
-        .is_ready(ReadyRequest {})
-        .await
-        .is_err()
-    {
-        let error_msg = "svc-storage vertiport unavailable.".to_string();
-        rest_error!("{}.", &error_msg);
-        ok = false;
+    let slo = config
+        .read(|c| LatencySlo::from_ms(c.storage_latency_slo_p95_ms, c.storage_latency_slo_p99_ms));
+    let storage_latency = grpc_clients.storage_latencies().report(slo);
+    for latency in storage_latency.iter().filter(|l| l.slo_exceeded) {
+        rest_warn!(
+            "svc-storage {} latency exceeds its objective: p95 {:.1}ms, p99 {:.1}ms.",
+            latency.resource,
+            latency.p95_ms,
+            latency.p99_ms
+        );
     }
 
-    if grpc_clients
-        .storage
-        .vertipad
-        .is_ready(ReadyRequest {})
-        .await
-        .is_err()
-    {
-        let error_msg = "svc-storage vertipad unavailable.".to_string();
-        rest_error!("{}.", &error_msg);
-        ok = false;
+    let status = HealthStatus {
+        startup: startup.state(),
+        degraded: storage_latency.iter().any(|l| l.slo_exceeded),
+        storage_latency,
+    };
+    if status.startup != StartupState::Ready {
+        rest_warn!("unhealthy, startup state {:?}.", status.startup);
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(status));
     }
 
-    if grpc_clients
-        .storage
-        .vehicle
-        .is_ready(ReadyRequest {})
-        .await
-        .is_err()
-    {
-        let error_msg = "svc-storage vehicle unavailable.".to_string();
-        rest_error!("{}.", &error_msg);
-        ok = false;
+    let storage = &grpc_clients.storage;
+    let (vertiport, vertipad, vehicle) = tokio::join!(
+        fan_out.call(storage.vertiport.is_ready(ReadyRequest {})),
+        fan_out.call(storage.vertipad.is_ready(ReadyRequest {})),
+        fan_out.call(storage.vehicle.is_ready(ReadyRequest {})),
+    );
+
+    let mut ok = true;
+    for (name, error) in [
+        ("vertiport", vertiport.err()),
+        ("vertipad", vertipad.err()),
+        ("vehicle", vehicle.err()),
+    ] {
+        if let Some(e) = error {
+            rest_error!("svc-storage {} unavailable: {}.", name, e);
+            ok = false;
+        }
     }
 
     match ok {
         true => {
             rest_debug!("healthy, all dependencies running.");
-            Ok(())
+            (StatusCode::OK, Json(status))
         }
         false => {
             rest_error!("unhealthy, 1+ dependencies down.");
-            Err(StatusCode::SERVICE_UNAVAILABLE)
+            (StatusCode::SERVICE_UNAVAILABLE, Json(status))
         }
     }
 }
@@ -78,7 +96,60 @@ mod tests {
     #[tokio::test]
     async fn test_health_check() {
         let config = crate::config::Config::default();
-        let grpc_clients = GrpcClients::default(config);
-        health_check(Extension(grpc_clients)).await.unwrap();
+        let grpc_clients = GrpcClients::default(config.clone());
+        let shared_config: SharedConfig = config.into();
+        let fan_out = FanOut::new(shared_config.clone());
+        let startup = Startup::default();
+
+        let (status, body) = health_check(
+            Extension(grpc_clients.clone()),
+            Extension(fan_out.clone()),
+            Extension(startup.clone()),
+            Extension(shared_config.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body.startup, StartupState::WaitingForStorage);
+
+        startup.set(StartupState::Ready);
+        let (status, body) = health_check(
+            Extension(grpc_clients),
+            Extension(fan_out),
+            Extension(startup),
+            Extension(shared_config),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.startup, StartupState::Ready);
+        assert!(!body.degraded);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_degraded() {
+        let mut config = crate::config::Config::default();
+        config.storage_latency_slo_p95_ms = 100;
+        let grpc_clients = GrpcClients::default(config.clone());
+        let shared_config: SharedConfig = config.into();
+        let startup = Startup::default();
+        startup.set(StartupState::Ready);
+
+        let latencies = grpc_clients.storage_latencies();
+        for _ in 0..10 {
+            latencies.record("vehicle", std::time::Duration::from_millis(500));
+            latencies.record("vertipad", std::time::Duration::from_millis(5));
+        }
+
+        let (status, body) = health_check(
+            Extension(grpc_clients),
+            Extension(FanOut::new(shared_config.clone())),
+            Extension(startup),
+            Extension(shared_config),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.degraded);
+        assert_eq!(body.storage_latency.len(), 2);
+        assert!(body.storage_latency[0].slo_exceeded);
+        assert!(!body.storage_latency[1].slo_exceeded);
     }
 }
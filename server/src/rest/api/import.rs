@@ -0,0 +1,443 @@
+//! Handlers for bulk importing assets.
+
+pub use super::rest_types::{
+    GeoJsonFeature, GeoJsonFeatureCollection, GeoJsonGeometry, GeoJsonImportQuery,
+    GeoJsonProperties, ImportedVertiport,
+};
+
+use super::errors::{storage_error_status, RegistrationError};
+use super::validation::*;
+use super::vertiport::validate_vertiport_data;
+use crate::geo::polygon_centroid;
+use crate::repo::Repositories;
+use crate::rest::json::Json;
+use crate::rest::quota::{owner_id_from_headers, OperatorQuotas, QuotaKind};
+use crate::saga::Saga;
+use axum::{extract::Query, http::HeaderMap, Extension};
+use serde_json::Value;
+use svc_storage_client_grpc::prelude::*;
+
+/// Maximum number of features accepted in a single import
+const MAX_IMPORT_FEATURES: usize = 100;
+
+/// Name suffix of the default vertipad created for an imported vertiport
+const DEFAULT_VERTIPAD_SUFFIX: &str = "Pad 1";
+
+/// Convert a GeoJSON position (`[longitude, latitude, altitude]`) into a point
+fn position_to_point(position: &Value) -> Option<GeoPointZ> {
+    let position = position.as_array()?;
+    if !(2..=3).contains(&position.len()) {
+        return None;
+    }
+
+    Some(GeoPointZ {
+        x: position[0].as_f64()?,
+        y: position[1].as_f64()?,
+        z: match position.get(2) {
+            Some(z) => z.as_f64()?,
+            None => 0.0,
+        },
+    })
+}
+
+/// Convert GeoJSON polygon coordinates into a polygon
+fn coordinates_to_polygon(coordinates: &Value) -> Option<GeoPolygonZ> {
+    let rings = coordinates
+        .as_array()?
+        .iter()
+        .map(|ring| {
+            ring.as_array()?
+                .iter()
+                .map(position_to_point)
+                .collect::<Option<Vec<_>>>()
+                .map(|points| GeoLineStringZ { points })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    if rings.is_empty() {
+        return None;
+    }
+
+    Some(GeoPolygonZ { rings })
+}
+
+/// Convert a GeoJSON feature into vertiport data.
+///
+/// Field errors are prefixed with the feature's location in the collection.
+fn feature_to_vertiport(
+    index: usize,
+    feature: GeoJsonFeature,
+) -> Result<vertiport::Data, Vec<FieldError>> {
+    let prefix = format!("features[{index}]");
+
+    if feature.kind != "Feature" {
+        return Err(vec![field_error(
+            &format!("{prefix}.type"),
+            "must be 'Feature'",
+        )]);
+    }
+
+    let geo_location = match feature.geometry {
+        Some(geometry) if geometry.kind == "Polygon" => Some(
+            coordinates_to_polygon(&geometry.coordinates).ok_or_else(|| {
+                vec![field_error(
+                    &format!("{prefix}.geometry.coordinates"),
+                    "must be a list of linear rings of [longitude, latitude, altitude] positions",
+                )]
+            })?,
+        ),
+        _ => {
+            return Err(vec![field_error(
+                &format!("{prefix}.geometry"),
+                "must be a Polygon",
+            )])
+        }
+    };
+
+    let properties = feature.properties.unwrap_or_default();
+    let data = vertiport::Data {
+        name: properties.name.unwrap_or_default(),
+        description: properties.description.unwrap_or_default(),
+        geo_location,
+        schedule: None,
+        created_at: None,
+        updated_at: None,
+    };
+
+    validate_vertiport_data(data).map_err(|errors| {
+        errors
+            .into_iter()
+            .map(|error| {
+                let field = match error.field.as_str() {
+                    "name" | "description" => format!("{prefix}.properties.{}", error.field),
+                    _ => format!("{prefix}.geometry"),
+                };
+                FieldError { field, ..error }
+            })
+            .collect()
+    })
+}
+
+/// Convert a feature collection into vertiport data, validating all features.
+fn collection_to_vertiports(
+    collection: GeoJsonFeatureCollection,
+) -> Result<Vec<vertiport::Data>, Vec<FieldError>> {
+    if collection.kind != "FeatureCollection" {
+        return Err(vec![field_error("type", "must be 'FeatureCollection'")]);
+    }
+
+    if collection.features.is_empty() || collection.features.len() > MAX_IMPORT_FEATURES {
+        return Err(vec![field_error(
+            "features",
+            &format!("must contain between 1 and {MAX_IMPORT_FEATURES} features"),
+        )]);
+    }
+
+    let mut vertiports = vec![];
+    let mut errors = vec![];
+    for (index, feature) in collection.features.into_iter().enumerate() {
+        match feature_to_vertiport(index, feature) {
+            Ok(data) => vertiports.push(data),
+            Err(feature_errors) => errors.extend(feature_errors),
+        }
+    }
+
+    into_result(vertiports, errors)
+}
+
+/// Register an imported vertiport and optionally a default vertipad at its
+/// centroid, recorded in the saga of the import.
+///
+/// Returns the id of the vertiport and of the vertipad.
+async fn import_vertiport(
+    saga: &mut Saga,
+    data: vertiport::Data,
+    create_vertipad: bool,
+) -> Result<(String, Option<String>), tonic::Status> {
+    let centroid = data.geo_location.as_ref().and_then(polygon_centroid);
+    let name = data.name.clone();
+    let vertiport_id = saga.insert_vertiport(data).await.map_err(|e| {
+        rest_error!("could not insert vertiport: {e}");
+        e
+    })?;
+
+    let (true, Some(centroid)) = (create_vertipad, centroid) else {
+        return Ok((vertiport_id, None));
+    };
+
+    let vertipad = vertipad::Data {
+        name: format!("{name} {DEFAULT_VERTIPAD_SUFFIX}"),
+        vertiport_id: vertiport_id.clone(),
+        geo_location: Some(centroid),
+        enabled: true,
+        occupied: false,
+        schedule: None,
+        created_at: None,
+        updated_at: None,
+    };
+    let vertipad_id = saga.insert_vertipad(vertipad).await.map_err(|e| {
+        rest_error!("could not insert vertipad: {e}");
+        e
+    })?;
+
+    Ok((vertiport_id, Some(vertipad_id)))
+}
+
+/// Import vertiports from a GeoJSON FeatureCollection.
+///
+/// Each feature must have a `Polygon` geometry and can have `name` and
+/// `description` properties. All features are validated before any
+/// vertiport is registered. Optionally, a default vertipad is created at the
+/// centroid of each vertiport. The importing operator becomes the owner of
+/// the vertiports. If a vertiport or vertipad can not be
+/// registered, the ones registered before are removed again; the ones which
+/// could not be removed are returned, see [`RegistrationError`].
+#[utoipa::path(
+    post,
+    path = "/assets/import/geojson",
+    tag = "svc-assets",
+    request_body = GeoJsonFeatureCollection,
+    responses(
+        (status = 200, description = "Vertiports registered in database", body = [ImportedVertiport]),
+        (status = 400, description = "Missing or invalid operator id header"),
+        (status = 403, description = "Operator reached its vertiport quota; if assets were created, the ones which could not be removed again are returned", body = super::errors::IncompleteRegistration),
+        (status = 422, description = "Features can not be imported", body = [FieldError]),
+        (status = 500, description = "The owner of the vertiports could not be recorded; the assets which could not be removed again are returned", body = super::errors::IncompleteRegistration),
+        (status = 503, description = "Could not connect to other microservice dependencies; if assets were created, the ones which could not be removed again are returned", body = super::errors::IncompleteRegistration)
+    ),
+    params(
+        GeoJsonImportQuery,
+        ("x-operator-id" = String, Header, description = "UUID of the operator importing and owning the vertiports; set from the API token if it is bound to an operator"),
+    )
+)]
+pub async fn import_geojson(
+    Extension(repos): Extension<Repositories>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Query(query): Query<GeoJsonImportQuery>,
+    headers: HeaderMap,
+    Json(collection): Json<GeoJsonFeatureCollection>,
+) -> Result<Json<Vec<ImportedVertiport>>, RegistrationError> {
+    rest_info!("entry.");
+    rest_debug!("Query: {:?}", query);
+
+    let operator_id = owner_id_from_headers(&headers)?;
+    let vertiports = collection_to_vertiports(collection).map_err(unprocessable)?;
+    let create_vertipads = query.create_vertipads.unwrap_or(false);
+
+    let mut saga = Saga::new("import geojson", &repos);
+    let mut reservations = vec![];
+    let mut imported = vec![];
+    for (feature_index, data) in vertiports.into_iter().enumerate() {
+        let reservation = match quotas
+            .reserve(&repos, &operator_id, QuotaKind::Vertiport)
+            .await
+        {
+            Ok(reservation) => reservation,
+            Err(status) => {
+                let leftover = saga.compensate().await;
+                return Err(RegistrationError::Incomplete(status, leftover));
+            }
+        };
+
+        match import_vertiport(&mut saga, data, create_vertipads).await {
+            Ok((vertiport_id, vertipad_id)) => {
+                reservations.push((reservation, vertiport_id.clone()));
+                imported.push(ImportedVertiport {
+                    feature_index,
+                    vertiport_id,
+                    vertipad_id,
+                });
+            }
+            Err(e) => {
+                let leftover = saga.compensate().await;
+                return Err(RegistrationError::Incomplete(
+                    storage_error_status(&e),
+                    leftover,
+                ));
+            }
+        }
+    }
+
+    // The quota is only used once all vertiports are imported
+    for (reservation, vertiport_id) in reservations {
+        if let Err(e) = reservation.commit(&vertiport_id) {
+            rest_error!("could not record the owner of vertiport {vertiport_id}: {e}");
+            let leftover = saga.compensate().await;
+            return Err(RegistrationError::Incomplete(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                leftover,
+            ));
+        }
+    }
+
+    rest_info!("imported {} vertiports.", imported.len());
+    Ok(Json(imported))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::client::GrpcClients;
+    use crate::rest::quota::{AssetOwners, OPERATOR_ID_HEADER};
+    use hyper::StatusCode;
+    use serde_json::json;
+
+    fn collection(features: Value) -> GeoJsonFeatureCollection {
+        serde_json::from_value(json!({
+            "type": "FeatureCollection",
+            "features": features,
+        }))
+        .unwrap()
+    }
+
+    fn square_feature(name: &str) -> Value {
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [[
+                    [4.0, 52.0, 0.0],
+                    [4.01, 52.0, 0.0],
+                    [4.01, 52.01, 0.0],
+                    [4.0, 52.01, 0.0],
+                    [4.0, 52.0, 0.0]
+                ]]
+            },
+            "properties": { "name": name, "description": "Imported" }
+        })
+    }
+
+    #[test]
+    fn test_collection_to_vertiports() {
+        let vertiports =
+            collection_to_vertiports(collection(json!([square_feature("Vertiport")]))).unwrap();
+        assert_eq!(vertiports.len(), 1);
+        assert_eq!(vertiports[0].name, "Vertiport");
+        assert_eq!(vertiports[0].description, "Imported");
+        assert_eq!(
+            vertiports[0].geo_location.as_ref().unwrap().rings[0]
+                .points
+                .len(),
+            5
+        );
+
+        let errors = collection_to_vertiports(collection(json!([
+            square_feature(""),
+            {
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [4.0, 52.0] },
+                "properties": { "name": "Point" }
+            },
+            {
+                "type": "Feature",
+                "geometry": { "type": "Polygon", "coordinates": [[[4.0]]] }
+            }
+        ])))
+        .unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].field, "features[0].properties.name");
+        assert_eq!(errors[1].field, "features[1].geometry");
+        assert_eq!(errors[2].field, "features[2].geometry.coordinates");
+
+        let errors = collection_to_vertiports(collection(json!([]))).unwrap_err();
+        assert_eq!(errors[0].field, "features");
+    }
+
+    #[tokio::test]
+    async fn test_import_geojson() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let config = crate::config::Config::default();
+        let quotas = OperatorQuotas::new(config.clone().into(), AssetOwners::default());
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+        let operator_id = lib_common::uuid::Uuid::new_v4().to_string();
+        let mut headers = HeaderMap::new();
+        headers.insert(OPERATOR_ID_HEADER, operator_id.parse().unwrap());
+
+        let imported = import_geojson(
+            Extension(repos.clone()),
+            Extension(quotas.clone()),
+            Query(GeoJsonImportQuery {
+                create_vertipads: Some(true),
+            }),
+            headers,
+            Json(collection(json!([
+                square_feature("Vertiport A"),
+                square_feature("Vertiport B")
+            ]))),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(imported.len(), 2);
+        assert_eq!(imported[1].feature_index, 1);
+        assert!(imported
+            .iter()
+            .all(|vertiport| vertiport.vertipad_id.is_some()));
+        // the importing operator owns the vertiports
+        assert!(imported.iter().all(|vertiport| {
+            quotas.owner(QuotaKind::Vertiport, &vertiport.vertiport_id) == Some(operator_id.clone())
+        }));
+
+        // an import without operator is rejected before anything is created
+        let error = import_geojson(
+            Extension(repos),
+            Extension(quotas),
+            Query(GeoJsonImportQuery::default()),
+            HeaderMap::new(),
+            Json(collection(json!([square_feature("Vertiport C")]))),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, RegistrationError::Status(StatusCode::BAD_REQUEST));
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_import_geojson_compensation() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let mut config = crate::config::Config::default();
+        config.max_vertiports_per_operator = 1;
+        let quotas = OperatorQuotas::new(config.into(), AssetOwners::default());
+        let repos = Repositories::memory();
+        let operator_id = lib_common::uuid::Uuid::new_v4().to_string();
+        let mut headers = HeaderMap::new();
+        headers.insert(OPERATOR_ID_HEADER, operator_id.parse().unwrap());
+
+        // the second vertiport exceeds the quota, the first is removed again
+        let error = import_geojson(
+            Extension(repos.clone()),
+            Extension(quotas.clone()),
+            Query(GeoJsonImportQuery {
+                create_vertipads: Some(true),
+            }),
+            headers,
+            Json(collection(json!([
+                square_feature("Vertiport A"),
+                square_feature("Vertiport B")
+            ]))),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error,
+            RegistrationError::Incomplete(StatusCode::FORBIDDEN, vec![])
+        );
+
+        let filter = || AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+        assert!(repos.vertiports.search(filter()).await.unwrap().is_empty());
+        assert!(repos.vertipads.search(filter()).await.unwrap().is_empty());
+        let usage = quotas
+            .usage(&repos, &operator_id, QuotaKind::Vertiport)
+            .await
+            .unwrap();
+        assert_eq!(usage.used, 0);
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,249 @@
+//! Application of JSON Patch (RFC 6902) documents.
+//!
+//! The operations are applied in order to a JSON representation of the
+//! resource, the handler compares the result with the current state to find
+//! the changes. Only the top-level fields listed by the handler can be
+//! patched. A patch is applied as a whole: if any operation fails, including
+//! a `test` operation, none of its changes are kept.
+
+pub use super::rest_types::JsonPatchOperation;
+
+use super::validation::{field_error, FieldError};
+use serde_json::Value;
+
+/// Error applying a JSON Patch
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonPatchError {
+    /// An operation can not be applied to the document
+    Invalid(Vec<FieldError>),
+    /// The value at the path of a `test` operation differs, the resource
+    /// changed since the patch was made
+    TestFailed(FieldError),
+}
+
+/// Parse a JSON Pointer (RFC 6901) into its reference tokens.
+fn pointer_tokens(path: &str) -> Option<Vec<String>> {
+    let tokens = path.strip_prefix('/')?;
+    Some(
+        tokens
+            .split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+/// Parse an array index, `-` being the end of the array if `append` is set.
+fn array_index(token: &str, len: usize, append: bool) -> Option<usize> {
+    if append && token == "-" {
+        return Some(len);
+    }
+
+    // leading zeros and signs are not allowed by RFC 6901
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+    let index: usize = token.parse().ok()?;
+    let bound = if append { len + 1 } else { len };
+    (index < bound).then_some(index)
+}
+
+/// Get the parent of the value a pointer refers to, with the last token.
+fn parent_mut<'a>(
+    document: &'a mut Value,
+    tokens: &'a [String],
+) -> Option<(&'a mut Value, &'a str)> {
+    let (last, parents) = tokens.split_last()?;
+    let mut parent = document;
+    for token in parents {
+        parent = match parent {
+            Value::Object(map) => map.get_mut(token)?,
+            Value::Array(list) => {
+                let index = array_index(token, list.len(), false)?;
+                list.get_mut(index)?
+            }
+            _ => return None,
+        };
+    }
+
+    Some((parent, last))
+}
+
+/// Add a value at the pointer, inserting it into arrays.
+fn add(document: &mut Value, tokens: &[String], value: Value) -> Option<()> {
+    match parent_mut(document, tokens)? {
+        (Value::Object(map), key) => {
+            map.insert(key.to_string(), value);
+        }
+        (Value::Array(list), token) => {
+            let index = array_index(token, list.len(), true)?;
+            list.insert(index, value);
+        }
+        _ => return None,
+    }
+
+    Some(())
+}
+
+/// Remove the value at the pointer, returning it.
+fn remove(document: &mut Value, tokens: &[String]) -> Option<Value> {
+    match parent_mut(document, tokens)? {
+        (Value::Object(map), key) => map.remove(key),
+        (Value::Array(list), token) => {
+            let index = array_index(token, list.len(), false)?;
+            Some(list.remove(index))
+        }
+        _ => None,
+    }
+}
+
+/// Get the value at the pointer.
+fn get<'a>(document: &'a Value, tokens: &[String]) -> Option<&'a Value> {
+    tokens
+        .iter()
+        .try_fold(document, |value, token| match value {
+            Value::Object(map) => map.get(token),
+            Value::Array(list) => list.get(array_index(token, list.len(), false)?),
+            _ => None,
+        })
+}
+
+/// Apply a JSON Patch to a copy of the document, returning the patched
+/// document.
+///
+/// `fields` lists the top-level fields which can be patched; operations on
+/// the document root or other fields are rejected.
+pub fn apply_json_patch(
+    document: &Value,
+    operations: &[JsonPatchOperation],
+    fields: &[&str],
+) -> Result<Value, JsonPatchError> {
+    let mut patched = document.clone();
+    for (index, operation) in operations.iter().enumerate() {
+        let path = match operation {
+            JsonPatchOperation::Add { path, .. }
+            | JsonPatchOperation::Remove { path }
+            | JsonPatchOperation::Replace { path, .. }
+            | JsonPatchOperation::Test { path, .. } => path,
+        };
+        let error = |message: &str| {
+            JsonPatchError::Invalid(vec![field_error(
+                path,
+                &format!("operation {index}: {message}"),
+            )])
+        };
+
+        let tokens = pointer_tokens(path).ok_or_else(|| error("invalid JSON Pointer"))?;
+        if !fields.contains(&tokens[0].as_str()) {
+            return Err(error("can not be patched"));
+        }
+
+        match operation {
+            JsonPatchOperation::Add { value, .. } => {
+                add(&mut patched, &tokens, value.clone())
+                    .ok_or_else(|| error("can not add a value at the path"))?;
+            }
+            JsonPatchOperation::Remove { .. } => {
+                remove(&mut patched, &tokens).ok_or_else(|| error("no value at the path"))?;
+            }
+            JsonPatchOperation::Replace { value, .. } => {
+                remove(&mut patched, &tokens).ok_or_else(|| error("no value at the path"))?;
+                add(&mut patched, &tokens, value.clone())
+                    .ok_or_else(|| error("can not replace the value at the path"))?;
+            }
+            JsonPatchOperation::Test { value, .. } => {
+                if get(&patched, &tokens) != Some(value) {
+                    return Err(JsonPatchError::TestFailed(field_error(
+                        path,
+                        &format!("operation {index}: does not match the tested value"),
+                    )));
+                }
+            }
+        }
+    }
+
+    Ok(patched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const FIELDS: &[&str] = &["name", "assets"];
+
+    fn patch(operations: Value) -> Result<Value, JsonPatchError> {
+        let operations: Vec<JsonPatchOperation> = serde_json::from_value(operations).unwrap();
+        apply_json_patch(
+            &json!({ "name": "Fleet", "assets": ["a", "b"], "owner": "x" }),
+            &operations,
+            FIELDS,
+        )
+    }
+
+    #[test]
+    fn test_apply_json_patch() {
+        let patched = patch(json!([
+            { "op": "test", "path": "/assets/1", "value": "b" },
+            { "op": "remove", "path": "/assets/0" },
+            { "op": "add", "path": "/assets/-", "value": "c" },
+            { "op": "add", "path": "/assets/0", "value": "d" },
+            { "op": "replace", "path": "/name", "value": "Renamed" },
+        ]))
+        .unwrap();
+        assert_eq!(
+            patched,
+            json!({ "name": "Renamed", "assets": ["d", "b", "c"], "owner": "x" })
+        );
+
+        let patched = patch(json!([{ "op": "remove", "path": "/name" }])).unwrap();
+        assert_eq!(patched, json!({ "assets": ["a", "b"], "owner": "x" }));
+    }
+
+    #[test]
+    fn test_apply_json_patch_errors() {
+        let invalid = |operations: Value| match patch(operations) {
+            Err(JsonPatchError::Invalid(errors)) => errors[0].field.clone(),
+            other => panic!("expected an invalid patch, got {other:?}"),
+        };
+
+        assert_eq!(
+            invalid(json!([{ "op": "replace", "path": "/owner", "value": "y" }])),
+            "/owner"
+        );
+        assert_eq!(
+            invalid(json!([{ "op": "replace", "path": "", "value": {} }])),
+            ""
+        );
+        assert_eq!(
+            invalid(json!([{ "op": "remove", "path": "/assets/2" }])),
+            "/assets/2"
+        );
+        assert_eq!(
+            invalid(json!([{ "op": "remove", "path": "/assets/01" }])),
+            "/assets/01"
+        );
+        assert_eq!(
+            invalid(json!([{ "op": "add", "path": "/assets/3", "value": "c" }])),
+            "/assets/3"
+        );
+        assert_eq!(
+            invalid(json!([{ "op": "replace", "path": "/assets/-", "value": "c" }])),
+            "/assets/-"
+        );
+
+        // a failed test fails the whole patch
+        let result = patch(json!([
+            { "op": "add", "path": "/assets/-", "value": "c" },
+            { "op": "test", "path": "/assets/0", "value": "b" },
+        ]));
+        assert!(
+            matches!(result, Err(JsonPatchError::TestFailed(error)) if error.field == "/assets/0")
+        );
+
+        // move and copy are not supported
+        let operations = serde_json::from_value::<Vec<JsonPatchOperation>>(json!([
+            { "op": "move", "from": "/name", "path": "/assets/0" }
+        ]));
+        assert!(operations.is_err());
+    }
+}
@@ -0,0 +1,336 @@
+//! Time and owner filters and result limits of the asset list endpoints.
+//!
+//! Removed assets are only listed with `include_deleted=true` or
+//! `include_deleted=only`. Their `deleted_at` time is set from the
+//! [`RemovedAssets`](crate::archive::RemovedAssets) recorded by this service.
+//!
+//! svc-storage has no owner field, so the `owner_id` filter passes the ids
+//! of the assets of the owner, recorded by the
+//! [`OperatorQuotas`](crate::rest::quota::OperatorQuotas), to the storage
+//! search. The result cap therefore only counts the assets of the owner.
+//! Assets without a known owner never match it.
+//!
+//! A list is capped at `rest_list_max_results` assets: the storage search
+//! asks for one object more than the cap, and a request exceeding it fails
+//! with `422 UNPROCESSABLE_ENTITY`, suggesting the time filters. A list
+//! larger than `rest_list_warn_results` is returned with a `Warning` header.
+
+pub use super::rest_types::{IncludeDeleted, ListAssetsQuery};
+
+use super::conversion::{ListError, Listed};
+use crate::config::Config;
+use crate::rest::structs::AssetsInfo;
+use hyper::StatusCode;
+use std::collections::HashSet;
+use svc_storage_client_grpc::prelude::AdvancedSearchFilter;
+
+/// Get the storage filter of the assets matching the query, only the
+/// available assets unless `include_deleted` is set.
+///
+/// Returns `BAD_REQUEST` if `created_before` is not after `created_after`.
+pub fn list_filter(query: &ListAssetsQuery) -> Result<AdvancedSearchFilter, StatusCode> {
+    if let (Some(after), Some(before)) = (query.created_after, query.created_before) {
+        if before <= after {
+            rest_info!(
+                "created_before {} is not after created_after {}.",
+                before,
+                after
+            );
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let mut filter = match query.include_deleted {
+        IncludeDeleted::False => AdvancedSearchFilter::search_is_null("deleted_at".to_string()),
+        IncludeDeleted::Only => AdvancedSearchFilter::search_is_not_null("deleted_at".to_string()),
+        // all stored objects have a creation time, so this matches both
+        // available and removed assets
+        IncludeDeleted::True => AdvancedSearchFilter::search_is_not_null("created_at".to_string()),
+    };
+    if let Some(after) = query.created_after {
+        filter = filter.and_greater_or_equal("created_at".to_string(), after.to_rfc3339());
+    }
+    if let Some(before) = query.created_before {
+        filter = filter.and_less("created_at".to_string(), before.to_rfc3339());
+    }
+    if let Some(after) = query.updated_after {
+        filter = filter.and_greater_or_equal("updated_at".to_string(), after.to_rfc3339());
+    }
+
+    Ok(filter)
+}
+
+/// Limit a storage search to the assets of the `owner_id` of the query.
+///
+/// `owned` returns the ids of the assets of an owner, which are matched
+/// against `column`. Returns `None` if the owner has no assets, so no asset
+/// can match.
+pub fn owner_filter(
+    query: &ListAssetsQuery,
+    filter: AdvancedSearchFilter,
+    column: &str,
+    owned: impl FnOnce(&str) -> HashSet<String>,
+) -> Option<AdvancedSearchFilter> {
+    let Some(owner_id) = &query.owner_id else {
+        return Some(filter);
+    };
+
+    let ids: Vec<String> = owned(owner_id).into_iter().collect();
+    if ids.is_empty() {
+        return None;
+    }
+
+    Some(filter.and_in(column.to_string(), ids))
+}
+
+/// Check if an asset returned by storage matches the query.
+///
+/// Storage already applies the filters of [`list_filter`] and
+/// [`owner_filter`], this guards against backends which ignore them. The
+/// owner of the asset has to be set before.
+pub fn matches_list_query(query: &ListAssetsQuery, asset: &impl AssetsInfo) -> bool {
+    if query.owner_id.is_some() && asset.basics().owner_id != query.owner_id {
+        return false;
+    }
+
+    let created_at = asset.created_at();
+    query
+        .created_after
+        .map_or(true, |after| created_at >= after)
+        && query
+            .created_before
+            .map_or(true, |before| created_at < before)
+        && query
+            .updated_after
+            .map_or(true, |after| asset.updated_at() >= after)
+}
+
+/// Limits of the number of assets returned by the list endpoints
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ListLimits {
+    /// Maximum number of stored objects listed, `None` for no limit
+    pub max_results: Option<usize>,
+    /// Number of assets above which a warning is returned, `None` to never
+    /// warn
+    pub warn_results: Option<usize>,
+}
+
+impl ListLimits {
+    /// Get the limits of the provided configuration, `0` disabling a limit
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_results: Some(config.rest_list_max_results).filter(|max| *max > 0),
+            warn_results: Some(config.rest_list_warn_results).filter(|warn| *warn > 0),
+        }
+    }
+
+    /// Limit a storage search to one object more than `max_results`, so an
+    /// exceeded limit is detected without reading all matching objects.
+    pub fn limit_filter(&self, mut filter: AdvancedSearchFilter) -> AdvancedSearchFilter {
+        if let Some(max) = self.max_results {
+            filter.page_number = 1;
+            filter.results_per_page = i32::try_from(max + 1).unwrap_or(i32::MAX);
+        }
+        filter
+    }
+
+    /// Check the number of objects returned by storage against
+    /// `max_results`.
+    pub fn check(&self, count: usize) -> Result<(), ListError> {
+        match self.max_results {
+            Some(max) if count > max => {
+                rest_warn!("more than {} objects match the list query, rejected.", max);
+                Err(ListError::TooMany(max))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Flag a list with more than `warn_results` assets.
+    pub fn warn<T>(&self, listed: &mut Listed<T>) {
+        if let Some(threshold) = self
+            .warn_results
+            .filter(|threshold| listed.assets.len() > *threshold)
+        {
+            rest_warn!(
+                "listed {} assets, more than the warning threshold of {}.",
+                listed.assets.len(),
+                threshold
+            );
+            listed.exceeded_warning = Some(threshold);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::api::vertiport::Vertiport;
+    use crate::testing::VertiportDataBuilder;
+    use lib_common::time::Utc;
+    use svc_storage_client_grpc::prelude::vertiport;
+
+    #[test]
+    fn test_list_filter() {
+        let now = Utc::now();
+        assert!(list_filter(&ListAssetsQuery::default()).is_ok());
+        assert!(list_filter(&ListAssetsQuery {
+            created_after: Some(now - chrono::Duration::days(1)),
+            created_before: Some(now),
+            updated_after: Some(now),
+            include_deleted: IncludeDeleted::Only,
+            owner_id: None,
+        })
+        .is_ok());
+        assert_eq!(
+            list_filter(&ListAssetsQuery {
+                created_after: Some(now),
+                created_before: Some(now),
+                updated_after: None,
+                include_deleted: IncludeDeleted::False,
+                owner_id: None,
+            })
+            .unwrap_err(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[test]
+    fn test_list_filter_include_deleted() {
+        let deleted_condition = |include_deleted| {
+            let filter = list_filter(&ListAssetsQuery {
+                include_deleted,
+                ..Default::default()
+            })
+            .unwrap();
+            let condition = &filter.filters[0];
+            (condition.search_field.clone(), condition.predicate_operator)
+        };
+        let (field, available) = deleted_condition(IncludeDeleted::False);
+        assert_eq!(field, "deleted_at");
+        let (field, removed) = deleted_condition(IncludeDeleted::Only);
+        assert_eq!(field, "deleted_at");
+        assert_ne!(available, removed);
+        let (field, _) = deleted_condition(IncludeDeleted::True);
+        assert_eq!(field, "created_at");
+    }
+
+    #[test]
+    fn test_owner_filter() {
+        let filter = list_filter(&ListAssetsQuery::default()).unwrap();
+        let owner_id = lib_common::uuid::Uuid::new_v4().to_string();
+        let owned_by = ListAssetsQuery {
+            owner_id: Some(owner_id.clone()),
+            ..Default::default()
+        };
+
+        // without an owner the filter is unchanged
+        let unchanged = owner_filter(&ListAssetsQuery::default(), filter.clone(), "id", |_| {
+            panic!("no owner to look up")
+        });
+        assert_eq!(unchanged, Some(filter.clone()));
+
+        // an owner without assets matches nothing
+        assert_eq!(
+            owner_filter(&owned_by, filter.clone(), "vehicle_id", |_| HashSet::new()),
+            None
+        );
+
+        let owned = owner_filter(&owned_by, filter.clone(), "vehicle_id", |owner| {
+            assert_eq!(owner, owner_id);
+            HashSet::from(["aircraft".to_string()])
+        })
+        .unwrap();
+        assert_eq!(owned.filters.len(), filter.filters.len() + 1);
+        assert_eq!(owned.filters[1].search_field, "vehicle_id");
+    }
+
+    #[test]
+    fn test_list_limits() {
+        let mut config = Config::default();
+        config.rest_list_max_results = 2;
+        config.rest_list_warn_results = 1;
+        let limits = ListLimits::from_config(&config);
+
+        let filter = limits.limit_filter(list_filter(&ListAssetsQuery::default()).unwrap());
+        assert_eq!(filter.results_per_page, 3);
+        assert!(limits.check(2).is_ok());
+        assert_eq!(limits.check(3), Err(ListError::TooMany(2)));
+
+        let mut listed = Listed {
+            assets: vec![1],
+            warnings: vec![],
+            dropped: vec![],
+            report: false,
+            exceeded_warning: None,
+        };
+        limits.warn(&mut listed);
+        assert_eq!(listed.exceeded_warning, None);
+        listed.assets.push(2);
+        limits.warn(&mut listed);
+        assert_eq!(listed.exceeded_warning, Some(1));
+
+        // disabled
+        config.rest_list_max_results = 0;
+        config.rest_list_warn_results = 0;
+        let limits = ListLimits::from_config(&config);
+        assert_eq!(limits, ListLimits::default());
+        let filter = limits.limit_filter(list_filter(&ListAssetsQuery::default()).unwrap());
+        assert_eq!(filter.results_per_page, 0);
+        assert!(limits.check(usize::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_matches_list_query() {
+        let now = Utc::now();
+        let mut data = VertiportDataBuilder::new().build();
+        data.created_at = Some((now - chrono::Duration::days(2)).into());
+        data.updated_at = Some((now - chrono::Duration::days(1)).into());
+        let vertiport = Vertiport::try_from(vertiport::Object {
+            id: lib_common::uuid::Uuid::new_v4().to_string(),
+            data: Some(data),
+        })
+        .unwrap();
+
+        let day = chrono::Duration::days(1);
+        let query = |created_after, created_before, updated_after| ListAssetsQuery {
+            created_after,
+            created_before,
+            updated_after,
+            include_deleted: IncludeDeleted::False,
+            owner_id: None,
+        };
+        assert!(matches_list_query(&query(None, None, None), &vertiport));
+        assert!(matches_list_query(
+            &query(Some(now - day * 3), Some(now), Some(now - day * 2)),
+            &vertiport
+        ));
+        assert!(!matches_list_query(
+            &query(Some(now - day), None, None),
+            &vertiport
+        ));
+        assert!(!matches_list_query(
+            &query(None, Some(now - day * 3), None),
+            &vertiport
+        ));
+        assert!(!matches_list_query(
+            &query(None, None, Some(now)),
+            &vertiport
+        ));
+
+        // an asset without a known owner matches no owner
+        let owner_id = lib_common::uuid::Uuid::new_v4().to_string();
+        let owned_by = |owner_id: &str| ListAssetsQuery {
+            owner_id: Some(owner_id.to_string()),
+            ..Default::default()
+        };
+        assert!(!matches_list_query(&owned_by(&owner_id), &vertiport));
+        let mut vertiport = vertiport;
+        vertiport.basics.owner_id = Some(owner_id.clone());
+        assert!(matches_list_query(&owned_by(&owner_id), &vertiport));
+        assert!(!matches_list_query(
+            &owned_by(&lib_common::uuid::Uuid::new_v4().to_string()),
+            &vertiport
+        ));
+    }
+}
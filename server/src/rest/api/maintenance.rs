@@ -0,0 +1,235 @@
+//! Handlers for the maintenance calendar of the fleet.
+
+pub use super::rest_types::MaintenanceCalendarQuery;
+
+use super::errors::storage_error_status;
+use crate::calendar::{render, CalendarEvent, CONTENT_TYPE};
+use crate::fan_out::FanOut;
+use crate::hold::MaintenanceHolds;
+use crate::repo::Repositories;
+use crate::rest::quota::{OperatorQuotas, QuotaKind};
+use axum::{
+    extract::Query,
+    http::header,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use hyper::StatusCode;
+use lib_common::time::{DateTime, Utc};
+use lib_common::uuid::to_uuid;
+use svc_storage_client_grpc::prelude::*;
+
+/// Name of the maintenance calendar
+const CALENDAR_NAME: &str = "Aircraft Maintenance";
+
+/// Get the calendar events of an aircraft: its next maintenance, if
+/// upcoming, and its maintenance holds which did not end yet
+fn aircraft_events(
+    object: &vehicle::Object,
+    holds: &MaintenanceHolds,
+    now: DateTime<Utc>,
+) -> Vec<CalendarEvent> {
+    let Some(data) = &object.data else {
+        return vec![];
+    };
+
+    let mut events = vec![];
+    if let Some(next) = data.next_maintenance.clone().map(DateTime::<Utc>::from) {
+        if next >= now {
+            events.push(CalendarEvent {
+                uid: format!("maintenance-{}@svc-assets", object.id),
+                start: next,
+                end: None,
+                summary: format!("Maintenance due: {}", data.registration_number),
+                description: Some(format!("Next maintenance of aircraft {}", object.id)),
+            });
+        }
+    }
+
+    events.extend(
+        holds
+            .list(&object.id, now)
+            .into_iter()
+            .map(|hold| CalendarEvent {
+                uid: format!("hold-{}@svc-assets", hold.id),
+                start: hold.start,
+                end: Some(hold.end),
+                summary: format!("Maintenance hold: {}", data.registration_number),
+                description: hold.reason,
+            }),
+    );
+
+    events
+}
+
+/// Export the upcoming maintenance of the fleet as an iCalendar feed.
+///
+/// The feed contains the `next_maintenance` dates in the future and the
+/// maintenance holds which did not end yet, so maintenance teams can
+/// subscribe to it in their calendar application. With `operator_id` only
+/// the aircraft registered by that operator are exported.
+#[utoipa::path(
+    get,
+    path = "/assets/maintenance/calendar.ics",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Maintenance calendar", content_type = "text/calendar", body = String),
+        (status = 400, description = "Invalid operator id"),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    ),
+    params(
+        MaintenanceCalendarQuery,
+    )
+)]
+pub async fn get_maintenance_calendar(
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+    Extension(holds): Extension<MaintenanceHolds>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Query(query): Query<MaintenanceCalendarQuery>,
+) -> Result<Response, StatusCode> {
+    rest_info!("entry [{:?}].", query.operator_id);
+
+    let registered = query
+        .operator_id
+        .as_deref()
+        .map(|operator_id| {
+            to_uuid(operator_id)
+                .map(|id| quotas.assets(&id.to_string(), QuotaKind::Aircraft))
+                .ok_or_else(|| {
+                    rest_error!("Invalid operator id: {}", operator_id);
+                    StatusCode::BAD_REQUEST
+                })
+        })
+        .transpose()?;
+
+    let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+    let aircraft = fan_out
+        .call(repos.aircraft.search(filter))
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve vehicles: {e}.");
+            storage_error_status(&e)
+        })?;
+
+    let now = Utc::now();
+    let mut events: Vec<CalendarEvent> = aircraft
+        .iter()
+        .filter(|object| {
+            registered
+                .as_ref()
+                .map_or(true, |registered| registered.contains(&object.id))
+        })
+        .flat_map(|object| aircraft_events(object, &holds, now))
+        .collect();
+    events.sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.uid.cmp(&b.uid)));
+
+    rest_info!("exporting {} maintenance events.", events.len());
+    Ok((
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        render(CALENDAR_NAME, &events, now),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SharedConfig;
+    use crate::rest::api::rest_types::MaintenanceHoldPayload;
+    use crate::rest::quota::AssetOwners;
+    use crate::testing::AircraftDataBuilder;
+    use crate::Config;
+    use chrono::Duration;
+    use lib_common::logger::get_log_handle;
+    use lib_common::uuid::Uuid;
+
+    async fn calendar(
+        repos: &Repositories,
+        holds: &MaintenanceHolds,
+        quotas: &OperatorQuotas,
+        operator_id: Option<String>,
+    ) -> Result<String, StatusCode> {
+        let config = SharedConfig::from(Config::default());
+        let response = get_maintenance_calendar(
+            Extension(repos.clone()),
+            Extension(FanOut::new(config)),
+            Extension(holds.clone()),
+            Extension(quotas.clone()),
+            Query(MaintenanceCalendarQuery { operator_id }),
+        )
+        .await?;
+        assert_eq!(response.headers()[header::CONTENT_TYPE], CONTENT_TYPE);
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        Ok(String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_get_maintenance_calendar() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let repos = Repositories::memory();
+        let holds = MaintenanceHolds::default();
+        let quotas = OperatorQuotas::new(Config::default().into(), AssetOwners::default());
+        let operator_id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        // upcoming maintenance of an aircraft registered by the operator
+        let data = AircraftDataBuilder::new()
+            .registration_number("N-UPCOMING")
+            .maintenance(now - Duration::days(30), now + Duration::days(7))
+            .build();
+        let upcoming = repos.aircraft.insert(data).await.unwrap().id;
+        quotas
+            .reserve(&repos, &operator_id, QuotaKind::Aircraft)
+            .await
+            .unwrap()
+            .commit(&upcoming)
+            .unwrap();
+
+        // overdue maintenance with a hold, of an unknown operator
+        let data = AircraftDataBuilder::new()
+            .registration_number("N-HELD")
+            .maintenance(now - Duration::days(30), now - Duration::days(1))
+            .build();
+        let held = repos.aircraft.insert(data).await.unwrap().id;
+        let payload = MaintenanceHoldPayload {
+            start: now + Duration::hours(1),
+            end: now + Duration::hours(5),
+            reason: Some("rotor inspection".to_string()),
+        };
+        let hold = holds.add(&held, payload, now).unwrap();
+
+        let feed = calendar(&repos, &holds, &quotas, None).await.unwrap();
+        assert!(feed.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert_eq!(feed.matches("BEGIN:VEVENT").count(), 2);
+        assert!(feed.contains(&format!("UID:maintenance-{upcoming}@svc-assets\r\n")));
+        assert!(feed.contains(&format!("UID:hold-{}@svc-assets\r\n", hold.id)));
+        assert!(!feed.contains(&format!("UID:maintenance-{held}@svc-assets")));
+        assert!(feed.contains("SUMMARY:Maintenance hold: N-HELD\r\n"));
+        assert!(feed.contains("DESCRIPTION:rotor inspection\r\n"));
+        // events are ordered by start
+        assert!(feed.find("N-HELD").unwrap() < feed.find("N-UPCOMING").unwrap());
+
+        let feed = calendar(&repos, &holds, &quotas, Some(operator_id))
+            .await
+            .unwrap();
+        assert_eq!(feed.matches("BEGIN:VEVENT").count(), 1);
+        assert!(feed.contains("SUMMARY:Maintenance due: N-UPCOMING\r\n"));
+
+        let feed = calendar(&repos, &holds, &quotas, Some(Uuid::new_v4().to_string()))
+            .await
+            .unwrap();
+        assert_eq!(feed.matches("BEGIN:VEVENT").count(), 0);
+
+        let error = calendar(&repos, &holds, &quotas, Some("invalid".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        ut_info!("success");
+    }
+}
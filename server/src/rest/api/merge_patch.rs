@@ -0,0 +1,145 @@
+//! Conversion of JSON Merge Patch (RFC 7396) bodies into update payloads.
+//!
+//! The fields present in the patch are used as the update mask, so clients
+//! don't need to provide the storage field paths themselves. Nested objects
+//! (like `geo_location`) replace the stored value as a whole.
+//! `expected_updated_at` is not patched, it's passed on to the update
+//! payload for the optimistic concurrency check.
+
+use super::validation::{field_error, FieldError};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// Field name used for errors about the patch document itself
+const BODY_FIELD: &str = "body";
+
+/// Field with the `updated_at` the patch is based on
+const EXPECTED_UPDATED_AT_FIELD: &str = "expected_updated_at";
+
+/// Convert a merge patch into an update payload for the provided id.
+///
+/// `fields` lists the fields which can be patched, `nullable` the fields
+/// which can be removed by setting them to `null`.
+pub fn merge_patch_payload<T: DeserializeOwned>(
+    id: &str,
+    patch: Value,
+    fields: &[&str],
+    nullable: &[&str],
+) -> Result<T, Vec<FieldError>> {
+    let Value::Object(mut patch) = patch else {
+        return Err(vec![field_error(BODY_FIELD, "must be a JSON object")]);
+    };
+    let expected_updated_at = patch.remove(EXPECTED_UPDATED_AT_FIELD);
+
+    if patch.is_empty() {
+        return Err(vec![field_error(
+            BODY_FIELD,
+            "must contain at least one field",
+        )]);
+    }
+
+    let mut errors = vec![];
+    for (field, value) in patch.iter() {
+        if !fields.contains(&field.as_str()) {
+            errors.push(field_error(field, "can not be patched"));
+        } else if value.is_null() && !nullable.contains(&field.as_str()) {
+            errors.push(field_error(field, "can not be removed"));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mask: Vec<Value> = patch.keys().cloned().map(Value::String).collect();
+    let mut payload = Map::new();
+    payload.insert("id".to_string(), Value::String(id.to_string()));
+    payload.insert("mask".to_string(), Value::Array(mask));
+    if let Some(expected_updated_at) = expected_updated_at {
+        payload.insert(EXPECTED_UPDATED_AT_FIELD.to_string(), expected_updated_at);
+    }
+    payload.extend(patch);
+
+    serde_json::from_value(Value::Object(payload))
+        .map_err(|e| vec![field_error(BODY_FIELD, &e.to_string())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::api::rest_types::UpdateVertiportPayload;
+    use serde_json::json;
+
+    const FIELDS: &[&str] = &["name", "description", "geo_location", "schedule"];
+    const NULLABLE: &[&str] = &["schedule"];
+
+    #[test]
+    fn test_merge_patch_payload() {
+        let payload: UpdateVertiportPayload = merge_patch_payload(
+            "id",
+            json!({ "name": "Vertiport", "schedule": null }),
+            FIELDS,
+            NULLABLE,
+        )
+        .unwrap();
+        assert_eq!(payload.id, "id");
+        assert_eq!(payload.name, Some("Vertiport".to_string()));
+        assert_eq!(payload.schedule, None);
+        assert!(payload.mask.contains(&"name".to_string()));
+        assert!(payload.mask.contains(&"schedule".to_string()));
+        assert_eq!(payload.mask.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_patch_payload_expected_updated_at() {
+        let payload: UpdateVertiportPayload = merge_patch_payload(
+            "id",
+            json!({ "name": "Vertiport", "expected_updated_at": "2024-01-01T00:00:00Z" }),
+            FIELDS,
+            NULLABLE,
+        )
+        .unwrap();
+        assert!(payload.expected_updated_at.is_some());
+        assert_eq!(payload.mask, vec!["name".to_string()]);
+
+        let errors = merge_patch_payload::<UpdateVertiportPayload>(
+            "id",
+            json!({ "expected_updated_at": "2024-01-01T00:00:00Z" }),
+            FIELDS,
+            NULLABLE,
+        )
+        .unwrap_err();
+        assert_eq!(errors[0].field, BODY_FIELD);
+    }
+
+    #[test]
+    fn test_merge_patch_payload_errors() {
+        let errors =
+            merge_patch_payload::<UpdateVertiportPayload>("id", json!(["name"]), FIELDS, NULLABLE)
+                .unwrap_err();
+        assert_eq!(errors[0].field, BODY_FIELD);
+
+        let errors =
+            merge_patch_payload::<UpdateVertiportPayload>("id", json!({}), FIELDS, NULLABLE)
+                .unwrap_err();
+        assert_eq!(errors[0].field, BODY_FIELD);
+
+        let errors = merge_patch_payload::<UpdateVertiportPayload>(
+            "id",
+            json!({ "id": "other", "mask": [], "name": null }),
+            FIELDS,
+            NULLABLE,
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 3);
+
+        let errors = merge_patch_payload::<UpdateVertiportPayload>(
+            "id",
+            json!({ "name": 1 }),
+            FIELDS,
+            NULLABLE,
+        )
+        .unwrap_err();
+        assert_eq!(errors[0].field, BODY_FIELD);
+    }
+}
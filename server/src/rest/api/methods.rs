@@ -0,0 +1,98 @@
+//! HEAD and OPTIONS support of the asset resources.
+//!
+//! A `HEAD` request on an asset returns the headers of the asset without
+//! fetching the full representation, so monitoring probes can check if an
+//! asset exists. An `OPTIONS` request returns the methods allowed on a
+//! resource in the `Allow` header. CORS preflight requests are answered by
+//! the CORS layer before they reach these handlers.
+
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::routing::{self, MethodRouter};
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// Methods allowed on an aircraft, vertiport or vertipad by id
+pub const ASSET_METHODS: &str = "GET, HEAD, PATCH, DELETE, OPTIONS";
+
+/// Methods allowed on the aircraft, vertiport and vertipad collections
+pub const ASSET_COLLECTION_METHODS: &str = "POST, PUT, OPTIONS";
+
+/// Headers describing the version of an asset
+pub type AssetHeaders = [(header::HeaderName, HeaderValue); 2];
+
+/// Get the `ETag` and `Last-Modified` headers of an asset last updated at
+/// `updated_at`.
+///
+/// The entity tag is the `updated_at` of the asset, which clients can pass
+/// as `expected_updated_at` of an update.
+pub fn asset_headers(updated_at: DateTime<Utc>) -> AssetHeaders {
+    let etag = format!(
+        "\"{}\"",
+        updated_at.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    );
+    let last_modified = updated_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+
+    // both values only contain visible ASCII characters
+    [
+        (
+            header::ETAG,
+            HeaderValue::from_str(&etag).unwrap_or(HeaderValue::from_static("\"\"")),
+        ),
+        (
+            header::LAST_MODIFIED,
+            HeaderValue::from_str(&last_modified)
+                .unwrap_or(HeaderValue::from_static("Thu, 01 Jan 1970 00:00:00 GMT")),
+        ),
+    ]
+}
+
+/// Get a route answering `OPTIONS` requests with the provided allowed
+/// methods.
+pub fn options(methods: &'static str) -> MethodRouter {
+    routing::options(move || async move {
+        (
+            StatusCode::NO_CONTENT,
+            [(header::ALLOW, HeaderValue::from_static(methods))],
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Method, Request};
+    use axum::Router;
+    use chrono::TimeZone;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_asset_headers() {
+        let updated_at = Utc
+            .with_ymd_and_hms(2024, 3, 1, 12, 30, 5)
+            .unwrap()
+            .checked_add_signed(chrono::Duration::milliseconds(250))
+            .unwrap();
+        let [(etag, etag_value), (last_modified, last_modified_value)] = asset_headers(updated_at);
+        assert_eq!(etag, header::ETAG);
+        assert_eq!(etag_value, "\"2024-03-01T12:30:05.250Z\"");
+        assert_eq!(last_modified, header::LAST_MODIFIED);
+        assert_eq!(last_modified_value, "Fri, 01 Mar 2024 12:30:05 GMT");
+    }
+
+    #[tokio::test]
+    async fn test_options() {
+        let app = Router::new().route("/assets/aircraft/:id", options(ASSET_METHODS));
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri("/assets/aircraft/id")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(response.headers()[header::ALLOW], ASSET_METHODS);
+    }
+}
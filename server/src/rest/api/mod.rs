@@ -1,13 +1,176 @@
 //! Handlers for the REST API
+//!
+//! Handlers take their extractors in the same order: the `Extension`s, the
+//! `Path`, the `Query`, the `HeaderMap` and last the body (`Json` or
+//! `BodyStream`), which can only be consumed once. The order is checked by
+//! the tests of this module, the mounting of the documented routes by the
+//! REST integration tests.
 
 /// Types used in REST messages to this server
 pub mod rest_types {
-    include!("../../../../openapi/types.rs");
+    pub use svc_assets_client_rest::timestamp;
+    pub use svc_assets_client_rest::types::*;
 }
 
+pub mod admin;
 pub mod aircraft;
+pub mod archive;
+pub mod asset;
+pub mod attachment;
+pub mod audit;
+pub mod batch;
+pub mod charger;
+pub mod conversion;
+pub mod diff;
+pub mod equipment;
+pub mod errors;
 pub mod group;
 pub mod health;
+pub mod import;
+pub mod json_patch;
+pub mod list;
+pub mod maintenance;
+pub mod merge_patch;
+pub mod methods;
 pub mod operator;
+pub mod permission;
+pub mod quality;
+pub mod query;
+pub mod region;
+pub mod restriction;
+pub mod snapshot;
+pub mod validation;
 pub mod vertipad;
 pub mod vertiport;
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    /// Get the rank of a handler parameter in the extractor order, `None` if
+    /// the parameter is not an extractor
+    fn extractor_rank(param: &str) -> Option<u8> {
+        let (_, ty) = param.split_once(':')?;
+        let name: String = ty
+            .trim()
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        match name.as_str() {
+            "Extension" => Some(0),
+            "Path" => Some(1),
+            "Query" => Some(2),
+            "HeaderMap" => Some(3),
+            "Json" | "BodyStream" => Some(4),
+            _ => None,
+        }
+    }
+
+    /// Split the parameters of a function at the commas outside of
+    /// parentheses and generics
+    fn split_params(params: &str) -> Vec<&str> {
+        let mut depth = 0;
+        let mut start = 0;
+        let mut split = vec![];
+        for (i, c) in params.char_indices() {
+            match c {
+                '(' | '<' | '[' => depth += 1,
+                ')' | '>' | ']' => depth -= 1,
+                ',' if depth == 0 => {
+                    split.push(&params[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        split.push(&params[start..]);
+        split.into_iter().filter(|p| !p.trim().is_empty()).collect()
+    }
+
+    /// Get the name and the parameters of the public async functions of a
+    /// source file
+    fn async_fns(source: &str) -> Vec<(String, Vec<&str>)> {
+        let mut fns = vec![];
+        for (start, _) in source.match_indices("pub async fn ") {
+            let rest = &source[start + "pub async fn ".len()..];
+            let name_end = rest.find(['(', '<']).unwrap_or(rest.len());
+            let name = rest[..name_end].to_string();
+
+            // skip the generics, then find the matching parenthesis
+            let mut depth = 0;
+            let mut open = None;
+            let mut close = None;
+            for (i, c) in rest[name_end..].char_indices() {
+                let i = name_end + i;
+                match c {
+                    '<' | '(' | '[' => {
+                        if c == '(' && depth == 0 && open.is_none() {
+                            open = Some(i);
+                        }
+                        depth += 1;
+                    }
+                    '>' | ')' | ']' => {
+                        depth -= 1;
+                        if depth == 0 && open.is_some() {
+                            close = Some(i);
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            if let (Some(open), Some(close)) = (open, close) {
+                fns.push((name, split_params(&rest[open + 1..close])));
+            }
+        }
+        fns
+    }
+
+    #[test]
+    fn test_split_params() {
+        let params = "Extension(repos): Extension<Repositories>,
+            Path((id, note_id)): Path<(String, String)>,
+            Json(payload): Json<HashMap<String, String>>,";
+        let ranks: Vec<Option<u8>> = split_params(params)
+            .into_iter()
+            .map(extractor_rank)
+            .collect();
+        assert_eq!(ranks, vec![Some(0), Some(1), Some(4)]);
+        assert_eq!(extractor_rank("repos: &Repositories"), None);
+    }
+
+    #[test]
+    fn test_extractor_order() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/rest/api");
+        let mut checked = 0;
+        let mut unordered = vec![];
+        for entry in fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().map_or(true, |ext| ext != "rs") {
+                continue;
+            }
+
+            let source = fs::read_to_string(&path).unwrap();
+            for (name, params) in async_fns(&source) {
+                let ranks: Vec<u8> = params.into_iter().filter_map(extractor_rank).collect();
+                if ranks.is_empty() {
+                    continue;
+                }
+
+                checked += 1;
+                if ranks.windows(2).any(|pair| pair[0] > pair[1]) {
+                    unordered.push(format!("{}: {}", path.display(), name));
+                }
+            }
+        }
+
+        assert!(checked > 0);
+        assert!(
+            unordered.is_empty(),
+            "handlers not taking their extractors as (Extension, Path, Query, HeaderMap, body): {:#?}",
+            unordered
+        );
+    }
+}
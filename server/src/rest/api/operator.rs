@@ -3,10 +3,31 @@
 /// Types Used in REST Messages
 pub use super::rest_types::*;
 
-use axum::{extract::Path, Extension, Json};
+use super::group::group_asset_ids;
+use super::validation::{field_error, unprocessable, ValidationError};
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, Query},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use futures::future::try_join_all;
+use futures::StreamExt;
 use hyper::StatusCode;
 
+use crate::delegation::GroupDelegations;
 use crate::grpc::client::GrpcClients;
+use crate::object_store::ObjectStoreError;
+use crate::repo::Repositories;
+use crate::rest::contacts::{
+    validate_contact, validate_preferences, ContactError, OperatorDirectory,
+    MAX_CONTACTS_PER_OPERATOR,
+};
+use crate::rest::json::Json;
+use crate::rest::logos::{
+    logo_etag, LogoError, OperatorLogos, MAX_LOGO_BYTES, VARIANT_CONTENT_TYPE,
+};
+use crate::rest::quota::OperatorQuotas;
 use lib_common::time::{DateTime, Utc};
 use lib_common::uuid::{to_uuid, Uuid};
 use serde::{Deserialize, Serialize};
@@ -50,13 +71,16 @@ pub struct Operator {
     /// The description of the operator.
     pub description: String,
 
-    /// The logo of the operator.
+    /// URL of the logo of the operator, see
+    /// `PUT /assets/operators/{id}/logo`.
     pub logo: String,
 
     /// The created_at timestamp.
+    #[serde(deserialize_with = "timestamp::deserialize")]
     pub created_at: DateTime<Utc>,
 
     /// The updated_at timestamp.
+    #[serde(deserialize_with = "timestamp::deserialize")]
     pub updated_at: DateTime<Utc>,
 }
 
@@ -88,6 +112,443 @@ pub async fn get_operator(
     Err(StatusCode::NOT_IMPLEMENTED)
 }
 
+/// Get the asset quota usage and limits of an operator.
+///
+/// The usage counts the assets registered by the operator through this
+/// service which still exist in svc-storage.
+#[utoipa::path(
+    get,
+    path = "/assets/operators/{id}/quota",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Quota usage of operator {id}", body = OperatorQuota),
+        (status = 400, description = "Invalid operator id"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Operator id"),
+    )
+)]
+pub async fn get_operator_quota(
+    Extension(repos): Extension<Repositories>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Path(operator_id): Path<String>,
+) -> Result<Json<OperatorQuota>, StatusCode> {
+    rest_info!("{}", operator_id);
+    let id = to_uuid(&operator_id).ok_or_else(|| {
+        rest_error!("Invalid operator id.");
+        StatusCode::BAD_REQUEST
+    })?;
+
+    Ok(Json(quotas.get(&repos, &id.to_string()).await?))
+}
+
+/// Parse an operator id from the request path
+fn operator_id(id: &str) -> Result<String, StatusCode> {
+    to_uuid(id).map(|id| id.to_string()).ok_or_else(|| {
+        rest_error!("Invalid operator id: {}", id);
+        StatusCode::BAD_REQUEST
+    })
+}
+
+/// Get the contacts of an operator.
+#[utoipa::path(
+    get,
+    path = "/assets/operators/{id}/contacts",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Contacts of operator {id}", body = [OperatorContact]),
+        (status = 400, description = "Invalid operator id"),
+    ),
+    params(
+        ("id" = String, Path, description = "Operator id"),
+    )
+)]
+pub async fn get_operator_contacts(
+    Extension(directory): Extension<OperatorDirectory>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<OperatorContact>>, StatusCode> {
+    rest_info!("{}", id);
+    let id = operator_id(&id)?;
+
+    Ok(Json(directory.contacts(&id)))
+}
+
+/// Add a contact to an operator.
+///
+/// The contact is persisted by this instance, see
+/// [`crate::rest::contacts`].
+#[utoipa::path(
+    post,
+    path = "/assets/operators/{id}/contacts",
+    tag = "svc-assets",
+    request_body = OperatorContactPayload,
+    responses(
+        (status = 200, description = "Contact added; the new contact is returned", body = OperatorContact),
+        (status = 400, description = "Invalid operator id"),
+        (status = 422, description = "Invalid contact or too many contacts", body = [FieldError]),
+        (status = 500, description = "The contact could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Operator id"),
+    )
+)]
+pub async fn add_operator_contact(
+    Extension(directory): Extension<OperatorDirectory>,
+    Path(id): Path<String>,
+    Json(payload): Json<OperatorContactPayload>,
+) -> Result<Json<OperatorContact>, ValidationError> {
+    rest_info!("{}", id);
+    let id = operator_id(&id).map_err(|status| (status, Json(vec![])))?;
+    let payload = validate_contact(payload).map_err(unprocessable)?;
+
+    let contact = directory.add_contact(&id, payload).map_err(|e| match e {
+        ContactError::State(e) => {
+            rest_error!("could not persist contact: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+        }
+        _ => {
+            rest_error!("operator {} reached the maximum number of contacts.", id);
+            unprocessable(vec![field_error(
+                "contacts",
+                &format!("can not contain more than {MAX_CONTACTS_PER_OPERATOR} contacts"),
+            )])
+        }
+    })?;
+
+    rest_info!("added contact [{}].", contact.id);
+    Ok(Json(contact))
+}
+
+/// Replace a contact of an operator.
+#[utoipa::path(
+    put,
+    path = "/assets/operators/{id}/contacts/{contact_id}",
+    tag = "svc-assets",
+    request_body = OperatorContactPayload,
+    responses(
+        (status = 200, description = "Contact updated; the updated contact is returned", body = OperatorContact),
+        (status = 400, description = "Invalid operator id"),
+        (status = 404, description = "Contact not found"),
+        (status = 422, description = "Invalid contact", body = [FieldError]),
+        (status = 500, description = "The contact could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Operator id"),
+        ("contact_id" = String, Path, description = "Contact id"),
+    )
+)]
+pub async fn update_operator_contact(
+    Extension(directory): Extension<OperatorDirectory>,
+    Path((id, contact_id)): Path<(String, String)>,
+    Json(payload): Json<OperatorContactPayload>,
+) -> Result<Json<OperatorContact>, ValidationError> {
+    rest_info!("{} [{}]", id, contact_id);
+    let id = operator_id(&id).map_err(|status| (status, Json(vec![])))?;
+    let payload = validate_contact(payload).map_err(unprocessable)?;
+
+    let contact = directory
+        .update_contact(&id, &contact_id, payload)
+        .map_err(|e| (contact_error_status(&id, &contact_id, e), Json(vec![])))?;
+
+    Ok(Json(contact))
+}
+
+/// Remove a contact of an operator.
+#[utoipa::path(
+    delete,
+    path = "/assets/operators/{id}/contacts/{contact_id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Contact removed"),
+        (status = 400, description = "Invalid operator id"),
+        (status = 404, description = "Contact not found"),
+        (status = 500, description = "The contact could not be removed"),
+    ),
+    params(
+        ("id" = String, Path, description = "Operator id"),
+        ("contact_id" = String, Path, description = "Contact id"),
+    )
+)]
+pub async fn remove_operator_contact(
+    Extension(directory): Extension<OperatorDirectory>,
+    Path((id, contact_id)): Path<(String, String)>,
+) -> Result<(), StatusCode> {
+    rest_info!("{} [{}]", id, contact_id);
+    let id = operator_id(&id)?;
+
+    directory
+        .remove_contact(&id, &contact_id)
+        .map_err(|e| contact_error_status(&id, &contact_id, e))
+}
+
+/// Map an error of an existing contact change to its status code
+fn contact_error_status(operator_id: &str, contact_id: &str, e: ContactError) -> StatusCode {
+    match e {
+        ContactError::State(e) => {
+            rest_error!("could not persist contact {}: {e}", contact_id);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        _ => {
+            rest_error!(
+                "contact {} of operator {} not found.",
+                contact_id,
+                operator_id
+            );
+            StatusCode::NOT_FOUND
+        }
+    }
+}
+
+/// Get the notification preferences of an operator.
+#[utoipa::path(
+    get,
+    path = "/assets/operators/{id}/notifications",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Notification preferences of operator {id}", body = [NotificationPreference]),
+        (status = 400, description = "Invalid operator id"),
+    ),
+    params(
+        ("id" = String, Path, description = "Operator id"),
+    )
+)]
+pub async fn get_operator_notifications(
+    Extension(directory): Extension<OperatorDirectory>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<NotificationPreference>>, StatusCode> {
+    rest_info!("{}", id);
+    let id = operator_id(&id)?;
+
+    Ok(Json(directory.preferences(&id)))
+}
+
+/// Replace the notification preferences of an operator.
+///
+/// Each preference sends the notifications of an event type to a webhook
+/// or an email address. An empty list disables all notifications.
+#[utoipa::path(
+    put,
+    path = "/assets/operators/{id}/notifications",
+    tag = "svc-assets",
+    request_body = [NotificationPreference],
+    responses(
+        (status = 200, description = "Notification preferences updated"),
+        (status = 400, description = "Invalid operator id"),
+        (status = 422, description = "Invalid notification preferences", body = [FieldError]),
+        (status = 500, description = "The notification preferences could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Operator id"),
+    )
+)]
+pub async fn put_operator_notifications(
+    Extension(directory): Extension<OperatorDirectory>,
+    Path(id): Path<String>,
+    Json(payload): Json<Vec<NotificationPreference>>,
+) -> Result<(), ValidationError> {
+    rest_info!("{}", id);
+    let id = operator_id(&id).map_err(|status| (status, Json(vec![])))?;
+    let preferences = validate_preferences(payload).map_err(unprocessable)?;
+
+    directory.set_preferences(&id, preferences).map_err(|e| {
+        rest_error!("could not persist notification preferences: {e}");
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+    })?;
+    rest_info!("successfully updated notification preferences.");
+    Ok(())
+}
+
+/// Name of the multipart field containing the logo
+const LOGO_FIELD: &str = "logo";
+
+/// Map a [`LogoError`] to a status code
+fn logo_error_status(error: &LogoError) -> StatusCode {
+    match error {
+        LogoError::NotFound | LogoError::Store(ObjectStoreError::NotFound) => StatusCode::NOT_FOUND,
+        LogoError::UnsupportedType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        LogoError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        LogoError::Store(ObjectStoreError::Unavailable(_)) => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+/// Upload the logo of an operator, replacing the current logo.
+///
+/// The logo is the `logo` field of a `multipart/form-data` body: a PNG or
+/// JPEG image of at most 1 MiB and 4096x4096 pixels. Resized variants are
+/// created for the UIs, see [`crate::rest::logos`].
+#[utoipa::path(
+    put,
+    path = "/assets/operators/{id}/logo",
+    tag = "svc-assets",
+    request_body(content = Vec<u8>, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Logo stored; the logo is returned", body = OperatorLogo),
+        (status = 400, description = "Invalid operator id or multipart body"),
+        (status = 413, description = "Logo too large", body = [FieldError]),
+        (status = 415, description = "Logo is not a PNG or JPEG image", body = [FieldError]),
+        (status = 422, description = "Missing logo or invalid image", body = [FieldError]),
+        (status = 503, description = "Could not connect to the object store"),
+    ),
+    params(
+        ("id" = String, Path, description = "Operator id"),
+    )
+)]
+pub async fn put_operator_logo(
+    Extension(logos): Extension<OperatorLogos>,
+    Path(id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<Json<OperatorLogo>, ValidationError> {
+    rest_info!("{}", id);
+    let id = operator_id(&id).map_err(|status| (status, Json(vec![])))?;
+
+    let bad_request = |e: MultipartError| {
+        rest_error!("could not read multipart body: {e}.");
+        (StatusCode::BAD_REQUEST, Json(vec![]))
+    };
+    let mut upload = None;
+    while let Some(mut field) = multipart.next_field().await.map_err(bad_request)? {
+        if field.name() != Some(LOGO_FIELD) {
+            continue;
+        }
+
+        let content_type = field
+            .content_type()
+            .map(|mime| mime.to_string())
+            .unwrap_or_default();
+        let mut content: Vec<u8> = vec![];
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(bad_request)?;
+            if content.len() + chunk.len() > MAX_LOGO_BYTES {
+                rest_warn!("logo exceeds {} bytes.", MAX_LOGO_BYTES);
+                return Err((
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    Json(vec![field_error(
+                        LOGO_FIELD,
+                        &format!("file exceeds the limit of {MAX_LOGO_BYTES} bytes"),
+                    )]),
+                ));
+            }
+            content.extend_from_slice(&chunk);
+        }
+        upload = Some((content_type, content));
+        break;
+    }
+
+    let Some((content_type, content)) = upload else {
+        return Err(unprocessable(vec![field_error(LOGO_FIELD, "is required")]));
+    };
+    let logo = logos.put(&id, &content_type, content).await.map_err(|e| {
+        rest_error!("could not store logo of operator {}: {:?}", id, e);
+        match e {
+            LogoError::UnsupportedType => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                Json(vec![field_error(LOGO_FIELD, "must be a PNG or JPEG image")]),
+            ),
+            LogoError::Invalid(message) => unprocessable(vec![field_error(LOGO_FIELD, &message)]),
+            e => (logo_error_status(&e), Json(vec![])),
+        }
+    })?;
+
+    rest_info!("stored logo [{}].", logo.sha256);
+    Ok(Json(logo))
+}
+
+/// Download the logo of an operator.
+///
+/// The `original` size is the uploaded file, the `medium` and `small` sizes
+/// are PNG images. Responses carry an `ETag`, a request with a matching
+/// `If-None-Match` header is answered with `304 NOT MODIFIED`.
+#[utoipa::path(
+    get,
+    path = "/assets/operators/{id}/logo",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Content of the logo", content_type = "image/png", body = Vec<u8>),
+        (status = 304, description = "Logo not modified"),
+        (status = 400, description = "Invalid operator id"),
+        (status = 404, description = "Operator has no logo"),
+        (status = 503, description = "Could not connect to the object store"),
+    ),
+    params(
+        ("id" = String, Path, description = "Operator id"),
+        LogoQuery,
+    )
+)]
+pub async fn get_operator_logo(
+    Extension(logos): Extension<OperatorLogos>,
+    Path(id): Path<String>,
+    Query(query): Query<LogoQuery>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    rest_info!("{} [{:?}]", id, query.size);
+    let id = operator_id(&id)?;
+
+    let logo = logos.get(&id).map_err(|e| {
+        rest_error!("could not get logo of operator {}: {:?}", id, e);
+        logo_error_status(&e)
+    })?;
+    let etag = logo_etag(&logo, query.size);
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| {
+            value
+                .split(',')
+                .any(|tag| tag.trim() == etag || tag.trim() == "*")
+        });
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    let (logo, content) = logos.download(&id, query.size).await.map_err(|e| {
+        rest_error!("could not get logo of operator {}: {:?}", id, e);
+        logo_error_status(&e)
+    })?;
+    let content_type = match query.size {
+        LogoSize::Original => logo.content_type.clone(),
+        _ => VARIANT_CONTENT_TYPE.to_string(),
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::ETAG, logo_etag(&logo, query.size)),
+            (header::CACHE_CONTROL, "no-cache".to_string()),
+        ],
+        content,
+    )
+        .into_response())
+}
+
+/// Remove the logo of an operator together with its files.
+#[utoipa::path(
+    delete,
+    path = "/assets/operators/{id}/logo",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Logo removed"),
+        (status = 400, description = "Invalid operator id"),
+        (status = 404, description = "Operator has no logo"),
+        (status = 503, description = "Could not connect to the object store"),
+    ),
+    params(
+        ("id" = String, Path, description = "Operator id"),
+    )
+)]
+pub async fn remove_operator_logo(
+    Extension(logos): Extension<OperatorLogos>,
+    Path(id): Path<String>,
+) -> Result<(), StatusCode> {
+    rest_info!("{}", id);
+    let id = operator_id(&id)?;
+
+    logos.remove(&id).await.map_err(|e| {
+        rest_error!("could not remove logo of operator {}: {:?}", id, e);
+        logo_error_status(&e)
+    })
+}
+
 //-----------------------------------------------------------
 // Get assets by operator
 //-----------------------------------------------------------
@@ -198,88 +659,107 @@ pub async fn get_all_grouped_assets(
     // Ok(Json(vec![]))
 }
 
+/// Get the assets of delegated groups, fetching the groups concurrently
+async fn delegated_asset_groups(
+    repos: &Repositories,
+    delegations: Vec<Delegation>,
+) -> Result<Vec<DelegatedAssetGroup>, StatusCode> {
+    let asset_ids = try_join_all(
+        delegations
+            .iter()
+            .map(|delegation| group_asset_ids(repos, &delegation.group_id)),
+    )
+    .await?;
+
+    Ok(delegations
+        .into_iter()
+        .zip(asset_ids)
+        .map(|(delegation, asset_ids)| DelegatedAssetGroup {
+            delegation,
+            asset_ids,
+        })
+        .collect())
+}
+
 /// Get all grouped assets delegated to an operator.
+///
+/// Returns the groups with an active delegation to the operator, with the
+/// delegation period and the ids of their assets.
 #[utoipa::path(
     get,
     path = "/assets/operators/{id}/grouped/delegated-to",
     tag = "svc-assets",
     responses(
-        (status = 200, description = "Grouped assets delegated to operator {id} found from database", body = [String]),
-        (status = 404, description = "Operator not found in database"),
+        (status = 200, description = "Asset groups delegated to operator {id}", body = [DelegatedAssetGroup]),
         (status = 400, description = "Invalid operator id"),
-        (status = 503, description = "Could not connect to other microservice dependencies")
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
     ),
     params(
         ("id" = String, Path, description = "Operator id"),
     )
 )]
 pub async fn get_all_grouped_assets_delegated_to(
-    Extension(mut _grpc_clients): Extension<GrpcClients>,
-    Path(operator_id): Path<String>,
-) -> Result<Json<Vec<Uuid>>, StatusCode> {
-    rest_info!("{}", operator_id);
-    let _id = to_uuid(&operator_id).ok_or_else(|| {
-        rest_error!("Invalid operator id.");
-        StatusCode::BAD_REQUEST
-    })?;
-
-    Err(StatusCode::NOT_IMPLEMENTED)
+    Extension(repos): Extension<Repositories>,
+    Extension(delegations): Extension<GroupDelegations>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<DelegatedAssetGroup>>, StatusCode> {
+    rest_info!("{}", id);
+    let id = operator_id(&id)?;
 
-    // Get Client
-    // let _client_option = grpc_clients.storage.get_client().await;
-    // if client_option.is_none() {
-    //     let error_msg = "svc-storage unavailable.".to_string();
-    //     rest_error!("{}", &error_msg);
-    //     return Err((StatusCode::SERVICE_UNAVAILABLE, error_msg));
-    // }
-    // let mut client = client_option.unwrap();
-    // TODO(R5)
-    // Ok(Json(vec![]))
+    let delegations = delegations.delegated_to(&id, Utc::now());
+    rest_debug!(
+        "{} asset groups delegated to operator {}.",
+        delegations.len(),
+        id
+    );
+    Ok(Json(delegated_asset_groups(&repos, delegations).await?))
 }
 
 /// Get all grouped assets delegated from an operator.
+///
+/// Returns the groups the operator delegated to other operators, with the
+/// delegation period and the ids of their assets. svc-storage does not link
+/// groups to operators yet, so a delegation is attributed to the operator in
+/// the `x-operator-id` header of the delegation request; delegations made
+/// without this header are not included.
 #[utoipa::path(
     get,
     path = "/assets/operators/{id}/grouped/delegated-from",
     tag = "svc-assets",
     responses(
-        (status = 200, description = "Grouped assets delegated from operator {id} found from database", body = [String]),
-        (status = 404, description = "Operator not found in database"),
+        (status = 200, description = "Asset groups delegated from operator {id}", body = [DelegatedAssetGroup]),
         (status = 400, description = "Invalid operator id"),
-        (status = 503, description = "Could not connect to other microservice dependencies")
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
     ),
     params(
         ("id" = String, Path, description = "Operator id"),
     )
 )]
 pub async fn get_all_grouped_assets_delegated_from(
-    Extension(mut _grpc_clients): Extension<GrpcClients>,
-    Path(operator_id): Path<String>,
-) -> Result<Json<Vec<Uuid>>, StatusCode> {
-    rest_info!("{}", operator_id);
+    Extension(repos): Extension<Repositories>,
+    Extension(delegations): Extension<GroupDelegations>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<DelegatedAssetGroup>>, StatusCode> {
+    rest_info!("{}", id);
+    let id = operator_id(&id)?;
 
-    let _id = to_uuid(&operator_id).ok_or_else(|| {
-        rest_error!("Invalid operator id.");
-        StatusCode::BAD_REQUEST
-    })?;
-
-    Err(StatusCode::NOT_IMPLEMENTED)
-
-    // Get Client
-    // let _client_option = grpc_clients.storage.get_client().await;
-    // if client_option.is_none() {
-    //     let error_msg = "svc-storage unavailable.".to_string();
-    //     rest_error!("{}", &error_msg);
-    //     return Err((StatusCode::SERVICE_UNAVAILABLE, error_msg));
-    // }
-    // let mut client = client_option.unwrap();
-    // TODO(R5)
-    // Ok(Json(vec![]))
+    let delegations = delegations.delegated_from(&id, Utc::now());
+    rest_debug!(
+        "{} asset groups delegated from operator {}.",
+        delegations.len(),
+        id
+    );
+    Ok(Json(delegated_asset_groups(&repos, delegations).await?))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rest::quota::AssetOwners;
+    use crate::testing::AircraftDataBuilder;
+    use axum::extract::{FromRequest, RequestParts};
     use lib_common::uuid::Uuid;
 
     #[tokio::test]
@@ -302,6 +782,39 @@ mod tests {
         assert_eq!(result, StatusCode::NOT_IMPLEMENTED);
     }
 
+    #[tokio::test]
+    async fn test_get_operator_quota() {
+        let mut config = crate::config::Config::default();
+        config.max_vertiports_per_operator = 3;
+        let quotas = OperatorQuotas::new(config.into(), AssetOwners::default());
+        let repos = Repositories::memory();
+
+        // invalid id
+        let id = "invalid".to_string();
+        let result = get_operator_quota(
+            Extension(repos.clone()),
+            Extension(quotas.clone()),
+            Path(id),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(result, StatusCode::BAD_REQUEST);
+
+        // valid id
+        let id = Uuid::new_v4().to_string();
+        let result = get_operator_quota(
+            Extension(repos),
+            Extension(quotas.clone()),
+            Path(id.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.operator_id, id);
+        assert_eq!(result.aircraft.used, 0);
+        assert_eq!(result.aircraft.limit, None);
+        assert_eq!(result.vertiports.limit, Some(3));
+    }
+
     #[tokio::test]
     async fn test_get_all_assets_by_operator() {
         let config = crate::config::Config::default();
@@ -343,44 +856,312 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_get_all_grouped_assets_delegated_to() {
-        let config = crate::config::Config::default();
-        let grpc_clients = GrpcClients::default(config);
+    async fn test_get_all_grouped_assets_delegated() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
 
-        // invalid id
-        let id = "invalid".to_string();
-        let result = get_all_grouped_assets_delegated_to(Extension(grpc_clients.clone()), Path(id))
+        let repos = Repositories::memory();
+        let delegations = GroupDelegations::default();
+        let owner = Uuid::new_v4().to_string();
+        let partner = Uuid::new_v4().to_string();
+        let group_id = Uuid::new_v4().to_string();
+        let aircraft_id = repos
+            .aircraft
+            .insert(
+                AircraftDataBuilder::new()
+                    .asset_group_id(group_id.clone())
+                    .build(),
+            )
             .await
-            .unwrap_err();
-        assert_eq!(result, StatusCode::BAD_REQUEST);
+            .unwrap()
+            .id;
+        let delegation = delegations
+            .delegate(
+                &group_id,
+                DelegationPayload {
+                    delegatee: partner.clone(),
+                    expires_at: Some(Utc::now() + chrono::Duration::hours(1)),
+                },
+                Some(owner.clone()),
+                Utc::now(),
+            )
+            .unwrap();
 
-        // valid id
+        let status = get_all_grouped_assets_delegated_to(
+            Extension(repos.clone()),
+            Extension(delegations.clone()),
+            Path("invalid".to_string()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let expected = vec![DelegatedAssetGroup {
+            delegation,
+            asset_ids: vec![aircraft_id],
+        }];
+        let Json(groups) = get_all_grouped_assets_delegated_to(
+            Extension(repos.clone()),
+            Extension(delegations.clone()),
+            Path(partner.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(groups, expected);
+
+        let Json(groups) = get_all_grouped_assets_delegated_from(
+            Extension(repos.clone()),
+            Extension(delegations.clone()),
+            Path(owner.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(groups, expected);
+
+        // the delegatee did not delegate any groups
+        let Json(groups) = get_all_grouped_assets_delegated_from(
+            Extension(repos.clone()),
+            Extension(delegations.clone()),
+            Path(partner.clone()),
+        )
+        .await
+        .unwrap();
+        assert!(groups.is_empty());
+
+        delegations.revoke(&group_id, None, Utc::now()).unwrap();
+        let Json(groups) = get_all_grouped_assets_delegated_to(
+            Extension(repos),
+            Extension(delegations),
+            Path(partner),
+        )
+        .await
+        .unwrap();
+        assert!(groups.is_empty());
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_operator_logo() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let logos = OperatorLogos::default();
         let id = Uuid::new_v4().to_string();
-        let result = get_all_grouped_assets_delegated_to(Extension(grpc_clients.clone()), Path(id))
+        let content = png(400, 200);
+
+        let (status, _) = put_operator_logo(
+            Extension(logos.clone()),
+            Path(id.clone()),
+            multipart("logo", "image/gif", b"GIF89a").await,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let (status, Json(errors)) = put_operator_logo(
+            Extension(logos.clone()),
+            Path(id.clone()),
+            multipart("file", "image/png", &content).await,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "logo");
+
+        let (status, _) = put_operator_logo(
+            Extension(logos.clone()),
+            Path(id.clone()),
+            multipart("logo", "image/png", &vec![0; MAX_LOGO_BYTES + 1]).await,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+        let Json(logo) = put_operator_logo(
+            Extension(logos.clone()),
+            Path(id.clone()),
+            multipart("logo", "image/png", &content).await,
+        )
+        .await
+        .unwrap();
+        assert_eq!((logo.width, logo.height), (400, 200));
+        assert_eq!(logo.url, format!("/assets/operators/{id}/logo"));
+
+        let response = get_operator_logo(
+            Extension(logos.clone()),
+            Path(id.clone()),
+            Query(LogoQuery {
+                size: LogoSize::Small,
+            }),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+        let etag = response.headers()[header::ETAG].clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag);
+        let response = get_operator_logo(
+            Extension(logos.clone()),
+            Path(id.clone()),
+            Query(LogoQuery {
+                size: LogoSize::Small,
+            }),
+            headers.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        // the ETag differs per size
+        let response = get_operator_logo(
+            Extension(logos.clone()),
+            Path(id.clone()),
+            Query(LogoQuery::default()),
+            headers,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        remove_operator_logo(Extension(logos.clone()), Path(id.clone()))
+            .await
+            .unwrap();
+        let status = get_operator_logo(
+            Extension(logos.clone()),
+            Path(id.clone()),
+            Query(LogoQuery::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let status = remove_operator_logo(Extension(logos), Path("invalid".to_string()))
             .await
             .unwrap_err();
-        assert_eq!(result, StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        ut_info!("success");
     }
 
     #[tokio::test]
-    async fn test_get_all_grouped_assets_delegated_from() {
-        let config = crate::config::Config::default();
-        let grpc_clients = GrpcClients::default(config);
+    async fn test_operator_contacts() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
 
-        // invalid id
-        let id = "invalid".to_string();
-        let result =
-            get_all_grouped_assets_delegated_from(Extension(grpc_clients.clone()), Path(id))
+        let directory = Extension(OperatorDirectory::default());
+        let id = Uuid::new_v4().to_string();
+        let payload = OperatorContactPayload {
+            name: None,
+            role: "maintenance".to_string(),
+            email: Some("maintenance@example.com".to_string()),
+            phone: None,
+        };
+
+        let (status, _) = add_operator_contact(
+            directory.clone(),
+            Path("invalid".to_string()),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, Json(errors)) = add_operator_contact(
+            directory.clone(),
+            Path(id.clone()),
+            Json(OperatorContactPayload {
+                email: Some("invalid".to_string()),
+                ..payload.clone()
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "email");
+
+        let Json(contact) =
+            add_operator_contact(directory.clone(), Path(id.clone()), Json(payload.clone()))
                 .await
-                .unwrap_err();
-        assert_eq!(result, StatusCode::BAD_REQUEST);
+                .unwrap();
 
-        // valid id
-        let id = Uuid::new_v4().to_string();
-        let result =
-            get_all_grouped_assets_delegated_from(Extension(grpc_clients.clone()), Path(id))
+        let Json(updated) = update_operator_contact(
+            directory.clone(),
+            Path((id.clone(), contact.id.clone())),
+            Json(OperatorContactPayload {
+                phone: Some("+31 20 123 4567".to_string()),
+                ..payload.clone()
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.id, contact.id);
+
+        let Json(contacts) = get_operator_contacts(directory.clone(), Path(id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(contacts, vec![updated]);
+
+        remove_operator_contact(directory.clone(), Path((id.clone(), contact.id.clone())))
+            .await
+            .unwrap();
+        let error =
+            remove_operator_contact(directory.clone(), Path((id.clone(), contact.id.clone())))
                 .await
                 .unwrap_err();
-        assert_eq!(result, StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        let (status, _) = update_operator_contact(directory, Path((id, contact.id)), Json(payload))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_operator_notifications() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let directory = Extension(OperatorDirectory::default());
+        let id = Uuid::new_v4().to_string();
+        let preference = NotificationPreference {
+            event: NotificationEvent::MaintenanceDue,
+            channel: NotificationChannel::Webhook,
+            target: "https://example.com/maintenance".to_string(),
+        };
+
+        let (status, Json(errors)) = put_operator_notifications(
+            directory.clone(),
+            Path(id.clone()),
+            Json(vec![NotificationPreference {
+                target: "example.com".to_string(),
+                ..preference.clone()
+            }]),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "[0].target");
+
+        put_operator_notifications(
+            directory.clone(),
+            Path(id.clone()),
+            Json(vec![preference.clone()]),
+        )
+        .await
+        .unwrap();
+
+        let Json(preferences) = get_operator_notifications(directory, Path(id))
+            .await
+            .unwrap();
+        assert_eq!(preferences, vec![preference]);
+
+        ut_info!("success");
     }
 }
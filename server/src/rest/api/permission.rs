@@ -0,0 +1,633 @@
+//! Handlers for the permissions of an operator on an asset.
+//!
+//! The permissions are derived from the same rules the service enforces, so
+//! user interfaces can enable or disable their actions without duplicating
+//! the authorization logic:
+//! - the scopes of the API token, see [`crate::rest::auth`]
+//! - the read-only mode, see [`crate::rest::read_only`]
+//! - the ownership of the asset, through the `x-operator-id` header of its
+//!   registration (see [`OperatorQuotas`]), and the delegation of asset
+//!   groups (see [`GroupDelegations`])
+//! - the whitelist of the asset
+
+pub use super::rest_types::{AssetPermissions, AssetRelation, PermissionAssetType};
+
+use super::aircraft::Aircraft;
+use super::errors::storage_error_status;
+use super::group::group_owners;
+use super::vertipad::Vertipad;
+use super::vertiport::Vertiport;
+use crate::delegation::GroupDelegations;
+use crate::features::{Feature, Features};
+use crate::repo::Repositories;
+use crate::rest::auth::{Scope, TokenScopes};
+use crate::rest::json::Json;
+use crate::rest::quota::{operator_id_from_headers, OperatorQuotas, QuotaKind};
+use crate::rest::read_only::ReadOnlyMode;
+use axum::{extract::Path, http::HeaderMap, Extension};
+use chrono::Utc;
+use hyper::StatusCode;
+use lib_common::uuid::to_uuid;
+
+/// Access of the request, independent of the asset
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Access {
+    /// Scopes of the API token, `None` if no tokens are configured
+    scopes: Option<Vec<Scope>>,
+    /// The service is in read-only mode
+    read_only: bool,
+    /// Asset group delegations are enabled
+    groups_v2: bool,
+}
+
+impl Access {
+    fn new(
+        read_only: &ReadOnlyMode,
+        features: &Features,
+        scopes: Option<Extension<TokenScopes>>,
+    ) -> Self {
+        Self {
+            scopes: scopes.map(|Extension(TokenScopes(scopes))| scopes),
+            read_only: read_only.is_enabled(),
+            groups_v2: features.is_enabled(Feature::GroupsV2),
+        }
+    }
+
+    /// Check if the request has a scope, any scope is granted if no tokens
+    /// are configured
+    fn has(&self, scope: Scope) -> bool {
+        self.scopes
+            .as_ref()
+            .map_or(true, |scopes| scopes.contains(&scope))
+    }
+
+    /// Check if the request may change assets of a type
+    fn can_write(&self, asset_type: PermissionAssetType) -> bool {
+        let scope = match asset_type {
+            PermissionAssetType::Group => Scope::GroupsAdmin,
+            _ => Scope::AssetsWrite,
+        };
+        !self.read_only && self.has(scope)
+    }
+}
+
+/// Get the relation of an operator to an asset.
+///
+/// `owners` are the known owners of the asset, `delegatee` the operator the
+/// asset (or its asset group) is currently delegated to.
+fn relation(
+    operator_id: Option<&str>,
+    owners: &[String],
+    delegatee: Option<&str>,
+) -> AssetRelation {
+    let Some(operator_id) = operator_id else {
+        return AssetRelation::Unknown;
+    };
+
+    if owners.iter().any(|owner| owner == operator_id) {
+        AssetRelation::Owner
+    } else if delegatee == Some(operator_id) {
+        AssetRelation::Delegatee
+    } else if owners.is_empty() {
+        AssetRelation::Unknown
+    } else {
+        AssetRelation::Other
+    }
+}
+
+/// Get the permissions of an operator on an asset.
+///
+/// Assets of which the owner is not known are not restricted. An empty
+/// whitelist makes the asset available to all operators.
+fn permissions(
+    asset_type: PermissionAssetType,
+    asset_id: String,
+    operator_id: Option<String>,
+    relation: AssetRelation,
+    whitelist: &[String],
+    access: &Access,
+) -> AssetPermissions {
+    let whitelisted = whitelist.is_empty()
+        || operator_id
+            .as_ref()
+            .map_or(false, |operator_id| whitelist.contains(operator_id));
+    let owned = matches!(relation, AssetRelation::Owner | AssetRelation::Unknown);
+    let writable = access.can_write(asset_type);
+
+    AssetPermissions {
+        read: access.has(Scope::AssetsRead) && (whitelisted || relation != AssetRelation::Other),
+        update: writable && relation != AssetRelation::Other,
+        delete: writable && owned,
+        delegate: asset_type == PermissionAssetType::Group && access.groups_v2 && writable && owned,
+        asset_type,
+        asset_id,
+        operator_id,
+        relation,
+    }
+}
+
+/// Parse an asset id, returning `BAD_REQUEST` if it is not a UUID.
+fn asset_id(id: &str) -> Result<String, StatusCode> {
+    to_uuid(id).map(|id| id.to_string()).ok_or_else(|| {
+        rest_error!("Invalid asset id: {}", id);
+        StatusCode::BAD_REQUEST
+    })
+}
+
+/// Get what the operator of the `x-operator-id` header may do with an
+/// [`Aircraft`].
+///
+/// The owner of the aircraft is the operator which registered it, the
+/// delegatee of its asset group may update it.
+#[utoipa::path(
+    get,
+    path = "/assets/aircraft/{id}/permissions",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Permissions of the operator", body = AssetPermissions),
+        (status = 400, description = "Invalid aircraft id or x-operator-id header"),
+        (status = 404, description = "Aircraft not found"),
+    ),
+    params(
+        ("id" = String, Path, description = "Aircraft id"),
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_aircraft_permissions(
+    Extension(repos): Extension<Repositories>,
+    Extension(delegations): Extension<GroupDelegations>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Extension(read_only): Extension<ReadOnlyMode>,
+    Extension(features): Extension<Features>,
+    scopes: Option<Extension<TokenScopes>>,
+    Path(aircraft_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<AssetPermissions>, StatusCode> {
+    rest_info!("entry [{}].", aircraft_id);
+
+    let id = asset_id(&aircraft_id)?;
+    let operator_id = operator_id_from_headers(&headers)?;
+    let aircraft: Aircraft = repos
+        .aircraft
+        .get_by_id(id.clone())
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve aircraft: {e}");
+            storage_error_status(&e)
+        })?
+        .try_into()
+        .map_err(|e| {
+            rest_error!("could not convert vehicle::Object to Aircraft: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let owners: Vec<String> = quotas.owner(QuotaKind::Aircraft, &id).into_iter().collect();
+    let delegatee = aircraft
+        .basics
+        .group_id
+        .as_ref()
+        .and_then(|group_id| delegations.get(group_id, Utc::now()))
+        .map(|delegation| delegation.delegatee);
+    let relation = relation(operator_id.as_deref(), &owners, delegatee.as_deref());
+
+    Ok(Json(permissions(
+        PermissionAssetType::Aircraft,
+        id,
+        operator_id,
+        relation,
+        &aircraft.basics.whitelist,
+        &Access::new(&read_only, &features, scopes),
+    )))
+}
+
+/// Get what the operator of the `x-operator-id` header may do with a
+/// [`Vertiport`].
+///
+/// The owner of the vertiport is the operator which registered it.
+#[utoipa::path(
+    get,
+    path = "/assets/vertiports/{id}/permissions",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Permissions of the operator", body = AssetPermissions),
+        (status = 400, description = "Invalid vertiport id or x-operator-id header"),
+        (status = 404, description = "Vertiport not found"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
+)]
+pub async fn get_vertiport_permissions(
+    Extension(repos): Extension<Repositories>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Extension(read_only): Extension<ReadOnlyMode>,
+    Extension(features): Extension<Features>,
+    scopes: Option<Extension<TokenScopes>>,
+    Path(vertiport_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<AssetPermissions>, StatusCode> {
+    rest_info!("entry [{}].", vertiport_id);
+
+    let id = asset_id(&vertiport_id)?;
+    let operator_id = operator_id_from_headers(&headers)?;
+    let vertiport: Vertiport = repos
+        .vertiports
+        .get_by_id(id.clone())
+        .await
+        .map_err(|e| {
+            rest_error!("could not get vertiport: {e}");
+            storage_error_status(&e)
+        })?
+        .try_into()
+        .map_err(|e| {
+            rest_error!("could not convert vertiport: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let owners: Vec<String> = quotas
+        .owner(QuotaKind::Vertiport, &id)
+        .into_iter()
+        .collect();
+    let relation = relation(operator_id.as_deref(), &owners, None);
+
+    Ok(Json(permissions(
+        PermissionAssetType::Vertiport,
+        id,
+        operator_id,
+        relation,
+        &vertiport.basics.whitelist,
+        &Access::new(&read_only, &features, scopes),
+    )))
+}
+
+/// Get what the operator of the `x-operator-id` header may do with a
+/// [`Vertipad`].
+///
+/// The owner of the vertipad is the owner of its vertiport.
+#[utoipa::path(
+    get,
+    path = "/assets/vertipads/{id}/permissions",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Permissions of the operator", body = AssetPermissions),
+        (status = 400, description = "Invalid vertipad id or x-operator-id header"),
+        (status = 404, description = "Vertipad not found"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+    )
+)]
+pub async fn get_vertipad_permissions(
+    Extension(repos): Extension<Repositories>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Extension(read_only): Extension<ReadOnlyMode>,
+    Extension(features): Extension<Features>,
+    scopes: Option<Extension<TokenScopes>>,
+    Path(vertipad_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<AssetPermissions>, StatusCode> {
+    rest_info!("entry [{}].", vertipad_id);
+
+    let id = asset_id(&vertipad_id)?;
+    let operator_id = operator_id_from_headers(&headers)?;
+    let vertipad: Vertipad = repos
+        .vertipads
+        .get_by_id(id.clone())
+        .await
+        .map_err(|e| {
+            rest_error!("could not get vertipad: {e}");
+            storage_error_status(&e)
+        })?
+        .try_into()
+        .map_err(|e| {
+            rest_error!("could not convert vertipad: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let owners: Vec<String> = quotas
+        .owner(QuotaKind::Vertiport, &vertipad.vertiport_id)
+        .into_iter()
+        .collect();
+    let relation = relation(operator_id.as_deref(), &owners, None);
+
+    Ok(Json(permissions(
+        PermissionAssetType::Vertipad,
+        id,
+        operator_id,
+        relation,
+        &vertipad.basics.whitelist,
+        &Access::new(&read_only, &features, scopes),
+    )))
+}
+
+/// Get what the operator of the `x-operator-id` header may do with an
+/// [`AssetGroup`](crate::rest::structs::AssetGroup).
+///
+/// The owners of the group are described by [`group_owners`], the delegatee
+/// of the group may update it but not delegate it again.
+#[utoipa::path(
+    get,
+    path = "/assets/groups/{id}/permissions",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Permissions of the operator", body = AssetPermissions),
+        (status = 400, description = "Invalid asset group id or x-operator-id header"),
+        (status = 404, description = "Asset group not found"),
+    ),
+    params(
+        ("id" = String, Path, description = "Asset group id"),
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_group_permissions(
+    Extension(repos): Extension<Repositories>,
+    Extension(delegations): Extension<GroupDelegations>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Extension(read_only): Extension<ReadOnlyMode>,
+    Extension(features): Extension<Features>,
+    scopes: Option<Extension<TokenScopes>>,
+    Path(group_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<AssetPermissions>, StatusCode> {
+    rest_info!("entry [{}].", group_id);
+
+    let id = asset_id(&group_id)?;
+    let operator_id = operator_id_from_headers(&headers)?;
+    repos.groups.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not get asset group: {e}");
+        storage_error_status(&e)
+    })?;
+
+    let owners = group_owners(&repos, &delegations, &quotas, &id).await?;
+    let delegatee = delegations
+        .get(&id, Utc::now())
+        .map(|delegation| delegation.delegatee);
+    let relation = relation(operator_id.as_deref(), &owners, delegatee.as_deref());
+
+    Ok(Json(permissions(
+        PermissionAssetType::Group,
+        id,
+        operator_id,
+        relation,
+        &[],
+        &Access::new(&read_only, &features, scopes),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::memory::MemoryRepository;
+    use crate::rest::api::rest_types::DelegationPayload;
+    use crate::rest::quota::{AssetOwners, OPERATOR_ID_HEADER};
+    use crate::testing::AircraftDataBuilder;
+    use crate::Config;
+    use lib_common::logger::get_log_handle;
+    use lib_common::uuid::Uuid;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use svc_storage_client_grpc::prelude::group;
+
+    fn access(scopes: Option<Vec<Scope>>, read_only: bool) -> Access {
+        Access {
+            scopes,
+            read_only,
+            groups_v2: true,
+        }
+    }
+
+    /// read, update, delete and delegate permissions
+    fn actions(permissions: &AssetPermissions) -> [bool; 4] {
+        [
+            permissions.read,
+            permissions.update,
+            permissions.delete,
+            permissions.delegate,
+        ]
+    }
+
+    #[test]
+    fn test_relation() {
+        let owners = vec!["owner".to_string()];
+        assert_eq!(relation(None, &owners, None), AssetRelation::Unknown);
+        assert_eq!(relation(Some("owner"), &owners, None), AssetRelation::Owner);
+        assert_eq!(
+            relation(Some("other"), &owners, Some("other")),
+            AssetRelation::Delegatee
+        );
+        assert_eq!(relation(Some("other"), &owners, None), AssetRelation::Other);
+        assert_eq!(relation(Some("other"), &[], None), AssetRelation::Unknown);
+    }
+
+    #[test]
+    fn test_permissions() {
+        let check = |asset_type, relation, whitelist: &[String], access: &Access| {
+            let operator_id = Some("operator".to_string());
+            actions(&permissions(
+                asset_type,
+                "id".to_string(),
+                operator_id,
+                relation,
+                whitelist,
+                access,
+            ))
+        };
+        let open = access(None, false);
+
+        // owners and unknown owners may do anything, groups can be delegated
+        assert_eq!(
+            check(
+                PermissionAssetType::Aircraft,
+                AssetRelation::Owner,
+                &[],
+                &open
+            ),
+            [true, true, true, false]
+        );
+        assert_eq!(
+            check(
+                PermissionAssetType::Group,
+                AssetRelation::Unknown,
+                &[],
+                &open
+            ),
+            [true, true, true, true]
+        );
+
+        // delegatees may update, other operators only read whitelisted assets
+        assert_eq!(
+            check(
+                PermissionAssetType::Group,
+                AssetRelation::Delegatee,
+                &[],
+                &open
+            ),
+            [true, true, false, false]
+        );
+        assert_eq!(
+            check(
+                PermissionAssetType::Vertiport,
+                AssetRelation::Other,
+                &[],
+                &open
+            ),
+            [true, false, false, false]
+        );
+        let whitelist = vec!["someone".to_string()];
+        assert_eq!(
+            check(
+                PermissionAssetType::Vertipad,
+                AssetRelation::Other,
+                &whitelist,
+                &open
+            ),
+            [false, false, false, false]
+        );
+        assert_eq!(
+            check(
+                PermissionAssetType::Vertipad,
+                AssetRelation::Owner,
+                &whitelist,
+                &open
+            ),
+            [true, true, true, false]
+        );
+
+        // scopes, read-only mode and feature flags
+        let read = access(Some(vec![Scope::AssetsRead]), false);
+        assert_eq!(
+            check(
+                PermissionAssetType::Aircraft,
+                AssetRelation::Owner,
+                &[],
+                &read
+            ),
+            [true, false, false, false]
+        );
+        let groups = access(Some(vec![Scope::AssetsRead, Scope::GroupsAdmin]), false);
+        assert_eq!(
+            check(
+                PermissionAssetType::Aircraft,
+                AssetRelation::Owner,
+                &[],
+                &groups
+            ),
+            [true, false, false, false]
+        );
+        assert_eq!(
+            check(
+                PermissionAssetType::Group,
+                AssetRelation::Owner,
+                &[],
+                &groups
+            ),
+            [true, true, true, true]
+        );
+        assert_eq!(
+            check(
+                PermissionAssetType::Group,
+                AssetRelation::Owner,
+                &[],
+                &access(None, true)
+            ),
+            [true, false, false, false]
+        );
+        let mut v1 = open.clone();
+        v1.groups_v2 = false;
+        assert_eq!(
+            check(PermissionAssetType::Group, AssetRelation::Owner, &[], &v1),
+            [true, true, true, false]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_aircraft_permissions() {
+        get_log_handle().await;
+        ut_info!("Start.");
+
+        let group_id = Uuid::new_v4().to_string();
+        let repos = Repositories {
+            groups: Arc::new(MemoryRepository::with_objects(HashMap::from([(
+                group_id.clone(),
+                group::Data {
+                    name: "Fleet".to_string(),
+                    ..Default::default()
+                },
+            )]))),
+            ..Repositories::memory()
+        };
+        let config = crate::config::SharedConfig::from(Config::default());
+        let delegations = GroupDelegations::default();
+        let quotas = OperatorQuotas::new(config.clone(), AssetOwners::default());
+        let (owner, delegatee, other) = (
+            Uuid::new_v4().to_string(),
+            Uuid::new_v4().to_string(),
+            Uuid::new_v4().to_string(),
+        );
+
+        let data = AircraftDataBuilder::new()
+            .asset_group_id(group_id.clone())
+            .build();
+        let aircraft_id = repos.aircraft.insert(data).await.unwrap().id;
+        quotas
+            .reserve(&repos, &owner, QuotaKind::Aircraft)
+            .await
+            .unwrap()
+            .commit(&aircraft_id)
+            .unwrap();
+        delegations
+            .delegate(
+                &group_id,
+                DelegationPayload {
+                    delegatee: delegatee.clone(),
+                    expires_at: None,
+                },
+                Some(owner.clone()),
+                Utc::now(),
+            )
+            .unwrap();
+
+        let get = |id: String, operator_id: Option<&str>| {
+            let mut headers = HeaderMap::new();
+            if let Some(operator_id) = operator_id {
+                headers.insert(OPERATOR_ID_HEADER, operator_id.parse().unwrap());
+            }
+            get_aircraft_permissions(
+                Extension(repos.clone()),
+                Extension(delegations.clone()),
+                Extension(quotas.clone()),
+                Extension(ReadOnlyMode::new(config.clone())),
+                Extension(Features::new(config.clone())),
+                None,
+                Path(id),
+                headers,
+            )
+        };
+
+        let Json(permissions) = get(aircraft_id.clone(), Some(&owner)).await.unwrap();
+        assert_eq!(permissions.relation, AssetRelation::Owner);
+        assert_eq!(permissions.operator_id.as_deref(), Some(owner.as_str()));
+        assert_eq!(actions(&permissions), [true, true, true, false]);
+
+        let Json(permissions) = get(aircraft_id.clone(), Some(&delegatee)).await.unwrap();
+        assert_eq!(permissions.relation, AssetRelation::Delegatee);
+        assert_eq!(actions(&permissions), [true, true, false, false]);
+
+        let Json(permissions) = get(aircraft_id.clone(), Some(&other)).await.unwrap();
+        assert_eq!(permissions.relation, AssetRelation::Other);
+        assert_eq!(actions(&permissions), [true, false, false, false]);
+
+        let Json(permissions) = get(aircraft_id.clone(), None).await.unwrap();
+        assert_eq!(permissions.relation, AssetRelation::Unknown);
+
+        // invalid ids and headers, unknown aircraft
+        let error = get("invalid".to_string(), None).await.unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+        let error = get(aircraft_id, Some("invalid")).await.unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+        let error = get(Uuid::new_v4().to_string(), None).await.unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,325 @@
+//! Data quality report of the inventory.
+//!
+//! Scans the stored aircraft, vertiports and vertipads for data which can
+//! not be converted, breaks a reference or is ignored, so data stewards find
+//! these issues before they show up as assets missing from the list
+//! endpoints.
+
+pub use super::rest_types::{ArchiveAssetType, QualityIssue, QualityReport};
+
+use super::aircraft::validate_vehicle_data;
+use super::errors::storage_error_status;
+use super::validation::*;
+use super::vertipad::validate_vertipad_data;
+use super::vertiport::validate_vertiport_data;
+
+use crate::fan_out::FanOut;
+use crate::repo::Repositories;
+use crate::rest::json::Json;
+use axum::Extension;
+use hyper::StatusCode;
+use lib_common::time::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use svc_storage_client_grpc::prelude::*;
+
+/// Fields whose issues make an asset unusable: the asset can not be
+/// converted, or it refers to a missing or duplicated asset. Issues of other
+/// fields are reported as warnings.
+const ERROR_FIELDS: &[&str] = &[
+    "data",
+    "created_at",
+    "updated_at",
+    "geo_location",
+    "vertiport_id",
+    "registration_number",
+];
+
+/// Check if a field error is about one of the [`ERROR_FIELDS`], including
+/// nested fields such as `geo_location.rings[0]`
+fn is_error_field(field: &str) -> bool {
+    ERROR_FIELDS.iter().any(|error_field| {
+        field.strip_prefix(error_field).map_or(false, |rest| {
+            rest.is_empty() || rest.starts_with('.') || rest.starts_with('[')
+        })
+    })
+}
+
+/// Add an error if a timestamp of an asset is missing
+fn check_timestamps<T>(
+    errors: &mut Vec<FieldError>,
+    created_at: &Option<T>,
+    updated_at: &Option<T>,
+) {
+    if created_at.is_none() {
+        errors.push(field_error("created_at", "is missing"));
+    }
+    if updated_at.is_none() {
+        errors.push(field_error("updated_at", "is missing"));
+    }
+}
+
+/// Get the field errors of a validation result
+fn validation_errors<T>(result: Result<T, Vec<FieldError>>) -> Vec<FieldError> {
+    result.err().unwrap_or_default()
+}
+
+/// Build the report from the stored assets, in the order they are provided.
+pub fn quality_report(
+    aircraft: &[vehicle::Object],
+    vertiports: &[vertiport::Object],
+    vertipads: &[vertipad::Object],
+    now: DateTime<Utc>,
+) -> QualityReport {
+    let mut report = QualityReport {
+        generated_at: now,
+        scanned: aircraft.len() + vertiports.len() + vertipads.len(),
+        errors: vec![],
+        warnings: vec![],
+    };
+    let mut add = |asset_type: ArchiveAssetType, id: &str, errors: Vec<FieldError>| {
+        for error in errors {
+            let issue = QualityIssue {
+                id: id.to_string(),
+                asset_type,
+                field: error.field,
+                message: error.message,
+            };
+            match is_error_field(&issue.field) {
+                true => report.errors.push(issue),
+                false => report.warnings.push(issue),
+            }
+        }
+    };
+
+    let mut registration_numbers: HashMap<String, Vec<&str>> = HashMap::new();
+    for object in aircraft {
+        if let Some(data) = &object.data {
+            registration_numbers
+                .entry(data.registration_number.trim().to_string())
+                .or_default()
+                .push(&object.id);
+        }
+    }
+
+    for object in aircraft {
+        let Some(data) = &object.data else {
+            add(
+                ArchiveAssetType::Aircraft,
+                &object.id,
+                vec![field_error("data", "is missing")],
+            );
+            continue;
+        };
+
+        let mut errors = validation_errors(validate_vehicle_data(data.clone()));
+        check_optional_schedule(&mut errors, "schedule", &data.schedule);
+        check_timestamps(&mut errors, &data.created_at, &data.updated_at);
+        let others: Vec<&str> = registration_numbers
+            .get(data.registration_number.trim())
+            .map(|ids| ids.iter().copied().filter(|id| *id != object.id).collect())
+            .unwrap_or_default();
+        if !data.registration_number.trim().is_empty() && !others.is_empty() {
+            errors.push(field_error(
+                "registration_number",
+                &format!("also used by aircraft {}", others.join(", ")),
+            ));
+        }
+        add(ArchiveAssetType::Aircraft, &object.id, errors);
+    }
+
+    for object in vertiports {
+        let Some(data) = &object.data else {
+            add(
+                ArchiveAssetType::Vertiport,
+                &object.id,
+                vec![field_error("data", "is missing")],
+            );
+            continue;
+        };
+
+        let mut errors = validation_errors(validate_vertiport_data(data.clone()));
+        check_optional_schedule(&mut errors, "schedule", &data.schedule);
+        check_timestamps(&mut errors, &data.created_at, &data.updated_at);
+        add(ArchiveAssetType::Vertiport, &object.id, errors);
+    }
+
+    let vertiport_ids: HashSet<&str> = vertiports.iter().map(|v| v.id.as_str()).collect();
+    for object in vertipads {
+        let Some(data) = &object.data else {
+            add(
+                ArchiveAssetType::Vertipad,
+                &object.id,
+                vec![field_error("data", "is missing")],
+            );
+            continue;
+        };
+
+        let mut errors = validation_errors(validate_vertipad_data(data.clone()));
+        check_timestamps(&mut errors, &data.created_at, &data.updated_at);
+        if !vertiport_ids.contains(data.vertiport_id.trim()) {
+            errors.push(field_error("vertiport_id", "refers to a missing vertiport"));
+        }
+        add(ArchiveAssetType::Vertipad, &object.id, errors);
+    }
+
+    report
+}
+
+/// Get the data quality issues of all aircraft, vertiports and vertipads.
+///
+/// Issues making an asset unusable, for example a missing `geo_location` or
+/// a vertipad of a missing vertiport, are reported as errors; other issues,
+/// for example an unparsable schedule, as warnings.
+#[utoipa::path(
+    get,
+    path = "/assets/quality-report",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Data quality issues grouped by severity", body = QualityReport),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    )
+)]
+pub async fn get_quality_report(
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+) -> Result<Json<QualityReport>, StatusCode> {
+    rest_info!("entry.");
+
+    let filter = || AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+    let (aircraft, vertiports, vertipads) = tokio::join!(
+        fan_out.call(repos.aircraft.search(filter())),
+        fan_out.call(repos.vertiports.search(filter())),
+        fan_out.call(repos.vertipads.search(filter())),
+    );
+    let storage_error = |e: tonic::Status| {
+        rest_error!("could not retrieve assets: {e}.");
+        storage_error_status(&e)
+    };
+
+    let report = quality_report(
+        &aircraft.map_err(storage_error)?,
+        &vertiports.map_err(storage_error)?,
+        &vertipads.map_err(storage_error)?,
+        Utc::now(),
+    );
+
+    rest_info!(
+        "scanned {} assets, found {} errors and {} warnings.",
+        report.scanned,
+        report.errors.len(),
+        report.warnings.len()
+    );
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{AircraftDataBuilder, VertipadDataBuilder, VertiportDataBuilder};
+    use lib_common::uuid::Uuid;
+
+    fn issues(issues: &[QualityIssue]) -> Vec<(&str, &str)> {
+        issues
+            .iter()
+            .map(|issue| (issue.id.as_str(), issue.field.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_is_error_field() {
+        assert!(is_error_field("geo_location"));
+        assert!(is_error_field("geo_location.rings[0]"));
+        assert!(is_error_field("created_at"));
+        assert!(!is_error_field("schedule"));
+        assert!(!is_error_field("geo_location_name"));
+    }
+
+    #[test]
+    fn test_quality_report() {
+        let now = Utc::now();
+        let vertiport = vertiport::Object {
+            id: Uuid::new_v4().to_string(),
+            data: Some(VertiportDataBuilder::new().timestamps(now, now).build()),
+        };
+        let mut data = VertiportDataBuilder::new().build();
+        data.geo_location = None;
+        let no_location = vertiport::Object {
+            id: Uuid::new_v4().to_string(),
+            data: Some(data),
+        };
+
+        let orphan = vertipad::Object {
+            id: Uuid::new_v4().to_string(),
+            data: Some(
+                VertipadDataBuilder::new()
+                    .vertiport_id(Uuid::new_v4().to_string())
+                    .timestamps(now, now)
+                    .build(),
+            ),
+        };
+
+        let aircraft = |registration_number: &str, schedule: &str| vehicle::Object {
+            id: Uuid::new_v4().to_string(),
+            data: Some(
+                AircraftDataBuilder::new()
+                    .registration_number(registration_number)
+                    .schedule(schedule)
+                    .timestamps(now, now)
+                    .build(),
+            ),
+        };
+        let first = aircraft("N12345", "invalid");
+        let second = aircraft(" N12345", "DTSTART:20221020T180000Z;DURATION:PT14H");
+
+        let report = quality_report(
+            &[first.clone(), second.clone()],
+            &[vertiport, no_location.clone()],
+            &[orphan.clone()],
+            now,
+        );
+        assert_eq!(report.generated_at, now);
+        assert_eq!(report.scanned, 5);
+        assert_eq!(
+            issues(&report.errors),
+            vec![
+                (first.id.as_str(), "registration_number"),
+                (second.id.as_str(), "registration_number"),
+                (no_location.id.as_str(), "geo_location"),
+                (no_location.id.as_str(), "created_at"),
+                (no_location.id.as_str(), "updated_at"),
+                (orphan.id.as_str(), "vertiport_id"),
+            ]
+        );
+        assert_eq!(report.errors[0].asset_type, ArchiveAssetType::Aircraft);
+        assert_eq!(
+            report.errors[0].message,
+            format!("also used by aircraft {}", second.id)
+        );
+        assert_eq!(
+            issues(&report.warnings),
+            vec![(first.id.as_str(), "schedule")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_quality_report() {
+        let repos = Repositories::memory();
+        repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap();
+
+        let report = get_quality_report(
+            Extension(repos),
+            Extension(FanOut::new(crate::Config::default().into())),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(report.scanned, 1);
+        assert!(report.errors.is_empty());
+        assert!(report.warnings.is_empty());
+    }
+}
@@ -0,0 +1,758 @@
+//! Structured search of the assets of a type.
+//!
+//! The filter of an [`AssetQuery`] is a tree of `and` and `or` nodes over
+//! field conditions. It is validated against the fields allowed for the
+//! queried type, then mapped to a storage [`AdvancedSearchFilter`].
+//!
+//! Storage chains the conditions of a filter without grouping them, `AND`
+//! taking precedence over `OR`. The tree is therefore expanded to a
+//! disjunction of conjunctions first, `a AND (b OR c)` becoming
+//! `a AND b OR a AND c`, and every conjunction excludes the removed assets.
+//! An `in` condition is expanded to one `eq` condition per value.
+
+pub use super::rest_types::{AssetQuery, QueryAssetType, QueryFilter, QueryOperator};
+
+use super::aircraft::Aircraft;
+use super::conversion::{
+    convert_objects, ConversionMode, FillMissing, ListError, Listed, PartialList, SkippedObjects,
+};
+use super::errors::storage_error_status;
+use super::list::ListLimits;
+use super::rest_types::timestamp;
+use super::validation::*;
+use super::vertipad::Vertipad;
+use super::vertiport::Vertiport;
+use crate::aircraft_status::AircraftStatuses;
+use crate::config::SharedConfig;
+use crate::hold::MaintenanceHolds;
+use crate::maintenance::OverdueAircraft;
+use crate::ops_status::VertiportOpsStatuses;
+use crate::repo::Repositories;
+use crate::rest::classification::PadClassifications;
+use crate::rest::json::Json;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use lib_common::uuid::to_uuid;
+use serde_json::Value;
+use std::future::Future;
+use svc_storage_client_grpc::prelude::AdvancedSearchFilter;
+use tonic::Status;
+
+/// Maximum nesting depth of the filter of a query
+pub const MAX_QUERY_DEPTH: usize = 8;
+
+/// Maximum number of conditions of the filter of a query, counting each
+/// value of an `in` condition
+pub const MAX_QUERY_CONDITIONS: usize = 32;
+
+/// Maximum number of conjunctions of the expanded filter of a query
+pub const MAX_QUERY_TERMS: usize = 32;
+
+/// Type of the values of a queryable field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    /// A string
+    Text,
+    /// A UUID string
+    Uuid,
+    /// A boolean
+    Bool,
+    /// An RFC 3339 timestamp string
+    Timestamp,
+}
+
+impl FieldKind {
+    /// Check if a field of this kind can be compared with the operator
+    fn allows(self, op: QueryOperator) -> bool {
+        use QueryOperator::*;
+        match self {
+            FieldKind::Text => !matches!(op, Lt | Le | Gt | Ge),
+            FieldKind::Uuid => matches!(op, Eq | Ne | In | IsNull | IsNotNull),
+            FieldKind::Bool => matches!(op, Eq | Ne),
+            FieldKind::Timestamp => !matches!(op, Like | In),
+        }
+    }
+
+    /// Get the storage representation of a value of this kind, `None` if
+    /// the value is not of this kind
+    fn parse(self, value: &Value) -> Option<String> {
+        match (self, value) {
+            (FieldKind::Text, Value::String(text)) => Some(text.clone()),
+            (FieldKind::Uuid, Value::String(id)) => to_uuid(id).map(|id| id.to_string()),
+            (FieldKind::Bool, Value::Bool(value)) => Some(value.to_string()),
+            (FieldKind::Timestamp, Value::String(value)) => timestamp::parse(value)
+                .ok()
+                .map(|timestamp| timestamp.to_rfc3339()),
+            _ => None,
+        }
+    }
+
+    /// Describe the values of this kind, for error messages
+    fn describe(self) -> &'static str {
+        match self {
+            FieldKind::Text => "a string",
+            FieldKind::Uuid => "a UUID",
+            FieldKind::Bool => "a boolean",
+            FieldKind::Timestamp => "an RFC 3339 timestamp",
+        }
+    }
+}
+
+/// Queryable fields of aircraft
+const AIRCRAFT_FIELDS: &[(&str, FieldKind)] = &[
+    ("vehicle_model_id", FieldKind::Uuid),
+    ("serial_number", FieldKind::Text),
+    ("registration_number", FieldKind::Text),
+    ("description", FieldKind::Text),
+    ("asset_group_id", FieldKind::Uuid),
+    ("hangar_id", FieldKind::Uuid),
+    ("hangar_bay_id", FieldKind::Uuid),
+    ("last_maintenance", FieldKind::Timestamp),
+    ("next_maintenance", FieldKind::Timestamp),
+    ("created_at", FieldKind::Timestamp),
+    ("updated_at", FieldKind::Timestamp),
+];
+
+/// Queryable fields of vertiports
+const VERTIPORT_FIELDS: &[(&str, FieldKind)] = &[
+    ("name", FieldKind::Text),
+    ("description", FieldKind::Text),
+    ("created_at", FieldKind::Timestamp),
+    ("updated_at", FieldKind::Timestamp),
+];
+
+/// Queryable fields of vertipads
+const VERTIPAD_FIELDS: &[(&str, FieldKind)] = &[
+    ("vertiport_id", FieldKind::Uuid),
+    ("name", FieldKind::Text),
+    ("enabled", FieldKind::Bool),
+    ("occupied", FieldKind::Bool),
+    ("created_at", FieldKind::Timestamp),
+    ("updated_at", FieldKind::Timestamp),
+];
+
+/// Get the queryable fields of an asset type
+fn query_fields(asset_type: QueryAssetType) -> &'static [(&'static str, FieldKind)] {
+    match asset_type {
+        QueryAssetType::Aircraft => AIRCRAFT_FIELDS,
+        QueryAssetType::Vertiport => VERTIPORT_FIELDS,
+        QueryAssetType::Vertipad => VERTIPAD_FIELDS,
+    }
+}
+
+/// A validated condition of a query, with the storage representation of
+/// its value
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Condition {
+    field: &'static str,
+    op: QueryOperator,
+    value: Option<String>,
+}
+
+/// A validated node of a query filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    And(Vec<Node>),
+    Or(Vec<Node>),
+    Condition(Condition),
+}
+
+/// Validation state of a query filter
+struct Validator {
+    fields: &'static [(&'static str, FieldKind)],
+    conditions: usize,
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    /// Validate a node of the filter found at `path`
+    fn node(&mut self, filter: &QueryFilter, path: &str, depth: usize) -> Option<Node> {
+        if depth > MAX_QUERY_DEPTH {
+            self.errors.push(field_error(
+                path,
+                &format!("filters can not be nested more than {MAX_QUERY_DEPTH} levels deep"),
+            ));
+            return None;
+        }
+
+        match filter {
+            QueryFilter::And { and } => self
+                .children(and, &format!("{path}.and"), depth)
+                .map(Node::And),
+            QueryFilter::Or { or } => self
+                .children(or, &format!("{path}.or"), depth)
+                .map(Node::Or),
+            QueryFilter::Condition { field, op, value } => {
+                self.condition(field, *op, value.as_ref(), path)
+            }
+        }
+    }
+
+    /// Validate the nodes combined by an `and` or `or` node
+    fn children(&mut self, filters: &[QueryFilter], path: &str, depth: usize) -> Option<Vec<Node>> {
+        if filters.is_empty() {
+            self.errors
+                .push(field_error(path, "must combine at least one filter"));
+            return None;
+        }
+
+        let nodes: Vec<Option<Node>> = filters
+            .iter()
+            .enumerate()
+            .map(|(index, filter)| self.node(filter, &format!("{path}[{index}]"), depth + 1))
+            .collect();
+        nodes.into_iter().collect()
+    }
+
+    /// Validate a field condition
+    fn condition(
+        &mut self,
+        field: &str,
+        op: QueryOperator,
+        value: Option<&Value>,
+        path: &str,
+    ) -> Option<Node> {
+        let Some((name, kind)) = self.fields.iter().find(|(name, _)| *name == field).copied()
+        else {
+            let allowed: Vec<&str> = self.fields.iter().map(|(name, _)| *name).collect();
+            self.errors.push(field_error(
+                &format!("{path}.field"),
+                &format!(
+                    "can not query {field}, allowed fields: {}",
+                    allowed.join(", ")
+                ),
+            ));
+            return None;
+        };
+
+        if !kind.allows(op) {
+            self.errors.push(field_error(
+                &format!("{path}.op"),
+                &format!("the operator is not supported by {name}"),
+            ));
+            return None;
+        }
+
+        let value_path = format!("{path}.value");
+        let invalid = |message: String| vec![field_error(&value_path, &message)];
+        let result = match (op, value) {
+            (QueryOperator::IsNull | QueryOperator::IsNotNull, None) => Ok(vec![None]),
+            (QueryOperator::IsNull | QueryOperator::IsNotNull, Some(_)) => Err(invalid(
+                "must be left out by is_null and is_not_null".to_string(),
+            )),
+            (QueryOperator::In, Some(Value::Array(values))) if !values.is_empty() => values
+                .iter()
+                .map(|value| kind.parse(value).map(Some))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| invalid(format!("must be an array of {}", kind.describe()))),
+            (QueryOperator::In, _) => Err(invalid(format!(
+                "must be a non empty array of {}",
+                kind.describe()
+            ))),
+            (_, Some(value)) => kind
+                .parse(value)
+                .map(|value| vec![Some(value)])
+                .ok_or_else(|| invalid(format!("must be {}", kind.describe()))),
+            (_, None) => Err(invalid("is required".to_string())),
+        };
+
+        let values = match result {
+            Ok(values) => values,
+            Err(errors) => {
+                self.errors.extend(errors);
+                return None;
+            }
+        };
+
+        self.conditions += values.len();
+        if self.conditions > MAX_QUERY_CONDITIONS {
+            self.errors.push(field_error(
+                path,
+                &format!("a query can not have more than {MAX_QUERY_CONDITIONS} conditions"),
+            ));
+            return None;
+        }
+
+        let op = match op {
+            QueryOperator::In => QueryOperator::Eq,
+            op => op,
+        };
+        let mut conditions: Vec<Node> = values
+            .into_iter()
+            .map(|value| {
+                Node::Condition(Condition {
+                    field: name,
+                    op,
+                    value,
+                })
+            })
+            .collect();
+        match conditions.len() {
+            1 => conditions.pop(),
+            _ => Some(Node::Or(conditions)),
+        }
+    }
+}
+
+/// Expand a node to a disjunction of conjunctions of conditions.
+///
+/// Returns `None` if the expansion has more than [`MAX_QUERY_TERMS`]
+/// conjunctions.
+fn expand(node: &Node) -> Option<Vec<Vec<Condition>>> {
+    let terms = match node {
+        Node::Condition(condition) => vec![vec![condition.clone()]],
+        Node::Or(nodes) => {
+            let mut terms = vec![];
+            for node in nodes {
+                terms.extend(expand(node)?);
+                if terms.len() > MAX_QUERY_TERMS {
+                    return None;
+                }
+            }
+            terms
+        }
+        Node::And(nodes) => {
+            let mut terms: Vec<Vec<Condition>> = vec![vec![]];
+            for node in nodes {
+                let expanded = expand(node)?;
+                if terms.len() * expanded.len() > MAX_QUERY_TERMS {
+                    return None;
+                }
+                terms = terms
+                    .iter()
+                    .flat_map(|term| {
+                        expanded.iter().map(move |other| {
+                            let mut term = term.clone();
+                            term.extend(other.iter().cloned());
+                            term
+                        })
+                    })
+                    .collect();
+            }
+            terms
+        }
+    };
+
+    Some(terms)
+}
+
+/// Join of a condition with the previous conditions of a storage filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Join {
+    And,
+    Or,
+}
+
+/// Add a condition to a storage filter, starting the filter if there is
+/// none yet
+macro_rules! chain {
+    ($filter:expr, $join:expr, $search:ident, $and:ident, $or:ident ($($arg:expr),*)) => {
+        match ($filter, $join) {
+            (None, _) => AdvancedSearchFilter::$search($($arg),*),
+            (Some(filter), Join::And) => filter.$and($($arg),*),
+            (Some(filter), Join::Or) => filter.$or($($arg),*),
+        }
+    };
+}
+
+/// Add a condition to a storage filter
+fn add_condition(
+    filter: Option<AdvancedSearchFilter>,
+    join: Join,
+    condition: &Condition,
+) -> AdvancedSearchFilter {
+    let field = condition.field.to_string();
+    let value = condition.value.clone().unwrap_or_default();
+    match condition.op {
+        QueryOperator::Eq | QueryOperator::In => {
+            chain!(
+                filter,
+                join,
+                search_equals,
+                and_equals,
+                or_equals(field, value)
+            )
+        }
+        QueryOperator::Ne => {
+            chain!(
+                filter,
+                join,
+                search_not_equals,
+                and_not_equals,
+                or_not_equals(field, value)
+            )
+        }
+        QueryOperator::Lt => chain!(filter, join, search_less, and_less, or_less(field, value)),
+        QueryOperator::Le => chain!(
+            filter,
+            join,
+            search_less_or_equal,
+            and_less_or_equal,
+            or_less_or_equal(field, value)
+        ),
+        QueryOperator::Gt => {
+            chain!(
+                filter,
+                join,
+                search_greater,
+                and_greater,
+                or_greater(field, value)
+            )
+        }
+        QueryOperator::Ge => chain!(
+            filter,
+            join,
+            search_greater_or_equal,
+            and_greater_or_equal,
+            or_greater_or_equal(field, value)
+        ),
+        QueryOperator::Like => {
+            chain!(
+                filter,
+                join,
+                search_ilike,
+                and_ilike,
+                or_ilike(field, value)
+            )
+        }
+        QueryOperator::IsNull => {
+            chain!(filter, join, search_is_null, and_is_null, or_is_null(field))
+        }
+        QueryOperator::IsNotNull => {
+            chain!(
+                filter,
+                join,
+                search_is_not_null,
+                and_is_not_null,
+                or_is_not_null(field)
+            )
+        }
+    }
+}
+
+/// Validate the filter of a query and map it to a storage filter of the
+/// available assets.
+pub fn query_filter(query: &AssetQuery) -> Result<AdvancedSearchFilter, Vec<FieldError>> {
+    let mut validator = Validator {
+        fields: query_fields(query.asset_type),
+        conditions: 0,
+        errors: vec![],
+    };
+    let node = validator.node(&query.filter, "filter", 1);
+    let Some(node) = into_result(node, validator.errors)? else {
+        // a node is only left out with an error
+        return Err(vec![field_error("filter", "invalid filter")]);
+    };
+
+    let terms = expand(&node).ok_or_else(|| {
+        vec![field_error(
+            "filter",
+            &format!("the filter expands to more than {MAX_QUERY_TERMS} alternatives"),
+        )]
+    })?;
+
+    let removed = Condition {
+        field: "deleted_at",
+        op: QueryOperator::IsNull,
+        value: None,
+    };
+    let mut filter = None;
+    for term in terms {
+        for (index, condition) in term.iter().chain([&removed]).enumerate() {
+            let join = match index {
+                0 => Join::Or,
+                _ => Join::And,
+            };
+            filter = Some(add_condition(filter, join, condition));
+        }
+    }
+
+    // every term ends with the removed assets condition
+    Ok(filter.unwrap_or_else(|| AdvancedSearchFilter::search_is_null("deleted_at".to_string())))
+}
+
+/// Search the storage objects of a query and convert them to assets.
+async fn search_assets<O, T>(
+    search: impl Future<Output = Result<Vec<O>, Status>>,
+    limits: ListLimits,
+    mode: ConversionMode,
+) -> Result<Listed<T>, ListError>
+where
+    O: FillMissing,
+    T: TryFrom<O, Error = String>,
+{
+    let objects = search.await.map_err(|e| {
+        rest_error!("could not query assets: {e}.");
+        storage_error_status(&e)
+    })?;
+    limits.check(objects.len())?;
+
+    let mut listed = convert_objects(objects, mode)?;
+    limits.warn(&mut listed);
+    Ok(listed)
+}
+
+/// Search the assets of a type matching a filter.
+///
+/// The fields of the conditions must be allowed for the queried type.
+/// Results are limited like the list endpoints.
+#[utoipa::path(
+    post,
+    path = "/assets/query",
+    tag = "svc-assets",
+    request_body = AssetQuery,
+    responses(
+        (status = 200, description = "Assets of the queried type matching the filter; the `x-dropped-records` header counts the stored objects which could not be converted, a `Warning` header reports a list larger than `rest_list_warn_results`"),
+        (status = 207, description = "Stored objects could not be converted in report conversion mode; the converted assets are returned with the failed objects", body = PartialList, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid filter, or more assets match the query than `rest_list_max_results`", body = [FieldError]),
+        (status = 502, description = "Stored objects could not be converted in strict conversion mode", body = SkippedObjects),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn query_assets(
+    Extension(repos): Extension<Repositories>,
+    Extension(overdue): Extension<OverdueAircraft>,
+    Extension(holds): Extension<MaintenanceHolds>,
+    Extension(statuses): Extension<AircraftStatuses>,
+    Extension(ops_statuses): Extension<VertiportOpsStatuses>,
+    Extension(classifications): Extension<PadClassifications>,
+    Extension(config): Extension<SharedConfig>,
+    Json(query): Json<AssetQuery>,
+) -> Result<Response, Response> {
+    rest_info!("entry.");
+    rest_debug!("Query: {:?}", query);
+
+    let filter = query_filter(&query).map_err(|errors| unprocessable(errors).into_response())?;
+    let limits = config.read(ListLimits::from_config);
+    let filter = limits.limit_filter(filter);
+    let mode = config.read(|c| c.conversion_mode);
+
+    let response = match query.asset_type {
+        QueryAssetType::Aircraft => {
+            let mut listed: Listed<Aircraft> =
+                search_assets(repos.aircraft.search(filter), limits, mode)
+                    .await
+                    .map_err(IntoResponse::into_response)?;
+            listed.assets = listed
+                .assets
+                .into_iter()
+                .map(|aircraft| {
+                    aircraft
+                        .apply_status(&statuses)
+                        .apply_maintenance_status(&overdue, &holds)
+                })
+                .collect();
+            listed.into_response()
+        }
+        QueryAssetType::Vertiport => {
+            let listed: Listed<Vertiport> =
+                search_assets(repos.vertiports.search(filter), limits, mode)
+                    .await
+                    .map_err(IntoResponse::into_response)?;
+            listed.into_response()
+        }
+        QueryAssetType::Vertipad => {
+            let mut listed: Listed<Vertipad> =
+                search_assets(repos.vertipads.search(filter), limits, mode)
+                    .await
+                    .map_err(IntoResponse::into_response)?;
+            listed.assets = listed
+                .assets
+                .into_iter()
+                .map(|vertipad| {
+                    vertipad
+                        .apply_ops_status(&ops_statuses)
+                        .apply_classification(&classifications)
+                })
+                .collect();
+            listed.into_response()
+        }
+    };
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::testing::VertipadDataBuilder;
+    use serde_json::json;
+
+    fn query(asset_type: QueryAssetType, filter: Value) -> AssetQuery {
+        AssetQuery {
+            asset_type,
+            filter: serde_json::from_value(filter).unwrap(),
+        }
+    }
+
+    fn error_fields(query: &AssetQuery) -> Vec<String> {
+        query_filter(query)
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.field)
+            .collect()
+    }
+
+    fn search_fields(filter: &AdvancedSearchFilter) -> Vec<&str> {
+        filter
+            .filters
+            .iter()
+            .map(|option| option.search_field.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_query_filter() {
+        let filter = query_filter(&query(
+            QueryAssetType::Vertipad,
+            json!({"and": [
+                {"field": "enabled", "op": "eq", "value": true},
+                {"or": [
+                    {"field": "name", "op": "like", "value": "Pad%"},
+                    {"field": "occupied", "op": "ne", "value": true},
+                ]},
+            ]}),
+        ))
+        .unwrap();
+        assert_eq!(
+            search_fields(&filter),
+            vec![
+                "enabled",
+                "name",
+                "deleted_at",
+                "enabled",
+                "occupied",
+                "deleted_at"
+            ]
+        );
+        assert_eq!(filter.filters[0].search_value, vec!["true".to_string()]);
+
+        // `in` is expanded to an alternative per value
+        let id = lib_common::uuid::Uuid::new_v4().to_string();
+        let filter = query_filter(&query(
+            QueryAssetType::Aircraft,
+            json!({"field": "asset_group_id", "op": "in", "value": [id, id.to_uppercase()]}),
+        ))
+        .unwrap();
+        assert_eq!(
+            search_fields(&filter),
+            vec![
+                "asset_group_id",
+                "deleted_at",
+                "asset_group_id",
+                "deleted_at"
+            ]
+        );
+        assert_eq!(filter.filters[2].search_value, vec![id]);
+
+        let filter = query_filter(&query(
+            QueryAssetType::Vertiport,
+            json!({"field": "description", "op": "is_null"}),
+        ))
+        .unwrap();
+        assert_eq!(search_fields(&filter), vec!["description", "deleted_at"]);
+    }
+
+    #[test]
+    fn test_query_filter_invalid() {
+        // not allowed for the type, or not comparable with the operator
+        let invalid = query(
+            QueryAssetType::Vertiport,
+            json!({"or": [
+                {"field": "vertiport_id", "op": "eq", "value": "x"},
+                {"field": "name", "op": "gt", "value": "x"},
+                {"field": "created_at", "op": "ge", "value": "yesterday"},
+                {"field": "name", "op": "is_null", "value": "x"},
+                {"field": "name", "op": "eq"},
+                {"field": "name", "op": "in", "value": []},
+            ]}),
+        );
+        assert_eq!(
+            error_fields(&invalid),
+            vec![
+                "filter.or[0].field",
+                "filter.or[1].op",
+                "filter.or[2].value",
+                "filter.or[3].value",
+                "filter.or[4].value",
+                "filter.or[5].value",
+            ]
+        );
+
+        let empty = query(QueryAssetType::Vertiport, json!({"and": []}));
+        assert_eq!(error_fields(&empty), vec!["filter.and"]);
+
+        let mut nested = json!({"field": "name", "op": "eq", "value": "x"});
+        for _ in 0..MAX_QUERY_DEPTH {
+            nested = json!({ "and": [nested] });
+        }
+        assert_eq!(
+            error_fields(&query(QueryAssetType::Vertiport, nested)).len(),
+            1
+        );
+
+        let values: Vec<String> = (0..=MAX_QUERY_CONDITIONS).map(|i| i.to_string()).collect();
+        let many = query(
+            QueryAssetType::Vertiport,
+            json!({"field": "name", "op": "in", "value": values}),
+        );
+        assert_eq!(error_fields(&many), vec!["filter"]);
+
+        // (a or b) and (c or d) and .. expands to 2^6 alternatives
+        let pair = json!({"or": [
+            {"field": "name", "op": "eq", "value": "a"},
+            {"field": "name", "op": "eq", "value": "b"},
+        ]});
+        let product = query(QueryAssetType::Vertiport, json!({ "and": vec![pair; 6] }));
+        assert_eq!(error_fields(&product), vec!["filter"]);
+    }
+
+    #[tokio::test]
+    async fn test_query_assets() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        repos
+            .vertipads
+            .insert(VertipadDataBuilder::new().build())
+            .await
+            .unwrap();
+
+        let handler = |query: AssetQuery| {
+            query_assets(
+                Extension(repos.clone()),
+                Extension(OverdueAircraft::default()),
+                Extension(MaintenanceHolds::default()),
+                Extension(AircraftStatuses::default()),
+                Extension(VertiportOpsStatuses::default()),
+                Extension(PadClassifications::default()),
+                Extension(SharedConfig::from(Config::default())),
+                Json(query),
+            )
+        };
+
+        let response = handler(query(
+            QueryAssetType::Vertipad,
+            json!({"field": "enabled", "op": "eq", "value": true}),
+        ))
+        .await
+        .unwrap();
+        assert_eq!(response.status(), hyper::StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let vertipads: Vec<Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(vertipads.len(), 1);
+
+        let response = handler(query(
+            QueryAssetType::Vertipad,
+            json!({"field": "geo_location", "op": "is_null"}),
+        ))
+        .await
+        .unwrap_err();
+        assert_eq!(response.status(), hyper::StatusCode::UNPROCESSABLE_ENTITY);
+
+        ut_info!("success");
+    }
+}
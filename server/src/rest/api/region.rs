@@ -0,0 +1,368 @@
+//! Handlers for the region API endpoints.
+
+pub use super::rest_types::{GeoPolygon, Region, RegionPayload};
+
+use super::errors::storage_error_status;
+use super::validation::*;
+use super::vertiport::{GeometryQuery, Vertiport};
+
+use crate::geo::polygons_intersect;
+use crate::repo::Repositories;
+use crate::rest::json::Json;
+use crate::rest::regions::{RegionError, Regions, MAX_REGIONS};
+use axum::{
+    extract::{Path, Query},
+    Extension,
+};
+use chrono::Utc;
+use hyper::StatusCode;
+use lib_common::uuid::to_uuid;
+use svc_storage_client_grpc::prelude::*;
+
+/// Validate and normalize a region payload.
+pub fn validate_region(mut payload: RegionPayload) -> Result<RegionPayload, Vec<FieldError>> {
+    let mut errors = vec![];
+    normalize_string(&mut payload.name);
+    check_not_empty(&mut errors, "name", &payload.name);
+    if payload.geo_location.rings.is_empty() {
+        errors.push(field_error("geo_location.rings", "must not be empty"));
+    }
+    check_geo_polygon(
+        &mut errors,
+        "geo_location",
+        &payload.geo_location.clone().into(),
+    );
+
+    into_result(payload, errors)
+}
+
+/// Parse a region id, returning `BAD_REQUEST` if it is not a valid UUID.
+fn region_id(id: &str) -> Result<String, StatusCode> {
+    to_uuid(id).map(|id| id.to_string()).ok_or_else(|| {
+        rest_error!("Invalid region id: {}", id);
+        StatusCode::BAD_REQUEST
+    })
+}
+
+/// Map an error of an existing region change to its status code.
+fn region_error_status(id: &str, e: RegionError) -> StatusCode {
+    match e {
+        RegionError::State(e) => {
+            rest_error!("could not persist region {}: {e}", id);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        _ => {
+            rest_info!("region {} not found.", id);
+            StatusCode::NOT_FOUND
+        }
+    }
+}
+
+/// Get all regions.
+#[utoipa::path(
+    get,
+    path = "/assets/regions",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "All regions, ordered by name", body = [Region]),
+    )
+)]
+pub async fn get_regions(Extension(regions): Extension<Regions>) -> Json<Vec<Region>> {
+    rest_info!("entry.");
+    Json(regions.list())
+}
+
+/// Register a region.
+///
+/// The region is persisted by this instance, see [`crate::rest::regions`].
+#[utoipa::path(
+    post,
+    path = "/assets/regions",
+    tag = "svc-assets",
+    request_body = RegionPayload,
+    responses(
+        (status = 200, description = "Region registered; the new region is returned", body = Region),
+        (status = 422, description = "Invalid region or too many regions", body = [FieldError]),
+        (status = 500, description = "The region could not be persisted"),
+    )
+)]
+pub async fn register_region(
+    Extension(regions): Extension<Regions>,
+    Json(payload): Json<RegionPayload>,
+) -> Result<Json<Region>, ValidationError> {
+    rest_info!("entry.");
+    let payload = validate_region(payload).map_err(unprocessable)?;
+
+    let region = regions.add(payload, Utc::now()).map_err(|e| match e {
+        RegionError::State(e) => {
+            rest_error!("could not persist region: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+        }
+        _ => {
+            rest_error!("reached the maximum number of regions.");
+            unprocessable(vec![field_error(
+                "regions",
+                &format!("can not contain more than {MAX_REGIONS} regions"),
+            )])
+        }
+    })?;
+
+    rest_info!("registered region [{}].", region.id);
+    Ok(Json(region))
+}
+
+/// Get a region by its id.
+#[utoipa::path(
+    get,
+    path = "/assets/regions/{id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Region {id} found", body = Region),
+        (status = 400, description = "Invalid region id"),
+        (status = 404, description = "Region not found"),
+    ),
+    params(
+        ("id" = String, Path, description = "Region id"),
+    )
+)]
+pub async fn get_region(
+    Extension(regions): Extension<Regions>,
+    Path(id): Path<String>,
+) -> Result<Json<Region>, StatusCode> {
+    rest_info!("entry [{}].", id);
+    let id = region_id(&id)?;
+
+    regions.get(&id).map(Json).ok_or_else(|| {
+        rest_info!("region {} not found.", id);
+        StatusCode::NOT_FOUND
+    })
+}
+
+/// Replace the name and area of a region.
+#[utoipa::path(
+    put,
+    path = "/assets/regions/{id}",
+    tag = "svc-assets",
+    request_body = RegionPayload,
+    responses(
+        (status = 200, description = "Region updated; the updated region is returned", body = Region),
+        (status = 400, description = "Invalid region id"),
+        (status = 404, description = "Region not found"),
+        (status = 422, description = "Invalid region", body = [FieldError]),
+        (status = 500, description = "The region could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Region id"),
+    )
+)]
+pub async fn update_region(
+    Extension(regions): Extension<Regions>,
+    Path(id): Path<String>,
+    Json(payload): Json<RegionPayload>,
+) -> Result<Json<Region>, ValidationError> {
+    rest_info!("entry [{}].", id);
+    let id = region_id(&id).map_err(|status| (status, Json(vec![])))?;
+    let payload = validate_region(payload).map_err(unprocessable)?;
+
+    let region = regions
+        .update(&id, payload, Utc::now())
+        .map_err(|e| (region_error_status(&id, e), Json(vec![])))?;
+
+    Ok(Json(region))
+}
+
+/// Remove a region.
+///
+/// The vertiports of the region are not changed.
+#[utoipa::path(
+    delete,
+    path = "/assets/regions/{id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Region removed"),
+        (status = 400, description = "Invalid region id"),
+        (status = 404, description = "Region not found"),
+        (status = 500, description = "The region could not be removed"),
+    ),
+    params(
+        ("id" = String, Path, description = "Region id"),
+    )
+)]
+pub async fn remove_region(
+    Extension(regions): Extension<Regions>,
+    Path(id): Path<String>,
+) -> Result<(), StatusCode> {
+    rest_info!("entry [{}].", id);
+    let id = region_id(&id)?;
+
+    regions.remove(&id).map_err(|e| region_error_status(&id, e))
+}
+
+/// Get the vertiports whose area intersects the area of a region.
+#[utoipa::path(
+    get,
+    path = "/assets/regions/{id}/vertiports",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Vertiports of region {id}", body = [Vertiport]),
+        (status = 400, description = "Invalid region id or geometry"),
+        (status = 404, description = "Region not found"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Region id"),
+        GeometryQuery,
+    )
+)]
+pub async fn get_region_vertiports(
+    Extension(repos): Extension<Repositories>,
+    Extension(regions): Extension<Regions>,
+    Path(id): Path<String>,
+    Query(geometry): Query<GeometryQuery>,
+) -> Result<Json<Vec<Vertiport>>, StatusCode> {
+    rest_info!("entry [{}].", id);
+    let id = region_id(&id)?;
+    let region = regions.get(&id).ok_or_else(|| {
+        rest_info!("region {} not found.", id);
+        StatusCode::NOT_FOUND
+    })?;
+    let area: GeoPolygonZ = region.geo_location.into();
+
+    let vertiports: Vec<Vertiport> = repos
+        .vertiports
+        .search(AdvancedSearchFilter::search_is_null(
+            "deleted_at".to_string(),
+        ))
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve vertiports: {e}.");
+            storage_error_status(&e)
+        })?
+        .into_iter()
+        .filter_map(|object| Vertiport::try_from(object).ok())
+        .filter(|vertiport| {
+            vertiport
+                .geo_location
+                .clone()
+                .map(|geo_location| polygons_intersect(&area, &geo_location.into()))
+                .unwrap_or(false)
+        })
+        .map(|vertiport| vertiport.with_geometry(geometry.geometry.unwrap_or_default()))
+        .collect();
+
+    rest_debug!("found {} vertiports in region {}.", vertiports.len(), id);
+    Ok(Json(vertiports))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::api::vertiport::GeometryDetail;
+    use crate::testing::VertiportDataBuilder;
+
+    fn area(x: f64, y: f64, size: f64) -> GeoPolygonZ {
+        let point = |x: f64, y: f64| GeoPointZ { x, y, z: 0.0 };
+        GeoPolygonZ {
+            rings: vec![GeoLineStringZ {
+                points: vec![
+                    point(x, y),
+                    point(x + size, y),
+                    point(x + size, y + size),
+                    point(x, y + size),
+                    point(x, y),
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_validate_region() {
+        let payload = validate_region(RegionPayload {
+            name: " Amsterdam ".to_string(),
+            geo_location: area(4.0, 52.0, 0.5).into(),
+        })
+        .unwrap();
+        assert_eq!(payload.name, "Amsterdam");
+
+        let errors = validate_region(RegionPayload {
+            name: " ".to_string(),
+            geo_location: GeoPolygon::default(),
+        })
+        .unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["name", "geo_location.rings"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_region_vertiports() {
+        let repos = Repositories::memory();
+        let regions = Regions::default();
+        for (name, x) in [("Inside", 4.0), ("Outside", 6.0)] {
+            let data = VertiportDataBuilder::new()
+                .name(name)
+                .geo_location(area(x, 52.0, 0.2))
+                .build();
+            repos.vertiports.insert(data).await.unwrap();
+        }
+
+        let region = register_region(
+            Extension(regions.clone()),
+            Json(RegionPayload {
+                name: "Amsterdam".to_string(),
+                geo_location: area(3.9, 51.9, 0.2).into(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let vertiports = get_region_vertiports(
+            Extension(repos.clone()),
+            Extension(regions.clone()),
+            Path(region.id.clone()),
+            Query(GeometryQuery::default()),
+        )
+        .await
+        .unwrap()
+        .0;
+        let names: Vec<String> = vertiports.iter().map(Vertiport::full_name).collect();
+        assert_eq!(names, vec!["Inside"]);
+        assert!(vertiports[0].geo_location.is_some());
+
+        let vertiports = get_region_vertiports(
+            Extension(repos.clone()),
+            Extension(regions.clone()),
+            Path(region.id.clone()),
+            Query(GeometryQuery {
+                geometry: Some(GeometryDetail::Centroid),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(vertiports[0].geo_location, None);
+        assert!(vertiports[0].centroid.is_some());
+
+        assert_eq!(
+            remove_region(Extension(regions.clone()), Path(region.id.clone())).await,
+            Ok(())
+        );
+        assert_eq!(
+            get_region_vertiports(
+                Extension(repos.clone()),
+                Extension(regions.clone()),
+                Path(region.id),
+                Query(GeometryQuery::default()),
+            )
+            .await
+            .unwrap_err(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            get_region(Extension(regions), Path("invalid".to_string()))
+                .await
+                .unwrap_err(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
@@ -0,0 +1,489 @@
+//! Handlers for the vertiport restriction API endpoints.
+
+pub use super::rest_types::{
+    ActiveRestriction, RestrictionKind, RestrictionRule, RestrictionRulePayload, RestrictionsQuery,
+    VertiportRestrictions,
+};
+
+use super::errors::storage_error_status;
+use super::validation::*;
+
+use crate::repo::Repositories;
+use crate::rest::json::Json;
+use crate::rest::restrictions::{RestrictionError, RestrictionRules, MAX_RULES_PER_VERTIPORT};
+use crate::schedule::Schedule;
+use axum::{
+    extract::{Path, Query},
+    Extension,
+};
+use chrono::Utc;
+use hyper::StatusCode;
+use lib_common::uuid::to_uuid;
+
+/// Maximum number of movements per hour of a noise abatement rule
+const MAX_MOVEMENTS_PER_HOUR: u32 = 1000;
+
+/// Validate and normalize a restriction rule payload.
+///
+/// Noise abatement rules must limit the movements per hour, curfews close
+/// the vertiport and can not set a limit.
+pub fn validate_restriction_rule(
+    mut payload: RestrictionRulePayload,
+) -> Result<RestrictionRulePayload, Vec<FieldError>> {
+    let mut errors = vec![];
+    normalize_string(&mut payload.name);
+    check_not_empty(&mut errors, "name", &payload.name);
+    if let Err(e) = payload.schedule.parse::<Schedule>() {
+        errors.push(field_error("schedule", &e.to_string()));
+    }
+
+    match (payload.kind, payload.max_movements_per_hour) {
+        (RestrictionKind::NoiseAbatement, None) => {
+            errors.push(field_error(
+                "max_movements_per_hour",
+                "is required for noise abatement rules",
+            ));
+        }
+        (RestrictionKind::NoiseAbatement, Some(max))
+            if !(1..=MAX_MOVEMENTS_PER_HOUR).contains(&max) =>
+        {
+            errors.push(field_error(
+                "max_movements_per_hour",
+                &format!("must be between 1 and {MAX_MOVEMENTS_PER_HOUR}"),
+            ));
+        }
+        (RestrictionKind::Curfew, Some(_)) => {
+            errors.push(field_error(
+                "max_movements_per_hour",
+                "must not be set for curfews",
+            ));
+        }
+        _ => {}
+    }
+
+    into_result(payload, errors)
+}
+
+/// Parse a vertiport or rule id, returning `BAD_REQUEST` if it is not a
+/// valid UUID.
+fn parse_id(kind: &str, id: &str) -> Result<String, StatusCode> {
+    to_uuid(id).map(|id| id.to_string()).ok_or_else(|| {
+        rest_error!("Invalid {} id: {}", kind, id);
+        StatusCode::BAD_REQUEST
+    })
+}
+
+/// Map an error of an existing rule change to its status code.
+fn rule_error_status(vertiport_id: &str, rule_id: &str, e: RestrictionError) -> StatusCode {
+    match e {
+        RestrictionError::State(e) => {
+            rest_error!("could not persist restriction rule {}: {e}", rule_id);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        _ => {
+            rest_info!(
+                "restriction rule {} of vertiport {} not found.",
+                rule_id,
+                vertiport_id
+            );
+            StatusCode::NOT_FOUND
+        }
+    }
+}
+
+/// Check that a vertiport exists in storage.
+async fn check_vertiport(repos: &Repositories, vertiport_id: &str) -> Result<(), StatusCode> {
+    repos
+        .vertiports
+        .get_by_id(vertiport_id.to_string())
+        .await
+        .map(|_| ())
+        .map_err(|e| {
+            rest_error!("could not get vertiport: {e}");
+            storage_error_status(&e)
+        })
+}
+
+/// Get the restrictions of a vertiport in effect at a time.
+///
+/// Used by svc-scheduler before planning takeoffs and landings at the
+/// vertiport.
+#[utoipa::path(
+    get,
+    path = "/assets/vertiports/{id}/restrictions",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Restrictions of vertiport {id} in effect at the requested time", body = VertiportRestrictions),
+        (status = 400, description = "Invalid vertiport id"),
+        (status = 404, description = "Vertiport not found in database"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+        RestrictionsQuery,
+    )
+)]
+pub async fn get_vertiport_restrictions(
+    Extension(repos): Extension<Repositories>,
+    Extension(rules): Extension<RestrictionRules>,
+    Path(id): Path<String>,
+    Query(query): Query<RestrictionsQuery>,
+) -> Result<Json<VertiportRestrictions>, StatusCode> {
+    rest_info!("entry [{}].", id);
+    let id = parse_id("vertiport", &id)?;
+    check_vertiport(&repos, &id).await?;
+
+    let at = query.at.unwrap_or_else(Utc::now);
+    Ok(Json(rules.evaluate(&id, at)))
+}
+
+/// Get the restriction rules of a vertiport.
+#[utoipa::path(
+    get,
+    path = "/assets/vertiports/{id}/restrictions/rules",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Restriction rules of vertiport {id}, in the order they were added", body = [RestrictionRule]),
+        (status = 400, description = "Invalid vertiport id"),
+        (status = 404, description = "Vertiport not found in database"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
+)]
+pub async fn get_vertiport_restriction_rules(
+    Extension(repos): Extension<Repositories>,
+    Extension(rules): Extension<RestrictionRules>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<RestrictionRule>>, StatusCode> {
+    rest_info!("entry [{}].", id);
+    let id = parse_id("vertiport", &id)?;
+    check_vertiport(&repos, &id).await?;
+
+    Ok(Json(rules.list(&id)))
+}
+
+/// Add a restriction rule to a vertiport.
+///
+/// The rule is persisted by this instance, see
+/// [`crate::rest::restrictions`].
+#[utoipa::path(
+    post,
+    path = "/assets/vertiports/{id}/restrictions/rules",
+    tag = "svc-assets",
+    request_body = RestrictionRulePayload,
+    responses(
+        (status = 200, description = "Rule added; the new rule is returned", body = RestrictionRule),
+        (status = 400, description = "Invalid vertiport id"),
+        (status = 404, description = "Vertiport not found in database"),
+        (status = 422, description = "Invalid rule or too many rules", body = [FieldError]),
+        (status = 500, description = "The rule could not be persisted"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
+)]
+pub async fn add_vertiport_restriction_rule(
+    Extension(repos): Extension<Repositories>,
+    Extension(rules): Extension<RestrictionRules>,
+    Path(id): Path<String>,
+    Json(payload): Json<RestrictionRulePayload>,
+) -> Result<Json<RestrictionRule>, ValidationError> {
+    rest_info!("entry [{}].", id);
+    let id = parse_id("vertiport", &id).map_err(|status| (status, Json(vec![])))?;
+    let payload = validate_restriction_rule(payload).map_err(unprocessable)?;
+    check_vertiport(&repos, &id)
+        .await
+        .map_err(|status| (status, Json(vec![])))?;
+
+    let rule = rules.add(&id, payload, Utc::now()).map_err(|e| match e {
+        RestrictionError::State(e) => {
+            rest_error!("could not persist restriction rule: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+        }
+        _ => {
+            rest_error!(
+                "reached the maximum number of restriction rules of vertiport {}.",
+                id
+            );
+            unprocessable(vec![field_error(
+                "rules",
+                &format!("can not contain more than {MAX_RULES_PER_VERTIPORT} rules"),
+            )])
+        }
+    })?;
+
+    rest_info!(
+        "added restriction rule [{}] to vertiport [{}].",
+        rule.id,
+        id
+    );
+    Ok(Json(rule))
+}
+
+/// Get a restriction rule of a vertiport by its id.
+#[utoipa::path(
+    get,
+    path = "/assets/vertiports/{id}/restrictions/rules/{rule_id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Rule {rule_id} found", body = RestrictionRule),
+        (status = 400, description = "Invalid vertiport or rule id"),
+        (status = 404, description = "Rule not found"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+        ("rule_id" = String, Path, description = "Rule id"),
+    )
+)]
+pub async fn get_vertiport_restriction_rule(
+    Extension(rules): Extension<RestrictionRules>,
+    Path((id, rule_id)): Path<(String, String)>,
+) -> Result<Json<RestrictionRule>, StatusCode> {
+    rest_info!("entry [{}] [{}].", id, rule_id);
+    let id = parse_id("vertiport", &id)?;
+    let rule_id = parse_id("rule", &rule_id)?;
+
+    rules.get(&id, &rule_id).map(Json).ok_or_else(|| {
+        rest_info!(
+            "restriction rule {} of vertiport {} not found.",
+            rule_id,
+            id
+        );
+        StatusCode::NOT_FOUND
+    })
+}
+
+/// Replace the name, kind, schedule and limit of a restriction rule.
+#[utoipa::path(
+    put,
+    path = "/assets/vertiports/{id}/restrictions/rules/{rule_id}",
+    tag = "svc-assets",
+    request_body = RestrictionRulePayload,
+    responses(
+        (status = 200, description = "Rule updated; the updated rule is returned", body = RestrictionRule),
+        (status = 400, description = "Invalid vertiport or rule id"),
+        (status = 404, description = "Rule not found"),
+        (status = 422, description = "Invalid rule", body = [FieldError]),
+        (status = 500, description = "The rule could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+        ("rule_id" = String, Path, description = "Rule id"),
+    )
+)]
+pub async fn update_vertiport_restriction_rule(
+    Extension(rules): Extension<RestrictionRules>,
+    Path((id, rule_id)): Path<(String, String)>,
+    Json(payload): Json<RestrictionRulePayload>,
+) -> Result<Json<RestrictionRule>, ValidationError> {
+    rest_info!("entry [{}] [{}].", id, rule_id);
+    let id = parse_id("vertiport", &id).map_err(|status| (status, Json(vec![])))?;
+    let rule_id = parse_id("rule", &rule_id).map_err(|status| (status, Json(vec![])))?;
+    let payload = validate_restriction_rule(payload).map_err(unprocessable)?;
+
+    let rule = rules
+        .update(&id, &rule_id, payload, Utc::now())
+        .map_err(|e| (rule_error_status(&id, &rule_id, e), Json(vec![])))?;
+
+    Ok(Json(rule))
+}
+
+/// Remove a restriction rule of a vertiport.
+#[utoipa::path(
+    delete,
+    path = "/assets/vertiports/{id}/restrictions/rules/{rule_id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Rule removed"),
+        (status = 400, description = "Invalid vertiport or rule id"),
+        (status = 404, description = "Rule not found"),
+        (status = 500, description = "The rule could not be removed"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+        ("rule_id" = String, Path, description = "Rule id"),
+    )
+)]
+pub async fn remove_vertiport_restriction_rule(
+    Extension(rules): Extension<RestrictionRules>,
+    Path((id, rule_id)): Path<(String, String)>,
+) -> Result<(), StatusCode> {
+    rest_info!("entry [{}] [{}].", id, rule_id);
+    let id = parse_id("vertiport", &id)?;
+    let rule_id = parse_id("rule", &rule_id)?;
+
+    rules
+        .remove(&id, &rule_id)
+        .map_err(|e| rule_error_status(&id, &rule_id, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::VertiportDataBuilder;
+    use lib_common::time::DateTime;
+    use lib_common::uuid::Uuid;
+
+    /// Daily rule from 22:00 to 06:00 UTC
+    const NIGHT: &str = "DTSTART:20240101T220000Z;DURATION:PT8H\nRRULE:FREQ=DAILY";
+
+    fn payload(
+        kind: RestrictionKind,
+        max_movements_per_hour: Option<u32>,
+    ) -> RestrictionRulePayload {
+        RestrictionRulePayload {
+            name: " Night ".to_string(),
+            kind,
+            schedule: NIGHT.to_string(),
+            max_movements_per_hour,
+        }
+    }
+
+    #[test]
+    fn test_validate_restriction_rule() {
+        let rule = validate_restriction_rule(payload(RestrictionKind::Curfew, None)).unwrap();
+        assert_eq!(rule.name, "Night");
+        assert!(
+            validate_restriction_rule(payload(RestrictionKind::NoiseAbatement, Some(10))).is_ok()
+        );
+
+        let invalid = [
+            payload(RestrictionKind::Curfew, Some(10)),
+            payload(RestrictionKind::NoiseAbatement, None),
+            payload(RestrictionKind::NoiseAbatement, Some(0)),
+            payload(
+                RestrictionKind::NoiseAbatement,
+                Some(MAX_MOVEMENTS_PER_HOUR + 1),
+            ),
+        ];
+        for rule in invalid {
+            let errors = validate_restriction_rule(rule).unwrap_err();
+            assert_eq!(errors[0].field, "max_movements_per_hour");
+        }
+
+        let mut rule = payload(RestrictionKind::Curfew, None);
+        rule.name = " ".to_string();
+        rule.schedule = "invalid".to_string();
+        let errors = validate_restriction_rule(rule).unwrap_err();
+        assert_eq!(errors[0].field, "name");
+        assert_eq!(errors[1].field, "schedule");
+    }
+
+    #[tokio::test]
+    async fn test_vertiport_restrictions() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let vertiport_id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let rules = RestrictionRules::default();
+        let ext = || (Extension(repos.clone()), Extension(rules.clone()));
+
+        let (r, s) = ext();
+        let Json(rule) = add_vertiport_restriction_rule(
+            r,
+            s,
+            Path(vertiport_id.clone()),
+            Json(payload(RestrictionKind::Curfew, None)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(rule.vertiport_id, vertiport_id);
+
+        let (r, s) = ext();
+        let Json(list) = get_vertiport_restriction_rules(r, s, Path(vertiport_id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(list, vec![rule.clone()]);
+
+        let at = |value: &str| {
+            Query(RestrictionsQuery {
+                at: Some(value.parse::<DateTime<Utc>>().unwrap()),
+            })
+        };
+        let (r, s) = ext();
+        let Json(restrictions) = get_vertiport_restrictions(
+            r,
+            s,
+            Path(vertiport_id.clone()),
+            at("2024-06-04T23:00:00Z"),
+        )
+        .await
+        .unwrap();
+        assert!(restrictions.curfew);
+        assert_eq!(restrictions.active_rules[0].rule, rule);
+
+        let ids = || Path((vertiport_id.clone(), rule.id.clone()));
+        let Json(updated) = update_vertiport_restriction_rule(
+            Extension(rules.clone()),
+            ids(),
+            Json(payload(RestrictionKind::NoiseAbatement, Some(4))),
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.kind, RestrictionKind::NoiseAbatement);
+
+        let (r, s) = ext();
+        let Json(restrictions) = get_vertiport_restrictions(
+            r,
+            s,
+            Path(vertiport_id.clone()),
+            at("2024-06-04T23:00:00Z"),
+        )
+        .await
+        .unwrap();
+        assert!(!restrictions.curfew);
+        assert_eq!(restrictions.max_movements_per_hour, Some(4));
+
+        // invalid payloads and ids
+        let (status, errors) = update_vertiport_restriction_rule(
+            Extension(rules.clone()),
+            ids(),
+            Json(payload(RestrictionKind::NoiseAbatement, None)),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "max_movements_per_hour");
+
+        let (r, s) = ext();
+        let error = get_vertiport_restrictions(
+            r,
+            s,
+            Path(Uuid::new_v4().to_string()),
+            Query(RestrictionsQuery::default()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        let (r, s) = ext();
+        let (status, _) = add_vertiport_restriction_rule(
+            r,
+            s,
+            Path("invalid".to_string()),
+            Json(payload(RestrictionKind::Curfew, None)),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        remove_vertiport_restriction_rule(Extension(rules.clone()), ids())
+            .await
+            .unwrap();
+        let error = get_vertiport_restriction_rule(Extension(rules.clone()), ids())
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,290 @@
+//! Handlers for exporting and importing asset snapshots.
+
+pub use super::rest_types::SnapshotImportSummary;
+
+use super::errors::{storage_error_status, RegistrationError};
+use crate::fan_out::FanOut;
+use crate::repo::Repositories;
+use crate::saga::Saga;
+use crate::snapshot::{
+    encode_header, encode_record, SnapshotDecoder, SnapshotRecord, CONTENT_TYPE,
+};
+use axum::{
+    body::StreamBody,
+    extract::BodyStream,
+    http::header,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use futures::{stream, StreamExt};
+use hyper::StatusCode;
+use std::collections::HashMap;
+use svc_storage_client_grpc::prelude::*;
+
+/// Export all assets as a snapshot.
+///
+/// Vertiports, vertipads and aircraft which have not been removed are
+/// written to a binary snapshot, which is streamed to the client.
+#[utoipa::path(
+    get,
+    path = "/assets/snapshot",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Snapshot of all assets", content_type = "application/x-protobuf", body = Vec<u8>),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    )
+)]
+pub async fn export_snapshot(
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+) -> Result<Response, StatusCode> {
+    rest_info!("entry.");
+
+    let filter = || AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+    let (vertiports, vertipads, aircraft) = tokio::join!(
+        fan_out.call(repos.vertiports.search(filter())),
+        fan_out.call(repos.vertipads.search(filter())),
+        fan_out.call(repos.aircraft.search(filter())),
+    );
+    let vertiports = vertiports.map_err(|e| {
+        rest_error!("could not retrieve vertiports: {e}.");
+        storage_error_status(&e)
+    })?;
+    let vertipads = vertipads.map_err(|e| {
+        rest_error!("could not retrieve vertipads: {e}.");
+        storage_error_status(&e)
+    })?;
+    let aircraft = aircraft.map_err(|e| {
+        rest_error!("could not retrieve vehicles: {e}.");
+        storage_error_status(&e)
+    })?;
+
+    rest_info!(
+        "exporting {} vertiports, {} vertipads and {} aircraft.",
+        vertiports.len(),
+        vertipads.len(),
+        aircraft.len()
+    );
+
+    // Vertiports must be written before the vertipads referring to them
+    let records = vertiports
+        .into_iter()
+        .map(SnapshotRecord::Vertiport)
+        .chain(vertipads.into_iter().map(SnapshotRecord::Vertipad))
+        .chain(aircraft.into_iter().map(SnapshotRecord::Aircraft));
+    let chunks = stream::iter(
+        std::iter::once(encode_header())
+            .chain(records.map(|record| encode_record(&record)))
+            .map(Ok::<_, std::io::Error>),
+    );
+
+    Ok((
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        StreamBody::new(chunks),
+    )
+        .into_response())
+}
+
+/// Import assets from a snapshot.
+///
+/// Every asset in the snapshot is registered as a new asset. Vertipads are
+/// linked to the vertiports imported from the same snapshot. The snapshot is
+/// imported while it is received; if the snapshot turns out to be invalid or
+/// an asset can not be registered, the assets imported before the error are
+/// removed again. The ones which could not be removed are returned, see
+/// [`RegistrationError`].
+#[utoipa::path(
+    post,
+    path = "/assets/snapshot",
+    tag = "svc-assets",
+    request_body(content = Vec<u8>, content_type = "application/x-protobuf"),
+    responses(
+        (status = 200, description = "Number of assets imported", body = SnapshotImportSummary),
+        (status = 400, description = "Invalid snapshot; if assets were created, the ones which could not be removed again are returned", body = super::errors::IncompleteRegistration),
+        (status = 503, description = "Could not connect to other microservice dependencies; if assets were created, the ones which could not be removed again are returned", body = super::errors::IncompleteRegistration)
+    )
+)]
+pub async fn import_snapshot(
+    Extension(repos): Extension<Repositories>,
+    body: BodyStream,
+) -> Result<Json<SnapshotImportSummary>, RegistrationError> {
+    rest_info!("entry.");
+
+    let mut importer = SnapshotImporter {
+        saga: Saga::new("import snapshot", &repos),
+        vertiport_ids: HashMap::new(),
+        summary: SnapshotImportSummary::default(),
+    };
+
+    if let Err(status) = importer.import_all(body).await {
+        let leftover = importer.saga.compensate().await;
+        return Err(RegistrationError::Incomplete(status, leftover));
+    }
+
+    rest_info!("imported snapshot: {:?}", importer.summary);
+    Ok(Json(importer.summary))
+}
+
+/// Registers the records of a snapshot
+struct SnapshotImporter {
+    /// The imported assets, removed again if the import fails
+    saga: Saga,
+    /// New ids of the imported vertiports, by their id in the snapshot
+    vertiport_ids: HashMap<String, String>,
+    summary: SnapshotImportSummary,
+}
+
+impl SnapshotImporter {
+    /// Register the records of a snapshot while it is received.
+    async fn import_all(&mut self, mut body: BodyStream) -> Result<(), StatusCode> {
+        let mut decoder = SnapshotDecoder::default();
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk.map_err(|e| {
+                rest_error!("could not read snapshot: {e}.");
+                StatusCode::BAD_REQUEST
+            })?;
+            decoder.push(&chunk);
+
+            while let Some(record) = decoder.next_record().map_err(|e| {
+                rest_error!("invalid snapshot: {e}.");
+                StatusCode::BAD_REQUEST
+            })? {
+                self.import(record).await?;
+            }
+        }
+
+        decoder.finish().map_err(|e| {
+            rest_error!("invalid snapshot: {e}.");
+            StatusCode::BAD_REQUEST
+        })
+    }
+
+    /// Register a single record.
+    async fn import(&mut self, record: SnapshotRecord) -> Result<(), StatusCode> {
+        let insert_error = |e: tonic::Status| {
+            rest_error!("could not import snapshot record: {e}.");
+            storage_error_status(&e)
+        };
+
+        match record {
+            SnapshotRecord::Vertiport(vertiport::Object { id, data, .. }) => {
+                let new_id = self
+                    .saga
+                    .insert_vertiport(data.unwrap_or_default())
+                    .await
+                    .map_err(insert_error)?;
+                self.vertiport_ids.insert(id, new_id);
+                self.summary.vertiports += 1;
+            }
+            SnapshotRecord::Vertipad(vertipad::Object { data, .. }) => {
+                let mut data = data.unwrap_or_default();
+                if let Some(id) = self.vertiport_ids.get(&data.vertiport_id) {
+                    data.vertiport_id = id.clone();
+                }
+
+                self.saga
+                    .insert_vertipad(data)
+                    .await
+                    .map_err(insert_error)?;
+                self.summary.vertipads += 1;
+            }
+            SnapshotRecord::Aircraft(vehicle::Object { data, .. }) => {
+                self.saga
+                    .insert_aircraft(data.unwrap_or_default())
+                    .await
+                    .map_err(insert_error)?;
+                self.summary.aircraft += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::{FromRequest, RequestParts};
+
+    #[tokio::test]
+    async fn test_snapshot_export_import() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let source = Repositories::memory();
+        let vertiport = source
+            .vertiports
+            .insert(vertiport::mock::get_data_obj())
+            .await
+            .unwrap();
+        let mut vertipad = vertipad::mock::get_data_obj();
+        vertipad.vertiport_id = vertiport.id.clone();
+        source.vertipads.insert(vertipad).await.unwrap();
+        source
+            .aircraft
+            .insert(vehicle::mock::get_data_obj())
+            .await
+            .unwrap();
+
+        let fan_out = FanOut::new(crate::Config::default().into());
+        let response = export_snapshot(Extension(source), Extension(fan_out))
+            .await
+            .unwrap();
+        let snapshot = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+        let target = Repositories::memory();
+        let request = hyper::Request::new(hyper::Body::from(snapshot.clone()));
+        let body = BodyStream::from_request(&mut RequestParts::new(request))
+            .await
+            .unwrap();
+        let summary = import_snapshot(Extension(target.clone()), body)
+            .await
+            .unwrap();
+        assert_eq!(
+            summary.0,
+            SnapshotImportSummary {
+                vertiports: 1,
+                vertipads: 1,
+                aircraft: 1,
+            }
+        );
+
+        // the vertipad refers to the imported vertiport
+        let filter = || AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+        let vertiport_id = target.vertiports.search(filter()).await.unwrap()[0]
+            .id
+            .clone();
+        let vertipads = target.vertipads.search(filter()).await.unwrap();
+        assert_eq!(
+            vertipads[0].data.as_ref().unwrap().vertiport_id,
+            vertiport_id
+        );
+
+        let request = hyper::Request::new(hyper::Body::from("invalid"));
+        let body = BodyStream::from_request(&mut RequestParts::new(request))
+            .await
+            .unwrap();
+        let error = import_snapshot(Extension(target), body).await.unwrap_err();
+        assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+
+        // the assets of a truncated snapshot are removed again
+        let truncated = snapshot.slice(..snapshot.len() - 1);
+        let request = hyper::Request::new(hyper::Body::from(truncated));
+        let body = BodyStream::from_request(&mut RequestParts::new(request))
+            .await
+            .unwrap();
+        let target = Repositories::memory();
+        let error = import_snapshot(Extension(target.clone()), body)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error,
+            RegistrationError::Incomplete(StatusCode::BAD_REQUEST, vec![])
+        );
+        assert!(target.vertiports.search(filter()).await.unwrap().is_empty());
+        assert!(target.vertipads.search(filter()).await.unwrap().is_empty());
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,360 @@
+//! Payload validation helpers shared by the register and update handlers.
+
+pub use super::rest_types::{FieldError, UpdateResult};
+
+use crate::rest::json::Json;
+use crate::schedule::Schedule;
+use hyper::StatusCode;
+use lib_common::time::{DateTime, Utc};
+use lib_common::uuid::to_uuid;
+use serde::Serialize;
+use svc_storage_client_grpc::prelude::{GeoPointZ, GeoPolygonZ};
+
+/// Lowest accepted altitude in meters, below the lowest land on earth
+pub const MIN_ALTITUDE_M: f64 = -500.0;
+
+/// Highest accepted altitude in meters, above the highest mountain on earth
+pub const MAX_ALTITUDE_M: f64 = 9000.0;
+
+/// Error type returned by the validate handlers.
+pub type ValidationError = (StatusCode, Json<Vec<FieldError>>);
+
+/// Create a new [`FieldError`] for the given field.
+pub fn field_error(field: &str, message: &str) -> FieldError {
+    FieldError {
+        field: field.to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// Trim leading and trailing whitespace of a string in place.
+pub fn normalize_string(value: &mut String) {
+    let trimmed = value.trim();
+    if trimmed.len() != value.len() {
+        *value = trimmed.to_string();
+    }
+}
+
+/// Trim an optional string in place, replacing empty strings with [`None`].
+pub fn normalize_optional_string(value: &mut Option<String>) {
+    if let Some(inner) = value.as_mut() {
+        normalize_string(inner);
+        if inner.is_empty() {
+            *value = None;
+        }
+    }
+}
+
+/// Add an error if the provided value is empty.
+pub fn check_not_empty(errors: &mut Vec<FieldError>, field: &str, value: &str) {
+    if value.trim().is_empty() {
+        errors.push(field_error(field, "must not be empty"));
+    }
+}
+
+/// Add an error if the provided value is not a valid UUID.
+pub fn check_uuid(errors: &mut Vec<FieldError>, field: &str, value: &str) {
+    if to_uuid(value).is_none() {
+        errors.push(field_error(field, "must be a valid UUID"));
+    }
+}
+
+/// Add an error if the provided value is set but not a valid UUID.
+pub fn check_optional_uuid(errors: &mut Vec<FieldError>, field: &str, value: &Option<String>) {
+    if let Some(value) = value {
+        check_uuid(errors, field, value);
+    }
+}
+
+/// Add an error if the provided value is set but not a valid schedule.
+pub fn check_optional_schedule(errors: &mut Vec<FieldError>, field: &str, value: &Option<String>) {
+    if let Some(Err(e)) = value.as_deref().map(str::parse::<Schedule>) {
+        errors.push(field_error(field, &e.to_string()));
+    }
+}
+
+/// Add an error if the provided point does not contain valid coordinates.
+///
+/// `x` is the longitude and `y` is the latitude, both in degrees; `z` is the
+/// altitude in meters above mean sea level.
+pub fn check_geo_point(errors: &mut Vec<FieldError>, field: &str, point: &GeoPointZ) {
+    if !(-180.0..=180.0).contains(&point.x) {
+        errors.push(field_error(
+            field,
+            "longitude (x) must be between -180 and 180 degrees",
+        ));
+    }
+
+    if !(-90.0..=90.0).contains(&point.y) {
+        errors.push(field_error(
+            field,
+            "latitude (y) must be between -90 and 90 degrees",
+        ));
+    }
+
+    if !(MIN_ALTITUDE_M..=MAX_ALTITUDE_M).contains(&point.z) {
+        errors.push(field_error(
+            field,
+            &format!("altitude (z) must be between {MIN_ALTITUDE_M} and {MAX_ALTITUDE_M} meters"),
+        ));
+    }
+}
+
+/// Add an error if the provided polygon is not a valid area.
+///
+/// Each ring must be closed and contain at least 4 points.
+pub fn check_geo_polygon(errors: &mut Vec<FieldError>, field: &str, polygon: &GeoPolygonZ) {
+    for (index, ring) in polygon.rings.iter().enumerate() {
+        let ring_field = format!("{field}.rings[{index}]");
+        if ring.points.len() < 4 {
+            errors.push(field_error(&ring_field, "must contain at least 4 points"));
+            continue;
+        }
+
+        if ring.points.first() != ring.points.last() {
+            errors.push(field_error(
+                &ring_field,
+                "must be closed (first and last point equal)",
+            ));
+        }
+
+        for point in ring.points.iter() {
+            check_geo_point(errors, &ring_field, point);
+        }
+    }
+}
+
+/// Convert collected errors into a [`Result`].
+///
+/// Returns the provided value if no errors were found.
+pub fn into_result<T>(value: T, errors: Vec<FieldError>) -> Result<T, Vec<FieldError>> {
+    match errors.is_empty() {
+        true => Ok(value),
+        false => Err(errors),
+    }
+}
+
+/// Map a list of field errors to an `UNPROCESSABLE_ENTITY` response.
+pub fn unprocessable(errors: Vec<FieldError>) -> ValidationError {
+    rest_info!("payload validation failed: {:?}", errors);
+    (StatusCode::UNPROCESSABLE_ENTITY, Json(errors))
+}
+
+/// Compare the `expected_updated_at` of an update payload with the
+/// `updated_at` of the stored record.
+///
+/// Returns `CONFLICT` if the record was updated since, updates without an
+/// expected timestamp are always accepted.
+pub fn check_expected_updated_at(
+    expected: Option<DateTime<Utc>>,
+    stored: Option<DateTime<Utc>>,
+) -> Result<(), StatusCode> {
+    match expected {
+        Some(expected) if stored != Some(expected) => {
+            rest_info!(
+                "record was updated since {}, stored updated_at: {:?}.",
+                expected,
+                stored
+            );
+            Err(StatusCode::CONFLICT)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check if applying an update changes any of the fields in the mask.
+///
+/// The stored and updated records are compared field by field in their JSON
+/// form, fields which can not be compared are considered changed.
+pub fn masked_fields_changed<T: Serialize>(stored: &T, updated: &T, mask: &[String]) -> bool {
+    let (Ok(stored), Ok(updated)) = (serde_json::to_value(stored), serde_json::to_value(updated))
+    else {
+        return true;
+    };
+
+    mask.iter()
+        .any(|field| stored.get(field) != updated.get(field))
+}
+
+/// Log and return the result of an update which did not change anything.
+pub fn unchanged() -> UpdateResult {
+    rest_info!("update does not change any field, skipping storage update.");
+    UpdateResult { changed: false }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use svc_storage_client_grpc::prelude::GeoLineStringZ;
+
+    fn point(x: f64, y: f64) -> GeoPointZ {
+        GeoPointZ { x, y, z: 0.0 }
+    }
+
+    #[test]
+    fn test_normalize_strings() {
+        let mut value = "  N12345 ".to_string();
+        normalize_string(&mut value);
+        assert_eq!(value, "N12345");
+
+        let mut value = Some("   ".to_string());
+        normalize_optional_string(&mut value);
+        assert_eq!(value, None);
+
+        let mut value = Some(" description".to_string());
+        normalize_optional_string(&mut value);
+        assert_eq!(value, Some("description".to_string()));
+    }
+
+    #[test]
+    fn test_check_uuid() {
+        let mut errors = vec![];
+        check_uuid(&mut errors, "id", "invalid");
+        check_optional_uuid(&mut errors, "group_id", &Some("invalid".to_string()));
+        check_optional_uuid(&mut errors, "hangar_id", &None);
+        check_uuid(
+            &mut errors,
+            "id",
+            &lib_common::uuid::Uuid::new_v4().to_string(),
+        );
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].field, "id");
+        assert_eq!(errors[1].field, "group_id");
+    }
+
+    #[test]
+    fn test_check_optional_schedule() {
+        let mut errors = vec![];
+        check_optional_schedule(
+            &mut errors,
+            "schedule",
+            &Some("DTSTART:20221020T180000Z;DURATION:PT14H".to_string()),
+        );
+        check_optional_schedule(&mut errors, "schedule", &None);
+        assert!(errors.is_empty());
+
+        check_optional_schedule(&mut errors, "schedule", &Some("invalid".to_string()));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "schedule");
+    }
+
+    #[test]
+    fn test_check_geo_point() {
+        let mut errors = vec![];
+        check_geo_point(&mut errors, "geo_location", &point(4.9, 52.3));
+        assert!(errors.is_empty());
+
+        check_geo_point(&mut errors, "geo_location", &point(181.0, -91.0));
+        assert_eq!(errors.len(), 2);
+
+        errors.clear();
+        let mut rooftop = point(4.9, 52.3);
+        rooftop.z = 120.5;
+        check_geo_point(&mut errors, "geo_location", &rooftop);
+        assert!(errors.is_empty());
+
+        for z in [-501.0, 9000.5, f64::NAN] {
+            rooftop.z = z;
+            check_geo_point(&mut errors, "geo_location", &rooftop);
+        }
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].message.starts_with("altitude (z)"));
+    }
+
+    #[test]
+    fn test_check_geo_polygon() {
+        let mut errors = vec![];
+        let ring = GeoLineStringZ {
+            points: vec![
+                point(4.0, 52.0),
+                point(4.1, 52.0),
+                point(4.1, 52.1),
+                point(4.0, 52.0),
+            ],
+        };
+        let polygon = GeoPolygonZ {
+            rings: vec![ring.clone()],
+        };
+        check_geo_polygon(&mut errors, "geo_location", &polygon);
+        assert!(errors.is_empty());
+
+        // open ring
+        let mut open = ring.clone();
+        open.points.push(point(4.0, 52.1));
+        check_geo_polygon(
+            &mut errors,
+            "geo_location",
+            &GeoPolygonZ { rings: vec![open] },
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "geo_location.rings[0]");
+
+        // too few points
+        let mut errors = vec![];
+        let short = GeoLineStringZ {
+            points: vec![point(4.0, 52.0), point(4.0, 52.0)],
+        };
+        check_geo_polygon(
+            &mut errors,
+            "geo_location",
+            &GeoPolygonZ { rings: vec![short] },
+        );
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_into_result() {
+        assert_eq!(into_result(1, vec![]), Ok(1));
+        let errors = vec![field_error("name", "must not be empty")];
+        assert_eq!(into_result(1, errors.clone()), Err(errors));
+    }
+
+    #[test]
+    fn test_check_expected_updated_at() {
+        let stored = Utc::now();
+        let earlier = stored - chrono::Duration::seconds(1);
+
+        assert_eq!(check_expected_updated_at(None, Some(stored)), Ok(()));
+        assert_eq!(check_expected_updated_at(None, None), Ok(()));
+        assert_eq!(
+            check_expected_updated_at(Some(stored), Some(stored)),
+            Ok(())
+        );
+        assert_eq!(
+            check_expected_updated_at(Some(earlier), Some(stored)),
+            Err(StatusCode::CONFLICT)
+        );
+        assert_eq!(
+            check_expected_updated_at(Some(stored), None),
+            Err(StatusCode::CONFLICT)
+        );
+    }
+
+    #[test]
+    fn test_masked_fields_changed() {
+        let stored = crate::testing::VertiportDataBuilder::new()
+            .name("Vertiport")
+            .build();
+        let mut updated = stored.clone();
+        updated.description = "changed".to_string();
+        let mask = |fields: &[&str]| -> Vec<String> {
+            fields.iter().map(|field| field.to_string()).collect()
+        };
+
+        assert!(!masked_fields_changed(&stored, &stored, &mask(&["name"])));
+        // fields outside of the mask are not written
+        assert!(!masked_fields_changed(&stored, &updated, &mask(&["name"])));
+        assert!(masked_fields_changed(
+            &stored,
+            &updated,
+            &mask(&["name", "description"])
+        ));
+
+        updated.geo_location = None;
+        assert!(masked_fields_changed(
+            &stored,
+            &updated,
+            &mask(&["geo_location"])
+        ));
+    }
+}
@@ -1,19 +1,74 @@
 //! Handlers for the vertipad API endpoints.
 
-pub use super::rest_types::UpdateVertipadPayload;
-
+pub use super::rest_types::{
+    AvailabilityQuery, GeoPoint, OccupancyLogQuery, OccupancyTransition, PadClassification,
+    TimeWindow, UpdateVertipadPayload, VertipadAvailability, VertipadClassificationQuery,
+};
+
+use super::batch::{batch_get, parse_batch_ids, BatchGetPayload, VertipadBatch};
+use super::conversion::{convert_objects, ListError, Listed, PartialList, SkippedObjects};
+use super::errors::storage_error_status;
+use super::list::{list_filter, matches_list_query, owner_filter, ListAssetsQuery, ListLimits};
+use super::merge_patch::merge_patch_payload;
+use super::methods::{asset_headers, AssetHeaders};
+use super::validation::*;
+
+use crate::archive::RemovedAssets;
+use crate::config::{ScheduleConflictMode, SharedConfig};
+use crate::daylight::night_windows;
+use crate::geo::distance_to_polygon_m;
+use crate::rest::chargers::{VertipadChargerQuery, VertipadChargers};
+use crate::rest::classification::{validate_pad_classification, PadClassifications};
+use crate::rest::equipment::VertipadEquipment;
+use crate::rest::json::Json;
+use crate::rest::locale::{
+    accept_languages, localized, validate_localization, AssetLocalization, Localizations,
+};
+use crate::rest::occupancy::{OccupancyLog, MAX_OCCUPANCY_LOG_LEN};
+use crate::rest::quota::{OperatorQuotas, QuotaKind};
 use crate::rest::structs::{AssetStatus, Basics};
-use axum::{extract::Path, Extension, Json};
+use crate::schedule::{
+    schedule_conflicts, subtract_window, subtract_windows, OccurrenceBudget, Schedule,
+    ScheduleError,
+};
+use axum::{
+    extract::{Path, Query},
+    http::HeaderMap,
+    Extension,
+};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
 use hyper::StatusCode;
 use svc_storage_client_grpc::prelude::*;
 use svc_storage_client_grpc::resources::vertipad;
 
-use crate::grpc::client::GrpcClients;
+use crate::fan_out::FanOut;
+use crate::ops_status::VertiportOpsStatuses;
+use crate::repo::Repositories;
 use lib_common::uuid::to_uuid;
 
 /// The default name for a vertipad
 const VERTIPAD_NAME: &str = "Unnamed Vertipad";
 
+/// Maximum time range, in days, the availability can be requested for
+const MAX_AVAILABILITY_RANGE_DAYS: i64 = 31;
+
+/// Days from now a vertipad schedule is checked against the schedule of its
+/// vertiport
+const SCHEDULE_CONFLICT_HORIZON_DAYS: i64 = 28;
+
+/// Fields of a [`Vertipad`] which can be changed with a merge patch
+const VERTIPAD_PATCH_FIELDS: &[&str] = &[
+    "name",
+    "vertiport_id",
+    "geo_location",
+    "enabled",
+    "occupied",
+    "schedule",
+];
+
+/// Fields of a [`Vertipad`] which can be removed with a merge patch
+const VERTIPAD_NULLABLE_FIELDS: &[&str] = &["schedule"];
+
 /// A struct representing a vertipad (a vertical landing pad).
 ///
 /// A vertipad is a landing pad that is used for vertical takeoff and
@@ -33,10 +88,18 @@ pub struct Vertipad {
     pub occupied: bool,
 
     /// The geographical location of the vertipad.
-    pub geo_location: GeoPointZ,
+    pub geo_location: GeoPoint,
 
     /// The schedule of the vertipad.
     pub schedule: Option<String>,
+
+    /// Localized names of the vertipad, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub localization: Option<AssetLocalization>,
+
+    /// Classification of the vertipad, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classification: Option<PadClassification>,
 }
 
 impl Vertipad {
@@ -50,6 +113,56 @@ impl Vertipad {
             None => VERTIPAD_NAME.to_string(),
         }
     }
+
+    /// Get the vertipad's name in the most preferred of the provided languages.
+    ///
+    /// Falls back to [`Vertipad::full_name`] if no localized name is available.
+    pub fn localized_name(&self, languages: &[String]) -> String {
+        self.localization
+            .as_ref()
+            .and_then(|localization| localized(&localization.names, languages))
+            .map(str::to_string)
+            .unwrap_or_else(|| self.full_name())
+    }
+
+    /// Apply the localization for the provided languages.
+    ///
+    /// The name is replaced by its localized version, if available.
+    pub fn localize(&mut self, localization: AssetLocalization, languages: &[String]) {
+        self.localization = Some(localization);
+        self.basics.name = Some(self.localized_name(languages));
+    }
+
+    /// Add the classification of the vertipad, if it was classified.
+    pub fn apply_classification(mut self, classifications: &PadClassifications) -> Self {
+        self.classification = classifications.get(&self.basics.id);
+        self
+    }
+
+    /// Add the operator owning the vertipad, the owner of its vertiport, if
+    /// it is known.
+    pub fn apply_owner(mut self, quotas: &OperatorQuotas) -> Self {
+        self.basics.owner_id = quotas.owner(QuotaKind::Vertiport, &self.vertiport_id);
+        self
+    }
+
+    /// Check if the vertipad is classified for aircraft of at least
+    /// `weight_kg`. Unclassified vertipads are not.
+    pub fn takes_weight(&self, weight_kg: f64) -> bool {
+        self.classification
+            .map(|classification| classification.max_aircraft_weight_kg >= weight_kg)
+            .unwrap_or(false)
+    }
+
+    /// Disable the vertipad and mark it unavailable if the operational
+    /// status of its vertiport currently disables its vertipads.
+    pub fn apply_ops_status(mut self, ops_statuses: &VertiportOpsStatuses) -> Self {
+        if ops_statuses.disables_vertipads(&self.vertiport_id, Utc::now()) {
+            self.enabled = false;
+            self.basics.status = AssetStatus::Unavailable;
+        }
+        self
+    }
 }
 
 impl TryFrom<vertipad::Object> for Vertipad {
@@ -74,23 +187,489 @@ impl TryFrom<vertipad::Object> for Vertipad {
                     .updated_at
                     .ok_or("(try_from) updated_at field empty but expected.")?
                     .into(),
+                deleted_at: None,
+                owner_id: None,
                 whitelist: Vec::new(),
                 status: AssetStatus::Available,
             },
             geo_location: data
                 .geo_location
-                .ok_or("(try_from) geo_location empty but expected.")?,
+                .ok_or("(try_from) geo_location empty but expected.")?
+                .into(),
             vertiport_id: data.vertiport_id,
             enabled: data.enabled,
             occupied: data.occupied,
             schedule: data.schedule,
+            localization: None,
+            classification: None,
+        })
+    }
+}
+
+/// Validate and normalize a [`vertipad::Data`] object.
+///
+/// Only the payload itself is checked, storage side checks are done
+/// separately by the handlers.
+pub fn validate_vertipad_data(mut data: vertipad::Data) -> Result<vertipad::Data, Vec<FieldError>> {
+    normalize_string(&mut data.name);
+    normalize_string(&mut data.vertiport_id);
+    normalize_optional_string(&mut data.schedule);
+
+    let mut errors = vec![];
+    check_not_empty(&mut errors, "name", &data.name);
+    check_uuid(&mut errors, "vertiport_id", &data.vertiport_id);
+    check_optional_schedule(&mut errors, "schedule", &data.schedule);
+    match &data.geo_location {
+        Some(geo_location) => check_geo_point(&mut errors, "geo_location", geo_location),
+        None => errors.push(field_error("geo_location", "is required")),
+    }
+
+    into_result(data, errors)
+}
+
+/// Validate and normalize an [`UpdateVertipadPayload`].
+///
+/// Only the payload itself is checked, storage side checks are done
+/// separately by the handlers.
+pub fn validate_update_vertipad_payload(
+    mut payload: UpdateVertipadPayload,
+) -> Result<UpdateVertipadPayload, Vec<FieldError>> {
+    normalize_string(&mut payload.id);
+    normalize_optional_string(&mut payload.schedule);
+
+    let mut errors = vec![];
+    check_uuid(&mut errors, "id", &payload.id);
+    check_optional_uuid(&mut errors, "vertiport_id", &payload.vertiport_id);
+    check_optional_schedule(&mut errors, "schedule", &payload.schedule);
+
+    if let Some(name) = payload.name.as_mut() {
+        normalize_string(name);
+        check_not_empty(&mut errors, "name", name);
+    }
+
+    if let Some(geo_location) = &payload.geo_location {
+        check_geo_point(&mut errors, "geo_location", &(*geo_location).into());
+    }
+
+    into_result(payload, errors)
+}
+
+/// Check if the vertipad name is already in use at the given vertiport.
+///
+/// The vertipad with the provided `id` (if any) is excluded from the check.
+async fn check_vertipad_name(
+    repos: &Repositories,
+    vertiport_id: &str,
+    name: &str,
+    id: Option<&str>,
+) -> Result<Option<FieldError>, StatusCode> {
+    let filter =
+        AdvancedSearchFilter::search_equals("vertiport_id".to_string(), vertiport_id.to_string())
+            .and_equals("name".to_string(), name.to_string())
+            .and_is_null("deleted_at".to_string());
+
+    let in_use = repos
+        .vertipads
+        .search(filter)
+        .await
+        .map_err(|e| {
+            rest_error!("could not search vertipads: {e}.");
+            storage_error_status(&e)
+        })?
+        .into_iter()
+        .filter(|object| Some(object.id.as_str()) != id)
+        .any(|object| {
+            object
+                .data
+                .map(|data| data.vertiport_id == vertiport_id && data.name == name)
+                .unwrap_or(false)
+        });
+
+    Ok(in_use.then(|| field_error("name", "already in use at this vertiport")))
+}
+
+/// Check if the vertiport referenced by a vertipad exists and is not deleted.
+async fn check_vertiport_exists(
+    repos: &Repositories,
+    vertiport_id: &str,
+) -> Result<Option<FieldError>, StatusCode> {
+    let filter =
+        AdvancedSearchFilter::search_equals("vertiport_id".to_string(), vertiport_id.to_string())
+            .and_is_null("deleted_at".to_string());
+
+    let exists = repos
+        .vertiports
+        .search(filter)
+        .await
+        .map_err(|e| {
+            rest_error!("could not search vertiports: {e}.");
+            storage_error_status(&e)
+        })?
+        .into_iter()
+        .any(|object| object.id == vertiport_id);
+
+    Ok((!exists).then(|| field_error("vertiport_id", "vertiport does not exist")))
+}
+
+/// Get the windows where a vertipad schedule allows operation while its
+/// vertiport is closed, within [`SCHEDULE_CONFLICT_HORIZON_DAYS`] days from
+/// `now`.
+///
+/// A vertipad without a schedule follows the schedule of its vertiport, and
+/// a vertiport without a schedule is always open, so neither conflicts.
+/// The occurrences evaluated are taken from the `budget` of the request.
+pub fn vertipad_schedule_conflicts(
+    vertipad_schedule: Option<&str>,
+    vertiport_schedule: Option<&str>,
+    now: DateTime<Utc>,
+    budget: &mut OccurrenceBudget,
+) -> Result<Vec<TimeWindow>, ScheduleError> {
+    let (vertipad_schedule, vertiport_schedule) = match (vertipad_schedule, vertiport_schedule) {
+        (Some(vertipad_schedule), Some(vertiport_schedule)) => {
+            (vertipad_schedule, vertiport_schedule)
+        }
+        _ => return Ok(vec![]),
+    };
+
+    let vertipad_schedule = vertipad_schedule.parse::<Schedule>()?;
+    let vertiport_schedule = vertiport_schedule.parse::<Schedule>()?;
+    schedule_conflicts(
+        &vertipad_schedule,
+        &vertiport_schedule,
+        now,
+        now + Duration::days(SCHEDULE_CONFLICT_HORIZON_DAYS),
+        budget,
+    )
+}
+
+/// Describe the windows a vertipad is open while its vertiport is closed.
+pub fn schedule_conflict_message(conflicts: &[TimeWindow]) -> String {
+    let windows = conflicts
+        .iter()
+        .map(|window| {
+            format!(
+                "{}/{}",
+                window.start.to_rfc3339_opts(SecondsFormat::Secs, true),
+                window.end.to_rfc3339_opts(SecondsFormat::Secs, true)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    format!("allows operation while the vertiport is closed: {windows}")
+}
+
+/// Check if a vertipad schedule allows operation while the vertiport is
+/// closed.
+///
+/// Conflicts are rejected in [`ScheduleConflictMode::Strict`], and only
+/// logged as a warning in [`ScheduleConflictMode::Lenient`]. Vertiport
+/// schedules which can not be parsed are not checked.
+async fn check_vertipad_schedule(
+    repos: &Repositories,
+    vertiport_id: &str,
+    schedule: Option<&str>,
+    mode: ScheduleConflictMode,
+) -> Result<Option<FieldError>, StatusCode> {
+    if schedule.is_none() {
+        return Ok(None);
+    }
+
+    let vertiport_schedule = repos
+        .vertiports
+        .get_by_id(vertiport_id.to_string())
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve vertiport: {e}");
+            storage_error_status(&e)
+        })?
+        .data
+        .and_then(|data| data.schedule);
+
+    let conflicts = match vertipad_schedule_conflicts(
+        schedule,
+        vertiport_schedule.as_deref(),
+        Utc::now(),
+        &mut OccurrenceBudget::default(),
+    ) {
+        Ok(conflicts) => conflicts,
+        Err(e) => {
+            rest_warn!("schedule conflicts with vertiport {vertiport_id} not checked: {e}");
+            return Ok(None);
+        }
+    };
+
+    if conflicts.is_empty() {
+        return Ok(None);
+    }
+
+    let message = schedule_conflict_message(&conflicts);
+    match mode {
+        ScheduleConflictMode::Strict => Ok(Some(field_error("schedule", &message))),
+        ScheduleConflictMode::Lenient => {
+            rest_warn!("vertipad schedule at vertiport {vertiport_id} {message}.");
+            Ok(None)
+        }
+    }
+}
+
+/// Check if a vertipad lies within the area of its vertiport, or at most
+/// `tolerance_m` meters outside of it.
+///
+/// Vertiports without an area are not checked.
+async fn check_vertipad_location(
+    repos: &Repositories,
+    vertiport_id: &str,
+    location: &GeoPointZ,
+    tolerance_m: f64,
+) -> Result<Option<FieldError>, StatusCode> {
+    let area = repos
+        .vertiports
+        .get_by_id(vertiport_id.to_string())
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve vertiport: {e}");
+            storage_error_status(&e)
+        })?
+        .data
+        .and_then(|data| data.geo_location);
+
+    let Some(distance_m) = area.and_then(|area| distance_to_polygon_m(location, &area)) else {
+        return Ok(None);
+    };
+
+    if distance_m <= tolerance_m {
+        return Ok(None);
+    }
+
+    rest_info!("vertipad lies {distance_m:.1} m outside vertiport {vertiport_id}.");
+    Ok(Some(field_error(
+        "geo_location",
+        &format!(
+            "lies {distance_m:.0} m outside the area of vertiport {vertiport_id}, more than the tolerance of {tolerance_m} m"
+        ),
+    )))
+}
+
+/// Checks of vertipad registrations and updates which depend on the
+/// configuration
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VertipadChecks {
+    /// Handling of schedules allowing operation while the vertiport is closed
+    pub schedule_conflict_mode: ScheduleConflictMode,
+    /// Maximum distance in meters a vertipad may lie outside the area of its
+    /// vertiport, [`None`] to not check the location
+    pub location_tolerance_m: Option<f64>,
+}
+
+impl VertipadChecks {
+    /// Get the checks of the current configuration
+    pub fn from_config(config: &SharedConfig) -> Self {
+        config.read(|config| Self {
+            schedule_conflict_mode: config.schedule_conflict_mode,
+            location_tolerance_m: Some(config.vertipad_location_tolerance_m),
         })
     }
 }
 
+/// Run payload validation and storage side checks for a [`vertipad::Data`] object.
+pub async fn check_vertipad_data(
+    repos: &Repositories,
+    data: vertipad::Data,
+    checks: VertipadChecks,
+) -> Result<vertipad::Data, ValidationError> {
+    let data = validate_vertipad_data(data).map_err(unprocessable)?;
+
+    if let Some(error) = check_vertiport_exists(repos, &data.vertiport_id)
+        .await
+        .map_err(|status| (status, Json(vec![])))?
+    {
+        return Err(unprocessable(vec![error]));
+    }
+
+    if let (Some(tolerance_m), Some(location)) = (checks.location_tolerance_m, &data.geo_location) {
+        if let Some(error) =
+            check_vertipad_location(repos, &data.vertiport_id, location, tolerance_m)
+                .await
+                .map_err(|status| (status, Json(vec![])))?
+        {
+            return Err(unprocessable(vec![error]));
+        }
+    }
+
+    if let Some(error) = check_vertipad_name(repos, &data.vertiport_id, &data.name, None)
+        .await
+        .map_err(|status| (status, Json(vec![])))?
+    {
+        return Err(unprocessable(vec![error]));
+    }
+
+    if let Some(error) = check_vertipad_schedule(
+        repos,
+        &data.vertiport_id,
+        data.schedule.as_deref(),
+        checks.schedule_conflict_mode,
+    )
+    .await
+    .map_err(|status| (status, Json(vec![])))?
+    {
+        return Err(unprocessable(vec![error]));
+    }
+
+    Ok(data)
+}
+
+/// Run payload validation and storage side checks for an [`UpdateVertipadPayload`].
+///
+/// Returns the normalized payload together with the currently stored
+/// vertipad data.
+async fn check_update_vertipad_payload(
+    repos: &Repositories,
+    payload: UpdateVertipadPayload,
+    checks: VertipadChecks,
+) -> Result<(UpdateVertipadPayload, vertipad::Data), ValidationError> {
+    let payload = validate_update_vertipad_payload(payload).map_err(unprocessable)?;
+
+    let vertipad_data = repos
+        .vertipads
+        .get_by_id(payload.id.clone())
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve vertipad: {e}");
+            (storage_error_status(&e), Json(vec![]))
+        })?
+        .data
+        .ok_or_else(|| {
+            rest_error!("vertipad not found");
+            (StatusCode::NOT_FOUND, Json(vec![]))
+        })?;
+
+    if let Some(vertiport_id) = &payload.vertiport_id {
+        if let Some(error) = check_vertiport_exists(repos, vertiport_id)
+            .await
+            .map_err(|status| (status, Json(vec![])))?
+        {
+            return Err(unprocessable(vec![error]));
+        }
+    }
+
+    let vertiport_id = payload
+        .vertiport_id
+        .as_ref()
+        .unwrap_or(&vertipad_data.vertiport_id);
+    if payload.name.is_some() || payload.vertiport_id.is_some() {
+        let name = payload.name.as_ref().unwrap_or(&vertipad_data.name);
+        if let Some(error) = check_vertipad_name(repos, vertiport_id, name, Some(&payload.id))
+            .await
+            .map_err(|status| (status, Json(vec![])))?
+        {
+            return Err(unprocessable(vec![error]));
+        }
+    }
+
+    // The location is checked if it changes, or the vertipad moves to another vertiport
+    let location_changed = payload.mask.iter().any(|field| field == "geo_location");
+    if location_changed || payload.vertiport_id.is_some() {
+        let location = match location_changed {
+            true => payload.geo_location.clone().map(Into::into),
+            false => vertipad_data.geo_location.clone(),
+        };
+        if let (Some(tolerance_m), Some(location)) = (checks.location_tolerance_m, location) {
+            if let Some(error) =
+                check_vertipad_location(repos, vertiport_id, &location, tolerance_m)
+                    .await
+                    .map_err(|status| (status, Json(vec![])))?
+            {
+                return Err(unprocessable(vec![error]));
+            }
+        }
+    }
+
+    // The schedule is checked if it changes, or the vertipad moves to another vertiport
+    let schedule_changed = payload.mask.iter().any(|field| field == "schedule");
+    if schedule_changed || payload.vertiport_id.is_some() {
+        let schedule = match schedule_changed {
+            true => &payload.schedule,
+            false => &vertipad_data.schedule,
+        };
+        if let Some(error) = check_vertipad_schedule(
+            repos,
+            vertiport_id,
+            schedule.as_deref(),
+            checks.schedule_conflict_mode,
+        )
+        .await
+        .map_err(|status| (status, Json(vec![])))?
+        {
+            return Err(unprocessable(vec![error]));
+        }
+    }
+
+    Ok((payload, vertipad_data))
+}
+
+/// Validate a [`Vertipad`] registration payload without persisting it.
+///
+/// Returns the normalized payload as it would be registered.
+#[utoipa::path(
+    post,
+    path = "/assets/vertipads/validate",
+    tag = "svc-assets",
+    request_body=vertipad::Data,
+    responses(
+        (status = 200, description = "Payload is valid; the normalized payload is returned", body = vertipad::Data),
+        (status = 422, description = "Payload is invalid; a list of field errors is returned", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    )
+)]
+pub async fn validate_vertipad_registration(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
+    Json(payload): Json<vertipad::Data>,
+) -> Result<Json<vertipad::Data>, ValidationError> {
+    rest_info!("entry.");
+    rest_debug!("Payload: {:?}", &payload);
+
+    let checks = VertipadChecks::from_config(&config);
+    let data = check_vertipad_data(&repos, payload, checks).await?;
+    Ok(Json(data))
+}
+
+/// Validate a [`Vertipad`] update payload without persisting it.
+///
+/// Returns the normalized payload as it would be applied.
+#[utoipa::path(
+    put,
+    path = "/assets/vertipads/validate",
+    tag = "svc-assets",
+    request_body=UpdateVertipadPayload,
+    responses(
+        (status = 200, description = "Payload is valid; the normalized payload is returned", body = UpdateVertipadPayload),
+        (status = 404, description = "Vertipad not found in database"),
+        (status = 422, description = "Payload is invalid; a list of field errors is returned", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    )
+)]
+pub async fn validate_vertipad_update(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
+    Json(payload): Json<UpdateVertipadPayload>,
+) -> Result<Json<UpdateVertipadPayload>, ValidationError> {
+    rest_info!("entry [{}].", payload.id);
+    rest_debug!("Payload: {:?}", &payload);
+
+    let checks = VertipadChecks::from_config(&config);
+    let (payload, _) = check_update_vertipad_payload(&repos, payload, checks).await?;
+    Ok(Json(payload))
+}
+
 /// Register an [`Vertipad`] in the database.
 ///
-/// Also inserts the vertipad into the vertiport's vertipad list.
+/// The vertipad refers to its vertiport by `vertiport_id`, the vertiport
+/// itself is not changed. The vertipad is owned by the owner of its
+/// vertiport, so no operator is required. A schedule allowing operation while the vertiport
+/// is closed is handled according to the `schedule_conflict_mode` of the
+/// configuration.
 #[utoipa::path(
     post,
     path = "/assets/vertipads",
@@ -98,33 +677,28 @@ impl TryFrom<vertipad::Object> for Vertipad {
     request_body=vertipad::Data,
     responses(
         (status = 200, description = "Vertipad registered in database; a UUID is returned", body = String),
-        (status = 422, description = "Request body is invalid format"),
+        (status = 422, description = "Request body is invalid format, the vertiport does not exist, the vertipad lies outside the vertiport area, or the schedule conflicts with the vertiport schedule; a list of field errors is returned", body = [FieldError]),
         (status = 503, description = "Could not connect to other microservice dependencies")
     )
 )]
 pub async fn register_vertipad(
-    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
     Json(payload): Json<vertipad::Data>,
-) -> Result<String, StatusCode> {
+) -> Result<String, ValidationError> {
     rest_info!("entry.");
     rest_debug!("Payload: {:?}", &payload);
 
-    // TODO(R5): maybe not safe to just take the storage type directly from the client and shove
-    //  it into svc-storage without parsing/checking
-    let id = grpc_clients
-        .storage
-        .vertipad
+    let checks = VertipadChecks::from_config(&config);
+    let payload = check_vertipad_data(&repos, payload, checks).await?;
+
+    let id = repos
+        .vertipads
         .insert(payload)
         .await
         .map_err(|e| {
             rest_error!("could not insert vertipad: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-        .into_inner()
-        .object
-        .ok_or_else(|| {
-            rest_error!("could not insert vertipad.");
-            StatusCode::INTERNAL_SERVER_ERROR
+            (storage_error_status(&e), Json(vec![]))
         })?
         .id;
 
@@ -134,50 +708,88 @@ pub async fn register_vertipad(
 }
 
 /// Update/modify a [`Vertipad`] in the database.
+///
+/// A schedule allowing operation while the vertiport is closed is handled
+/// according to the `schedule_conflict_mode` of the configuration. The
+/// vertipad is not written if the masked fields already have the provided
+/// values.
 #[utoipa::path(
     put,
     path = "/assets/vertipads",
     tag = "svc-assets",
     request_body=UpdateVertipadPayload,
     responses(
-        (status = 200, description = "Vertipad updated in database; a UUID is returned", body = String),
-        (status = 422, description = "Request body is invalid format"),
+        (status = 200, description = "Vertipad updated in database, `changed` is `false` if nothing differed", body = UpdateResult),
+        (status = 409, description = "Vertipad can not be released manually while reserved by the scheduler, was updated since `expected_updated_at`, or its occupancy changed since `expected_occupancy_version`"),
+        (status = 422, description = "Request body is invalid format, the vertiport does not exist, the vertipad lies outside the vertiport area, or the schedule conflicts with the vertiport schedule; a list of field errors is returned", body = [FieldError]),
+        (status = 500, description = "The vertipad was updated but its occupancy transition could not be persisted"),
         (status = 503, description = "Could not connect to other microservice dependencies")
     )
 )]
 pub async fn update_vertipad(
-    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(occupancy): Extension<OccupancyLog>,
     Json(payload): Json<UpdateVertipadPayload>,
-) -> Result<(), StatusCode> {
+) -> Result<Json<UpdateResult>, ValidationError> {
     rest_info!("entry [{}].", payload.id);
     rest_debug!("Payload: {:?}", &payload);
 
+    let checks = VertipadChecks::from_config(&config);
+    apply_vertipad_update(&repos, &occupancy, payload, checks)
+        .await
+        .map(Json)
+}
+
+/// Apply an [`UpdateVertipadPayload`] to a stored vertipad.
+///
+/// Shared by the REST handlers and the gRPC mutation methods. The updates of
+/// a vertipad are serialized with [`OccupancyLog::lock_updates`], and the
+/// vertipad is read, checked against `expected_updated_at` and
+/// `expected_occupancy_version` and written while holding the lock, so an
+/// update based on an outdated vertipad is rejected with `CONFLICT`.
+pub async fn apply_vertipad_update(
+    repos: &Repositories,
+    occupancy: &OccupancyLog,
+    payload: UpdateVertipadPayload,
+    checks: VertipadChecks,
+) -> Result<UpdateResult, ValidationError> {
     let id = to_uuid(&payload.id)
         .ok_or_else(|| {
             let error_msg = "Invalid vertipad id".to_string();
             rest_error!("{}", &error_msg);
-            StatusCode::BAD_REQUEST
+            (StatusCode::BAD_REQUEST, Json(vec![]))
         })?
         .to_string();
 
-    let mut vertipad_data = grpc_clients
-        .storage
-        .vertipad
-        .get_by_id(Id { id: id.clone() })
-        .await
-        .map_err(|e| {
-            let error_msg = format!("could not retrieve vertipad: {}", e);
-            rest_error!("{}", &error_msg);
-            StatusCode::NOT_FOUND
-        })?
-        .into_inner()
-        .data
-        .ok_or_else(|| {
-            let error_msg = "vertipad not found".to_string();
-            rest_error!("{}", &error_msg);
-            StatusCode::NOT_FOUND
+    let _guard = occupancy.lock_updates(&id).await;
+    let (payload, mut vertipad_data) =
+        check_update_vertipad_payload(repos, payload, checks).await?;
+
+    check_expected_updated_at(
+        payload.expected_updated_at,
+        vertipad_data.updated_at.clone().map(Into::into),
+    )
+    .map_err(|status| (status, Json(vec![])))?;
+
+    if let Some(expected) = payload.expected_occupancy_version {
+        occupancy.check_version(&id, expected).map_err(|e| {
+            rest_warn!("could not update vertipad {}: {e}", id);
+            (StatusCode::CONFLICT, Json(vec![]))
+        })?;
+    }
+
+    let occupancy_change = payload
+        .occupied
+        .map(|occupied| (occupied, payload.occupancy_source.unwrap_or_default()));
+    if let Some((occupied, source)) = occupancy_change {
+        occupancy.check(&id, occupied, source).map_err(|e| {
+            rest_error!("could not update vertipad occupancy: {e}");
+            (StatusCode::CONFLICT, Json(vec![]))
         })?;
+    }
 
+    let stored = vertipad_data.clone();
     if let Some(name) = payload.name {
         vertipad_data.name = name;
     }
@@ -194,7 +806,12 @@ pub async fn update_vertipad(
         vertipad_data.occupied = occupied;
     }
 
-    vertipad_data.geo_location = payload.geo_location;
+    vertipad_data.geo_location = payload.geo_location.map(Into::into);
+    vertipad_data.schedule = payload.schedule;
+
+    if !masked_fields_changed(&stored, &vertipad_data, &payload.mask) {
+        return Ok(unchanged());
+    }
 
     let object = vertipad::UpdateObject {
         id: id.clone(),
@@ -204,19 +821,21 @@ pub async fn update_vertipad(
         }),
     };
 
-    grpc_clients
-        .storage
-        .vertipad
-        .update(object)
-        .await
-        .map_err(|e| {
-            let error_msg = format!("could not update vertipad: {}", e);
-            rest_error!("{}", &error_msg);
-            StatusCode::INTERNAL_SERVER_ERROR
+    repos.vertipads.update(object).await.map_err(|e| {
+        let error_msg = format!("could not update vertipad: {}", e);
+        rest_error!("{}", &error_msg);
+        (storage_error_status(&e), Json(vec![]))
+    })?;
+
+    if let Some((occupied, source)) = occupancy_change {
+        occupancy.record(&id, occupied, source).map_err(|e| {
+            rest_error!("could not persist vertipad occupancy transition: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
         })?;
+    }
 
     rest_info!("successfully updated vertipad.");
-    Ok(())
+    Ok(UpdateResult { changed: true })
 }
 
 /// Remove a [`Vertipad`] from the database.
@@ -233,7 +852,13 @@ pub async fn update_vertipad(
     )
 )]
 pub async fn remove_vertipad(
-    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(repos): Extension<Repositories>,
+    Extension(localizations): Extension<Localizations>,
+    Extension(classifications): Extension<PadClassifications>,
+    Extension(chargers): Extension<VertipadChargers>,
+    Extension(equipment): Extension<VertipadEquipment>,
+    Extension(occupancy): Extension<OccupancyLog>,
+    Extension(removed): Extension<RemovedAssets>,
     Path(id): Path<String>,
 ) -> Result<(), StatusCode> {
     rest_info!("entry [{}].", &id);
@@ -245,110 +870,668 @@ pub async fn remove_vertipad(
         })?
         .to_string();
 
-    grpc_clients
-        .storage
-        .vertipad
-        .delete(Id { id })
-        .await
-        .map_err(|e| {
-            rest_error!("could not remove vertipad: {e}");
-            StatusCode::NOT_FOUND
-        })?;
+    repos.vertipads.delete(id.clone()).await.map_err(|e| {
+        rest_error!("could not remove vertipad: {e}");
+        storage_error_status(&e)
+    })?;
+    removed.record(&id, Utc::now());
+    localizations.remove(&id);
+    classifications.remove(&id);
+    chargers.remove_vertipad(&id);
+    equipment.remove_vertipad(&id);
+    occupancy.remove(&id);
 
     Ok(())
 }
 
+/// Set the localized names of a [`Vertipad`].
+///
+/// Replaces all existing localizations of the vertipad. Localizations are
+/// persisted, see [`crate::rest::locale`].
 #[utoipa::path(
-    get,
-    path = "/assets/demo/vertipads",
+    put,
+    path = "/assets/vertipads/{id}/localization",
     tag = "svc-assets",
+    request_body = AssetLocalization,
     responses(
-        (status = 200, description = "Assets successfully found", body = [Vertipad]),
+        (status = 200, description = "Vertipad localization updated"),
+        (status = 400, description = "Invalid vertipad id"),
+        (status = 404, description = "Vertipad not found in database"),
+        (status = 422, description = "Invalid localization", body = [FieldError]),
+        (status = 500, description = "The localization could not be persisted"),
         (status = 503, description = "Could not connect to other microservice dependencies")
     ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+    )
 )]
-/// Get all vertipads from the database.
-pub async fn get_all_vertipads(
-    Extension(grpc_clients): Extension<GrpcClients>,
-) -> Result<Json<Vec<Vertipad>>, StatusCode> {
-    rest_info!("entry.");
-    let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
-    let assets: Vec<Vertipad> = grpc_clients
-        .storage
-        .vertipad
-        .search(filter)
-        .await
-        .map_err(|e| {
-            rest_error!("could not retrieve vertipads: {e}.");
-            StatusCode::NOT_FOUND
+pub async fn put_vertipad_localization(
+    Extension(repos): Extension<Repositories>,
+    Extension(localizations): Extension<Localizations>,
+    Path(id): Path<String>,
+    Json(payload): Json<AssetLocalization>,
+) -> Result<(), ValidationError> {
+    rest_info!("entry [{}].", &id);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertipad id: {}", &id);
+            (StatusCode::BAD_REQUEST, Json(vec![]))
         })?
-        .into_inner()
-        .list
-        .into_iter()
-        .filter_map(|object| object.try_into().ok())
-        .collect();
+        .to_string();
 
-    Ok(Json(assets))
+    let localization = validate_localization(payload).map_err(unprocessable)?;
+
+    repos.vertipads.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not get vertipad: {e}");
+        (storage_error_status(&e), Json(vec![]))
+    })?;
+
+    localizations.set(&id, localization).map_err(|e| {
+        rest_error!("could not persist vertipad localization: {e}");
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+    })?;
+    rest_info!("successfully updated vertipad localization.");
+    Ok(())
 }
 
-/// Get an [`Vertipad`] by its id.
+/// Set the classification of a [`Vertipad`].
+///
+/// Replaces the existing classification of the vertipad. The classification is
+/// persisted, see [`crate::rest::classification`].
 #[utoipa::path(
-    get,
-    path = "/assets/vertipads/{id}",
+    put,
+    path = "/assets/vertipads/{id}/classification",
     tag = "svc-assets",
+    request_body = PadClassification,
     responses(
-        (status = 200, description = "Vertipad {id} found from database", body = Vertipad),
-        (status = 404, description = "Vertipad not found in database"),
+        (status = 200, description = "Vertipad classification updated"),
         (status = 400, description = "Invalid vertipad id"),
+        (status = 404, description = "Vertipad not found in database"),
+        (status = 422, description = "Invalid classification", body = [FieldError]),
+        (status = 500, description = "The classification could not be persisted"),
         (status = 503, description = "Could not connect to other microservice dependencies")
     ),
     params(
         ("id" = String, Path, description = "Vertipad id"),
     )
 )]
-pub async fn get_vertipad_by_id(
-    Extension(grpc_clients): Extension<GrpcClients>,
-    Path(vertipad_id): Path<String>,
-) -> Result<Json<Vertipad>, StatusCode> {
-    rest_info!("entry [{}].", vertipad_id);
+pub async fn put_vertipad_classification(
+    Extension(repos): Extension<Repositories>,
+    Extension(classifications): Extension<PadClassifications>,
+    Path(id): Path<String>,
+    Json(payload): Json<PadClassification>,
+) -> Result<(), ValidationError> {
+    rest_info!("entry [{}].", &id);
 
-    let id = to_uuid(&vertipad_id)
+    let id = to_uuid(&id)
         .ok_or_else(|| {
-            rest_error!("Invalid vertipad id: {}", vertipad_id);
-            StatusCode::BAD_REQUEST
-        })? // Check if the vertipad_id is a valid UUID
+            rest_error!("Invalid vertipad id: {}", &id);
+            (StatusCode::BAD_REQUEST, Json(vec![]))
+        })?
         .to_string();
 
-    let vertipad: Vertipad = grpc_clients
-        .storage
-        .vertipad
-        .get_by_id(Id { id })
+    let classification = validate_pad_classification(payload).map_err(unprocessable)?;
+
+    repos.vertipads.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not get vertipad: {e}");
+        (storage_error_status(&e), Json(vec![]))
+    })?;
+
+    classifications.set(&id, classification).map_err(|e| {
+        rest_error!("could not persist vertipad classification: {e}");
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+    })?;
+    rest_info!("successfully updated vertipad classification.");
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/assets/demo/vertipads",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Assets successfully found; the `x-dropped-records` header counts the stored objects which could not be converted, a `Warning` header reports a list larger than `rest_list_warn_results`", body = [Vertipad]),
+        (status = 207, description = "Stored objects could not be converted in report conversion mode; the converted assets are returned with the failed objects", body = PartialList, content_type = "application/problem+json"),
+        (status = 400, description = "Invalid time filters, minimum weight or minimum charging power"),
+        (status = 422, description = "More assets match the query than `rest_list_max_results`", body = [FieldError]),
+        (status = 502, description = "Stored objects could not be converted in strict conversion mode", body = SkippedObjects),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(ListAssetsQuery, VertipadClassificationQuery, VertipadChargerQuery)
+)]
+/// Get all vertipads from the database, optionally filtered by their
+/// creation and update times, by their owner, by the aircraft weight they
+/// are classified for and by their chargers.
+pub async fn get_all_vertipads(
+    Extension(repos): Extension<Repositories>,
+    Extension(ops_statuses): Extension<VertiportOpsStatuses>,
+    Extension(classifications): Extension<PadClassifications>,
+    Extension(chargers): Extension<VertipadChargers>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(removed): Extension<RemovedAssets>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Query(classification): Query<VertipadClassificationQuery>,
+    Query(charger_query): Query<VertipadChargerQuery>,
+    Query(query): Query<ListAssetsQuery>,
+) -> Result<Listed<Vertipad>, ListError> {
+    rest_info!("entry.");
+    rest_debug!("Query: {:?}", query);
+    if let Some(weight_kg) = classification.min_weight_kg {
+        if !weight_kg.is_finite() || weight_kg < 0.0 {
+            rest_error!("Invalid minimum weight: {}", weight_kg);
+            return Err(ListError::Status(StatusCode::BAD_REQUEST));
+        }
+    }
+    if let Some(min_kw) = charger_query.min_kw {
+        if !min_kw.is_finite() || min_kw < 0.0 {
+            rest_error!("Invalid minimum charging power: {}", min_kw);
+            return Err(ListError::Status(StatusCode::BAD_REQUEST));
+        }
+    }
+    let limits = config.read(ListLimits::from_config);
+    // a vertipad is owned by the owner of its vertiport
+    let filter = owner_filter(&query, list_filter(&query)?, "vertiport_id", |owner_id| {
+        quotas.assets(owner_id, QuotaKind::Vertiport)
+    });
+    let objects = match filter {
+        Some(filter) => repos
+            .vertipads
+            .search(limits.limit_filter(filter))
+            .await
+            .map_err(|e| {
+                rest_error!("could not retrieve vertipads: {e}.");
+                storage_error_status(&e)
+            })?,
+        None => vec![],
+    };
+    limits.check(objects.len())?;
+
+    let mut listed: Listed<Vertipad> =
+        convert_objects(objects, config.read(|c| c.conversion_mode))?;
+    listed.assets = listed
+        .assets
+        .into_iter()
+        .map(|vertipad| vertipad.apply_owner(&quotas))
+        .filter(|vertipad| matches_list_query(&query, vertipad))
+        .map(|vertipad| {
+            vertipad
+                .apply_ops_status(&ops_statuses)
+                .apply_classification(&classifications)
+        })
+        .filter(|vertipad| {
+            classification
+                .min_weight_kg
+                .map_or(true, |weight_kg| vertipad.takes_weight(weight_kg))
+        })
+        .filter(|vertipad| chargers.matches(&vertipad.basics.id, &charger_query))
+        .collect();
+    for vertipad in &mut listed.assets {
+        vertipad.basics.deleted_at = removed.removed_at(&vertipad.basics.id);
+    }
+
+    limits.warn(&mut listed);
+    Ok(listed)
+}
+
+/// Get an [`Vertipad`] by its id.
+#[utoipa::path(
+    get,
+    path = "/assets/vertipads/{id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Vertipad {id} found from database", body = Vertipad),
+        (status = 404, description = "Vertipad not found in database"),
+        (status = 400, description = "Invalid vertipad id"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+    )
+)]
+pub async fn get_vertipad_by_id(
+    Extension(repos): Extension<Repositories>,
+    Extension(localizations): Extension<Localizations>,
+    Extension(ops_statuses): Extension<VertiportOpsStatuses>,
+    Extension(classifications): Extension<PadClassifications>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Path(vertipad_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Vertipad>, StatusCode> {
+    rest_info!("entry [{}].", vertipad_id);
+
+    let id = to_uuid(&vertipad_id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertipad id: {}", vertipad_id);
+            StatusCode::BAD_REQUEST
+        })? // Check if the vertipad_id is a valid UUID
+        .to_string();
+
+    let vertipad: Vertipad = repos
+        .vertipads
+        .get_by_id(id.clone())
         .await
         .map_err(|e| {
             rest_error!("could not get vertipad: {e}");
-            StatusCode::NOT_FOUND
+            storage_error_status(&e)
         })?
-        .into_inner()
         .try_into()
         .map_err(|e| {
             rest_error!("could not convert vertipad: {e}");
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
+    let mut vertipad = vertipad
+        .apply_owner(&quotas)
+        .apply_ops_status(&ops_statuses)
+        .apply_classification(&classifications);
 
     rest_debug!("vertipad found: {:#?}", vertipad);
+    if let Some(localization) = localizations.get(&id) {
+        vertipad.localize(localization, &accept_languages(&headers));
+    }
+
     Ok(Json(vertipad))
 }
 
+/// Check if a [`Vertipad`] exists.
+///
+/// Returns the `ETag` and `Last-Modified` headers of the vertipad without a
+/// body.
+#[utoipa::path(
+    head,
+    path = "/assets/vertipads/{id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Vertipad {id} exists"),
+        (status = 404, description = "Vertipad not found in database"),
+        (status = 400, description = "Invalid vertipad id"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+    )
+)]
+pub async fn head_vertipad_by_id(
+    Extension(repos): Extension<Repositories>,
+    Path(vertipad_id): Path<String>,
+) -> Result<AssetHeaders, StatusCode> {
+    rest_info!("entry [{}].", vertipad_id);
+    let id = to_uuid(&vertipad_id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertipad id: {}", vertipad_id);
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    let vertipad: Vertipad = repos
+        .vertipads
+        .get_by_id(id)
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve vertipad: {e}");
+            storage_error_status(&e)
+        })?
+        .try_into()
+        .map_err(|e| {
+            rest_error!("could not convert vertipad: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(asset_headers(vertipad.basics.updated_at))
+}
+
+/// Get the windows a vertipad is available within the provided range.
+///
+/// A disabled vertipad is never available. A vertipad without a schedule is
+/// available for the whole range, otherwise only within its schedule.
+/// An occupied vertipad is not available until it is released, so the
+/// window containing `now` is excluded.
+pub fn vertipad_availability(
+    data: &vertipad::Data,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<Vec<TimeWindow>, ScheduleError> {
+    if !data.enabled {
+        return Ok(vec![]);
+    }
+
+    let windows =
+        match &data.schedule {
+            Some(schedule) if !schedule.trim().is_empty() => schedule
+                .parse::<Schedule>()?
+                .windows(from, to, &mut OccurrenceBudget::default())?,
+            _ => vec![TimeWindow {
+                start: from,
+                end: to,
+            }],
+        };
+
+    if !data.occupied {
+        return Ok(windows);
+    }
+
+    Ok(windows
+        .into_iter()
+        .filter(|window| !(window.start <= now && now < window.end))
+        .collect())
+}
+
+/// Default number of occupancy transitions returned
+const DEFAULT_OCCUPANCY_LOG_LIMIT: usize = 10;
+
+/// Get the last occupancy transitions of a [`Vertipad`], most recent first.
+///
+/// Only the transitions recorded by this instance since it started are
+/// returned, see [`crate::rest::occupancy`].
+#[utoipa::path(
+    get,
+    path = "/assets/vertipads/{id}/occupancy-log",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Occupancy transitions of vertipad {id}", body = [OccupancyTransition]),
+        (status = 400, description = "Invalid vertipad id"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+        OccupancyLogQuery,
+    )
+)]
+pub async fn get_vertipad_occupancy_log(
+    Extension(occupancy): Extension<OccupancyLog>,
+    Path(id): Path<String>,
+    Query(query): Query<OccupancyLogQuery>,
+) -> Result<Json<Vec<OccupancyTransition>>, StatusCode> {
+    rest_info!("entry [{}].", &id);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertipad id: {}", &id);
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_OCCUPANCY_LOG_LIMIT)
+        .min(MAX_OCCUPANCY_LOG_LEN);
+    Ok(Json(occupancy.last(&id, limit)))
+}
+
+/// Get the available time windows of a [`Vertipad`].
+///
+/// Combines the vertipad's schedule, enabled flag and occupancy state, the
+/// operational status of its vertiport and its lighting: a vertipad with
+/// failed lighting is not available between sunset and sunrise.
+#[utoipa::path(
+    get,
+    path = "/assets/vertipads/{id}/availability",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Availability of vertipad {id}", body = VertipadAvailability),
+        (status = 400, description = "Invalid vertipad id or time range"),
+        (status = 404, description = "Vertipad not found in database"),
+        (status = 500, description = "Vertipad schedule could not be parsed or expanded"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+        AvailabilityQuery,
+    )
+)]
+pub async fn get_vertipad_availability(
+    Extension(repos): Extension<Repositories>,
+    Extension(ops_statuses): Extension<VertiportOpsStatuses>,
+    Extension(equipment): Extension<VertipadEquipment>,
+    Path(vertipad_id): Path<String>,
+    Query(query): Query<AvailabilityQuery>,
+) -> Result<Json<VertipadAvailability>, StatusCode> {
+    rest_info!("entry [{}].", vertipad_id);
+    rest_debug!("Query: {:?}", query);
+
+    let id = to_uuid(&vertipad_id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertipad id: {}", vertipad_id);
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    if query.to <= query.from || query.to - query.from > Duration::days(MAX_AVAILABILITY_RANGE_DAYS)
+    {
+        rest_error!(
+            "invalid time range, 'to' must be after 'from' and the range can not exceed {} days.",
+            MAX_AVAILABILITY_RANGE_DAYS
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let data = repos
+        .vertipads
+        .get_by_id(id.clone())
+        .await
+        .map_err(|e| {
+            rest_error!("could not get vertipad: {e}");
+            storage_error_status(&e)
+        })?
+        .data
+        .ok_or_else(|| {
+            rest_error!("vertipad has no data.");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let mut windows =
+        vertipad_availability(&data, query.from, query.to, Utc::now()).map_err(|e| {
+            rest_error!("could not expand vertipad schedule: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // The vertipad is disabled while its vertiport is closed
+    if let Some(closure) = ops_statuses.disabled_window(&data.vertiport_id, query.from, query.to) {
+        windows = subtract_window(windows, &closure);
+    }
+
+    // The vertipad can not be used at night while its lighting is failed
+    if equipment.lighting_failed(&id) {
+        match &data.geo_location {
+            Some(location) => {
+                let nights = night_windows(location.x, location.y, query.from, query.to);
+                windows = subtract_windows(windows, &nights);
+            }
+            None => rest_warn!("vertipad {id} has no location, can not exclude the night."),
+        }
+    }
+
+    Ok(Json(VertipadAvailability {
+        vertipad_id: id,
+        from: query.from,
+        to: query.to,
+        windows,
+    }))
+}
+
+/// Get multiple [`Vertipad`]s by their ids.
+///
+/// The vertipads are fetched concurrently. Ids which could not be found are
+/// returned in the `missing` list, objects which could not be converted in
+/// the `failed` list.
+#[utoipa::path(
+    post,
+    path = "/assets/vertipads/batch-get",
+    tag = "svc-assets",
+    request_body = BatchGetPayload,
+    responses(
+        (status = 200, description = "Vertipads found and ids missing from database", body = VertipadBatch),
+        (status = 400, description = "Invalid ids or too many ids requested"),
+        (status = 502, description = "Stored objects could not be converted in strict conversion mode", body = SkippedObjects),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    )
+)]
+pub async fn batch_get_vertipads(
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+    Extension(ops_statuses): Extension<VertiportOpsStatuses>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Json(payload): Json<BatchGetPayload>,
+) -> Result<Json<VertipadBatch>, ListError> {
+    rest_info!("entry.");
+    rest_debug!("Payload: {:?}", &payload);
+
+    let ids = parse_batch_ids(payload)?;
+    let mode = config.read(|c| c.conversion_mode);
+    let mut response: VertipadBatch = batch_get(ids, mode, |id| {
+        let repos = repos.clone();
+        let fan_out = fan_out.clone();
+        async move {
+            match fan_out.call(repos.vertipads.get_by_id(id)).await {
+                Ok(object) => Ok(Some(object)),
+                Err(e) => match storage_error_status(&e) {
+                    StatusCode::NOT_FOUND => Ok(None),
+                    status => {
+                        rest_error!("could not retrieve vertipad: {e}");
+                        Err(status)
+                    }
+                },
+            }
+        }
+    })
+    .await?;
+    response.found = response
+        .found
+        .into_iter()
+        .map(|vertipad| {
+            vertipad
+                .apply_owner(&quotas)
+                .apply_ops_status(&ops_statuses)
+        })
+        .collect();
+
+    rest_debug!(
+        "found {}, missing {}, failed {}.",
+        response.found.len(),
+        response.missing.len(),
+        response.failed.len()
+    );
+    Ok(Json(response))
+}
+
+/// Update/modify a [`Vertipad`] in the database using a JSON Merge Patch (RFC 7396).
+///
+/// Only the fields present in the patch are updated, fields set to `null`
+/// are removed.
+#[utoipa::path(
+    patch,
+    path = "/assets/vertipads/{id}",
+    tag = "svc-assets",
+    request_body(content = Object, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "Vertipad updated in database, `changed` is `false` if nothing differed", body = UpdateResult),
+        (status = 400, description = "Invalid vertipad id"),
+        (status = 404, description = "Vertipad not found in database"),
+        (status = 422, description = "Patch can not be applied; a list of field errors is returned", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertipad id"),
+    )
+)]
+pub async fn patch_vertipad(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(occupancy): Extension<OccupancyLog>,
+    Path(id): Path<String>,
+    Json(patch): Json<serde_json::Value>,
+) -> Result<Json<UpdateResult>, ValidationError> {
+    rest_info!("entry [{}].", id);
+    rest_debug!("Patch: {:?}", &patch);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertipad id.");
+            (StatusCode::BAD_REQUEST, Json(vec![]))
+        })?
+        .to_string();
+
+    let payload: UpdateVertipadPayload =
+        merge_patch_payload(&id, patch, VERTIPAD_PATCH_FIELDS, VERTIPAD_NULLABLE_FIELDS)
+            .map_err(unprocessable)?;
+
+    update_vertipad(
+        Extension(repos),
+        Extension(config),
+        Extension(occupancy),
+        Json(payload),
+    )
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::grpc::client::GrpcClients;
+    use crate::rest::chargers::{ChargerAvailability, ChargerConnector, ChargerPayload};
+    use crate::rest::classification::{PadSurface, PadType};
+    use crate::rest::equipment::{EquipmentKind, EquipmentPayload, EquipmentStatus};
+    use crate::rest::occupancy::OccupancySource;
+    use crate::rest::quota::AssetOwners;
     use crate::rest::structs::AssetsInfo;
+    use crate::testing::{VertipadDataBuilder, VertiportDataBuilder};
     use axum::extract::Extension;
     use lib_common::time::Utc;
     use lib_common::uuid::Uuid;
     use svc_storage_client_grpc::resources::vertipad;
 
+    fn operator_quotas() -> Extension<OperatorQuotas> {
+        Extension(OperatorQuotas::new(
+            crate::config::Config::default().into(),
+            AssetOwners::default(),
+        ))
+    }
+
+    fn localizations() -> Extension<Localizations> {
+        Extension(Localizations::default())
+    }
+
+    fn shared_config(mode: ScheduleConflictMode) -> Extension<SharedConfig> {
+        let mut config = crate::config::Config::default();
+        config.schedule_conflict_mode = mode;
+        Extension(config.into())
+    }
+
+    fn fan_out() -> Extension<FanOut> {
+        Extension(FanOut::new(crate::Config::default().into()))
+    }
+
+    fn classifications() -> Extension<PadClassifications> {
+        Extension(PadClassifications::default())
+    }
+
+    fn chargers() -> Extension<VertipadChargers> {
+        Extension(VertipadChargers::default())
+    }
+
+    fn equipment() -> Extension<VertipadEquipment> {
+        Extension(VertipadEquipment::default())
+    }
+
+    fn occupancy_log() -> Extension<OccupancyLog> {
+        Extension(OccupancyLog::default())
+    }
+
+    fn ops_statuses() -> Extension<VertiportOpsStatuses> {
+        Extension(VertiportOpsStatuses::default())
+    }
+
+    async fn vertiport_id(repos: &Repositories) -> String {
+        repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id
+    }
+
     #[test]
     fn test_vertipad_basics_trait_methods() {
         let vertipad = vertipad::mock::get_data_obj();
@@ -365,17 +1548,21 @@ mod tests {
                 .updated_at
                 .expect("mock didn't return a updated_at date.")
                 .into(),
+            deleted_at: None,
+            owner_id: None,
             whitelist: vec![Uuid::new_v4().to_string()],
             status: AssetStatus::Available,
         };
 
         let mut vertipad = Vertipad {
             basics: basics.clone(),
-            geo_location: vertipad.geo_location.unwrap(),
+            geo_location: vertipad.geo_location.unwrap().into(),
             vertiport_id: vertipad.vertiport_id,
             enabled: vertipad.enabled,
             occupied: vertipad.occupied,
             schedule: vertipad.schedule,
+            localization: None,
+            classification: None,
         };
         assert_eq!(vertipad.id().unwrap(), Uuid::parse_str(&basics.id).unwrap());
         assert_eq!(vertipad.name(), expected_name);
@@ -459,148 +1646,246 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_register_vertipad() {
+    async fn test_validate_vertipad_registration() {
         let config = crate::config::Config::default();
         let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
 
-        let vertipad_data = vertipad::Data {
-            name: "Test Vertipad".to_string(),
-            vertiport_id: "test-vertiport".to_string(),
-            geo_location: Some(GeoPointZ {
-                y: 0.0,
-                x: 0.0,
-                z: 0.0,
-            }),
-            enabled: true,
-            occupied: false,
-            schedule: None,
-            created_at: None,
-            updated_at: None,
-        };
+        let mut data = VertipadDataBuilder::new()
+            .vertiport_id(vertiport_id(&repos).await)
+            .build();
 
-        let response = register_vertipad(Extension(grpc_clients), Json(vertipad_data))
-            .await
-            .expect("Failed to register vertipad");
+        validate_vertipad_registration(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(data.clone()),
+        )
+        .await
+        .unwrap();
 
-        // Should be valid UUID
-        to_uuid(&response).unwrap();
+        // Name already in use at the same vertiport
+        register_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(data.clone()),
+        )
+        .await
+        .unwrap();
+        let (status, errors) = validate_vertipad_registration(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(data.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "name");
+
+        // Unknown vertiport
+        let mut unknown = data.clone();
+        unknown.vertiport_id = Uuid::new_v4().to_string();
+        let (status, errors) = validate_vertipad_registration(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(unknown),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "vertiport_id");
+
+        // Invalid payload
+        data.vertiport_id = "invalid".to_string();
+        data.geo_location = Some(GeoPointZ {
+            y: 91.0,
+            x: 0.0,
+            z: 0.0,
+        });
+        let (status, errors) = validate_vertipad_registration(
+            Extension(repos),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(data),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0.len(), 2);
     }
 
     #[tokio::test]
-    async fn test_get_all_vertipads() {
+    async fn test_register_vertipad() {
         let config = crate::config::Config::default();
         let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
 
-        let data = vertipad::Data {
-            name: "Test Vertipad".to_string(),
-            vertiport_id: "test-vertiport".to_string(),
-            geo_location: Some(GeoPointZ {
-                y: 0.0,
-                x: 0.0,
-                z: 0.0,
-            }),
-            enabled: true,
-            occupied: false,
-            schedule: None,
-            created_at: Some(Utc::now().into()),
-            updated_at: Some(Utc::now().into()),
-        };
+        let vertipad_data = VertipadDataBuilder::new()
+            .vertiport_id(vertiport_id(&repos).await)
+            .build();
 
-        let _ = register_vertipad(Extension(grpc_clients.clone()), Json(data))
-            .await
-            .unwrap();
+        let response = register_vertipad(
+            Extension(repos),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(vertipad_data),
+        )
+        .await
+        .expect("Failed to register vertipad");
 
-        let response = get_all_vertipads(Extension(grpc_clients)).await.unwrap();
-        assert!(!response.0.is_empty());
+        // Should be valid UUID
+        to_uuid(&response).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_all_vertipads() {
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        let data = VertipadDataBuilder::new()
+            .vertiport_id(vertiport_id(&repos).await)
+            .timestamps(Utc::now(), Utc::now())
+            .build();
+
+        let _ = register_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(data),
+        )
+        .await
+        .unwrap();
+
+        let response = get_all_vertipads(
+            Extension(repos),
+            ops_statuses(),
+            classifications(),
+            chargers(),
+            shared_config(ScheduleConflictMode::Strict),
+            Extension(RemovedAssets::default()),
+            operator_quotas(),
+            Query(VertipadClassificationQuery::default()),
+            Query(VertipadChargerQuery::default()),
+            Query(ListAssetsQuery::default()),
+        )
+        .await
+        .unwrap();
+        assert!(!response.assets.is_empty());
     }
 
     #[tokio::test]
     async fn test_remove_vertipad() {
         let config = crate::config::Config::default();
         let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
 
-        let vertipad_data = vertipad::Data {
-            name: "Test Vertipad".to_string(),
-            vertiport_id: "test-vertiport".to_string(),
-            geo_location: Some(GeoPointZ {
-                y: 0.0,
-                x: 0.0,
-                z: 0.0,
-            }),
-            enabled: true,
-            occupied: false,
-            schedule: None,
-            created_at: None,
-            updated_at: None,
-        };
+        let vertipad_data = VertipadDataBuilder::new()
+            .vertiport_id(vertiport_id(&repos).await)
+            .build();
 
         // INVALID UUID
-        let error = remove_vertipad(Extension(grpc_clients.clone()), Path("invalid".to_string()))
-            .await
-            .unwrap_err();
+        let error = remove_vertipad(
+            Extension(repos.clone()),
+            localizations(),
+            classifications(),
+            chargers(),
+            equipment(),
+            occupancy_log(),
+            Extension(RemovedAssets::default()),
+            Path("invalid".to_string()),
+        )
+        .await
+        .unwrap_err();
         assert_eq!(error, StatusCode::BAD_REQUEST);
 
         // // NONEXISTENT UUID
-        // let error = remove_vertipad(Extension(grpc_clients.clone()), Path(Uuid::new_v4().to_string()))
+        // let error = remove_vertipad(Extension(repos.clone()), localizations(), classifications(), chargers(), occupancy_log(), Path(Uuid::new_v4().to_string()))
         //     .await
         //     .unwrap_err();
         // assert_eq!(error, StatusCode::NOT_FOUND);
 
         // EXISTING UUID
-        let vertipad_id = register_vertipad(Extension(grpc_clients.clone()), Json(vertipad_data))
-            .await
-            .expect("Failed to register vertipad");
-
-        let response = remove_vertipad(Extension(grpc_clients), Path(vertipad_id))
-            .await
-            .expect("Failed to remove vertipad");
+        let vertipad_id = register_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(vertipad_data),
+        )
+        .await
+        .expect("Failed to register vertipad");
+
+        let removed = RemovedAssets::default();
+        let response = remove_vertipad(
+            Extension(repos),
+            localizations(),
+            classifications(),
+            chargers(),
+            equipment(),
+            occupancy_log(),
+            Extension(removed.clone()),
+            Path(vertipad_id.clone()),
+        )
+        .await
+        .expect("Failed to remove vertipad");
 
         assert_eq!(response, ());
+        assert!(removed.removed_at(&vertipad_id).is_some());
     }
 
     #[tokio::test]
     async fn test_get_vertipad_by_id() {
         let config = crate::config::Config::default();
         let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
 
         // INVALID UUID
-        let error =
-            get_vertipad_by_id(Extension(grpc_clients.clone()), Path("invalid".to_string()))
-                .await
-                .unwrap_err();
+        let error = get_vertipad_by_id(
+            Extension(repos.clone()),
+            localizations(),
+            ops_statuses(),
+            classifications(),
+            operator_quotas(),
+            Path("invalid".to_string()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap_err();
         assert_eq!(error, StatusCode::BAD_REQUEST);
 
         // NONEXISTENT UUID
         let error = get_vertipad_by_id(
-            Extension(grpc_clients.clone()),
+            Extension(repos.clone()),
+            localizations(),
+            ops_statuses(),
+            classifications(),
+            operator_quotas(),
             Path(Uuid::new_v4().to_string()),
+            HeaderMap::new(),
         )
         .await
         .unwrap_err();
         assert_eq!(error, StatusCode::NOT_FOUND);
 
-        let data = vertipad::Data {
-            name: "Test Vertipad".to_string(),
-            vertiport_id: "test-vertiport".to_string(),
-            geo_location: Some(GeoPointZ {
-                y: 0.0,
-                x: 0.0,
-                z: 0.0,
-            }),
-            enabled: true,
-            occupied: false,
-            schedule: None,
-            created_at: Some(Utc::now().into()),
-            updated_at: Some(Utc::now().into()),
-        };
-
-        let id = register_vertipad(Extension(grpc_clients.clone()), Json(data.clone()))
-            .await
-            .expect("Failed to register vertipad");
+        let data = VertipadDataBuilder::new()
+            .vertiport_id(vertiport_id(&repos).await)
+            .timestamps(Utc::now(), Utc::now())
+            .build();
 
-        let response = get_vertipad_by_id(Extension(grpc_clients), Path(id.clone()))
-            .await
-            .unwrap();
+        let id = register_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(data.clone()),
+        )
+        .await
+        .expect("Failed to register vertipad");
+
+        let response = get_vertipad_by_id(
+            Extension(repos),
+            localizations(),
+            ops_statuses(),
+            classifications(),
+            operator_quotas(),
+            Path(id.clone()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap();
 
         let expected: Vertipad = vertipad::Object {
             id: id.clone(),
@@ -612,58 +1897,981 @@ mod tests {
         assert_eq!(response.0, expected);
     }
 
+    #[tokio::test]
+    async fn test_vertipad_owner() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let quotas = operator_quotas();
+        let owner_id = Uuid::new_v4().to_string();
+        let owned_vertiport = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        quotas
+            .reserve(&repos, &owner_id, QuotaKind::Vertiport)
+            .await
+            .unwrap()
+            .commit(&owned_vertiport)
+            .unwrap();
+        let other_vertiport = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+
+        let mut ids = vec![];
+        for vertiport_id in [&owned_vertiport, &other_vertiport] {
+            let data = VertipadDataBuilder::new()
+                .vertiport_id(vertiport_id.clone())
+                .build();
+            ids.push(repos.vertipads.insert(data).await.unwrap().id);
+        }
+
+        // a vertipad is owned by the owner of its vertiport
+        let Json(vertipad) = get_vertipad_by_id(
+            Extension(repos.clone()),
+            localizations(),
+            ops_statuses(),
+            classifications(),
+            quotas.clone(),
+            Path(ids[0].clone()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(vertipad.basics.owner_id, Some(owner_id.clone()));
+
+        let listed = get_all_vertipads(
+            Extension(repos),
+            ops_statuses(),
+            classifications(),
+            chargers(),
+            shared_config(ScheduleConflictMode::Strict),
+            Extension(RemovedAssets::default()),
+            quotas,
+            Query(VertipadClassificationQuery::default()),
+            Query(VertipadChargerQuery::default()),
+            Query(ListAssetsQuery {
+                owner_id: Some(owner_id),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            listed
+                .assets
+                .iter()
+                .map(|vertipad| vertipad.basics.id.clone())
+                .collect::<Vec<_>>(),
+            vec![ids[0].clone()]
+        );
+
+        ut_info!("success");
+    }
+
     #[tokio::test]
     async fn test_update_vertipad() {
         let config = crate::config::Config::default();
         let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
 
         let mut payload = UpdateVertipadPayload {
             id: "invalid".to_string(),
-            vertiport_id: Some(Uuid::new_v4().to_string()),
+            vertiport_id: Some(vertiport_id(&repos).await),
+            expected_updated_at: None,
+            expected_occupancy_version: None,
             mask: vec!["name".to_string()],
             name: Some("Updated Vertipad".to_string()),
             geo_location: None,
             enabled: None,
             occupied: None,
+            occupancy_source: None,
             schedule: None,
         };
 
         // INVALID UUID in request
-        let error = update_vertipad(Extension(grpc_clients.clone()), Json(payload.clone()))
-            .await
-            .unwrap_err();
-        assert_eq!(error, StatusCode::BAD_REQUEST);
+        let error = update_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            occupancy_log(),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.0, StatusCode::BAD_REQUEST);
 
         // NONEXISTENT UUID
         payload.id = Uuid::new_v4().to_string();
-        let error = update_vertipad(Extension(grpc_clients.clone()), Json(payload.clone()))
-            .await
-            .unwrap_err();
-        assert_eq!(error, StatusCode::NOT_FOUND);
+        let error = update_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            occupancy_log(),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.0, StatusCode::NOT_FOUND);
 
         // VALID
-        let vertipad_data = vertipad::Data {
-            name: "Test Vertipad".to_string(),
-            vertiport_id: "test-vertiport".to_string(),
-            geo_location: Some(GeoPointZ {
-                y: 0.0,
-                x: 0.0,
+        let vertipad_data = VertipadDataBuilder::new()
+            .vertiport_id(vertiport_id(&repos).await)
+            .build();
+
+        payload.id = register_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(vertipad_data),
+        )
+        .await
+        .expect("Failed to register vertipad");
+        let response = update_vertipad(
+            Extension(repos),
+            shared_config(ScheduleConflictMode::Strict),
+            occupancy_log(),
+            Json(payload.clone()),
+        )
+        .await
+        .expect("Failed to update vertipad");
+
+        assert!(response.changed);
+    }
+
+    #[test]
+    fn test_vertipad_schedule_conflicts() {
+        let now = Utc::now();
+        let mut budget = OccurrenceBudget::default();
+        let vertiport = "DTSTART:20221020T060000Z;DURATION:PT16H\nRRULE:FREQ=DAILY";
+        let inside = "DTSTART:20221020T080000Z;DURATION:PT8H\nRRULE:FREQ=DAILY";
+        let late = "DTSTART:20221020T200000Z;DURATION:PT4H\nRRULE:FREQ=DAILY";
+
+        assert!(
+            vertipad_schedule_conflicts(Some(inside), Some(vertiport), now, &mut budget)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            vertipad_schedule_conflicts(None, Some(vertiport), now, &mut budget)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            vertipad_schedule_conflicts(Some(late), None, now, &mut budget)
+                .unwrap()
+                .is_empty()
+        );
+
+        let conflicts =
+            vertipad_schedule_conflicts(Some(late), Some(vertiport), now, &mut budget).unwrap();
+        assert!(conflicts.len() >= SCHEDULE_CONFLICT_HORIZON_DAYS as usize);
+        assert!(conflicts
+            .iter()
+            .all(|window| window.end - window.start <= Duration::hours(2)));
+        assert!(schedule_conflict_message(&conflicts[..1])
+            .starts_with("allows operation while the vertiport is closed: "));
+
+        assert!(
+            vertipad_schedule_conflicts(Some(late), Some("invalid"), now, &mut budget).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_vertipad_schedule_conflict_mode() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let vertiport_id = repos
+            .vertiports
+            .insert(
+                VertiportDataBuilder::new()
+                    .schedule("DTSTART:20221020T060000Z;DURATION:PT16H\nRRULE:FREQ=DAILY")
+                    .build(),
+            )
+            .await
+            .unwrap()
+            .id;
+        let data = VertipadDataBuilder::new()
+            .vertiport_id(vertiport_id)
+            .schedule("DTSTART:20221020T200000Z;DURATION:PT4H\nRRULE:FREQ=DAILY")
+            .build();
+
+        let (status, errors) = register_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(data.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "schedule");
+
+        let id = register_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Lenient),
+            Json(data),
+        )
+        .await
+        .unwrap();
+
+        // invalid schedule
+        let (status, errors) = patch_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            occupancy_log(),
+            Path(id.clone()),
+            Json(serde_json::json!({ "schedule": "invalid" })),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "schedule");
+
+        // schedule within the vertiport schedule
+        patch_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            occupancy_log(),
+            Path(id.clone()),
+            Json(serde_json::json!({
+                "schedule": "DTSTART:20221020T080000Z;DURATION:PT8H\nRRULE:FREQ=DAILY"
+            })),
+        )
+        .await
+        .unwrap();
+
+        // the vertipad follows the vertiport schedule without a schedule
+        patch_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            occupancy_log(),
+            Path(id.clone()),
+            Json(serde_json::json!({ "schedule": null })),
+        )
+        .await
+        .unwrap();
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_vertipad_location() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let data = VertipadDataBuilder::new()
+            .vertiport_id(vertiport_id(&repos).await)
+            .build();
+        let id = register_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(data.clone()),
+        )
+        .await
+        .unwrap();
+
+        // about 14 meters east of the vertiport
+        let outside = VertipadDataBuilder::new()
+            .vertiport_id(data.vertiport_id.clone())
+            .name("Outside")
+            .geo_location(GeoPointZ {
+                x: 4.2002,
+                y: 52.1,
                 z: 0.0,
+            })
+            .build();
+        let (status, errors) = register_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            Json(outside.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "geo_location");
+
+        let mut config = crate::config::Config::default();
+        config.vertipad_location_tolerance_m = 20.0;
+        register_vertipad(
+            Extension(repos.clone()),
+            Extension(config.into()),
+            Json(outside),
+        )
+        .await
+        .unwrap();
+
+        // a typo moving the vertipad kilometers away
+        let (status, errors) = patch_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            occupancy_log(),
+            Path(id),
+            Json(serde_json::json!({ "geo_location": { "x": 5.1, "y": 52.1 } })),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "geo_location");
+
+        ut_info!("success");
+    }
+
+    #[test]
+    fn test_vertipad_availability() {
+        let from = Utc::now();
+        let to = from + Duration::days(1);
+        let mut data = vertipad::mock::get_data_obj();
+        data.enabled = true;
+        data.occupied = false;
+        data.schedule = None;
+
+        // no schedule, available the whole range
+        let windows = vertipad_availability(&data, from, to, from).unwrap();
+        assert_eq!(
+            windows,
+            vec![TimeWindow {
+                start: from,
+                end: to
+            }]
+        );
+
+        // occupied, the current window is not available
+        data.occupied = true;
+        let windows = vertipad_availability(&data, from, to, from).unwrap();
+        assert!(windows.is_empty());
+
+        // disabled, never available
+        data.occupied = false;
+        data.enabled = false;
+        let windows = vertipad_availability(&data, from, to, from).unwrap();
+        assert!(windows.is_empty());
+
+        // invalid schedule
+        data.enabled = true;
+        data.schedule = Some("invalid".to_string());
+        assert!(vertipad_availability(&data, from, to, from).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_vertipad_availability() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+        let from = Utc::now();
+        let query = AvailabilityQuery {
+            from,
+            to: from + Duration::days(1),
+        };
+
+        // invalid id
+        let error = get_vertipad_availability(
+            Extension(repos.clone()),
+            ops_statuses(),
+            equipment(),
+            Path("invalid".to_string()),
+            Query(query),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        // invalid range
+        let error = get_vertipad_availability(
+            Extension(repos.clone()),
+            ops_statuses(),
+            equipment(),
+            Path(Uuid::new_v4().to_string()),
+            Query(AvailabilityQuery {
+                from,
+                to: from + Duration::days(MAX_AVAILABILITY_RANGE_DAYS + 1),
             }),
-            enabled: true,
-            occupied: false,
-            schedule: None,
-            created_at: None,
-            updated_at: None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        // vertipad without schedule
+        let mut data = vertipad::mock::get_data_obj();
+        data.vertiport_id = Uuid::new_v4().to_string();
+        data.name = Uuid::new_v4().to_string();
+        data.enabled = true;
+        data.occupied = false;
+        data.schedule = None;
+        let id = grpc_clients
+            .storage
+            .vertipad
+            .insert(data)
+            .await
+            .unwrap()
+            .into_inner()
+            .object
+            .unwrap()
+            .id;
+
+        let availability = get_vertipad_availability(
+            Extension(repos),
+            ops_statuses(),
+            equipment(),
+            Path(id.clone()),
+            Query(query),
+        )
+        .await
+        .unwrap();
+        assert_eq!(availability.vertipad_id, id);
+        assert_eq!(
+            availability.windows,
+            vec![TimeWindow {
+                start: query.from,
+                end: query.to,
+            }]
+        );
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_batch_get_vertipads() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        let mut data = vertipad::mock::get_data_obj();
+        data.vertiport_id = Uuid::new_v4().to_string();
+        let id = grpc_clients
+            .storage
+            .vertipad
+            .insert(data)
+            .await
+            .unwrap()
+            .into_inner()
+            .object
+            .unwrap()
+            .id;
+
+        // invalid payload
+        let error = batch_get_vertipads(
+            Extension(repos.clone()),
+            fan_out(),
+            ops_statuses(),
+            Extension(crate::Config::default().into()),
+            operator_quotas(),
+            Json(BatchGetPayload {
+                ids: vec!["invalid".to_string()],
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, ListError::Status(StatusCode::BAD_REQUEST));
+
+        let missing_id = Uuid::new_v4().to_string();
+        let response = batch_get_vertipads(
+            Extension(repos),
+            fan_out(),
+            ops_statuses(),
+            Extension(crate::Config::default().into()),
+            operator_quotas(),
+            Json(BatchGetPayload {
+                ids: vec![id.clone(), missing_id.clone()],
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.found.len(), 1);
+        assert_eq!(response.found[0].basics.id, id);
+        assert_eq!(response.missing, vec![missing_id]);
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_vertipad_vertiport_ops_status() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let Extension(statuses) = ops_statuses();
+        let mut data = vertipad::mock::get_data_obj();
+        data.vertiport_id = vertiport_id(&repos).await;
+        data.enabled = true;
+        data.occupied = false;
+        data.schedule = None;
+        let id = repos.vertipads.insert(data.clone()).await.unwrap().id;
+
+        let now = Utc::now();
+        statuses
+            .set(
+                &data.vertiport_id,
+                crate::ops_status::VertiportOpsStatusPayload {
+                    status: crate::ops_status::VertiportOpsStatus::Closed,
+                    effective_from: None,
+                    effective_until: Some(now + Duration::hours(2)),
+                    reason: Some("weather".to_string()),
+                },
+                now,
+            )
+            .unwrap();
+
+        let all = get_all_vertipads(
+            Extension(repos.clone()),
+            Extension(statuses.clone()),
+            classifications(),
+            chargers(),
+            shared_config(ScheduleConflictMode::Strict),
+            Extension(RemovedAssets::default()),
+            operator_quotas(),
+            Query(VertipadClassificationQuery::default()),
+            Query(VertipadChargerQuery::default()),
+            Query(ListAssetsQuery::default()),
+        )
+        .await
+        .unwrap()
+        .assets;
+        assert!(!all[0].enabled);
+        assert_eq!(all[0].basics.status, AssetStatus::Unavailable);
+
+        let Json(availability) = get_vertipad_availability(
+            Extension(repos),
+            Extension(statuses),
+            equipment(),
+            Path(id),
+            Query(AvailabilityQuery {
+                from: now,
+                to: now + Duration::hours(4),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            availability.windows,
+            vec![TimeWindow {
+                start: now + Duration::hours(2),
+                end: now + Duration::hours(4),
+            }]
+        );
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_vertipad_availability_with_failed_lighting() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        // in London
+        let repos = Repositories::memory();
+        let data = VertipadDataBuilder::new()
+            .vertiport_id(vertiport_id(&repos).await)
+            .geo_location(GeoPointZ {
+                x: -0.1278,
+                y: 51.5074,
+                z: 0.0,
+            })
+            .build();
+        let id = repos.vertipads.insert(data).await.unwrap().id;
+        let equipment = VertipadEquipment::default();
+        let from: DateTime<Utc> = "2024-06-21T12:00:00Z".parse().unwrap();
+        let query = AvailabilityQuery {
+            from,
+            to: from + Duration::days(1),
+        };
+        let availability = || {
+            get_vertipad_availability(
+                Extension(repos.clone()),
+                ops_statuses(),
+                Extension(equipment.clone()),
+                Path(id.clone()),
+                Query(query),
+            )
         };
 
-        payload.id = register_vertipad(Extension(grpc_clients.clone()), Json(vertipad_data))
+        let Json(available) = availability().await.unwrap();
+        assert_eq!(available.windows.len(), 1);
+
+        let lighting = equipment
+            .add(
+                &id,
+                EquipmentPayload {
+                    kind: EquipmentKind::Lighting,
+                    name: "Pad lights".to_string(),
+                    status: EquipmentStatus::Failed,
+                    last_inspection: None,
+                },
+                from,
+            )
+            .unwrap();
+        let Json(available) = availability().await.unwrap();
+        assert_eq!(available.windows.len(), 2);
+        assert_eq!(available.windows[0].start, query.from);
+        assert_eq!(
+            available.windows[0].end.format("%H").to_string(),
+            "20",
+            "unavailable after sunset"
+        );
+        assert_eq!(
+            available.windows[1].start.format("%H").to_string(),
+            "03",
+            "available after sunrise"
+        );
+        assert_eq!(available.windows[1].end, query.to);
+
+        equipment.remove(&id, &lighting.id).unwrap();
+        let Json(available) = availability().await.unwrap();
+        assert_eq!(available.windows.len(), 1);
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_vertipad_occupancy_source() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let occupancy = occupancy_log();
+        let id = repos
+            .vertipads
+            .insert(vertipad::mock::get_data_obj())
             .await
-            .expect("Failed to register vertipad");
-        let response = update_vertipad(Extension(grpc_clients), Json(payload.clone()))
+            .unwrap()
+            .id;
+
+        let payload = |occupied: bool, source: Option<OccupancySource>| UpdateVertipadPayload {
+            id: id.clone(),
+            vertiport_id: None,
+            name: None,
+            geo_location: None,
+            enabled: None,
+            occupied: Some(occupied),
+            occupancy_source: source,
+            schedule: None,
+            expected_updated_at: None,
+            expected_occupancy_version: None,
+            mask: vec!["occupied".to_string()],
+        };
+
+        // reserved by the scheduler
+        update_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            occupancy.clone(),
+            Json(payload(true, Some(OccupancySource::Scheduler))),
+        )
+        .await
+        .unwrap();
+
+        // manual release is rejected
+        let error = update_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            occupancy.clone(),
+            Json(payload(false, None)),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.0, StatusCode::CONFLICT);
+
+        let error = patch_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            occupancy.clone(),
+            Path(id.clone()),
+            Json(serde_json::json!({ "occupied": false })),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.0, StatusCode::CONFLICT);
+
+        // released by the scheduler
+        update_vertipad(
+            Extension(repos.clone()),
+            shared_config(ScheduleConflictMode::Strict),
+            occupancy.clone(),
+            Json(payload(false, Some(OccupancySource::Scheduler))),
+        )
+        .await
+        .unwrap();
+
+        let transitions = get_vertipad_occupancy_log(
+            occupancy.clone(),
+            Path(id.clone()),
+            Query(OccupancyLogQuery { limit: Some(1) }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(transitions.len(), 1);
+        assert!(!transitions[0].occupied);
+        assert_eq!(transitions[0].source, OccupancySource::Scheduler);
+
+        let error = get_vertipad_occupancy_log(
+            occupancy,
+            Path("invalid".to_string()),
+            Query(OccupancyLogQuery::default()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_vertipad_classification() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let Extension(classifications) = classifications();
+        let vertiport_id = vertiport_id(&repos).await;
+        let mut ids = vec![];
+        for _ in 0..2 {
+            let data = VertipadDataBuilder::new()
+                .vertiport_id(vertiport_id.clone())
+                .build();
+            ids.push(repos.vertipads.insert(data).await.unwrap().id);
+        }
+
+        let classification = PadClassification {
+            pad_type: PadType::FatoTlof,
+            surface: PadSurface::Concrete,
+            max_aircraft_weight_kg: 3175.0,
+            length_m: 30.0,
+            width_m: 30.0,
+        };
+        put_vertipad_classification(
+            Extension(repos.clone()),
+            Extension(classifications.clone()),
+            Path(ids[0].clone()),
+            Json(classification),
+        )
+        .await
+        .unwrap();
+
+        let Json(vertipad) = get_vertipad_by_id(
+            Extension(repos.clone()),
+            localizations(),
+            ops_statuses(),
+            equipment(),
+            Extension(classifications.clone()),
+            operator_quotas(),
+            Path(ids[0].clone()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(vertipad.classification, Some(classification));
+
+        let list = |min_weight_kg: Option<f64>| {
+            get_all_vertipads(
+                Extension(repos.clone()),
+                ops_statuses(),
+                Extension(classifications.clone()),
+                chargers(),
+                shared_config(ScheduleConflictMode::Strict),
+                Extension(RemovedAssets::default()),
+                operator_quotas(),
+                Query(VertipadClassificationQuery { min_weight_kg }),
+                Query(VertipadChargerQuery::default()),
+                Query(ListAssetsQuery::default()),
+            )
+        };
+        assert_eq!(list(None).await.unwrap().assets.len(), 2);
+        let heavy = list(Some(2500.0)).await.unwrap().assets;
+        assert_eq!(heavy.len(), 1);
+        assert_eq!(heavy[0].basics.id, ids[0]);
+        assert!(list(Some(5000.0)).await.unwrap().assets.is_empty());
+        let error = list(Some(-1.0)).await.unwrap_err();
+        assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+
+        // invalid values are rejected
+        let (status, errors) = put_vertipad_classification(
+            Extension(repos.clone()),
+            Extension(classifications.clone()),
+            Path(ids[1].clone()),
+            Json(PadClassification {
+                max_aircraft_weight_kg: 0.0,
+                ..classification
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "max_aircraft_weight_kg");
+        assert_eq!(classifications.get(&ids[1]), None);
+
+        let (status, _) = put_vertipad_classification(
+            Extension(repos.clone()),
+            Extension(classifications.clone()),
+            Path("invalid".to_string()),
+            Json(classification),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // the classification is removed with the vertipad
+        remove_vertipad(
+            Extension(repos),
+            localizations(),
+            Extension(classifications.clone()),
+            chargers(),
+            equipment(),
+            occupancy_log(),
+            Extension(RemovedAssets::default()),
+            Path(ids[0].clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(classifications.get(&ids[0]), None);
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_get_all_vertipads_by_charger() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let Extension(chargers) = chargers();
+        let vertiport_id = vertiport_id(&repos).await;
+        let mut ids = vec![];
+        for _ in 0..2 {
+            let data = VertipadDataBuilder::new()
+                .vertiport_id(vertiport_id.clone())
+                .build();
+            ids.push(repos.vertipads.insert(data).await.unwrap().id);
+        }
+        chargers
+            .add(
+                &ids[0],
+                ChargerPayload {
+                    connector: ChargerConnector::Ccs2,
+                    max_kw: 150.0,
+                    availability: ChargerAvailability::Available,
+                },
+                Utc::now(),
+            )
+            .unwrap();
+
+        let list = |has_charger: Option<bool>, min_kw: Option<f64>| {
+            get_all_vertipads(
+                Extension(repos.clone()),
+                ops_statuses(),
+                classifications(),
+                Extension(chargers.clone()),
+                shared_config(ScheduleConflictMode::Strict),
+                Extension(RemovedAssets::default()),
+                operator_quotas(),
+                Query(VertipadClassificationQuery::default()),
+                Query(VertipadChargerQuery {
+                    has_charger,
+                    min_kw,
+                }),
+                Query(ListAssetsQuery::default()),
+            )
+        };
+        let ids_of = |listed: Listed<Vertipad>| -> Vec<String> {
+            listed.assets.into_iter().map(|v| v.basics.id).collect()
+        };
+
+        assert_eq!(list(None, None).await.unwrap().assets.len(), 2);
+        assert_eq!(
+            ids_of(list(Some(true), None).await.unwrap()),
+            vec![ids[0].clone()]
+        );
+        assert_eq!(
+            ids_of(list(Some(false), None).await.unwrap()),
+            vec![ids[1].clone()]
+        );
+        assert_eq!(
+            ids_of(list(Some(true), Some(150.0)).await.unwrap()),
+            vec![ids[0].clone()]
+        );
+        assert!(list(Some(true), Some(350.0))
             .await
-            .expect("Failed to update vertipad");
+            .unwrap()
+            .assets
+            .is_empty());
+        let error = list(None, Some(f64::NAN)).await.unwrap_err();
+        assert_eq!(error.status(), StatusCode::BAD_REQUEST);
 
-        assert_eq!(response, ());
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_vertipad_occupancy_version() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let mut data = vertipad::mock::get_data_obj();
+        data.occupied = false;
+        let id = repos.vertipads.insert(data).await.unwrap().id;
+
+        let payload = |occupied: bool, expected: Option<u64>| UpdateVertipadPayload {
+            id: id.clone(),
+            vertiport_id: None,
+            name: None,
+            geo_location: None,
+            enabled: None,
+            occupied: Some(occupied),
+            occupancy_source: Some(OccupancySource::Scheduler),
+            schedule: None,
+            expected_updated_at: None,
+            expected_occupancy_version: expected,
+            mask: vec!["occupied".to_string()],
+        };
+        let Extension(occupancy) = occupancy_log();
+
+        let result = apply_vertipad_update(
+            &repos,
+            &occupancy,
+            payload(true, Some(0)),
+            VertipadChecks::default(),
+        )
+        .await
+        .unwrap();
+        assert!(result.changed);
+        assert_eq!(occupancy.version(&id), 1);
+
+        // an update based on the occupancy before the reservation is rejected
+        let (status, _) = apply_vertipad_update(
+            &repos,
+            &occupancy,
+            payload(false, Some(0)),
+            VertipadChecks::default(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+        let stored = repos.vertipads.get_by_id(id.clone()).await.unwrap();
+        assert!(stored.data.unwrap().occupied);
+        assert_eq!(occupancy.last(&id, 10).len(), 1);
+
+        apply_vertipad_update(
+            &repos,
+            &occupancy,
+            payload(false, Some(1)),
+            VertipadChecks::default(),
+        )
+        .await
+        .unwrap();
+        let stored = repos.vertipads.get_by_id(id.clone()).await.unwrap();
+        assert!(!stored.data.unwrap().occupied);
+        assert_eq!(occupancy.last(&id, 1)[0].version, 2);
+
+        ut_info!("success");
     }
 }
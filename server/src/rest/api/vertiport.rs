@@ -1,19 +1,75 @@
 //! Handlers for the vertiport API endpoints.
 
-pub use super::rest_types::UpdateVertiportPayload;
-
+pub use super::rest_types::{
+    GeoBoundingBox, GeoPoint, GeoPolygon, GeometryDetail, GeometryQuery, PadLayout,
+    RegisterVertiportQuery, UpdateVertiportPayload, VertipadAdjacency, VertipadAdjacencyQuery,
+    VertiportSuggestion, VertiportTypeaheadQuery,
+};
+
+use super::batch::{batch_get, parse_batch_ids, BatchGetPayload, VertiportBatch};
+use super::conversion::{convert_objects, ListError, Listed, PartialList, SkippedObjects};
+use super::errors::{storage_error_status, RegistrationError};
+use super::list::{list_filter, matches_list_query, owner_filter, ListAssetsQuery, ListLimits};
+use super::merge_patch::merge_patch_payload;
+use super::methods::{asset_headers, AssetHeaders};
+use super::validation::*;
+use super::vertipad::{schedule_conflict_message, vertipad_schedule_conflicts, Vertipad};
+
+use crate::archive::RemovedAssets;
+use crate::config::{ScheduleConflictMode, SharedConfig, VertiportDeletePolicy};
+use crate::geo::{centroid_offset_points, distance_m, grid_points, polygon_bbox, polygon_centroid};
+use crate::ops_status::{
+    OpsStatusError, VertiportOpsState, VertiportOpsStatusPayload, VertiportOpsStatuses,
+};
+use crate::rest::chargers::VertipadChargers;
+use crate::rest::classification::PadClassifications;
+use crate::rest::equipment::VertipadEquipment;
+use crate::rest::json::Json;
+use crate::rest::locale::{
+    accept_languages, localized, validate_localization, AssetLocalization, Localizations,
+};
+use crate::rest::occupancy::OccupancyLog;
+use crate::rest::quota::{owner_id_from_headers, OperatorQuotas, QuotaKind};
+use crate::rest::restrictions::RestrictionRules;
 use crate::rest::structs::{AssetStatus, Basics};
-use axum::{extract::Path, Extension, Json};
+use crate::schedule::OccurrenceBudget;
+use crate::typeahead::{search_vertiports, VertiportIndex};
+use crate::weather_minimums::{
+    validate_weather_minimums, VertiportWeatherMinimums, WeatherMinimums, WeatherMinimumsPayload,
+};
+use axum::{
+    extract::{Path, Query},
+    http::HeaderMap,
+    Extension,
+};
+use chrono::Utc;
 use hyper::StatusCode;
 use svc_storage_client_grpc::prelude::*;
 use svc_storage_client_grpc::resources::vertiport;
 
-use crate::grpc::client::GrpcClients;
+use crate::fan_out::FanOut;
+use crate::repo::Repositories;
+use crate::saga::Saga;
 use lib_common::uuid::to_uuid;
 
 /// The default name for a vertiport
 const VERTIPORT_NAME: &str = "Unnamed Vertiport";
 
+/// Default number of vertiports suggested by the typeahead search
+const TYPEAHEAD_DEFAULT_LIMIT: u8 = 10;
+
+/// Maximum number of vertiports suggested by the typeahead search
+const TYPEAHEAD_MAX_LIMIT: u8 = 50;
+
+/// Maximum number of vertipads created with a vertiport registration
+const MAX_AUTO_CREATE_PADS: u8 = 20;
+
+/// Fields of a [`Vertiport`] which can be changed with a merge patch
+const VERTIPORT_PATCH_FIELDS: &[&str] = &["name", "description", "geo_location", "schedule"];
+
+/// Fields of a [`Vertiport`] which can be removed with a merge patch
+const VERTIPORT_NULLABLE_FIELDS: &[&str] = &["schedule"];
+
 /// A struct representing a vertiport (a vertical airport).
 ///
 /// A vertiport is an airport that is used for vertical takeoff and
@@ -26,11 +82,25 @@ pub struct Vertiport {
     /// A description of the vertiport.
     pub description: String,
 
-    /// The geographic location of the vertiport.
-    pub geo_location: GeoPolygonZ,
+    /// The geographic location of the vertiport, omitted unless the full
+    /// geometry is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo_location: Option<GeoPolygon>,
+
+    /// The centroid of the vertiport area, for example for a map marker.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub centroid: Option<GeoPoint>,
+
+    /// The bounding box of the vertiport area.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<GeoBoundingBox>,
 
     /// The schedule of the vertiport.
     pub schedule: Option<String>,
+
+    /// Localized names and descriptions of the vertiport, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub localization: Option<AssetLocalization>,
 }
 
 impl Vertiport {
@@ -44,6 +114,48 @@ impl Vertiport {
             None => VERTIPORT_NAME.to_string(),
         }
     }
+
+    /// Get the vertiport's name in the most preferred of the provided languages.
+    ///
+    /// Falls back to [`Vertiport::full_name`] if no localized name is available.
+    pub fn localized_name(&self, languages: &[String]) -> String {
+        self.localization
+            .as_ref()
+            .and_then(|localization| localized(&localization.names, languages))
+            .map(str::to_string)
+            .unwrap_or_else(|| self.full_name())
+    }
+
+    /// Add the operator owning the vertiport, if it is known.
+    pub fn apply_owner(mut self, quotas: &OperatorQuotas) -> Self {
+        self.basics.owner_id = quotas.owner(QuotaKind::Vertiport, &self.basics.id);
+        self
+    }
+
+    /// Keep only the requested geometry of the vertiport.
+    pub fn with_geometry(mut self, geometry: GeometryDetail) -> Self {
+        match geometry {
+            GeometryDetail::Full => {}
+            GeometryDetail::Centroid => self.geo_location = None,
+            GeometryDetail::None => {
+                self.geo_location = None;
+                self.centroid = None;
+                self.bbox = None;
+            }
+        }
+        self
+    }
+
+    /// Apply the localization for the provided languages.
+    ///
+    /// The name and description are replaced by their localized version, if available.
+    pub fn localize(&mut self, localization: AssetLocalization, languages: &[String]) {
+        if let Some(description) = localized(&localization.descriptions, languages) {
+            self.description = description.to_string();
+        }
+        self.localization = Some(localization);
+        self.basics.name = Some(self.localized_name(languages));
+    }
 }
 
 impl TryFrom<vertiport::Object> for Vertiport {
@@ -68,19 +180,186 @@ impl TryFrom<vertiport::Object> for Vertiport {
                     .updated_at
                     .ok_or("(try_from) updated_at field empty but expected.")?
                     .into(),
+                deleted_at: None,
+                owner_id: None,
                 whitelist: Vec::new(),
                 status: AssetStatus::Available,
             },
             description: data.description,
-            geo_location: data
-                .geo_location
-                .ok_or("(try_from) geo_location empty but expected.")?,
+            centroid: polygon_centroid(&geo_location).map(Into::into),
+            bbox: polygon_bbox(&geo_location).map(
+                |(min_longitude, min_latitude, max_longitude, max_latitude)| GeoBoundingBox {
+                    min_longitude,
+                    min_latitude,
+                    max_longitude,
+                    max_latitude,
+                },
+            ),
+            geo_location: Some(geo_location.into()),
             schedule: data.schedule,
+            localization: None,
         })
     }
 }
 
+/// Validate and normalize a [`vertiport::Data`] object.
+pub fn validate_vertiport_data(
+    mut data: vertiport::Data,
+) -> Result<vertiport::Data, Vec<FieldError>> {
+    normalize_string(&mut data.name);
+    normalize_string(&mut data.description);
+    normalize_optional_string(&mut data.schedule);
+
+    let mut errors = vec![];
+    check_not_empty(&mut errors, "name", &data.name);
+    match &data.geo_location {
+        Some(geo_location) => check_geo_polygon(&mut errors, "geo_location", geo_location),
+        None => errors.push(field_error("geo_location", "is required")),
+    }
+
+    into_result(data, errors)
+}
+
+/// Validate and normalize an [`UpdateVertiportPayload`].
+pub fn validate_update_vertiport_payload(
+    mut payload: UpdateVertiportPayload,
+) -> Result<UpdateVertiportPayload, Vec<FieldError>> {
+    normalize_string(&mut payload.id);
+    normalize_optional_string(&mut payload.description);
+    normalize_optional_string(&mut payload.schedule);
+
+    let mut errors = vec![];
+    check_uuid(&mut errors, "id", &payload.id);
+
+    if let Some(name) = payload.name.as_mut() {
+        normalize_string(name);
+        check_not_empty(&mut errors, "name", name);
+    }
+
+    if let Some(geo_location) = &payload.geo_location {
+        check_geo_polygon(&mut errors, "geo_location", &geo_location.clone().into());
+    }
+
+    into_result(payload, errors)
+}
+
+/// Validate a [`Vertiport`] registration payload without persisting it.
+///
+/// Returns the normalized payload as it would be registered.
+#[utoipa::path(
+    post,
+    path = "/assets/vertiports/validate",
+    tag = "svc-assets",
+    request_body=vertiport::Data,
+    responses(
+        (status = 200, description = "Payload is valid; the normalized payload is returned", body = vertiport::Data),
+        (status = 422, description = "Payload is invalid; a list of field errors is returned", body = [FieldError])
+    )
+)]
+pub async fn validate_vertiport_registration(
+    Json(payload): Json<vertiport::Data>,
+) -> Result<Json<vertiport::Data>, ValidationError> {
+    rest_info!("entry.");
+    rest_debug!("Payload: {:?}", &payload);
+
+    let data = validate_vertiport_data(payload).map_err(unprocessable)?;
+    Ok(Json(data))
+}
+
+/// Validate a [`Vertiport`] update payload without persisting it.
+///
+/// Returns the normalized payload as it would be applied.
+#[utoipa::path(
+    put,
+    path = "/assets/vertiports/validate",
+    tag = "svc-assets",
+    request_body=UpdateVertiportPayload,
+    responses(
+        (status = 200, description = "Payload is valid; the normalized payload is returned", body = UpdateVertiportPayload),
+        (status = 422, description = "Payload is invalid; a list of field errors is returned", body = [FieldError])
+    )
+)]
+pub async fn validate_vertiport_update(
+    Json(payload): Json<UpdateVertiportPayload>,
+) -> Result<Json<UpdateVertiportPayload>, ValidationError> {
+    rest_info!("entry [{}].", payload.id);
+    rest_debug!("Payload: {:?}", &payload);
+
+    let payload = validate_update_vertiport_payload(payload).map_err(unprocessable)?;
+    Ok(Json(payload))
+}
+
+/// Get the locations of the vertipads to create with a vertiport registration.
+fn auto_pad_locations(
+    data: &vertiport::Data,
+    query: &RegisterVertiportQuery,
+) -> Result<Vec<GeoPointZ>, Vec<FieldError>> {
+    let count = query.auto_create_pads.unwrap_or(0);
+    if count == 0 {
+        return Ok(vec![]);
+    }
+
+    if count > MAX_AUTO_CREATE_PADS {
+        return Err(vec![field_error(
+            "auto_create_pads",
+            &format!("must be at most {MAX_AUTO_CREATE_PADS}"),
+        )]);
+    }
+
+    let Some(polygon) = &data.geo_location else {
+        return Err(vec![field_error("geo_location", "is required")]);
+    };
+
+    let locations = match query.pad_layout.unwrap_or_default() {
+        PadLayout::Grid => grid_points(polygon, count as usize),
+        PadLayout::CentroidOffset => centroid_offset_points(polygon, count as usize),
+    };
+
+    locations.ok_or_else(|| {
+        vec![field_error(
+            "auto_create_pads",
+            "the vertiport area can not fit the requested vertipads",
+        )]
+    })
+}
+
+/// Create the vertipads of a new vertiport, recorded in the saga of the
+/// registration.
+async fn create_vertipads(
+    saga: &mut Saga,
+    vertiport_id: &str,
+    vertiport_name: &str,
+    locations: Vec<GeoPointZ>,
+) -> Result<usize, tonic::Status> {
+    let count = locations.len();
+    for (index, location) in locations.into_iter().enumerate() {
+        let data = vertipad::Data {
+            name: format!("{vertiport_name} Pad {}", index + 1),
+            vertiport_id: vertiport_id.to_string(),
+            geo_location: Some(location),
+            enabled: true,
+            occupied: false,
+            schedule: None,
+            created_at: None,
+            updated_at: None,
+        };
+
+        saga.insert_vertipad(data).await.map_err(|e| {
+            rest_error!("could not insert vertipad {}: {e}", index + 1);
+            e
+        })?;
+    }
+
+    Ok(count)
+}
+
 /// Register an [`Vertiport`] in the database.
+///
+/// The operator registering the vertiport becomes its owner, and the owner
+/// of its vertipads. Optionally, `auto_create_pads` vertipads are created within the
+/// vertiport area. If any of them can not be created, the vertiport and the
+/// vertipads created so far are removed again; the ones which could not be
+/// removed are returned, see [`RegistrationError`].
 #[utoipa::path(
     post,
     path = "/assets/vertiports",
@@ -88,33 +367,66 @@ impl TryFrom<vertiport::Object> for Vertiport {
     request_body=vertiport::Data,
     responses(
         (status = 200, description = "Vertiport registered in database; a UUID is returned", body = String),
-        (status = 422, description = "Request body is invalid format"),
-        (status = 503, description = "Could not connect to other microservice dependencies")
+        (status = 400, description = "Missing or invalid operator id header"),
+        (status = 403, description = "Operator reached its vertiport quota"),
+        (status = 422, description = "Request body is invalid format, or the vertiport area can not fit the requested vertipads"),
+        (status = 500, description = "The owner of the vertiport could not be recorded; if assets were created, the ones which could not be removed again are returned", body = super::errors::IncompleteRegistration),
+        (status = 503, description = "Could not connect to other microservice dependencies; if assets were created, the ones which could not be removed again are returned", body = super::errors::IncompleteRegistration)
+    ),
+    params(
+        RegisterVertiportQuery,
+        ("x-operator-id" = String, Header, description = "UUID of the operator registering and owning the vertiport; set from the API token if it is bound to an operator"),
     )
 )]
 pub async fn register_vertiport(
-    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(repos): Extension<Repositories>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Query(query): Query<RegisterVertiportQuery>,
+    headers: HeaderMap,
     Json(payload): Json<vertiport::Data>,
-) -> Result<String, StatusCode> {
+) -> Result<String, RegistrationError> {
     rest_info!("entry.");
     rest_debug!("Payload: {:?}", &payload);
+    rest_debug!("Query: {:?}", query);
+
+    let operator_id = owner_id_from_headers(&headers)?;
+
+    let payload = validate_vertiport_data(payload).map_err(|errors| unprocessable(errors).0)?;
+    let pad_locations =
+        auto_pad_locations(&payload, &query).map_err(|errors| unprocessable(errors).0)?;
+    let name = payload.name.clone();
+
+    let reservation = quotas
+        .reserve(&repos, &operator_id, QuotaKind::Vertiport)
+        .await?;
+
+    let mut saga = Saga::new("register vertiport", &repos);
+    let id = saga.insert_vertiport(payload).await.map_err(|e| {
+        rest_error!("could not insert vertiport: {e}");
+        storage_error_status(&e)
+    })?;
+
+    match create_vertipads(&mut saga, &id, &name, pad_locations).await {
+        Ok(0) => {}
+        Ok(count) => rest_info!("created {count} vertipads for vertiport {id}."),
+        Err(e) => {
+            let leftover = saga.compensate().await;
+            return Err(RegistrationError::Incomplete(
+                storage_error_status(&e),
+                leftover,
+            ));
+        }
+    }
 
-    let id = grpc_clients
-        .storage
-        .vertiport
-        .insert(payload)
-        .await
-        .map_err(|e| {
-            rest_error!("could not insert vertiport: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-        .into_inner()
-        .object
-        .ok_or_else(|| {
-            rest_error!("vertiport not found.");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-        .id;
+    // a vertiport without its owner would not count towards the quota
+    if let Err(e) = reservation.commit(&id) {
+        rest_error!("could not record the owner of vertiport {id}: {e}");
+        let leftover = saga.compensate().await;
+        return Err(RegistrationError::Incomplete(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            leftover,
+        ));
+    }
 
     Ok(id)
 }
@@ -122,49 +434,71 @@ pub async fn register_vertiport(
 /// Update/modify a [`Vertiport`] in the database.
 ///
 /// This will update the vertiport's information. It can also be used to
-/// perform batch add/remove of vertipads.
+/// perform batch add/remove of vertipads. A schedule conflicting with the
+/// schedules of the vertipads is handled according to the
+/// `schedule_conflict_mode` of the configuration. The vertiport is not
+/// written if the masked fields already have the provided values.
 #[utoipa::path(
     put,
     path = "/assets/vertiports",
     tag = "svc-assets",
     request_body=UpdateVertiportPayload,
     responses(
-        (status = 200, description = "Vertiport updated in database; a UUID is returned", body = String),
-        (status = 422, description = "Request body is invalid format"),
+        (status = 200, description = "Vertiport updated in database, `changed` is `false` if nothing differed", body = UpdateResult),
+        (status = 409, description = "Vertiport was updated since `expected_updated_at`"),
+        (status = 422, description = "Request body is invalid format, or the schedule conflicts with the vertipad schedules; a list of field errors is returned", body = [FieldError]),
         (status = 503, description = "Could not connect to other microservice dependencies")
     )
 )]
 pub async fn update_vertiport(
-    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
     Json(payload): Json<UpdateVertiportPayload>,
-) -> Result<(), StatusCode> {
+) -> Result<Json<UpdateResult>, ValidationError> {
     rest_info!("entry [{}].", payload.id);
     rest_debug!("Payload: {:?}", &payload);
 
     let id = to_uuid(&payload.id)
         .ok_or_else(|| {
             rest_error!("Invalid vertiport id: {}", &payload.id);
-            StatusCode::BAD_REQUEST
+            (StatusCode::BAD_REQUEST, Json(vec![]))
         })?
         .to_string();
 
-    let mut vertiport_data = grpc_clients
-        .storage
-        .vertiport
-        .get_by_id(Id { id: id.clone() })
+    let payload = validate_update_vertiport_payload(payload).map_err(unprocessable)?;
+
+    let mut vertiport_data = repos
+        .vertiports
+        .get_by_id(id.clone())
         .await
         .map_err(|e| {
             rest_error!("error getting vertiport from storage: {e}");
-            StatusCode::NOT_FOUND
+            (storage_error_status(&e), Json(vec![]))
         })?
-        .into_inner()
         .data
         .ok_or_else(|| {
             rest_error!("vertiport data malformed.");
-            StatusCode::INTERNAL_SERVER_ERROR
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
         })?;
 
-    vertiport_data.geo_location = payload.geo_location;
+    check_expected_updated_at(
+        payload.expected_updated_at,
+        vertiport_data.updated_at.clone().map(Into::into),
+    )
+    .map_err(|status| (status, Json(vec![])))?;
+
+    if payload.mask.iter().any(|field| field == "schedule") {
+        let mode = config.read(|config| config.schedule_conflict_mode);
+        let errors = check_vertiport_schedule(&repos, &id, payload.schedule.as_deref(), mode)
+            .await
+            .map_err(|status| (status, Json(vec![])))?;
+        if !errors.is_empty() {
+            return Err(unprocessable(errors));
+        }
+    }
+
+    let stored = vertiport_data.clone();
+    vertiport_data.geo_location = payload.geo_location.map(Into::into);
     vertiport_data.schedule = payload.schedule;
     if let Some(name) = payload.name {
         vertiport_data.name = name;
@@ -174,6 +508,10 @@ pub async fn update_vertiport(
         vertiport_data.description = description;
     }
 
+    if !masked_fields_changed(&stored, &vertiport_data, &payload.mask) {
+        return Ok(Json(unchanged()));
+    }
+
     let object = vertiport::UpdateObject {
         id,
         data: Some(vertiport_data),
@@ -182,38 +520,178 @@ pub async fn update_vertiport(
         }),
     };
 
-    grpc_clients
-        .storage
-        .vertiport
-        .update(object)
+    repos.vertiports.update(object).await.map_err(|e| {
+        rest_error!("could not update vertiport: {e}");
+        (storage_error_status(&e), Json(vec![]))
+    })?;
+
+    rest_info!("successfully updated vertiport.",);
+
+    Ok(Json(UpdateResult { changed: true }))
+}
+
+/// Check if the vertipads of a vertiport allow operation while the vertiport
+/// is closed according to a new vertiport schedule.
+///
+/// Returns an error per conflicting vertipad in
+/// [`ScheduleConflictMode::Strict`]; in [`ScheduleConflictMode::Lenient`]
+/// the conflicts are only logged as a warning.
+async fn check_vertiport_schedule(
+    repos: &Repositories,
+    vertiport_id: &str,
+    schedule: Option<&str>,
+    mode: ScheduleConflictMode,
+) -> Result<Vec<FieldError>, StatusCode> {
+    if schedule.is_none() {
+        return Ok(vec![]);
+    }
+
+    let now = Utc::now();
+    let mut budget = OccurrenceBudget::default();
+    let mut errors = vec![];
+    for vertipad in vertiport_vertipads(repos, vertiport_id).await? {
+        let vertipad_schedule = vertipad.data.and_then(|data| data.schedule);
+        let conflicts = match vertipad_schedule_conflicts(
+            vertipad_schedule.as_deref(),
+            schedule,
+            now,
+            &mut budget,
+        ) {
+            Ok(conflicts) => conflicts,
+            Err(e) => {
+                rest_warn!(
+                    "schedule conflicts of vertipad {} not checked: {e}",
+                    vertipad.id
+                );
+                continue;
+            }
+        };
+
+        if conflicts.is_empty() {
+            continue;
+        }
+
+        let message = format!(
+            "vertipad {} {}",
+            vertipad.id,
+            schedule_conflict_message(&conflicts)
+        );
+        match mode {
+            ScheduleConflictMode::Strict => errors.push(field_error("schedule", &message)),
+            ScheduleConflictMode::Lenient => {
+                rest_warn!("vertiport {vertiport_id} schedule: {message}.")
+            }
+        }
+    }
+
+    Ok(errors)
+}
+
+/// Get the vertipads located at a vertiport.
+async fn vertiport_vertipads(
+    repos: &Repositories,
+    vertiport_id: &str,
+) -> Result<Vec<vertipad::Object>, StatusCode> {
+    let filter =
+        AdvancedSearchFilter::search_equals("vertiport_id".to_string(), vertiport_id.to_string())
+            .and_is_null("deleted_at".to_string());
+
+    let vertipads = repos
+        .vertipads
+        .search(filter)
         .await
         .map_err(|e| {
-            rest_error!("could not update vertiport: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+            rest_error!("could not search vertipads: {e}.");
+            storage_error_status(&e)
+        })?
+        .into_iter()
+        .filter(|object| {
+            object
+                .data
+                .as_ref()
+                .map(|data| data.vertiport_id == vertiport_id)
+                .unwrap_or(false)
+        })
+        .collect();
 
-    rest_info!("successfully updated vertiport.",);
+    Ok(vertipads)
+}
 
-    Ok(())
+/// Get the ids of the vertipads located at a vertiport.
+async fn vertiport_vertipad_ids(
+    repos: &Repositories,
+    vertiport_id: &str,
+) -> Result<Vec<String>, StatusCode> {
+    Ok(vertiport_vertipads(repos, vertiport_id)
+        .await?
+        .into_iter()
+        .map(|object| object.id)
+        .collect())
 }
 
-/// Remove a [`Vertiport`] from the database.
+/// Compute the pairwise distances and adjacency of vertipad locations.
+///
+/// The vertipads are sorted by id; two different vertipads are adjacent if
+/// their distance does not exceed `threshold_m`.
+pub fn vertipad_adjacency(
+    vertiport_id: &str,
+    threshold_m: f64,
+    mut locations: Vec<(String, GeoPointZ)>,
+) -> VertipadAdjacency {
+    locations.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let distances_m: Vec<Vec<f64>> = locations
+        .iter()
+        .map(|(_, a)| locations.iter().map(|(_, b)| distance_m(a, b)).collect())
+        .collect();
+    let adjacency = distances_m
+        .iter()
+        .enumerate()
+        .map(|(row, distances)| {
+            distances
+                .iter()
+                .enumerate()
+                .map(|(column, distance)| row != column && *distance <= threshold_m)
+                .collect()
+        })
+        .collect();
+
+    VertipadAdjacency {
+        vertiport_id: vertiport_id.to_string(),
+        threshold_m,
+        vertipad_ids: locations.into_iter().map(|(id, _)| id).collect(),
+        distances_m,
+        adjacency,
+    }
+}
+
+/// Get the pairwise distances and adjacency of the vertipads of a [`Vertiport`].
+///
+/// Distances are the ground distances in meters between the vertipad
+/// locations. Vertipads are adjacent if their distance does not exceed the
+/// `threshold_m`, which defaults to the `vertipad_adjacency_threshold_m` of
+/// the configuration. Used for ground movement planning.
 #[utoipa::path(
-    delete,
-    path = "/assets/vertiports/{id}",
+    get,
+    path = "/assets/vertiports/{id}/vertipads/adjacency",
     tag = "svc-assets",
     responses(
-        (status = 200, description = "Vertiport removed from database; a UUID is returned", body = String),
+        (status = 200, description = "Vertipad distances and adjacency of vertiport {id}", body = VertipadAdjacency),
+        (status = 400, description = "Invalid vertiport id or threshold"),
+        (status = 404, description = "Vertiport not found in database"),
         (status = 503, description = "Could not connect to other microservice dependencies")
     ),
     params(
         ("id" = String, Path, description = "Vertiport id"),
+        VertipadAdjacencyQuery,
     )
 )]
-pub async fn remove_vertiport(
-    Extension(grpc_clients): Extension<GrpcClients>,
+pub async fn get_vertipad_adjacency(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
     Path(id): Path<String>,
-) -> Result<(), StatusCode> {
+    Query(query): Query<VertipadAdjacencyQuery>,
+) -> Result<Json<VertipadAdjacency>, StatusCode> {
     rest_info!("entry [{}].", &id);
 
     let id = to_uuid(&id)
@@ -223,280 +701,1736 @@ pub async fn remove_vertiport(
         })?
         .to_string();
 
-    grpc_clients
-        .storage
-        .vertiport
-        .delete(Id { id })
-        .await
-        .map_err(|e| {
-            rest_error!("could not remove vertiport: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+    let threshold_m = query
+        .threshold_m
+        .unwrap_or_else(|| config.read(|config| config.vertipad_adjacency_threshold_m));
+    if !threshold_m.is_finite() || threshold_m < 0.0 {
+        rest_error!("Invalid adjacency threshold: {}", threshold_m);
+        return Err(StatusCode::BAD_REQUEST);
+    }
 
-    Ok(())
+    repos.vertiports.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not get vertiport: {e}");
+        storage_error_status(&e)
+    })?;
+
+    let locations = vertiport_vertipads(&repos, &id)
+        .await?
+        .into_iter()
+        .filter_map(|object| {
+            let location = object.data?.geo_location?;
+            Some((object.id, location))
+        })
+        .collect();
+
+    Ok(Json(vertipad_adjacency(&id, threshold_m, locations)))
 }
 
+/// Get the vertipads of a [`Vertiport`] sorted by elevation.
+///
+/// Vertipads are sorted by the altitude of their location in meters above
+/// mean sea level, lowest first, and by id for equal altitudes. Used for the
+/// approach planning of elevated rooftop vertipads.
 #[utoipa::path(
     get,
-    path = "/assets/demo/vertiports",
+    path = "/assets/vertiports/{id}/vertipads/by-elevation",
     tag = "svc-assets",
     responses(
-        (status = 200, description = "Assets successfully found", body = [Vertiport]),
+        (status = 200, description = "Vertipads of vertiport {id}, lowest first", body = [Vertipad]),
+        (status = 400, description = "Invalid vertiport id"),
+        (status = 404, description = "Vertiport not found in database"),
         (status = 503, description = "Could not connect to other microservice dependencies")
     ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
 )]
-/// Get all vertiports from the database.
-pub async fn get_all_vertiports(
-    Extension(grpc_clients): Extension<GrpcClients>,
-) -> Result<Json<Vec<Vertiport>>, StatusCode> {
-    rest_info!("entry.");
-    let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
-    let assets: Vec<Vertiport> = grpc_clients
-        .storage
-        .vertiport
-        .search(filter)
-        .await
-        .map_err(|e| {
-            rest_error!("could not retrieve vertiports: {e}.");
-            StatusCode::INTERNAL_SERVER_ERROR
+pub async fn get_vertipads_by_elevation(
+    Extension(repos): Extension<Repositories>,
+    Extension(ops_statuses): Extension<VertiportOpsStatuses>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<Vertipad>>, StatusCode> {
+    rest_info!("entry [{}].", &id);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertiport id: {}", &id);
+            StatusCode::BAD_REQUEST
         })?
-        .into_inner()
-        .list
+        .to_string();
+
+    repos.vertiports.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not get vertiport: {e}");
+        storage_error_status(&e)
+    })?;
+
+    let mut vertipads: Vec<Vertipad> = vertiport_vertipads(&repos, &id)
+        .await?
         .into_iter()
-        .filter_map(|object| object.try_into().ok())
+        .filter_map(|object| {
+            let object_id = object.id.clone();
+            Vertipad::try_from(object)
+                .map_err(|e| rest_warn!("could not convert vertipad [{}]: {e}", object_id))
+                .ok()
+        })
+        .map(|vertipad| vertipad.apply_ops_status(&ops_statuses))
         .collect();
-
-    Ok(Json(assets))
+    vertipads.sort_by(|a, b| {
+        a.geo_location
+            .altitude
+            .total_cmp(&b.geo_location.altitude)
+            .then_with(|| a.basics.id.cmp(&b.basics.id))
+    });
+
+    Ok(Json(vertipads))
 }
 
-/// Get an [`Vertiport`] by its id.
+/// Suggest vertiports with a name starting with `q`, for autocomplete.
+///
+/// Names are compared case insensitive and served from the in memory
+/// [`VertiportIndex`], so a new or renamed vertiport is suggested after the
+/// next refresh of the index. With tenants configured, the vertiports of the
+/// tenant are searched in storage instead.
 #[utoipa::path(
     get,
+    path = "/assets/vertiports/typeahead",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Vertiports with a name starting with q, sorted by name", body = [VertiportSuggestion]),
+        (status = 400, description = "Empty prefix or invalid limit"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(VertiportTypeaheadQuery)
+)]
+pub async fn typeahead_vertiports(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(index): Extension<VertiportIndex>,
+    Query(query): Query<VertiportTypeaheadQuery>,
+) -> Result<Json<Vec<VertiportSuggestion>>, StatusCode> {
+    rest_debug!("entry [{}].", &query.q);
+
+    if query.q.trim().is_empty() {
+        rest_error!("Empty typeahead prefix.");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let limit = query.limit.unwrap_or(TYPEAHEAD_DEFAULT_LIMIT);
+    if limit == 0 || limit > TYPEAHEAD_MAX_LIMIT {
+        rest_error!("Invalid typeahead limit: {}", limit);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    if config.read(|config| config.tenants.is_empty()) {
+        return Ok(Json(index.search(&query.q, limit.into())));
+    }
+
+    search_vertiports(&repos, &query.q, limit.into())
+        .await
+        .map(Json)
+        .map_err(|e| {
+            rest_error!("could not search vertiports: {e}");
+            storage_error_status(&e)
+        })
+}
+
+/// Remove a [`Vertiport`] from the database.
+///
+/// If vertipads are still located at the vertiport, the removal is either
+/// rejected or the vertipads are removed as well, depending on the
+/// `vertiport_delete_policy` of the configuration.
+#[utoipa::path(
+    delete,
     path = "/assets/vertiports/{id}",
     tag = "svc-assets",
     responses(
-        (status = 200, description = "Vertiport {id} found from database", body = Vertiport),
-        (status = 404, description = "Vertiport not found in database"),
-        (status = 400, description = "Invalid vertiport id"),
+        (status = 200, description = "Vertiport removed from database; a UUID is returned", body = String),
+        (status = 409, description = "Vertipads are still located at the vertiport"),
         (status = 503, description = "Could not connect to other microservice dependencies")
     ),
     params(
         ("id" = String, Path, description = "Vertiport id"),
     )
 )]
-pub async fn get_vertiport_by_id(
-    Extension(grpc_clients): Extension<GrpcClients>,
-    Path(vertiport_id): Path<String>,
-) -> Result<Json<Vertiport>, StatusCode> {
-    rest_info!("entry [{}].", vertiport_id);
+pub async fn remove_vertiport(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(localizations): Extension<Localizations>,
+    Extension(classifications): Extension<PadClassifications>,
+    Extension(chargers): Extension<VertipadChargers>,
+    Extension(equipment): Extension<VertipadEquipment>,
+    Extension(occupancy): Extension<OccupancyLog>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Extension(ops_statuses): Extension<VertiportOpsStatuses>,
+    Extension(weather_minimums): Extension<WeatherMinimums>,
+    Extension(restriction_rules): Extension<RestrictionRules>,
+    Extension(removed): Extension<RemovedAssets>,
+    Path(id): Path<String>,
+) -> Result<(), StatusCode> {
+    rest_info!("entry [{}].", &id);
 
-    let id = to_uuid(&vertiport_id)
+    let id = to_uuid(&id)
         .ok_or_else(|| {
-            rest_error!("Invalid vertiport id: {}", vertiport_id);
+            rest_error!("Invalid vertiport id: {}", &id);
             StatusCode::BAD_REQUEST
         })?
         .to_string();
 
-    let vertiport: Vertiport = grpc_clients
-        .storage
-        .vertiport
-        .get_by_id(Id { id })
-        .await
-        .map_err(|e| {
-            rest_error!("error getting vertiport from storage: {e}");
-            StatusCode::NOT_FOUND
-        })?
-        .into_inner()
-        .try_into()
-        .map_err(|e| {
-            rest_error!("error converting vehicle to vertiport: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+    let vertipads = vertiport_vertipad_ids(&repos, &id).await?;
+    if !vertipads.is_empty() {
+        match config.read(|config| config.vertiport_delete_policy) {
+            VertiportDeletePolicy::Reject => {
+                rest_warn!(
+                    "vertiport {id} can not be removed, {} vertipads are located at it.",
+                    vertipads.len()
+                );
+                return Err(StatusCode::CONFLICT);
+            }
+            VertiportDeletePolicy::Cascade => {
+                for vertipad_id in vertipads {
+                    repos
+                        .vertipads
+                        .delete(vertipad_id.clone())
+                        .await
+                        .map_err(|e| {
+                            rest_error!("could not remove vertipad {vertipad_id}: {e}");
+                            storage_error_status(&e)
+                        })?;
+                    removed.record(&vertipad_id, Utc::now());
+                    localizations.remove(&vertipad_id);
+                    classifications.remove(&vertipad_id);
+                    chargers.remove_vertipad(&vertipad_id);
+                    equipment.remove_vertipad(&vertipad_id);
+                    occupancy.remove(&vertipad_id);
+                }
+            }
+        }
+    }
 
-    rest_info!("Vertiport found: {}", vertiport_id);
+    repos.vertiports.delete(id.clone()).await.map_err(|e| {
+        rest_error!("could not remove vertiport: {e}");
+        storage_error_status(&e)
+    })?;
+    removed.record(&id, Utc::now());
+    quotas.release(QuotaKind::Vertiport, &id);
+    localizations.remove(&id);
+    ops_statuses.remove(&id);
+    if let Err(e) = weather_minimums.remove(&id) {
+        rest_warn!(
+            "could not remove the weather minimums of vertiport [{}]: {e}",
+            id
+        );
+    }
+    restriction_rules.remove_vertiport(&id);
 
-    Ok(Json(vertiport))
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::rest::structs::AssetsInfo;
-    use lib_common::time::Utc;
-    use lib_common::uuid::Uuid;
-
-    #[test]
-    fn test_vertiport_basics_trait_methods() {
-        let vertiport = vertiport::mock::get_data_obj();
-        let basics = Basics {
-            id: Uuid::new_v4().to_string(),
-            name: Some("Test asset".to_string()),
-            group_id: Some(Uuid::new_v4().to_string()),
-            created_at: vertiport
+/// Set the localized names and descriptions of a [`Vertiport`].
+///
+/// Replaces all existing localizations of the vertiport. Localizations are
+/// persisted, see [`crate::rest::locale`].
+#[utoipa::path(
+    put,
+    path = "/assets/vertiports/{id}/localization",
+    tag = "svc-assets",
+    request_body = AssetLocalization,
+    responses(
+        (status = 200, description = "Vertiport localization updated"),
+        (status = 400, description = "Invalid vertiport id"),
+        (status = 404, description = "Vertiport not found in database"),
+        (status = 422, description = "Invalid localization", body = [FieldError]),
+        (status = 500, description = "The localization could not be persisted"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
+)]
+pub async fn put_vertiport_localization(
+    Extension(repos): Extension<Repositories>,
+    Extension(localizations): Extension<Localizations>,
+    Path(id): Path<String>,
+    Json(payload): Json<AssetLocalization>,
+) -> Result<(), ValidationError> {
+    rest_info!("entry [{}].", &id);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertiport id: {}", &id);
+            (StatusCode::BAD_REQUEST, Json(vec![]))
+        })?
+        .to_string();
+
+    let localization = validate_localization(payload).map_err(unprocessable)?;
+
+    repos.vertiports.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not get vertiport: {e}");
+        (storage_error_status(&e), Json(vec![]))
+    })?;
+
+    localizations.set(&id, localization).map_err(|e| {
+        rest_error!("could not persist vertiport localization: {e}");
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+    })?;
+    rest_info!("successfully updated vertiport localization.");
+    Ok(())
+}
+
+/// Get the operational status of a [`Vertiport`].
+///
+/// A vertiport of which the status was never changed is `Open`.
+#[utoipa::path(
+    get,
+    path = "/assets/vertiports/{id}/ops-status",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Operational status of vertiport {id}", body = VertiportOpsState),
+        (status = 400, description = "Invalid vertiport id"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
+)]
+pub async fn get_vertiport_ops_status(
+    Extension(ops_statuses): Extension<VertiportOpsStatuses>,
+    Path(id): Path<String>,
+) -> Result<Json<VertiportOpsState>, StatusCode> {
+    rest_info!("entry [{}].", &id);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertiport id: {}", &id);
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    Ok(Json(ops_statuses.get(&id, Utc::now())))
+}
+
+/// Set the operational status of a [`Vertiport`].
+///
+/// Replaces the previous status. A `Closed` or `Emergency` vertiport
+/// disables its vertipads within the effective time window. The status is
+/// persisted, see [`crate::ops_status`].
+#[utoipa::path(
+    put,
+    path = "/assets/vertiports/{id}/ops-status",
+    tag = "svc-assets",
+    request_body = VertiportOpsStatusPayload,
+    responses(
+        (status = 200, description = "Operational status updated; the status is returned", body = VertiportOpsState),
+        (status = 400, description = "Invalid vertiport id"),
+        (status = 404, description = "Vertiport not found in database"),
+        (status = 422, description = "Invalid effective time window", body = [FieldError]),
+        (status = 500, description = "The status could not be persisted"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
+)]
+pub async fn put_vertiport_ops_status(
+    Extension(repos): Extension<Repositories>,
+    Extension(ops_statuses): Extension<VertiportOpsStatuses>,
+    Path(id): Path<String>,
+    Json(mut payload): Json<VertiportOpsStatusPayload>,
+) -> Result<Json<VertiportOpsState>, ValidationError> {
+    rest_info!("entry [{}].", &id);
+    rest_debug!("Payload: {:?}", &payload);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertiport id: {}", &id);
+            (StatusCode::BAD_REQUEST, Json(vec![]))
+        })?
+        .to_string();
+    normalize_optional_string(&mut payload.reason);
+
+    repos.vertiports.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not get vertiport: {e}");
+        (storage_error_status(&e), Json(vec![]))
+    })?;
+
+    let state = ops_statuses
+        .set(&id, payload, Utc::now())
+        .map_err(|e| match e {
+            OpsStatusError::State(e) => {
+                rest_error!("could not persist vertiport operational status: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+            }
+            e => {
+                rest_error!("could not set vertiport operational status: {e}");
+                unprocessable(vec![field_error("effective_until", &e.to_string())])
+            }
+        })?;
+
+    rest_info!(
+        "vertiport operational status set to {:?} [{}].",
+        state.status,
+        id
+    );
+    Ok(Json(state))
+}
+
+/// Get the weather minimums of a [`Vertiport`].
+#[utoipa::path(
+    get,
+    path = "/assets/vertiports/{id}/weather-minimums",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Weather minimums of vertiport {id}", body = VertiportWeatherMinimums),
+        (status = 400, description = "Invalid vertiport id"),
+        (status = 404, description = "No weather minimums configured for vertiport {id}"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
+)]
+pub async fn get_vertiport_weather_minimums(
+    Extension(weather_minimums): Extension<WeatherMinimums>,
+    Path(id): Path<String>,
+) -> Result<Json<VertiportWeatherMinimums>, StatusCode> {
+    rest_info!("entry [{}].", &id);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertiport id: {}", &id);
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    weather_minimums.get(&id).map(Json).ok_or_else(|| {
+        rest_info!("no weather minimums configured for vertiport [{}].", id);
+        StatusCode::NOT_FOUND
+    })
+}
+
+/// Set the weather minimums of a [`Vertiport`].
+///
+/// Replaces the previous minimums. The minimums are persisted and exposed to
+/// svc-scheduler through the gRPC API of this instance only, see
+/// [`crate::weather_minimums`].
+#[utoipa::path(
+    put,
+    path = "/assets/vertiports/{id}/weather-minimums",
+    tag = "svc-assets",
+    request_body = WeatherMinimumsPayload,
+    responses(
+        (status = 200, description = "Weather minimums updated; the minimums are returned", body = VertiportWeatherMinimums),
+        (status = 400, description = "Invalid vertiport id"),
+        (status = 404, description = "Vertiport not found in database"),
+        (status = 422, description = "Invalid weather minimums", body = [FieldError]),
+        (status = 500, description = "The minimums could not be persisted"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
+)]
+pub async fn put_vertiport_weather_minimums(
+    Extension(repos): Extension<Repositories>,
+    Extension(weather_minimums): Extension<WeatherMinimums>,
+    Path(id): Path<String>,
+    Json(payload): Json<WeatherMinimumsPayload>,
+) -> Result<Json<VertiportWeatherMinimums>, ValidationError> {
+    rest_info!("entry [{}].", &id);
+    rest_debug!("Payload: {:?}", &payload);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertiport id: {}", &id);
+            (StatusCode::BAD_REQUEST, Json(vec![]))
+        })?
+        .to_string();
+    let payload = validate_weather_minimums(payload).map_err(unprocessable)?;
+
+    repos.vertiports.get_by_id(id.clone()).await.map_err(|e| {
+        rest_error!("could not get vertiport: {e}");
+        (storage_error_status(&e), Json(vec![]))
+    })?;
+
+    let minimums = weather_minimums
+        .set(&id, payload, Utc::now())
+        .map_err(|e| {
+            rest_error!("could not persist vertiport weather minimums: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+        })?;
+    rest_info!("vertiport weather minimums set [{}].", id);
+    Ok(Json(minimums))
+}
+
+/// Remove the weather minimums of a [`Vertiport`].
+#[utoipa::path(
+    delete,
+    path = "/assets/vertiports/{id}/weather-minimums",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Weather minimums removed"),
+        (status = 400, description = "Invalid vertiport id"),
+        (status = 404, description = "No weather minimums configured for vertiport {id}"),
+        (status = 500, description = "The removal could not be persisted"),
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
+)]
+pub async fn remove_vertiport_weather_minimums(
+    Extension(weather_minimums): Extension<WeatherMinimums>,
+    Path(id): Path<String>,
+) -> Result<(), StatusCode> {
+    rest_info!("entry [{}].", &id);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertiport id: {}", &id);
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    match weather_minimums.remove(&id) {
+        Ok(true) => {
+            rest_info!("vertiport weather minimums removed [{}].", id);
+            Ok(())
+        }
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            rest_error!("could not persist the removal of the weather minimums: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/assets/demo/vertiports",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Assets successfully found; the `x-dropped-records` header counts the stored objects which could not be converted, a `Warning` header reports a list larger than `rest_list_warn_results`", body = [Vertiport]),
+        (status = 207, description = "Stored objects could not be converted in report conversion mode; the converted assets are returned with the failed objects", body = PartialList, content_type = "application/problem+json"),
+        (status = 400, description = "Invalid time filters"),
+        (status = 422, description = "More assets match the query than `rest_list_max_results`", body = [FieldError]),
+        (status = 502, description = "Stored objects could not be converted in strict conversion mode", body = SkippedObjects),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(GeometryQuery, ListAssetsQuery)
+)]
+/// Get all vertiports from the database, optionally filtered by their
+/// creation and update times and by their owner.
+pub async fn get_all_vertiports(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(removed): Extension<RemovedAssets>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Query(geometry): Query<GeometryQuery>,
+    Query(query): Query<ListAssetsQuery>,
+) -> Result<Listed<Vertiport>, ListError> {
+    rest_info!("entry.");
+    rest_debug!("Query: {:?}", query);
+    let limits = config.read(ListLimits::from_config);
+    let filter = owner_filter(&query, list_filter(&query)?, "vertiport_id", |owner_id| {
+        quotas.assets(owner_id, QuotaKind::Vertiport)
+    });
+    let objects = match filter {
+        Some(filter) => repos
+            .vertiports
+            .search(limits.limit_filter(filter))
+            .await
+            .map_err(|e| {
+                rest_error!("could not retrieve vertiports: {e}.");
+                storage_error_status(&e)
+            })?,
+        None => vec![],
+    };
+    limits.check(objects.len())?;
+
+    let mut listed: Listed<Vertiport> =
+        convert_objects(objects, config.read(|c| c.conversion_mode))?;
+    let geometry = geometry.geometry.unwrap_or_default();
+    listed.assets = listed
+        .assets
+        .into_iter()
+        .map(|vertiport| vertiport.apply_owner(&quotas))
+        .filter(|vertiport| matches_list_query(&query, vertiport))
+        .map(|vertiport| vertiport.with_geometry(geometry))
+        .collect();
+    for vertiport in &mut listed.assets {
+        vertiport.basics.deleted_at = removed.removed_at(&vertiport.basics.id);
+    }
+
+    limits.warn(&mut listed);
+    Ok(listed)
+}
+
+/// Get an [`Vertiport`] by its id.
+///
+/// `?geometry=centroid` leaves out the area of the vertiport, and
+/// `?geometry=none` all of its geometry.
+#[utoipa::path(
+    get,
+    path = "/assets/vertiports/{id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Vertiport {id} found from database", body = Vertiport),
+        (status = 404, description = "Vertiport not found in database"),
+        (status = 400, description = "Invalid vertiport id or geometry"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+        GeometryQuery,
+    )
+)]
+pub async fn get_vertiport_by_id(
+    Extension(repos): Extension<Repositories>,
+    Extension(localizations): Extension<Localizations>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Path(vertiport_id): Path<String>,
+    Query(geometry): Query<GeometryQuery>,
+    headers: HeaderMap,
+) -> Result<Json<Vertiport>, StatusCode> {
+    rest_info!("entry [{}].", vertiport_id);
+
+    let id = to_uuid(&vertiport_id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertiport id: {}", vertiport_id);
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    let vertiport: Vertiport = repos
+        .vertiports
+        .get_by_id(id.clone())
+        .await
+        .map_err(|e| {
+            rest_error!("error getting vertiport from storage: {e}");
+            storage_error_status(&e)
+        })?
+        .try_into()
+        .map_err(|e| {
+            rest_error!("error converting vehicle to vertiport: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let mut vertiport = vertiport.apply_owner(&quotas);
+
+    rest_info!("Vertiport found: {}", vertiport_id);
+
+    if let Some(localization) = localizations.get(&id) {
+        vertiport.localize(localization, &accept_languages(&headers));
+    }
+
+    Ok(Json(
+        vertiport.with_geometry(geometry.geometry.unwrap_or_default()),
+    ))
+}
+
+/// Check if a [`Vertiport`] exists.
+///
+/// Returns the `ETag` and `Last-Modified` headers of the vertiport without a
+/// body.
+#[utoipa::path(
+    head,
+    path = "/assets/vertiports/{id}",
+    tag = "svc-assets",
+    responses(
+        (status = 200, description = "Vertiport {id} exists"),
+        (status = 404, description = "Vertiport not found in database"),
+        (status = 400, description = "Invalid vertiport id"),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
+)]
+pub async fn head_vertiport_by_id(
+    Extension(repos): Extension<Repositories>,
+    Path(vertiport_id): Path<String>,
+) -> Result<AssetHeaders, StatusCode> {
+    rest_info!("entry [{}].", vertiport_id);
+    let id = to_uuid(&vertiport_id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertiport id: {}", vertiport_id);
+            StatusCode::BAD_REQUEST
+        })?
+        .to_string();
+
+    let vertiport: Vertiport = repos
+        .vertiports
+        .get_by_id(id)
+        .await
+        .map_err(|e| {
+            rest_error!("could not retrieve vertiport: {e}");
+            storage_error_status(&e)
+        })?
+        .try_into()
+        .map_err(|e| {
+            rest_error!("could not convert vertiport: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(asset_headers(vertiport.basics.updated_at))
+}
+
+/// Get multiple [`Vertiport`]s by their ids.
+///
+/// The vertiports are fetched concurrently. Ids which could not be found are
+/// returned in the `missing` list, objects which could not be converted in
+/// the `failed` list.
+#[utoipa::path(
+    post,
+    path = "/assets/vertiports/batch-get",
+    tag = "svc-assets",
+    request_body = BatchGetPayload,
+    responses(
+        (status = 200, description = "Vertiports found and ids missing from database", body = VertiportBatch),
+        (status = 400, description = "Invalid ids or too many ids requested"),
+        (status = 502, description = "Stored objects could not be converted in strict conversion mode", body = SkippedObjects),
+        (status = 503, description = "Could not connect to other microservice dependencies"),
+        (status = 504, description = "A svc-storage call timed out")
+    )
+)]
+pub async fn batch_get_vertiports(
+    Extension(repos): Extension<Repositories>,
+    Extension(fan_out): Extension<FanOut>,
+    Extension(config): Extension<SharedConfig>,
+    Extension(quotas): Extension<OperatorQuotas>,
+    Json(payload): Json<BatchGetPayload>,
+) -> Result<Json<VertiportBatch>, ListError> {
+    rest_info!("entry.");
+    rest_debug!("Payload: {:?}", &payload);
+
+    let ids = parse_batch_ids(payload)?;
+    let mode = config.read(|c| c.conversion_mode);
+    let mut response: VertiportBatch = batch_get(ids, mode, |id| {
+        let repos = repos.clone();
+        let fan_out = fan_out.clone();
+        async move {
+            match fan_out.call(repos.vertiports.get_by_id(id)).await {
+                Ok(object) => Ok(Some(object)),
+                Err(e) => match storage_error_status(&e) {
+                    StatusCode::NOT_FOUND => Ok(None),
+                    status => {
+                        rest_error!("could not retrieve vertiport: {e}");
+                        Err(status)
+                    }
+                },
+            }
+        }
+    })
+    .await?;
+    response.found = response
+        .found
+        .into_iter()
+        .map(|vertiport| vertiport.apply_owner(&quotas))
+        .collect();
+
+    rest_debug!(
+        "found {}, missing {}, failed {}.",
+        response.found.len(),
+        response.missing.len(),
+        response.failed.len()
+    );
+    Ok(Json(response))
+}
+
+/// Update/modify a [`Vertiport`] in the database using a JSON Merge Patch (RFC 7396).
+///
+/// Only the fields present in the patch are updated, fields set to `null`
+/// are removed.
+#[utoipa::path(
+    patch,
+    path = "/assets/vertiports/{id}",
+    tag = "svc-assets",
+    request_body(content = Object, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "Vertiport updated in database, `changed` is `false` if nothing differed", body = UpdateResult),
+        (status = 400, description = "Invalid vertiport id"),
+        (status = 404, description = "Vertiport not found in database"),
+        (status = 422, description = "Patch can not be applied; a list of field errors is returned", body = [FieldError]),
+        (status = 503, description = "Could not connect to other microservice dependencies")
+    ),
+    params(
+        ("id" = String, Path, description = "Vertiport id"),
+    )
+)]
+pub async fn patch_vertiport(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<SharedConfig>,
+    Path(id): Path<String>,
+    Json(patch): Json<serde_json::Value>,
+) -> Result<Json<UpdateResult>, ValidationError> {
+    rest_info!("entry [{}].", id);
+    rest_debug!("Patch: {:?}", &patch);
+
+    let id = to_uuid(&id)
+        .ok_or_else(|| {
+            rest_error!("Invalid vertiport id.");
+            (StatusCode::BAD_REQUEST, Json(vec![]))
+        })?
+        .to_string();
+
+    let payload: UpdateVertiportPayload = merge_patch_payload(
+        &id,
+        patch,
+        VERTIPORT_PATCH_FIELDS,
+        VERTIPORT_NULLABLE_FIELDS,
+    )
+    .map_err(unprocessable)?;
+
+    update_vertiport(Extension(repos), Extension(config), Json(payload)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::client::GrpcClients;
+    use crate::rest::quota::{AssetOwners, OPERATOR_ID_HEADER};
+
+    fn operator_quotas() -> Extension<OperatorQuotas> {
+        Extension(OperatorQuotas::new(
+            crate::config::Config::default().into(),
+            AssetOwners::default(),
+        ))
+    }
+
+    /// Headers of a registration by a new operator
+    fn operator_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            OPERATOR_ID_HEADER,
+            Uuid::new_v4().to_string().parse().unwrap(),
+        );
+        headers
+    }
+
+    fn localizations() -> Extension<Localizations> {
+        Extension(Localizations::default())
+    }
+
+    fn classifications() -> Extension<PadClassifications> {
+        Extension(PadClassifications::default())
+    }
+
+    fn chargers() -> Extension<VertipadChargers> {
+        Extension(VertipadChargers::default())
+    }
+
+    fn equipment() -> Extension<VertipadEquipment> {
+        Extension(VertipadEquipment::default())
+    }
+
+    fn occupancy_log() -> Extension<OccupancyLog> {
+        Extension(OccupancyLog::default())
+    }
+
+    fn ops_statuses() -> Extension<VertiportOpsStatuses> {
+        Extension(VertiportOpsStatuses::default())
+    }
+
+    fn weather_minimums() -> Extension<WeatherMinimums> {
+        Extension(WeatherMinimums::default())
+    }
+
+    fn shared_config(policy: VertiportDeletePolicy) -> Extension<SharedConfig> {
+        let mut config = crate::config::Config::default();
+        config.vertiport_delete_policy = policy;
+        Extension(config.into())
+    }
+
+    fn fan_out() -> Extension<FanOut> {
+        Extension(FanOut::new(crate::Config::default().into()))
+    }
+    use crate::rest::structs::AssetsInfo;
+    use crate::testing::{VertipadDataBuilder, VertiportDataBuilder};
+    use axum::{http::header, response::IntoResponse};
+    use lib_common::time::Utc;
+    use lib_common::uuid::Uuid;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_vertiport_basics_trait_methods() {
+        let now = Utc::now();
+        let vertiport = VertiportDataBuilder::seeded(1).timestamps(now, now).build();
+        let basics = Basics {
+            id: Uuid::new_v4().to_string(),
+            name: Some("Test asset".to_string()),
+            group_id: Some(Uuid::new_v4().to_string()),
+            created_at: vertiport
                 .created_at
-                .expect("mock didn't return a created_at date.")
+                .expect("builder didn't set a created_at date.")
                 .into(),
             updated_at: vertiport
                 .updated_at
-                .expect("mock didn't return a updated_at date.")
+                .expect("builder didn't set a updated_at date.")
                 .into(),
+            deleted_at: None,
+            owner_id: None,
             whitelist: vec![Uuid::new_v4().to_string()],
             status: AssetStatus::Available,
         };
 
-        let mut vertiport = Vertiport {
-            basics: basics.clone(),
-            description: vertiport.description,
-            geo_location: vertiport.geo_location.unwrap(),
-            schedule: vertiport.schedule,
+        let mut vertiport = Vertiport {
+            basics: basics.clone(),
+            description: vertiport.description,
+            geo_location: Some(vertiport.geo_location.unwrap().into()),
+            centroid: None,
+            bbox: None,
+            schedule: vertiport.schedule,
+            localization: None,
+        };
+        assert_eq!(
+            vertiport.id().unwrap(),
+            Uuid::parse_str(&basics.id).unwrap()
+        );
+        assert_eq!(vertiport.name(), basics.name.clone().unwrap());
+        assert_eq!(vertiport.created_at(), basics.created_at);
+        assert_eq!(vertiport.updated_at(), basics.updated_at);
+        assert_eq!(vertiport.is_grouped(), true);
+        assert_eq!(vertiport.is_public(), false);
+        assert_eq!(vertiport.status(), basics.status);
+
+        vertiport.basics.name = None;
+        assert_eq!(vertiport.full_name(), VERTIPORT_NAME);
+
+        vertiport.basics.group_id = None;
+        assert_eq!(vertiport.is_grouped(), false);
+        assert_eq!(vertiport.group_id(), Ok(None));
+    }
+
+    #[test]
+    fn test_vertiport_from_vertiport_object() {
+        let expected_vertiport_id = Uuid::new_v4();
+        let now = Utc::now();
+        let mut vertiport_data = VertiportDataBuilder::seeded(2).timestamps(now, now).build();
+        let mut vertiport_obj = vertiport::Object {
+            id: expected_vertiport_id.clone().to_string(),
+            data: Some(vertiport_data.clone()),
+        };
+        let vertiport_result = vertiport_obj.clone().try_into();
+        assert!(vertiport_result.is_ok());
+        let vertiport: Vertiport = vertiport_result.unwrap();
+
+        assert_eq!(vertiport.id(), Ok(expected_vertiport_id));
+        assert_eq!(vertiport.name(), vertiport_data.name);
+        assert_eq!(
+            vertiport.created_at(),
+            vertiport_data
+                .created_at
+                .clone()
+                .expect("builder didn't set a created_at date.")
+                .into()
+        );
+        assert_eq!(
+            vertiport.updated_at(),
+            vertiport_data
+                .updated_at
+                .clone()
+                .expect("builder didn't set a updated_at date.")
+                .into()
+        );
+        assert_eq!(vertiport.is_grouped(), false);
+        assert_eq!(vertiport.is_public(), true);
+        assert_eq!(vertiport.status(), AssetStatus::Available);
+
+        vertiport_obj.data = None;
+        let result: Result<Vertiport, String> = vertiport_obj.clone().try_into();
+        assert_eq!(result.unwrap_err(), "(try_from) vertiport data is missing");
+
+        vertiport_data.updated_at = None;
+        vertiport_obj.data = Some(vertiport_data.clone());
+        let result: Result<Vertiport, String> = vertiport_obj.clone().try_into();
+        assert_eq!(
+            result.unwrap_err(),
+            "(try_from) updated_at field empty but expected."
+        );
+
+        vertiport_data.created_at = None;
+        vertiport_data.updated_at = Some(Utc::now().into());
+        vertiport_obj.data = Some(vertiport_data.clone());
+        let result: Result<Vertiport, String> = vertiport_obj.clone().try_into();
+        assert_eq!(
+            result.unwrap_err(),
+            "(try_from) created_at field empty but expected."
+        );
+
+        vertiport_data.created_at = Some(Utc::now().into());
+        vertiport_data.geo_location = None;
+        vertiport_obj.data = Some(vertiport_data.clone());
+        let result: Result<Vertiport, String> = vertiport_obj.clone().try_into();
+        assert_eq!(
+            result.unwrap_err(),
+            "(try_from) geo_location empty but expected."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_vertiport_registration() {
+        let mut data = VertiportDataBuilder::seeded(3).build();
+        data.name = " Test Vertiport ".to_string();
+
+        let result = validate_vertiport_registration(Json(data.clone()))
+            .await
+            .unwrap();
+        assert_eq!(result.0.name, "Test Vertiport");
+
+        data.name = "".to_string();
+        data.geo_location = None;
+        let (status, errors) = validate_vertiport_registration(Json(data.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0.len(), 2);
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+        let error = register_vertiport(
+            Extension(repos),
+            operator_quotas(),
+            Query(RegisterVertiportQuery::default()),
+            operator_headers(),
+            Json(data),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_validate_vertiport_update() {
+        let payload = UpdateVertiportPayload {
+            id: Uuid::new_v4().to_string(),
+            name: Some("".to_string()),
+            description: None,
+            geo_location: None,
+            schedule: None,
+            expected_updated_at: None,
+            mask: vec!["name".to_string()],
+        };
+
+        let (status, errors) = validate_vertiport_update(Json(payload)).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0[0].field, "name");
+    }
+
+    #[tokio::test]
+    async fn test_register_vertiport() {
+        let vertiport_data = VertiportDataBuilder::new().build();
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+        let result = register_vertiport(
+            Extension(repos.clone()),
+            operator_quotas(),
+            Query(RegisterVertiportQuery::default()),
+            operator_headers(),
+            Json(vertiport_data.clone()),
+        )
+        .await
+        .unwrap();
+
+        // Confirm valid UUID
+        to_uuid(&result).unwrap();
+
+        // the operator registering the vertiport is its owner
+        let error = register_vertiport(
+            Extension(repos),
+            operator_quotas(),
+            Query(RegisterVertiportQuery::default()),
+            HeaderMap::new(),
+            Json(vertiport_data),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, RegistrationError::Status(StatusCode::BAD_REQUEST));
+    }
+
+    #[tokio::test]
+    async fn test_register_vertiport_with_pads() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let data = VertiportDataBuilder::new().name("Test").build();
+        let query = |auto_create_pads: u8, pad_layout: PadLayout| RegisterVertiportQuery {
+            auto_create_pads: Some(auto_create_pads),
+            pad_layout: Some(pad_layout),
         };
+
+        let repos = Repositories::memory();
+        let id = register_vertiport(
+            Extension(repos.clone()),
+            operator_quotas(),
+            Query(query(4, PadLayout::Grid)),
+            operator_headers(),
+            Json(data.clone()),
+        )
+        .await
+        .unwrap();
+
+        let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+        let vertipads = repos.vertipads.search(filter.clone()).await.unwrap();
+        assert_eq!(vertipads.len(), 4);
+        assert!(vertipads.iter().all(|object| {
+            let data = object.data.as_ref().unwrap();
+            data.vertiport_id == id && data.name.starts_with("Test Pad ")
+        }));
+
+        register_vertiport(
+            Extension(repos.clone()),
+            operator_quotas(),
+            Query(query(3, PadLayout::CentroidOffset)),
+            operator_headers(),
+            Json(data.clone()),
+        )
+        .await
+        .unwrap();
         assert_eq!(
-            vertiport.id().unwrap(),
-            Uuid::parse_str(&basics.id).unwrap()
+            repos.vertipads.search(filter.clone()).await.unwrap().len(),
+            7
         );
-        assert_eq!(vertiport.name(), basics.name.clone().unwrap());
-        assert_eq!(vertiport.created_at(), basics.created_at);
-        assert_eq!(vertiport.updated_at(), basics.updated_at);
-        assert_eq!(vertiport.is_grouped(), true);
-        assert_eq!(vertiport.is_public(), false);
-        assert_eq!(vertiport.status(), basics.status);
 
-        vertiport.basics.name = None;
-        assert_eq!(vertiport.full_name(), VERTIPORT_NAME);
+        let error = register_vertiport(
+            Extension(repos.clone()),
+            operator_quotas(),
+            Query(query(MAX_AUTO_CREATE_PADS + 1, PadLayout::Grid)),
+            operator_headers(),
+            Json(data.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
-        vertiport.basics.group_id = None;
-        assert_eq!(vertiport.is_grouped(), false);
-        assert_eq!(vertiport.group_id(), Ok(None));
+        // the vertiport is removed again if the vertipads can not be created
+        let memory = Repositories::memory();
+        let repos = Repositories {
+            vertipads: Repositories::unavailable().vertipads,
+            ..memory.clone()
+        };
+        let error = register_vertiport(
+            Extension(repos),
+            operator_quotas(),
+            Query(query(2, PadLayout::Grid)),
+            operator_headers(),
+            Json(data),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error,
+            RegistrationError::Incomplete(StatusCode::SERVICE_UNAVAILABLE, vec![])
+        );
+        assert!(memory.vertiports.search(filter).await.unwrap().is_empty());
+
+        ut_info!("success");
     }
 
-    #[test]
-    fn test_vertiport_from_vertiport_object() {
-        let expected_vertiport_id = Uuid::new_v4();
-        let mut vertiport_data: vertiport::Data = vertiport::mock::get_data_obj();
-        let mut vertiport_obj = vertiport::Object {
-            id: expected_vertiport_id.clone().to_string(),
-            data: Some(vertiport_data.clone()),
+    #[tokio::test]
+    async fn test_update_vertiport() {
+        let data = VertiportDataBuilder::new()
+            .geo_location(GeoPolygonZ { rings: vec![] })
+            .timestamps(Utc::now(), Utc::now())
+            .build();
+
+        let mut payload = UpdateVertiportPayload {
+            id: Uuid::new_v4().to_string(),
+            name: Some("Test Vertiport".to_string()),
+            description: Some("Test description".to_string()),
+            geo_location: None,
+            schedule: Some("Test schedule".to_string()),
+            expected_updated_at: None,
+            mask: vec!["name".to_string()],
         };
-        let vertiport_result = vertiport_obj.clone().try_into();
-        assert!(vertiport_result.is_ok());
-        let vertiport: Vertiport = vertiport_result.unwrap();
 
-        assert_eq!(vertiport.id(), Ok(expected_vertiport_id));
-        assert_eq!(vertiport.name(), vertiport_data.name);
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        // INVALID UUID
+        payload.id = "invalid".to_string();
+        let result = update_vertiport(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::Reject),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(result.0, StatusCode::BAD_REQUEST);
+
+        // NONEXISTENT RECORD
+        payload.id = Uuid::new_v4().to_string();
+        let result = update_vertiport(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::Reject),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(result.0, StatusCode::NOT_FOUND);
+
+        payload.id = grpc_clients
+            .storage
+            .vertiport
+            .insert(data.clone())
+            .await
+            .unwrap()
+            .into_inner()
+            .object
+            .unwrap()
+            .id;
+
+        // Update record
+        update_vertiport(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::Reject),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap();
+
+        // Record was updated since the expected timestamp
+        payload.expected_updated_at = Some(Utc::now() - chrono::Duration::days(1));
+        let result = update_vertiport(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::Reject),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(result.0, StatusCode::CONFLICT);
+
+        // Update based on the stored record
+        let stored = repos
+            .vertiports
+            .get_by_id(payload.id.clone())
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        payload.expected_updated_at = stored.updated_at.map(Into::into);
+        update_vertiport(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::Reject),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap();
+
+        // let expected = vertiport::Object {
+        //     id: payload.id.clone(),
+        //     data
+        // }
+        // .try_into()
+        // .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_vertiport_schedule_conflicts() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let vertipad_id = repos
+            .vertipads
+            .insert(
+                VertipadDataBuilder::new()
+                    .vertiport_id(id.clone())
+                    .schedule("DTSTART:20221020T200000Z;DURATION:PT4H\nRRULE:FREQ=DAILY")
+                    .build(),
+            )
+            .await
+            .unwrap()
+            .id;
+
+        let config = |mode: ScheduleConflictMode| {
+            let mut config = crate::config::Config::default();
+            config.schedule_conflict_mode = mode;
+            Extension(SharedConfig::from(config))
+        };
+        let patch = serde_json::json!({ "schedule": "DTSTART:20221020T060000Z;DURATION:PT16H\nRRULE:FREQ=DAILY" });
+
+        let (status, errors) = patch_vertiport(
+            Extension(repos.clone()),
+            config(ScheduleConflictMode::Strict),
+            Path(id.clone()),
+            Json(patch.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.0.len(), 1);
+        assert_eq!(errors.0[0].field, "schedule");
+        assert!(errors.0[0]
+            .message
+            .starts_with(&format!("vertipad {vertipad_id} ")));
+
+        patch_vertiport(
+            Extension(repos.clone()),
+            config(ScheduleConflictMode::Lenient),
+            Path(id),
+            Json(patch),
+        )
+        .await
+        .unwrap();
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_remove_vertiport() {
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        // INVALID UUID
+        let result = remove_vertiport(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::Reject),
+            localizations(),
+            classifications(),
+            chargers(),
+            equipment(),
+            occupancy_log(),
+            operator_quotas(),
+            ops_statuses(),
+            weather_minimums(),
+            Extension(RestrictionRules::default()),
+            Extension(RemovedAssets::default()),
+            Path("invalid".to_string()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(result, StatusCode::BAD_REQUEST);
+
+        // NONEXISTENT RECORD will return Ok(())
+        remove_vertiport(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::Reject),
+            localizations(),
+            classifications(),
+            chargers(),
+            equipment(),
+            occupancy_log(),
+            operator_quotas(),
+            ops_statuses(),
+            weather_minimums(),
+            Extension(RestrictionRules::default()),
+            Extension(RemovedAssets::default()),
+            Path(Uuid::new_v4().to_string()),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_remove_vertiport_with_vertipads() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::seeded(4).build())
+            .await
+            .unwrap()
+            .id;
+        let vertipad_data = VertipadDataBuilder::seeded(4).vertiport_id(&id).build();
+        let vertipad_id = repos.vertipads.insert(vertipad_data).await.unwrap().id;
+
+        let remove = |policy: VertiportDeletePolicy| {
+            remove_vertiport(
+                Extension(repos.clone()),
+                shared_config(policy),
+                localizations(),
+                classifications(),
+                chargers(),
+                equipment(),
+                occupancy_log(),
+                operator_quotas(),
+                ops_statuses(),
+                weather_minimums(),
+                Extension(RestrictionRules::default()),
+                Extension(RemovedAssets::default()),
+                Path(id.clone()),
+            )
+        };
+
+        let error = remove(VertiportDeletePolicy::Reject).await.unwrap_err();
+        assert_eq!(error, StatusCode::CONFLICT);
+        repos.vertiports.get_by_id(id.clone()).await.unwrap();
+
+        remove(VertiportDeletePolicy::Cascade).await.unwrap();
+        assert!(repos.vertiports.get_by_id(id.clone()).await.is_err());
+        assert!(repos.vertipads.get_by_id(vertipad_id).await.is_err());
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_get_all_vertiports() {
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        let data = VertiportDataBuilder::new()
+            .geo_location(GeoPolygonZ { rings: vec![] })
+            .timestamps(Utc::now(), Utc::now())
+            .build();
+
+        let _ = register_vertiport(
+            Extension(repos.clone()),
+            operator_quotas(),
+            Query(RegisterVertiportQuery::default()),
+            operator_headers(),
+            Json(data.clone()),
+        )
+        .await
+        .expect("Failed to register vertipad");
+
+        let response = get_all_vertiports(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::default()),
+            Extension(RemovedAssets::default()),
+            operator_quotas(),
+            Query(GeometryQuery::default()),
+            Query(ListAssetsQuery::default()),
+        )
+        .await
+        .unwrap();
+
+        assert!(!response.assets.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_all_vertiports_time_filters() {
+        let repos = Repositories::memory();
+        let now = Utc::now();
+        let day = chrono::Duration::days(1);
+        for created_at in [now - day * 3, now - day] {
+            let data = VertiportDataBuilder::new()
+                .timestamps(created_at, created_at)
+                .build();
+            repos.vertiports.insert(data).await.unwrap();
+        }
+
+        let list = |created_after, created_before, updated_after| {
+            get_all_vertiports(
+                Extension(repos.clone()),
+                shared_config(VertiportDeletePolicy::default()),
+                Extension(RemovedAssets::default()),
+                operator_quotas(),
+                Query(GeometryQuery::default()),
+                Query(ListAssetsQuery {
+                    created_after,
+                    created_before,
+                    updated_after,
+                    include_deleted: Default::default(),
+                    owner_id: None,
+                }),
+            )
+        };
+
+        assert_eq!(list(None, None, None).await.unwrap().assets.len(), 2);
         assert_eq!(
-            vertiport.created_at(),
-            vertiport_data
-                .created_at
-                .clone()
-                .expect("mock didn't return a created_at date.")
-                .into()
+            list(Some(now - day * 2), None, None)
+                .await
+                .unwrap()
+                .assets
+                .len(),
+            1
         );
         assert_eq!(
-            vertiport.updated_at(),
-            vertiport_data
-                .updated_at
-                .clone()
-                .expect("mock didn't return a updated_at date.")
-                .into()
+            list(None, Some(now - day * 2), None)
+                .await
+                .unwrap()
+                .assets
+                .len(),
+            1
         );
-        assert_eq!(vertiport.is_grouped(), false);
-        assert_eq!(vertiport.is_public(), true);
-        assert_eq!(vertiport.status(), AssetStatus::Available);
+        assert!(list(None, None, Some(now)).await.unwrap().assets.is_empty());
+        assert_eq!(
+            list(Some(now), Some(now - day), None).await.unwrap_err(),
+            ListError::Status(StatusCode::BAD_REQUEST)
+        );
+    }
 
-        vertiport_obj.data = None;
-        let result: Result<Vertiport, String> = vertiport_obj.clone().try_into();
-        assert_eq!(result.unwrap_err(), "(try_from) vertiport data is missing");
+    #[tokio::test]
+    async fn test_get_vertiport_by_id() {
+        let data = VertiportDataBuilder::new()
+            .geo_location(GeoPolygonZ { rings: vec![] })
+            .timestamps(Utc::now(), Utc::now())
+            .build();
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        // INVALID UUID
+        let result = get_vertiport_by_id(
+            Extension(repos.clone()),
+            localizations(),
+            operator_quotas(),
+            Path("invalid".to_string()),
+            Query(GeometryQuery::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(result, StatusCode::BAD_REQUEST);
+
+        // NONEXISTENT RECORD
+        let result = get_vertiport_by_id(
+            Extension(repos.clone()),
+            localizations(),
+            operator_quotas(),
+            Path(Uuid::new_v4().to_string()),
+            Query(GeometryQuery::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(result, StatusCode::NOT_FOUND);
+
+        // Insert new record
+        let quotas = operator_quotas();
+        let headers = operator_headers();
+        let id = register_vertiport(
+            Extension(repos.clone()),
+            quotas.clone(),
+            Query(RegisterVertiportQuery::default()),
+            headers.clone(),
+            Json(data.clone()),
+        )
+        .await
+        .expect("Failed to register vertipad");
+
+        // Get record
+        let result = get_vertiport_by_id(
+            Extension(repos.clone()),
+            localizations(),
+            quotas,
+            Path(id.clone()),
+            Query(GeometryQuery::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap();
+
+        let mut expected: Vertiport = vertiport::Object {
+            id: id.clone(),
+            data: Some(data),
+        }
+        .try_into()
+        .unwrap();
+        expected.basics.owner_id = headers
+            .get(OPERATOR_ID_HEADER)
+            .map(|value| value.to_str().unwrap().to_string());
+
+        assert_eq!(result.0, expected);
+    }
+
+    #[tokio::test]
+    async fn test_get_vertiport_geometry() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let get = |geometry: Option<GeometryDetail>| {
+            get_vertiport_by_id(
+                Extension(repos.clone()),
+                localizations(),
+                operator_quotas(),
+                Path(id.clone()),
+                Query(GeometryQuery { geometry }),
+                HeaderMap::new(),
+            )
+        };
 
-        vertiport_data.updated_at = None;
-        vertiport_obj.data = Some(vertiport_data.clone());
-        let result: Result<Vertiport, String> = vertiport_obj.clone().try_into();
+        let Json(full) = get(None).await.unwrap();
+        assert!(full.geo_location.is_some());
+        let centroid = full.centroid.clone().unwrap();
+        assert!((centroid.longitude - 4.1).abs() < 1e-9);
+        assert!((centroid.latitude - 52.1).abs() < 1e-9);
         assert_eq!(
-            result.unwrap_err(),
-            "(try_from) updated_at field empty but expected."
+            full.bbox,
+            Some(GeoBoundingBox {
+                min_longitude: 4.0,
+                min_latitude: 52.0,
+                max_longitude: 4.2,
+                max_latitude: 52.2,
+            })
         );
+        assert_eq!(get(Some(GeometryDetail::Full)).await.unwrap().0, full);
 
-        vertiport_data.created_at = None;
-        vertiport_data.updated_at = Some(Utc::now().into());
-        vertiport_obj.data = Some(vertiport_data.clone());
-        let result: Result<Vertiport, String> = vertiport_obj.clone().try_into();
-        assert_eq!(
-            result.unwrap_err(),
-            "(try_from) created_at field empty but expected."
-        );
+        let Json(marker) = get(Some(GeometryDetail::Centroid)).await.unwrap();
+        assert_eq!(marker.geo_location, None);
+        assert_eq!(marker.centroid, full.centroid);
+        assert_eq!(marker.bbox, full.bbox);
+        let json = serde_json::to_value(&marker).unwrap();
+        assert!(json.get("geo_location").is_none());
+        assert!(json.get("centroid").is_some());
 
-        vertiport_data.created_at = Some(Utc::now().into());
-        vertiport_data.geo_location = None;
-        vertiport_obj.data = Some(vertiport_data.clone());
-        let result: Result<Vertiport, String> = vertiport_obj.clone().try_into();
+        let Json(none) = get(Some(GeometryDetail::None)).await.unwrap();
         assert_eq!(
-            result.unwrap_err(),
-            "(try_from) geo_location empty but expected."
+            (none.geo_location, none.centroid, none.bbox),
+            (None, None, None)
         );
+        assert_eq!(none.basics, full.basics);
+
+        let listed = get_all_vertiports(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::default()),
+            Extension(RemovedAssets::default()),
+            operator_quotas(),
+            Query(GeometryQuery {
+                geometry: Some(GeometryDetail::Centroid),
+            }),
+            Query(ListAssetsQuery::default()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(listed.assets, vec![marker]);
+
+        ut_info!("success");
     }
 
     #[tokio::test]
-    async fn test_register_vertiport() {
-        let vertiport_data = vertiport::mock::get_data_obj();
-        let vertiport_data = vertiport::Data {
-            name: vertiport_data.name,
-            description: vertiport_data.description,
-            geo_location: vertiport_data.geo_location,
-            schedule: vertiport_data.schedule,
-            created_at: None,
-            updated_at: None,
+    async fn test_get_all_vertiports_limits() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        for _ in 0..3 {
+            repos
+                .vertiports
+                .insert(VertiportDataBuilder::new().build())
+                .await
+                .unwrap();
+        }
+        let list = |max_results: usize, warn_results: usize| {
+            let mut config = crate::config::Config::default();
+            config.rest_list_max_results = max_results;
+            config.rest_list_warn_results = warn_results;
+            get_all_vertiports(
+                Extension(repos.clone()),
+                Extension(config.into()),
+                Extension(RemovedAssets::default()),
+                operator_quotas(),
+                Query(GeometryQuery::default()),
+                Query(ListAssetsQuery::default()),
+            )
         };
 
-        let config = crate::config::Config::default();
-        let grpc_clients = GrpcClients::default(config);
-        let result = register_vertiport(Extension(grpc_clients), Json(vertiport_data.clone()))
-            .await
-            .unwrap();
+        let error = list(2, 0).await.unwrap_err();
+        assert_eq!(error, ListError::TooMany(2));
+        assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
-        // Confirm valid UUID
-        to_uuid(&result).unwrap();
+        let listed = list(3, 2).await.unwrap();
+        assert_eq!(listed.assets.len(), 3);
+        assert_eq!(listed.exceeded_warning, Some(2));
+        let response = listed.into_response();
+        assert!(response.headers()[header::WARNING]
+            .to_str()
+            .unwrap()
+            .starts_with("299 svc-assets \"more than 2 assets listed"));
+
+        let listed = list(0, 0).await.unwrap();
+        assert_eq!(listed.exceeded_warning, None);
+
+        ut_info!("success");
     }
 
     #[tokio::test]
-    async fn test_update_vertiport() {
-        let data = vertiport::mock::get_data_obj();
-        let data = vertiport::Data {
-            geo_location: Some(GeoPolygonZ { rings: vec![] }),
-            created_at: Some(Utc::now().into()),
-            updated_at: Some(Utc::now().into()),
-            ..data
-        };
-
-        let mut payload = UpdateVertiportPayload {
-            id: Uuid::new_v4().to_string(),
-            name: Some("Test Vertiport".to_string()),
-            description: Some("Test description".to_string()),
-            geo_location: None,
-            schedule: Some("Test schedule".to_string()),
-            mask: vec!["name".to_string()],
-        };
+    async fn test_batch_get_vertiports() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
 
         let config = crate::config::Config::default();
         let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
 
-        // INVALID UUID
-        payload.id = "invalid".to_string();
-        let result = update_vertiport(Extension(grpc_clients.clone()), Json(payload.clone()))
+        let data = VertiportDataBuilder::seeded(5).build();
+        let id = grpc_clients
+            .storage
+            .vertiport
+            .insert(data)
             .await
-            .unwrap_err();
-        assert_eq!(result, StatusCode::BAD_REQUEST);
+            .unwrap()
+            .into_inner()
+            .object
+            .unwrap()
+            .id;
 
-        // NONEXISTENT RECORD
-        payload.id = Uuid::new_v4().to_string();
-        let result = update_vertiport(Extension(grpc_clients.clone()), Json(payload.clone()))
-            .await
-            .unwrap_err();
+        // invalid payload
+        let error = batch_get_vertiports(
+            Extension(repos.clone()),
+            fan_out(),
+            Extension(crate::Config::default().into()),
+            operator_quotas(),
+            Json(BatchGetPayload {
+                ids: vec!["invalid".to_string()],
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, ListError::Status(StatusCode::BAD_REQUEST));
+
+        let missing_id = Uuid::new_v4().to_string();
+        let response = batch_get_vertiports(
+            Extension(repos),
+            fan_out(),
+            Extension(crate::Config::default().into()),
+            operator_quotas(),
+            Json(BatchGetPayload {
+                ids: vec![id.clone(), missing_id.clone()],
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.found.len(), 1);
+        assert_eq!(response.found[0].basics.id, id);
+        assert_eq!(response.missing, vec![missing_id]);
 
-        assert_eq!(result, StatusCode::NOT_FOUND);
+        ut_info!("success");
+    }
 
-        payload.id = grpc_clients
+    #[tokio::test]
+    async fn test_patch_vertiport() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let config = crate::config::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        let repos = Repositories::grpc(&grpc_clients);
+
+        // invalid id
+        let error = patch_vertiport(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::Reject),
+            Path("invalid".to_string()),
+            Json(serde_json::json!({ "name": "Patched" })),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.0, StatusCode::BAD_REQUEST);
+
+        let id = grpc_clients
             .storage
             .vertiport
-            .insert(data.clone())
+            .insert(VertiportDataBuilder::seeded(6).build())
             .await
             .unwrap()
             .into_inner()
@@ -504,111 +2438,525 @@ mod tests {
             .unwrap()
             .id;
 
-        // Update record
-        update_vertiport(Extension(grpc_clients.clone()), Json(payload.clone()))
-            .await
-            .unwrap();
+        // required fields can not be removed
+        let error = patch_vertiport(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::Reject),
+            Path(id.clone()),
+            Json(serde_json::json!({ "name": null })),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.0, StatusCode::UNPROCESSABLE_ENTITY);
 
-        // let expected = vertiport::Object {
-        //     id: payload.id.clone(),
-        //     data
-        // }
-        // .try_into()
-        // .unwrap();
+        patch_vertiport(
+            Extension(repos.clone()),
+            shared_config(VertiportDeletePolicy::Reject),
+            Path(id.clone()),
+            Json(serde_json::json!({ "name": "Patched", "schedule": null })),
+        )
+        .await
+        .unwrap();
+
+        let vertiport = get_vertiport_by_id(
+            Extension(repos),
+            localizations(),
+            operator_quotas(),
+            Path(id),
+            Query(GeometryQuery::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(vertiport.basics.name, Some("Patched".to_string()));
+
+        ut_info!("success");
     }
 
     #[tokio::test]
-    async fn test_remove_vertiport() {
-        let config = crate::config::Config::default();
-        let grpc_clients = GrpcClients::default(config);
+    async fn test_vertiport_ops_status() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let ops_statuses = ops_statuses();
+        let id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::seeded(7).build())
+            .await
+            .unwrap()
+            .id;
+        let now = Utc::now();
+        let payload = VertiportOpsStatusPayload {
+            status: crate::ops_status::VertiportOpsStatus::Closed,
+            effective_from: None,
+            effective_until: Some(now + chrono::Duration::hours(1)),
+            reason: Some(" weather ".to_string()),
+        };
 
-        // INVALID UUID
-        let result = remove_vertiport(Extension(grpc_clients.clone()), Path("invalid".to_string()))
+        let Json(state) = get_vertiport_ops_status(ops_statuses.clone(), Path(id.clone()))
             .await
-            .unwrap_err();
-        assert_eq!(result, StatusCode::BAD_REQUEST);
+            .unwrap();
+        assert_eq!(
+            state.current_status,
+            crate::ops_status::VertiportOpsStatus::Open
+        );
 
-        // NONEXISTENT RECORD will return Ok(())
-        remove_vertiport(
-            Extension(grpc_clients.clone()),
+        let (status, _) = put_vertiport_ops_status(
+            Extension(repos.clone()),
+            ops_statuses.clone(),
             Path(Uuid::new_v4().to_string()),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, Json(errors)) = put_vertiport_ops_status(
+            Extension(repos.clone()),
+            ops_statuses.clone(),
+            Path(id.clone()),
+            Json(VertiportOpsStatusPayload {
+                effective_from: Some(now + chrono::Duration::hours(2)),
+                ..payload.clone()
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "effective_until");
+
+        let Json(state) = put_vertiport_ops_status(
+            Extension(repos.clone()),
+            ops_statuses.clone(),
+            Path(id.clone()),
+            Json(payload),
+        )
+        .await
+        .unwrap();
+        assert_eq!(state.reason, Some("weather".to_string()));
+        assert_eq!(
+            state.current_status,
+            crate::ops_status::VertiportOpsStatus::Closed
+        );
+
+        let Json(state) = get_vertiport_ops_status(ops_statuses.clone(), Path(id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(
+            state.current_status,
+            crate::ops_status::VertiportOpsStatus::Closed
+        );
+
+        // the status is removed with the vertiport
+        remove_vertiport(
+            Extension(repos),
+            shared_config(VertiportDeletePolicy::Reject),
+            localizations(),
+            classifications(),
+            chargers(),
+            equipment(),
+            occupancy_log(),
+            operator_quotas(),
+            ops_statuses.clone(),
+            weather_minimums(),
+            Extension(RestrictionRules::default()),
+            Extension(RemovedAssets::default()),
+            Path(id.clone()),
         )
         .await
         .unwrap();
+        let Json(state) = get_vertiport_ops_status(ops_statuses, Path(id))
+            .await
+            .unwrap();
+        assert_eq!(state.updated_at, None);
+
+        ut_info!("success");
     }
 
     #[tokio::test]
-    async fn test_get_all_vertiports() {
-        let config = crate::config::Config::default();
-        let grpc_clients = GrpcClients::default(config);
-
-        let data = vertiport::Data {
-            name: "Test".to_string(),
-            description: "Description".to_string(),
-            geo_location: Some(GeoPolygonZ { rings: vec![] }),
-            schedule: None,
-            created_at: Some(Utc::now().into()),
-            updated_at: Some(Utc::now().into()),
+    async fn test_vertiport_weather_minimums() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let weather_minimums = weather_minimums();
+        let id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::seeded(8).build())
+            .await
+            .unwrap()
+            .id;
+        let payload = WeatherMinimumsPayload {
+            max_wind_speed_mps: 12.5,
+            min_visibility_m: 1500.0,
+            max_precipitation_mm_per_hour: 2.0,
         };
 
-        let _ = register_vertiport(Extension(grpc_clients.clone()), Json(data.clone()))
+        let status = get_vertiport_weather_minimums(weather_minimums.clone(), Path(id.clone()))
             .await
-            .expect("Failed to register vertipad");
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = put_vertiport_weather_minimums(
+            Extension(repos.clone()),
+            weather_minimums.clone(),
+            Path(Uuid::new_v4().to_string()),
+            Json(payload.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, Json(errors)) = put_vertiport_weather_minimums(
+            Extension(repos.clone()),
+            weather_minimums.clone(),
+            Path(id.clone()),
+            Json(WeatherMinimumsPayload {
+                min_visibility_m: -1.0,
+                ..payload.clone()
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "min_visibility_m");
+
+        let Json(minimums) = put_vertiport_weather_minimums(
+            Extension(repos.clone()),
+            weather_minimums.clone(),
+            Path(id.clone()),
+            Json(payload),
+        )
+        .await
+        .unwrap();
+        assert_eq!(minimums.vertiport_id, id);
+        assert_eq!(minimums.max_wind_speed_mps, 12.5);
+
+        let Json(stored) =
+            get_vertiport_weather_minimums(weather_minimums.clone(), Path(id.clone()))
+                .await
+                .unwrap();
+        assert_eq!(stored, minimums);
 
-        let response = get_all_vertiports(Extension(grpc_clients.clone()))
+        remove_vertiport_weather_minimums(weather_minimums.clone(), Path(id.clone()))
             .await
             .unwrap();
+        let status = remove_vertiport_weather_minimums(weather_minimums.clone(), Path(id))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let status = get_vertiport_weather_minimums(weather_minimums, Path("invalid".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
 
-        assert!(!response.0.is_empty());
+        ut_info!("success");
     }
 
     #[tokio::test]
-    async fn test_get_vertiport_by_id() {
-        let data = vertiport::Data {
-            name: "Test".to_string(),
-            description: "Description".to_string(),
-            geo_location: Some(GeoPolygonZ { rings: vec![] }),
-            schedule: None,
-            created_at: Some(Utc::now().into()),
-            updated_at: Some(Utc::now().into()),
+    async fn test_vertiport_localization() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let localizations = localizations();
+        let data = VertiportDataBuilder::seeded(9).build();
+        let id = repos.vertiports.insert(data.clone()).await.unwrap().id;
+
+        let localization = AssetLocalization {
+            names: HashMap::from([
+                ("de".to_string(), "Hauptbahnhof".to_string()),
+                ("ja-JP".to_string(), "中央駅".to_string()),
+            ]),
+            descriptions: HashMap::from([("de".to_string(), "Beschreibung".to_string())]),
         };
 
-        let config = crate::config::Config::default();
-        let grpc_clients = GrpcClients::default(config);
-
         // INVALID UUID
-        let result =
-            get_vertiport_by_id(Extension(grpc_clients.clone()), Path("invalid".to_string()))
-                .await
-                .unwrap_err();
-        assert_eq!(result, StatusCode::BAD_REQUEST);
-
-        // NONEXISTENT RECORD
-        let result = get_vertiport_by_id(
-            Extension(grpc_clients.clone()),
-            Path(Uuid::new_v4().to_string()),
+        let (status, _) = put_vertiport_localization(
+            Extension(repos.clone()),
+            localizations.clone(),
+            Path("invalid".to_string()),
+            Json(localization.clone()),
         )
         .await
         .unwrap_err();
-        assert_eq!(result, StatusCode::NOT_FOUND);
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // INVALID LOCALE
+        let (status, Json(errors)) = put_vertiport_localization(
+            Extension(repos.clone()),
+            localizations.clone(),
+            Path(id.clone()),
+            Json(AssetLocalization {
+                names: HashMap::from([("not a locale".to_string(), "name".to_string())]),
+                descriptions: HashMap::new(),
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "names.not a locale");
+
+        put_vertiport_localization(
+            Extension(repos.clone()),
+            localizations.clone(),
+            Path(id.clone()),
+            Json(localization),
+        )
+        .await
+        .unwrap();
 
-        // Insert new record
-        let id = register_vertiport(Extension(grpc_clients.clone()), Json(data.clone()))
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::ACCEPT_LANGUAGE,
+            "de-CH, en;q=0.5".parse().unwrap(),
+        );
+        let vertiport = get_vertiport_by_id(
+            Extension(repos.clone()),
+            localizations.clone(),
+            operator_quotas(),
+            Path(id.clone()),
+            Query(GeometryQuery::default()),
+            headers,
+        )
+        .await
+        .unwrap();
+        assert_eq!(vertiport.basics.name, Some("Hauptbahnhof".to_string()));
+        assert_eq!(vertiport.description, "Beschreibung");
+        assert!(vertiport.localization.unwrap().names.contains_key("ja-jp"));
+
+        // without a matching language the stored name is returned
+        let vertiport = get_vertiport_by_id(
+            Extension(repos.clone()),
+            localizations.clone(),
+            operator_quotas(),
+            Path(id.clone()),
+            Query(GeometryQuery::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(vertiport.basics.name, Some(data.name));
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_get_vertipads_by_elevation() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
             .await
-            .expect("Failed to register vertipad");
+            .unwrap()
+            .id;
+        for (name, z) in [("Rooftop", 85.5), ("Ground", 2.0), ("Garage", 24.0)] {
+            let data = VertipadDataBuilder::new()
+                .vertiport_id(id.clone())
+                .name(name)
+                .geo_location(GeoPointZ { x: 4.1, y: 52.1, z })
+                .build();
+            repos.vertipads.insert(data).await.unwrap();
+        }
 
-        // Get record
-        let result = get_vertiport_by_id(Extension(grpc_clients.clone()), Path(id.clone()))
+        let Json(vertipads) =
+            get_vertipads_by_elevation(Extension(repos.clone()), ops_statuses(), Path(id))
+                .await
+                .unwrap();
+        let names: Vec<String> = vertipads.iter().map(Vertipad::full_name).collect();
+        assert_eq!(names, vec!["Ground", "Garage", "Rooftop"]);
+        assert_eq!(vertipads[2].geo_location.altitude, 85.5);
+
+        assert_eq!(
+            get_vertipads_by_elevation(
+                Extension(repos),
+                ops_statuses(),
+                Path("invalid".to_string())
+            )
             .await
-            .unwrap();
+            .unwrap_err(),
+            StatusCode::BAD_REQUEST
+        );
 
-        let expected: Vertiport = vertiport::Object {
-            id: id.clone(),
-            data: Some(data),
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_typeahead_vertiports() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        for name in ["Amsterdam Zuid", "Amstelveen", "Rotterdam"] {
+            repos
+                .vertiports
+                .insert(VertiportDataBuilder::new().name(name).build())
+                .await
+                .unwrap();
         }
-        .try_into()
+        let index = VertiportIndex::default();
+        crate::typeahead::refresh_vertiport_index(&repos, &index)
+            .await
+            .unwrap();
+
+        let config = |tenants: Vec<String>| {
+            let mut config = crate::config::Config::default();
+            config.tenants = tenants;
+            Extension(SharedConfig::from(config))
+        };
+        let query = |q: &str, limit: Option<u8>| {
+            Query(VertiportTypeaheadQuery {
+                q: q.to_string(),
+                limit,
+            })
+        };
+
+        let Json(suggestions) = typeahead_vertiports(
+            Extension(repos.clone()),
+            config(vec![]),
+            Extension(index.clone()),
+            query("ams", None),
+        )
+        .await
         .unwrap();
+        let names: Vec<&str> = suggestions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Amstelveen", "Amsterdam Zuid"]);
+        assert!(suggestions[0].centroid.is_some());
+
+        // vertiports registered since the last refresh are not suggested yet
+        repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().name("Amsterdam Noord").build())
+            .await
+            .unwrap();
+        let Json(suggestions) = typeahead_vertiports(
+            Extension(repos.clone()),
+            config(vec![]),
+            Extension(index.clone()),
+            query("AMS", Some(1)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].name, "Amstelveen");
+
+        // with tenants the vertiports are searched in storage
+        let Json(suggestions) = typeahead_vertiports(
+            Extension(repos.clone()),
+            config(vec!["staging".to_string()]),
+            Extension(index.clone()),
+            query("amsterdam", None),
+        )
+        .await
+        .unwrap();
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(
+            typeahead_vertiports(
+                Extension(Repositories::unavailable()),
+                config(vec!["staging".to_string()]),
+                Extension(index.clone()),
+                query("amsterdam", None),
+            )
+            .await
+            .unwrap_err(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
 
-        assert_eq!(result.0, expected);
+        for (q, limit) in [(" ", None), ("ams", Some(0)), ("ams", Some(51))] {
+            assert_eq!(
+                typeahead_vertiports(
+                    Extension(repos.clone()),
+                    config(vec![]),
+                    Extension(index.clone()),
+                    query(q, limit),
+                )
+                .await
+                .unwrap_err(),
+                StatusCode::BAD_REQUEST
+            );
+        }
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_get_vertipad_adjacency() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let id = repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        let mut pad_ids = vec![];
+        for (x, y) in [(4.1, 52.1), (4.1001, 52.1), (4.1, 52.1002)] {
+            let data = VertipadDataBuilder::new()
+                .vertiport_id(id.clone())
+                .geo_location(GeoPointZ { x, y, z: 0.0 })
+                .build();
+            pad_ids.push(repos.vertipads.insert(data).await.unwrap().id);
+        }
+        // vertipads of other vertiports are not included
+        repos
+            .vertipads
+            .insert(VertipadDataBuilder::new().build())
+            .await
+            .unwrap();
+
+        let adjacency = |id: String, threshold_m: Option<f64>| {
+            get_vertipad_adjacency(
+                Extension(repos.clone()),
+                shared_config(VertiportDeletePolicy::Reject),
+                Path(id),
+                Query(VertipadAdjacencyQuery { threshold_m }),
+            )
+        };
+
+        let Json(result) = adjacency(id.clone(), None).await.unwrap();
+        pad_ids.sort();
+        assert_eq!(result.vertiport_id, id);
+        assert_eq!(result.threshold_m, 30.0);
+        assert_eq!(result.vertipad_ids, pad_ids);
+        assert_eq!(result.distances_m.len(), 3);
+        for row in 0..3 {
+            assert_eq!(result.distances_m[row][row], 0.0);
+            assert!(!result.adjacency[row][row]);
+            for column in 0..3 {
+                assert_eq!(
+                    result.distances_m[row][column],
+                    result.distances_m[column][row]
+                );
+                assert_eq!(row != column, result.adjacency[row][column]);
+            }
+        }
+
+        // only the pads about 7 meters apart are within 10 meters
+        let Json(result) = adjacency(id.clone(), Some(10.0)).await.unwrap();
+        let adjacent = result
+            .adjacency
+            .iter()
+            .flatten()
+            .filter(|adjacent| **adjacent)
+            .count();
+        assert_eq!(adjacent, 2);
+
+        let error = adjacency(id.clone(), Some(-1.0)).await.unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+        let error = adjacency("invalid".to_string(), None).await.unwrap_err();
+        assert_eq!(error, StatusCode::BAD_REQUEST);
+        let error = adjacency(Uuid::new_v4().to_string(), None)
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::NOT_FOUND);
+
+        ut_info!("success");
     }
 }
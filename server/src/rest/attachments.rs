@@ -0,0 +1,492 @@
+//! Asset notes and attachments
+//!
+//! Free-form notes and files (like images or PDFs of certificates) can be
+//! attached to any asset. The files are stored in the configured
+//! [`ObjectStore`], see [`crate::object_store`].
+//!
+//! svc-storage does not store notes or attachment metadata yet, so
+//! [`AssetAttachments`] keeps them in the [`ASSET_ATTACHMENTS_TABLE`] of the
+//! persisted state, see [`crate::state`]. The metadata is written after the
+//! file is stored or removed: an upload whose metadata can not be written
+//! removes its file again, while a removal whose metadata can not be written
+//! leaves an attachment without a file, which can be removed again.
+
+pub use super::api::rest_types::{AssetAttachment, AssetNote, AssetNotePayload};
+
+use super::api::validation::{
+    check_not_empty, field_error, into_result, normalize_optional_string, normalize_string,
+    FieldError,
+};
+use crate::object_store::memory::MemoryObjectStore;
+use crate::object_store::{ObjectStore, ObjectStoreError};
+use crate::state::{StateError, StateTable};
+use lib_common::time::Utc;
+use lib_common::uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Maximum number of notes per asset
+pub const MAX_NOTES_PER_ASSET: usize = 100;
+
+/// Maximum number of attachments per asset
+pub const MAX_ATTACHMENTS_PER_ASSET: usize = 20;
+
+/// Maximum length in characters of a note
+pub const MAX_NOTE_LENGTH: usize = 4000;
+
+/// Maximum length in characters of an attachment file name
+pub const MAX_FILE_NAME_LENGTH: usize = 255;
+
+/// Name of the table of the notes and attachments in the state directory
+pub const ASSET_ATTACHMENTS_TABLE: &str = "asset_attachments";
+
+/// Notes and attachments of a single asset
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AssetEntry {
+    notes: Vec<AssetNote>,
+    attachments: Vec<AssetAttachment>,
+}
+
+/// Errors returned when changing the notes or attachments of an asset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachmentError {
+    /// The note or attachment does not exist
+    NotFound,
+    /// The asset reached [`MAX_NOTES_PER_ASSET`] or [`MAX_ATTACHMENTS_PER_ASSET`]
+    TooMany,
+    /// The object store returned an error
+    Store(ObjectStoreError),
+    /// The notes or attachments could not be persisted
+    State(StateError),
+}
+
+/// Store of the notes and attachments per asset id
+#[derive(Clone)]
+pub struct AssetAttachments {
+    assets: StateTable<AssetEntry>,
+    objects: Arc<dyn ObjectStore>,
+}
+
+impl Default for AssetAttachments {
+    fn default() -> Self {
+        Self::new(Arc::new(MemoryObjectStore::default()))
+    }
+}
+
+impl std::fmt::Debug for AssetAttachments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AssetAttachments").finish_non_exhaustive()
+    }
+}
+
+/// Get the object store key of an attachment
+fn object_key(asset_id: &str, attachment_id: &str) -> String {
+    format!("{asset_id}/{attachment_id}")
+}
+
+impl AssetAttachments {
+    /// Create a new store keeping the notes and attachments in memory, and
+    /// the attachment files in the provided object store
+    pub fn new(objects: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            assets: StateTable::default(),
+            objects,
+        }
+    }
+
+    /// Open the notes and attachments persisted in the state directory,
+    /// keeping the attachment files in the provided object store
+    pub fn open(dir: Option<&str>, objects: Arc<dyn ObjectStore>) -> Result<Self, StateError> {
+        Ok(Self {
+            assets: StateTable::open(dir, ASSET_ATTACHMENTS_TABLE)?,
+            objects,
+        })
+    }
+
+    /// Get the notes of an asset
+    pub fn notes(&self, asset_id: &str) -> Vec<AssetNote> {
+        self.assets
+            .get(asset_id)
+            .map(|entry| entry.notes)
+            .unwrap_or_default()
+    }
+
+    /// Add a note to an asset, returning the new note
+    pub fn add_note(
+        &self,
+        asset_id: &str,
+        payload: AssetNotePayload,
+    ) -> Result<AssetNote, AttachmentError> {
+        if self.notes(asset_id).len() >= MAX_NOTES_PER_ASSET {
+            return Err(AttachmentError::TooMany);
+        }
+
+        let note = AssetNote {
+            id: Uuid::new_v4().to_string(),
+            asset_id: asset_id.to_string(),
+            text: payload.text,
+            author: payload.author,
+            created_at: Utc::now(),
+        };
+        self.assets
+            .update(|assets| {
+                // Notes added since the check may have reached the limit
+                let entry = assets.entry(asset_id.to_string()).or_default();
+                if entry.notes.len() >= MAX_NOTES_PER_ASSET {
+                    return Err(AttachmentError::TooMany);
+                }
+                entry.notes.push(note.clone());
+                Ok(note)
+            })
+            .map_err(AttachmentError::State)?
+    }
+
+    /// Remove a note of an asset
+    pub fn remove_note(&self, asset_id: &str, note_id: &str) -> Result<(), AttachmentError> {
+        if !self.notes(asset_id).iter().any(|note| note.id == note_id) {
+            return Err(AttachmentError::NotFound);
+        }
+
+        self.assets
+            .update(|assets| {
+                if let Some(entry) = assets.get_mut(asset_id) {
+                    entry.notes.retain(|note| note.id != note_id);
+                }
+            })
+            .map_err(AttachmentError::State)
+    }
+
+    /// Get the attachments of an asset
+    pub fn attachments(&self, asset_id: &str) -> Vec<AssetAttachment> {
+        self.assets
+            .get(asset_id)
+            .map(|entry| entry.attachments)
+            .unwrap_or_default()
+    }
+
+    /// Get an attachment of an asset
+    pub fn attachment(
+        &self,
+        asset_id: &str,
+        attachment_id: &str,
+    ) -> Result<AssetAttachment, AttachmentError> {
+        self.attachments(asset_id)
+            .into_iter()
+            .find(|attachment| attachment.id == attachment_id)
+            .ok_or(AttachmentError::NotFound)
+    }
+
+    /// Store a file and attach it to an asset, returning the new attachment
+    pub async fn add_attachment(
+        &self,
+        asset_id: &str,
+        file_name: String,
+        content_type: String,
+        body: Vec<u8>,
+    ) -> Result<AssetAttachment, AttachmentError> {
+        if self.attachments(asset_id).len() >= MAX_ATTACHMENTS_PER_ASSET {
+            return Err(AttachmentError::TooMany);
+        }
+
+        let attachment = AssetAttachment {
+            id: Uuid::new_v4().to_string(),
+            asset_id: asset_id.to_string(),
+            file_name,
+            content_type,
+            size: body.len() as u64,
+            created_at: Utc::now(),
+        };
+        let key = object_key(asset_id, &attachment.id);
+        self.objects
+            .put(&key, &attachment.content_type, body)
+            .await
+            .map_err(AttachmentError::Store)?;
+
+        // Attachments added while the file was stored may have reached the limit
+        let added = match self.assets.update(|assets| {
+            let entry = assets.entry(asset_id.to_string()).or_default();
+            if entry.attachments.len() >= MAX_ATTACHMENTS_PER_ASSET {
+                return Err(AttachmentError::TooMany);
+            }
+            entry.attachments.push(attachment.clone());
+            Ok(attachment)
+        }) {
+            Ok(added) => added,
+            Err(e) => Err(AttachmentError::State(e)),
+        };
+
+        if added.is_err() {
+            if let Err(e) = self.objects.delete(&key).await {
+                rest_warn!("could not remove object [{}]: {}", key, e);
+            }
+        }
+
+        added
+    }
+
+    /// Get an attachment of an asset with the content of the file
+    pub async fn download(
+        &self,
+        asset_id: &str,
+        attachment_id: &str,
+    ) -> Result<(AssetAttachment, Vec<u8>), AttachmentError> {
+        let attachment = self.attachment(asset_id, attachment_id)?;
+        let body = self
+            .objects
+            .get(&object_key(asset_id, attachment_id))
+            .await
+            .map_err(AttachmentError::Store)?;
+
+        Ok((attachment, body))
+    }
+
+    /// Remove an attachment of an asset together with its file
+    pub async fn remove_attachment(
+        &self,
+        asset_id: &str,
+        attachment_id: &str,
+    ) -> Result<(), AttachmentError> {
+        self.attachment(asset_id, attachment_id)?;
+        match self
+            .objects
+            .delete(&object_key(asset_id, attachment_id))
+            .await
+        {
+            // The file is gone already, remove the attachment anyway
+            Ok(()) | Err(ObjectStoreError::NotFound) => (),
+            Err(e) => return Err(AttachmentError::Store(e)),
+        }
+
+        self.assets
+            .update(|assets| {
+                if let Some(entry) = assets.get_mut(asset_id) {
+                    entry
+                        .attachments
+                        .retain(|attachment| attachment.id != attachment_id);
+                }
+            })
+            .map_err(AttachmentError::State)
+    }
+}
+
+/// Validate and normalize an [`AssetNotePayload`]
+pub fn validate_note(mut payload: AssetNotePayload) -> Result<AssetNotePayload, Vec<FieldError>> {
+    normalize_string(&mut payload.text);
+    normalize_optional_string(&mut payload.author);
+
+    let mut errors = vec![];
+    check_not_empty(&mut errors, "text", &payload.text);
+    if payload.text.chars().count() > MAX_NOTE_LENGTH {
+        errors.push(field_error(
+            "text",
+            &format!("can not be longer than {MAX_NOTE_LENGTH} characters"),
+        ));
+    }
+
+    into_result(payload, errors)
+}
+
+/// Validate and normalize the file name of an attachment.
+///
+/// The file name is returned in the `Content-Disposition` header of a
+/// download, so path separators, quotes and control characters are rejected.
+pub fn validate_file_name(file_name: &str) -> Result<String, Vec<FieldError>> {
+    let file_name = file_name.trim().to_string();
+
+    let mut errors = vec![];
+    check_not_empty(&mut errors, "file_name", &file_name);
+    if file_name.chars().count() > MAX_FILE_NAME_LENGTH {
+        errors.push(field_error(
+            "file_name",
+            &format!("can not be longer than {MAX_FILE_NAME_LENGTH} characters"),
+        ));
+    }
+    if file_name
+        .chars()
+        .any(|c| matches!(c, '/' | '\\' | '"') || c.is_control())
+    {
+        errors.push(field_error(
+            "file_name",
+            "can not contain path separators, quotes or control characters",
+        ));
+    }
+
+    into_result(file_name, errors)
+}
+
+/// Check if files of the provided content type can be attached.
+///
+/// Images, PDFs and plain text files are allowed.
+pub fn is_allowed_content_type(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+
+    (mime.starts_with("image/") && mime.len() > "image/".len())
+        || mime == "application/pdf"
+        || mime == "text/plain"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(text: &str) -> AssetNotePayload {
+        AssetNotePayload {
+            text: text.to_string(),
+            author: Some(" Jane ".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_validate_note() {
+        let normalized = validate_note(note(" Replaced rotor blade ")).unwrap();
+        assert_eq!(normalized.text, "Replaced rotor blade");
+        assert_eq!(normalized.author, Some("Jane".to_string()));
+
+        let errors = validate_note(note(" ")).unwrap_err();
+        assert_eq!(errors[0].field, "text");
+
+        let errors = validate_note(note(&"a".repeat(MAX_NOTE_LENGTH + 1))).unwrap_err();
+        assert_eq!(errors[0].field, "text");
+    }
+
+    #[test]
+    fn test_validate_file_name() {
+        assert_eq!(
+            validate_file_name(" certificate.pdf ").unwrap(),
+            "certificate.pdf"
+        );
+        assert!(validate_file_name("").is_err());
+        assert!(validate_file_name("../certificate.pdf").is_err());
+        assert!(validate_file_name("certificate\".pdf").is_err());
+        assert!(validate_file_name("certificate\n.pdf").is_err());
+        assert!(validate_file_name(&"a".repeat(MAX_FILE_NAME_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_is_allowed_content_type() {
+        assert!(is_allowed_content_type("image/png"));
+        assert!(is_allowed_content_type("Application/PDF"));
+        assert!(is_allowed_content_type("text/plain; charset=utf-8"));
+        assert!(!is_allowed_content_type("image/"));
+        assert!(!is_allowed_content_type("application/octet-stream"));
+        assert!(!is_allowed_content_type("text/html"));
+    }
+
+    #[test]
+    fn test_asset_notes() {
+        let store = AssetAttachments::default();
+        let id = "asset";
+        assert!(store.notes(id).is_empty());
+
+        let added = store.add_note(id, note("text")).unwrap();
+        assert_eq!(added.asset_id, id);
+        assert_eq!(store.notes(id), vec![added.clone()]);
+
+        store.remove_note(id, &added.id).unwrap();
+        assert_eq!(
+            store.remove_note(id, &added.id),
+            Err(AttachmentError::NotFound)
+        );
+        assert_eq!(
+            store.remove_note("unknown", &added.id),
+            Err(AttachmentError::NotFound)
+        );
+
+        for _ in 0..MAX_NOTES_PER_ASSET {
+            store.add_note(id, note("text")).unwrap();
+        }
+        assert_eq!(
+            store.add_note(id, note("text")),
+            Err(AttachmentError::TooMany)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_asset_attachments() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let store = AssetAttachments::default();
+        let id = "asset";
+
+        let added = store
+            .add_attachment(
+                id,
+                "certificate.pdf".to_string(),
+                "application/pdf".to_string(),
+                vec![1, 2, 3],
+            )
+            .await
+            .unwrap();
+        assert_eq!(added.size, 3);
+        assert_eq!(store.attachments(id), vec![added.clone()]);
+
+        let (attachment, body) = store.download(id, &added.id).await.unwrap();
+        assert_eq!(attachment, added);
+        assert_eq!(body, vec![1, 2, 3]);
+        assert_eq!(
+            store.download("unknown", &added.id).await,
+            Err(AttachmentError::NotFound)
+        );
+
+        store.remove_attachment(id, &added.id).await.unwrap();
+        assert!(store.attachments(id).is_empty());
+        assert_eq!(
+            store.remove_attachment(id, &added.id).await,
+            Err(AttachmentError::NotFound)
+        );
+
+        for _ in 0..MAX_ATTACHMENTS_PER_ASSET {
+            store
+                .add_attachment(id, "a.png".to_string(), "image/png".to_string(), vec![])
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            store
+                .add_attachment(id, "a.png".to_string(), "image/png".to_string(), vec![])
+                .await,
+            Err(AttachmentError::TooMany)
+        );
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_asset_attachments_persisted() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let dir = crate::state::test_state_dir();
+        let objects: Arc<dyn ObjectStore> = Arc::new(MemoryObjectStore::default());
+        let id = "asset";
+
+        let store = AssetAttachments::open(Some(&dir), objects.clone()).unwrap();
+        let note = store.add_note(id, note("text")).unwrap();
+        let attachment = store
+            .add_attachment(
+                id,
+                "certificate.pdf".to_string(),
+                "application/pdf".to_string(),
+                vec![1, 2, 3],
+            )
+            .await
+            .unwrap();
+
+        // the metadata is read back after a restart, the files are still in
+        // the object store
+        let store = AssetAttachments::open(Some(&dir), objects).unwrap();
+        assert_eq!(store.notes(id), vec![note]);
+        let (downloaded, body) = store.download(id, &attachment.id).await.unwrap();
+        assert_eq!(downloaded, attachment);
+        assert_eq!(body, vec![1, 2, 3]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,598 @@
+//! Audit log of the REST requests
+//!
+//! While enabled, every authorized REST request is recorded to the
+//! [`AUDIT_LOG_TARGET`] log target: the method, path, status, latency, the
+//! operator of the API token and the JSON request and response bodies.
+//! Values of the body fields listed in `audit_log_redact_fields` are
+//! replaced by [`REDACTED`], so the log can be kept without leaking personal
+//! data. The log runs after the API token check, which logs the requests it
+//! rejects itself.
+//!
+//! The log is enabled with the `audit_log` value of the [`SharedConfig`] and
+//! can be toggled at runtime with the admin endpoint.
+//!
+//! Successful changes made with an API token bound to an operator are kept
+//! in an audit trail as well, so the changes of an actor can be listed with
+//! [`AuditLog::changes`] during incident investigations. The actor is the
+//! operator of the token, never the `x-operator-id` header as sent, so
+//! changes through an open API (without tokens) are not kept. Each change
+//! records the tenant of the token as well, and is only listed to the same
+//! tenant, see [`super::tenant`]. The trail is
+//! the [`AUDIT_TRAIL_LOG`] of the persisted state, see [`crate::state`],
+//! and keeps every change.
+
+pub use super::api::rest_types::{AuditChange, AuditChanges, AuditQuery};
+
+use crate::config::SharedConfig;
+use crate::rest::auth::{unversioned, TokenOperator, TokenTenant};
+use crate::rest::read_only::is_mutating;
+use crate::state::{StateError, StateLog};
+use axum::{
+    body::{self, Body, Bytes, Full, HttpBody},
+    http::{header::CONTENT_TYPE, HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
+use lib_common::time::Utc;
+use lib_common::uuid::to_uuid;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+/// Log target of the audit log entries
+pub const AUDIT_LOG_TARGET: &str = "app::audit";
+
+/// Replacement of redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Maximum size in bytes of a request or response body recorded in the log,
+/// larger bodies are omitted
+pub const MAX_AUDIT_BODY_BYTES: u64 = 64 * 1024;
+
+/// Name of the log of the audit trail in the state directory
+pub const AUDIT_TRAIL_LOG: &str = "audit_trail";
+
+/// Audit log of the REST requests
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    config: SharedConfig,
+    toggle: Arc<RwLock<Option<bool>>>,
+    trail: StateLog<AuditChange>,
+}
+
+impl AuditLog {
+    /// Create a new audit log using the `audit_log` value of the
+    /// configuration, keeping the audit trail in memory
+    pub fn new(config: SharedConfig) -> Self {
+        Self {
+            config,
+            toggle: Arc::new(RwLock::new(None)),
+            trail: StateLog::default(),
+        }
+    }
+
+    /// Open the audit log with the audit trail persisted in the state
+    /// directory, see [`StateLog::open`]
+    pub fn open(config: SharedConfig, dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            trail: StateLog::open(dir, AUDIT_TRAIL_LOG)?,
+            ..Self::new(config)
+        })
+    }
+
+    /// Add a change to the audit trail, numbering it with the next sequence.
+    ///
+    /// The change has been made when it is recorded, so an error writing it
+    /// is only logged.
+    pub fn push_change(&self, change: AuditChange) {
+        let appended = self.trail.append(|sequence| AuditChange {
+            sequence,
+            ..change.clone()
+        });
+        if let Err(e) = appended {
+            rest_error!(
+                "could not record change {:?} in the audit trail: {}",
+                change,
+                e
+            );
+        }
+    }
+
+    /// Get at most `limit` changes of the actor of the query made in
+    /// `tenant`, `None` being the default tenant, most recent first.
+    ///
+    /// The `next_cursor` of the result is set if more changes match, and
+    /// passed as `cursor` to get the next page.
+    pub fn changes(
+        &self,
+        query: &AuditQuery,
+        tenant: Option<&str>,
+        limit: usize,
+    ) -> Result<AuditChanges, StateError> {
+        // the trail is read oldest first, keeping the most recent matches
+        let mut matching = VecDeque::with_capacity(limit + 1);
+        self.trail.for_each(|change| {
+            let matches = query.cursor.map_or(true, |cursor| change.sequence < cursor)
+                && change.actor == query.actor
+                && change.tenant.as_deref() == tenant
+                && query.from.map_or(true, |from| change.timestamp >= from)
+                && query.to.map_or(true, |to| change.timestamp < to);
+            if matches {
+                if matching.len() > limit {
+                    matching.pop_front();
+                }
+                matching.push_back(change);
+            }
+        })?;
+        let mut changes: Vec<AuditChange> = matching.into_iter().rev().collect();
+
+        let next_cursor = match changes.len() > limit {
+            true => {
+                changes.truncate(limit);
+                changes.last().map(|change| change.sequence)
+            }
+            false => None,
+        };
+
+        Ok(AuditChanges {
+            changes,
+            next_cursor,
+        })
+    }
+
+    /// Check if the audit log is enabled.
+    ///
+    /// A runtime toggle takes precedence over the configuration.
+    pub fn is_enabled(&self) -> bool {
+        let toggle = match self.toggle.read() {
+            Ok(toggle) => *toggle,
+            Err(poisoned) => *poisoned.into_inner(),
+        };
+        toggle.unwrap_or_else(|| self.config.read(|config| config.audit_log))
+    }
+
+    /// Enable or disable the audit log until the service restarts
+    pub fn set(&self, enabled: bool) {
+        let mut toggle = match self.toggle.write() {
+            Ok(toggle) => toggle,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *toggle = Some(enabled);
+    }
+
+    /// Middleware function recording the request and its response while
+    /// the audit log is enabled.
+    pub async fn record(self, req: Request<Body>, next: Next<Body>) -> Response {
+        if !self.is_enabled() {
+            return next.run(req).await;
+        }
+
+        let start = Instant::now();
+        let timestamp = Utc::now();
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+        let operator_id = req
+            .extensions()
+            .get::<TokenOperator>()
+            .map(|operator| operator.0.clone());
+        let tenant = req
+            .extensions()
+            .get::<TokenTenant>()
+            .map(|tenant| tenant.0.clone());
+        let redact_fields = self
+            .config
+            .read(|config| config.audit_log_redact_fields.clone());
+
+        let (parts, request_body) = req.into_parts();
+        let (request_body, request_json) = match is_recorded(&parts.headers, &request_body) {
+            true => {
+                let bytes = read_body(request_body).await;
+                let json = redacted_json(&bytes, &redact_fields);
+                (Body::from(bytes), json)
+            }
+            false => (request_body, Value::Null),
+        };
+        let response = next.run(Request::from_parts(parts, request_body)).await;
+
+        let (parts, response_body) = response.into_parts();
+        let (response_body, response_json) = match is_recorded(&parts.headers, &response_body) {
+            true => {
+                let bytes = read_body(response_body).await;
+                let json = redacted_json(&bytes, &redact_fields);
+                (body::boxed(Full::from(bytes)), json)
+            }
+            false => (response_body, Value::Null),
+        };
+
+        let entry = json!({
+            "method": method.as_str(),
+            "path": path,
+            "status": parts.status.as_u16(),
+            "latency_ms": start.elapsed().as_millis() as u64,
+            "operator_id": operator_id,
+            "request_body": request_json,
+            "response_body": response_json,
+        });
+        log::info!(target: AUDIT_LOG_TARGET, "{}", entry);
+
+        if let Some(actor) = operator_id {
+            if parts.status.is_success() && is_mutating(&method, &path) {
+                let (asset_type, asset_id) = changed_asset(&path, &request_json, &response_json);
+                self.push_change(AuditChange {
+                    sequence: 0,
+                    actor,
+                    tenant,
+                    timestamp,
+                    method: method.to_string(),
+                    path,
+                    asset_type,
+                    asset_id,
+                    status: parts.status.as_u16(),
+                });
+            }
+        }
+
+        Response::from_parts(parts, response_body)
+    }
+}
+
+/// Get the type and id of the asset changed by a request.
+///
+/// The type is the path segment after `/assets`, unless it is the id of the
+/// asset. The id is taken from the path, the `id` of the request body or the
+/// id returned by a registration, whichever is found first.
+pub fn changed_asset(
+    path: &str,
+    request_body: &Value,
+    response_body: &Value,
+) -> (Option<String>, Option<String>) {
+    let mut segments = unversioned(path).trim_start_matches('/').split('/');
+    if segments.next() != Some("assets") {
+        return (None, None);
+    }
+
+    let (asset_type, path_id) = match segments.next() {
+        Some(segment) if to_uuid(segment).is_some() => (None, Some(segment)),
+        Some(segment) if !segment.is_empty() => (Some(segment.to_string()), segments.next()),
+        _ => (None, None),
+    };
+    let asset_id = [
+        path_id,
+        request_body.get("id").and_then(Value::as_str),
+        response_body.as_str(),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(to_uuid)
+    .map(|id| id.to_string());
+
+    (asset_type, asset_id)
+}
+
+/// Check if the headers declare a JSON body
+pub fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/json"))
+        .unwrap_or(false)
+}
+
+/// Check if a body is recorded in the log.
+///
+/// Only JSON bodies which can not exceed [`MAX_AUDIT_BODY_BYTES`] are read,
+/// other bodies are passed on unread and recorded as `null`.
+fn is_recorded<B: HttpBody>(headers: &HeaderMap, body: &B) -> bool {
+    is_json(headers)
+        && body
+            .size_hint()
+            .upper()
+            .map(|upper| upper <= MAX_AUDIT_BODY_BYTES)
+            .unwrap_or(false)
+}
+
+/// Read a body to record it in the log
+async fn read_body<B>(body: B) -> Bytes
+where
+    B: HttpBody<Data = Bytes>,
+    B::Error: std::fmt::Display,
+{
+    hyper::body::to_bytes(body).await.unwrap_or_else(|e| {
+        rest_warn!("could not read body for the audit log: {}", e);
+        Bytes::new()
+    })
+}
+
+/// Parse a JSON body and redact its fields, invalid JSON is recorded as
+/// `null`
+fn redacted_json(bytes: &[u8], redact_fields: &[String]) -> Value {
+    let mut json = serde_json::from_slice(bytes).unwrap_or(Value::Null);
+    redact(&mut json, redact_fields);
+    json
+}
+
+/// Replace the values of the object fields listed in `fields` by
+/// [`REDACTED`], at any depth of the document.
+///
+/// Field names are compared case insensitive.
+pub fn redact(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                match fields.iter().any(|field| field.eq_ignore_ascii_case(key)) {
+                    true => *value = Value::String(REDACTED.to_string()),
+                    false => redact(value, fields),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| redact(value, fields)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::auth::ApiAuth;
+    use crate::rest::quota::OPERATOR_ID_HEADER;
+    use crate::state::test_state_dir;
+    use crate::Config;
+    use axum::http::{header::AUTHORIZATION, StatusCode};
+    use axum::{middleware, routing, Router};
+    use tower::ServiceExt;
+
+    fn fields(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|field| field.to_string()).collect()
+    }
+
+    #[test]
+    fn test_redact() {
+        let mut value = json!({
+            "name": "Operator",
+            "Email": "ops@example.com",
+            "contacts": [{ "phone": "+31 20 0000000", "role": "dispatch" }],
+            "token": null,
+        });
+        redact(&mut value, &fields(&["email", "phone", "token"]));
+        assert_eq!(
+            value,
+            json!({
+                "name": "Operator",
+                "Email": REDACTED,
+                "contacts": [{ "phone": REDACTED, "role": "dispatch" }],
+                "token": REDACTED,
+            })
+        );
+
+        let mut value = json!(["email"]);
+        redact(&mut value, &fields(&["email"]));
+        assert_eq!(value, json!(["email"]));
+    }
+
+    #[test]
+    fn test_audit_log_toggle() {
+        let mut config = Config::default();
+        config.audit_log = true;
+        let config = SharedConfig::from(config);
+        let audit_log = AuditLog::new(config.clone());
+        assert!(audit_log.is_enabled());
+
+        // The configuration is used until the log is toggled at runtime
+        let mut new = config.get();
+        new.audit_log = false;
+        config.apply(new);
+        assert!(!audit_log.is_enabled());
+
+        audit_log.set(true);
+        assert!(audit_log.is_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_record() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let mut config = Config::default();
+        config.audit_log = true;
+        let audit_log = AuditLog::new(config.into());
+        let app = Router::new()
+            .route(
+                "/echo",
+                routing::post(|body: String| async move {
+                    ([(CONTENT_TYPE, "application/json")], body)
+                }),
+            )
+            .layer(middleware::from_fn(
+                move |req: Request<Body>, next: Next<Body>| audit_log.clone().record(req, next),
+            ));
+
+        // The bodies are passed on unchanged, only the log is redacted
+        let payload = r#"{"email":"ops@example.com","role":"dispatch"}"#;
+        let request = Request::post("/echo")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(payload))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, payload.as_bytes());
+
+        assert_eq!(
+            redacted_json(payload.as_bytes(), &fields(&["email"])),
+            json!({ "email": REDACTED, "role": "dispatch" })
+        );
+        assert_eq!(redacted_json(b"not json", &[]), Value::Null);
+
+        ut_info!("success");
+    }
+
+    #[test]
+    fn test_changed_asset() {
+        let id = "00000000-0000-4000-8000-000000000001";
+        let asset = |kind: &str| (Some(kind.to_string()), Some(id.to_string()));
+        assert_eq!(
+            changed_asset(
+                &format!("/assets/aircraft/{id}"),
+                &Value::Null,
+                &Value::Null
+            ),
+            asset("aircraft")
+        );
+        assert_eq!(
+            changed_asset("/v1/assets/vertiports", &json!({ "id": id }), &Value::Null),
+            asset("vertiports")
+        );
+        assert_eq!(
+            changed_asset("/assets/vertipads", &json!({}), &json!(id)),
+            asset("vertipads")
+        );
+        assert_eq!(
+            changed_asset(&format!("/assets/{id}/notes"), &Value::Null, &Value::Null),
+            (None, Some(id.to_string()))
+        );
+        assert_eq!(
+            changed_asset("/assets/groups", &Value::Null, &json!("invalid")),
+            (Some("groups".to_string()), None)
+        );
+        assert_eq!(
+            changed_asset("/health", &Value::Null, &Value::Null),
+            (None, None)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audit_trail() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let alice = "00000000-0000-4000-8000-00000000000a";
+        let bob = "00000000-0000-4000-8000-00000000000b";
+        let mut config = Config::default();
+        config.audit_log = true;
+        config.api_tokens = vec![
+            format!("alice-token=assets:read assets:write operator:{alice}"),
+            format!("bob-token=assets:write tenant:pilot operator:{bob}"),
+            "unbound-token=assets:write".to_string(),
+        ];
+        let config = SharedConfig::from(config);
+        let dir = test_state_dir();
+        let audit_log = AuditLog::open(config.clone(), Some(&dir)).unwrap();
+        let recorder = audit_log.clone();
+        let api_auth = ApiAuth::new(config.clone());
+        let app = Router::new()
+            .route(
+                "/assets/aircraft",
+                routing::post(|| async {
+                    (
+                        [(CONTENT_TYPE, "application/json")],
+                        r#""00000000-0000-4000-8000-000000000001""#,
+                    )
+                })
+                .get(|| async { "[]" }),
+            )
+            .route(
+                "/assets/vertiports",
+                routing::post(|| async { StatusCode::UNPROCESSABLE_ENTITY }),
+            )
+            .layer(middleware::from_fn(
+                move |req: Request<Body>, next: Next<Body>| recorder.clone().record(req, next),
+            ))
+            .layer(middleware::from_fn(
+                move |req: Request<Body>, next: Next<Body>| api_auth.clone().authorize(req, next),
+            ));
+        let send = |method: &str, uri: &str, token: &str| {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(AUTHORIZATION, format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        // only successful changes with a token bound to an operator are kept
+        send("POST", "/assets/aircraft", "alice-token")
+            .await
+            .unwrap();
+        send("POST", "/assets/aircraft", "bob-token").await.unwrap();
+        send("POST", "/assets/aircraft", "unbound-token")
+            .await
+            .unwrap();
+        send("GET", "/assets/aircraft", "alice-token")
+            .await
+            .unwrap();
+        send("POST", "/assets/vertiports", "alice-token")
+            .await
+            .unwrap();
+        send("POST", "/v1/assets/aircraft", "alice-token")
+            .await
+            .unwrap();
+
+        // the header can not name another actor than the token
+        let request = Request::post("/assets/aircraft")
+            .header(AUTHORIZATION, "Bearer bob-token")
+            .header(OPERATOR_ID_HEADER, alice)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let query = |cursor| AuditQuery {
+            actor: alice.to_string(),
+            from: None,
+            to: None,
+            limit: None,
+            cursor,
+        };
+        let page = audit_log.changes(&query(None), None, 1).unwrap();
+        assert_eq!(page.changes.len(), 1);
+        assert_eq!(page.changes[0].path, "/v1/assets/aircraft");
+        assert_eq!(page.changes[0].asset_type, Some("aircraft".to_string()));
+        assert_eq!(
+            page.changes[0].asset_id,
+            Some("00000000-0000-4000-8000-000000000001".to_string())
+        );
+        let cursor = page.next_cursor;
+        assert!(cursor.is_some());
+
+        let page = audit_log.changes(&query(cursor), None, 1).unwrap();
+        assert_eq!(page.changes.len(), 1);
+        assert_eq!(page.changes[0].path, "/assets/aircraft");
+        assert_eq!(page.next_cursor, None);
+
+        let mut late = query(None);
+        late.from = Some(Utc::now());
+        assert!(audit_log
+            .changes(&late, None, 10)
+            .unwrap()
+            .changes
+            .is_empty());
+
+        // the trail is kept after a restart
+        let reopened = AuditLog::open(config, Some(&dir)).unwrap();
+        assert_eq!(
+            reopened.changes(&query(None), None, 10).unwrap(),
+            audit_log.changes(&query(None), None, 10).unwrap()
+        );
+
+        // nothing is kept while the audit log is disabled
+        audit_log.set(false);
+        send("POST", "/assets/aircraft", "bob-token").await.unwrap();
+        let mut bob_changes = query(None);
+        bob_changes.actor = bob.to_string();
+        let pilot_changes = audit_log.changes(&bob_changes, Some("pilot"), 10).unwrap();
+        assert_eq!(pilot_changes.changes.len(), 1);
+        assert_eq!(pilot_changes.changes[0].tenant, Some("pilot".to_string()));
+
+        // the changes of a tenant are not listed to the other tenants
+        assert!(audit_log
+            .changes(&bob_changes, None, 10)
+            .unwrap()
+            .changes
+            .is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,680 @@
+//! API token scopes
+//!
+//! Requests to the REST API can be restricted to bearer tokens configured in
+//! `API_TOKENS`, each granted a set of [`Scope`]s. Every route requires one
+//! scope, derived from its method and path by [`required_scope`]:
+//! - reads require `assets:read`, except the audit trail which requires
+//!   `audit:admin`
+//! - changes of asset groups require `groups:admin`
+//! - changes of operators require `operators:admin`
+//! - all other changes require `assets:write`
+//!
+//! Scopes don't imply each other, a token changing assets usually needs
+//! `assets:read` as well. If no token is configured the API is open, as
+//! before tokens were introduced. The health check and CORS preflight
+//! requests never require a token, and the admin endpoints use the admin
+//! token instead (see [`super::admin`]).
+//!
+//! A token can be bound to a tenant with a `tenant:<name>` entry in its
+//! scopes, see [`super::tenant`], and to an operator with an
+//! `operator:<uuid>` entry. The `x-operator-id` header of a request is set to
+//! the operator of its token; it is rejected if it names another operator,
+//! or if tokens are configured and the token is not bound to an operator.
+//! Only an open API, without tokens, takes the operator from the header as
+//! sent. Response fields configured in
+//! `RESPONSE_REDACT_FIELDS` are redacted for tokens without the scope of the
+//! field, usually `sensitive:read`, see [`super::redaction`].
+//!
+//! The scopes are added to the OpenAPI specification by [`ApiScopes`].
+
+use super::admin::{constant_time_eq, BEARER_PREFIX};
+use super::quota::OPERATOR_ID_HEADER;
+use super::read_only::is_mutating;
+use super::version::ApiVersion;
+use crate::config::SharedConfig;
+use axum::{
+    http::{header::AUTHORIZATION, HeaderMap, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use utoipa::openapi::path::PathItemType;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme};
+use utoipa::Modify;
+
+/// Name of the API token security scheme in the OpenAPI specification
+pub const API_TOKEN_SCHEME: &str = "api_token";
+
+/// Permission granted to an API token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// Read all assets, groups and operators
+    AssetsRead,
+    /// Register, update and remove aircraft, vertiports and vertipads
+    AssetsWrite,
+    /// Register, update and remove asset groups, their schedules and
+    /// delegations
+    GroupsAdmin,
+    /// Change operator contacts and notification preferences
+    OperatorsAdmin,
+    /// Read the audit trail of the changes, see [`super::audit`]
+    AuditAdmin,
+    /// Read the response fields redacted by default, see
+    /// [`super::redaction`]
+    SensitiveRead,
+}
+
+impl Scope {
+    /// All scopes
+    pub const ALL: &'static [Scope] = &[
+        Scope::AssetsRead,
+        Scope::AssetsWrite,
+        Scope::GroupsAdmin,
+        Scope::OperatorsAdmin,
+        Scope::AuditAdmin,
+        Scope::SensitiveRead,
+    ];
+
+    /// Name of the scope (for example `assets:read`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::AssetsRead => "assets:read",
+            Scope::AssetsWrite => "assets:write",
+            Scope::GroupsAdmin => "groups:admin",
+            Scope::OperatorsAdmin => "operators:admin",
+            Scope::AuditAdmin => "audit:admin",
+            Scope::SensitiveRead => "sensitive:read",
+        }
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scope::ALL
+            .iter()
+            .find(|scope| scope.as_str() == s)
+            .copied()
+            .ok_or_else(|| format!("unknown scope '{s}'"))
+    }
+}
+
+/// Prefix of the scope entry binding a token to a tenant
+pub const TENANT_CLAIM_PREFIX: &str = "tenant:";
+
+/// Prefix of the scope entry binding a token to an operator
+pub const OPERATOR_CLAIM_PREFIX: &str = "operator:";
+
+/// An API token and the scopes granted to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiToken {
+    /// The bearer token
+    pub token: String,
+    /// Scopes granted to the token
+    pub scopes: Vec<Scope>,
+    /// Tenant the token is bound to, if any
+    pub tenant: Option<String>,
+    /// UUID of the operator the token is bound to, if any
+    pub operator: Option<String>,
+}
+
+impl FromStr for ApiToken {
+    type Err = String;
+
+    /// Parse a configured token like `<token>=assets:read assets:write`,
+    /// optionally bound to a tenant with a `tenant:<name>` entry and to an
+    /// operator with an `operator:<uuid>` entry.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (token, entries) = s
+            .split_once('=')
+            .ok_or_else(|| "expected '<token>=<scopes>'".to_string())?;
+        let token = token.trim();
+        if token.is_empty() {
+            return Err("empty token".to_string());
+        }
+
+        let mut scopes = vec![];
+        let mut tenant = None;
+        let mut operator = None;
+        for entry in entries.split_whitespace() {
+            if let Some(id) = entry.strip_prefix(OPERATOR_CLAIM_PREFIX) {
+                if operator.is_some() {
+                    return Err("a token can be bound to one operator only".to_string());
+                }
+                let id = lib_common::uuid::to_uuid(id)
+                    .ok_or_else(|| format!("invalid operator id '{id}'"))?;
+                operator = Some(id.to_string());
+                continue;
+            }
+
+            match entry.strip_prefix(TENANT_CLAIM_PREFIX) {
+                Some(_) if tenant.is_some() => {
+                    return Err("a token can be bound to one tenant only".to_string())
+                }
+                Some("") => return Err("empty tenant".to_string()),
+                Some(name) => tenant = Some(name.to_string()),
+                None => scopes.push(Scope::from_str(entry)?),
+            }
+        }
+        if scopes.is_empty() {
+            return Err("a token needs at least one scope".to_string());
+        }
+
+        Ok(Self {
+            token: token.to_string(),
+            scopes,
+            tenant,
+            operator,
+        })
+    }
+}
+
+/// Tenant of the API token of a request, added to the request extensions by
+/// [`ApiAuth::authorize`] if the token is bound to a tenant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenTenant(pub String);
+
+/// Operator of the API token of a request, added to the request extensions
+/// by [`ApiAuth::authorize`] if the token is bound to an operator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenOperator(pub String);
+
+/// Scopes of the API token of a request, added to the request extensions by
+/// [`ApiAuth::authorize`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenScopes(pub Vec<Scope>);
+
+/// Remove the version prefix of a path, if any
+pub fn unversioned(path: &str) -> &str {
+    ApiVersion::ALL
+        .iter()
+        .find_map(|version| {
+            path.strip_prefix(&version.prefix())
+                .filter(|rest| rest.starts_with('/'))
+        })
+        .unwrap_or(path)
+}
+
+/// Get the scope required by a request, `None` for public routes
+pub fn required_scope(method: &Method, path: &str) -> Option<Scope> {
+    let path = unversioned(path);
+    if *method == Method::OPTIONS || path == "/health" || path.starts_with("/admin/") {
+        return None;
+    }
+
+    if path == "/assets/audit" {
+        return Some(Scope::AuditAdmin);
+    }
+
+    if !is_mutating(method, path) {
+        return Some(Scope::AssetsRead);
+    }
+
+    if path.starts_with("/assets/groups") {
+        Some(Scope::GroupsAdmin)
+    } else if path.starts_with("/assets/operators") {
+        Some(Scope::OperatorsAdmin)
+    } else {
+        Some(Scope::AssetsWrite)
+    }
+}
+
+/// Set the `x-operator-id` header of a request to the operator of its
+/// token.
+///
+/// Returns an error message if the header names another operator, or if the
+/// token is not bound to an operator.
+fn bind_operator(headers: &mut HeaderMap, token: &ApiToken) -> Result<(), String> {
+    let header = headers
+        .get(OPERATOR_ID_HEADER)
+        .map(|value| value.to_str().unwrap_or_default().trim().to_lowercase());
+
+    match (&token.operator, header) {
+        (None, None) => Ok(()),
+        (None, Some(_)) => Err(format!(
+            "(server) the {OPERATOR_ID_HEADER} header requires an API token bound to an operator."
+        )),
+        (Some(operator), Some(header)) if header != *operator => Err(format!(
+            "(server) the API token is bound to operator '{operator}'."
+        )),
+        (Some(operator), _) => {
+            let value = HeaderValue::from_str(operator)
+                .map_err(|_| format!("(server) invalid operator '{operator}'."))?;
+            headers.insert(OPERATOR_ID_HEADER, value);
+            Ok(())
+        }
+    }
+}
+
+/// Authorization of the REST API using the `api_tokens` of the
+/// [`SharedConfig`]
+#[derive(Debug, Clone)]
+pub struct ApiAuth {
+    config: SharedConfig,
+}
+
+impl ApiAuth {
+    /// Create a new authorization for the provided configuration
+    pub fn new(config: SharedConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get the configured tokens, skipping invalid entries.
+    ///
+    /// Returns `None` if no token is configured.
+    fn tokens(&self) -> Option<Vec<ApiToken>> {
+        self.config.read(|config| {
+            if config.api_tokens.is_empty() {
+                return None;
+            }
+
+            let tokens = config
+                .api_tokens
+                .iter()
+                .enumerate()
+                .filter_map(|(index, entry)| match ApiToken::from_str(entry) {
+                    Ok(token) => Some(token),
+                    Err(e) => {
+                        rest_warn!("ignoring invalid API token {}: {}", index, e);
+                        None
+                    }
+                })
+                .collect();
+            Some(tokens)
+        })
+    }
+
+    /// Middleware function rejecting requests without a token granted the
+    /// scope of the route.
+    ///
+    /// Returns `UNAUTHORIZED` if the token is missing or unknown, and
+    /// `FORBIDDEN` if it is not granted the required scope or the
+    /// `x-operator-id` header does not match its operator, see
+    /// [`bind_operator`]. All requests are allowed if no token is configured.
+    pub async fn authorize<B>(self, mut req: Request<B>, next: Next<B>) -> Response {
+        let Some(scope) = required_scope(req.method(), req.uri().path()) else {
+            return next.run(req).await;
+        };
+
+        let Some(tokens) = self.tokens() else {
+            return next.run(req).await;
+        };
+
+        let bearer = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix(BEARER_PREFIX));
+        let token = bearer.and_then(|bearer| {
+            tokens
+                .iter()
+                .find(|token| constant_time_eq(bearer.as_bytes(), token.token.as_bytes()))
+        });
+
+        match token {
+            None => {
+                rest_warn!(
+                    "rejected {} {}: invalid API token.",
+                    req.method(),
+                    req.uri().path()
+                );
+                StatusCode::UNAUTHORIZED.into_response()
+            }
+            Some(token) if !token.scopes.contains(&scope) => {
+                rest_warn!(
+                    "rejected {} {}: missing scope {}.",
+                    req.method(),
+                    req.uri().path(),
+                    scope
+                );
+                (
+                    StatusCode::FORBIDDEN,
+                    format!("(server) the API token requires the '{scope}' scope."),
+                )
+                    .into_response()
+            }
+            Some(token) => {
+                if let Err(message) = bind_operator(req.headers_mut(), token) {
+                    rest_warn!(
+                        "rejected {} {}: {}",
+                        req.method(),
+                        req.uri().path(),
+                        message
+                    );
+                    return (StatusCode::FORBIDDEN, message).into_response();
+                }
+                if let Some(tenant) = &token.tenant {
+                    req.extensions_mut().insert(TokenTenant(tenant.clone()));
+                }
+                if let Some(operator) = &token.operator {
+                    req.extensions_mut().insert(TokenOperator(operator.clone()));
+                }
+                req.extensions_mut()
+                    .insert(TokenScopes(token.scopes.clone()));
+                next.run(req).await
+            }
+        }
+    }
+}
+
+/// Get the method of an OpenAPI operation
+fn method(item_type: &PathItemType) -> Method {
+    match item_type {
+        PathItemType::Get => Method::GET,
+        PathItemType::Post => Method::POST,
+        PathItemType::Put => Method::PUT,
+        PathItemType::Delete => Method::DELETE,
+        PathItemType::Options => Method::OPTIONS,
+        PathItemType::Head => Method::HEAD,
+        PathItemType::Patch => Method::PATCH,
+        PathItemType::Trace => Method::TRACE,
+        PathItemType::Connect => Method::CONNECT,
+    }
+}
+
+/// Adds the API token security scheme to the OpenAPI specification, with the
+/// scope required by each operation.
+#[derive(Debug, Clone, Copy)]
+pub struct ApiScopes;
+
+impl Modify for ApiScopes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let scopes = Scope::ALL
+            .iter()
+            .map(|scope| format!("`{scope}`"))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let scheme = HttpBuilder::new()
+            .scheme(HttpAuthScheme::Bearer)
+            .description(Some(format!(
+                "API token configured in `API_TOKENS`, granted one or more of the scopes {scopes}. \
+                 Only required if tokens are configured."
+            )))
+            .build();
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(API_TOKEN_SCHEME, SecurityScheme::Http(scheme));
+
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            for (item_type, operation) in item.operations.iter_mut() {
+                if let Some(scope) = required_scope(&method(item_type), path) {
+                    operation.security = Some(vec![SecurityRequirement::new(
+                        API_TOKEN_SCHEME,
+                        [scope.as_str()],
+                    )]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use axum::{body::Body, middleware, routing, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_required_scope() {
+        let scope = |method: Method, path: &str| required_scope(&method, path);
+        assert_eq!(scope(Method::GET, "/health"), None);
+        assert_eq!(scope(Method::GET, "/v1/health"), None);
+        assert_eq!(scope(Method::OPTIONS, "/assets/aircraft"), None);
+        assert_eq!(scope(Method::PUT, "/admin/read-only"), None);
+        assert_eq!(
+            scope(Method::GET, "/assets/aircraft/id"),
+            Some(Scope::AssetsRead)
+        );
+        assert_eq!(
+            scope(Method::POST, "/v1/assets/aircraft/batch-get"),
+            Some(Scope::AssetsRead)
+        );
+        assert_eq!(
+            scope(Method::POST, "/assets/query"),
+            Some(Scope::AssetsRead)
+        );
+        assert_eq!(
+            scope(Method::DELETE, "/v1/assets/vertiports/id"),
+            Some(Scope::AssetsWrite)
+        );
+        assert_eq!(
+            scope(Method::PUT, "/assets/groups/id/schedule"),
+            Some(Scope::GroupsAdmin)
+        );
+        assert_eq!(
+            scope(Method::POST, "/assets/operators/id/contacts"),
+            Some(Scope::OperatorsAdmin)
+        );
+        assert_eq!(
+            scope(Method::GET, "/v1/assets/groups/id"),
+            Some(Scope::AssetsRead)
+        );
+        assert_eq!(
+            scope(Method::GET, "/v1/assets/audit"),
+            Some(Scope::AuditAdmin)
+        );
+    }
+
+    #[test]
+    fn test_parse_api_token() {
+        assert_eq!(
+            ApiToken::from_str("partner= assets:read ").unwrap(),
+            ApiToken {
+                token: "partner".to_string(),
+                scopes: vec![Scope::AssetsRead],
+                tenant: None,
+                operator: None,
+            }
+        );
+        assert_eq!(
+            ApiToken::from_str("pilot=tenant:pilot assets:read").unwrap(),
+            ApiToken {
+                token: "pilot".to_string(),
+                scopes: vec![Scope::AssetsRead],
+                tenant: Some("pilot".to_string()),
+                operator: None,
+            }
+        );
+        let operator = "1cf2b3a0-0e0a-4bb3-9d23-0a1c5c7e1d10";
+        assert_eq!(
+            ApiToken::from_str(&format!("ops=assets:write operator:{operator}"))
+                .unwrap()
+                .operator,
+            Some(operator.to_string())
+        );
+        assert_eq!(
+            ApiToken::from_str("ops=assets:read groups:admin")
+                .unwrap()
+                .scopes,
+            vec![Scope::AssetsRead, Scope::GroupsAdmin]
+        );
+        assert!(ApiToken::from_str("partner").is_err());
+        assert!(ApiToken::from_str("=assets:read").is_err());
+        assert!(ApiToken::from_str("partner=").is_err());
+        assert!(ApiToken::from_str("partner=assets:delete").is_err());
+        assert!(ApiToken::from_str("partner=tenant:pilot").is_err());
+        assert!(ApiToken::from_str("partner=tenant: assets:read").is_err());
+        assert!(ApiToken::from_str("partner=tenant:a tenant:b assets:read").is_err());
+        assert!(ApiToken::from_str("partner=operator:invalid assets:read").is_err());
+        assert!(ApiToken::from_str(&format!(
+            "partner=operator:{operator} operator:{operator} assets:read"
+        ))
+        .is_err());
+    }
+
+    async fn request(tokens: &[&str], method: Method, authorization: Option<&str>) -> StatusCode {
+        let mut config = Config::default();
+        config.api_tokens = tokens.iter().map(|token| token.to_string()).collect();
+        let auth = ApiAuth::new(SharedConfig::from(config));
+
+        let app = Router::new()
+            .route(
+                "/assets/vertiports/id",
+                routing::get(|| async { "ok" }).delete(|| async { "ok" }),
+            )
+            .layer(middleware::from_fn(
+                move |req: Request<Body>, next: Next<Body>| auth.clone().authorize(req, next),
+            ));
+
+        let mut req = Request::builder()
+            .method(method)
+            .uri("/assets/vertiports/id");
+        if let Some(authorization) = authorization {
+            req = req.header(AUTHORIZATION, authorization);
+        }
+
+        app.oneshot(req.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_api_auth() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let tokens = [
+            "partner=assets:read",
+            "ops=assets:read assets:write",
+            "invalid",
+        ];
+
+        // without tokens the API is open
+        assert_eq!(request(&[], Method::DELETE, None).await, StatusCode::OK);
+
+        // invalid tokens don't open the API
+        assert_eq!(
+            request(&["invalid"], Method::GET, Some("Bearer invalid")).await,
+            StatusCode::UNAUTHORIZED
+        );
+
+        assert_eq!(
+            request(&tokens, Method::GET, Some("Bearer partner")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            request(&tokens, Method::DELETE, Some("Bearer partner")).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            request(&tokens, Method::DELETE, Some("Bearer ops")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            request(&tokens, Method::GET, Some("Bearer unknown")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            request(&tokens, Method::GET, None).await,
+            StatusCode::UNAUTHORIZED
+        );
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_api_auth_operator() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let operator = lib_common::uuid::Uuid::new_v4().to_string();
+        let other = lib_common::uuid::Uuid::new_v4().to_string();
+        let bound = format!("ops=assets:read operator:{operator}");
+        let request = |tokens: Vec<String>, token: Option<&str>, header: Option<&str>| {
+            let mut config = Config::default();
+            config.api_tokens = tokens;
+            let auth = ApiAuth::new(SharedConfig::from(config));
+            let app = Router::new()
+                .route(
+                    "/assets/vertiports/id",
+                    routing::get(|headers: HeaderMap| async move {
+                        headers
+                            .get(OPERATOR_ID_HEADER)
+                            .map(|value| value.to_str().unwrap().to_string())
+                            .unwrap_or_default()
+                    }),
+                )
+                .layer(middleware::from_fn(
+                    move |req: Request<Body>, next: Next<Body>| auth.clone().authorize(req, next),
+                ));
+
+            let mut req = Request::builder().uri("/assets/vertiports/id");
+            if let Some(token) = token {
+                req = req.header(AUTHORIZATION, format!("Bearer {token}"));
+            }
+            if let Some(header) = header {
+                req = req.header(OPERATOR_ID_HEADER, header);
+            }
+            async move {
+                let response = app.oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+                let status = response.status();
+                let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                (status, String::from_utf8(body.to_vec()).unwrap())
+            }
+        };
+
+        // the operator of the token is set, a matching header is accepted
+        for header in [None, Some(operator.as_str())] {
+            assert_eq!(
+                request(vec![bound.clone()], Some("ops"), header).await,
+                (StatusCode::OK, operator.clone())
+            );
+        }
+        let (status, _) = request(vec![bound.clone()], Some("ops"), Some(&other)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // the header is not trusted for tokens without operator
+        let tokens = vec!["partner=assets:read".to_string()];
+        let (status, _) = request(tokens.clone(), Some("partner"), Some(&other)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(
+            request(tokens, Some("partner"), None).await,
+            (StatusCode::OK, String::new())
+        );
+
+        // an open API takes the header as sent
+        assert_eq!(
+            request(vec![], None, Some(&other)).await,
+            (StatusCode::OK, other.clone())
+        );
+
+        ut_info!("success");
+    }
+
+    #[test]
+    fn test_api_scopes() {
+        use utoipa::OpenApi;
+
+        let openapi = crate::rest::ApiDoc::openapi();
+        let components = openapi.components.unwrap();
+        assert!(components.security_schemes.contains_key(API_TOKEN_SCHEME));
+
+        let security = |path: &str, item_type: PathItemType| {
+            serde_json::to_value(&openapi.paths.paths[path].operations[&item_type].security)
+                .unwrap()
+        };
+        assert_eq!(
+            security("/assets/vertiports/{id}", PathItemType::Delete),
+            serde_json::json!([{ "api_token": ["assets:write"] }])
+        );
+        assert_eq!(
+            security("/assets/vertiports/{id}", PathItemType::Get),
+            serde_json::json!([{ "api_token": ["assets:read"] }])
+        );
+        assert_eq!(
+            security("/health", PathItemType::Get),
+            serde_json::Value::Null
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Vertipad chargers
+//!
+//! The charging infrastructure of the vertipads, used to route electric
+//! aircraft to pads where they can charge. svc-storage does not store
+//! chargers, so they are kept in the [`VERTIPAD_CHARGERS_TABLE`] of the
+//! persisted state, see [`crate::state`]. The chargers are not shared
+//! between replicas, so a pad only has the chargers registered on the
+//! instance routing the aircraft.
+
+pub use super::api::rest_types::{
+    Charger, ChargerAvailability, ChargerConnector, ChargerPayload, VertipadChargerQuery,
+};
+
+use crate::state::{StateError, StateTable};
+use lib_common::time::{DateTime, Utc};
+use lib_common::uuid::Uuid;
+
+/// Name of the table of the vertipad chargers in the state directory
+pub const VERTIPAD_CHARGERS_TABLE: &str = "vertipad_chargers";
+
+/// Maximum number of chargers of a vertipad
+pub const MAX_CHARGERS_PER_VERTIPAD: usize = 16;
+
+/// Error returned when changing the chargers of a vertipad
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChargerError {
+    /// The charger does not exist
+    NotFound,
+    /// The number of chargers of the vertipad reached
+    /// [`MAX_CHARGERS_PER_VERTIPAD`]
+    TooMany,
+    /// The chargers could not be persisted
+    State(StateError),
+}
+
+/// Store of the chargers by vertipad id.
+///
+/// The default store is kept in memory, [`VertipadChargers::open`]
+/// persists the chargers.
+#[derive(Debug, Clone, Default)]
+pub struct VertipadChargers {
+    vertipads: StateTable<Vec<Charger>>,
+}
+
+impl VertipadChargers {
+    /// Open the chargers persisted in the state directory, kept in memory if
+    /// no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            vertipads: StateTable::open(dir, VERTIPAD_CHARGERS_TABLE)?,
+        })
+    }
+
+    /// Get the chargers of a vertipad, in the order they were added
+    pub fn list(&self, vertipad_id: &str) -> Vec<Charger> {
+        self.vertipads.get(vertipad_id).unwrap_or_default()
+    }
+
+    /// Get a charger of a vertipad by id
+    pub fn get(&self, vertipad_id: &str, charger_id: &str) -> Option<Charger> {
+        self.list(vertipad_id)
+            .into_iter()
+            .find(|charger| charger.id == charger_id)
+    }
+
+    /// Add a charger to a vertipad, returning the new charger
+    pub fn add(
+        &self,
+        vertipad_id: &str,
+        payload: ChargerPayload,
+        now: DateTime<Utc>,
+    ) -> Result<Charger, ChargerError> {
+        if self.list(vertipad_id).len() >= MAX_CHARGERS_PER_VERTIPAD {
+            return Err(ChargerError::TooMany);
+        }
+
+        let charger = Charger {
+            id: Uuid::new_v4().to_string(),
+            vertipad_id: vertipad_id.to_string(),
+            connector: payload.connector,
+            max_kw: payload.max_kw,
+            availability: payload.availability,
+            created_at: now,
+            updated_at: now,
+        };
+        self.vertipads
+            .update(|vertipads| {
+                let chargers = vertipads.entry(vertipad_id.to_string()).or_default();
+                match chargers.len() < MAX_CHARGERS_PER_VERTIPAD {
+                    true => {
+                        chargers.push(charger.clone());
+                        Ok(charger)
+                    }
+                    false => Err(ChargerError::TooMany),
+                }
+            })
+            .map_err(ChargerError::State)?
+    }
+
+    /// Replace the connector, power and availability of a charger, returning
+    /// the updated charger
+    pub fn update(
+        &self,
+        vertipad_id: &str,
+        charger_id: &str,
+        payload: ChargerPayload,
+        now: DateTime<Utc>,
+    ) -> Result<Charger, ChargerError> {
+        if self.get(vertipad_id, charger_id).is_none() {
+            return Err(ChargerError::NotFound);
+        }
+
+        self.vertipads
+            .update(|vertipads| {
+                let charger = vertipads
+                    .get_mut(vertipad_id)
+                    .and_then(|chargers| {
+                        chargers.iter_mut().find(|charger| charger.id == charger_id)
+                    })
+                    .ok_or(ChargerError::NotFound)?;
+                charger.connector = payload.connector;
+                charger.max_kw = payload.max_kw;
+                charger.availability = payload.availability;
+                charger.updated_at = now;
+                Ok(charger.clone())
+            })
+            .map_err(ChargerError::State)?
+    }
+
+    /// Remove a charger of a vertipad
+    pub fn remove(&self, vertipad_id: &str, charger_id: &str) -> Result<(), ChargerError> {
+        if self.get(vertipad_id, charger_id).is_none() {
+            return Err(ChargerError::NotFound);
+        }
+
+        self.vertipads
+            .update(|vertipads| {
+                let chargers = vertipads
+                    .get_mut(vertipad_id)
+                    .ok_or(ChargerError::NotFound)?;
+                let index = chargers
+                    .iter()
+                    .position(|charger| charger.id == charger_id)
+                    .ok_or(ChargerError::NotFound)?;
+                chargers.remove(index);
+                if chargers.is_empty() {
+                    vertipads.remove(vertipad_id);
+                }
+                Ok(())
+            })
+            .map_err(ChargerError::State)?
+    }
+
+    /// Remove all chargers of a removed vertipad.
+    ///
+    /// The vertipad is removed even if its chargers could not be written, so
+    /// the error is only logged.
+    pub fn remove_vertipad(&self, vertipad_id: &str) {
+        if let Err(e) = self.vertipads.remove(vertipad_id) {
+            rest_warn!(
+                "could not remove the chargers of vertipad {}: {e}",
+                vertipad_id
+            );
+        }
+    }
+
+    /// Check if the chargers of a vertipad match the charger filters of the
+    /// vertipad list. Chargers out of service are not counted.
+    pub fn matches(&self, vertipad_id: &str, query: &VertipadChargerQuery) -> bool {
+        let chargers = self.list(vertipad_id);
+        let mut usable = chargers
+            .iter()
+            .filter(|charger| charger.availability != ChargerAvailability::OutOfService);
+
+        match (query.has_charger, query.min_kw) {
+            (Some(false), _) => usable.next().is_none(),
+            (_, Some(min_kw)) => usable.any(|charger| charger.max_kw >= min_kw),
+            (Some(true), None) => usable.next().is_some(),
+            (None, None) => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(max_kw: f64, availability: ChargerAvailability) -> ChargerPayload {
+        ChargerPayload {
+            connector: ChargerConnector::Ccs2,
+            max_kw,
+            availability,
+        }
+    }
+
+    #[test]
+    fn test_vertipad_chargers() {
+        let chargers = VertipadChargers::default();
+        let now = Utc::now();
+        assert!(chargers.list("pad").is_empty());
+
+        let fast = chargers
+            .add("pad", payload(150.0, ChargerAvailability::Available), now)
+            .unwrap();
+        chargers
+            .add("pad", payload(50.0, ChargerAvailability::InUse), now)
+            .unwrap();
+        let kw: Vec<f64> = chargers.list("pad").iter().map(|c| c.max_kw).collect();
+        assert_eq!(kw, vec![150.0, 50.0]);
+        assert_eq!(chargers.get("pad", &fast.id), Some(fast.clone()));
+        assert_eq!(chargers.get("other", &fast.id), None);
+
+        let later = now + chrono::Duration::minutes(1);
+        let updated = chargers
+            .update(
+                "pad",
+                &fast.id,
+                payload(350.0, ChargerAvailability::OutOfService),
+                later,
+            )
+            .unwrap();
+        assert_eq!(updated.max_kw, 350.0);
+        assert_eq!(updated.created_at, now);
+        assert_eq!(updated.updated_at, later);
+        assert_eq!(
+            chargers.update("other", &fast.id, payload(1.0, Default::default()), now),
+            Err(ChargerError::NotFound)
+        );
+
+        assert_eq!(chargers.remove("pad", &fast.id), Ok(()));
+        assert_eq!(
+            chargers.remove("pad", &fast.id),
+            Err(ChargerError::NotFound)
+        );
+        assert_eq!(chargers.list("pad").len(), 1);
+
+        chargers.remove_vertipad("pad");
+        assert!(chargers.list("pad").is_empty());
+    }
+
+    #[test]
+    fn test_max_chargers_per_vertipad() {
+        let chargers = VertipadChargers::default();
+        let now = Utc::now();
+        for _ in 0..MAX_CHARGERS_PER_VERTIPAD {
+            chargers
+                .add("pad", payload(50.0, Default::default()), now)
+                .unwrap();
+        }
+        assert_eq!(
+            chargers.add("pad", payload(50.0, Default::default()), now),
+            Err(ChargerError::TooMany)
+        );
+        chargers
+            .add("other", payload(50.0, Default::default()), now)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_vertipad_chargers_persisted() {
+        let dir = crate::state::test_state_dir();
+        let now = Utc::now();
+
+        let chargers = VertipadChargers::open(Some(&dir)).unwrap();
+        let fast = chargers
+            .add("pad", payload(150.0, ChargerAvailability::Available), now)
+            .unwrap();
+        chargers
+            .add(
+                "removed",
+                payload(50.0, ChargerAvailability::Available),
+                now,
+            )
+            .unwrap();
+        chargers.remove_vertipad("removed");
+
+        let chargers = VertipadChargers::open(Some(&dir)).unwrap();
+        assert_eq!(chargers.list("pad"), vec![fast]);
+        assert!(chargers.list("removed").is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_charger_query() {
+        let chargers = VertipadChargers::default();
+        let now = Utc::now();
+        chargers
+            .add("fast", payload(150.0, ChargerAvailability::InUse), now)
+            .unwrap();
+        chargers
+            .add("slow", payload(50.0, ChargerAvailability::Available), now)
+            .unwrap();
+        chargers
+            .add(
+                "broken",
+                payload(350.0, ChargerAvailability::OutOfService),
+                now,
+            )
+            .unwrap();
+
+        let query = |has_charger, min_kw| VertipadChargerQuery {
+            has_charger,
+            min_kw,
+        };
+        let matching = |query: VertipadChargerQuery| -> Vec<&str> {
+            ["fast", "slow", "broken", "none"]
+                .into_iter()
+                .filter(|id| chargers.matches(id, &query))
+                .collect()
+        };
+
+        assert_eq!(
+            matching(query(None, None)),
+            vec!["fast", "slow", "broken", "none"]
+        );
+        assert_eq!(matching(query(Some(true), None)), vec!["fast", "slow"]);
+        assert_eq!(matching(query(Some(false), None)), vec!["broken", "none"]);
+        assert_eq!(matching(query(Some(true), Some(150.0))), vec!["fast"]);
+        assert_eq!(matching(query(None, Some(100.0))), vec!["fast"]);
+    }
+}
@@ -0,0 +1,176 @@
+//! Vertipad classification
+//!
+//! The type (FATO, TLOF or both), surface, maximum aircraft weight and
+//! dimensions of a vertipad, used to schedule heavier aircraft types on the
+//! pads able to take them.
+//!
+//! svc-storage has no classification fields for vertipads, so
+//! classifications are kept in the [`PAD_CLASSIFICATIONS_TABLE`] of the
+//! persisted state, see [`crate::state`]. They are not shared between
+//! replicas. An unclassified vertipad is left out of the searches by
+//! aircraft weight.
+
+pub use super::api::rest_types::{PadClassification, PadSurface, PadType};
+
+use super::api::validation::{field_error, into_result, FieldError};
+use crate::state::{StateError, StateTable};
+
+/// Name of the table of the vertipad classifications in the state directory
+pub const PAD_CLASSIFICATIONS_TABLE: &str = "pad_classifications";
+
+/// Maximum aircraft weight in kilograms a vertipad can be classified for
+const MAX_AIRCRAFT_WEIGHT_KG: f64 = 20_000.0;
+
+/// Maximum length and width of a vertipad in meters
+const MAX_PAD_DIMENSION_M: f64 = 500.0;
+
+/// Store of the classification per vertipad id.
+///
+/// The default store is kept in memory, [`PadClassifications::open`]
+/// persists the classifications.
+#[derive(Debug, Clone, Default)]
+pub struct PadClassifications {
+    vertipads: StateTable<PadClassification>,
+}
+
+impl PadClassifications {
+    /// Open the classifications persisted in the state directory, kept in
+    /// memory if no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            vertipads: StateTable::open(dir, PAD_CLASSIFICATIONS_TABLE)?,
+        })
+    }
+
+    /// Get the classification of a vertipad
+    pub fn get(&self, vertipad_id: &str) -> Option<PadClassification> {
+        self.vertipads.get(vertipad_id)
+    }
+
+    /// Replace the classification of a vertipad
+    pub fn set(
+        &self,
+        vertipad_id: &str,
+        classification: PadClassification,
+    ) -> Result<(), StateError> {
+        self.vertipads
+            .insert(vertipad_id, classification)
+            .map(|_| ())
+    }
+
+    /// Remove the classification of a removed vertipad.
+    ///
+    /// The vertipad is removed even if its classification could not be
+    /// written, so the error is only logged.
+    pub fn remove(&self, vertipad_id: &str) {
+        if let Err(e) = self.vertipads.remove(vertipad_id) {
+            rest_warn!(
+                "could not remove the classification of vertipad {}: {e}",
+                vertipad_id
+            );
+        }
+    }
+}
+
+/// Check that a value is a number within `1..=max`
+fn check_range(errors: &mut Vec<FieldError>, field: &str, value: f64, max: f64) {
+    if !value.is_finite() || !(1.0..=max).contains(&value) {
+        errors.push(field_error(field, &format!("must be between 1 and {max}")));
+    }
+}
+
+/// Validate a [`PadClassification`].
+///
+/// The weight and dimensions must be within range, see [`PadClassification`].
+pub fn validate_pad_classification(
+    classification: PadClassification,
+) -> Result<PadClassification, Vec<FieldError>> {
+    let mut errors = vec![];
+    check_range(
+        &mut errors,
+        "max_aircraft_weight_kg",
+        classification.max_aircraft_weight_kg,
+        MAX_AIRCRAFT_WEIGHT_KG,
+    );
+    check_range(
+        &mut errors,
+        "length_m",
+        classification.length_m,
+        MAX_PAD_DIMENSION_M,
+    );
+    check_range(
+        &mut errors,
+        "width_m",
+        classification.width_m,
+        MAX_PAD_DIMENSION_M,
+    );
+
+    into_result(classification, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classification() -> PadClassification {
+        PadClassification {
+            pad_type: PadType::FatoTlof,
+            surface: PadSurface::Concrete,
+            max_aircraft_weight_kg: 3175.0,
+            length_m: 30.0,
+            width_m: 30.0,
+        }
+    }
+
+    #[test]
+    fn test_validate_pad_classification() {
+        assert_eq!(
+            validate_pad_classification(classification()),
+            Ok(classification())
+        );
+
+        let invalid = PadClassification {
+            max_aircraft_weight_kg: 0.0,
+            length_m: f64::NAN,
+            width_m: 501.0,
+            ..classification()
+        };
+        let fields: Vec<String> = validate_pad_classification(invalid)
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.field)
+            .collect();
+        assert_eq!(
+            fields,
+            vec!["max_aircraft_weight_kg", "length_m", "width_m"]
+        );
+    }
+
+    #[test]
+    fn test_pad_classifications() {
+        let classifications = PadClassifications::default();
+        assert_eq!(classifications.get("pad"), None);
+
+        classifications.set("pad", classification()).unwrap();
+        assert_eq!(classifications.get("pad"), Some(classification()));
+
+        classifications.remove("pad");
+        assert_eq!(classifications.get("pad"), None);
+    }
+
+    #[test]
+    fn test_pad_classifications_persisted() {
+        let dir = crate::state::test_state_dir();
+
+        let classifications = PadClassifications::open(Some(&dir)).unwrap();
+        classifications.set("pad", classification()).unwrap();
+        classifications.set("removed", classification()).unwrap();
+        classifications.remove("removed");
+
+        let classifications = PadClassifications::open(Some(&dir)).unwrap();
+        assert_eq!(classifications.get("pad"), Some(classification()));
+        assert_eq!(classifications.get("removed"), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
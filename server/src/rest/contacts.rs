@@ -0,0 +1,449 @@
+//! Operator contacts and notification preferences
+//!
+//! svc-storage does not store operators yet, so the contacts and
+//! notification preferences are kept in the [`OPERATOR_CONTACTS_TABLE`] of
+//! the persisted state, see [`crate::state`]. The preferences determine who
+//! receives the notifications of an event, for example an overdue
+//! maintenance. The contacts and preferences are not shared between
+//! replicas, so the subscribers of an event are the ones set through the
+//! same instance.
+
+pub use super::api::rest_types::{
+    NotificationChannel, NotificationEvent, NotificationPreference, OperatorContact,
+    OperatorContactPayload,
+};
+
+use super::api::validation::{
+    check_not_empty, field_error, into_result, normalize_optional_string, normalize_string,
+    FieldError,
+};
+use crate::state::{StateError, StateTable};
+use lib_common::uuid::Uuid;
+use serde::{Deserialize, Serialize};
+
+/// Name of the table of the operator contacts in the state directory
+pub const OPERATOR_CONTACTS_TABLE: &str = "operator_contacts";
+
+/// Maximum number of contacts per operator
+pub const MAX_CONTACTS_PER_OPERATOR: usize = 50;
+
+/// Maximum number of notification preferences per operator
+pub const MAX_NOTIFICATION_PREFERENCES: usize = 50;
+
+/// Contacts and notification preferences of a single operator
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct OperatorEntry {
+    contacts: Vec<OperatorContact>,
+    preferences: Vec<NotificationPreference>,
+}
+
+/// Error returned when changing the contacts of an operator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContactError {
+    /// The contact does not exist
+    NotFound,
+    /// The operator reached [`MAX_CONTACTS_PER_OPERATOR`]
+    TooMany,
+    /// The contacts could not be persisted
+    State(StateError),
+}
+
+/// Store of the contacts and notification preferences per operator id.
+///
+/// The default store is kept in memory, [`OperatorDirectory::open`]
+/// persists the contacts and preferences.
+#[derive(Debug, Clone, Default)]
+pub struct OperatorDirectory {
+    operators: StateTable<OperatorEntry>,
+}
+
+impl OperatorDirectory {
+    /// Open the contacts and preferences persisted in the state directory,
+    /// kept in memory if no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            operators: StateTable::open(dir, OPERATOR_CONTACTS_TABLE)?,
+        })
+    }
+
+    /// Get the contacts of an operator
+    pub fn contacts(&self, operator_id: &str) -> Vec<OperatorContact> {
+        self.operators
+            .get(operator_id)
+            .map(|entry| entry.contacts)
+            .unwrap_or_default()
+    }
+
+    /// Add a contact to an operator, returning the new contact
+    pub fn add_contact(
+        &self,
+        operator_id: &str,
+        payload: OperatorContactPayload,
+    ) -> Result<OperatorContact, ContactError> {
+        if self.contacts(operator_id).len() >= MAX_CONTACTS_PER_OPERATOR {
+            return Err(ContactError::TooMany);
+        }
+
+        let contact = OperatorContact {
+            id: Uuid::new_v4().to_string(),
+            name: payload.name,
+            role: payload.role,
+            email: payload.email,
+            phone: payload.phone,
+        };
+        self.operators
+            .update(|operators| {
+                let entry = operators.entry(operator_id.to_string()).or_default();
+                match entry.contacts.len() < MAX_CONTACTS_PER_OPERATOR {
+                    true => {
+                        entry.contacts.push(contact.clone());
+                        Ok(contact)
+                    }
+                    false => Err(ContactError::TooMany),
+                }
+            })
+            .map_err(ContactError::State)?
+    }
+
+    /// Replace a contact of an operator, returning the updated contact
+    pub fn update_contact(
+        &self,
+        operator_id: &str,
+        contact_id: &str,
+        payload: OperatorContactPayload,
+    ) -> Result<OperatorContact, ContactError> {
+        if !self.has_contact(operator_id, contact_id) {
+            return Err(ContactError::NotFound);
+        }
+
+        self.operators
+            .update(|operators| {
+                let contact = operators
+                    .get_mut(operator_id)
+                    .and_then(|entry| {
+                        entry
+                            .contacts
+                            .iter_mut()
+                            .find(|contact| contact.id == contact_id)
+                    })
+                    .ok_or(ContactError::NotFound)?;
+
+                contact.name = payload.name;
+                contact.role = payload.role;
+                contact.email = payload.email;
+                contact.phone = payload.phone;
+                Ok(contact.clone())
+            })
+            .map_err(ContactError::State)?
+    }
+
+    /// Remove a contact of an operator
+    pub fn remove_contact(&self, operator_id: &str, contact_id: &str) -> Result<(), ContactError> {
+        if !self.has_contact(operator_id, contact_id) {
+            return Err(ContactError::NotFound);
+        }
+
+        self.operators
+            .update(|operators| {
+                let contacts = &mut operators
+                    .get_mut(operator_id)
+                    .ok_or(ContactError::NotFound)?
+                    .contacts;
+                let len = contacts.len();
+                contacts.retain(|contact| contact.id != contact_id);
+
+                match contacts.len() < len {
+                    true => Ok(()),
+                    false => Err(ContactError::NotFound),
+                }
+            })
+            .map_err(ContactError::State)?
+    }
+
+    /// Check if an operator has a contact
+    fn has_contact(&self, operator_id: &str, contact_id: &str) -> bool {
+        self.contacts(operator_id)
+            .iter()
+            .any(|contact| contact.id == contact_id)
+    }
+
+    /// Get the notification preferences of an operator
+    pub fn preferences(&self, operator_id: &str) -> Vec<NotificationPreference> {
+        self.operators
+            .get(operator_id)
+            .map(|entry| entry.preferences)
+            .unwrap_or_default()
+    }
+
+    /// Replace the notification preferences of an operator
+    pub fn set_preferences(
+        &self,
+        operator_id: &str,
+        preferences: Vec<NotificationPreference>,
+    ) -> Result<(), StateError> {
+        self.operators.update(|operators| {
+            operators
+                .entry(operator_id.to_string())
+                .or_default()
+                .preferences = preferences;
+        })
+    }
+
+    /// Get the preferences of an operator the provided event has to be sent to
+    pub fn subscribers(
+        &self,
+        operator_id: &str,
+        event: NotificationEvent,
+    ) -> Vec<NotificationPreference> {
+        self.preferences(operator_id)
+            .into_iter()
+            .filter(|preference| preference.event == event)
+            .collect()
+    }
+}
+
+/// Check if a value looks like an email address
+fn is_valid_email(value: &str) -> bool {
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !value.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+/// Check if a value looks like a phone number, for example `+31 20 123 4567`
+fn is_valid_phone(value: &str) -> bool {
+    let digits = value.chars().filter(char::is_ascii_digit).count();
+    (6..=15).contains(&digits)
+        && value
+            .trim_start_matches('+')
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '-' | '(' | ')'))
+}
+
+/// Check if a value is an http(s) url
+fn is_valid_webhook(value: &str) -> bool {
+    ["https://", "http://"].iter().any(|scheme| {
+        value
+            .strip_prefix(scheme)
+            .map(|rest| !rest.is_empty() && !rest.chars().any(char::is_whitespace))
+            .unwrap_or(false)
+    })
+}
+
+/// Validate and normalize an [`OperatorContactPayload`].
+///
+/// A contact needs an email address or a phone number.
+pub fn validate_contact(
+    mut payload: OperatorContactPayload,
+) -> Result<OperatorContactPayload, Vec<FieldError>> {
+    normalize_optional_string(&mut payload.name);
+    normalize_string(&mut payload.role);
+    normalize_optional_string(&mut payload.email);
+    normalize_optional_string(&mut payload.phone);
+    payload.role = payload.role.to_lowercase();
+
+    let mut errors = vec![];
+    check_not_empty(&mut errors, "role", &payload.role);
+
+    if let Some(email) = &payload.email {
+        if !is_valid_email(email) {
+            errors.push(field_error("email", "must be a valid email address"));
+        }
+    }
+
+    if let Some(phone) = &payload.phone {
+        if !is_valid_phone(phone) {
+            errors.push(field_error("phone", "must be a valid phone number"));
+        }
+    }
+
+    if payload.email.is_none() && payload.phone.is_none() {
+        errors.push(field_error("email", "an email or phone number is required"));
+    }
+
+    into_result(payload, errors)
+}
+
+/// Validate and normalize a list of [`NotificationPreference`]s.
+///
+/// Duplicate preferences are removed.
+pub fn validate_preferences(
+    preferences: Vec<NotificationPreference>,
+) -> Result<Vec<NotificationPreference>, Vec<FieldError>> {
+    let mut errors = vec![];
+    if preferences.len() > MAX_NOTIFICATION_PREFERENCES {
+        errors.push(field_error(
+            "preferences",
+            &format!("can not contain more than {MAX_NOTIFICATION_PREFERENCES} entries"),
+        ));
+    }
+
+    let mut normalized: Vec<NotificationPreference> = vec![];
+    for (index, mut preference) in preferences.into_iter().enumerate() {
+        normalize_string(&mut preference.target);
+        let valid = match preference.channel {
+            NotificationChannel::Webhook => is_valid_webhook(&preference.target),
+            NotificationChannel::Email => is_valid_email(&preference.target),
+        };
+
+        if !valid {
+            errors.push(field_error(
+                &format!("[{index}].target"),
+                "must be an http(s) url for webhooks or an email address for emails",
+            ));
+        } else if !normalized.contains(&preference) {
+            normalized.push(preference);
+        }
+    }
+
+    into_result(normalized, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(email: Option<&str>, phone: Option<&str>) -> OperatorContactPayload {
+        OperatorContactPayload {
+            name: Some(" Jane ".to_string()),
+            role: " Maintenance ".to_string(),
+            email: email.map(str::to_string),
+            phone: phone.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_validate_contact() {
+        let normalized = validate_contact(contact(Some("jane@example.com"), None)).unwrap();
+        assert_eq!(normalized.name, Some("Jane".to_string()));
+        assert_eq!(normalized.role, "maintenance");
+        assert!(validate_contact(contact(None, Some("+31 (20) 123-4567"))).is_ok());
+
+        let errors = validate_contact(contact(Some("jane@example"), Some("12"))).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["email", "phone"]);
+
+        let errors = validate_contact(OperatorContactPayload::default()).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["role", "email"]);
+    }
+
+    #[test]
+    fn test_validate_preferences() {
+        let preference = |channel, target: &str| NotificationPreference {
+            event: NotificationEvent::MaintenanceDue,
+            channel,
+            target: target.to_string(),
+        };
+
+        let preferences = validate_preferences(vec![
+            preference(NotificationChannel::Webhook, " https://example.com/hook "),
+            preference(NotificationChannel::Webhook, "https://example.com/hook"),
+            preference(NotificationChannel::Email, "ops@example.com"),
+        ])
+        .unwrap();
+        assert_eq!(preferences.len(), 2);
+        assert_eq!(preferences[0].target, "https://example.com/hook");
+
+        let errors = validate_preferences(vec![
+            preference(NotificationChannel::Webhook, "ops@example.com"),
+            preference(NotificationChannel::Email, "https://example.com/hook"),
+        ])
+        .unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["[0].target", "[1].target"]);
+    }
+
+    #[test]
+    fn test_operator_directory() {
+        let directory = OperatorDirectory::default();
+        let id = "operator";
+        assert!(directory.contacts(id).is_empty());
+
+        let added = directory
+            .add_contact(id, contact(Some("jane@example.com"), None))
+            .unwrap();
+        assert_eq!(directory.contacts(id), vec![added.clone()]);
+
+        let updated = directory
+            .update_contact(id, &added.id, contact(None, Some("+31201234567")))
+            .unwrap();
+        assert_eq!(updated.id, added.id);
+        assert_eq!(updated.email, None);
+        assert_eq!(
+            directory.update_contact(id, "unknown", contact(None, None)),
+            Err(ContactError::NotFound)
+        );
+
+        directory.remove_contact(id, &added.id).unwrap();
+        assert_eq!(
+            directory.remove_contact(id, &added.id),
+            Err(ContactError::NotFound)
+        );
+        assert_eq!(
+            directory.remove_contact("unknown", &added.id),
+            Err(ContactError::NotFound)
+        );
+
+        for _ in 0..MAX_CONTACTS_PER_OPERATOR {
+            directory
+                .add_contact(id, contact(Some("jane@example.com"), None))
+                .unwrap();
+        }
+        assert_eq!(
+            directory.add_contact(id, contact(Some("jane@example.com"), None)),
+            Err(ContactError::TooMany)
+        );
+
+        let webhook = NotificationPreference {
+            event: NotificationEvent::Delegation,
+            channel: NotificationChannel::Webhook,
+            target: "https://example.com/hook".to_string(),
+        };
+        let email = NotificationPreference {
+            event: NotificationEvent::MaintenanceDue,
+            channel: NotificationChannel::Email,
+            target: "ops@example.com".to_string(),
+        };
+        directory
+            .set_preferences(id, vec![webhook.clone(), email.clone()])
+            .unwrap();
+        assert_eq!(directory.preferences(id).len(), 2);
+        assert_eq!(
+            directory.subscribers(id, NotificationEvent::MaintenanceDue),
+            vec![email]
+        );
+        assert!(directory
+            .subscribers("unknown", NotificationEvent::Delegation)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_operator_directory_persisted() {
+        let dir = crate::state::test_state_dir();
+        let id = "operator";
+        let email = NotificationPreference {
+            event: NotificationEvent::MaintenanceDue,
+            channel: NotificationChannel::Email,
+            target: "ops@example.com".to_string(),
+        };
+
+        let directory = OperatorDirectory::open(Some(&dir)).unwrap();
+        let added = directory
+            .add_contact(id, contact(Some("jane@example.com"), None))
+            .unwrap();
+        directory.set_preferences(id, vec![email.clone()]).unwrap();
+
+        let directory = OperatorDirectory::open(Some(&dir)).unwrap();
+        assert_eq!(directory.contacts(id), vec![added]);
+        assert_eq!(directory.preferences(id), vec![email]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
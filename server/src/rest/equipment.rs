@@ -0,0 +1,328 @@
+//! Vertipad equipment
+//!
+//! The lighting, sensors and weather stations of the vertipads, with their
+//! status and last inspection, so ground operations no longer track them in
+//! a separate tool. A vertipad with failed lighting can not be used at
+//! night, see
+//! [`get_vertipad_availability`](super::api::vertipad::get_vertipad_availability).
+//!
+//! svc-storage does not store equipment, so it is kept in the
+//! [`VERTIPAD_EQUIPMENT_TABLE`] of the persisted state, see
+//! [`crate::state`], and a vertipad with failed lighting stays unusable at
+//! night after a restart. Replicas do not share the equipment.
+
+pub use super::api::rest_types::{Equipment, EquipmentKind, EquipmentPayload, EquipmentStatus};
+
+use crate::state::{StateError, StateTable};
+use lib_common::time::{DateTime, Utc};
+use lib_common::uuid::Uuid;
+
+/// Name of the table of the vertipad equipment in the state directory
+pub const VERTIPAD_EQUIPMENT_TABLE: &str = "vertipad_equipment";
+
+/// Maximum number of equipment records of a vertipad
+pub const MAX_EQUIPMENT_PER_VERTIPAD: usize = 32;
+
+/// Error returned when changing the equipment of a vertipad
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EquipmentError {
+    /// The equipment does not exist
+    NotFound,
+    /// The number of equipment records of the vertipad reached
+    /// [`MAX_EQUIPMENT_PER_VERTIPAD`]
+    TooMany,
+    /// The equipment could not be persisted
+    State(StateError),
+}
+
+/// Store of the equipment by vertipad id.
+///
+/// The default store is kept in memory, [`VertipadEquipment::open`]
+/// persists the equipment.
+#[derive(Debug, Clone, Default)]
+pub struct VertipadEquipment {
+    vertipads: StateTable<Vec<Equipment>>,
+}
+
+impl VertipadEquipment {
+    /// Open the equipment persisted in the state directory, kept in memory
+    /// if no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            vertipads: StateTable::open(dir, VERTIPAD_EQUIPMENT_TABLE)?,
+        })
+    }
+
+    /// Get the equipment of a vertipad, in the order it was added
+    pub fn list(&self, vertipad_id: &str) -> Vec<Equipment> {
+        self.vertipads.get(vertipad_id).unwrap_or_default()
+    }
+
+    /// Get equipment of a vertipad by id
+    pub fn get(&self, vertipad_id: &str, equipment_id: &str) -> Option<Equipment> {
+        self.list(vertipad_id)
+            .into_iter()
+            .find(|equipment| equipment.id == equipment_id)
+    }
+
+    /// Add equipment to a vertipad, returning the new equipment
+    pub fn add(
+        &self,
+        vertipad_id: &str,
+        payload: EquipmentPayload,
+        now: DateTime<Utc>,
+    ) -> Result<Equipment, EquipmentError> {
+        if self.list(vertipad_id).len() >= MAX_EQUIPMENT_PER_VERTIPAD {
+            return Err(EquipmentError::TooMany);
+        }
+
+        let equipment = Equipment {
+            id: Uuid::new_v4().to_string(),
+            vertipad_id: vertipad_id.to_string(),
+            kind: payload.kind,
+            name: payload.name,
+            status: payload.status,
+            last_inspection: payload.last_inspection,
+            created_at: now,
+            updated_at: now,
+        };
+        self.vertipads
+            .update(|vertipads| {
+                let records = vertipads.entry(vertipad_id.to_string()).or_default();
+                match records.len() < MAX_EQUIPMENT_PER_VERTIPAD {
+                    true => {
+                        records.push(equipment.clone());
+                        Ok(equipment)
+                    }
+                    false => Err(EquipmentError::TooMany),
+                }
+            })
+            .map_err(EquipmentError::State)?
+    }
+
+    /// Replace equipment of a vertipad, returning the updated equipment
+    pub fn update(
+        &self,
+        vertipad_id: &str,
+        equipment_id: &str,
+        payload: EquipmentPayload,
+        now: DateTime<Utc>,
+    ) -> Result<Equipment, EquipmentError> {
+        if self.get(vertipad_id, equipment_id).is_none() {
+            return Err(EquipmentError::NotFound);
+        }
+
+        self.vertipads
+            .update(|vertipads| {
+                let equipment = vertipads
+                    .get_mut(vertipad_id)
+                    .and_then(|records| {
+                        records
+                            .iter_mut()
+                            .find(|equipment| equipment.id == equipment_id)
+                    })
+                    .ok_or(EquipmentError::NotFound)?;
+                equipment.kind = payload.kind;
+                equipment.name = payload.name;
+                equipment.status = payload.status;
+                equipment.last_inspection = payload.last_inspection;
+                equipment.updated_at = now;
+                Ok(equipment.clone())
+            })
+            .map_err(EquipmentError::State)?
+    }
+
+    /// Remove equipment of a vertipad
+    pub fn remove(&self, vertipad_id: &str, equipment_id: &str) -> Result<(), EquipmentError> {
+        if self.get(vertipad_id, equipment_id).is_none() {
+            return Err(EquipmentError::NotFound);
+        }
+
+        self.vertipads
+            .update(|vertipads| {
+                let records = vertipads
+                    .get_mut(vertipad_id)
+                    .ok_or(EquipmentError::NotFound)?;
+                let index = records
+                    .iter()
+                    .position(|equipment| equipment.id == equipment_id)
+                    .ok_or(EquipmentError::NotFound)?;
+                records.remove(index);
+                if records.is_empty() {
+                    vertipads.remove(vertipad_id);
+                }
+                Ok(())
+            })
+            .map_err(EquipmentError::State)?
+    }
+
+    /// Remove all equipment of a removed vertipad.
+    ///
+    /// The vertipad is removed even if its equipment could not be written,
+    /// so the error is only logged.
+    pub fn remove_vertipad(&self, vertipad_id: &str) {
+        if let Err(e) = self.vertipads.remove(vertipad_id) {
+            rest_warn!(
+                "could not remove the equipment of vertipad {}: {e}",
+                vertipad_id
+            );
+        }
+    }
+
+    /// Check if lighting of a vertipad failed, in which case the vertipad
+    /// can not be used at night
+    pub fn lighting_failed(&self, vertipad_id: &str) -> bool {
+        self.list(vertipad_id).iter().any(|equipment| {
+            equipment.kind == EquipmentKind::Lighting && equipment.status == EquipmentStatus::Failed
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(kind: EquipmentKind, status: EquipmentStatus) -> EquipmentPayload {
+        EquipmentPayload {
+            kind,
+            name: format!("{kind:?}"),
+            status,
+            last_inspection: None,
+        }
+    }
+
+    #[test]
+    fn test_vertipad_equipment() {
+        let equipment = VertipadEquipment::default();
+        let now = Utc::now();
+        assert!(equipment.list("pad").is_empty());
+
+        let lights = equipment
+            .add(
+                "pad",
+                payload(EquipmentKind::Lighting, EquipmentStatus::Operational),
+                now,
+            )
+            .unwrap();
+        let station = equipment
+            .add(
+                "pad",
+                payload(EquipmentKind::WeatherStation, EquipmentStatus::Degraded),
+                now,
+            )
+            .unwrap();
+        assert_eq!(equipment.list("pad"), vec![lights.clone(), station.clone()]);
+        assert_eq!(equipment.get("pad", &lights.id), Some(lights.clone()));
+        assert_eq!(equipment.get("other", &lights.id), None);
+
+        let later = now + chrono::Duration::minutes(1);
+        let mut changed = payload(EquipmentKind::Lighting, EquipmentStatus::Failed);
+        changed.last_inspection = Some(now);
+        let updated = equipment
+            .update("pad", &lights.id, changed.clone(), later)
+            .unwrap();
+        assert_eq!(updated.status, EquipmentStatus::Failed);
+        assert_eq!(updated.last_inspection, Some(now));
+        assert_eq!(updated.created_at, now);
+        assert_eq!(updated.updated_at, later);
+        assert_eq!(
+            equipment.update("other", &lights.id, changed, now),
+            Err(EquipmentError::NotFound)
+        );
+
+        assert_eq!(equipment.remove("pad", &station.id), Ok(()));
+        assert_eq!(
+            equipment.remove("pad", &station.id),
+            Err(EquipmentError::NotFound)
+        );
+        equipment.remove_vertipad("pad");
+        assert!(equipment.list("pad").is_empty());
+
+        for _ in 0..MAX_EQUIPMENT_PER_VERTIPAD {
+            equipment
+                .add(
+                    "pad",
+                    payload(EquipmentKind::Sensor, Default::default()),
+                    now,
+                )
+                .unwrap();
+        }
+        assert_eq!(
+            equipment.add(
+                "pad",
+                payload(EquipmentKind::Sensor, Default::default()),
+                now
+            ),
+            Err(EquipmentError::TooMany)
+        );
+    }
+
+    #[test]
+    fn test_vertipad_equipment_persisted() {
+        let dir = crate::state::test_state_dir();
+        let now = Utc::now();
+
+        let equipment = VertipadEquipment::open(Some(&dir)).unwrap();
+        let lights = equipment
+            .add(
+                "pad",
+                payload(EquipmentKind::Lighting, EquipmentStatus::Failed),
+                now,
+            )
+            .unwrap();
+        equipment
+            .add(
+                "removed",
+                payload(EquipmentKind::Sensor, EquipmentStatus::Operational),
+                now,
+            )
+            .unwrap();
+        equipment.remove_vertipad("removed");
+
+        // the failed lighting still closes the pad at night after a restart
+        let equipment = VertipadEquipment::open(Some(&dir)).unwrap();
+        assert_eq!(equipment.list("pad"), vec![lights]);
+        assert!(equipment.lighting_failed("pad"));
+        assert!(equipment.list("removed").is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_lighting_failed() {
+        let equipment = VertipadEquipment::default();
+        let now = Utc::now();
+        equipment
+            .add(
+                "dark",
+                payload(EquipmentKind::Lighting, EquipmentStatus::Failed),
+                now,
+            )
+            .unwrap();
+        equipment
+            .add(
+                "dark",
+                payload(EquipmentKind::Lighting, EquipmentStatus::Operational),
+                now,
+            )
+            .unwrap();
+        equipment
+            .add(
+                "lit",
+                payload(EquipmentKind::Lighting, EquipmentStatus::Degraded),
+                now,
+            )
+            .unwrap();
+        equipment
+            .add(
+                "lit",
+                payload(EquipmentKind::Sensor, EquipmentStatus::Failed),
+                now,
+            )
+            .unwrap();
+
+        assert!(equipment.lighting_failed("dark"));
+        assert!(!equipment.lighting_failed("lit"));
+        assert!(!equipment.lighting_failed("none"));
+    }
+}
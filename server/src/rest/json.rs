@@ -0,0 +1,724 @@
+//! JSON request bodies
+//!
+//! [`Json`] replaces [`axum::Json`] for request bodies. The body size is
+//! limited per route (see [`BodyLimit`]) and the nesting depth of the JSON
+//! document is bounded by [`MAX_JSON_DEPTH`], so large or deeply nested
+//! payloads are rejected before they are deserialized. Rejections are
+//! returned as a list of [`FieldError`]s, with the path of the offending field
+//! if the payload does not match the expected type.
+//!
+//! Timestamps must be RFC 3339 timestamps, including the timestamp fields
+//! of the svc-storage payloads (see [`invalid_timestamps`]).
+//!
+//! While the [`Feature::StrictValidation`] flag is enabled, unknown top-level
+//! fields of struct payloads are rejected instead of ignored, so typos in
+//! optional fields do not go unnoticed.
+
+pub use super::api::rest_types::FieldError;
+
+use super::api::rest_types::timestamp;
+use crate::config::SharedConfig;
+use crate::features::{Feature, Features};
+use axum::{
+    async_trait,
+    body::HttpBody,
+    extract::{FromRequest, RequestParts},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    BoxError,
+};
+use hyper::body::Buf;
+use serde::{
+    de::{self, DeserializeOwned, Visitor},
+    forward_to_deserialize_any, Deserializer, Serialize,
+};
+use std::ops::{Deref, DerefMut};
+use utoipa::openapi::schema::{KnownFormat, ObjectBuilder, Schema, SchemaFormat, SchemaType};
+use utoipa::openapi::{OpenApi, RefOr};
+use utoipa::Modify;
+
+/// Maximum nesting depth of objects and arrays in a JSON request body
+pub const MAX_JSON_DEPTH: usize = 32;
+
+/// Error returned when a JSON request body is rejected
+pub type JsonRejection = (StatusCode, Json<Vec<FieldError>>);
+
+/// Size class of the request body of a route.
+///
+/// Added to a route with `.layer(Extension(BodyLimit::Bulk))`, routes
+/// without a [`BodyLimit`] use [`BodyLimit::Standard`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BodyLimit {
+    /// Single asset payloads, limited by `rest_max_body_bytes`
+    #[default]
+    Standard,
+    /// Bulk and import payloads, limited by `rest_max_bulk_body_bytes`
+    Bulk,
+}
+
+/// Configured body size limits, read for each request so they can be
+/// changed at runtime
+#[derive(Debug, Clone)]
+pub struct BodyLimits {
+    config: SharedConfig,
+}
+
+impl BodyLimits {
+    /// Create new [`BodyLimits`] using the provided configuration
+    pub fn new(config: SharedConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get the maximum body size in bytes for the provided [`BodyLimit`]
+    pub fn max_bytes(&self, limit: BodyLimit) -> usize {
+        self.config.read(|config| match limit {
+            BodyLimit::Standard => config.rest_max_body_bytes,
+            BodyLimit::Bulk => config.rest_max_bulk_body_bytes,
+        })
+    }
+}
+
+/// JSON extractor and response.
+///
+/// Behaves like [`axum::Json`], but applies the [`BodyLimit`] of the route
+/// and returns [`FieldError`]s instead of plain text rejections:
+/// * `413 PAYLOAD_TOO_LARGE` if the body exceeds the limit
+/// * `415 UNSUPPORTED_MEDIA_TYPE` if the content type is not JSON
+/// * `422 UNPROCESSABLE_ENTITY` if the body is malformed, nested too deeply or
+///   does not match the expected type
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Json<T> {
+    fn from(inner: T) -> Self {
+        Self(inner)
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+/// Create a rejection for the whole body
+fn body_error(status: StatusCode, message: &str) -> JsonRejection {
+    (
+        status,
+        Json(vec![FieldError {
+            field: "body".to_string(),
+            message: message.to_string(),
+        }]),
+    )
+}
+
+/// Check if the content type is `application/json` or a `+json` type like
+/// `application/merge-patch+json`
+fn is_json_content_type(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|mime| {
+            let mime = mime.trim().to_lowercase();
+            mime == "application/json"
+                || (mime.starts_with("application/") && mime.ends_with("+json"))
+        })
+        .unwrap_or(false)
+}
+
+/// Get the maximum nesting depth of objects and arrays in a JSON document.
+///
+/// Brackets within strings are ignored. The document is not validated.
+pub fn json_depth(bytes: &[u8]) -> usize {
+    let (mut depth, mut max_depth) = (0_usize, 0_usize);
+    let (mut in_string, mut escaped) = (false, false);
+
+    for byte in bytes {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                max_depth = max_depth.max(depth);
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+    }
+
+    max_depth
+}
+
+/// Deserialize a JSON document, returning the path of the offending field
+/// if the document does not match the expected type
+pub fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, FieldError> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+        let path = e.path().to_string();
+        let field = match e.inner().is_data() && path != "." {
+            true => path,
+            false => "body".to_string(),
+        };
+        FieldError {
+            field,
+            message: e.into_inner().to_string(),
+        }
+    })?;
+
+    deserializer.end().map_err(|e| FieldError {
+        field: "body".to_string(),
+        message: e.to_string(),
+    })?;
+
+    Ok(value)
+}
+
+/// Deserializer recording the field names of the struct deserialized with
+/// it, every deserialization fails
+struct FieldProbe<'a>(&'a mut Option<&'static [&'static str]>);
+
+impl<'de> Deserializer<'de> for FieldProbe<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = Some(fields);
+        Err(de::Error::custom("probed"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Get the field names (including aliases) of `T` if it is deserialized as
+/// a struct.
+///
+/// Returns `None` for other types, and for structs with flattened fields
+/// which are deserialized as maps.
+pub fn struct_fields<T: DeserializeOwned>() -> Option<&'static [&'static str]> {
+    let mut fields = None;
+    let _ = T::deserialize(FieldProbe(&mut fields));
+    fields
+}
+
+/// Get the top-level fields of a JSON object which are not fields of `T`.
+///
+/// Returns no fields if `T` is not a struct or the document is not an
+/// object.
+pub fn unknown_fields<T: DeserializeOwned>(bytes: &[u8]) -> Vec<String> {
+    let Some(fields) = struct_fields::<T>() else {
+        return vec![];
+    };
+    let Ok(serde_json::Value::Object(object)) = serde_json::from_slice(bytes) else {
+        return vec![];
+    };
+
+    object
+        .keys()
+        .filter(|key| !fields.contains(&key.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Timestamp fields of the svc-storage payloads, like [`vehicle::Data`].
+///
+/// Their `Timestamp` type parses any string chrono accepts, so the fields
+/// are checked to be RFC 3339 timestamps like the fields of the REST types.
+///
+/// [`vehicle::Data`]: svc_storage_client_grpc::prelude::vehicle::Data
+const STORAGE_TIMESTAMP_FIELDS: &[&str] = &[
+    "created_at",
+    "updated_at",
+    "last_maintenance",
+    "next_maintenance",
+];
+
+/// Get the errors of the top-level timestamp fields of `T` which are not
+/// RFC 3339 timestamps.
+///
+/// Returns no errors if `T` is not a struct or the document is not an
+/// object.
+pub fn invalid_timestamps<T: DeserializeOwned>(bytes: &[u8]) -> Vec<FieldError> {
+    let Some(fields) = struct_fields::<T>() else {
+        return vec![];
+    };
+    let Ok(serde_json::Value::Object(object)) = serde_json::from_slice(bytes) else {
+        return vec![];
+    };
+
+    STORAGE_TIMESTAMP_FIELDS
+        .iter()
+        .filter(|field| fields.contains(field))
+        .filter_map(|field| match object.get(*field) {
+            Some(serde_json::Value::String(value)) => {
+                timestamp::parse(value).err().map(|message| FieldError {
+                    field: field.to_string(),
+                    message,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Documents the timestamps of the OpenAPI specification as RFC 3339
+/// date-times with an example, including the timestamp fields of the
+/// svc-storage payloads.
+#[derive(Debug, Clone, Copy)]
+pub struct TimestampSchemas;
+
+impl Modify for TimestampSchemas {
+    fn modify(&self, openapi: &mut OpenApi) {
+        let Some(components) = openapi.components.as_mut() else {
+            return;
+        };
+
+        let example = || Some(serde_json::json!(timestamp::EXAMPLE));
+        for schema in components.schemas.values_mut() {
+            let RefOr::T(Schema::Object(object)) = schema else {
+                continue;
+            };
+
+            for (name, property) in object.properties.iter_mut() {
+                match property {
+                    RefOr::T(Schema::Object(property))
+                        if matches!(
+                            property.format,
+                            Some(SchemaFormat::KnownFormat(KnownFormat::DateTime))
+                        ) =>
+                    {
+                        property.example = example();
+                    }
+                    _ if STORAGE_TIMESTAMP_FIELDS.contains(&name.as_str()) => {
+                        *property = ObjectBuilder::new()
+                            .schema_type(SchemaType::String)
+                            .format(Some(SchemaFormat::KnownFormat(KnownFormat::DateTime)))
+                            .nullable(true)
+                            .description(Some("RFC 3339 timestamp"))
+                            .example(example())
+                            .into();
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T, B> FromRequest<B> for Json<T>
+where
+    T: DeserializeOwned,
+    B: HttpBody + Send,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    type Rejection = JsonRejection;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        if !is_json_content_type(req.headers()) {
+            rest_warn!("request body is not JSON.");
+            return Err(body_error(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "expected a request with `Content-Type: application/json`",
+            ));
+        }
+
+        let limit = req
+            .extensions()
+            .get::<BodyLimit>()
+            .copied()
+            .unwrap_or_default();
+        let max_bytes = req
+            .extensions()
+            .get::<BodyLimits>()
+            .map(|limits| limits.max_bytes(limit))
+            .ok_or_else(|| {
+                rest_error!("BodyLimits extension missing.");
+                body_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "body limits not configured",
+                )
+            })?;
+        let too_large = || {
+            rest_warn!("request body exceeds {} bytes.", max_bytes);
+            body_error(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("request body exceeds the limit of {} bytes", max_bytes),
+            )
+        };
+
+        let content_length = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<usize>().ok());
+        if content_length
+            .map(|length| length > max_bytes)
+            .unwrap_or(false)
+        {
+            return Err(too_large());
+        }
+
+        let body = req.take_body().ok_or_else(|| {
+            rest_error!("request body already extracted.");
+            body_error(StatusCode::INTERNAL_SERVER_ERROR, "body already extracted")
+        })?;
+
+        // Read the body in chunks, so a body without a (correct) content
+        // length can not exceed the limit either
+        let mut body = Box::pin(body);
+        let mut bytes: Vec<u8> = vec![];
+        while let Some(chunk) = body.data().await {
+            let mut chunk = chunk.map_err(|e| {
+                let e: BoxError = e.into();
+                rest_warn!("could not read request body: {e}");
+                body_error(StatusCode::BAD_REQUEST, "could not read request body")
+            })?;
+
+            if bytes.len() + chunk.remaining() > max_bytes {
+                return Err(too_large());
+            }
+
+            while chunk.has_remaining() {
+                let part = chunk.chunk();
+                bytes.extend_from_slice(part);
+                let len = part.len();
+                chunk.advance(len);
+            }
+        }
+
+        if json_depth(&bytes) > MAX_JSON_DEPTH {
+            rest_warn!("request body nested too deeply.");
+            return Err(body_error(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                &format!("JSON nesting exceeds the maximum depth of {MAX_JSON_DEPTH}"),
+            ));
+        }
+
+        let value = deserialize(&bytes).map_err(|error| {
+            rest_warn!("invalid request body: {} ({}).", error.message, error.field);
+            (StatusCode::UNPROCESSABLE_ENTITY, Json(vec![error]))
+        })?;
+
+        let errors = invalid_timestamps::<T>(&bytes);
+        if !errors.is_empty() {
+            rest_warn!("request body has {} invalid timestamp(s).", errors.len());
+            return Err((StatusCode::UNPROCESSABLE_ENTITY, Json(errors)));
+        }
+
+        let strict = req
+            .extensions()
+            .get::<Features>()
+            .map(|features| features.is_enabled(Feature::StrictValidation))
+            .unwrap_or(false);
+        if strict {
+            let errors: Vec<FieldError> = unknown_fields::<T>(&bytes)
+                .into_iter()
+                .map(|field| FieldError {
+                    field,
+                    message: "unknown field".to_string(),
+                })
+                .collect();
+            if !errors.is_empty() {
+                rest_warn!("request body has {} unknown field(s).", errors.len());
+                return Err((StatusCode::UNPROCESSABLE_ENTITY, Json(errors)));
+            }
+        }
+
+        Ok(Json(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::AircraftDataBuilder;
+    use crate::Config;
+    use axum::{body::Body, http::Request};
+    use serde::Deserialize;
+    use svc_storage_client_grpc::prelude::vehicle;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Payload {
+        name: String,
+        location: Location,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Location {
+        latitude: f64,
+        longitude: f64,
+    }
+
+    async fn extract(
+        body: &str,
+        content_type: &str,
+        limit: BodyLimit,
+    ) -> Result<Json<Payload>, JsonRejection> {
+        let mut config = Config::default();
+        config.rest_max_body_bytes = 64;
+        config.rest_max_bulk_body_bytes = 1024;
+
+        let mut request = Request::post("/")
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(BodyLimits::new(config.into()));
+        request.extensions_mut().insert(limit);
+
+        Json::<Payload>::from_request(&mut RequestParts::new(request)).await
+    }
+
+    #[test]
+    fn test_json_depth() {
+        assert_eq!(json_depth(b"1"), 0);
+        assert_eq!(json_depth(br#"{"a": [1, {"b": []}]}"#), 4);
+        assert_eq!(json_depth(br#"{"a": "[[[{{\"]]"}"#), 1);
+        assert_eq!(json_depth(&b"[".repeat(100)), 100);
+    }
+
+    #[test]
+    fn test_is_json_content_type() {
+        let headers = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::CONTENT_TYPE, value.parse().unwrap());
+            headers
+        };
+        assert!(is_json_content_type(&headers("application/json")));
+        assert!(is_json_content_type(&headers(
+            "application/json; charset=utf-8"
+        )));
+        assert!(is_json_content_type(&headers(
+            "application/merge-patch+json"
+        )));
+        assert!(!is_json_content_type(&headers("text/plain")));
+        assert!(!is_json_content_type(&HeaderMap::new()));
+    }
+
+    #[tokio::test]
+    async fn test_json_extract() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let valid = r#"{"name":"a","location":{"latitude":1,"longitude":2}}"#;
+        let Json(payload) = extract(valid, "application/json", BodyLimit::Standard)
+            .await
+            .unwrap();
+        assert_eq!(payload.name, "a");
+
+        // field context for type errors
+        let (status, Json(errors)) = extract(
+            r#"{"name":"a","location":{"latitude":"north","longitude":2}}"#,
+            "application/json",
+            BodyLimit::Bulk,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "location.latitude");
+
+        // malformed
+        let (status, Json(errors)) =
+            extract(r#"{"name":"#, "application/json", BodyLimit::Standard)
+                .await
+                .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].field, "body");
+
+        // too deep
+        let deep = format!(
+            "{}{}",
+            "[".repeat(MAX_JSON_DEPTH + 1),
+            "]".repeat(MAX_JSON_DEPTH + 1)
+        );
+        let (status, _) = extract(&deep, "application/json", BodyLimit::Bulk)
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // standard limit exceeded, bulk limit is not
+        let large = format!(
+            r#"{{"name":"{}","location":{{"latitude":1,"longitude":2}}}}"#,
+            "a".repeat(100)
+        );
+        let (status, _) = extract(&large, "application/json", BodyLimit::Standard)
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(extract(&large, "application/json", BodyLimit::Bulk)
+            .await
+            .is_ok());
+
+        let (status, _) = extract(valid, "text/plain", BodyLimit::Standard)
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        ut_info!("success");
+    }
+
+    #[test]
+    fn test_struct_fields() {
+        assert_eq!(struct_fields::<Payload>(), Some(&["name", "location"][..]));
+        assert_eq!(struct_fields::<serde_json::Value>(), None);
+        assert_eq!(struct_fields::<Vec<Location>>(), None);
+
+        assert_eq!(
+            unknown_fields::<Location>(br#"{"latitude":1,"longitude":2,"altitude":3}"#),
+            vec!["altitude".to_string()]
+        );
+        assert!(unknown_fields::<Location>(b"[]").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_json_strict_validation() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let body = r#"{"name":"a","nmae":"b","location":{"latitude":1,"longitude":2}}"#;
+        let request = |strict: bool| {
+            let mut config = Config::default();
+            config.strict_validation = strict;
+            let config = SharedConfig::from(config);
+            let mut request = Request::post("/")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .unwrap();
+            request
+                .extensions_mut()
+                .insert(BodyLimits::new(config.clone()));
+            request.extensions_mut().insert(Features::new(config));
+            RequestParts::new(request)
+        };
+
+        assert!(Json::<Payload>::from_request(&mut request(false))
+            .await
+            .is_ok());
+
+        let (status, Json(errors)) = Json::<Payload>::from_request(&mut request(true))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "nmae");
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_json_storage_timestamps() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let mut data = serde_json::to_value(AircraftDataBuilder::new().build()).unwrap();
+        data["last_maintenance"] = serde_json::json!("2024-01-01T12:00:00Z");
+        data["next_maintenance"] = serde_json::json!("2024-07-01T12:00:00+02:00");
+        assert!(invalid_timestamps::<vehicle::Data>(data.to_string().as_bytes()).is_empty());
+        // timestamps of other types are not checked
+        assert!(invalid_timestamps::<Payload>(data.to_string().as_bytes()).is_empty());
+
+        data["next_maintenance"] = serde_json::json!("2024-07-01");
+        let errors = invalid_timestamps::<vehicle::Data>(data.to_string().as_bytes());
+        let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+        assert_eq!(fields, vec!["next_maintenance"]);
+        assert!(errors[0]
+            .message
+            .starts_with("invalid RFC 3339 timestamp '2024-07-01'"));
+
+        let mut request = Request::post("/")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(data.to_string()))
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(BodyLimits::new(Config::default().into()));
+        let (status, Json(errors)) =
+            Json::<vehicle::Data>::from_request(&mut RequestParts::new(request))
+                .await
+                .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "next_maintenance");
+
+        ut_info!("success");
+    }
+
+    #[test]
+    fn test_timestamp_schemas() {
+        use utoipa::OpenApi;
+
+        let spec = serde_json::to_value(crate::rest::ApiDoc::openapi()).unwrap();
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+        let mut checked = 0;
+        for schema in schemas.values() {
+            let Some(properties) = schema["properties"].as_object() else {
+                continue;
+            };
+            for (name, property) in properties {
+                if STORAGE_TIMESTAMP_FIELDS.contains(&name.as_str())
+                    || property["format"] == "date-time"
+                {
+                    assert_eq!(property["type"], "string", "{name}");
+                    assert_eq!(property["format"], "date-time", "{name}");
+                    assert_eq!(property["example"], timestamp::EXAMPLE, "{name}");
+                    checked += 1;
+                }
+            }
+        }
+        assert!(checked > 0);
+        assert_eq!(
+            schemas["MaintenanceHoldPayload"]["properties"]["start"]["example"],
+            timestamp::EXAMPLE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_json_missing_limits() {
+        let request = Request::post("/")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from("{}"))
+            .unwrap();
+        let (status, _) = Json::<serde_json::Value>::from_request(&mut RequestParts::new(request))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
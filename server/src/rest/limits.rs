@@ -0,0 +1,226 @@
+//! Request rate and concurrency limiting
+//!
+//! Unlike the static tower limit layers, the limits are read from the
+//! [`SharedConfig`] for each request so they can be changed at runtime.
+
+use crate::config::SharedConfig;
+use axum::{
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tokio::time::{Duration, Instant};
+
+/// Maximum number of requests waiting for a free slot before new requests are rejected
+const MAX_QUEUED_REQUESTS: usize = 100;
+
+/// Interval at which waiting requests re-check the (possibly reloaded) limits
+const RECHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Length of the rate limit window
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+struct RateWindow {
+    start: Instant,
+    count: u64,
+}
+
+/// Rate and concurrency limiter using the limits of the [`SharedConfig`]
+#[derive(Debug, Clone)]
+pub struct RequestLimiter {
+    config: SharedConfig,
+    window: Arc<Mutex<RateWindow>>,
+    in_flight: Arc<AtomicUsize>,
+    queued: Arc<AtomicUsize>,
+    released: Arc<Notify>,
+}
+
+/// Decrements the provided counter when dropped
+#[derive(Debug)]
+struct CounterGuard {
+    counter: Arc<AtomicUsize>,
+    released: Option<Arc<Notify>>,
+}
+
+impl Drop for CounterGuard {
+    fn drop(&mut self) {
+        self.counter.fetch_sub(1, Ordering::SeqCst);
+        if let Some(released) = &self.released {
+            released.notify_waiters();
+        }
+    }
+}
+
+impl RequestLimiter {
+    /// Create a new limiter for the provided configuration
+    pub fn new(config: SharedConfig) -> Self {
+        Self {
+            config,
+            window: Arc::new(Mutex::new(RateWindow {
+                start: Instant::now(),
+                count: 0,
+            })),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            queued: Arc::new(AtomicUsize::new(0)),
+            released: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Middleware function limiting the incoming requests.
+    ///
+    /// Requests exceeding the limits are delayed until they are allowed.
+    /// Returns `TOO_MANY_REQUESTS` if too many requests are already waiting.
+    pub async fn limit<B>(self, req: Request<B>, next: Next<B>) -> Response {
+        if self.queued.fetch_add(1, Ordering::SeqCst) >= MAX_QUEUED_REQUESTS {
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+            rest_warn!("too many requests: queue full.");
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                "(server) too many requests.".to_string(),
+            )
+                .into_response();
+        }
+        let queued = CounterGuard {
+            counter: self.queued.clone(),
+            released: None,
+        };
+
+        self.acquire_rate().await;
+        let _slot = self.acquire_slot().await;
+        drop(queued);
+
+        next.run(req).await
+    }
+
+    /// Wait until the request fits within the configured requests per second
+    async fn acquire_rate(&self) {
+        loop {
+            let limit = self
+                .config
+                .read(|config| config.rest_request_limit_per_second as u64)
+                .max(1);
+
+            let wait = {
+                let mut window = match self.window.lock() {
+                    Ok(window) => window,
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+
+                let now = Instant::now();
+                if now.duration_since(window.start) >= RATE_WINDOW {
+                    window.start = now;
+                    window.count = 0;
+                }
+
+                if window.count < limit {
+                    window.count += 1;
+                    None
+                } else {
+                    Some(window.start + RATE_WINDOW - now)
+                }
+            };
+
+            match wait {
+                None => return,
+                Some(wait) => tokio::time::sleep(wait.min(RECHECK_INTERVAL)).await,
+            }
+        }
+    }
+
+    /// Wait until the number of requests in flight is below the configured limit
+    async fn acquire_slot(&self) -> CounterGuard {
+        loop {
+            // Register for notifications before checking to not miss any releases
+            let released = self.released.notified();
+
+            let limit = self
+                .config
+                .read(|config| config.rest_concurrency_limit_per_service as usize)
+                .max(1);
+
+            let current = self.in_flight.load(Ordering::SeqCst);
+            if current < limit {
+                if self
+                    .in_flight
+                    .compare_exchange(current, current + 1, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_ok()
+                {
+                    return CounterGuard {
+                        counter: self.in_flight.clone(),
+                        released: Some(self.released.clone()),
+                    };
+                }
+                continue;
+            }
+
+            // Time out to pick up limit changes from a configuration reload
+            let _ = tokio::time::timeout(RECHECK_INTERVAL, released).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    fn shared_config(rate: u8, concurrency: u8) -> SharedConfig {
+        let mut config = Config::default();
+        config.rest_request_limit_per_second = rate;
+        config.rest_concurrency_limit_per_service = concurrency;
+        SharedConfig::from(config)
+    }
+
+    #[tokio::test]
+    async fn test_acquire_rate() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let limiter = RequestLimiter::new(shared_config(2, 5));
+        let start = Instant::now();
+        limiter.acquire_rate().await;
+        limiter.acquire_rate().await;
+        assert!(start.elapsed() < RATE_WINDOW);
+
+        // Third request must wait for the next window
+        limiter.acquire_rate().await;
+        assert!(start.elapsed() >= RATE_WINDOW);
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_acquire_slot_reloaded_limit() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let config = shared_config(10, 1);
+        let limiter = RequestLimiter::new(config.clone());
+
+        let first = limiter.acquire_slot().await;
+        assert_eq!(limiter.in_flight.load(Ordering::SeqCst), 1);
+
+        // Second slot is not available with the current limit
+        let result = tokio::time::timeout(Duration::from_millis(300), limiter.acquire_slot()).await;
+        assert!(result.is_err());
+
+        // Raising the limit at runtime makes a new slot available
+        let mut new = config.get();
+        new.rest_concurrency_limit_per_service = 2;
+        config.apply(new);
+        let second = tokio::time::timeout(Duration::from_millis(300), limiter.acquire_slot())
+            .await
+            .expect("slot should be available after reload");
+        assert_eq!(limiter.in_flight.load(Ordering::SeqCst), 2);
+
+        drop(first);
+        drop(second);
+        assert_eq!(limiter.in_flight.load(Ordering::SeqCst), 0);
+
+        ut_info!("success");
+    }
+}
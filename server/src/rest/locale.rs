@@ -0,0 +1,250 @@
+//! Localized asset names and descriptions
+//!
+//! svc-storage has no metadata fields for vertiports and vertipads yet, so
+//! localizations are kept in the [`LOCALIZATIONS_TABLE`] of the persisted
+//! state, see [`crate::state`]. Responses are localized using the
+//! `Accept-Language` request header. The localizations are not shared
+//! between replicas, in which case the stored names and descriptions are
+//! returned.
+
+pub use super::api::rest_types::AssetLocalization;
+
+use super::api::validation::{field_error, into_result, FieldError};
+use crate::state::{StateError, StateTable};
+use axum::http::{header::ACCEPT_LANGUAGE, HeaderMap};
+use std::collections::HashMap;
+
+/// Name of the table of the localizations in the state directory
+pub const LOCALIZATIONS_TABLE: &str = "localizations";
+
+/// Maximum length of a locale (BCP 47 language tag)
+const MAX_LOCALE_LEN: usize = 35;
+
+/// Store of the localizations per asset id.
+///
+/// The default store is kept in memory, [`Localizations::open`] persists
+/// the localizations.
+#[derive(Debug, Clone, Default)]
+pub struct Localizations {
+    assets: StateTable<AssetLocalization>,
+}
+
+impl Localizations {
+    /// Open the localizations persisted in the state directory, kept in
+    /// memory if no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            assets: StateTable::open(dir, LOCALIZATIONS_TABLE)?,
+        })
+    }
+
+    /// Get the localizations of an asset
+    pub fn get(&self, asset_id: &str) -> Option<AssetLocalization> {
+        self.assets.get(asset_id)
+    }
+
+    /// Replace the localizations of an asset
+    pub fn set(&self, asset_id: &str, localization: AssetLocalization) -> Result<(), StateError> {
+        self.assets.insert(asset_id, localization).map(|_| ())
+    }
+
+    /// Remove the localizations of a removed asset.
+    ///
+    /// The asset is removed even if its localizations could not be written,
+    /// so the error is only logged.
+    pub fn remove(&self, asset_id: &str) {
+        if let Err(e) = self.assets.remove(asset_id) {
+            rest_warn!(
+                "could not remove the localizations of asset {}: {e}",
+                asset_id
+            );
+        }
+    }
+}
+
+/// Check if a locale looks like a BCP 47 language tag (for example `de` or `ja-JP`).
+fn is_valid_locale(locale: &str) -> bool {
+    !locale.is_empty()
+        && locale.len() <= MAX_LOCALE_LEN
+        && locale
+            .split('-')
+            .all(|tag| !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Validate and normalize an [`AssetLocalization`].
+///
+/// Locales are converted to lowercase, values are trimmed.
+pub fn validate_localization(
+    localization: AssetLocalization,
+) -> Result<AssetLocalization, Vec<FieldError>> {
+    let mut errors = vec![];
+    let mut normalize = |field: &str, values: HashMap<String, String>| {
+        values
+            .into_iter()
+            .filter_map(|(locale, value)| {
+                let value = value.trim().to_string();
+                if !is_valid_locale(&locale) {
+                    errors.push(field_error(
+                        &format!("{field}.{locale}"),
+                        "must be a language tag like 'de' or 'ja-JP'",
+                    ));
+                    return None;
+                }
+
+                if value.is_empty() {
+                    errors.push(field_error(
+                        &format!("{field}.{locale}"),
+                        "can not be empty",
+                    ));
+                    return None;
+                }
+
+                Some((locale.to_lowercase(), value))
+            })
+            .collect()
+    };
+
+    let names = normalize("names", localization.names);
+    let descriptions = normalize("descriptions", localization.descriptions);
+
+    into_result(
+        AssetLocalization {
+            names,
+            descriptions,
+        },
+        errors,
+    )
+}
+
+/// Get the languages accepted by the client, most preferred first.
+///
+/// Languages are converted to lowercase. Wildcards and languages with a
+/// quality of `0` are ignored.
+pub fn accept_languages(headers: &HeaderMap) -> Vec<String> {
+    let Some(header) = headers
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return vec![];
+    };
+
+    let mut languages: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let language = parts.next()?.trim().to_lowercase();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+                .unwrap_or(1.0);
+
+            (is_valid_locale(&language) && quality > 0.0).then_some((language, quality))
+        })
+        .collect();
+
+    // stable sort keeps the header order for equal qualities
+    languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+    languages
+        .into_iter()
+        .map(|(language, _)| language)
+        .collect()
+}
+
+/// Get the value for the most preferred language.
+///
+/// An exact match is preferred, otherwise a value with the same primary
+/// language (`de` for `de-CH`) is used.
+pub fn localized<'a>(values: &'a HashMap<String, String>, languages: &[String]) -> Option<&'a str> {
+    let primary = |locale: &str| locale.split('-').next().unwrap_or_default().to_string();
+
+    languages.iter().find_map(|language| {
+        values.get(language).map(String::as_str).or_else(|| {
+            let language = primary(language);
+            let mut matches: Vec<(&String, &String)> = values
+                .iter()
+                .filter(|(locale, _)| primary(locale) == language)
+                .collect();
+            // prefer the shortest (most generic) locale for a deterministic result
+            matches.sort_by(|a, b| a.0.len().cmp(&b.0.len()).then(a.0.cmp(b.0)));
+            matches.first().map(|(_, value)| value.as_str())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> HashMap<String, String> {
+        HashMap::from([
+            ("de".to_string(), "Hauptbahnhof".to_string()),
+            ("ja-jp".to_string(), "中央駅".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_accept_languages() {
+        let mut headers = HeaderMap::new();
+        assert!(accept_languages(&headers).is_empty());
+
+        headers.insert(
+            ACCEPT_LANGUAGE,
+            "en;q=0.5, ja-JP, de;q=0.8, *;q=0.1, fr;q=0"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(accept_languages(&headers), vec!["ja-jp", "de", "en"]);
+    }
+
+    #[test]
+    fn test_localized() {
+        let values = values();
+        assert_eq!(
+            localized(&values, &["de-ch".to_string()]),
+            Some("Hauptbahnhof")
+        );
+        assert_eq!(localized(&values, &["ja".to_string()]), Some("中央駅"));
+        assert_eq!(
+            localized(&values, &["fr".to_string(), "ja-jp".to_string()]),
+            Some("中央駅")
+        );
+        assert_eq!(localized(&values, &["fr".to_string()]), None);
+    }
+
+    #[test]
+    fn test_validate_localization() {
+        let localization = validate_localization(AssetLocalization {
+            names: HashMap::from([("ja-JP".to_string(), " 中央駅 ".to_string())]),
+            descriptions: HashMap::new(),
+        })
+        .unwrap();
+        assert_eq!(localization.names["ja-jp"], "中央駅");
+
+        let errors = validate_localization(AssetLocalization {
+            names: HashMap::from([("not a locale".to_string(), "name".to_string())]),
+            descriptions: HashMap::from([("de".to_string(), " ".to_string())]),
+        })
+        .unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_localizations_persisted() {
+        let dir = crate::state::test_state_dir();
+        let localization = AssetLocalization {
+            names: values(),
+            descriptions: HashMap::new(),
+        };
+
+        let localizations = Localizations::open(Some(&dir)).unwrap();
+        localizations.set("port", localization.clone()).unwrap();
+        localizations.set("removed", localization.clone()).unwrap();
+        localizations.remove("removed");
+
+        let localizations = Localizations::open(Some(&dir)).unwrap();
+        assert_eq!(localizations.get("port"), Some(localization));
+        assert_eq!(localizations.get("removed"), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
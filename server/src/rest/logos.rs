@@ -0,0 +1,413 @@
+//! Operator logos
+//!
+//! Operators upload their logo as a PNG or JPEG image, which is served to
+//! the UIs as uploaded and in resized variants (see [`LogoSize`]). The
+//! uploaded file and the variants are stored in the configured
+//! [`ObjectStore`] under `operator-logos/{operator_id}/{sha256}/{size}`, so a
+//! replaced logo never mixes with the variants of its successor.
+//!
+//! svc-storage does not store operators yet, so the logo metadata is kept in
+//! memory by this service instance.
+
+pub use super::api::rest_types::{LogoSize, OperatorLogo};
+
+use crate::object_store::memory::MemoryObjectStore;
+use crate::object_store::s3::hex;
+use crate::object_store::{ObjectStore, ObjectStoreError};
+use image::{GenericImageView, ImageFormat, ImageOutputFormat};
+use lib_common::time::Utc;
+use openssl::sha::sha256;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Maximum size in bytes of an uploaded logo
+pub const MAX_LOGO_BYTES: usize = 1024 * 1024;
+
+/// Maximum width and height in pixels of an uploaded logo
+pub const MAX_LOGO_PIXELS: u32 = 4096;
+
+/// Content type of the resized variants
+pub const VARIANT_CONTENT_TYPE: &str = "image/png";
+
+/// Variants stored next to the uploaded file
+const VARIANTS: [LogoSize; 2] = [LogoSize::Medium, LogoSize::Small];
+
+/// Errors returned when changing or getting the logo of an operator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogoError {
+    /// The operator has no logo
+    NotFound,
+    /// The content type is not `image/png` or `image/jpeg`
+    UnsupportedType,
+    /// The file is not a valid image of its content type or exceeds
+    /// [`MAX_LOGO_PIXELS`]
+    Invalid(String),
+    /// The object store returned an error
+    Store(ObjectStoreError),
+}
+
+/// Get the width and height in pixels the variant fits in, `None` for the
+/// uploaded file
+pub fn variant_pixels(size: LogoSize) -> Option<u32> {
+    match size {
+        LogoSize::Original => None,
+        LogoSize::Medium => Some(256),
+        LogoSize::Small => Some(64),
+    }
+}
+
+/// Get the image format of a supported logo content type.
+pub fn logo_format(content_type: &str) -> Option<ImageFormat> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+
+    match mime.as_str() {
+        "image/png" => Some(ImageFormat::Png),
+        "image/jpeg" => Some(ImageFormat::Jpeg),
+        _ => None,
+    }
+}
+
+/// Get the name of a variant used in object keys and ETags
+fn size_name(size: LogoSize) -> &'static str {
+    match size {
+        LogoSize::Original => "original",
+        LogoSize::Medium => "medium",
+        LogoSize::Small => "small",
+    }
+}
+
+/// Get the object store key of a logo variant
+fn object_key(operator_id: &str, sha256: &str, size: LogoSize) -> String {
+    format!("operator-logos/{operator_id}/{sha256}/{}", size_name(size))
+}
+
+/// Get the ETag of a logo variant
+pub fn logo_etag(logo: &OperatorLogo, size: LogoSize) -> String {
+    format!("\"{}-{}\"", logo.sha256, size_name(size))
+}
+
+/// An uploaded logo checked and resized by [`process_logo`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessedLogo {
+    /// Width of the uploaded image in pixels
+    pub width: u32,
+    /// Height of the uploaded image in pixels
+    pub height: u32,
+    /// Hex encoded SHA-256 hash of the uploaded file
+    pub sha256: String,
+    /// PNG encoded variants, see [`VARIANTS`]
+    pub variants: Vec<(LogoSize, Vec<u8>)>,
+}
+
+/// Check an uploaded logo and create its resized variants.
+///
+/// The content has to match the format of the content type, and the
+/// dimensions are checked before the image is decoded. Images smaller than a
+/// variant are not enlarged.
+pub fn process_logo(body: &[u8], format: ImageFormat) -> Result<ProcessedLogo, LogoError> {
+    match image::guess_format(body) {
+        Ok(guessed) if guessed == format => (),
+        _ => {
+            return Err(LogoError::Invalid(format!(
+                "content is not a {format:?} image"
+            )))
+        }
+    }
+
+    let (width, height) = image::io::Reader::with_format(Cursor::new(body), format)
+        .into_dimensions()
+        .map_err(|e| LogoError::Invalid(format!("could not read dimensions: {e}")))?;
+    if width > MAX_LOGO_PIXELS || height > MAX_LOGO_PIXELS {
+        return Err(LogoError::Invalid(format!(
+            "{width}x{height} exceeds {MAX_LOGO_PIXELS}x{MAX_LOGO_PIXELS} pixels"
+        )));
+    }
+
+    let image = image::load_from_memory_with_format(body, format)
+        .map_err(|e| LogoError::Invalid(format!("could not decode image: {e}")))?;
+
+    let mut variants = vec![];
+    for size in VARIANTS {
+        let pixels = variant_pixels(size).unwrap_or(MAX_LOGO_PIXELS);
+        let resized = match width > pixels || height > pixels {
+            true => image.thumbnail(pixels, pixels),
+            false => image.clone(),
+        };
+
+        let mut content = Cursor::new(vec![]);
+        resized
+            .write_to(&mut content, ImageOutputFormat::Png)
+            .map_err(|e| LogoError::Invalid(format!("could not encode variant: {e}")))?;
+        variants.push((size, content.into_inner()));
+    }
+
+    Ok(ProcessedLogo {
+        width,
+        height,
+        sha256: hex(&sha256(body)),
+        variants,
+    })
+}
+
+/// Store of the logo per operator id
+#[derive(Clone)]
+pub struct OperatorLogos {
+    logos: Arc<RwLock<HashMap<String, OperatorLogo>>>,
+    objects: Arc<dyn ObjectStore>,
+}
+
+impl Default for OperatorLogos {
+    fn default() -> Self {
+        Self::new(Arc::new(MemoryObjectStore::default()))
+    }
+}
+
+impl std::fmt::Debug for OperatorLogos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OperatorLogos").finish_non_exhaustive()
+    }
+}
+
+impl OperatorLogos {
+    /// Create a new store, keeping the logo files in the provided object
+    /// store
+    pub fn new(objects: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            logos: Arc::new(RwLock::new(HashMap::new())),
+            objects,
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, OperatorLogo>> {
+        self.logos
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, OperatorLogo>> {
+        self.logos
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Get the logo of an operator
+    pub fn get(&self, operator_id: &str) -> Result<OperatorLogo, LogoError> {
+        self.read()
+            .get(operator_id)
+            .cloned()
+            .ok_or(LogoError::NotFound)
+    }
+
+    /// Remove the files of a logo, logging failures
+    async fn delete_objects(&self, operator_id: &str, sha256: &str) {
+        for size in [LogoSize::Original, LogoSize::Medium, LogoSize::Small] {
+            let key = object_key(operator_id, sha256, size);
+            match self.objects.delete(&key).await {
+                Ok(()) | Err(ObjectStoreError::NotFound) => (),
+                Err(e) => rest_warn!("could not remove object [{}]: {}", key, e),
+            }
+        }
+    }
+
+    /// Check, resize and store a logo, replacing the logo of the operator.
+    pub async fn put(
+        &self,
+        operator_id: &str,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> Result<OperatorLogo, LogoError> {
+        let format = logo_format(content_type).ok_or(LogoError::UnsupportedType)?;
+
+        // Decoding and resizing is CPU bound, keep it off the async workers
+        let (body, processed) = tokio::task::spawn_blocking(move || {
+            let processed = process_logo(&body, format);
+            (body, processed)
+        })
+        .await
+        .map_err(|e| LogoError::Invalid(format!("could not process logo: {e}")))?;
+        let processed = processed?;
+
+        let content_type = match format {
+            ImageFormat::Png => "image/png",
+            _ => "image/jpeg",
+        }
+        .to_string();
+        let logo = OperatorLogo {
+            operator_id: operator_id.to_string(),
+            content_type: content_type.clone(),
+            size: body.len() as u64,
+            width: processed.width,
+            height: processed.height,
+            sha256: processed.sha256,
+            url: format!("/assets/operators/{operator_id}/logo"),
+            updated_at: Utc::now(),
+        };
+
+        let objects = std::iter::once((LogoSize::Original, content_type.as_str(), body)).chain(
+            processed
+                .variants
+                .into_iter()
+                .map(|(size, content)| (size, VARIANT_CONTENT_TYPE, content)),
+        );
+        for (size, content_type, content) in objects {
+            let key = object_key(operator_id, &logo.sha256, size);
+            if let Err(e) = self.objects.put(&key, content_type, content).await {
+                self.delete_objects(operator_id, &logo.sha256).await;
+                return Err(LogoError::Store(e));
+            }
+        }
+
+        let previous = self.write().insert(operator_id.to_string(), logo.clone());
+        if let Some(previous) = previous.filter(|previous| previous.sha256 != logo.sha256) {
+            self.delete_objects(operator_id, &previous.sha256).await;
+        }
+
+        Ok(logo)
+    }
+
+    /// Get the logo of an operator with the content of a variant
+    pub async fn download(
+        &self,
+        operator_id: &str,
+        size: LogoSize,
+    ) -> Result<(OperatorLogo, Vec<u8>), LogoError> {
+        let logo = self.get(operator_id)?;
+        let content = self
+            .objects
+            .get(&object_key(operator_id, &logo.sha256, size))
+            .await
+            .map_err(LogoError::Store)?;
+
+        Ok((logo, content))
+    }
+
+    /// Remove the logo of an operator together with its files
+    pub async fn remove(&self, operator_id: &str) -> Result<(), LogoError> {
+        let logo = self.get(operator_id)?;
+        for size in [LogoSize::Original, LogoSize::Medium, LogoSize::Small] {
+            match self
+                .objects
+                .delete(&object_key(operator_id, &logo.sha256, size))
+                .await
+            {
+                // The file is gone already, remove the logo anyway
+                Ok(()) | Err(ObjectStoreError::NotFound) => (),
+                Err(e) => return Err(LogoError::Store(e)),
+            }
+        }
+
+        // Keep a logo uploaded while the files were removed
+        let mut logos = self.write();
+        if logos
+            .get(operator_id)
+            .map_or(false, |current| current.sha256 == logo.sha256)
+        {
+            logos.remove(operator_id);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, RgbImage};
+
+    /// Encode a blank image of the provided dimensions
+    fn image(width: u32, height: u32, format: ImageOutputFormat) -> Vec<u8> {
+        let mut content = Cursor::new(vec![]);
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+            .write_to(&mut content, format)
+            .unwrap();
+        content.into_inner()
+    }
+
+    fn dimensions(content: &[u8]) -> (u32, u32) {
+        image::load_from_memory(content).unwrap().dimensions()
+    }
+
+    #[test]
+    fn test_logo_format() {
+        assert_eq!(logo_format("image/png"), Some(ImageFormat::Png));
+        assert_eq!(logo_format("IMAGE/JPEG; q=1"), Some(ImageFormat::Jpeg));
+        assert_eq!(logo_format("image/gif"), None);
+        assert_eq!(logo_format(""), None);
+    }
+
+    #[test]
+    fn test_process_logo() {
+        let png = image(512, 300, ImageOutputFormat::Png);
+        let processed = process_logo(&png, ImageFormat::Png).unwrap();
+        assert_eq!((processed.width, processed.height), (512, 300));
+        assert_eq!(processed.sha256, hex(&sha256(&png)));
+        assert_eq!(processed.sha256.len(), 64);
+
+        let (size, medium) = &processed.variants[0];
+        assert_eq!(*size, LogoSize::Medium);
+        assert_eq!(dimensions(medium).0, 256);
+        let (size, small) = &processed.variants[1];
+        assert_eq!(*size, LogoSize::Small);
+        assert_eq!(dimensions(small).0, 64);
+
+        // small images are not enlarged
+        let jpeg = image(100, 40, ImageOutputFormat::Jpeg(80));
+        let processed = process_logo(&jpeg, ImageFormat::Jpeg).unwrap();
+        assert_eq!(dimensions(&processed.variants[0].1), (100, 40));
+        assert_eq!(dimensions(&processed.variants[1].1).0, 64);
+
+        // content not matching the content type
+        let result = process_logo(&jpeg, ImageFormat::Png);
+        assert!(matches!(result, Err(LogoError::Invalid(_))));
+        let result = process_logo(b"not an image", ImageFormat::Png);
+        assert!(matches!(result, Err(LogoError::Invalid(_))));
+
+        // too large
+        let png = image(MAX_LOGO_PIXELS + 1, 1, ImageOutputFormat::Png);
+        let result = process_logo(&png, ImageFormat::Png);
+        assert!(matches!(result, Err(LogoError::Invalid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_operator_logos() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let objects = Arc::new(MemoryObjectStore::default());
+        let logos = OperatorLogos::new(objects.clone());
+        let id = "operator";
+
+        let result = logos.put(id, "image/gif", vec![]).await;
+        assert_eq!(result, Err(LogoError::UnsupportedType));
+        assert_eq!(logos.get(id), Err(LogoError::NotFound));
+
+        let png = image(300, 300, ImageOutputFormat::Png);
+        let logo = logos.put(id, "image/png", png.clone()).await.unwrap();
+        assert_eq!(logo.size, png.len() as u64);
+        assert_eq!(logo.url, "/assets/operators/operator/logo");
+
+        let (_, content) = logos.download(id, LogoSize::Original).await.unwrap();
+        assert_eq!(content, png);
+        let (_, content) = logos.download(id, LogoSize::Small).await.unwrap();
+        assert_eq!(dimensions(&content), (64, 64));
+
+        // replacing the logo removes the files of the previous logo
+        let jpeg = image(50, 50, ImageOutputFormat::Jpeg(80));
+        let replaced = logos.put(id, "image/jpeg", jpeg).await.unwrap();
+        assert_eq!(replaced.content_type, "image/jpeg");
+        let key = object_key(id, &logo.sha256, LogoSize::Original);
+        assert_eq!(objects.get(&key).await, Err(ObjectStoreError::NotFound));
+
+        logos.remove(id).await.unwrap();
+        assert_eq!(logos.remove(id).await, Err(LogoError::NotFound));
+        let key = object_key(id, &replaced.sha256, LogoSize::Medium);
+        assert_eq!(objects.get(&key).await, Err(ObjectStoreError::NotFound));
+
+        ut_info!("success");
+    }
+}
@@ -3,9 +3,32 @@
 
 #[macro_use]
 pub mod macros;
+pub mod admin;
 pub mod api;
+pub mod attachments;
+pub mod audit;
+pub mod auth;
+pub mod chargers;
+pub mod classification;
+pub mod contacts;
+pub mod equipment;
+pub mod json;
+pub mod limits;
+pub mod locale;
+pub mod logos;
+pub mod occupancy;
+pub mod quota;
+pub mod read_only;
+pub mod redaction;
+pub mod regions;
+pub mod restrictions;
+pub mod routes;
+pub mod sdk;
 pub mod server;
 pub mod structs;
+pub mod tenant;
+pub mod timeout;
+pub mod version;
 
 pub use api::rest_types::*;
 
@@ -19,7 +42,27 @@ use utoipa::OpenApi;
         api::health::health_check,
 
         // GET
+        api::admin::get_read_only,
+        api::admin::put_read_only,
+        api::admin::get_audit_log,
+        api::admin::put_audit_log,
+        api::admin::get_config,
+        api::admin::get_features,
+        api::admin::flush_caches,
+        api::admin::get_grpc_connections,
+        api::admin::reconnect_grpc_clients,
+        api::admin::put_group_tenant,
         api::operator::get_operator,
+        api::operator::get_operator_quota,
+        api::operator::get_operator_contacts,
+        api::operator::add_operator_contact,
+        api::operator::update_operator_contact,
+        api::operator::remove_operator_contact,
+        api::operator::get_operator_notifications,
+        api::operator::put_operator_notifications,
+        api::operator::get_operator_logo,
+        api::operator::put_operator_logo,
+        api::operator::remove_operator_logo,
         api::operator::get_all_assets_by_operator,
         api::operator::get_all_grouped_assets,
         api::operator::get_all_grouped_assets_delegated_to,
@@ -28,27 +71,123 @@ use utoipa::OpenApi;
         api::vertiport::get_all_vertiports,
         api::vertipad::get_all_vertipads,
         api::aircraft::get_aircraft_by_id,
+        api::aircraft::get_aircraft_utilization,
+        api::aircraft::get_aircraft_holds,
+        api::vertiport::get_vertiport_ops_status,
+        api::vertiport::get_vertiport_weather_minimums,
         api::vertipad::get_vertipad_by_id,
+        api::vertipad::get_vertipad_availability,
+        api::vertipad::get_vertipad_occupancy_log,
+        api::charger::get_vertipad_chargers,
+        api::charger::add_vertipad_charger,
+        api::charger::get_vertipad_charger,
+        api::charger::update_vertipad_charger,
+        api::charger::remove_vertipad_charger,
+        api::equipment::get_vertipad_equipment,
+        api::equipment::add_vertipad_equipment,
+        api::equipment::get_vertipad_equipment_by_id,
+        api::equipment::update_vertipad_equipment,
+        api::equipment::remove_vertipad_equipment,
+        api::restriction::get_vertiport_restrictions,
+        api::restriction::get_vertiport_restriction_rules,
+        api::restriction::add_vertiport_restriction_rule,
+        api::restriction::get_vertiport_restriction_rule,
+        api::restriction::update_vertiport_restriction_rule,
+        api::restriction::remove_vertiport_restriction_rule,
         api::vertiport::get_vertiport_by_id,
+        api::vertiport::get_vertipad_adjacency,
+        api::vertiport::get_vertipads_by_elevation,
+        api::vertiport::typeahead_vertiports,
+        api::region::get_regions,
+        api::region::get_region,
+        api::region::get_region_vertiports,
         api::group::get_asset_group_by_id,
+        api::group::get_asset_group_availability,
+        api::group::get_asset_group_assets,
+        api::group::get_asset_group_delegation,
+        api::group::get_asset_group_delegation_audit,
+        api::permission::get_aircraft_permissions,
+        api::permission::get_vertiport_permissions,
+        api::permission::get_vertipad_permissions,
+        api::permission::get_group_permissions,
+        api::diff::get_asset_diff,
+        api::audit::get_audit_changes,
+        api::maintenance::get_maintenance_calendar,
+        api::quality::get_quality_report,
+        api::archive::get_archive,
+        api::archive::restore_archived_asset,
+        api::asset::get_asset_by_id,
+        api::query::query_assets,
+        api::attachment::get_asset_notes,
+        api::attachment::get_asset_attachments,
+        api::attachment::download_asset_attachment,
+
+        // HEAD
+        api::aircraft::head_aircraft_by_id,
+        api::vertiport::head_vertiport_by_id,
+        api::vertipad::head_vertipad_by_id,
 
         // CREATE
         api::aircraft::register_aircraft,
         api::vertiport::register_vertiport,
         api::vertipad::register_vertipad,
         api::group::register_asset_group,
+        api::region::register_region,
+        api::aircraft::assign_aircraft_hangar,
+        api::aircraft::create_aircraft_hold,
+        api::import::import_geojson,
+        api::snapshot::export_snapshot,
+        api::snapshot::import_snapshot,
+        api::attachment::add_asset_note,
+        api::attachment::upload_asset_attachment,
+
+        // BATCH
+        api::aircraft::batch_get_aircraft,
+        api::vertiport::batch_get_vertiports,
+        api::vertipad::batch_get_vertipads,
+        api::aircraft::batch_set_aircraft_status,
+
+        // VALIDATE
+        api::aircraft::validate_aircraft_registration,
+        api::aircraft::validate_aircraft_update,
+        api::aircraft::check_aircraft_registration,
+        api::vertiport::validate_vertiport_registration,
+        api::vertiport::validate_vertiport_update,
+        api::vertipad::validate_vertipad_registration,
+        api::vertipad::validate_vertipad_update,
 
         // UPDATE
         api::aircraft::update_aircraft,
         api::vertiport::update_vertiport,
         api::vertipad::update_vertipad,
         api::group::update_asset_group,
+        api::region::update_region,
+        api::vertiport::put_vertiport_localization,
+        api::vertipad::put_vertipad_localization,
+        api::vertipad::put_vertipad_classification,
+        api::vertiport::put_vertiport_ops_status,
+        api::vertiport::put_vertiport_weather_minimums,
+        api::group::put_asset_group_schedule,
+        api::group::put_asset_group_parent,
+        api::group::put_asset_group_delegation,
+
+        // PATCH
+        api::aircraft::patch_aircraft,
+        api::vertiport::patch_vertiport,
+        api::vertipad::patch_vertipad,
+        api::group::patch_asset_group,
 
         // REMOVE
         api::aircraft::remove_aircraft,
+        api::aircraft::cancel_aircraft_hold,
         api::vertiport::remove_vertiport,
+        api::vertiport::remove_vertiport_weather_minimums,
         api::vertipad::remove_vertipad,
         api::group::remove_asset_group,
+        api::region::remove_region,
+        api::group::revoke_asset_group_delegation,
+        api::attachment::remove_asset_note,
+        api::attachment::remove_asset_attachment,
     ),
     components(
         schemas(
@@ -58,10 +197,130 @@ use utoipa::OpenApi;
             GeoPointZ,
             GeoPolygonZ,
             GeoLineStringZ,
+            GeoPoint,
+            GeoPolygon,
+            GeoLineString,
+            GeoBoundingBox,
+            GeometryDetail,
+            PadLayout,
+            VertipadAdjacency,
+            VertiportSuggestion,
+            RegionPayload,
+            Region,
             RegisterAssetGroupPayload,
             UpdateAircraftPayload,
+            RegistrationNumberCheck,
+            UpdateResult,
             UpdateVertiportPayload,
             UpdateVertipadPayload,
+            FieldError,
+            OperatorQuota,
+            QuotaUsage,
+            OperatorContactPayload,
+            OperatorContact,
+            AssetNotePayload,
+            AssetNote,
+            AssetAttachment,
+            LogoSize,
+            OperatorLogo,
+            NotificationEvent,
+            NotificationChannel,
+            NotificationPreference,
+            TimeWindow,
+            VertipadAvailability,
+            GroupSchedulePayload,
+            GroupAvailability,
+            GroupParentPayload,
+            GroupAssetFailure,
+            DelegationPayload,
+            Delegation,
+            DelegationAction,
+            DelegationAuditEntry,
+            DelegatedAssetGroup,
+            JsonPatchOperation,
+            AircraftUtilization,
+            AssignedFlight,
+            AircraftStatusFilter,
+            BatchAircraftStatusPayload,
+            AircraftStatusResult,
+            AssignHangarPayload,
+            MaintenanceHoldPayload,
+            MaintenanceHold,
+            VertiportOpsStatus,
+            VertiportOpsStatusPayload,
+            WeatherMinimumsPayload,
+            VertiportWeatherMinimums,
+            VertiportOpsState,
+            BatchGetPayload,
+            GeoJsonFeatureCollection,
+            GeoJsonFeature,
+            GeoJsonGeometry,
+            GeoJsonProperties,
+            ImportedVertiport,
+            SnapshotImportSummary,
+            QualityIssue,
+            QualityReport,
+            SkippedObjects,
+            ConversionFailure,
+            PartialList,
+            AssetLocalization,
+            PadClassification,
+            PadType,
+            PadSurface,
+            Charger,
+            ChargerPayload,
+            ChargerConnector,
+            ChargerAvailability,
+            EquipmentKind,
+            EquipmentStatus,
+            EquipmentPayload,
+            Equipment,
+            RestrictionKind,
+            RestrictionRulePayload,
+            RestrictionRule,
+            ActiveRestriction,
+            VertiportRestrictions,
+            ReadOnlyStatus,
+            AuditLogStatus,
+            GroupTenant,
+            LeftoverAssetType,
+            LeftoverAsset,
+            IncompleteRegistration,
+            AuditChange,
+            AuditChanges,
+            PermissionAssetType,
+            AssetRelation,
+            AssetPermissions,
+            FeatureFlag,
+            StartupState,
+            HealthStatus,
+            StorageLatency,
+            GrpcConnectionState,
+            GrpcConnectionStatus,
+            OccupancySource,
+            OccupancyTransition,
+            api::batch::AircraftBatch,
+            api::batch::VertiportBatch,
+            api::batch::VertipadBatch,
+            api::asset::AssetSummary,
+            api::asset::ResolvedAsset,
+            api::group::GroupAssets,
+            api::asset::AircraftSummary,
+            api::asset::VertiportSummary,
+            api::asset::VertipadSummary,
+            api::asset::GroupSummary,
+            api::diff::AssetDiff,
+            api::diff::AircraftChanges,
+            api::diff::VertiportChanges,
+            api::diff::VertipadChanges,
+            IncludeDeleted,
+            AssetQuery,
+            QueryAssetType,
+            QueryFilter,
+            QueryOperator,
+            ArchiveAssetType,
+            ArchivedAsset,
+            RestoredAsset,
             api::operator::Operator,
             api::aircraft::Aircraft,
             api::vertiport::Vertiport,
@@ -73,7 +332,8 @@ use utoipa::OpenApi;
     ),
     tags(
         (name = "svc-assets", description = "svc-assets API")
-    )
+    ),
+    modifiers(&auth::ApiScopes, &json::TimestampSchemas)
 )]
 #[cfg(not(tarpaulin_include))]
 // no_coverage: (Rnever) not unit testable
@@ -0,0 +1,296 @@
+//! Vertipad occupancy transitions
+//!
+//! svc-storage only stores whether a vertipad is occupied, not who set it.
+//! The transitions are recorded in the [`OCCUPANCY_TRANSITIONS_TABLE`] of
+//! the persisted state (see [`crate::state`]), so a manual release can be
+//! rejected while the scheduler holds a reservation, also after a restart.
+//!
+//! Every recorded transition increments the occupancy version of the
+//! vertipad, so an update can be made conditional on the version it is
+//! based on. The updates of a vertipad are serialized with
+//! [`OccupancyLog::lock_updates`], and the version is compared and the
+//! vertipad written while holding the lock, so no other update can change
+//! the occupancy in between. The transitions and the locks are not shared
+//! between replicas, so the checks apply to the updates of one instance.
+
+pub use super::api::rest_types::{OccupancySource, OccupancyTransition};
+
+use crate::state::{StateError, StateTable};
+use lib_common::time::Utc;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Display, Formatter};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Mutex as UpdateLock, OwnedMutexGuard};
+
+/// Name of the table of the occupancy transitions in the state directory
+pub const OCCUPANCY_TRANSITIONS_TABLE: &str = "occupancy_transitions";
+
+/// Maximum number of transitions kept per vertipad
+pub const MAX_OCCUPANCY_LOG_LEN: usize = 100;
+
+/// Error returned for a transition which is not allowed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OccupancyError {
+    /// The vertipad can not be released manually while it is reserved by the scheduler.
+    ScheduledReservation,
+    /// The occupancy changed since the expected version.
+    VersionChanged {
+        /// Version the update is based on
+        expected: u64,
+        /// Current version of the vertipad occupancy
+        current: u64,
+    },
+}
+
+impl Display for OccupancyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            OccupancyError::ScheduledReservation => {
+                write!(f, "vertipad is reserved by the scheduler")
+            }
+            OccupancyError::VersionChanged { expected, current } => {
+                write!(
+                    f,
+                    "vertipad occupancy changed from version {expected} to {current}"
+                )
+            }
+        }
+    }
+}
+
+/// Store of the occupancy transitions per vertipad id.
+///
+/// The default store is kept in memory, [`OccupancyLog::open`] persists the
+/// transitions. The update locks are only held within this process.
+#[derive(Debug, Clone, Default)]
+pub struct OccupancyLog {
+    transitions: StateTable<VecDeque<OccupancyTransition>>,
+    updates: Arc<Mutex<HashMap<String, Arc<UpdateLock<()>>>>>,
+}
+
+impl OccupancyLog {
+    /// Open the transitions persisted in the state directory, kept in memory
+    /// if no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            transitions: StateTable::open(dir, OCCUPANCY_TRANSITIONS_TABLE)?,
+            ..Default::default()
+        })
+    }
+
+    /// Wait until no other occupancy update of the vertipad is in progress.
+    ///
+    /// The vertipad is held until the returned guard is dropped.
+    pub async fn lock_updates(&self, vertipad_id: &str) -> OwnedMutexGuard<()> {
+        let lock = self
+            .updates
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(vertipad_id.to_string())
+            .or_default()
+            .clone();
+        lock.lock_owned().await
+    }
+
+    /// Check if the vertipad is currently reserved by the scheduler
+    pub fn is_reserved(&self, vertipad_id: &str) -> bool {
+        self.transitions.read(|transitions| {
+            transitions
+                .get(vertipad_id)
+                .and_then(|transitions| transitions.back())
+                .map(|last| last.occupied && last.source == OccupancySource::Scheduler)
+                .unwrap_or(false)
+        })
+    }
+
+    /// Get the occupancy version of the vertipad, `0` if it has no
+    /// transitions
+    pub fn version(&self, vertipad_id: &str) -> u64 {
+        self.transitions.read(|transitions| {
+            transitions
+                .get(vertipad_id)
+                .and_then(|transitions| transitions.back())
+                .map_or(0, |last| last.version)
+        })
+    }
+
+    /// Check if the occupancy of the vertipad is still at the expected
+    /// version
+    pub fn check_version(&self, vertipad_id: &str, expected: u64) -> Result<(), OccupancyError> {
+        match self.version(vertipad_id) {
+            current if current == expected => Ok(()),
+            current => Err(OccupancyError::VersionChanged { expected, current }),
+        }
+    }
+
+    /// Check if the vertipad occupancy can be changed by the provided source
+    pub fn check(
+        &self,
+        vertipad_id: &str,
+        occupied: bool,
+        source: OccupancySource,
+    ) -> Result<(), OccupancyError> {
+        if !occupied && source == OccupancySource::Manual && self.is_reserved(vertipad_id) {
+            return Err(OccupancyError::ScheduledReservation);
+        }
+
+        Ok(())
+    }
+
+    /// Record an occupancy change of the vertipad, incrementing its version
+    pub fn record(
+        &self,
+        vertipad_id: &str,
+        occupied: bool,
+        source: OccupancySource,
+    ) -> Result<(), StateError> {
+        self.transitions.update(|transitions| {
+            let transitions = transitions.entry(vertipad_id.to_string()).or_default();
+            let version = transitions.back().map_or(0, |last| last.version) + 1;
+            if transitions.len() == MAX_OCCUPANCY_LOG_LEN {
+                transitions.pop_front();
+            }
+
+            transitions.push_back(OccupancyTransition {
+                occupied,
+                source,
+                timestamp: Utc::now(),
+                version,
+            });
+        })
+    }
+
+    /// Get the last `limit` transitions of the vertipad, most recent first
+    pub fn last(&self, vertipad_id: &str, limit: usize) -> Vec<OccupancyTransition> {
+        self.transitions.read(|transitions| {
+            transitions
+                .get(vertipad_id)
+                .map(|transitions| transitions.iter().rev().take(limit).cloned().collect())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Remove all transitions of a removed vertipad.
+    ///
+    /// The vertipad is removed even if its transitions could not be written,
+    /// so the error is only logged.
+    pub fn remove(&self, vertipad_id: &str) {
+        if let Err(e) = self.transitions.remove(vertipad_id) {
+            rest_warn!(
+                "could not remove the occupancy transitions of vertipad {}: {e}",
+                vertipad_id
+            );
+        }
+        self.updates
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(vertipad_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scheduler_reservation() {
+        let log = OccupancyLog::default();
+        let id = "vertipad";
+        assert!(log.check(id, false, OccupancySource::Manual).is_ok());
+
+        log.record(id, true, OccupancySource::Scheduler).unwrap();
+        assert!(log.is_reserved(id));
+        assert_eq!(
+            log.check(id, false, OccupancySource::Manual),
+            Err(OccupancyError::ScheduledReservation)
+        );
+        assert!(log.check(id, false, OccupancySource::Scheduler).is_ok());
+
+        log.record(id, false, OccupancySource::Scheduler).unwrap();
+        assert!(!log.is_reserved(id));
+        assert!(log.check(id, false, OccupancySource::Manual).is_ok());
+    }
+
+    #[test]
+    fn test_occupancy_version() {
+        let log = OccupancyLog::default();
+        let id = "vertipad";
+        assert_eq!(log.version(id), 0);
+        assert!(log.check_version(id, 0).is_ok());
+
+        log.record(id, true, OccupancySource::Manual).unwrap();
+        log.record(id, false, OccupancySource::Manual).unwrap();
+        assert_eq!(log.version(id), 2);
+        assert!(log.check_version(id, 2).is_ok());
+        assert_eq!(
+            log.check_version(id, 1),
+            Err(OccupancyError::VersionChanged {
+                expected: 1,
+                current: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_occupancy_log_len() {
+        let log = OccupancyLog::default();
+        for i in 0..MAX_OCCUPANCY_LOG_LEN + 5 {
+            log.record("vertipad", i % 2 == 0, OccupancySource::Manual)
+                .unwrap();
+        }
+
+        let transitions = log.last("vertipad", usize::MAX);
+        assert_eq!(transitions.len(), MAX_OCCUPANCY_LOG_LEN);
+        // most recent first
+        assert!(transitions[0].occupied);
+        // the version keeps counting the dropped transitions
+        assert_eq!(transitions[0].version, MAX_OCCUPANCY_LOG_LEN as u64 + 5);
+        assert_eq!(log.last("vertipad", 3).len(), 3);
+        assert!(log.last("unknown", 3).is_empty());
+
+        log.remove("vertipad");
+        assert!(log.last("vertipad", 3).is_empty());
+    }
+
+    #[test]
+    fn test_occupancy_log_persisted() {
+        let dir = crate::state::test_state_dir();
+
+        let log = OccupancyLog::open(Some(&dir)).unwrap();
+        log.record("vertipad", true, OccupancySource::Scheduler)
+            .unwrap();
+        log.record("removed", true, OccupancySource::Manual)
+            .unwrap();
+        log.remove("removed");
+
+        // the reservation still blocks a manual release after a restart
+        let log = OccupancyLog::open(Some(&dir)).unwrap();
+        assert_eq!(
+            log.check("vertipad", false, OccupancySource::Manual),
+            Err(OccupancyError::ScheduledReservation)
+        );
+        assert!(log.last("removed", 3).is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_lock_updates() {
+        let log = OccupancyLog::default();
+        let wait = std::time::Duration::from_millis(20);
+
+        let guard = log.lock_updates("vertipad").await;
+        assert!(tokio::time::timeout(wait, log.lock_updates("vertipad"))
+            .await
+            .is_err());
+        // other vertipads are not held
+        tokio::time::timeout(wait, log.lock_updates("other"))
+            .await
+            .unwrap();
+
+        drop(guard);
+        tokio::time::timeout(wait, log.lock_updates("vertipad"))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,508 @@
+//! Per-operator asset quotas
+//!
+//! svc-storage does not store the operator owning an aircraft or vertiport,
+//! so the owner of each asset registered through this service is recorded
+//! in the persisted `asset_owners` table (see [`crate::state`]). The owner
+//! is the operator of the registration, which is required, and is reported
+//! as the `owner_id` of the asset. The usage of an operator is the number of
+//! its recorded assets which still exist in svc-storage, so assets removed
+//! outside of this service no longer count. Assets registered before the
+//! table existed, imported from a snapshot or created outside of this
+//! service have no owner and are not counted.
+//!
+//! Slots reserved by registrations in progress are kept in memory and count
+//! towards the limit, so concurrent registrations of one instance can not
+//! exceed it. The limits are read from the [`SharedConfig`] so they can be
+//! changed at runtime.
+
+pub use super::api::rest_types::{OperatorQuota, QuotaUsage};
+
+use super::api::errors::storage_error_status;
+use crate::config::SharedConfig;
+use crate::repo::Repositories;
+use crate::state::{StateError, StateTable};
+use axum::http::HeaderMap;
+use hyper::StatusCode;
+use lib_common::uuid::to_uuid;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+use svc_storage_client_grpc::prelude::AdvancedSearchFilter;
+
+/// Header used to provide the UUID of the operator registering an asset
+pub const OPERATOR_ID_HEADER: &str = "x-operator-id";
+
+/// Name of the persisted table of the asset owners
+pub const ASSET_OWNERS_TABLE: &str = "asset_owners";
+
+/// Kind of assets limited by a quota
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaKind {
+    /// Aircraft assets
+    Aircraft,
+    /// Vertiport assets
+    Vertiport,
+}
+
+/// Operator owning an asset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetOwner {
+    /// UUID of the operator
+    pub operator_id: String,
+    /// Kind of the asset
+    pub kind: QuotaKind,
+}
+
+/// Persisted owners of the assets registered through this service, by
+/// asset id
+pub type AssetOwners = StateTable<AssetOwner>;
+
+type UsageKey = (String, QuotaKind);
+
+/// Quotas of the operators, with the owners of their assets
+#[derive(Debug, Clone)]
+pub struct OperatorQuotas {
+    config: SharedConfig,
+    owners: AssetOwners,
+    pending: Arc<Mutex<HashMap<UsageKey, usize>>>,
+}
+
+/// A reserved quota slot for an asset which is being registered.
+///
+/// The slot is released when dropped, unless [`QuotaReservation::commit`] is called.
+#[derive(Debug)]
+pub struct QuotaReservation {
+    quotas: OperatorQuotas,
+    key: UsageKey,
+    released: bool,
+}
+
+impl QuotaReservation {
+    /// Record the operator as the owner of the registered asset.
+    ///
+    /// The slot is released even if the owner could not be recorded.
+    pub fn commit(mut self, asset_id: &str) -> Result<(), StateError> {
+        let mut pending = self.quotas.lock();
+        let owner = AssetOwner {
+            operator_id: self.key.0.clone(),
+            kind: self.key.1,
+        };
+        let result = self.quotas.owners.insert(asset_id, owner).map(|_| ());
+        release_slot(&mut pending, &self.key);
+        self.released = true;
+
+        result
+    }
+}
+
+impl Drop for QuotaReservation {
+    fn drop(&mut self) {
+        if !self.released {
+            release_slot(&mut self.quotas.lock(), &self.key);
+        }
+    }
+}
+
+fn release_slot(pending: &mut HashMap<UsageKey, usize>, key: &UsageKey) {
+    if let Some(count) = pending.get_mut(key) {
+        *count = count.saturating_sub(1);
+    }
+}
+
+/// Count the assets which still exist in svc-storage.
+///
+/// The assets are looked up with a single search on their ids, so the
+/// number of storage requests does not grow with the number of assets.
+async fn count_stored(
+    repos: &Repositories,
+    kind: QuotaKind,
+    asset_ids: HashSet<String>,
+) -> Result<u32, StatusCode> {
+    if asset_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let ids: Vec<String> = asset_ids.iter().cloned().collect();
+    let found = match kind {
+        QuotaKind::Aircraft => {
+            let filter = AdvancedSearchFilter::search_in("vehicle_id".to_string(), ids)
+                .and_is_null("deleted_at".to_string());
+            repos
+                .aircraft
+                .search(filter)
+                .await
+                .map(|objects| objects.into_iter().map(|object| object.id).collect())
+        }
+        QuotaKind::Vertiport => {
+            let filter = AdvancedSearchFilter::search_in("vertiport_id".to_string(), ids)
+                .and_is_null("deleted_at".to_string());
+            repos
+                .vertiports
+                .search(filter)
+                .await
+                .map(|objects| objects.into_iter().map(|object| object.id).collect())
+        }
+    };
+    let found: HashSet<String> = found.map_err(|e| {
+        rest_error!("could not count the {:?} assets of an operator: {e}", kind);
+        storage_error_status(&e)
+    })?;
+
+    // the ids are checked again, for the repositories which do not apply
+    // search filters
+    Ok(found.intersection(&asset_ids).count() as u32)
+}
+
+impl OperatorQuotas {
+    /// Create the quotas using the limits of the provided configuration
+    pub fn new(config: SharedConfig, owners: AssetOwners) -> Self {
+        Self {
+            config,
+            owners,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<UsageKey, usize>> {
+        match self.pending.lock() {
+            Ok(pending) => pending,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Get the configured limit for the provided kind, [`None`] if unlimited
+    pub fn limit(&self, kind: QuotaKind) -> Option<u32> {
+        let limit = self.config.read(|config| match kind {
+            QuotaKind::Aircraft => config.max_aircraft_per_operator,
+            QuotaKind::Vertiport => config.max_vertiports_per_operator,
+        });
+
+        match limit {
+            0 => None,
+            limit => Some(limit),
+        }
+    }
+
+    /// Get the current usage for the provided operator and kind, counting
+    /// its assets which exist in svc-storage
+    pub async fn usage(
+        &self,
+        repos: &Repositories,
+        operator_id: &str,
+        kind: QuotaKind,
+    ) -> Result<QuotaUsage, StatusCode> {
+        let used = count_stored(repos, kind, self.assets(operator_id, kind)).await?;
+
+        Ok(QuotaUsage {
+            used,
+            limit: self.limit(kind),
+        })
+    }
+
+    /// Get the quota overview for the provided operator
+    pub async fn get(
+        &self,
+        repos: &Repositories,
+        operator_id: &str,
+    ) -> Result<OperatorQuota, StatusCode> {
+        Ok(OperatorQuota {
+            operator_id: operator_id.to_string(),
+            aircraft: self.usage(repos, operator_id, QuotaKind::Aircraft).await?,
+            vertiports: self.usage(repos, operator_id, QuotaKind::Vertiport).await?,
+        })
+    }
+
+    /// Reserve a slot for a new asset of the provided operator.
+    ///
+    /// Returns `FORBIDDEN` if the operator reached its limit.
+    pub async fn reserve(
+        &self,
+        repos: &Repositories,
+        operator_id: &str,
+        kind: QuotaKind,
+    ) -> Result<QuotaReservation, StatusCode> {
+        let key = (operator_id.to_string(), kind);
+
+        // the owned assets and the other pending slots are read together, so
+        // a registration committing meanwhile is counted exactly once
+        let (owned, pending) = {
+            let mut pending = self.lock();
+            let slots = pending.entry(key.clone()).or_default();
+            let others = *slots;
+            *slots += 1;
+            (self.assets(operator_id, kind), others)
+        };
+        let reservation = QuotaReservation {
+            quotas: self.clone(),
+            key,
+            released: false,
+        };
+
+        let Some(limit) = self.limit(kind) else {
+            return Ok(reservation);
+        };
+
+        let used = count_stored(repos, kind, owned).await? as usize + pending;
+        if used >= limit as usize {
+            rest_warn!(
+                "operator [{}] reached its {:?} quota of {}.",
+                operator_id,
+                kind,
+                limit
+            );
+            return Err(StatusCode::FORBIDDEN);
+        }
+
+        Ok(reservation)
+    }
+
+    /// Get the operator which registered an asset through this service, if
+    /// known
+    pub fn owner(&self, kind: QuotaKind, asset_id: &str) -> Option<String> {
+        self.owners
+            .get(asset_id)
+            .filter(|owner| owner.kind == kind)
+            .map(|owner| owner.operator_id)
+    }
+
+    /// Get the assets an operator registered through this service
+    pub fn assets(&self, operator_id: &str, kind: QuotaKind) -> HashSet<String> {
+        self.owners.read(|owners| {
+            owners
+                .iter()
+                .filter(|(_, owner)| owner.kind == kind && owner.operator_id == operator_id)
+                .map(|(asset_id, _)| asset_id.clone())
+                .collect()
+        })
+    }
+
+    /// Forget the owner of a removed asset.
+    ///
+    /// A removed asset no longer counts towards the quota even if its owner
+    /// could not be forgotten, so the error is only logged.
+    pub fn release(&self, kind: QuotaKind, asset_id: &str) {
+        if self.owner(kind, asset_id).is_none() {
+            return;
+        }
+
+        if let Err(e) = self.owners.remove(asset_id) {
+            rest_warn!("could not forget the owner of asset {}: {}", asset_id, e);
+        }
+    }
+}
+
+/// Get the operator id from the request headers, if provided.
+///
+/// Returns `BAD_REQUEST` if the header is not a valid UUID.
+pub fn operator_id_from_headers(headers: &HeaderMap) -> Result<Option<String>, StatusCode> {
+    let Some(value) = headers.get(OPERATOR_ID_HEADER) else {
+        return Ok(None);
+    };
+
+    value
+        .to_str()
+        .ok()
+        .and_then(to_uuid)
+        .map(|id| Some(id.to_string()))
+        .ok_or_else(|| {
+            rest_error!("invalid {} header.", OPERATOR_ID_HEADER);
+            StatusCode::BAD_REQUEST
+        })
+}
+
+/// Get the operator registering an asset from the request headers. The
+/// operator becomes the owner of the asset, so it is required.
+///
+/// Returns `BAD_REQUEST` if the header is missing or not a valid UUID.
+pub fn owner_id_from_headers(headers: &HeaderMap) -> Result<String, StatusCode> {
+    operator_id_from_headers(headers)?.ok_or_else(|| {
+        rest_warn!("the operator registering the asset is required.");
+        StatusCode::BAD_REQUEST
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::AircraftDataBuilder;
+    use crate::Config;
+    use axum::http::HeaderValue;
+    use lib_common::uuid::Uuid;
+
+    fn quotas(max_aircraft: u32) -> OperatorQuotas {
+        let mut config = Config::default();
+        config.max_aircraft_per_operator = max_aircraft;
+        OperatorQuotas::new(config.into(), AssetOwners::default())
+    }
+
+    #[tokio::test]
+    async fn test_reserve_and_release() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let quotas = quotas(1);
+        let operator_id = Uuid::new_v4().to_string();
+
+        let reservation = quotas
+            .reserve(&repos, &operator_id, QuotaKind::Aircraft)
+            .await
+            .expect("first aircraft should fit the quota");
+        // pending reservations count towards the limit
+        assert_eq!(
+            quotas
+                .reserve(&repos, &operator_id, QuotaKind::Aircraft)
+                .await
+                .unwrap_err(),
+            StatusCode::FORBIDDEN
+        );
+        let aircraft_id = repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+        reservation.commit(&aircraft_id).unwrap();
+        // aircraft of other operators are not counted
+        repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap();
+        let usage = quotas
+            .usage(&repos, &operator_id, QuotaKind::Aircraft)
+            .await
+            .unwrap();
+        assert_eq!(usage.used, 1);
+        assert_eq!(
+            quotas.owner(QuotaKind::Aircraft, &aircraft_id),
+            Some(operator_id.clone())
+        );
+        assert_eq!(quotas.owner(QuotaKind::Vertiport, &aircraft_id), None);
+        assert_eq!(
+            quotas.assets(&operator_id, QuotaKind::Aircraft),
+            HashSet::from([aircraft_id.clone()])
+        );
+        assert!(quotas.assets(&operator_id, QuotaKind::Vertiport).is_empty());
+        assert!(quotas
+            .reserve(&repos, &operator_id, QuotaKind::Aircraft)
+            .await
+            .is_err());
+
+        // the usage can not be counted without svc-storage
+        let error = quotas
+            .reserve(
+                &Repositories::unavailable(),
+                &operator_id,
+                QuotaKind::Aircraft,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error, StatusCode::SERVICE_UNAVAILABLE);
+
+        // vertiports are unlimited by default
+        assert!(quotas
+            .reserve(&repos, &operator_id, QuotaKind::Vertiport)
+            .await
+            .is_ok());
+        let quota = quotas.get(&repos, &operator_id).await.unwrap();
+        assert_eq!(quota.vertiports.limit, None);
+
+        // dropped reservations are released
+        let other_operator = Uuid::new_v4().to_string();
+        drop(
+            quotas
+                .reserve(&repos, &other_operator, QuotaKind::Aircraft)
+                .await
+                .unwrap(),
+        );
+        assert!(quotas
+            .reserve(&repos, &other_operator, QuotaKind::Aircraft)
+            .await
+            .is_ok());
+
+        // an aircraft removed outside of this service no longer counts
+        repos.aircraft.delete(aircraft_id.clone()).await.unwrap();
+        let quota = quotas.get(&repos, &operator_id).await.unwrap();
+        assert_eq!(quota.aircraft.used, 0);
+        assert_eq!(quota.aircraft.limit, Some(1));
+
+        quotas.release(QuotaKind::Aircraft, &aircraft_id);
+        assert_eq!(quotas.owner(QuotaKind::Aircraft, &aircraft_id), None);
+        assert!(quotas.assets(&operator_id, QuotaKind::Aircraft).is_empty());
+
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_asset_owners_persisted() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let dir = crate::state::test_state_dir();
+        let repos = Repositories::memory();
+        let operator_id = Uuid::new_v4().to_string();
+        let aircraft_id = repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap()
+            .id;
+
+        let owners = AssetOwners::open(Some(&dir), ASSET_OWNERS_TABLE).unwrap();
+        let quotas = OperatorQuotas::new(Config::default().into(), owners);
+        quotas
+            .reserve(&repos, &operator_id, QuotaKind::Aircraft)
+            .await
+            .unwrap()
+            .commit(&aircraft_id)
+            .unwrap();
+
+        // the owners are read back after a restart
+        let owners = AssetOwners::open(Some(&dir), ASSET_OWNERS_TABLE).unwrap();
+        let quotas = OperatorQuotas::new(Config::default().into(), owners);
+        assert_eq!(
+            quotas.owner(QuotaKind::Aircraft, &aircraft_id),
+            Some(operator_id.clone())
+        );
+        let usage = quotas
+            .usage(&repos, &operator_id, QuotaKind::Aircraft)
+            .await
+            .unwrap();
+        assert_eq!(usage.used, 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+        ut_info!("success");
+    }
+
+    #[tokio::test]
+    async fn test_operator_id_from_headers() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(operator_id_from_headers(&headers), Ok(None));
+        assert_eq!(
+            owner_id_from_headers(&headers),
+            Err(StatusCode::BAD_REQUEST)
+        );
+
+        headers.insert(OPERATOR_ID_HEADER, HeaderValue::from_static("invalid"));
+        assert_eq!(
+            operator_id_from_headers(&headers),
+            Err(StatusCode::BAD_REQUEST)
+        );
+        assert_eq!(
+            owner_id_from_headers(&headers),
+            Err(StatusCode::BAD_REQUEST)
+        );
+
+        let id = Uuid::new_v4().to_string();
+        headers.insert(OPERATOR_ID_HEADER, HeaderValue::from_str(&id).unwrap());
+        assert_eq!(operator_id_from_headers(&headers), Ok(Some(id.clone())));
+        assert_eq!(owner_id_from_headers(&headers), Ok(id));
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,132 @@
+//! Read-only maintenance mode
+//!
+//! While enabled, requests which change assets are rejected with a
+//! `503 SERVICE_UNAVAILABLE` and reads keep working. The mode is read from the
+//! [`SharedConfig`] (`READ_ONLY`) and can be overridden at runtime with the
+//! admin endpoint, for example during storage migrations.
+
+use crate::config::SharedConfig;
+use axum::{
+    http::{Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::{Arc, RwLock};
+
+/// Suffixes of `POST` and `PUT` routes which don't change any assets
+const NON_MUTATING_SUFFIXES: &[&str] = &["/validate", "/batch-get", "/assets/query"];
+
+/// Message returned for rejected requests
+const READ_ONLY_MESSAGE: &str =
+    "(server) service is in read-only mode for maintenance, changes are not accepted.";
+
+/// Read-only mode of the service
+#[derive(Debug, Clone)]
+pub struct ReadOnlyMode {
+    config: SharedConfig,
+    toggle: Arc<RwLock<Option<bool>>>,
+}
+
+impl ReadOnlyMode {
+    /// Create a new read-only mode using the `read_only` value of the configuration
+    pub fn new(config: SharedConfig) -> Self {
+        Self {
+            config,
+            toggle: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Check if the service is in read-only mode.
+    ///
+    /// A runtime toggle takes precedence over the configuration.
+    pub fn is_enabled(&self) -> bool {
+        let toggle = match self.toggle.read() {
+            Ok(toggle) => *toggle,
+            Err(poisoned) => *poisoned.into_inner(),
+        };
+        toggle.unwrap_or_else(|| self.config.read(|config| config.read_only))
+    }
+
+    /// Enable or disable the read-only mode until the service restarts
+    pub fn set(&self, enabled: bool) {
+        let mut toggle = match self.toggle.write() {
+            Ok(toggle) => toggle,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *toggle = Some(enabled);
+    }
+
+    /// Middleware function rejecting requests which change assets while
+    /// the service is in read-only mode.
+    pub async fn guard<B>(self, req: Request<B>, next: Next<B>) -> Response {
+        if self.is_enabled() && is_mutating(req.method(), req.uri().path()) {
+            rest_warn!(
+                "rejected {} {}: read-only mode.",
+                req.method(),
+                req.uri().path()
+            );
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                READ_ONLY_MESSAGE.to_string(),
+            )
+                .into_response();
+        }
+
+        next.run(req).await
+    }
+}
+
+/// Check if a request changes assets
+pub fn is_mutating(method: &Method, path: &str) -> bool {
+    match *method {
+        Method::POST | Method::PUT => !NON_MUTATING_SUFFIXES
+            .iter()
+            .any(|suffix| path.ends_with(suffix)),
+        Method::PATCH | Method::DELETE => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_is_mutating() {
+        assert!(is_mutating(&Method::POST, "/assets/aircraft"));
+        assert!(is_mutating(&Method::PUT, "/v1/assets/vertiports"));
+        assert!(is_mutating(&Method::PATCH, "/assets/vertipads/id"));
+        assert!(is_mutating(&Method::DELETE, "/assets/aircraft/id"));
+        assert!(!is_mutating(&Method::GET, "/assets/aircraft/id"));
+        assert!(!is_mutating(&Method::POST, "/assets/aircraft/batch-get"));
+        assert!(!is_mutating(&Method::PUT, "/v1/assets/vertipads/validate"));
+        assert!(!is_mutating(&Method::POST, "/v1/assets/query"));
+    }
+
+    #[test]
+    fn test_read_only_mode() {
+        let mut config = Config::default();
+        config.read_only = true;
+        let config = SharedConfig::from(config);
+        let mode = ReadOnlyMode::new(config.clone());
+        assert!(mode.is_enabled());
+
+        // The configuration is used until the mode is toggled at runtime
+        let mut new = config.get();
+        new.read_only = false;
+        config.apply(new);
+        assert!(!mode.is_enabled());
+
+        mode.set(true);
+        assert!(mode.is_enabled());
+
+        let mut new = config.get();
+        new.read_only = false;
+        config.apply(new);
+        assert!(mode.is_enabled());
+
+        mode.set(false);
+        assert!(!mode.is_enabled());
+    }
+}
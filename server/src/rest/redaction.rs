@@ -0,0 +1,232 @@
+//! Redaction of sensitive response fields
+//!
+//! The `response_redact_fields` of the [`SharedConfig`] map JSON fields to
+//! the [`Scope`] required to read them, like `serial_number=sensitive:read`
+//! or `email=operators:admin`. Values of these fields are replaced by
+//! [`REDACTED`], at any depth of a JSON response, if the API token of the
+//! request is not granted the scope. Handlers don't need to know which
+//! fields are sensitive.
+//!
+//! Without configured API tokens there are no scopes to check, so responses
+//! are not redacted, as is the case for the public routes.
+
+use super::audit::{is_json, redact, REDACTED};
+use super::auth::{Scope, TokenScopes};
+use crate::config::SharedConfig;
+use axum::{
+    body::{self, Body, Full},
+    http::{header::CONTENT_LENGTH, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use std::str::FromStr;
+
+/// A response field and the scope required to read it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactedField {
+    /// Name of the JSON field, compared case insensitive
+    pub field: String,
+    /// Scope required to read the field
+    pub scope: Scope,
+}
+
+impl FromStr for RedactedField {
+    type Err = String;
+
+    /// Parse a configured field like `<field>=<scope>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (field, scope) = s
+            .split_once('=')
+            .ok_or_else(|| "expected '<field>=<scope>'".to_string())?;
+        let field = field.trim();
+        if field.is_empty() {
+            return Err("empty field".to_string());
+        }
+
+        Ok(Self {
+            field: field.to_string(),
+            scope: Scope::from_str(scope.trim())?,
+        })
+    }
+}
+
+/// Redaction of the responses using the `response_redact_fields` of the
+/// [`SharedConfig`]
+#[derive(Debug, Clone)]
+pub struct ResponseRedaction {
+    config: SharedConfig,
+}
+
+impl ResponseRedaction {
+    /// Create a new redaction for the provided configuration
+    pub fn new(config: SharedConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get the fields which can not be read with the provided scopes,
+    /// skipping invalid entries
+    fn hidden_fields(&self, scopes: &[Scope]) -> Vec<String> {
+        self.config.read(|config| {
+            config
+                .response_redact_fields
+                .iter()
+                .filter_map(|entry| match RedactedField::from_str(entry) {
+                    Ok(field) => Some(field),
+                    Err(e) => {
+                        rest_warn!("ignoring invalid redacted field '{}': {}", entry, e);
+                        None
+                    }
+                })
+                .filter(|field| !scopes.contains(&field.scope))
+                .map(|field| field.field)
+                .collect()
+        })
+    }
+
+    /// Middleware function redacting the fields of JSON responses the API
+    /// token of the request is not allowed to read.
+    pub async fn redact(self, req: Request<Body>, next: Next<Body>) -> Response {
+        let Some(TokenScopes(scopes)) = req.extensions().get::<TokenScopes>().cloned() else {
+            return next.run(req).await;
+        };
+
+        let fields = self.hidden_fields(&scopes);
+        if fields.is_empty() {
+            return next.run(req).await;
+        }
+
+        let response = next.run(req).await;
+        if !is_json(response.headers()) {
+            return response;
+        }
+
+        let (mut parts, response_body) = response.into_parts();
+        let bytes = match hyper::body::to_bytes(response_body).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                rest_error!("could not read response body to redact: {}", e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+        let Ok(mut json) = serde_json::from_slice::<Value>(&bytes) else {
+            return Response::from_parts(parts, body::boxed(Full::from(bytes)));
+        };
+        redact(&mut json, &fields);
+
+        let bytes = match serde_json::to_vec(&json) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                rest_error!("could not serialize redacted response: {}", e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+        parts.headers.remove(CONTENT_LENGTH);
+
+        Response::from_parts(parts, body::boxed(Full::from(bytes)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::auth::ApiAuth;
+    use crate::Config;
+    use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
+    use axum::{middleware, routing, Router};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_parse_redacted_field() {
+        assert_eq!(
+            RedactedField::from_str(" serial_number = sensitive:read").unwrap(),
+            RedactedField {
+                field: "serial_number".to_string(),
+                scope: Scope::SensitiveRead,
+            }
+        );
+        assert!(RedactedField::from_str("serial_number").is_err());
+        assert!(RedactedField::from_str("=sensitive:read").is_err());
+        assert!(RedactedField::from_str("serial_number=sensitive:write").is_err());
+    }
+
+    fn app(tokens: &[&str]) -> Router {
+        let mut config = Config::default();
+        config.api_tokens = tokens.iter().map(|token| token.to_string()).collect();
+        config.response_redact_fields = vec![
+            "serial_number=sensitive:read".to_string(),
+            "email=operators:admin".to_string(),
+            "invalid".to_string(),
+        ];
+        let config = SharedConfig::from(config);
+        let auth = ApiAuth::new(config.clone());
+        let redaction = ResponseRedaction::new(config);
+
+        Router::new()
+            .route(
+                "/assets/aircraft/id",
+                routing::get(|| async {
+                    axum::Json(json!({
+                        "serial_number": "SN-1",
+                        "contacts": [{ "email": "ops@example.com" }],
+                        "name": "Aircraft",
+                    }))
+                }),
+            )
+            .layer(middleware::from_fn(
+                move |req: Request<Body>, next: Next<Body>| redaction.clone().redact(req, next),
+            ))
+            .layer(middleware::from_fn(
+                move |req: Request<Body>, next: Next<Body>| auth.clone().authorize(req, next),
+            ))
+    }
+
+    async fn get(app: Router, token: Option<&str>) -> Value {
+        let mut req = Request::get("/assets/aircraft/id");
+        if let Some(token) = token {
+            req = req.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+
+        let response = app.oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("application/json"));
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_response_redaction() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let tokens = [
+            "partner=assets:read",
+            "ops=assets:read sensitive:read operators:admin",
+        ];
+
+        let body = get(app(&tokens), Some("partner")).await;
+        assert_eq!(
+            body,
+            json!({
+                "serial_number": REDACTED,
+                "contacts": [{ "email": REDACTED }],
+                "name": "Aircraft",
+            })
+        );
+
+        let body = get(app(&tokens), Some("ops")).await;
+        assert_eq!(body["serial_number"], "SN-1");
+        assert_eq!(body["contacts"][0]["email"], "ops@example.com");
+
+        // without tokens the API is open
+        let body = get(app(&[]), None).await;
+        assert_eq!(body["serial_number"], "SN-1");
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,175 @@
+//! Regions
+//!
+//! Regions group vertiports by area for city level reporting. svc-storage
+//! does not store regions, so they are kept in the [`REGIONS_TABLE`] of the
+//! persisted state, see [`crate::state`]. The regions are not shared between
+//! replicas, so reports only cover the regions of the instance serving them.
+
+pub use super::api::rest_types::{Region, RegionPayload};
+
+use crate::state::{StateError, StateTable};
+use lib_common::time::{DateTime, Utc};
+use lib_common::uuid::Uuid;
+
+/// Name of the table of the regions in the state directory
+pub const REGIONS_TABLE: &str = "regions";
+
+/// Maximum number of regions
+pub const MAX_REGIONS: usize = 1000;
+
+/// Error returned when changing the regions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionError {
+    /// The region does not exist
+    NotFound,
+    /// The number of regions reached [`MAX_REGIONS`]
+    TooMany,
+    /// The regions could not be persisted
+    State(StateError),
+}
+
+/// Store of the regions by id.
+///
+/// The default store is kept in memory, [`Regions::open`] persists the
+/// regions.
+#[derive(Debug, Clone, Default)]
+pub struct Regions {
+    regions: StateTable<Region>,
+}
+
+impl Regions {
+    /// Open the regions persisted in the state directory, kept in memory if
+    /// no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            regions: StateTable::open(dir, REGIONS_TABLE)?,
+        })
+    }
+
+    /// Get all regions, ordered by name
+    pub fn list(&self) -> Vec<Region> {
+        let mut regions: Vec<Region> = self
+            .regions
+            .read(|regions| regions.values().cloned().collect());
+        regions.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+        regions
+    }
+
+    /// Get a region by id
+    pub fn get(&self, id: &str) -> Option<Region> {
+        self.regions.get(id)
+    }
+
+    /// Add a region, returning the new region
+    pub fn add(&self, payload: RegionPayload, now: DateTime<Utc>) -> Result<Region, RegionError> {
+        let region = Region {
+            id: Uuid::new_v4().to_string(),
+            name: payload.name,
+            geo_location: payload.geo_location,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.regions
+            .update(|regions| match regions.len() < MAX_REGIONS {
+                true => {
+                    regions.insert(region.id.clone(), region.clone());
+                    Ok(region)
+                }
+                false => Err(RegionError::TooMany),
+            })
+            .map_err(RegionError::State)?
+    }
+
+    /// Replace the name and area of a region, returning the updated region
+    pub fn update(
+        &self,
+        id: &str,
+        payload: RegionPayload,
+        now: DateTime<Utc>,
+    ) -> Result<Region, RegionError> {
+        if self.regions.get(id).is_none() {
+            return Err(RegionError::NotFound);
+        }
+
+        self.regions
+            .update(|regions| {
+                let region = regions.get_mut(id).ok_or(RegionError::NotFound)?;
+                region.name = payload.name;
+                region.geo_location = payload.geo_location;
+                region.updated_at = now;
+                Ok(region.clone())
+            })
+            .map_err(RegionError::State)?
+    }
+
+    /// Remove a region
+    pub fn remove(&self, id: &str) -> Result<(), RegionError> {
+        if self.regions.get(id).is_none() {
+            return Err(RegionError::NotFound);
+        }
+
+        self.regions
+            .remove(id)
+            .map_err(RegionError::State)?
+            .map(|_| ())
+            .ok_or(RegionError::NotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::GeoPolygon;
+
+    fn payload(name: &str) -> RegionPayload {
+        RegionPayload {
+            name: name.to_string(),
+            geo_location: GeoPolygon::default(),
+        }
+    }
+
+    #[test]
+    fn test_regions() {
+        let regions = Regions::default();
+        let now = Utc::now();
+
+        let amsterdam = regions.add(payload("Amsterdam"), now).unwrap();
+        regions.add(payload("Almere"), now).unwrap();
+        let names: Vec<String> = regions.list().into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["Almere", "Amsterdam"]);
+
+        let later = now + chrono::Duration::minutes(1);
+        let updated = regions
+            .update(&amsterdam.id, payload("Amsterdam Area"), later)
+            .unwrap();
+        assert_eq!(updated.name, "Amsterdam Area");
+        assert_eq!(updated.created_at, now);
+        assert_eq!(updated.updated_at, later);
+        assert_eq!(regions.get(&amsterdam.id), Some(updated));
+
+        assert_eq!(regions.remove(&amsterdam.id), Ok(()));
+        assert_eq!(regions.remove(&amsterdam.id), Err(RegionError::NotFound));
+        assert_eq!(
+            regions.update(&amsterdam.id, payload("Amsterdam"), now),
+            Err(RegionError::NotFound)
+        );
+        assert_eq!(regions.get(&amsterdam.id), None);
+    }
+
+    #[test]
+    fn test_regions_persisted() {
+        let dir = crate::state::test_state_dir();
+        let now = Utc::now();
+
+        let regions = Regions::open(Some(&dir)).unwrap();
+        let amsterdam = regions.add(payload("Amsterdam"), now).unwrap();
+        let almere = regions.add(payload("Almere"), now).unwrap();
+        regions.remove(&almere.id).unwrap();
+
+        let regions = Regions::open(Some(&dir)).unwrap();
+        assert_eq!(regions.list(), vec![amsterdam]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
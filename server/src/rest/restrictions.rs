@@ -0,0 +1,399 @@
+//! Vertiport restrictions
+//!
+//! Curfews and noise abatement rules of the vertiports, each in effect
+//! during the windows of a schedule. svc-scheduler evaluates the rules in
+//! effect at a time before planning takeoffs and landings at a vertiport.
+//!
+//! svc-storage does not store restriction rules, so they are kept in the
+//! [`RESTRICTION_RULES_TABLE`] of the persisted state, see [`crate::state`].
+//! svc-scheduler only sees the rules of the replica it calls.
+
+pub use super::api::rest_types::{
+    ActiveRestriction, RestrictionKind, RestrictionRule, RestrictionRulePayload, RestrictionsQuery,
+    VertiportRestrictions,
+};
+
+use crate::schedule::{OccurrenceBudget, Schedule};
+use crate::state::{StateError, StateTable};
+use chrono::Duration;
+use lib_common::time::{DateTime, Utc};
+use lib_common::uuid::Uuid;
+
+/// Name of the table of the restriction rules in the state directory
+pub const RESTRICTION_RULES_TABLE: &str = "restriction_rules";
+
+/// Maximum number of restriction rules of a vertiport
+pub const MAX_RULES_PER_VERTIPORT: usize = 32;
+
+/// Number of days after the evaluated time the end of a rule in effect is
+/// looked up
+const HORIZON_DAYS: i64 = 7;
+
+/// Error returned when changing the restriction rules of a vertiport
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestrictionError {
+    /// The rule does not exist
+    NotFound,
+    /// The number of rules of the vertiport reached
+    /// [`MAX_RULES_PER_VERTIPORT`]
+    TooMany,
+    /// The rules could not be persisted
+    State(StateError),
+}
+
+/// Store of the restriction rules by vertiport id.
+///
+/// The default store is kept in memory, [`RestrictionRules::open`] persists
+/// the rules.
+#[derive(Debug, Clone, Default)]
+pub struct RestrictionRules {
+    vertiports: StateTable<Vec<RestrictionRule>>,
+}
+
+impl RestrictionRules {
+    /// Open the rules persisted in the state directory, kept in memory if no
+    /// directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            vertiports: StateTable::open(dir, RESTRICTION_RULES_TABLE)?,
+        })
+    }
+
+    /// Get the rules of a vertiport, in the order they were added
+    pub fn list(&self, vertiport_id: &str) -> Vec<RestrictionRule> {
+        self.vertiports.get(vertiport_id).unwrap_or_default()
+    }
+
+    /// Get a rule of a vertiport by id
+    pub fn get(&self, vertiport_id: &str, rule_id: &str) -> Option<RestrictionRule> {
+        self.list(vertiport_id)
+            .into_iter()
+            .find(|rule| rule.id == rule_id)
+    }
+
+    /// Add a rule to a vertiport, returning the new rule
+    pub fn add(
+        &self,
+        vertiport_id: &str,
+        payload: RestrictionRulePayload,
+        now: DateTime<Utc>,
+    ) -> Result<RestrictionRule, RestrictionError> {
+        if self.list(vertiport_id).len() >= MAX_RULES_PER_VERTIPORT {
+            return Err(RestrictionError::TooMany);
+        }
+
+        let rule = RestrictionRule {
+            id: Uuid::new_v4().to_string(),
+            vertiport_id: vertiport_id.to_string(),
+            name: payload.name,
+            kind: payload.kind,
+            schedule: payload.schedule,
+            max_movements_per_hour: payload.max_movements_per_hour,
+            created_at: now,
+            updated_at: now,
+        };
+        self.vertiports
+            .update(|vertiports| {
+                let rules = vertiports.entry(vertiport_id.to_string()).or_default();
+                match rules.len() < MAX_RULES_PER_VERTIPORT {
+                    true => {
+                        rules.push(rule.clone());
+                        Ok(rule)
+                    }
+                    false => Err(RestrictionError::TooMany),
+                }
+            })
+            .map_err(RestrictionError::State)?
+    }
+
+    /// Replace a rule of a vertiport, returning the updated rule
+    pub fn update(
+        &self,
+        vertiport_id: &str,
+        rule_id: &str,
+        payload: RestrictionRulePayload,
+        now: DateTime<Utc>,
+    ) -> Result<RestrictionRule, RestrictionError> {
+        if self.get(vertiport_id, rule_id).is_none() {
+            return Err(RestrictionError::NotFound);
+        }
+
+        self.vertiports
+            .update(|vertiports| {
+                let rule = vertiports
+                    .get_mut(vertiport_id)
+                    .and_then(|rules| rules.iter_mut().find(|rule| rule.id == rule_id))
+                    .ok_or(RestrictionError::NotFound)?;
+                rule.name = payload.name;
+                rule.kind = payload.kind;
+                rule.schedule = payload.schedule;
+                rule.max_movements_per_hour = payload.max_movements_per_hour;
+                rule.updated_at = now;
+                Ok(rule.clone())
+            })
+            .map_err(RestrictionError::State)?
+    }
+
+    /// Remove a rule of a vertiport
+    pub fn remove(&self, vertiport_id: &str, rule_id: &str) -> Result<(), RestrictionError> {
+        if self.get(vertiport_id, rule_id).is_none() {
+            return Err(RestrictionError::NotFound);
+        }
+
+        self.vertiports
+            .update(|vertiports| {
+                let rules = vertiports
+                    .get_mut(vertiport_id)
+                    .ok_or(RestrictionError::NotFound)?;
+                let index = rules
+                    .iter()
+                    .position(|rule| rule.id == rule_id)
+                    .ok_or(RestrictionError::NotFound)?;
+                rules.remove(index);
+                if rules.is_empty() {
+                    vertiports.remove(vertiport_id);
+                }
+                Ok(())
+            })
+            .map_err(RestrictionError::State)?
+    }
+
+    /// Remove all rules of a removed vertiport.
+    ///
+    /// The vertiport is removed even if its rules could not be written, so
+    /// the error is only logged.
+    pub fn remove_vertiport(&self, vertiport_id: &str) {
+        if let Err(e) = self.vertiports.remove(vertiport_id) {
+            rest_warn!(
+                "could not remove the restriction rules of vertiport {}: {e}",
+                vertiport_id
+            );
+        }
+    }
+
+    /// Get the restrictions of a vertiport in effect at the provided time
+    pub fn evaluate(&self, vertiport_id: &str, at: DateTime<Utc>) -> VertiportRestrictions {
+        let horizon = at + Duration::days(HORIZON_DAYS);
+        let mut budget = OccurrenceBudget::default();
+        let active_rules: Vec<ActiveRestriction> = self
+            .list(vertiport_id)
+            .into_iter()
+            .filter_map(|rule| {
+                let schedule = match rule.schedule.parse::<Schedule>() {
+                    Ok(schedule) => schedule,
+                    Err(e) => {
+                        rest_warn!(
+                            "ignoring restriction rule {} with invalid schedule: {e}",
+                            rule.id
+                        );
+                        return None;
+                    }
+                };
+
+                // windows are clipped to the range, so a window in effect
+                // starts at the evaluated time
+                let windows = match schedule.windows(at, horizon, &mut budget) {
+                    Ok(windows) => windows,
+                    Err(e) => {
+                        rest_warn!("ignoring restriction rule {}: {e}", rule.id);
+                        return None;
+                    }
+                };
+                let window = windows
+                    .into_iter()
+                    .next()
+                    .filter(|window| window.start <= at)?;
+                Some(ActiveRestriction {
+                    rule,
+                    until: Some(window.end).filter(|end| *end < horizon),
+                })
+            })
+            .collect();
+
+        let curfew = active_rules
+            .iter()
+            .any(|active| active.rule.kind == RestrictionKind::Curfew);
+        let max_movements_per_hour = match curfew {
+            true => Some(0),
+            false => active_rules
+                .iter()
+                .filter_map(|active| active.rule.max_movements_per_hour)
+                .min(),
+        };
+
+        VertiportRestrictions {
+            vertiport_id: vertiport_id.to_string(),
+            at,
+            curfew,
+            max_movements_per_hour,
+            active_rules,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Daily rule from 22:00 to 06:00 UTC
+    const NIGHT: &str = "DTSTART:20240101T220000Z;DURATION:PT8H\nRRULE:FREQ=DAILY";
+
+    /// Weekday rule from 07:00 to 09:00 UTC
+    const RUSH_HOUR: &str =
+        "DTSTART:20240101T070000Z;DURATION:PT2H\nRRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR";
+
+    fn payload(
+        kind: RestrictionKind,
+        schedule: &str,
+        max_movements_per_hour: Option<u32>,
+    ) -> RestrictionRulePayload {
+        RestrictionRulePayload {
+            name: format!("{kind:?}"),
+            kind,
+            schedule: schedule.to_string(),
+            max_movements_per_hour,
+        }
+    }
+
+    fn time(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_restriction_rules() {
+        let rules = RestrictionRules::default();
+        let now = Utc::now();
+        assert!(rules.list("port").is_empty());
+
+        let curfew = rules
+            .add("port", payload(RestrictionKind::Curfew, NIGHT, None), now)
+            .unwrap();
+        let noise = rules
+            .add(
+                "port",
+                payload(RestrictionKind::NoiseAbatement, RUSH_HOUR, Some(12)),
+                now,
+            )
+            .unwrap();
+        assert_eq!(rules.list("port"), vec![curfew.clone(), noise.clone()]);
+        assert_eq!(rules.get("port", &curfew.id), Some(curfew.clone()));
+        assert_eq!(rules.get("other", &curfew.id), None);
+
+        let later = now + Duration::minutes(1);
+        let updated = rules
+            .update(
+                "port",
+                &noise.id,
+                payload(RestrictionKind::NoiseAbatement, RUSH_HOUR, Some(6)),
+                later,
+            )
+            .unwrap();
+        assert_eq!(updated.max_movements_per_hour, Some(6));
+        assert_eq!(updated.created_at, now);
+        assert_eq!(updated.updated_at, later);
+        assert_eq!(
+            rules.update(
+                "other",
+                &noise.id,
+                payload(RestrictionKind::Curfew, NIGHT, None),
+                now
+            ),
+            Err(RestrictionError::NotFound)
+        );
+
+        assert_eq!(rules.remove("port", &curfew.id), Ok(()));
+        assert_eq!(
+            rules.remove("port", &curfew.id),
+            Err(RestrictionError::NotFound)
+        );
+        rules.remove_vertiport("port");
+        assert!(rules.list("port").is_empty());
+
+        for _ in 0..MAX_RULES_PER_VERTIPORT {
+            rules
+                .add("port", payload(RestrictionKind::Curfew, NIGHT, None), now)
+                .unwrap();
+        }
+        assert_eq!(
+            rules.add("port", payload(RestrictionKind::Curfew, NIGHT, None), now),
+            Err(RestrictionError::TooMany)
+        );
+    }
+
+    #[test]
+    fn test_restriction_rules_persisted() {
+        let dir = crate::state::test_state_dir();
+        let now = Utc::now();
+
+        let rules = RestrictionRules::open(Some(&dir)).unwrap();
+        let curfew = rules
+            .add("port", payload(RestrictionKind::Curfew, NIGHT, None), now)
+            .unwrap();
+        let noise = rules
+            .add(
+                "port",
+                payload(RestrictionKind::NoiseAbatement, RUSH_HOUR, Some(12)),
+                now,
+            )
+            .unwrap();
+        rules.remove("port", &noise.id).unwrap();
+
+        // the curfew is still in effect after a restart
+        let rules = RestrictionRules::open(Some(&dir)).unwrap();
+        assert_eq!(rules.list("port"), vec![curfew]);
+        assert!(rules.evaluate("port", time("2024-06-04T23:00:00Z")).curfew);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_evaluate_restrictions() {
+        let rules = RestrictionRules::default();
+        let now = Utc::now();
+        rules
+            .add("port", payload(RestrictionKind::Curfew, NIGHT, None), now)
+            .unwrap();
+        rules
+            .add(
+                "port",
+                payload(RestrictionKind::NoiseAbatement, RUSH_HOUR, Some(12)),
+                now,
+            )
+            .unwrap();
+        rules
+            .add(
+                "port",
+                payload(RestrictionKind::NoiseAbatement, RUSH_HOUR, Some(6)),
+                now,
+            )
+            .unwrap();
+
+        // Tuesday at noon
+        let restrictions = rules.evaluate("port", time("2024-06-04T12:00:00Z"));
+        assert!(!restrictions.curfew);
+        assert_eq!(restrictions.max_movements_per_hour, None);
+        assert!(restrictions.active_rules.is_empty());
+
+        // Tuesday at 23:00
+        let restrictions = rules.evaluate("port", time("2024-06-04T23:00:00Z"));
+        assert!(restrictions.curfew);
+        assert_eq!(restrictions.max_movements_per_hour, Some(0));
+        assert_eq!(restrictions.active_rules.len(), 1);
+        assert_eq!(
+            restrictions.active_rules[0].until,
+            Some(time("2024-06-05T06:00:00Z"))
+        );
+
+        // Wednesday at 08:00, the lowest limit applies
+        let restrictions = rules.evaluate("port", time("2024-06-05T08:00:00Z"));
+        assert!(!restrictions.curfew);
+        assert_eq!(restrictions.max_movements_per_hour, Some(6));
+        assert_eq!(restrictions.active_rules.len(), 2);
+
+        // Saturday at 08:00
+        let restrictions = rules.evaluate("port", time("2024-06-08T08:00:00Z"));
+        assert_eq!(restrictions.max_movements_per_hour, None);
+
+        assert!(rules.evaluate("other", now).active_rules.is_empty());
+    }
+}
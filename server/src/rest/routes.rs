@@ -0,0 +1,148 @@
+//! Route tables of the REST API
+//!
+//! Routes are declared as [`ApiRoute`]s, one per method, and mounted with
+//! [`mount`]. Unlike an axum [`Router`], a route table can be inspected, so
+//! the tests compare the mounted routes with the paths of the
+//! [`ApiDoc`](super::ApiDoc).
+
+use crate::features::{self, Feature};
+use axum::{
+    body::Body,
+    handler::Handler,
+    http::{Method, Request},
+    middleware::{self, Next},
+    routing::{self, MethodRouter},
+    Router,
+};
+
+/// A route of the REST API for a single method
+pub struct ApiRoute {
+    /// Method of the route
+    pub method: Method,
+    /// Path of the route, with axum `:param` parameters
+    pub path: &'static str,
+    router: MethodRouter,
+}
+
+impl ApiRoute {
+    /// Create a route from a method router serving `method`
+    fn new(method: Method, path: &'static str, router: MethodRouter) -> Self {
+        Self {
+            method,
+            path,
+            router,
+        }
+    }
+
+    /// Create a `GET` route
+    pub fn get<H, T>(path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Body>,
+        T: 'static,
+    {
+        Self::new(Method::GET, path, routing::get(handler))
+    }
+
+    /// Create a `HEAD` route
+    pub fn head<H, T>(path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Body>,
+        T: 'static,
+    {
+        Self::new(Method::HEAD, path, routing::head(handler))
+    }
+
+    /// Create a `POST` route
+    pub fn post<H, T>(path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Body>,
+        T: 'static,
+    {
+        Self::new(Method::POST, path, routing::post(handler))
+    }
+
+    /// Create a `PUT` route
+    pub fn put<H, T>(path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Body>,
+        T: 'static,
+    {
+        Self::new(Method::PUT, path, routing::put(handler))
+    }
+
+    /// Create a `PATCH` route
+    pub fn patch<H, T>(path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Body>,
+        T: 'static,
+    {
+        Self::new(Method::PATCH, path, routing::patch(handler))
+    }
+
+    /// Create a `DELETE` route
+    pub fn delete<H, T>(path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Body>,
+        T: 'static,
+    {
+        Self::new(Method::DELETE, path, routing::delete(handler))
+    }
+
+    /// Create an `OPTIONS` route from a method router, see
+    /// [`super::api::methods::options`]
+    pub fn options(path: &'static str, router: MethodRouter) -> Self {
+        Self::new(Method::OPTIONS, path, router)
+    }
+
+    /// Change the method router of the route, for example to add a layer
+    pub fn map(mut self, f: impl FnOnce(MethodRouter) -> MethodRouter) -> Self {
+        self.router = f(self.router);
+        self
+    }
+
+    /// Serve the route only while a feature is enabled, see
+    /// [`crate::features`]
+    pub fn feature(self, feature: Feature) -> Self {
+        self.map(|router| {
+            router.layer(middleware::from_fn(
+                move |req: Request<Body>, next: Next<Body>| features::require(feature, req, next),
+            ))
+        })
+    }
+
+    /// Get the path of the route in the OpenAPI format, with `{param}`
+    /// parameters
+    pub fn openapi_path(&self) -> String {
+        self.path
+            .split('/')
+            .map(|segment| match segment.strip_prefix(':') {
+                Some(param) => format!("{{{param}}}"),
+                None => segment.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join("/")
+    }
+}
+
+/// Mount a route table on a new router, the routes of a path are merged
+pub fn mount(routes: Vec<ApiRoute>) -> Router {
+    routes.into_iter().fold(Router::new(), |router, route| {
+        router.route(route.path, route.router)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_path() {
+        let route = ApiRoute::get("/assets/:id/notes/:note_id", || async {});
+        assert_eq!(route.openapi_path(), "/assets/{id}/notes/{note_id}");
+        assert_eq!(route.method, Method::GET);
+        assert_eq!(
+            ApiRoute::get("/health", || async {}).openapi_path(),
+            "/health"
+        );
+    }
+}
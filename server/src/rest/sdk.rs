@@ -0,0 +1,336 @@
+//! Client SDKs
+//!
+//! Prepare the OpenAPI specification for the client SDK generators, so
+//! partner teams can publish generated clients instead of writing their own.
+//!
+//! The specification is patched with `x-enum-varnames` naming the variants of
+//! the string enums in the style of the target language, and tagged with the
+//! version of the SDK. A configuration for
+//! [openapi-generator](https://openapi-generator.tech) is written next to the
+//! specification, naming and versioning the package.
+
+use super::OpenApiError;
+use clap::ValueEnum;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::path::Path;
+use utoipa::OpenApi;
+
+/// npm package name of the TypeScript SDK
+const NPM_PACKAGE: &str = "@aetheric/svc-assets-client";
+
+/// Project and package names of the Python SDK
+const PYTHON_PROJECT: &str = "svc-assets-client";
+const PYTHON_PACKAGE: &str = "svc_assets_client";
+
+/// Language of a client SDK
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SdkLanguage {
+    /// TypeScript client using the fetch API
+    Typescript,
+    /// Python client
+    Python,
+}
+
+impl SdkLanguage {
+    /// Name of the openapi-generator generator for this language
+    pub fn generator(&self) -> &'static str {
+        match self {
+            SdkLanguage::Typescript => "typescript-fetch",
+            SdkLanguage::Python => "python",
+        }
+    }
+
+    /// Name of the enum variant with the provided value, `PascalCase` for
+    /// TypeScript and `UPPER_SNAKE_CASE` for Python
+    pub fn variant_name(&self, value: &str) -> String {
+        let words = words(value);
+        let name = match self {
+            SdkLanguage::Typescript => words
+                .iter()
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars.next().map_or_else(String::new, |first| {
+                        first.to_ascii_uppercase().to_string()
+                            + &chars.as_str().to_ascii_lowercase()
+                    })
+                })
+                .collect::<String>(),
+            SdkLanguage::Python => words
+                .iter()
+                .map(|word| word.to_ascii_uppercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+        };
+
+        match name.chars().next() {
+            None => self.variant_name("empty"),
+            Some(first) if first.is_ascii_digit() => match self {
+                SdkLanguage::Typescript => format!("Value{name}"),
+                SdkLanguage::Python => format!("VALUE_{name}"),
+            },
+            Some(_) => name,
+        }
+    }
+
+    /// Version of the SDK package for the provided service version
+    ///
+    /// npm packages use the semantic version of the service. Python packages
+    /// use the matching PEP 440 version, `1.2.0-rc.1` becomes `1.2.0rc1` and
+    /// other pre-releases become development releases.
+    pub fn package_version(&self, version: &str) -> String {
+        match self {
+            SdkLanguage::Typescript => version.to_string(),
+            SdkLanguage::Python => {
+                let version = version.split('+').next().unwrap_or_default();
+                let Some((release, pre)) = version.split_once('-') else {
+                    return version.to_string();
+                };
+
+                let (label, number) = pre.split_once('.').unwrap_or((pre, "0"));
+                let number: u64 = number.parse().unwrap_or(0);
+                match label {
+                    "alpha" | "a" => format!("{release}a{number}"),
+                    "beta" | "b" => format!("{release}b{number}"),
+                    "rc" => format!("{release}rc{number}"),
+                    _ => format!("{release}.dev{number}"),
+                }
+            }
+        }
+    }
+
+    /// openapi-generator configuration of the SDK package
+    pub fn config(&self, version: &str) -> Value {
+        let version = self.package_version(version);
+        match self {
+            SdkLanguage::Typescript => json!({
+                "npmName": NPM_PACKAGE,
+                "npmVersion": version,
+                "supportsES6": true,
+                "enumPropertyNaming": "original",
+            }),
+            SdkLanguage::Python => json!({
+                "projectName": PYTHON_PROJECT,
+                "packageName": PYTHON_PACKAGE,
+                "packageVersion": version,
+            }),
+        }
+    }
+}
+
+/// Split a value into words at non alphanumeric characters and at the start
+/// of capitalized words
+fn words(value: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in value.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+
+        if c.is_ascii_uppercase() && previous_lower {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Add `x-enum-varnames` to the string enums of a specification
+///
+/// Enums already naming their variants are left unchanged, as are enums
+/// whose values would result in duplicate names.
+pub fn patch_enum_names(value: &mut Value, language: SdkLanguage) {
+    match value {
+        Value::Object(object) => {
+            patch_enum(object, language);
+            for child in object.values_mut() {
+                patch_enum_names(child, language);
+            }
+        }
+        Value::Array(array) => {
+            for child in array.iter_mut() {
+                patch_enum_names(child, language);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Add `x-enum-varnames` to a schema if it is a string enum
+fn patch_enum(object: &mut Map<String, Value>, language: SdkLanguage) {
+    if object.contains_key("x-enum-varnames") {
+        return;
+    }
+
+    let Some(Value::Array(values)) = object.get("enum") else {
+        return;
+    };
+
+    let Some(names) = values
+        .iter()
+        .map(|value| value.as_str().map(|value| language.variant_name(value)))
+        .collect::<Option<Vec<String>>>()
+    else {
+        return;
+    };
+
+    if names.iter().collect::<HashSet<_>>().len() != names.len() {
+        rest_warn!("not naming the variants of enum {values:?}, names are not unique");
+        return;
+    }
+
+    object.insert("x-enum-varnames".to_string(), json!(names));
+}
+
+/// Get the specification of `T` prepared for the SDK generator of a
+/// language
+pub fn sdk_spec<T>(language: SdkLanguage, version: &str) -> Result<Value, OpenApiError>
+where
+    T: OpenApi,
+{
+    let mut spec = serde_json::to_value(T::openapi()).map_err(|e| {
+        rest_error!("failed to export as JSON: {e}");
+        OpenApiError::Json
+    })?;
+
+    patch_enum_names(&mut spec, language);
+    spec["info"]["version"] = json!(language.package_version(version));
+    Ok(spec)
+}
+
+/// Path of the generator configuration written next to a specification
+pub fn config_path(target: &str) -> String {
+    let stem = target.strip_suffix(".json").unwrap_or(target);
+    format!("{stem}-config.json")
+}
+
+/// Create the OpenAPI 3.0 Specification File prepared for the SDK
+/// generator of a language, and its generator configuration
+///
+/// The configuration is written to [`config_path`] of the target.
+pub fn generate_sdk_spec<T>(target: &str, language: SdkLanguage) -> Result<(), OpenApiError>
+where
+    T: OpenApi,
+{
+    let version = env!("CARGO_PKG_VERSION");
+    let spec = sdk_spec::<T>(language, version)?;
+    let config = language.config(version);
+
+    for (path, value) in [(target.to_string(), spec), (config_path(target), config)] {
+        let output = serde_json::to_string_pretty(&value).map_err(|e| {
+            rest_error!("failed to export as JSON string: {e}");
+            OpenApiError::Json
+        })?;
+
+        std::fs::write(Path::new(&path), output).map_err(|e| {
+            rest_error!("failed to write to file {path}: {e}");
+            OpenApiError::FileWrite
+        })?;
+    }
+
+    rest_info!(
+        "wrote the specification for the {} generator to {target}.",
+        language.generator()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::ApiDoc;
+
+    #[test]
+    fn test_variant_name() {
+        let cases = [
+            ("noise_abatement", "NoiseAbatement", "NOISE_ABATEMENT"),
+            ("LimitedOps", "LimitedOps", "LIMITED_OPS"),
+            ("MCS", "Mcs", "MCS"),
+            ("ccs-2", "Ccs2", "CCS_2"),
+            ("2d", "Value2d", "VALUE_2D"),
+            ("", "Empty", "EMPTY"),
+        ];
+        for (value, typescript, python) in cases {
+            assert_eq!(SdkLanguage::Typescript.variant_name(value), typescript);
+            assert_eq!(SdkLanguage::Python.variant_name(value), python);
+        }
+    }
+
+    #[test]
+    fn test_package_version() {
+        let cases = [
+            ("0.5.0", "0.5.0"),
+            ("1.2.0-rc.1", "1.2.0rc1"),
+            ("1.2.0-beta.3", "1.2.0b3"),
+            ("1.2.0-alpha", "1.2.0a0"),
+            ("1.2.0-develop.7", "1.2.0.dev7"),
+            ("1.2.0+build.5", "1.2.0"),
+        ];
+        for (version, python) in cases {
+            assert_eq!(SdkLanguage::Typescript.package_version(version), version);
+            assert_eq!(SdkLanguage::Python.package_version(version), python);
+        }
+    }
+
+    #[test]
+    fn test_patch_enum_names() {
+        let mut spec = json!({
+            "kind": { "type": "string", "enum": ["curfew", "noise_abatement"] },
+            "nested": [{ "enum": ["a-b", "a_b"] }],
+            "named": { "enum": ["x"], "x-enum-varnames": ["Custom"] },
+            "numbers": { "type": "integer", "enum": [1, 2] },
+        });
+        patch_enum_names(&mut spec, SdkLanguage::Typescript);
+
+        assert_eq!(
+            spec["kind"]["x-enum-varnames"],
+            json!(["Curfew", "NoiseAbatement"])
+        );
+        assert!(spec["nested"][0].get("x-enum-varnames").is_none());
+        assert_eq!(spec["named"]["x-enum-varnames"], json!(["Custom"]));
+        assert!(spec["numbers"].get("x-enum-varnames").is_none());
+    }
+
+    #[test]
+    fn test_sdk_spec() {
+        let spec = sdk_spec::<ApiDoc>(SdkLanguage::Python, "1.0.0-rc.2").unwrap();
+        assert_eq!(spec["info"]["version"], "1.0.0rc2");
+        assert_eq!(
+            spec["components"]["schemas"]["RestrictionKind"]["x-enum-varnames"],
+            json!(["CURFEW", "NOISE_ABATEMENT"])
+        );
+    }
+
+    #[test]
+    fn test_generate_sdk_spec() {
+        assert_eq!(config_path("out/assets.json"), "out/assets-config.json");
+        assert_eq!(config_path("assets"), "assets-config.json");
+
+        let error = generate_sdk_spec::<ApiDoc>("/nonsense/", SdkLanguage::Typescript).unwrap_err();
+        assert_eq!(error, OpenApiError::FileWrite);
+
+        let dir = std::env::temp_dir().join(format!("svc-assets-sdk-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("openapi.json").display().to_string();
+        generate_sdk_spec::<ApiDoc>(&target, SdkLanguage::Typescript).unwrap();
+
+        let config: Value =
+            serde_json::from_str(&std::fs::read_to_string(config_path(&target)).unwrap()).unwrap();
+        assert_eq!(config["npmName"], NPM_PACKAGE);
+        assert_eq!(config["npmVersion"], env!("CARGO_PKG_VERSION"));
+        let spec: Value = serde_json::from_str(&std::fs::read_to_string(&target).unwrap()).unwrap();
+        assert_eq!(spec["info"]["version"], env!("CARGO_PKG_VERSION"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,199 +1,905 @@
 //! Rest server implementation
 
+use super::admin::{admin_routes, AdminAuth};
 use super::api;
+use super::attachments::AssetAttachments;
+use super::audit::AuditLog;
+use super::auth::ApiAuth;
+use super::chargers::VertipadChargers;
+use super::classification::PadClassifications;
+use super::contacts::OperatorDirectory;
+use super::equipment::VertipadEquipment;
+use super::json::{BodyLimit, BodyLimits};
+use super::limits::RequestLimiter;
+use super::locale::Localizations;
+use super::logos::OperatorLogos;
+use super::quota::{AssetOwners, OperatorQuotas, ASSET_OWNERS_TABLE};
+use super::read_only::ReadOnlyMode;
+use super::redaction::ResponseRedaction;
+use super::regions::Regions;
+use super::restrictions::RestrictionRules;
+use super::routes::{mount, ApiRoute};
+use super::tenant::Tenancy;
+use super::timeout::RequestTimeout;
+use super::version::{negotiate_version, versioned_router, ApiVersion};
+use crate::archive::{RemovedAssets, RestoredAssets};
+use crate::config::SharedConfig;
+use crate::delegation::{delegation_monitor, GroupDelegations};
+use crate::fan_out::FanOut;
+use crate::features::{Feature, Features};
 use crate::grpc::client::GrpcClients;
+use crate::hold::MaintenanceHolds;
+use crate::maintenance::{maintenance_monitor, OverdueAircraft};
+use crate::object_store::object_store;
+use crate::ops_status::VertiportOpsStatuses;
+use crate::repo::tenant::TenantAssets;
+use crate::repo::Repositories;
+use crate::shared::SharedState;
 use crate::shutdown_signal;
-use crate::Config;
+use crate::state::StateError;
+use crate::tls::{Protocol, ServerTls};
+use crate::typeahead::{vertiport_index_monitor, VertiportIndex};
 use axum::{
-    error_handling::HandleErrorLayer,
+    body::Body,
     extract::Extension,
-    http::{HeaderValue, StatusCode},
-    routing, BoxError, Router,
+    http::{request::Parts, HeaderValue, Request},
+    middleware::{self, Next},
+    Router,
 };
+use hyper::server::accept;
 use std::net::SocketAddr;
-use tower::{
-    buffer::BufferLayer,
-    limit::{ConcurrencyLimitLayer, RateLimitLayer},
-    ServiceBuilder,
-};
-use tower_http::cors::{Any, CorsLayer};
+use tokio::net::TcpListener;
+use tower::make::Shared;
+use tower::ServiceBuilder;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 
+/// Get the route table of an API version.
+///
+/// Versions are mounted under their own prefix by [`versioned_router`]; add
+/// a match arm here when a version changes a payload.
+fn api_route_table(version: ApiVersion) -> Vec<ApiRoute> {
+    match version {
+        ApiVersion::V1 => vec![
+            ApiRoute::get("/health", api::health::health_check),
+            ApiRoute::get("/assets/operators/:id", api::operator::get_operator),
+            ApiRoute::get("/assets/demo/aircraft", api::aircraft::get_all_aircraft)
+                .feature(Feature::PublicApi),
+            ApiRoute::get(
+                "/assets/demo/vertiports",
+                api::vertiport::get_all_vertiports,
+            )
+            .feature(Feature::PublicApi),
+            ApiRoute::get("/assets/demo/vertipads", api::vertipad::get_all_vertipads)
+                .feature(Feature::PublicApi),
+            ApiRoute::get("/assets/regions", api::region::get_regions),
+            ApiRoute::post("/assets/regions", api::region::register_region),
+            ApiRoute::get("/assets/regions/:id", api::region::get_region),
+            ApiRoute::put("/assets/regions/:id", api::region::update_region),
+            ApiRoute::delete("/assets/regions/:id", api::region::remove_region),
+            ApiRoute::get(
+                "/assets/regions/:id/vertiports",
+                api::region::get_region_vertiports,
+            ),
+            ApiRoute::get(
+                "/assets/operators/:id/quota",
+                api::operator::get_operator_quota,
+            ),
+            ApiRoute::get(
+                "/assets/operators/:id/contacts",
+                api::operator::get_operator_contacts,
+            ),
+            ApiRoute::post(
+                "/assets/operators/:id/contacts",
+                api::operator::add_operator_contact,
+            ),
+            ApiRoute::put(
+                "/assets/operators/:id/contacts/:contact_id",
+                api::operator::update_operator_contact,
+            ),
+            ApiRoute::delete(
+                "/assets/operators/:id/contacts/:contact_id",
+                api::operator::remove_operator_contact,
+            ),
+            ApiRoute::get(
+                "/assets/vertipads/:id/chargers",
+                api::charger::get_vertipad_chargers,
+            ),
+            ApiRoute::post(
+                "/assets/vertipads/:id/chargers",
+                api::charger::add_vertipad_charger,
+            ),
+            ApiRoute::get(
+                "/assets/vertipads/:id/chargers/:charger_id",
+                api::charger::get_vertipad_charger,
+            ),
+            ApiRoute::put(
+                "/assets/vertipads/:id/chargers/:charger_id",
+                api::charger::update_vertipad_charger,
+            ),
+            ApiRoute::delete(
+                "/assets/vertipads/:id/chargers/:charger_id",
+                api::charger::remove_vertipad_charger,
+            ),
+            ApiRoute::get(
+                "/assets/vertipads/:id/equipment",
+                api::equipment::get_vertipad_equipment,
+            ),
+            ApiRoute::post(
+                "/assets/vertipads/:id/equipment",
+                api::equipment::add_vertipad_equipment,
+            ),
+            ApiRoute::get(
+                "/assets/vertipads/:id/equipment/:equipment_id",
+                api::equipment::get_vertipad_equipment_by_id,
+            ),
+            ApiRoute::put(
+                "/assets/vertipads/:id/equipment/:equipment_id",
+                api::equipment::update_vertipad_equipment,
+            ),
+            ApiRoute::delete(
+                "/assets/vertipads/:id/equipment/:equipment_id",
+                api::equipment::remove_vertipad_equipment,
+            ),
+            ApiRoute::get(
+                "/assets/vertiports/:id/restrictions",
+                api::restriction::get_vertiport_restrictions,
+            ),
+            ApiRoute::get(
+                "/assets/vertiports/:id/restrictions/rules",
+                api::restriction::get_vertiport_restriction_rules,
+            ),
+            ApiRoute::post(
+                "/assets/vertiports/:id/restrictions/rules",
+                api::restriction::add_vertiport_restriction_rule,
+            ),
+            ApiRoute::get(
+                "/assets/vertiports/:id/restrictions/rules/:rule_id",
+                api::restriction::get_vertiport_restriction_rule,
+            ),
+            ApiRoute::put(
+                "/assets/vertiports/:id/restrictions/rules/:rule_id",
+                api::restriction::update_vertiport_restriction_rule,
+            ),
+            ApiRoute::delete(
+                "/assets/vertiports/:id/restrictions/rules/:rule_id",
+                api::restriction::remove_vertiport_restriction_rule,
+            ),
+            ApiRoute::get(
+                "/assets/operators/:id/logo",
+                api::operator::get_operator_logo,
+            ),
+            ApiRoute::put(
+                "/assets/operators/:id/logo",
+                api::operator::put_operator_logo,
+            ),
+            ApiRoute::delete(
+                "/assets/operators/:id/logo",
+                api::operator::remove_operator_logo,
+            ),
+            ApiRoute::get(
+                "/assets/operators/:id/notifications",
+                api::operator::get_operator_notifications,
+            ),
+            ApiRoute::put(
+                "/assets/operators/:id/notifications",
+                api::operator::put_operator_notifications,
+            ),
+            ApiRoute::get(
+                "/assets/operators/:id/assets",
+                api::operator::get_all_assets_by_operator,
+            ),
+            ApiRoute::get(
+                "/assets/operators/:id/grouped",
+                api::operator::get_all_grouped_assets,
+            ),
+            ApiRoute::get(
+                "/assets/operators/:id/grouped/delegated-to",
+                api::operator::get_all_grouped_assets_delegated_to,
+            )
+            .feature(Feature::GroupsV2),
+            ApiRoute::get(
+                "/assets/operators/:id/grouped/delegated-from",
+                api::operator::get_all_grouped_assets_delegated_from,
+            )
+            .feature(Feature::GroupsV2),
+            ApiRoute::get("/assets/aircraft/:id", api::aircraft::get_aircraft_by_id),
+            ApiRoute::head("/assets/aircraft/:id", api::aircraft::head_aircraft_by_id),
+            ApiRoute::get("/assets/vertipads/:id", api::vertipad::get_vertipad_by_id),
+            ApiRoute::head("/assets/vertipads/:id", api::vertipad::head_vertipad_by_id),
+            ApiRoute::post(
+                "/assets/aircraft/:id/assign-hangar",
+                api::aircraft::assign_aircraft_hangar,
+            ),
+            ApiRoute::get(
+                "/assets/aircraft/:id/hold",
+                api::aircraft::get_aircraft_holds,
+            ),
+            ApiRoute::post(
+                "/assets/aircraft/:id/hold",
+                api::aircraft::create_aircraft_hold,
+            ),
+            ApiRoute::delete(
+                "/assets/aircraft/:id/hold/:hold_id",
+                api::aircraft::cancel_aircraft_hold,
+            ),
+            ApiRoute::get(
+                "/assets/aircraft/:id/utilization",
+                api::aircraft::get_aircraft_utilization,
+            ),
+            ApiRoute::get(
+                "/assets/vertipads/:id/availability",
+                api::vertipad::get_vertipad_availability,
+            ),
+            ApiRoute::get(
+                "/assets/vertipads/:id/occupancy-log",
+                api::vertipad::get_vertipad_occupancy_log,
+            ),
+            ApiRoute::get(
+                "/assets/vertiports/:id",
+                api::vertiport::get_vertiport_by_id,
+            ),
+            ApiRoute::head(
+                "/assets/vertiports/:id",
+                api::vertiport::head_vertiport_by_id,
+            ),
+            ApiRoute::get(
+                "/assets/vertiports/typeahead",
+                api::vertiport::typeahead_vertiports,
+            ),
+            ApiRoute::get(
+                "/assets/vertiports/:id/vertipads/adjacency",
+                api::vertiport::get_vertipad_adjacency,
+            ),
+            ApiRoute::get(
+                "/assets/vertiports/:id/vertipads/by-elevation",
+                api::vertiport::get_vertipads_by_elevation,
+            ),
+            ApiRoute::get("/assets/groups/:id", api::group::get_asset_group_by_id),
+            ApiRoute::get(
+                "/assets/groups/:id/assets",
+                api::group::get_asset_group_assets,
+            ),
+            ApiRoute::get(
+                "/assets/groups/:id/availability",
+                api::group::get_asset_group_availability,
+            )
+            .feature(Feature::GroupsV2),
+            // Permissions of the calling operator
+            ApiRoute::get(
+                "/assets/aircraft/:id/permissions",
+                api::permission::get_aircraft_permissions,
+            ),
+            ApiRoute::get(
+                "/assets/vertiports/:id/permissions",
+                api::permission::get_vertiport_permissions,
+            ),
+            ApiRoute::get(
+                "/assets/vertipads/:id/permissions",
+                api::permission::get_vertipad_permissions,
+            ),
+            ApiRoute::get(
+                "/assets/groups/:id/permissions",
+                api::permission::get_group_permissions,
+            ),
+            ApiRoute::get("/assets/diff", api::diff::get_asset_diff),
+            ApiRoute::get("/assets/audit", api::audit::get_audit_changes),
+            ApiRoute::get(
+                "/assets/maintenance/calendar.ics",
+                api::maintenance::get_maintenance_calendar,
+            ),
+            ApiRoute::get("/assets/quality-report", api::quality::get_quality_report),
+            // Removed assets
+            ApiRoute::get("/assets/archive", api::archive::get_archive),
+            ApiRoute::post(
+                "/assets/archive/:id/restore",
+                api::archive::restore_archived_asset,
+            ),
+            // Assets of any type
+            ApiRoute::get("/assets/:id", api::asset::get_asset_by_id),
+            ApiRoute::post("/assets/query", api::query::query_assets),
+            // Notes and attachments of any asset
+            ApiRoute::get("/assets/:id/notes", api::attachment::get_asset_notes),
+            ApiRoute::post("/assets/:id/notes", api::attachment::add_asset_note),
+            ApiRoute::delete(
+                "/assets/:id/notes/:note_id",
+                api::attachment::remove_asset_note,
+            ),
+            ApiRoute::get(
+                "/assets/:id/attachments",
+                api::attachment::get_asset_attachments,
+            ),
+            ApiRoute::post(
+                "/assets/:id/attachments",
+                api::attachment::upload_asset_attachment,
+            ),
+            ApiRoute::get(
+                "/assets/:id/attachments/:attachment_id",
+                api::attachment::download_asset_attachment,
+            ),
+            ApiRoute::delete(
+                "/assets/:id/attachments/:attachment_id",
+                api::attachment::remove_asset_attachment,
+            ),
+            // POST endpoints
+            ApiRoute::post("/assets/aircraft", api::aircraft::register_aircraft),
+            ApiRoute::post("/assets/vertiports", api::vertiport::register_vertiport),
+            ApiRoute::post("/assets/vertipads", api::vertipad::register_vertipad),
+            ApiRoute::post("/assets/groups", api::group::register_asset_group),
+            ApiRoute::get("/assets/snapshot", api::snapshot::export_snapshot),
+            ApiRoute::post("/assets/snapshot", api::snapshot::import_snapshot),
+            ApiRoute::post("/assets/import/geojson", api::import::import_geojson)
+                .map(|router| router.layer(Extension(BodyLimit::Bulk))),
+            // Batch endpoints
+            ApiRoute::post(
+                "/assets/aircraft/status-batch",
+                api::aircraft::batch_set_aircraft_status,
+            ),
+            ApiRoute::post(
+                "/assets/aircraft/batch-get",
+                api::aircraft::batch_get_aircraft,
+            ),
+            ApiRoute::post(
+                "/assets/vertiports/batch-get",
+                api::vertiport::batch_get_vertiports,
+            ),
+            ApiRoute::post(
+                "/assets/vertipads/batch-get",
+                api::vertipad::batch_get_vertipads,
+            ),
+            // Validation endpoints
+            ApiRoute::get(
+                "/assets/aircraft/check-registration/:registration_number",
+                api::aircraft::check_aircraft_registration,
+            ),
+            ApiRoute::post(
+                "/assets/aircraft/validate",
+                api::aircraft::validate_aircraft_registration,
+            ),
+            ApiRoute::put(
+                "/assets/aircraft/validate",
+                api::aircraft::validate_aircraft_update,
+            ),
+            ApiRoute::post(
+                "/assets/vertiports/validate",
+                api::vertiport::validate_vertiport_registration,
+            ),
+            ApiRoute::put(
+                "/assets/vertiports/validate",
+                api::vertiport::validate_vertiport_update,
+            ),
+            ApiRoute::post(
+                "/assets/vertipads/validate",
+                api::vertipad::validate_vertipad_registration,
+            ),
+            ApiRoute::put(
+                "/assets/vertipads/validate",
+                api::vertipad::validate_vertipad_update,
+            ),
+            // PUT endpoints
+            ApiRoute::put("/assets/aircraft", api::aircraft::update_aircraft),
+            ApiRoute::put("/assets/vertiports", api::vertiport::update_vertiport),
+            ApiRoute::put("/assets/vertipads", api::vertipad::update_vertipad),
+            ApiRoute::put("/assets/groups/:id", api::group::update_asset_group),
+            ApiRoute::put(
+                "/assets/vertiports/:id/localization",
+                api::vertiport::put_vertiport_localization,
+            ),
+            ApiRoute::get(
+                "/assets/vertiports/:id/ops-status",
+                api::vertiport::get_vertiport_ops_status,
+            ),
+            ApiRoute::put(
+                "/assets/vertiports/:id/ops-status",
+                api::vertiport::put_vertiport_ops_status,
+            ),
+            ApiRoute::get(
+                "/assets/vertiports/:id/weather-minimums",
+                api::vertiport::get_vertiport_weather_minimums,
+            ),
+            ApiRoute::put(
+                "/assets/vertiports/:id/weather-minimums",
+                api::vertiport::put_vertiport_weather_minimums,
+            ),
+            ApiRoute::put(
+                "/assets/vertipads/:id/localization",
+                api::vertipad::put_vertipad_localization,
+            ),
+            ApiRoute::put(
+                "/assets/vertipads/:id/classification",
+                api::vertipad::put_vertipad_classification,
+            ),
+            ApiRoute::put(
+                "/assets/groups/:id/schedule",
+                api::group::put_asset_group_schedule,
+            )
+            .feature(Feature::GroupsV2),
+            ApiRoute::put(
+                "/assets/groups/:id/parent",
+                api::group::put_asset_group_parent,
+            ),
+            ApiRoute::get(
+                "/assets/groups/:id/delegation",
+                api::group::get_asset_group_delegation,
+            )
+            .feature(Feature::GroupsV2),
+            ApiRoute::put(
+                "/assets/groups/:id/delegation",
+                api::group::put_asset_group_delegation,
+            )
+            .feature(Feature::GroupsV2),
+            ApiRoute::delete(
+                "/assets/groups/:id/delegation",
+                api::group::revoke_asset_group_delegation,
+            )
+            .feature(Feature::GroupsV2),
+            ApiRoute::get(
+                "/assets/groups/:id/delegation/audit",
+                api::group::get_asset_group_delegation_audit,
+            )
+            .feature(Feature::GroupsV2),
+            // PATCH endpoints
+            ApiRoute::patch("/assets/aircraft/:id", api::aircraft::patch_aircraft),
+            ApiRoute::patch("/assets/vertiports/:id", api::vertiport::patch_vertiport),
+            ApiRoute::patch("/assets/vertipads/:id", api::vertipad::patch_vertipad),
+            ApiRoute::patch("/assets/groups/:id", api::group::patch_asset_group),
+            // DELETE endpoints
+            ApiRoute::delete("/assets/aircraft/:id", api::aircraft::remove_aircraft),
+            ApiRoute::delete("/assets/vertiports/:id", api::vertiport::remove_vertiport),
+            ApiRoute::delete(
+                "/assets/vertiports/:id/weather-minimums",
+                api::vertiport::remove_vertiport_weather_minimums,
+            ),
+            ApiRoute::delete("/assets/vertipads/:id", api::vertipad::remove_vertipad),
+            ApiRoute::delete("/assets/groups/:id", api::group::remove_asset_group),
+            // OPTIONS endpoints
+            ApiRoute::options(
+                "/assets/aircraft",
+                api::methods::options(api::methods::ASSET_COLLECTION_METHODS),
+            ),
+            ApiRoute::options(
+                "/assets/aircraft/:id",
+                api::methods::options(api::methods::ASSET_METHODS),
+            ),
+            ApiRoute::options(
+                "/assets/vertiports",
+                api::methods::options(api::methods::ASSET_COLLECTION_METHODS),
+            ),
+            ApiRoute::options(
+                "/assets/vertiports/:id",
+                api::methods::options(api::methods::ASSET_METHODS),
+            ),
+            ApiRoute::options(
+                "/assets/vertipads",
+                api::methods::options(api::methods::ASSET_COLLECTION_METHODS),
+            ),
+            ApiRoute::options(
+                "/assets/vertipads/:id",
+                api::methods::options(api::methods::ASSET_METHODS),
+            ),
+        ],
+    }
+}
+
+/// Get the routes of an API version.
+fn api_routes(version: ApiVersion) -> Router {
+    mount(api_route_table(version))
+}
+
+/// Bind a listener for a server using TLS
+async fn bind(addr: SocketAddr) -> Result<TcpListener, ()> {
+    TcpListener::bind(addr).await.map_err(|e| {
+        rest_error!("could not bind {}: {}, exiting.", addr, e);
+    })
+}
+
+/// Describe the client certificate handling of a TLS server for the logs
+fn client_auth(tls: &ServerTls) -> &'static str {
+    let settings = tls.settings();
+    match (&settings.client_ca_path, settings.client_auth_required) {
+        (None, _) => "not requested",
+        (Some(_), false) => "optional",
+        (Some(_), true) => "required",
+    }
+}
+
+/// Components shared by the REST API, the admin endpoints, the background
+/// monitors of the REST server and the gRPC server
+#[derive(Clone)]
+pub struct RestComponents {
+    /// Configuration, read for each request
+    pub config: SharedConfig,
+    /// In memory state shared with the gRPC server
+    pub shared: SharedState,
+    /// Svc-storage clients
+    pub grpc_clients: GrpcClients,
+    /// Storage repositories
+    pub repositories: Repositories,
+    /// Read-only maintenance mode
+    pub read_only: ReadOnlyMode,
+    /// Audit log of the authorized requests, with the persisted audit trail
+    pub audit_log: AuditLog,
+    /// Aircraft with an overdue maintenance, updated by the maintenance
+    /// monitor
+    pub overdue_aircraft: OverdueAircraft,
+    /// Persisted asset group delegations, expired delegations are reverted
+    /// by the delegation monitor
+    pub group_delegations: GroupDelegations,
+    /// Vertiport names of the typeahead search, refreshed by the vertiport
+    /// index monitor
+    pub vertiport_index: VertiportIndex,
+    /// Operator quotas, with the persisted owners of the assets
+    pub operator_quotas: OperatorQuotas,
+    /// Persisted maintenance holds of the aircraft
+    pub maintenance_holds: MaintenanceHolds,
+    /// Persisted notes and attachments of the assets, the files are kept in
+    /// the object store
+    pub asset_attachments: AssetAttachments,
+    /// Operator logos, kept in the same object store as the attachments
+    pub operator_logos: OperatorLogos,
+    /// Persisted tenants of the assets and asset groups
+    pub tenant_assets: TenantAssets,
+    /// Persisted ids of the restored archived assets
+    pub restored_assets: RestoredAssets,
+    /// Persisted removal times of the assets removed through this service
+    pub removed_assets: RemovedAssets,
+    /// Persisted operational statuses of the vertiports
+    pub vertiport_ops_statuses: VertiportOpsStatuses,
+    /// Persisted curfews and noise abatement rules of the vertiports
+    pub restriction_rules: RestrictionRules,
+    /// Persisted regions grouping the vertiports by area
+    pub regions: Regions,
+    /// Persisted operator contacts and notification preferences
+    pub operator_directory: OperatorDirectory,
+    /// Persisted type, surface, weight and dimensions of the vertipads
+    pub pad_classifications: PadClassifications,
+    /// Persisted charging infrastructure of the vertipads
+    pub vertipad_chargers: VertipadChargers,
+    /// Persisted lighting, sensors and weather stations of the vertipads
+    pub vertipad_equipment: VertipadEquipment,
+    /// Persisted localized vertiport and vertipad names
+    pub localizations: Localizations,
+}
+
+impl RestComponents {
+    /// Create the components, using repositories backed by the svc-storage
+    /// clients
+    pub fn new(
+        config: SharedConfig,
+        shared: SharedState,
+        grpc_clients: GrpcClients,
+    ) -> Result<Self, StateError> {
+        let repositories = Repositories::grpc(&grpc_clients);
+        Self::with_repositories(config, shared, grpc_clients, repositories)
+    }
+
+    /// Create the components using the provided repositories, for example
+    /// [`Repositories::memory`] in tests.
+    ///
+    /// The persisted tables are opened in the `state_dir` of the
+    /// configuration, see [`crate::state`].
+    pub fn with_repositories(
+        config: SharedConfig,
+        shared: SharedState,
+        grpc_clients: GrpcClients,
+        repositories: Repositories,
+    ) -> Result<Self, StateError> {
+        let state_dir = config.read(|config| config.state_dir.clone());
+        let asset_owners = AssetOwners::open(state_dir.as_deref(), ASSET_OWNERS_TABLE)?;
+        let maintenance_holds = MaintenanceHolds::open(state_dir.as_deref())?;
+        let objects = object_store(&config.get());
+        let asset_attachments = AssetAttachments::open(state_dir.as_deref(), objects.clone())?;
+        let tenant_assets = TenantAssets::open(state_dir.as_deref())?;
+        let restored_assets = RestoredAssets::open(state_dir.as_deref())?;
+        let removed_assets = RemovedAssets::open(state_dir.as_deref())?;
+        let vertiport_ops_statuses = VertiportOpsStatuses::open(state_dir.as_deref())?;
+        let restriction_rules = RestrictionRules::open(state_dir.as_deref())?;
+        let regions = Regions::open(state_dir.as_deref())?;
+        let operator_directory = OperatorDirectory::open(state_dir.as_deref())?;
+        let pad_classifications = PadClassifications::open(state_dir.as_deref())?;
+        let vertipad_chargers = VertipadChargers::open(state_dir.as_deref())?;
+        let vertipad_equipment = VertipadEquipment::open(state_dir.as_deref())?;
+        let group_delegations = GroupDelegations::open(state_dir.as_deref())?;
+        let localizations = Localizations::open(state_dir.as_deref())?;
+
+        Ok(Self {
+            read_only: ReadOnlyMode::new(config.clone()),
+            audit_log: AuditLog::open(config.clone(), state_dir.as_deref())?,
+            operator_quotas: OperatorQuotas::new(config.clone(), asset_owners),
+            maintenance_holds,
+            asset_attachments,
+            operator_logos: OperatorLogos::new(objects),
+            tenant_assets,
+            restored_assets,
+            removed_assets,
+            vertiport_ops_statuses,
+            restriction_rules,
+            regions,
+            operator_directory,
+            pad_classifications,
+            vertipad_chargers,
+            vertipad_equipment,
+            localizations,
+            config,
+            shared,
+            grpc_clients,
+            repositories,
+            overdue_aircraft: OverdueAircraft::default(),
+            group_delegations,
+            vertiport_index: VertiportIndex::default(),
+        })
+    }
+}
+
+/// Create the router of the versioned REST API with its middleware and
+/// extensions.
+///
+/// The admin endpoints are not included, see [`super::admin`]. The router is
+/// served by [`rest_server`], and can be called directly in tests.
+pub fn api_router(components: &RestComponents) -> Router {
+    let shared_config = components.config.clone();
+
+    // The allowed origin is checked against the current config for each request
+    let cors_config = shared_config.clone();
+    let cors_allowed_origin =
+        AllowOrigin::predicate(move |origin: &HeaderValue, _parts: &Parts| {
+            cors_config
+                .read(|config| origin.as_bytes() == config.rest_cors_allowed_origin.as_bytes())
+        });
+
+    // API token scopes
+    let api_auth = ApiAuth::new(shared_config.clone());
+    // Sensitive response fields, redacted for tokens without their scope
+    let redaction = ResponseRedaction::new(shared_config.clone());
+    // Tenants, the repositories of a request are limited to its tenant
+    let tenancy = Tenancy::new(shared_config.clone(), components.tenant_assets.clone());
+    // Rate limiting
+    let limiter = RequestLimiter::new(shared_config.clone());
+    // Read-only maintenance mode
+    let read_only_guard = components.read_only.clone();
+    // Timeout of the whole request, once it passed the rate limiting
+    let request_timeout = RequestTimeout::new(shared_config.clone());
+    // Audit log of the authorized requests, with the operator of their token
+    let audit_log_recorder = components.audit_log.clone();
+    let limit_middleware = ServiceBuilder::new()
+        .layer(TraceLayer::new_for_http())
+        .layer(middleware::from_fn(
+            move |req: Request<Body>, next: Next<Body>| api_auth.clone().authorize(req, next),
+        ))
+        .layer(middleware::from_fn(
+            move |req: Request<Body>, next: Next<Body>| {
+                audit_log_recorder.clone().record(req, next)
+            },
+        ))
+        .layer(middleware::from_fn(
+            move |req: Request<Body>, next: Next<Body>| redaction.clone().redact(req, next),
+        ))
+        .layer(middleware::from_fn(
+            move |req: Request<Body>, next: Next<Body>| tenancy.clone().scope(req, next),
+        ))
+        .layer(middleware::from_fn(
+            move |req: Request<Body>, next: Next<Body>| read_only_guard.clone().guard(req, next),
+        ))
+        .layer(middleware::from_fn(
+            move |req: Request<Body>, next: Next<Body>| limiter.clone().limit(req, next),
+        ))
+        .layer(middleware::from_fn(
+            move |req: Request<Body>, next: Next<Body>| request_timeout.clone().enforce(req, next),
+        ));
+
+    //
+    // Extensions
+    //
+    // Request body size limits
+    let body_limits = BodyLimits::new(shared_config.clone());
+    // Timeout of concurrent storage calls
+    let fan_out = FanOut::new(shared_config.clone());
+    // Feature flags, checked by the routes of the features
+    let features = Features::new(shared_config.clone());
+    let shared = components.shared.clone();
+
+    let app = versioned_router(api_routes)
+        .layer(
+            CorsLayer::new()
+                .allow_origin(cors_allowed_origin)
+                .allow_headers(Any)
+                .allow_methods(Any),
+        )
+        .layer(limit_middleware)
+        .layer(Extension(shared_config))
+        .layer(Extension(body_limits))
+        .layer(Extension(components.audit_log.clone()))
+        .layer(Extension(features))
+        .layer(Extension(components.read_only.clone()))
+        .layer(Extension(components.operator_quotas.clone()))
+        .layer(Extension(components.operator_directory.clone()))
+        .layer(Extension(components.regions.clone()))
+        .layer(Extension(components.localizations.clone()))
+        .layer(Extension(components.pad_classifications.clone()))
+        .layer(Extension(components.vertipad_chargers.clone()))
+        .layer(Extension(components.vertipad_equipment.clone()))
+        .layer(Extension(components.overdue_aircraft.clone()))
+        .layer(Extension(components.maintenance_holds.clone()))
+        .layer(Extension(components.vertiport_ops_statuses.clone()))
+        .layer(Extension(components.restriction_rules.clone()))
+        .layer(Extension(components.asset_attachments.clone()))
+        .layer(Extension(components.operator_logos.clone()))
+        .layer(Extension(shared.group_schedules))
+        .layer(Extension(shared.occupancy_log))
+        .layer(Extension(shared.aircraft_statuses))
+        .layer(Extension(shared.weather_minimums))
+        .layer(Extension(shared.startup))
+        .layer(Extension(components.group_delegations.clone()))
+        .layer(Extension(components.vertiport_index.clone()))
+        .layer(Extension(components.restored_assets.clone()))
+        .layer(Extension(components.removed_assets.clone()))
+        .layer(Extension(fan_out))
+        .layer(Extension(components.repositories.clone()))
+        .layer(Extension(components.grpc_clients.clone())); // Extension layer must be last
+
+    // The version negotiation rewrites the request path, so it has to wrap
+    // the router instead of being added as a layer (which runs after routing)
+    Router::new().fallback(
+        ServiceBuilder::new()
+            .layer(middleware::from_fn(negotiate_version))
+            .service(app),
+    )
+}
+
 /// Starts the REST API server for this microservice
 ///
+/// The API tokens, rate limit, concurrency limit, request body size limits,
+/// read-only mode and CORS allowed origin are read from the provided
+/// configuration for each request, so changes applied to a [`SharedConfig`] take effect without
+/// restarting the server.
+///
+/// The [`RestComponents`] are shared with the gRPC server, see
+/// [`crate::grpc::server::grpc_server`]. The admin endpoints (see [`super::admin`]) are served on the
+/// `docker_port_admin` if configured, on the REST port otherwise. Both ports
+/// use TLS if configured, see [`crate::tls`].
+///
 /// # Example:
 /// ```
-/// use svc_assets::rest::server::rest_server;
+/// use svc_assets::config::SharedConfig;
+/// use svc_assets::grpc::client::GrpcClients;
+/// use svc_assets::rest::server::{rest_server, RestComponents};
+/// use svc_assets::shared::SharedState;
 /// use svc_assets::Config;
-/// async fn example() -> Result<(), tokio::task::JoinError> {
-///     let config = Config::default();
-///     tokio::spawn(rest_server(config, None)).await;
+/// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+///     let config = SharedConfig::from(Config::default());
+///     let grpc_clients = GrpcClients::default(config.get());
+///     let components = RestComponents::new(config, SharedState::default(), grpc_clients)?;
+///     let _ = tokio::spawn(rest_server(components, None)).await?;
 ///     Ok(())
 /// }
 /// ```
 pub async fn rest_server(
-    config: Config,
+    components: RestComponents,
     shutdown_rx: Option<tokio::sync::oneshot::Receiver<()>>,
 ) -> Result<(), ()> {
     rest_info!("entry.");
+    let shared_config = components.config.clone();
+    let config = shared_config.get();
     let rest_port = config.docker_port_rest;
 
     let full_rest_addr: SocketAddr = format!("[::]:{}", rest_port).parse().map_err(|e| {
         rest_error!("invalid address: {:?}, exiting.", e);
     })?;
 
-    let cors_allowed_origin = config
+    config
         .rest_cors_allowed_origin
         .parse::<HeaderValue>()
         .map_err(|e| {
             rest_error!("invalid cors_allowed_origin address: {:?}, exiting.", e);
         })?;
 
-    // Rate limiting
-    let rate_limit = config.rest_request_limit_per_second as u64;
-    let concurrency_limit = config.rest_concurrency_limit_per_service as usize;
-    let limit_middleware = ServiceBuilder::new()
-        .layer(TraceLayer::new_for_http())
-        .layer(HandleErrorLayer::new(|e: BoxError| async move {
-            rest_warn!("too many requests: {}", e);
-            (
-                StatusCode::TOO_MANY_REQUESTS,
-                "(server) too many requests.".to_string(),
-            )
-        }))
-        .layer(BufferLayer::new(100))
-        .layer(ConcurrencyLimitLayer::new(concurrency_limit))
-        .layer(RateLimitLayer::new(
-            rate_limit,
-            std::time::Duration::from_secs(1),
-        ));
+    // TLS termination, the certificates are reloaded when the files change
+    let tls = ServerTls::from_config(&config, Protocol::Http1).map_err(|e| {
+        rest_error!("invalid TLS configuration: {}, exiting.", e);
+    })?;
 
-    //
-    // Extensions
-    //
-    // GRPC Clients
-    let grpc_clients = GrpcClients::default(config.clone());
-
-    let app = Router::new()
-        .route("/health", routing::get(api::health::health_check))
-        .route(
-            "/assets/operators/:id",
-            routing::get(api::operator::get_operator),
-        )
-        .route(
-            "/assets/demo/aircraft",
-            routing::get(api::aircraft::get_all_aircraft),
-        )
-        .route(
-            "/assets/demo/vertiports",
-            routing::get(api::vertiport::get_all_vertiports),
-        )
-        .route(
-            "/assets/demo/vertipads",
-            routing::get(api::vertipad::get_all_vertipads),
-        )
-        .route(
-            "/assets/operators/:id/assets",
-            routing::get(api::operator::get_all_assets_by_operator),
-        )
-        .route(
-            "/assets/operators/:id/grouped",
-            routing::get(api::operator::get_all_grouped_assets),
-        )
-        .route(
-            "/assets/operators/:id/grouped/delegated-to",
-            routing::get(api::operator::get_all_grouped_assets_delegated_to),
-        )
-        .route(
-            "/assets/operators/:id/grouped/delegated-from",
-            routing::get(api::operator::get_all_grouped_assets_delegated_from),
-        )
-        .route(
-            "/assets/aircraft/:id",
-            routing::get(api::aircraft::get_aircraft_by_id),
-        )
-        .route(
-            "/assets/vertipads/:id",
-            routing::get(api::vertipad::get_vertipad_by_id),
-        )
-        .route(
-            "/assets/vertiports/:id",
-            routing::get(api::vertiport::get_vertiport_by_id),
-        )
-        .route(
-            "/assets/groups/:id",
-            routing::get(api::group::get_asset_group_by_id),
-        )
-        // POST endpoints
-        .route(
-            "/assets/aircraft",
-            routing::post(api::aircraft::register_aircraft),
-        )
-        .route(
-            "/assets/vertiports",
-            routing::post(api::vertiport::register_vertiport),
-        )
-        .route(
-            "/assets/vertipads",
-            routing::post(api::vertipad::register_vertipad),
-        )
-        .route(
-            "/assets/groups",
-            routing::post(api::group::register_asset_group),
-        )
-        // PUT endpoints
-        .route(
-            "/assets/aircraft",
-            routing::put(api::aircraft::update_aircraft),
-        )
-        .route(
-            "/assets/vertiports",
-            routing::put(api::vertiport::update_vertiport),
-        )
-        .route(
-            "/assets/vertipads",
-            routing::put(api::vertipad::update_vertipad),
-        )
-        .route(
-            "/assets/groups/:id",
-            routing::put(api::group::update_asset_group),
-        )
-        // DELETE endpoints
-        .route(
-            "/assets/aircraft/:id",
-            routing::delete(api::aircraft::remove_aircraft),
-        )
-        .route(
-            "/assets/vertiports/:id",
-            routing::delete(api::vertiport::remove_vertiport),
-        )
-        .route(
-            "/assets/vertipads/:id",
-            routing::delete(api::vertipad::remove_vertipad),
-        )
-        .route(
-            "/assets/groups/:id",
-            routing::delete(api::group::remove_asset_group),
-        )
-        .layer(
-            CorsLayer::new()
-                .allow_origin(cors_allowed_origin)
-                .allow_headers(Any)
-                .allow_methods(Any),
-        )
-        .layer(limit_middleware)
-        .layer(Extension(grpc_clients)); // Extension layer must be last
+    if config.state_dir.is_none() {
+        rest_warn!("no state_dir configured, the persisted state is lost on restart.");
+    }
+    let delegation = tokio::spawn(delegation_monitor(components.group_delegations.clone()));
+    let maintenance = tokio::spawn(maintenance_monitor(
+        shared_config.clone(),
+        components.repositories.clone(),
+        components.overdue_aircraft.clone(),
+    ));
+    let vertiport_index = tokio::spawn(vertiport_index_monitor(
+        shared_config.clone(),
+        components.repositories.clone(),
+        components.vertiport_index.clone(),
+    ));
+
+    // Admin endpoints
+    let admin_auth = AdminAuth::new(shared_config.clone());
+    let admin_app = admin_routes()
+        .layer(Extension(shared_config.clone()))
+        .layer(Extension(Features::new(shared_config.clone())))
+        .layer(Extension(components.read_only.clone()))
+        .layer(Extension(components.audit_log.clone()))
+        .layer(Extension(components.overdue_aircraft.clone()))
+        .layer(Extension(components.tenant_assets.clone()))
+        .layer(Extension(components.repositories.clone()))
+        .layer(Extension(components.grpc_clients.clone()))
+        .layer(middleware::from_fn(
+            move |req: Request<Body>, next: Next<Body>| admin_auth.clone().authorize(req, next),
+        ))
+        .layer(TraceLayer::new_for_http());
+
+    let app = api_router(&components);
+
+    // The admin endpoints are not versioned, so they are routed before the
+    // version negotiation, or served on their own port if configured
+    let (app, admin) = match config.docker_port_admin {
+        None => (admin_app.fallback(app), None),
+        Some(admin_port) => {
+            let admin_addr: SocketAddr = format!("[::]:{}", admin_port).parse().map_err(|e| {
+                rest_error!("invalid admin address: {:?}, exiting.", e);
+            })?;
+            let (admin_tx, admin_rx) = tokio::sync::oneshot::channel::<()>();
+            let admin_signal = shutdown_signal("admin", Some(admin_rx));
+            let admin_server = match &tls {
+                None => tokio::spawn(
+                    axum::Server::bind(&admin_addr)
+                        .serve(admin_app.into_make_service())
+                        .with_graceful_shutdown(admin_signal),
+                ),
+                Some(tls) => tokio::spawn(
+                    axum::Server::builder(accept::from_stream(
+                        tls.incoming(bind(admin_addr).await?),
+                    ))
+                    .serve(admin_app.into_make_service())
+                    .with_graceful_shutdown(admin_signal),
+                ),
+            };
+            rest_info!("admin endpoints served at: {}.", admin_addr);
+            (Router::new().fallback(app), Some((admin_server, admin_tx)))
+        }
+    };
 
     //
     // Bind to address
     //
-    axum::Server::bind(&full_rest_addr)
-        .serve(app.into_make_service())
-        .with_graceful_shutdown(shutdown_signal("rest", shutdown_rx))
-        .await
-        .map_err(|e| {
-            rest_error!("could not start server: {}", e);
-        })?;
+    let tls_watch = tls.clone().map(|tls| tokio::spawn(tls.watch()));
+    let signal = shutdown_signal("rest", shutdown_rx);
+    // reported by the gRPC health service until the server stops
+    let serving = components.shared.rest_serving.start();
+    let result = match &tls {
+        None => {
+            axum::Server::bind(&full_rest_addr)
+                .serve(Shared::new(app))
+                .with_graceful_shutdown(signal)
+                .await
+        }
+        Some(tls) => {
+            rest_info!("TLS enabled, client certificates {}.", client_auth(tls));
+            axum::Server::builder(accept::from_stream(
+                tls.incoming(bind(full_rest_addr).await?),
+            ))
+            .serve(Shared::new(app))
+            .with_graceful_shutdown(signal)
+            .await
+        }
+    };
+    drop(serving);
+    maintenance.abort();
+    vertiport_index.abort();
+    delegation.abort();
+    if let Some(tls_watch) = tls_watch {
+        tls_watch.abort();
+    }
+    if let Some((admin_server, admin_tx)) = admin {
+        let _ = admin_tx.send(());
+        if let Ok(Err(e)) = admin_server.await {
+            rest_error!("admin server error: {}", e);
+        }
+    }
+    result.map_err(|e| {
+        rest_error!("could not start server: {}", e);
+    })?;
 
     rest_info!("server running at: {}.", full_rest_addr);
     Ok(())
@@ -202,6 +908,68 @@ pub async fn rest_server(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rest::admin::admin_route_table;
+    use crate::rest::ApiDoc;
+    use crate::Config;
+    use std::collections::BTreeSet;
+    use utoipa::openapi::path::PathItemType;
+    use utoipa::OpenApi;
+
+    /// Get the method of an OpenAPI operation
+    fn method(operation: &PathItemType) -> axum::http::Method {
+        use axum::http::Method;
+        match operation {
+            PathItemType::Get => Method::GET,
+            PathItemType::Post => Method::POST,
+            PathItemType::Put => Method::PUT,
+            PathItemType::Delete => Method::DELETE,
+            PathItemType::Options => Method::OPTIONS,
+            PathItemType::Head => Method::HEAD,
+            PathItemType::Patch => Method::PATCH,
+            PathItemType::Trace => Method::TRACE,
+            PathItemType::Connect => Method::CONNECT,
+        }
+    }
+
+    #[test]
+    fn test_routes_match_openapi_paths() {
+        let documented: BTreeSet<String> = ApiDoc::openapi()
+            .paths
+            .paths
+            .iter()
+            .flat_map(|(path, item)| {
+                item.operations
+                    .keys()
+                    .map(move |operation| format!("{} {}", method(operation), path))
+            })
+            .collect();
+
+        for version in ApiVersion::ALL {
+            // the OPTIONS routes only list the allowed methods and are not
+            // documented
+            let mounted: BTreeSet<String> = api_route_table(*version)
+                .into_iter()
+                .chain(admin_route_table())
+                .filter(|route| route.method != axum::http::Method::OPTIONS)
+                .map(|route| format!("{} {}", route.method, route.openapi_path()))
+                .collect();
+
+            let undocumented: Vec<&String> = mounted.difference(&documented).collect();
+            let unmounted: Vec<&String> = documented.difference(&mounted).collect();
+            assert!(
+                undocumented.is_empty(),
+                "{} routes missing from the ApiDoc: {:#?}",
+                version.as_str(),
+                undocumented
+            );
+            assert!(
+                unmounted.is_empty(),
+                "{} ApiDoc paths not mounted: {:#?}",
+                version.as_str(),
+                unmounted
+            );
+        }
+    }
 
     #[tokio::test]
     async fn test_server_start_and_shutdown() {
@@ -214,7 +982,13 @@ mod tests {
         let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
 
         // Start the rest server
-        tokio::spawn(rest_server(config, Some(shutdown_rx)));
+        let components = RestComponents::new(
+            SharedConfig::from(config.clone()),
+            SharedState::default(),
+            GrpcClients::default(config),
+        )
+        .unwrap();
+        tokio::spawn(rest_server(components, Some(shutdown_rx)));
 
         // Give the server time to get through the startup sequence (and thus code)
         sleep(Duration::from_secs(1)).await;
@@ -6,6 +6,7 @@
 //! Types here are different from the openapi types.
 #![allow(missing_docs)]
 
+use crate::rest::api::rest_types::timestamp;
 use crate::rest::api::{aircraft::Aircraft, vertipad::Vertipad, vertiport::Vertiport};
 use duplicate::duplicate_item;
 use lib_common::time::{DateTime, Utc};
@@ -20,20 +21,29 @@ use utoipa::{IntoParams, ToSchema};
 
 /// A struct representing a group of assets.
 ///
-/// The asset group can be delegated to another operator.
+/// The asset group can be delegated to another operator, and can contain
+/// child groups.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema, IntoParams)]
 pub struct AssetGroup {
     /// UUID of the asset group.
     pub id: String,
     pub name: Option<String>,
-    /// The UUID of an [`Operator`] struct.
-    pub owner: String,
+    /// The UUID of an [`Operator`] struct, if known.
+    pub owner: Option<String>,
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
     pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
     pub updated_at: Option<DateTime<Utc>>,
     /// The UUID of an [`Operator`] struct, if available.
     pub delegatee: Option<String>,
     /// The UUIDs of the assets in the group.
     pub assets: Vec<String>,
+    /// The UUID of the parent [`AssetGroup`], if the group is a child group.
+    #[serde(default)]
+    pub parent_group_id: Option<String>,
+    /// The UUIDs of the child groups of the group.
+    #[serde(default)]
+    pub child_group_ids: Vec<String>,
 }
 
 /// Attributes that are common to all assets.
@@ -46,9 +56,24 @@ pub struct Basics {
     /// The UUID of an [`AssetGroup`] struct, if available.
     pub group_id: Option<String>,
     /// Created at time.
+    #[serde(deserialize_with = "timestamp::deserialize")]
     pub created_at: DateTime<Utc>,
     /// Updated at time.
+    #[serde(deserialize_with = "timestamp::deserialize")]
     pub updated_at: DateTime<Utc>,
+    /// Removed at time, only set for removed assets listed with
+    /// `include_deleted` of which this service recorded the removal.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "timestamp::deserialize_option"
+    )]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// The UUID of the [`Operator`] owning the asset, only set for the
+    /// aircraft and vertiports registered through this service with an
+    /// operator, and for the vertipads of such a vertiport.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<String>,
     /// A list of UUIDs of [`Operator`] structs.
     ///
     /// If the vector is empty, the asset is available to everyone.
@@ -168,6 +193,17 @@ pub enum AssetStatus {
     Emergency,
 }
 
+impl From<crate::rest::api::rest_types::AssetStatus> for AssetStatus {
+    fn from(status: crate::rest::api::rest_types::AssetStatus) -> Self {
+        use crate::rest::api::rest_types::AssetStatus as RestAssetStatus;
+        match status {
+            RestAssetStatus::Available => AssetStatus::Available,
+            RestAssetStatus::Unavailable => AssetStatus::Unavailable,
+            RestAssetStatus::Emergency => AssetStatus::Emergency,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,6 +218,8 @@ mod tests {
             group_id: Some(Uuid::new_v4().to_string()),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            deleted_at: None,
+            owner_id: None,
             whitelist: vec![whitelisted_id.clone(), "invalid".to_string()],
             status: AssetStatus::Available,
         };
@@ -231,6 +269,8 @@ mod tests {
             group_id: Some(group_id.clone()),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            deleted_at: None,
+            owner_id: None,
             whitelist: vec![Uuid::new_v4().to_string()],
             status: AssetStatus::Available,
         };
@@ -253,8 +293,11 @@ mod tests {
         let vertiport = Vertiport {
             basics: basics.clone(),
             description: vertiport.description,
-            geo_location: vertiport.geo_location.unwrap(),
+            geo_location: Some(vertiport.geo_location.unwrap().into()),
+            centroid: None,
+            bbox: None,
             schedule: vertiport.schedule,
+            localization: None,
         };
 
         let asset_group = AssetGroup {
@@ -267,7 +310,7 @@ mod tests {
             // pub assets: Vec<Uuid>,
             id: group_id.clone(),
             name: Some("Test group".to_string()),
-            owner: Uuid::new_v4().to_string(),
+            owner: Some(Uuid::new_v4().to_string()),
             created_at: Some(Utc::now()),
             updated_at: Some(Utc::now()),
             delegatee: None,
@@ -275,6 +318,8 @@ mod tests {
                 aircraft.id().unwrap().to_string(),
                 vertiport.id().unwrap().to_string(),
             ],
+            parent_group_id: None,
+            child_group_ids: vec![],
         };
 
         assert_eq!(&asset_group.id, &group_id);
@@ -0,0 +1,272 @@
+//! Tenants
+//!
+//! Staging and customer fleets can share one deployment as separate tenants.
+//! The tenants are listed in the `tenants` value of the [`SharedConfig`]; the
+//! service is single tenant while the list is empty.
+//!
+//! The tenant of a request is the tenant its API token is bound to (see
+//! [`super::auth`]). The `x-tenant-id` header can not select a tenant: it is
+//! rejected unless it names the tenant of the token, so an open API or a
+//! token without a tenant only reaches the default tenant. Requests without
+//! a tenant use the default tenant. The [`Repositories`] of the request are
+//! replaced by repositories limited to the assets of the tenant, see
+//! [`crate::repo::tenant`].
+
+use super::auth::TokenTenant;
+use crate::config::SharedConfig;
+use crate::repo::tenant::TenantAssets;
+use crate::repo::Repositories;
+use axum::{
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Header naming the tenant of a request, which has to match the tenant of
+/// its API token
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// Tenants of the service
+#[derive(Debug, Clone)]
+pub struct Tenancy {
+    config: SharedConfig,
+    assets: TenantAssets,
+}
+
+impl Tenancy {
+    /// Create a new tenancy using the `tenants` of the configuration
+    pub fn new(config: SharedConfig, assets: TenantAssets) -> Self {
+        Self { config, assets }
+    }
+
+    /// Get the tenant of a request, `None` for the default tenant.
+    ///
+    /// Returns `FORBIDDEN` if the header names another tenant than the API
+    /// token or the token is not bound to a tenant, and `BAD_REQUEST` if the
+    /// tenant is not configured.
+    pub fn tenant<B>(&self, req: &Request<B>) -> Result<Option<String>, (StatusCode, String)> {
+        let header = req
+            .headers()
+            .get(TENANT_HEADER)
+            .map(|value| {
+                value.to_str().map(str::trim).map_err(|_| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        format!("(server) invalid {TENANT_HEADER} header."),
+                    )
+                })
+            })
+            .transpose()?
+            .filter(|value| !value.is_empty());
+        let token = req
+            .extensions()
+            .get::<TokenTenant>()
+            .map(|tenant| tenant.0.as_str());
+
+        let tenant = match (token, header) {
+            (Some(token), Some(header)) if token != header => {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    format!("(server) the API token is bound to tenant '{token}'."),
+                ))
+            }
+            (None, Some(_)) => {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    "(server) the API token is not bound to a tenant.".to_string(),
+                ))
+            }
+            (Some(tenant), _) => tenant,
+            (None, None) => return Ok(None),
+        };
+
+        match self
+            .config
+            .read(|config| config.tenants.iter().any(|known| known == tenant))
+        {
+            true => Ok(Some(tenant.to_string())),
+            false => Err((
+                StatusCode::BAD_REQUEST,
+                format!("(server) unknown tenant '{tenant}'."),
+            )),
+        }
+    }
+
+    /// Limit the repositories to the assets of a tenant, `None` being the
+    /// default tenant.
+    ///
+    /// The repositories are returned unchanged if no tenants are configured.
+    pub fn repositories(&self, repos: &Repositories, tenant: Option<String>) -> Repositories {
+        match self.config.read(|config| config.tenants.is_empty()) {
+            true => repos.clone(),
+            false => repos.for_tenant(tenant, self.assets.clone()),
+        }
+    }
+
+    /// Middleware function limiting the repositories of a request to the
+    /// assets of its tenant.
+    ///
+    /// Requests pass unchanged if no tenants are configured.
+    pub async fn scope<B>(self, mut req: Request<B>, next: Next<B>) -> Response {
+        if self.config.read(|config| config.tenants.is_empty()) {
+            return next.run(req).await;
+        }
+
+        let tenant = match self.tenant(&req) {
+            Ok(tenant) => tenant,
+            Err((status, message)) => {
+                rest_warn!(
+                    "rejected {} {}: {}",
+                    req.method(),
+                    req.uri().path(),
+                    message
+                );
+                return (status, message).into_response();
+            }
+        };
+
+        if let Some(repos) = req.extensions().get::<Repositories>().cloned() {
+            req.extensions_mut()
+                .insert(self.repositories(&repos, tenant));
+        }
+
+        next.run(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::AircraftDataBuilder;
+    use crate::Config;
+    use axum::{body::Body, middleware, routing, Extension, Router};
+    use svc_storage_client_grpc::prelude::AdvancedSearchFilter;
+    use tower::ServiceExt;
+
+    fn tenancy(tenants: &[&str]) -> Tenancy {
+        let mut config = Config::default();
+        config.tenants = tenants.iter().map(|tenant| tenant.to_string()).collect();
+        Tenancy::new(config.into(), TenantAssets::default())
+    }
+
+    fn request(header: Option<&str>, token: Option<&str>) -> Request<()> {
+        let mut req = Request::builder().uri("/assets/aircraft");
+        if let Some(header) = header {
+            req = req.header(TENANT_HEADER, header);
+        }
+        let mut req = req.body(()).unwrap();
+        if let Some(token) = token {
+            req.extensions_mut().insert(TokenTenant(token.to_string()));
+        }
+        req
+    }
+
+    #[test]
+    fn test_tenant() {
+        let tenancy = tenancy(&["pilot", "staging"]);
+
+        assert_eq!(tenancy.tenant(&request(None, None)), Ok(None));
+        assert_eq!(tenancy.tenant(&request(Some(" "), None)), Ok(None));
+        // the header alone does not select a tenant
+        assert_eq!(
+            tenancy.tenant(&request(Some("pilot"), None)).unwrap_err().0,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            tenancy.tenant(&request(None, Some("staging"))),
+            Ok(Some("staging".to_string()))
+        );
+        assert_eq!(
+            tenancy.tenant(&request(Some("staging"), Some("staging"))),
+            Ok(Some("staging".to_string()))
+        );
+        assert_eq!(
+            tenancy
+                .tenant(&request(Some("pilot"), Some("staging")))
+                .unwrap_err()
+                .0,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            tenancy
+                .tenant(&request(None, Some("unknown")))
+                .unwrap_err()
+                .0,
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tenancy_scope() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        repos
+            .aircraft
+            .insert(AircraftDataBuilder::new().build())
+            .await
+            .unwrap();
+
+        let count = |tenancy: Tenancy,
+                     header: Option<&'static str>,
+                     token: Option<&'static str>| {
+            let app = Router::new()
+                .route(
+                    "/assets/aircraft",
+                    routing::get(|Extension(repos): Extension<Repositories>| async move {
+                        let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+                        repos
+                            .aircraft
+                            .search(filter)
+                            .await
+                            .unwrap()
+                            .len()
+                            .to_string()
+                    }),
+                )
+                .layer(middleware::from_fn(
+                    move |req: Request<Body>, next: Next<Body>| tenancy.clone().scope(req, next),
+                ))
+                .layer(Extension(repos.clone()));
+
+            let mut req = Request::builder().uri("/assets/aircraft");
+            if let Some(header) = header {
+                req = req.header(TENANT_HEADER, header);
+            }
+            if let Some(token) = token {
+                req = req.extension(TokenTenant(token.to_string()));
+            }
+            async move {
+                let response = app.oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+                let status = response.status();
+                let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                (status, String::from_utf8(body.to_vec()).unwrap())
+            }
+        };
+
+        // the aircraft belongs to the default tenant
+        assert_eq!(
+            count(tenancy(&[]), Some("pilot"), None).await,
+            (StatusCode::OK, "1".to_string())
+        );
+        assert_eq!(
+            count(tenancy(&["pilot"]), None, None).await,
+            (StatusCode::OK, "1".to_string())
+        );
+        assert_eq!(
+            count(tenancy(&["pilot"]), None, Some("pilot")).await,
+            (StatusCode::OK, "0".to_string())
+        );
+        assert_eq!(
+            count(tenancy(&["pilot"]), Some("pilot"), None).await.0,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            count(tenancy(&["pilot"]), None, Some("staging")).await.0,
+            StatusCode::BAD_REQUEST
+        );
+
+        ut_info!("success");
+    }
+}
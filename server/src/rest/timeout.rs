@@ -0,0 +1,120 @@
+//! Request timeout
+//!
+//! Bounds the time a REST request may take, so a hung svc-storage connection
+//! can not hold a worker task indefinitely. The timeout is read from the
+//! [`SharedConfig`] (`REST_REQUEST_TIMEOUT_MS`) for each request, requests
+//! exceeding it are cancelled and answered with `504 GATEWAY_TIMEOUT`.
+//!
+//! The single svc-storage calls have a shorter deadline of their own, see
+//! [`crate::repo::grpc::GrpcRepository`] and [`crate::fan_out`].
+
+use crate::config::SharedConfig;
+use crate::fan_out::call_timeout;
+use axum::{
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::time::Duration;
+
+/// Timeout of the REST requests
+#[derive(Debug, Clone)]
+pub struct RequestTimeout {
+    config: SharedConfig,
+}
+
+impl RequestTimeout {
+    /// Create a new request timeout using the provided configuration
+    pub fn new(config: SharedConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get the current request timeout, `None` if disabled
+    pub fn timeout(&self) -> Option<Duration> {
+        call_timeout(self.config.read(|config| config.rest_request_timeout_ms))
+    }
+
+    /// Middleware function cancelling requests which don't complete within
+    /// the timeout.
+    pub async fn enforce<B>(self, req: Request<B>, next: Next<B>) -> Response {
+        let Some(timeout) = self.timeout() else {
+            return next.run(req).await;
+        };
+
+        let (method, path) = (req.method().clone(), req.uri().path().to_string());
+        match tokio::time::timeout(timeout, next.run(req)).await {
+            Ok(response) => response,
+            Err(_) => {
+                rest_error!(
+                    "{} {} did not complete within {:?}, cancelled.",
+                    method,
+                    path,
+                    timeout
+                );
+                (
+                    StatusCode::GATEWAY_TIMEOUT,
+                    format!("(server) request did not complete within {:?}.", timeout),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use axum::{body::Body, middleware, routing, Router};
+    use tower::ServiceExt;
+
+    async fn request(config: &SharedConfig, path: &str) -> StatusCode {
+        let request_timeout = RequestTimeout::new(config.clone());
+        let app = Router::new()
+            .route("/fast", routing::get(|| async { "ok" }))
+            .route(
+                "/hung",
+                routing::get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "ok"
+                }),
+            )
+            .layer(middleware::from_fn(
+                move |req: Request<Body>, next: Next<Body>| {
+                    request_timeout.clone().enforce(req, next)
+                },
+            ));
+
+        app.oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let mut config = Config::default();
+        config.rest_request_timeout_ms = 50;
+        let config = SharedConfig::from(config);
+        assert_eq!(
+            RequestTimeout::new(config.clone()).timeout(),
+            Some(Duration::from_millis(50))
+        );
+
+        assert_eq!(request(&config, "/fast").await, StatusCode::OK);
+        let start = std::time::Instant::now();
+        assert_eq!(request(&config, "/hung").await, StatusCode::GATEWAY_TIMEOUT);
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        // disabled at runtime
+        let mut new = config.get();
+        new.rest_request_timeout_ms = 0;
+        config.apply(new);
+        assert_eq!(RequestTimeout::new(config.clone()).timeout(), None);
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,219 @@
+//! API versioning
+//!
+//! Every API version is mounted under its own prefix (`/v1`). Requests to
+//! unversioned paths are served by the version requested with the
+//! [`API_VERSION_HEADER`], or by [`ApiVersion::UNVERSIONED`] if the header is
+//! not present, so existing clients of the unversioned paths keep working
+//! when a new version is added.
+
+use axum::{
+    http::{HeaderValue, Request, StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Router,
+};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Header used to request an API version, and to return the served version
+pub const API_VERSION_HEADER: &str = "x-api-version";
+
+/// Versions of the REST API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiVersion {
+    /// First version of the API
+    V1,
+}
+
+impl ApiVersion {
+    /// All versions mounted by the server
+    pub const ALL: &'static [ApiVersion] = &[ApiVersion::V1];
+
+    /// Version serving the unversioned paths when no version is requested
+    pub const UNVERSIONED: ApiVersion = ApiVersion::V1;
+
+    /// Name of the version (for example `v1`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+        }
+    }
+
+    /// Path prefix of the version (for example `/v1`)
+    pub fn prefix(&self) -> String {
+        format!("/{}", self.as_str())
+    }
+}
+
+impl Display for ApiVersion {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ApiVersion {
+    type Err = String;
+
+    /// Parse a version like `v1` or `1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let name = if s.starts_with('v') {
+            s
+        } else {
+            format!("v{s}")
+        };
+        ApiVersion::ALL
+            .iter()
+            .find(|version| version.as_str() == name)
+            .copied()
+            .ok_or_else(|| format!("unsupported API version '{name}'"))
+    }
+}
+
+/// Mount the routes of all API versions under their prefix.
+pub fn versioned_router(routes: impl Fn(ApiVersion) -> Router) -> Router {
+    ApiVersion::ALL
+        .iter()
+        .fold(Router::new(), |router, version| {
+            router.nest(&version.prefix(), routes(*version))
+        })
+}
+
+/// Get the version of a path starting with a version prefix.
+fn path_version(path: &str) -> Option<ApiVersion> {
+    ApiVersion::ALL.iter().copied().find(|version| {
+        path.strip_prefix(&version.prefix())
+            .map(|rest| rest.is_empty() || rest.starts_with('/'))
+            .unwrap_or(false)
+    })
+}
+
+/// Add the version prefix to the path of an uri.
+fn with_prefix(uri: &Uri, version: ApiVersion) -> Option<Uri> {
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{}{}?{}", version.prefix(), uri.path(), query),
+        None => format!("{}{}", version.prefix(), uri.path()),
+    };
+
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(path_and_query.parse().ok()?);
+    Uri::from_parts(parts).ok()
+}
+
+/// Middleware negotiating the API version of a request.
+///
+/// Unversioned paths are rewritten to the prefix of the requested version,
+/// so this middleware must wrap the whole router. The served version is
+/// returned in the [`API_VERSION_HEADER`] response header.
+pub async fn negotiate_version<B>(mut req: Request<B>, next: Next<B>) -> Response {
+    let version = match path_version(req.uri().path()) {
+        Some(version) => version,
+        None => {
+            let version = match req.headers().get(API_VERSION_HEADER) {
+                None => ApiVersion::UNVERSIONED,
+                Some(value) => match value.to_str().map_err(|e| e.to_string()) {
+                    Ok(value) => match value.parse() {
+                        Ok(version) => version,
+                        Err(e) => {
+                            rest_warn!("{e}.");
+                            return (StatusCode::NOT_ACCEPTABLE, e).into_response();
+                        }
+                    },
+                    Err(e) => {
+                        rest_warn!("invalid {API_VERSION_HEADER} header: {e}.");
+                        return StatusCode::BAD_REQUEST.into_response();
+                    }
+                },
+            };
+
+            let Some(uri) = with_prefix(req.uri(), version) else {
+                rest_warn!("could not add version prefix to {}.", req.uri());
+                return StatusCode::BAD_REQUEST.into_response();
+            };
+            *req.uri_mut() = uri;
+            version
+        }
+    };
+
+    let mut response = next.run(req).await;
+    response.headers_mut().insert(
+        API_VERSION_HEADER,
+        HeaderValue::from_static(version.as_str()),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing};
+    use tower::{ServiceBuilder, ServiceExt};
+
+    async fn get(path: &str, version: Option<&str>) -> Response {
+        let router = versioned_router(|version| {
+            Router::new().route(
+                "/assets",
+                routing::get(move || async move { version.to_string() }),
+            )
+        });
+        let app = ServiceBuilder::new()
+            .layer(middleware::from_fn(negotiate_version))
+            .service(router);
+
+        let mut request = Request::get(path);
+        if let Some(version) = version {
+            request = request.header(API_VERSION_HEADER, version);
+        }
+
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!("v1".parse(), Ok(ApiVersion::V1));
+        assert_eq!(" V1 ".parse(), Ok(ApiVersion::V1));
+        assert_eq!("1".parse(), Ok(ApiVersion::V1));
+        assert!("v0".parse::<ApiVersion>().is_err());
+    }
+
+    #[test]
+    fn test_path_version() {
+        assert_eq!(path_version("/v1/assets"), Some(ApiVersion::V1));
+        assert_eq!(path_version("/v1"), Some(ApiVersion::V1));
+        assert_eq!(path_version("/v10/assets"), None);
+        assert_eq!(path_version("/assets/v1"), None);
+
+        let uri: Uri = "/assets/vertipads?limit=1".parse().unwrap();
+        assert_eq!(
+            with_prefix(&uri, ApiVersion::V1).unwrap(),
+            "/v1/assets/vertipads?limit=1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_version() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        for (path, version) in [
+            ("/v1/assets", None),
+            ("/assets", None),
+            ("/assets", Some("v1")),
+        ] {
+            let response = get(path, version).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[API_VERSION_HEADER], "v1");
+        }
+
+        let response = get("/assets", Some("v9")).await;
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+
+        // the path prefix takes precedence over the header
+        let response = get("/v1/assets", Some("v9")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        ut_info!("success");
+    }
+}
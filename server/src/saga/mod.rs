@@ -0,0 +1,167 @@
+//! # Saga
+//!
+//! Compensation of multi-step flows registering several assets.
+//!
+//! svc-storage has no transactions spanning several inserts, so a flow
+//! registering a vertiport together with its vertipads, or importing a batch
+//! of assets, could leave the assets created before a failing step behind. A
+//! [`Saga`] records every asset created by the flow; if a later step fails,
+//! [`Saga::compensate`] removes them again in reverse order. The assets which
+//! could not be removed are returned, so the caller can report them.
+
+#[macro_use]
+pub mod macros;
+
+use crate::repo::Repositories;
+use svc_storage_client_grpc::prelude::{vehicle, vertipad, vertiport};
+use tonic::Status;
+
+/// An asset created by a step of a [`Saga`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Created {
+    /// A vertiport with the id
+    Vertiport(String),
+    /// A vertipad with the id
+    Vertipad(String),
+    /// An aircraft with the id
+    Aircraft(String),
+}
+
+/// Records the assets created by a multi-step flow, to remove them again if
+/// the flow fails
+#[derive(Debug)]
+pub struct Saga {
+    repos: Repositories,
+    name: &'static str,
+    created: Vec<Created>,
+}
+
+impl Saga {
+    /// Start a saga for the named flow, used in the logs
+    pub fn new(name: &'static str, repos: &Repositories) -> Self {
+        Self {
+            repos: repos.clone(),
+            name,
+            created: vec![],
+        }
+    }
+
+    /// Get the assets created so far, in the order they were created
+    pub fn created(&self) -> &[Created] {
+        &self.created
+    }
+
+    /// Insert a vertiport, recording it for compensation
+    pub async fn insert_vertiport(&mut self, data: vertiport::Data) -> Result<String, Status> {
+        let id = self.repos.vertiports.insert(data).await?.id;
+        self.created.push(Created::Vertiport(id.clone()));
+        Ok(id)
+    }
+
+    /// Insert a vertipad, recording it for compensation
+    pub async fn insert_vertipad(&mut self, data: vertipad::Data) -> Result<String, Status> {
+        let id = self.repos.vertipads.insert(data).await?.id;
+        self.created.push(Created::Vertipad(id.clone()));
+        Ok(id)
+    }
+
+    /// Insert an aircraft, recording it for compensation
+    pub async fn insert_aircraft(&mut self, data: vehicle::Data) -> Result<String, Status> {
+        let id = self.repos.aircraft.insert(data).await?.id;
+        self.created.push(Created::Aircraft(id.clone()));
+        Ok(id)
+    }
+
+    /// Remove the created assets again, most recent first.
+    ///
+    /// Removals which fail are logged and skipped, so one unavailable asset
+    /// does not keep the others. Returns the assets which could not be
+    /// removed.
+    pub async fn compensate(self) -> Vec<Created> {
+        saga_warn!(
+            "{}: removing {} created assets.",
+            self.name,
+            self.created.len()
+        );
+
+        let mut remaining = vec![];
+        for created in self.created.into_iter().rev() {
+            let result = match &created {
+                Created::Vertiport(id) => self.repos.vertiports.delete(id.clone()).await,
+                Created::Vertipad(id) => self.repos.vertipads.delete(id.clone()).await,
+                Created::Aircraft(id) => self.repos.aircraft.delete(id.clone()).await,
+            };
+
+            if let Err(e) = result {
+                saga_error!(
+                    "{}: could not remove {:?} during compensation: {}",
+                    self.name,
+                    created,
+                    e
+                );
+                remaining.push(created);
+            }
+        }
+
+        remaining
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{AircraftDataBuilder, VertipadDataBuilder, VertiportDataBuilder};
+    use svc_storage_client_grpc::prelude::AdvancedSearchFilter;
+
+    #[tokio::test]
+    async fn test_saga_compensate() {
+        let repos = Repositories::memory();
+        let mut saga = Saga::new("test", &repos);
+
+        let vertiport_id = saga
+            .insert_vertiport(VertiportDataBuilder::new().build())
+            .await
+            .unwrap();
+        let vertipad_id = saga
+            .insert_vertipad(
+                VertipadDataBuilder::new()
+                    .vertiport_id(vertiport_id.clone())
+                    .build(),
+            )
+            .await
+            .unwrap();
+        let aircraft_id = saga
+            .insert_aircraft(AircraftDataBuilder::new().build())
+            .await
+            .unwrap();
+        assert_eq!(
+            saga.created(),
+            &[
+                Created::Vertiport(vertiport_id.clone()),
+                Created::Vertipad(vertipad_id.clone()),
+                Created::Aircraft(aircraft_id.clone()),
+            ]
+        );
+
+        // an asset removed in the meantime is reported, the others removed
+        repos.vertipads.delete(vertipad_id.clone()).await.unwrap();
+        let remaining = saga.compensate().await;
+        assert_eq!(remaining, vec![Created::Vertipad(vertipad_id)]);
+
+        let filter = || AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+        assert!(repos.vertiports.search(filter()).await.unwrap().is_empty());
+        assert!(repos.aircraft.search(filter()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_saga_insert_error() {
+        let mut saga = Saga::new("test", &Repositories::unavailable());
+        let error = saga
+            .insert_vertiport(VertiportDataBuilder::new().build())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unavailable);
+        assert!(saga.created().is_empty());
+        assert!(saga.compensate().await.is_empty());
+    }
+}
@@ -0,0 +1,918 @@
+//! # Schedule
+//!
+//! Parse asset schedules and expand them into concrete time windows.
+//!
+//! Schedules use the same format as svc-scheduler: one or more events, each
+//! starting with a `DTSTART` line followed by optional `RRULE` lines.
+//!
+//! ```text
+//! DTSTART:20221020T180000Z;DURATION:PT14H
+//! RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR
+//! DTSTART:20221022T000000Z;DURATION:PT24H
+//! RRULE:FREQ=WEEKLY;BYDAY=SA,SU
+//! ```
+//!
+//! Only `DAILY` and `WEEKLY` recurrence rules are supported, with the
+//! `INTERVAL`, `BYDAY`, `COUNT` and `UNTIL` parts.
+//!
+//! Schedules are user input, so the expansion is bounded: durations can not
+//! exceed [`MAX_DURATION_DAYS`], intervals can not exceed [`MAX_INTERVAL`],
+//! occurrences past the supported dates end a rule, and a request expands at
+//! most [`MAX_OCCURRENCES`] occurrences through its [`OccurrenceBudget`].
+//! Occurrences ending before the expanded range are skipped without being
+//! evaluated, so a schedule starting far in the past costs no more than a
+//! recent one.
+
+pub use crate::rest::TimeWindow;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Utc, Weekday};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Maximum number of occurrences evaluated for a single request, shared by
+/// all the schedules it expands
+pub const MAX_OCCURRENCES: u32 = 100_000;
+
+/// Longest duration of an event, in days
+pub const MAX_DURATION_DAYS: i64 = 366;
+
+/// Largest `INTERVAL` of a recurrence rule
+pub const MAX_INTERVAL: u32 = 1000;
+
+/// Errors returned when parsing a schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// The schedule does not contain any events
+    Empty,
+    /// A line could not be parsed
+    InvalidLine(String),
+    /// A date could not be parsed
+    InvalidDate(String),
+    /// A duration could not be parsed
+    InvalidDuration(String),
+    /// A recurrence rule could not be parsed or is not supported
+    InvalidRule(String),
+    /// The expansion exceeded the [`OccurrenceBudget`] of the request
+    TooManyOccurrences,
+}
+
+impl Display for ScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::Empty => write!(f, "schedule contains no events"),
+            ScheduleError::InvalidLine(line) => write!(f, "invalid schedule line: {}", line),
+            ScheduleError::InvalidDate(date) => write!(f, "invalid date: {}", date),
+            ScheduleError::InvalidDuration(duration) => write!(f, "invalid duration: {}", duration),
+            ScheduleError::InvalidRule(rule) => write!(f, "invalid or unsupported rule: {}", rule),
+            ScheduleError::TooManyOccurrences => write!(
+                f,
+                "schedules expand to more than {} occurrences",
+                MAX_OCCURRENCES
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Number of occurrences a request can still expand.
+///
+/// A request expanding several schedules shares a single budget between
+/// them, so the work done for a request stays bounded whatever the number of
+/// schedules, events and rules involved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OccurrenceBudget {
+    remaining: u32,
+}
+
+impl Default for OccurrenceBudget {
+    fn default() -> Self {
+        Self::new(MAX_OCCURRENCES)
+    }
+}
+
+impl OccurrenceBudget {
+    /// Create a budget of `occurrences`
+    pub fn new(occurrences: u32) -> Self {
+        Self {
+            remaining: occurrences,
+        }
+    }
+
+    /// Take an occurrence from the budget
+    fn take(&mut self) -> Result<(), ScheduleError> {
+        self.remaining = self
+            .remaining
+            .checked_sub(1)
+            .ok_or(ScheduleError::TooManyOccurrences)?;
+        Ok(())
+    }
+}
+
+/// Recurrence frequency of a rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+}
+
+/// A recurrence rule (`RRULE`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    by_day: Vec<Weekday>,
+    count: Option<u32>,
+    until: Option<DateTime<Utc>>,
+}
+
+/// A single schedule event with its recurrence rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Event {
+    start: DateTime<Utc>,
+    duration: Duration,
+    rules: Vec<Rule>,
+}
+
+/// A parsed asset schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    events: Vec<Event>,
+}
+
+/// Parse a date in the `20221020T180000Z` format
+fn parse_date(value: &str) -> Result<DateTime<Utc>, ScheduleError> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y%m%dT%H%M%SZ")
+        .map(|date| date.and_utc())
+        .map_err(|_| ScheduleError::InvalidDate(value.to_string()))
+}
+
+/// Parse an ISO 8601 duration like `PT14H`, `P1D` or `P1DT2H30M`, of at
+/// most [`MAX_DURATION_DAYS`]
+fn parse_duration(value: &str) -> Result<Duration, ScheduleError> {
+    let error = || ScheduleError::InvalidDuration(value.to_string());
+    let rest = value.trim().strip_prefix('P').ok_or_else(error)?;
+
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut found = false;
+    for c in rest.chars() {
+        match c {
+            'T' if !in_time && number.is_empty() => in_time = true,
+            '0'..='9' => number.push(c),
+            _ => {
+                let amount: i64 = number.parse().map_err(|_| error())?;
+                number.clear();
+                let part = match (c, in_time) {
+                    ('W', false) => Duration::try_weeks(amount),
+                    ('D', false) => Duration::try_days(amount),
+                    ('H', true) => Duration::try_hours(amount),
+                    ('M', true) => Duration::try_minutes(amount),
+                    ('S', true) => Duration::try_seconds(amount),
+                    _ => return Err(error()),
+                }
+                .ok_or_else(error)?;
+                duration = duration.checked_add(&part).ok_or_else(error)?;
+                found = true;
+            }
+        }
+    }
+
+    if !found
+        || !number.is_empty()
+        || duration <= Duration::zero()
+        || duration > Duration::days(MAX_DURATION_DAYS)
+    {
+        return Err(error());
+    }
+
+    Ok(duration)
+}
+
+/// Parse a weekday abbreviation like `MO`
+fn parse_weekday(value: &str) -> Result<Weekday, ScheduleError> {
+    match value.trim() {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        other => Err(ScheduleError::InvalidRule(other.to_string())),
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ScheduleError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = || ScheduleError::InvalidRule(value.to_string());
+
+        let mut frequency = None;
+        let mut rule = Rule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            by_day: vec![],
+            count: None,
+            until: None,
+        };
+
+        for part in value.split(';').filter(|part| !part.trim().is_empty()) {
+            let (key, val) = part.split_once('=').ok_or_else(error)?;
+            match key.trim() {
+                "FREQ" => {
+                    frequency = Some(match val.trim() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        _ => return Err(error()),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = val.trim().parse().map_err(|_| error())?;
+                    if rule.interval == 0 || rule.interval > MAX_INTERVAL {
+                        return Err(error());
+                    }
+                }
+                "BYDAY" => {
+                    rule.by_day = val
+                        .split(',')
+                        .map(parse_weekday)
+                        .collect::<Result<_, _>>()?
+                }
+                "COUNT" => rule.count = Some(val.trim().parse().map_err(|_| error())?),
+                "UNTIL" => rule.until = Some(parse_date(val)?),
+                // Week start does not change the expansion of the supported rules
+                "WKST" => (),
+                _ => return Err(error()),
+            }
+        }
+
+        rule.frequency = frequency.ok_or_else(error)?;
+        Ok(rule)
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = ScheduleError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut events: Vec<Event> = vec![];
+
+        for line in value.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(rule) = line.strip_prefix("RRULE:") {
+                let event = events
+                    .last_mut()
+                    .ok_or_else(|| ScheduleError::InvalidLine(line.to_string()))?;
+                event.rules.push(rule.parse()?);
+                continue;
+            }
+
+            let Some(dtstart) = line.strip_prefix("DTSTART:") else {
+                return Err(ScheduleError::InvalidLine(line.to_string()));
+            };
+            let (start, duration) = dtstart
+                .split_once(";DURATION:")
+                .ok_or_else(|| ScheduleError::InvalidLine(line.to_string()))?;
+
+            events.push(Event {
+                start: parse_date(start)?,
+                duration: parse_duration(duration)?,
+                rules: vec![],
+            });
+        }
+
+        if events.is_empty() {
+            return Err(ScheduleError::Empty);
+        }
+
+        Ok(Schedule { events })
+    }
+}
+
+/// Get the start of the `index`th period of `period_days` after `start`, or
+/// `None` past the supported dates
+fn nth_period(start: DateTime<Utc>, period_days: i64, index: i64) -> Option<DateTime<Utc>> {
+    period_days
+        .checked_mul(index)
+        .and_then(Duration::try_days)
+        .and_then(|offset| start.checked_add_signed(offset))
+}
+
+/// Get the number of whole periods of `period_days` from `start` to `after`
+fn periods_before(start: DateTime<Utc>, after: DateTime<Utc>, period_days: i64) -> i64 {
+    match after > start {
+        true => (after - start).num_days() / period_days,
+        false => 0,
+    }
+}
+
+impl Rule {
+    /// Call `f` with each occurrence start of this rule, in order, until
+    /// `f` returns `false` or the rule ends.
+    ///
+    /// Occurrences starting before `after` may be skipped without calling
+    /// `f`, they still count towards the `COUNT` of the rule. Each evaluated
+    /// occurrence is taken from the `budget`.
+    fn for_each_occurrence(
+        &self,
+        start: DateTime<Utc>,
+        after: DateTime<Utc>,
+        budget: &mut OccurrenceBudget,
+        mut f: impl FnMut(DateTime<Utc>) -> bool,
+    ) -> Result<(), ScheduleError> {
+        let interval = i64::from(self.interval);
+        let mut emitted: u64 = 0;
+        let mut emit = |occurrence: DateTime<Utc>, emitted: &mut u64| -> bool {
+            if self.until.map_or(false, |until| occurrence > until)
+                || self
+                    .count
+                    .map_or(false, |count| *emitted >= u64::from(count))
+            {
+                return false;
+            }
+            *emitted += 1;
+            f(occurrence)
+        };
+
+        match self.frequency {
+            Frequency::Daily => {
+                let skipped = periods_before(start, after, interval);
+                emitted = skipped as u64;
+                for index in skipped.. {
+                    budget.take()?;
+                    let Some(occurrence) = nth_period(start, interval, index) else {
+                        return Ok(());
+                    };
+                    if !emit(occurrence, &mut emitted) {
+                        return Ok(());
+                    }
+                }
+            }
+            Frequency::Weekly => {
+                let start_day = start.weekday().num_days_from_monday() as i64;
+                let mut days: Vec<i64> = match self.by_day.is_empty() {
+                    true => vec![start_day],
+                    false => self
+                        .by_day
+                        .iter()
+                        .map(|day| day.num_days_from_monday() as i64)
+                        .collect(),
+                };
+                days.sort_unstable();
+                days.dedup();
+
+                let Some(week_start) = start.checked_sub_signed(Duration::days(start_day)) else {
+                    return Ok(());
+                };
+
+                // the first week only has the days from the start on
+                let skipped = periods_before(week_start, after, 7 * interval);
+                if skipped > 0 {
+                    let first_week = days.iter().filter(|day| **day >= start_day).count() as u64;
+                    emitted = first_week + (skipped as u64 - 1) * days.len() as u64;
+                }
+
+                for week in skipped.. {
+                    let Some(week_occurrence) = nth_period(week_start, 7 * interval, week) else {
+                        return Ok(());
+                    };
+                    for day in days.iter() {
+                        budget.take()?;
+                        let Some(occurrence) = nth_period(week_occurrence, 1, *day) else {
+                            return Ok(());
+                        };
+                        if occurrence < start {
+                            continue;
+                        }
+                        if !emit(occurrence, &mut emitted) {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Schedule {
+    /// Get the windows of this schedule overlapping the provided range.
+    ///
+    /// Windows are clipped to the range, sorted and merged where they
+    /// overlap or touch. The occurrences evaluated are taken from the
+    /// `budget` of the request.
+    pub fn windows(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        budget: &mut OccurrenceBudget,
+    ) -> Result<Vec<TimeWindow>, ScheduleError> {
+        let mut windows = vec![];
+
+        for event in self.events.iter() {
+            let mut add = |start: DateTime<Utc>| -> bool {
+                if start >= to {
+                    return false;
+                }
+                // an end past the supported dates is past the range too
+                let end = start
+                    .checked_add_signed(event.duration)
+                    .map_or(to, |end| end.min(to));
+                if end > from {
+                    windows.push(TimeWindow {
+                        start: start.max(from),
+                        end,
+                    });
+                }
+                true
+            };
+
+            if event.rules.is_empty() {
+                budget.take()?;
+                add(event.start);
+                continue;
+            }
+
+            // occurrences starting before this have ended before the range
+            let after = from
+                .checked_sub_signed(event.duration)
+                .unwrap_or(DateTime::<Utc>::MIN_UTC);
+            for rule in event.rules.iter() {
+                rule.for_each_occurrence(event.start, after, budget, &mut add)?;
+            }
+        }
+
+        Ok(merge_windows(windows))
+    }
+}
+
+/// Sort the provided windows and merge the ones overlapping or touching
+pub fn merge_windows(mut windows: Vec<TimeWindow>) -> Vec<TimeWindow> {
+    windows.sort_by_key(|window| window.start);
+
+    let mut merged: Vec<TimeWindow> = Vec::with_capacity(windows.len());
+    for window in windows
+        .into_iter()
+        .filter(|window| window.end > window.start)
+    {
+        match merged.last_mut() {
+            Some(last) if window.start <= last.end => last.end = last.end.max(window.end),
+            _ => merged.push(window),
+        }
+    }
+
+    merged
+}
+
+/// Get the windows covered by both lists of windows
+pub fn intersect_windows(a: &[TimeWindow], b: &[TimeWindow]) -> Vec<TimeWindow> {
+    let a = merge_windows(a.to_vec());
+    let b = merge_windows(b.to_vec());
+
+    let mut windows = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].start.max(b[j].start);
+        let end = a[i].end.min(b[j].end);
+        if start < end {
+            windows.push(TimeWindow { start, end });
+        }
+
+        if a[i].end < b[j].end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    windows
+}
+
+/// Remove the excluded window from the provided windows
+pub fn subtract_window(windows: Vec<TimeWindow>, excluded: &TimeWindow) -> Vec<TimeWindow> {
+    windows
+        .into_iter()
+        .flat_map(|window| {
+            [
+                TimeWindow {
+                    start: window.start,
+                    end: window.end.min(excluded.start),
+                },
+                TimeWindow {
+                    start: window.start.max(excluded.end),
+                    end: window.end,
+                },
+            ]
+        })
+        .filter(|window| window.end > window.start)
+        .collect()
+}
+
+/// Remove all excluded windows from the provided windows
+pub fn subtract_windows(windows: Vec<TimeWindow>, excluded: &[TimeWindow]) -> Vec<TimeWindow> {
+    excluded.iter().fold(windows, |windows, excluded| {
+        subtract_window(windows, excluded)
+    })
+}
+
+/// Get the windows within the provided range where the `child` schedule
+/// allows operation while the `parent` schedule does not.
+pub fn schedule_conflicts(
+    child: &Schedule,
+    parent: &Schedule,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    budget: &mut OccurrenceBudget,
+) -> Result<Vec<TimeWindow>, ScheduleError> {
+    Ok(subtract_windows(
+        child.windows(from, to, budget)?,
+        &parent.windows(from, to, budget)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> DateTime<Utc> {
+        parse_date(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT14H"), Ok(Duration::hours(14)));
+        assert_eq!(parse_duration("P1D"), Ok(Duration::days(1)));
+        assert_eq!(
+            parse_duration("P1DT2H30M"),
+            Ok(Duration::days(1) + Duration::hours(2) + Duration::minutes(30))
+        );
+        assert!(parse_duration("14H").is_err());
+        assert!(parse_duration("PT").is_err());
+        assert!(parse_duration("P1H").is_err());
+        assert!(parse_duration("PT0H").is_err());
+
+        // out of range amounts are rejected instead of overflowing
+        assert!(parse_duration("P999999999999D").is_err());
+        assert!(parse_duration("P99999999999999999999W").is_err());
+        assert!(parse_duration("P366DT24H").is_err());
+        assert_eq!(
+            parse_duration("P366D"),
+            Ok(Duration::days(MAX_DURATION_DAYS))
+        );
+    }
+
+    #[test]
+    fn test_parse_schedule() {
+        let schedule: Schedule = "DTSTART:20221020T180000Z;DURATION:PT14H\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR\n\
+            DTSTART:20221022T000000Z;DURATION:PT24H\n\
+            RRULE:FREQ=WEEKLY;BYDAY=SA,SU"
+            .parse()
+            .unwrap();
+        assert_eq!(schedule.events.len(), 2);
+        assert_eq!(schedule.events[0].rules[0].by_day.len(), 5);
+
+        assert_eq!("".parse::<Schedule>(), Err(ScheduleError::Empty));
+        assert!("RRULE:FREQ=DAILY".parse::<Schedule>().is_err());
+        assert!("invalid".parse::<Schedule>().is_err());
+        assert!("DTSTART:20221020T180000Z;DURATION:PT1H\nRRULE:FREQ=MONTHLY"
+            .parse::<Schedule>()
+            .is_err());
+        assert!(
+            "DTSTART:20221020T180000Z;DURATION:PT1H\nRRULE:FREQ=DAILY;INTERVAL=4294967295"
+                .parse::<Schedule>()
+                .is_err()
+        );
+        assert!(
+            "DTSTART:20221020T180000Z;DURATION:PT1H\nRRULE:FREQ=DAILY;INTERVAL=1001"
+                .parse::<Schedule>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_windows_single_event() {
+        let schedule: Schedule = "DTSTART:20221020T180000Z;DURATION:PT2H".parse().unwrap();
+
+        let windows = schedule
+            .windows(
+                date("20221020T000000Z"),
+                date("20221021T000000Z"),
+                &mut OccurrenceBudget::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            windows,
+            vec![TimeWindow {
+                start: date("20221020T180000Z"),
+                end: date("20221020T200000Z"),
+            }]
+        );
+
+        // clipped to the requested range
+        let windows = schedule
+            .windows(
+                date("20221020T190000Z"),
+                date("20221021T000000Z"),
+                &mut OccurrenceBudget::default(),
+            )
+            .unwrap();
+        assert_eq!(windows[0].start, date("20221020T190000Z"));
+
+        // outside of the requested range
+        assert!(schedule
+            .windows(
+                date("20221021T000000Z"),
+                date("20221022T000000Z"),
+                &mut OccurrenceBudget::default()
+            )
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_windows_weekly() {
+        // 2022-10-20 is a Thursday
+        let schedule: Schedule = "DTSTART:20221020T080000Z;DURATION:PT10H\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,TH"
+            .parse()
+            .unwrap();
+
+        let windows = schedule
+            .windows(
+                date("20221020T000000Z"),
+                date("20221028T000000Z"),
+                &mut OccurrenceBudget::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            windows,
+            vec![
+                TimeWindow {
+                    start: date("20221020T080000Z"),
+                    end: date("20221020T180000Z"),
+                },
+                TimeWindow {
+                    start: date("20221024T080000Z"),
+                    end: date("20221024T180000Z"),
+                },
+                TimeWindow {
+                    start: date("20221027T080000Z"),
+                    end: date("20221027T180000Z"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_windows_daily_count_and_merge() {
+        // Back to back days merge into a single window
+        let schedule: Schedule = "DTSTART:20221020T000000Z;DURATION:PT24H\n\
+            RRULE:FREQ=DAILY;COUNT=3"
+            .parse()
+            .unwrap();
+
+        let windows = schedule
+            .windows(
+                date("20221001T000000Z"),
+                date("20221101T000000Z"),
+                &mut OccurrenceBudget::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            windows,
+            vec![TimeWindow {
+                start: date("20221020T000000Z"),
+                end: date("20221023T000000Z"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_windows_skip_past_occurrences() {
+        // starting centuries ago takes no more occurrences than starting
+        // now, 0001-01-01 is a Monday and 2022-10-17 starts an even week
+        let schedule: Schedule = "DTSTART:00010101T080000Z;DURATION:PT10H\n\
+            RRULE:FREQ=DAILY\n\
+            DTSTART:00010101T000000Z;DURATION:PT2H\n\
+            RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH"
+            .parse()
+            .unwrap();
+        let mut budget = OccurrenceBudget::new(20);
+        let windows = schedule
+            .windows(
+                date("20221020T000000Z"),
+                date("20221022T000000Z"),
+                &mut budget,
+            )
+            .unwrap();
+        assert_eq!(
+            windows,
+            vec![
+                TimeWindow {
+                    start: date("20221020T000000Z"),
+                    end: date("20221020T020000Z"),
+                },
+                TimeWindow {
+                    start: date("20221020T080000Z"),
+                    end: date("20221020T180000Z"),
+                },
+                TimeWindow {
+                    start: date("20221021T080000Z"),
+                    end: date("20221021T180000Z"),
+                },
+            ]
+        );
+
+        // skipped occurrences still count, 2022-10-20 is a Thursday
+        let schedule: Schedule = "DTSTART:20221020T080000Z;DURATION:PT10H\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,TH;COUNT=3"
+            .parse()
+            .unwrap();
+        let windows = |from: &str, to: &str| {
+            schedule
+                .windows(date(from), date(to), &mut OccurrenceBudget::default())
+                .unwrap()
+        };
+        assert_eq!(
+            windows("20221026T000000Z", "20221101T000000Z"),
+            vec![TimeWindow {
+                start: date("20221027T080000Z"),
+                end: date("20221027T180000Z"),
+            }]
+        );
+        assert!(windows("20221101T000000Z", "20221201T000000Z").is_empty());
+    }
+
+    #[test]
+    fn test_windows_bounded() {
+        // an event at the end of the supported dates ends the rule
+        let start = DateTime::<Utc>::MAX_UTC - Duration::hours(1);
+        let schedule = Schedule {
+            events: vec![Event {
+                start,
+                duration: Duration::hours(2),
+                rules: vec!["FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR,SA,SU".parse().unwrap()],
+            }],
+        };
+        let to = DateTime::<Utc>::MAX_UTC;
+        assert_eq!(
+            schedule
+                .windows(
+                    start - Duration::days(1),
+                    to,
+                    &mut OccurrenceBudget::default()
+                )
+                .unwrap(),
+            vec![TimeWindow { start, end: to }]
+        );
+
+        // the budget is shared by all the schedules of a request
+        let schedule: Schedule = "DTSTART:20221020T000000Z;DURATION:PT1H\nRRULE:FREQ=DAILY"
+            .parse()
+            .unwrap();
+        let from = date("20221020T000000Z");
+        let to = date("20221030T000000Z");
+        let mut budget = OccurrenceBudget::new(15);
+        assert_eq!(schedule.windows(from, to, &mut budget).unwrap().len(), 10);
+        assert_eq!(
+            schedule.windows(from, to, &mut budget),
+            Err(ScheduleError::TooManyOccurrences)
+        );
+
+        // a rule without an end stops at the end of the budget
+        let mut budget = OccurrenceBudget::default();
+        assert_eq!(
+            schedule.windows(from, DateTime::<Utc>::MAX_UTC, &mut budget),
+            Err(ScheduleError::TooManyOccurrences)
+        );
+    }
+
+    #[test]
+    fn test_intersect_windows() {
+        let window = |start: &str, end: &str| TimeWindow {
+            start: date(start),
+            end: date(end),
+        };
+
+        let a = vec![
+            window("20221020T000000Z", "20221021T000000Z"),
+            window("20221022T000000Z", "20221024T000000Z"),
+        ];
+        let b = vec![
+            window("20221020T120000Z", "20221022T120000Z"),
+            window("20221023T000000Z", "20221023T060000Z"),
+        ];
+
+        assert_eq!(
+            intersect_windows(&a, &b),
+            vec![
+                window("20221020T120000Z", "20221021T000000Z"),
+                window("20221022T000000Z", "20221022T120000Z"),
+                window("20221023T000000Z", "20221023T060000Z"),
+            ]
+        );
+        assert_eq!(intersect_windows(&b, &a), intersect_windows(&a, &b));
+        assert!(intersect_windows(&a, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_subtract_window() {
+        let window = |start: &str, end: &str| TimeWindow {
+            start: date(start),
+            end: date(end),
+        };
+
+        let windows = vec![
+            window("20221020T000000Z", "20221021T000000Z"),
+            window("20221022T000000Z", "20221024T000000Z"),
+        ];
+
+        assert_eq!(
+            subtract_window(
+                windows.clone(),
+                &window("20221020T120000Z", "20221022T120000Z")
+            ),
+            vec![
+                window("20221020T000000Z", "20221020T120000Z"),
+                window("20221022T120000Z", "20221024T000000Z"),
+            ]
+        );
+        assert_eq!(
+            subtract_window(
+                windows.clone(),
+                &window("20221022T060000Z", "20221022T120000Z")
+            ),
+            vec![
+                window("20221020T000000Z", "20221021T000000Z"),
+                window("20221022T000000Z", "20221022T060000Z"),
+                window("20221022T120000Z", "20221024T000000Z"),
+            ]
+        );
+        assert!(subtract_window(
+            windows.clone(),
+            &window("20221019T000000Z", "20221025T000000Z")
+        )
+        .is_empty());
+        assert_eq!(
+            subtract_window(
+                windows.clone(),
+                &window("20221025T000000Z", "20221026T000000Z")
+            ),
+            windows
+        );
+    }
+
+    #[test]
+    fn test_schedule_conflicts() {
+        let window = |start: &str, end: &str| TimeWindow {
+            start: date(start),
+            end: date(end),
+        };
+
+        // vertiport open 06:00-22:00 every day
+        let parent: Schedule = "DTSTART:20221020T060000Z;DURATION:PT16H\nRRULE:FREQ=DAILY"
+            .parse()
+            .unwrap();
+        let from = date("20221020T000000Z");
+        let to = date("20221022T000000Z");
+
+        let inside: Schedule = "DTSTART:20221020T080000Z;DURATION:PT8H\nRRULE:FREQ=DAILY"
+            .parse()
+            .unwrap();
+        assert!(
+            schedule_conflicts(&inside, &parent, from, to, &mut OccurrenceBudget::default())
+                .unwrap()
+                .is_empty()
+        );
+
+        let late: Schedule = "DTSTART:20221020T200000Z;DURATION:PT4H\nRRULE:FREQ=DAILY"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            schedule_conflicts(&late, &parent, from, to, &mut OccurrenceBudget::default()).unwrap(),
+            vec![
+                window("20221020T220000Z", "20221021T000000Z"),
+                window("20221021T220000Z", "20221022T000000Z"),
+            ]
+        );
+
+        assert_eq!(
+            subtract_windows(
+                vec![window("20221020T000000Z", "20221021T000000Z")],
+                &[
+                    window("20221020T020000Z", "20221020T040000Z"),
+                    window("20221020T060000Z", "20221020T220000Z"),
+                ]
+            ),
+            vec![
+                window("20221020T000000Z", "20221020T020000Z"),
+                window("20221020T040000Z", "20221020T060000Z"),
+                window("20221020T220000Z", "20221021T000000Z"),
+            ]
+        );
+    }
+}
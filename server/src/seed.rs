@@ -0,0 +1,431 @@
+//! # Seed
+//!
+//! Optional startup seeding from a manifest file (`--seed <file>`), so demo
+//! and test environments get a reproducible fleet.
+//!
+//! The manifest lists vertiports with their vertipads, and aircraft. Assets
+//! which are already registered are skipped: vertiports are matched by
+//! name, vertipads by name within their vertiport and aircraft by
+//! registration number. Missing assets are validated like REST
+//! registrations before they are registered.
+//!
+//! Manifests with a `.json` extension are read as JSON, all other manifests
+//! as YAML.
+
+use crate::repo::Repositories;
+use crate::rest::api::aircraft::check_vehicle_data;
+use crate::rest::api::vertipad::{check_vertipad_data, VertipadChecks};
+use crate::rest::api::vertiport::validate_vertiport_data;
+
+use serde::Deserialize;
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+use svc_storage_client_grpc::prelude::{
+    vehicle, vertipad, vertiport, AdvancedSearchFilter, GeoPointZ, GeoPolygonZ,
+};
+use tonic::Status;
+
+/// Assets to register at startup
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SeedManifest {
+    /// Vertiports with their vertipads
+    #[serde(default)]
+    pub vertiports: Vec<SeedVertiport>,
+    /// Aircraft, in the same format as the REST registration payload
+    #[serde(default)]
+    pub aircraft: Vec<vehicle::Data>,
+}
+
+/// A vertiport of a [`SeedManifest`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeedVertiport {
+    /// Name of the vertiport, used to find an existing vertiport
+    pub name: String,
+    /// Description of the vertiport
+    #[serde(default)]
+    pub description: String,
+    /// Area of the vertiport
+    pub geo_location: GeoPolygonZ,
+    /// Optional schedule of the vertiport
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// Vertipads of the vertiport
+    #[serde(default)]
+    pub vertipads: Vec<SeedVertipad>,
+}
+
+/// A vertipad of a [`SeedVertiport`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeedVertipad {
+    /// Name of the vertipad, used to find an existing vertipad
+    pub name: String,
+    /// Location of the vertipad
+    pub geo_location: GeoPointZ,
+    /// Whether the vertipad is in business, `true` if not provided
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Optional schedule of the vertipad
+    #[serde(default)]
+    pub schedule: Option<String>,
+}
+
+/// Vertipads are enabled unless the manifest says otherwise
+fn default_enabled() -> bool {
+    true
+}
+
+/// Errors reading a seed manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeedError {
+    /// The manifest file could not be read.
+    Read(String),
+    /// The manifest is not a valid JSON or YAML manifest.
+    Parse(String),
+}
+
+impl Display for SeedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedError::Read(e) => write!(f, "could not read seed manifest: {e}"),
+            SeedError::Parse(e) => write!(f, "invalid seed manifest: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SeedError {}
+
+/// Number of assets registered, skipped and failed while seeding
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SeedSummary {
+    /// Number of registered vertiports
+    pub vertiports: usize,
+    /// Number of registered vertipads
+    pub vertipads: usize,
+    /// Number of registered aircraft
+    pub aircraft: usize,
+    /// Number of assets which were already registered
+    pub skipped: usize,
+    /// Number of assets which could not be registered
+    pub failed: usize,
+}
+
+impl Display for SeedSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "registered {} vertiports, {} vertipads and {} aircraft, skipped {} existing, {} failed",
+            self.vertiports, self.vertipads, self.aircraft, self.skipped, self.failed
+        )
+    }
+}
+
+/// Parse a manifest, as JSON if the path has a `.json` extension and as
+/// YAML otherwise.
+pub fn parse_manifest(path: &Path, content: &str) -> Result<SeedManifest, SeedError> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("json") => {
+            serde_json::from_str(content).map_err(|e| SeedError::Parse(e.to_string()))
+        }
+        _ => serde_yaml::from_str(content).map_err(|e| SeedError::Parse(e.to_string())),
+    }
+}
+
+/// Read and parse the manifest at the provided path.
+pub fn read_manifest(path: &Path) -> Result<SeedManifest, SeedError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| SeedError::Read(format!("{}: {e}", path.display())))?;
+    parse_manifest(path, &content)
+}
+
+/// Get the id of the vertiport with the provided name.
+async fn find_vertiport(repos: &Repositories, name: &str) -> Result<Option<String>, Status> {
+    let filter = AdvancedSearchFilter::search_equals("name".to_string(), name.to_string())
+        .and_is_null("deleted_at".to_string());
+
+    Ok(repos
+        .vertiports
+        .search(filter)
+        .await?
+        .into_iter()
+        .find(|object| object.data.as_ref().map(|data| data.name == name) == Some(true))
+        .map(|object| object.id))
+}
+
+/// Check if the vertiport has a vertipad with the provided name.
+async fn vertipad_exists(
+    repos: &Repositories,
+    vertiport_id: &str,
+    name: &str,
+) -> Result<bool, Status> {
+    let filter =
+        AdvancedSearchFilter::search_equals("vertiport_id".to_string(), vertiport_id.to_string())
+            .and_is_null("deleted_at".to_string());
+
+    Ok(repos
+        .vertipads
+        .search(filter)
+        .await?
+        .into_iter()
+        .filter_map(|object| object.data)
+        .any(|data| data.vertiport_id == vertiport_id && data.name == name))
+}
+
+/// Check if an aircraft with the provided registration number exists.
+async fn aircraft_exists(repos: &Repositories, registration_number: &str) -> Result<bool, Status> {
+    let filter = AdvancedSearchFilter::search_equals(
+        "registration_number".to_string(),
+        registration_number.to_string(),
+    )
+    .and_is_null("deleted_at".to_string());
+
+    Ok(repos
+        .aircraft
+        .search(filter)
+        .await?
+        .into_iter()
+        .filter_map(|object| object.data)
+        .any(|data| data.registration_number == registration_number.trim()))
+}
+
+/// Get the id of the vertiport of the manifest, registering it if missing.
+async fn seed_vertiport(
+    repos: &Repositories,
+    vertiport: &SeedVertiport,
+    summary: &mut SeedSummary,
+) -> Result<String, String> {
+    if let Some(id) = find_vertiport(repos, &vertiport.name)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        summary.skipped += 1;
+        return Ok(id);
+    }
+
+    let data = validate_vertiport_data(vertiport::Data {
+        name: vertiport.name.clone(),
+        description: vertiport.description.clone(),
+        geo_location: Some(vertiport.geo_location.clone()),
+        schedule: vertiport.schedule.clone(),
+        created_at: None,
+        updated_at: None,
+    })
+    .map_err(|errors| format!("{errors:?}"))?;
+
+    let id = repos
+        .vertiports
+        .insert(data)
+        .await
+        .map_err(|e| e.to_string())?
+        .id;
+    summary.vertiports += 1;
+    Ok(id)
+}
+
+/// Register the vertipad of the manifest if missing.
+async fn seed_vertipad(
+    repos: &Repositories,
+    vertiport_id: &str,
+    vertipad: &SeedVertipad,
+    checks: VertipadChecks,
+    summary: &mut SeedSummary,
+) -> Result<(), String> {
+    if vertipad_exists(repos, vertiport_id, &vertipad.name)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        summary.skipped += 1;
+        return Ok(());
+    }
+
+    let data = vertipad::Data {
+        name: vertipad.name.clone(),
+        vertiport_id: vertiport_id.to_string(),
+        geo_location: Some(vertipad.geo_location.clone()),
+        enabled: vertipad.enabled,
+        occupied: false,
+        schedule: vertipad.schedule.clone(),
+        created_at: None,
+        updated_at: None,
+    };
+    let data = check_vertipad_data(repos, data, checks)
+        .await
+        .map_err(|(status, errors)| format!("{status} {:?}", errors.0))?;
+
+    repos
+        .vertipads
+        .insert(data)
+        .await
+        .map_err(|e| e.to_string())?;
+    summary.vertipads += 1;
+    Ok(())
+}
+
+/// Register the aircraft of the manifest if missing.
+async fn seed_aircraft(
+    repos: &Repositories,
+    data: &vehicle::Data,
+    summary: &mut SeedSummary,
+) -> Result<(), String> {
+    if aircraft_exists(repos, &data.registration_number)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        summary.skipped += 1;
+        return Ok(());
+    }
+
+    let data = check_vehicle_data(repos, data.clone())
+        .await
+        .map_err(|(status, errors)| format!("{status} {:?}", errors.0))?;
+
+    repos
+        .aircraft
+        .insert(data)
+        .await
+        .map_err(|e| e.to_string())?;
+    summary.aircraft += 1;
+    Ok(())
+}
+
+/// Register the assets of the manifest which are missing.
+///
+/// Assets which can not be registered are logged and counted as failed,
+/// the vertipads of a failed vertiport are counted as failed as well.
+pub async fn seed(
+    repos: &Repositories,
+    manifest: &SeedManifest,
+    checks: VertipadChecks,
+) -> SeedSummary {
+    let mut summary = SeedSummary::default();
+
+    for vertiport in manifest.vertiports.iter() {
+        let vertiport_id = match seed_vertiport(repos, vertiport, &mut summary).await {
+            Ok(id) => id,
+            Err(e) => {
+                log::warn!(
+                    "(seed) could not seed vertiport '{}': {}",
+                    vertiport.name,
+                    e
+                );
+                summary.failed += 1 + vertiport.vertipads.len();
+                continue;
+            }
+        };
+
+        for vertipad in vertiport.vertipads.iter() {
+            if let Err(e) =
+                seed_vertipad(repos, &vertiport_id, vertipad, checks, &mut summary).await
+            {
+                log::warn!(
+                    "(seed) could not seed vertipad '{}' of vertiport '{}': {}",
+                    vertipad.name,
+                    vertiport.name,
+                    e
+                );
+                summary.failed += 1;
+            }
+        }
+    }
+
+    for aircraft in manifest.aircraft.iter() {
+        if let Err(e) = seed_aircraft(repos, aircraft, &mut summary).await {
+            log::warn!(
+                "(seed) could not seed aircraft '{}': {}",
+                aircraft.registration_number,
+                e
+            );
+            summary.failed += 1;
+        }
+    }
+
+    log::info!("(seed) {}.", summary);
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, SharedConfig};
+
+    fn checks() -> VertipadChecks {
+        VertipadChecks::from_config(&SharedConfig::from(Config::default()))
+    }
+
+    const MANIFEST: &str = r#"
+vertiports:
+  - name: Vertiport A
+    description: Seeded
+    geo_location:
+      rings:
+        - points:
+            - { x: 4.0, y: 52.0, z: 0.0 }
+            - { x: 4.01, y: 52.0, z: 0.0 }
+            - { x: 4.01, y: 52.01, z: 0.0 }
+            - { x: 4.0, y: 52.0, z: 0.0 }
+    vertipads:
+      - name: Pad 1
+        geo_location: { x: 4.005, y: 52.005, z: 0.0 }
+      - name: Pad 2
+        geo_location: { x: 4.006, y: 52.005, z: 0.0 }
+        enabled: false
+"#;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = parse_manifest(Path::new("fleet.yaml"), MANIFEST).unwrap();
+        assert_eq!(manifest.vertiports.len(), 1);
+        assert_eq!(manifest.vertiports[0].vertipads.len(), 2);
+        assert!(manifest.vertiports[0].vertipads[0].enabled);
+        assert!(!manifest.vertiports[0].vertipads[1].enabled);
+        assert!(manifest.aircraft.is_empty());
+
+        let manifest = parse_manifest(Path::new("fleet.json"), r#"{"vertiports": []}"#).unwrap();
+        assert!(manifest.vertiports.is_empty());
+
+        assert!(matches!(
+            parse_manifest(Path::new("fleet.json"), MANIFEST),
+            Err(SeedError::Parse(_))
+        ));
+        assert!(matches!(
+            read_manifest(Path::new("/nonexistent/fleet.yaml")),
+            Err(SeedError::Read(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_seed() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let manifest = parse_manifest(Path::new("fleet.yaml"), MANIFEST).unwrap();
+
+        let summary = seed(&repos, &manifest, checks()).await;
+        assert_eq!(
+            summary,
+            SeedSummary {
+                vertiports: 1,
+                vertipads: 2,
+                ..Default::default()
+            }
+        );
+
+        // seeding again only skips the registered assets
+        let summary = seed(&repos, &manifest, checks()).await;
+        assert_eq!(
+            summary,
+            SeedSummary {
+                skipped: 3,
+                ..Default::default()
+            }
+        );
+
+        // vertipads of an invalid vertiport fail as well
+        let mut manifest = manifest;
+        manifest.vertiports[0].name = " ".to_string();
+        let summary = seed(&repos, &manifest, checks()).await;
+        assert_eq!(summary.failed, 3);
+
+        ut_info!("success");
+    }
+}
@@ -0,0 +1,102 @@
+//! # Shared State
+//!
+//! State shared by the REST and gRPC servers, so changes made through one
+//! API are visible through the other. [`SharedState::open`] persists the
+//! parts svc-storage has no field for, see [`crate::state`].
+
+use crate::aircraft_status::AircraftStatuses;
+use crate::group_schedule::GroupSchedules;
+use crate::rest::occupancy::OccupancyLog;
+use crate::startup::Startup;
+use crate::state::StateError;
+use crate::weather_minimums::WeatherMinimums;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// State shared by the REST and gRPC servers.
+///
+/// The default state is kept in memory.
+#[derive(Debug, Clone, Default)]
+pub struct SharedState {
+    /// Persisted schedules of the asset groups, set through REST and
+    /// enforced through gRPC
+    pub group_schedules: GroupSchedules,
+    /// Persisted vertipad occupancy transitions, changed through REST and
+    /// gRPC
+    pub occupancy_log: OccupancyLog,
+    /// Persisted aircraft statuses, set through gRPC and reported through
+    /// REST
+    pub aircraft_statuses: AircraftStatuses,
+    /// Persisted weather minimums of the vertiports, set through REST and
+    /// exposed through gRPC
+    pub weather_minimums: WeatherMinimums,
+    /// Startup state, set by the startup phase and reported by both servers
+    pub startup: Startup,
+    /// Set while the REST server serves requests, reported by the gRPC
+    /// health service
+    pub rest_serving: Serving,
+}
+
+impl SharedState {
+    /// Open the state persisted in the state directory, kept in memory if no
+    /// directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            aircraft_statuses: AircraftStatuses::open(dir)?,
+            weather_minimums: WeatherMinimums::open(dir)?,
+            group_schedules: GroupSchedules::open(dir)?,
+            occupancy_log: OccupancyLog::open(dir)?,
+            ..Default::default()
+        })
+    }
+}
+
+/// Shared flag telling if a server is serving requests
+#[derive(Debug, Clone, Default)]
+pub struct Serving {
+    serving: Arc<AtomicBool>,
+}
+
+/// Marks a server as serving until dropped, see [`Serving::start`]
+#[derive(Debug)]
+pub struct ServingGuard {
+    serving: Arc<AtomicBool>,
+}
+
+impl Drop for ServingGuard {
+    fn drop(&mut self) {
+        self.serving.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Serving {
+    /// Check if the server is serving requests
+    pub fn is_serving(&self) -> bool {
+        self.serving.load(Ordering::SeqCst)
+    }
+
+    /// Mark the server as serving until the returned guard is dropped, so
+    /// the flag is cleared however the server stops
+    pub fn start(&self) -> ServingGuard {
+        self.serving.store(true, Ordering::SeqCst);
+        ServingGuard {
+            serving: self.serving.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serving() {
+        let shared = SharedState::default();
+        assert!(!shared.rest_serving.is_serving());
+
+        let guard = shared.rest_serving.start();
+        assert!(shared.clone().rest_serving.is_serving());
+        drop(guard);
+        assert!(!shared.rest_serving.is_serving());
+    }
+}
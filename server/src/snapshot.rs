@@ -0,0 +1,266 @@
+//! # Snapshot
+//!
+//! Binary snapshot format for the asset inventory, used for backups and to
+//! clone an environment.
+//!
+//! A snapshot starts with a header ([`MAGIC`] followed by the format
+//! [`VERSION`]), followed by any number of records. Each record is a single
+//! byte record kind, followed by the length delimited protobuf encoding of
+//! the storage object. Vertiports are written before vertipads, so the
+//! vertipads can be linked to the imported vertiports.
+
+use prost::Message;
+use std::fmt::{self, Display, Formatter};
+use svc_storage_client_grpc::prelude::{vehicle, vertipad, vertiport};
+
+/// Bytes identifying a snapshot
+pub const MAGIC: &[u8; 8] = b"SVCASNAP";
+
+/// Version of the snapshot format
+pub const VERSION: u8 = 1;
+
+/// Content type of a snapshot body
+pub const CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// Record kind of a vertiport
+const KIND_VERTIPORT: u8 = 1;
+/// Record kind of a vertipad
+const KIND_VERTIPAD: u8 = 2;
+/// Record kind of an aircraft
+const KIND_AIRCRAFT: u8 = 3;
+
+/// Maximum length of a single record, to protect against corrupt snapshots
+const MAX_RECORD_LEN: usize = 1024 * 1024;
+
+/// Errors reading a snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The snapshot does not start with the snapshot header.
+    InvalidHeader,
+    /// The snapshot format version is not supported.
+    UnsupportedVersion(u8),
+    /// A record has an unknown kind.
+    UnknownRecord(u8),
+    /// A record could not be decoded.
+    InvalidRecord(String),
+    /// The snapshot ended in the middle of a record.
+    Truncated,
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::InvalidHeader => write!(f, "missing snapshot header"),
+            SnapshotError::UnsupportedVersion(version) => {
+                write!(f, "unsupported snapshot version {version}")
+            }
+            SnapshotError::UnknownRecord(kind) => write!(f, "unknown record kind {kind}"),
+            SnapshotError::InvalidRecord(e) => write!(f, "invalid record: {e}"),
+            SnapshotError::Truncated => write!(f, "snapshot is truncated"),
+        }
+    }
+}
+
+/// A single object in a snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotRecord {
+    /// A vertiport
+    Vertiport(vertiport::Object),
+    /// A vertipad
+    Vertipad(vertipad::Object),
+    /// An aircraft
+    Aircraft(vehicle::Object),
+}
+
+/// Get the snapshot header.
+pub fn encode_header() -> Vec<u8> {
+    let mut header = MAGIC.to_vec();
+    header.push(VERSION);
+    header
+}
+
+/// Encode a single record.
+pub fn encode_record(record: &SnapshotRecord) -> Vec<u8> {
+    let (kind, payload) = match record {
+        SnapshotRecord::Vertiport(object) => {
+            (KIND_VERTIPORT, object.encode_length_delimited_to_vec())
+        }
+        SnapshotRecord::Vertipad(object) => {
+            (KIND_VERTIPAD, object.encode_length_delimited_to_vec())
+        }
+        SnapshotRecord::Aircraft(object) => {
+            (KIND_AIRCRAFT, object.encode_length_delimited_to_vec())
+        }
+    };
+
+    let mut bytes = Vec::with_capacity(payload.len() + 1);
+    bytes.push(kind);
+    bytes.extend(payload);
+    bytes
+}
+
+/// Incremental snapshot decoder.
+///
+/// Chunks of the snapshot are added with [`SnapshotDecoder::push`], complete
+/// records are taken with [`SnapshotDecoder::next_record`].
+#[derive(Debug, Default)]
+pub struct SnapshotDecoder {
+    buffer: Vec<u8>,
+    header_read: bool,
+}
+
+impl SnapshotDecoder {
+    /// Add a chunk of the snapshot.
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Take the next complete record, if available.
+    pub fn next_record(&mut self) -> Result<Option<SnapshotRecord>, SnapshotError> {
+        if !self.header_read {
+            let header_len = MAGIC.len() + 1;
+            if self.buffer.len() < header_len {
+                return Ok(None);
+            }
+
+            if &self.buffer[..MAGIC.len()] != MAGIC {
+                return Err(SnapshotError::InvalidHeader);
+            }
+
+            let version = self.buffer[MAGIC.len()];
+            if version != VERSION {
+                return Err(SnapshotError::UnsupportedVersion(version));
+            }
+
+            self.buffer.drain(..header_len);
+            self.header_read = true;
+        }
+
+        let Some((&kind, rest)) = self.buffer.split_first() else {
+            return Ok(None);
+        };
+
+        let mut cursor = rest;
+        let len = match prost::encoding::decode_varint(&mut cursor) {
+            Ok(len) => len as usize,
+            // A varint is at most 10 bytes, anything shorter might be incomplete
+            Err(_) if rest.len() < 10 => return Ok(None),
+            Err(e) => return Err(SnapshotError::InvalidRecord(e.to_string())),
+        };
+
+        if len > MAX_RECORD_LEN {
+            return Err(SnapshotError::InvalidRecord(format!(
+                "record of {len} bytes exceeds the maximum of {MAX_RECORD_LEN} bytes"
+            )));
+        }
+
+        let prefix_len = 1 + rest.len() - cursor.len();
+        if cursor.len() < len {
+            return Ok(None);
+        }
+
+        let payload = &cursor[..len];
+        let invalid = |e: prost::DecodeError| SnapshotError::InvalidRecord(e.to_string());
+        let record = match kind {
+            KIND_VERTIPORT => {
+                SnapshotRecord::Vertiport(vertiport::Object::decode(payload).map_err(invalid)?)
+            }
+            KIND_VERTIPAD => {
+                SnapshotRecord::Vertipad(vertipad::Object::decode(payload).map_err(invalid)?)
+            }
+            KIND_AIRCRAFT => {
+                SnapshotRecord::Aircraft(vehicle::Object::decode(payload).map_err(invalid)?)
+            }
+            kind => return Err(SnapshotError::UnknownRecord(kind)),
+        };
+
+        self.buffer.drain(..prefix_len + len);
+        Ok(Some(record))
+    }
+
+    /// Check that the snapshot ended after a complete record.
+    pub fn finish(&self) -> Result<(), SnapshotError> {
+        match (self.header_read, self.buffer.is_empty()) {
+            (true, true) => Ok(()),
+            (false, _) => Err(SnapshotError::InvalidHeader),
+            (true, false) => Err(SnapshotError::Truncated),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<SnapshotRecord> {
+        vec![
+            SnapshotRecord::Vertiport(vertiport::Object {
+                id: "vertiport".to_string(),
+                data: Some(vertiport::mock::get_data_obj()),
+            }),
+            SnapshotRecord::Vertipad(vertipad::Object {
+                id: "vertipad".to_string(),
+                data: Some(vertipad::mock::get_data_obj()),
+            }),
+            SnapshotRecord::Aircraft(vehicle::Object {
+                id: "aircraft".to_string(),
+                data: Some(vehicle::mock::get_data_obj()),
+            }),
+        ]
+    }
+
+    fn snapshot() -> Vec<u8> {
+        let mut bytes = encode_header();
+        for record in records() {
+            bytes.extend(encode_record(&record));
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        // feed the snapshot in small chunks to cover records split across chunks
+        let mut decoder = SnapshotDecoder::default();
+        let mut decoded = vec![];
+        for chunk in snapshot().chunks(7) {
+            decoder.push(chunk);
+            while let Some(record) = decoder.next_record().unwrap() {
+                decoded.push(record);
+            }
+        }
+
+        decoder.finish().unwrap();
+        assert_eq!(decoded, records());
+    }
+
+    #[test]
+    fn test_snapshot_errors() {
+        let mut decoder = SnapshotDecoder::default();
+        decoder.push(b"NOTASNAPSHOT");
+        assert_eq!(decoder.next_record(), Err(SnapshotError::InvalidHeader));
+
+        let mut decoder = SnapshotDecoder::default();
+        decoder.push(MAGIC);
+        decoder.push(&[VERSION + 1]);
+        assert_eq!(
+            decoder.next_record(),
+            Err(SnapshotError::UnsupportedVersion(VERSION + 1))
+        );
+
+        let mut decoder = SnapshotDecoder::default();
+        decoder.push(&encode_header());
+        decoder.push(&[42, 0]);
+        assert_eq!(decoder.next_record(), Err(SnapshotError::UnknownRecord(42)));
+
+        let bytes = snapshot();
+        let mut decoder = SnapshotDecoder::default();
+        decoder.push(&bytes[..bytes.len() - 1]);
+        while decoder.next_record().unwrap().is_some() {}
+        assert_eq!(decoder.finish(), Err(SnapshotError::Truncated));
+
+        assert_eq!(
+            SnapshotDecoder::default().finish(),
+            Err(SnapshotError::InvalidHeader)
+        );
+    }
+}
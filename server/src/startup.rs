@@ -0,0 +1,220 @@
+//! # Startup
+//!
+//! Startup phase waiting for svc-storage. The REST and gRPC servers start
+//! listening right away, but only report ready once svc-storage is ready, so
+//! requests are not routed to a service which can not reach its storage
+//! during a cold cluster start.
+//!
+//! svc-storage is checked with an exponential backoff until it is ready or
+//! the startup timeout expires. The service exits on a timeout, so it is
+//! restarted by its orchestrator.
+//!
+//! The startup state is kept in memory by [`Startup`] and reported by the
+//! health endpoint.
+
+use crate::grpc::client::GrpcClients;
+use crate::rest::api::rest_types::StartupState;
+use crate::Config;
+
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use tokio::time::{sleep, Duration, Instant};
+
+/// Settings of the startup phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartupSettings {
+    /// Time to wait for svc-storage, [`None`] to wait without a timeout
+    pub timeout: Option<Duration>,
+    /// Delay after the first failed check
+    pub initial_backoff: Duration,
+    /// Maximum delay between two checks
+    pub max_backoff: Duration,
+}
+
+impl From<&Config> for StartupSettings {
+    fn from(config: &Config) -> Self {
+        Self {
+            timeout: match config.startup_timeout_secs {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            },
+            initial_backoff: Duration::from_millis(config.startup_backoff_initial_ms),
+            max_backoff: Duration::from_millis(config.startup_backoff_max_ms),
+        }
+    }
+}
+
+/// svc-storage did not become ready before the startup timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartupTimeout {
+    /// Number of readiness checks made
+    pub attempts: u32,
+    /// Time spent waiting
+    pub elapsed: Duration,
+}
+
+impl Display for StartupTimeout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "svc-storage not ready after {} checks in {}s",
+            self.attempts,
+            self.elapsed.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for StartupTimeout {}
+
+/// Shared handle to the [`StartupState`] of the service
+#[derive(Debug, Clone, Default)]
+pub struct Startup {
+    state: Arc<RwLock<StartupState>>,
+}
+
+impl Startup {
+    /// Get the current startup state
+    pub fn state(&self) -> StartupState {
+        match self.state.read() {
+            Ok(state) => *state,
+            Err(poisoned) => *poisoned.into_inner(),
+        }
+    }
+
+    /// Set the startup state
+    pub fn set(&self, state: StartupState) {
+        match self.state.write() {
+            Ok(mut current) => *current = state,
+            Err(poisoned) => *poisoned.into_inner() = state,
+        }
+    }
+
+    /// Check if the startup phase completed
+    pub fn is_ready(&self) -> bool {
+        self.state() == StartupState::Ready
+    }
+}
+
+/// Wait until the `ready` check succeeds, doubling the delay between checks
+/// up to the maximum backoff.
+///
+/// Sets the [`Startup`] to [`StartupState::Ready`] and returns the number
+/// of checks made, or sets it to [`StartupState::TimedOut`] if the timeout
+/// expires first.
+pub async fn wait_until_ready<F, Fut>(
+    settings: &StartupSettings,
+    startup: &Startup,
+    mut ready: F,
+) -> Result<u32, StartupTimeout>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let start = Instant::now();
+    let mut backoff = settings.initial_backoff;
+    let mut attempts = 0;
+    startup.set(StartupState::WaitingForStorage);
+
+    loop {
+        attempts += 1;
+        if ready().await {
+            startup.set(StartupState::Ready);
+            return Ok(attempts);
+        }
+
+        let elapsed = start.elapsed();
+        let delay = match settings.timeout {
+            Some(timeout) if elapsed >= timeout => {
+                startup.set(StartupState::TimedOut);
+                return Err(StartupTimeout { attempts, elapsed });
+            }
+            Some(timeout) => backoff.min(timeout - elapsed),
+            None => backoff,
+        };
+
+        log::info!(
+            "(wait_until_ready) dependencies not ready after {} checks, retrying in {}ms.",
+            attempts,
+            delay.as_millis()
+        );
+        sleep(delay).await;
+        backoff = (backoff * 2).min(settings.max_backoff);
+    }
+}
+
+/// Wait until the svc-storage clients are ready, see [`wait_until_ready`]
+pub async fn wait_for_storage(
+    settings: &StartupSettings,
+    startup: &Startup,
+    grpc_clients: &GrpcClients,
+) -> Result<u32, StartupTimeout> {
+    wait_until_ready(settings, startup, || grpc_clients.storage_ready()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn settings(timeout_ms: Option<u64>) -> StartupSettings {
+        StartupSettings {
+            timeout: timeout_ms.map(Duration::from_millis),
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+        }
+    }
+
+    #[test]
+    fn test_startup_settings_from_config() {
+        let mut config = Config::default();
+        let settings = StartupSettings::from(&config);
+        assert_eq!(settings.timeout, Some(Duration::from_secs(300)));
+        assert_eq!(settings.initial_backoff, Duration::from_millis(500));
+        assert_eq!(settings.max_backoff, Duration::from_millis(10000));
+
+        config.startup_timeout_secs = 0;
+        assert_eq!(StartupSettings::from(&config).timeout, None);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let startup = Startup::default();
+        assert_eq!(startup.state(), StartupState::WaitingForStorage);
+
+        // ready on the third check
+        let checks = AtomicU32::new(0);
+        let result = wait_until_ready(&settings(None), &startup, || async {
+            checks.fetch_add(1, Ordering::SeqCst) >= 2
+        })
+        .await;
+        assert_eq!(result, Ok(3));
+        assert!(startup.is_ready());
+
+        // never ready
+        let result = wait_until_ready(&settings(Some(20)), &startup, || async { false }).await;
+        let timeout = result.unwrap_err();
+        assert!(timeout.attempts > 1);
+        assert!(timeout.elapsed >= Duration::from_millis(20));
+        assert_eq!(startup.state(), StartupState::TimedOut);
+
+        ut_info!("Success.");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_storage() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let startup = Startup::default();
+        let grpc_clients = GrpcClients::default(Config::default());
+        let result = wait_for_storage(&settings(Some(1000)), &startup, &grpc_clients).await;
+        assert_eq!(result, Ok(1));
+        assert!(startup.is_ready());
+
+        ut_info!("Success.");
+    }
+}
@@ -0,0 +1,389 @@
+//! # Persisted State
+//!
+//! Side tables for the data svc-storage has no field for, like the operator
+//! owning an asset. Each [`StateTable`] is a JSON file named after the table
+//! in the `state_dir` of the [`Config`](crate::Config), loaded when the
+//! service starts.
+//!
+//! A change is written to a temporary file which then replaces the table
+//! file, so a crash leaves either the old or the new table behind. The whole
+//! table is written for each change, which suits tables of a few thousand
+//! entries. The files belong to a single instance of the service: replicas
+//! do not see each other's changes, so a deployment relying on these tables
+//! runs one instance with the `state_dir` on a persistent volume.
+//!
+//! Records which are only added to, like the audit trail, are kept in a
+//! [`StateLog`] instead: a JSON lines file appended to for each record, so a
+//! record is written without rewriting the others. A record cut short by a
+//! crash is dropped when the log is opened again.
+//!
+//! Without a `state_dir` the tables and logs are kept in memory and are lost
+//! on restart, which is only meant for tests and local development.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Errors reading or writing a [`StateTable`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateError {
+    /// The table file could not be read or written
+    Io(String),
+    /// The table file is not a valid table
+    Format(String),
+}
+
+impl Display for StateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::Io(e) => write!(f, "could not access state table: {e}"),
+            StateError::Format(e) => write!(f, "invalid state table: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+/// A table of values by id, persisted in the state directory
+#[derive(Debug)]
+pub struct StateTable<V> {
+    path: Option<PathBuf>,
+    entries: Arc<Mutex<HashMap<String, V>>>,
+}
+
+// derived Clone would require `V: Clone` for the shared handle
+impl<V> Clone for StateTable<V> {
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<V> Default for StateTable<V> {
+    fn default() -> Self {
+        Self {
+            path: None,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<V: Serialize + DeserializeOwned + Clone> StateTable<V> {
+    /// Open the table `name` in the state directory, creating the directory
+    /// if needed. The table is kept in memory if no directory is provided.
+    pub fn open(dir: Option<&str>, name: &str) -> Result<Self, StateError> {
+        let Some(dir) = dir else {
+            return Ok(Self::default());
+        };
+
+        fs::create_dir_all(dir).map_err(|e| StateError::Io(format!("{dir}: {e}")))?;
+        let path = Path::new(dir).join(format!("{name}.json"));
+        let entries = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| StateError::Format(format!("{}: {e}", path.display())))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(StateError::Io(format!("{}: {e}", path.display()))),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            entries: Arc::new(Mutex::new(entries)),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, V>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Write the entries to a temporary file replacing the table file
+    fn persist(&self, entries: &HashMap<String, V>) -> Result<(), StateError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let io_error = |e: std::io::Error| StateError::Io(format!("{}: {e}", path.display()));
+        let bytes = serde_json::to_vec(entries).map_err(|e| StateError::Format(e.to_string()))?;
+        let tmp = path.with_extension("json.tmp");
+        let mut file = fs::File::create(&tmp).map_err(io_error)?;
+        file.write_all(&bytes).map_err(io_error)?;
+        file.sync_all().map_err(io_error)?;
+        fs::rename(&tmp, path).map_err(io_error)
+    }
+
+    /// Get the value of an id
+    pub fn get(&self, id: &str) -> Option<V> {
+        self.lock().get(id).cloned()
+    }
+
+    /// Read the entries of the table
+    pub fn read<T>(&self, f: impl FnOnce(&HashMap<String, V>) -> T) -> T {
+        f(&self.lock())
+    }
+
+    /// Change the entries of the table and persist them.
+    ///
+    /// The change is applied to a copy of the entries, which replaces the
+    /// entries once it is written, so the table is unchanged if writing
+    /// fails.
+    pub fn update<T>(&self, f: impl FnOnce(&mut HashMap<String, V>) -> T) -> Result<T, StateError> {
+        let mut entries = self.lock();
+        let mut changed = entries.clone();
+        let result = f(&mut changed);
+        self.persist(&changed)?;
+        *entries = changed;
+
+        Ok(result)
+    }
+
+    /// Set the value of an id, returning the previous value
+    pub fn insert(&self, id: &str, value: V) -> Result<Option<V>, StateError> {
+        self.update(|entries| entries.insert(id.to_string(), value))
+    }
+
+    /// Remove the value of an id, returning it
+    pub fn remove(&self, id: &str) -> Result<Option<V>, StateError> {
+        self.update(|entries| entries.remove(id))
+    }
+}
+
+/// An append-only log of records, persisted in the state directory.
+///
+/// Records are numbered from 1 in the order they are added.
+#[derive(Debug)]
+pub struct StateLog<V> {
+    path: Option<PathBuf>,
+    records: Arc<Mutex<LogRecords<V>>>,
+}
+
+/// Number of records of a [`StateLog`], and the records themselves if the
+/// log is kept in memory
+#[derive(Debug)]
+struct LogRecords<V> {
+    len: u64,
+    records: Vec<V>,
+}
+
+// derived Clone would require `V: Clone` for the shared handle
+impl<V> Clone for StateLog<V> {
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            records: self.records.clone(),
+        }
+    }
+}
+
+impl<V> Default for StateLog<V> {
+    fn default() -> Self {
+        Self {
+            path: None,
+            records: Arc::new(Mutex::new(LogRecords {
+                len: 0,
+                records: vec![],
+            })),
+        }
+    }
+}
+
+impl<V: Serialize + DeserializeOwned + Clone> StateLog<V> {
+    /// Open the log `name` in the state directory, creating the directory if
+    /// needed. The log is kept in memory if no directory is provided.
+    ///
+    /// A last record cut short by a crash is removed from the file.
+    pub fn open(dir: Option<&str>, name: &str) -> Result<Self, StateError> {
+        let Some(dir) = dir else {
+            return Ok(Self::default());
+        };
+
+        fs::create_dir_all(dir).map_err(|e| StateError::Io(format!("{dir}: {e}")))?;
+        let path = Path::new(dir).join(format!("{name}.jsonl"));
+        let io_error = |e: std::io::Error| StateError::Io(format!("{}: {e}", path.display()));
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(e) => return Err(io_error(e)),
+        };
+
+        let complete = bytes
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |last| last + 1);
+        if complete < bytes.len() {
+            OpenOptions::new()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_len(complete as u64))
+                .map_err(io_error)?;
+        }
+        let len = bytes[..complete]
+            .iter()
+            .filter(|byte| **byte == b'\n')
+            .count() as u64;
+
+        Ok(Self {
+            path: Some(path),
+            records: Arc::new(Mutex::new(LogRecords {
+                len,
+                records: vec![],
+            })),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LogRecords<V>> {
+        self.records
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Add the record created by `record` from its number.
+    ///
+    /// The record is not added if it can not be written.
+    pub fn append(&self, record: impl FnOnce(u64) -> V) -> Result<(), StateError> {
+        let mut records = self.lock();
+        let record = record(records.len + 1);
+        match &self.path {
+            Some(path) => {
+                let io_error =
+                    |e: std::io::Error| StateError::Io(format!("{}: {e}", path.display()));
+                let mut line =
+                    serde_json::to_vec(&record).map_err(|e| StateError::Format(e.to_string()))?;
+                line.push(b'\n');
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(io_error)?;
+                file.write_all(&line).map_err(io_error)?;
+                file.sync_data().map_err(io_error)?;
+            }
+            None => records.records.push(record),
+        }
+        records.len += 1;
+
+        Ok(())
+    }
+
+    /// Call `f` with each record, oldest first
+    pub fn for_each(&self, mut f: impl FnMut(V)) -> Result<(), StateError> {
+        let records = self.lock();
+        let Some(path) = &self.path else {
+            records.records.iter().cloned().for_each(f);
+            return Ok(());
+        };
+
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(StateError::Io(format!("{}: {e}", path.display()))),
+        };
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| StateError::Io(format!("{}: {e}", path.display())))?;
+            let record = serde_json::from_str(&line)
+                .map_err(|e| StateError::Format(format!("{}: {e}", path.display())))?;
+            f(record);
+        }
+
+        Ok(())
+    }
+
+    /// Get the number of records
+    pub fn len(&self) -> u64 {
+        self.lock().len
+    }
+
+    /// Check if the log has no records
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Create an empty state directory for a test
+#[cfg(any(test, feature = "testing"))]
+pub fn test_state_dir() -> String {
+    std::env::temp_dir()
+        .join(format!("svc-assets-{}", lib_common::uuid::Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_table() {
+        let table = StateTable::<String>::default();
+        assert_eq!(table.insert("a", "1".to_string()), Ok(None));
+        assert_eq!(table.get("a"), Some("1".to_string()));
+        assert_eq!(table.remove("a"), Ok(Some("1".to_string())));
+        assert_eq!(table.get("a"), None);
+    }
+
+    #[test]
+    fn test_state_table_persisted() {
+        let dir = test_state_dir();
+
+        let table = StateTable::<u32>::open(Some(&dir), "counts").unwrap();
+        table.insert("a", 1).unwrap();
+        table.insert("b", 2).unwrap();
+        table.remove("b").unwrap();
+        assert_eq!(table.update(|entries| entries.len()), Ok(1));
+
+        // a reopened table has the persisted entries
+        let table = StateTable::<u32>::open(Some(&dir), "counts").unwrap();
+        assert_eq!(table.get("a"), Some(1));
+        assert_eq!(table.get("b"), None);
+        let other = StateTable::<u32>::open(Some(&dir), "other").unwrap();
+        assert_eq!(other.read(|entries| entries.len()), 0);
+
+        fs::write(Path::new(&dir).join("broken.json"), b"{").unwrap();
+        assert!(matches!(
+            StateTable::<u32>::open(Some(&dir), "broken"),
+            Err(StateError::Format(_))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_state_log() {
+        let collect = |log: &StateLog<String>| {
+            let mut records = vec![];
+            log.for_each(|record| records.push(record)).unwrap();
+            records
+        };
+
+        let log = StateLog::<String>::default();
+        assert!(log.is_empty());
+        log.append(|number| format!("record {number}")).unwrap();
+        log.append(|number| format!("record {number}")).unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(collect(&log), vec!["record 1", "record 2"]);
+
+        let dir = test_state_dir();
+        let log = StateLog::<String>::open(Some(&dir), "records").unwrap();
+        assert_eq!(collect(&log), Vec::<String>::new());
+        log.append(|number| format!("record {number}")).unwrap();
+        log.append(|number| format!("record {number}")).unwrap();
+
+        // a record cut short by a crash is dropped, the others are kept
+        let path = Path::new(&dir).join("records.jsonl");
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"\"record").unwrap();
+        let log = StateLog::<String>::open(Some(&dir), "records").unwrap();
+        assert_eq!(log.len(), 2);
+        log.append(|number| format!("record {number}")).unwrap();
+        assert_eq!(collect(&log), vec!["record 1", "record 2", "record 3"]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,392 @@
+//! # Testing
+//!
+//! Builders producing valid storage `Data` objects for tests, so a test only
+//! needs to set the fields it cares about. The builders are available to
+//! integrators through the `testing` feature, for example for contract tests
+//! against the REST API.
+//!
+//! Every `build` result passes the same validation as a registration
+//! payload: identifiers are random uuids, names are not empty and the
+//! locations are valid coordinates. The default vertipad location lies
+//! inside the default vertiport area.
+//!
+//! The `seeded` constructors derive the identifiers and numbers from a
+//! seed instead, so the same seed always builds the same data, for example
+//! in doc examples or snapshot tests.
+
+use lib_common::time::{DateTime, Utc};
+use lib_common::uuid::{Builder, Uuid};
+use svc_storage_client_grpc::prelude::{
+    vehicle, vertipad, vertiport, GeoLineStringZ, GeoPointZ, GeoPolygonZ,
+};
+
+/// Create a version 4 uuid from a seed, `stream` tells apart the uuids
+/// made from the same seed
+fn seeded_uuid(seed: u64, stream: u64) -> Uuid {
+    // splitmix64
+    let mut state = seed ^ stream.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    let mut next = || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    };
+    let bytes = (u128::from(next()) << 64 | u128::from(next())).to_be_bytes();
+    Builder::from_random_bytes(bytes).into_uuid()
+}
+
+/// Builder of a valid [`vehicle::Data`]
+#[derive(Debug, Clone)]
+pub struct AircraftDataBuilder {
+    data: vehicle::Data,
+}
+
+impl Default for AircraftDataBuilder {
+    fn default() -> Self {
+        Self {
+            data: vehicle::Data {
+                hangar_id: None,
+                hangar_bay_id: None,
+                vehicle_model_id: Uuid::new_v4().to_string(),
+                serial_number: Uuid::new_v4().to_string(),
+                registration_number: Uuid::new_v4().to_string(),
+                description: None,
+                asset_group_id: None,
+                schedule: None,
+                last_maintenance: None,
+                next_maintenance: None,
+                created_at: None,
+                updated_at: None,
+            },
+        }
+    }
+}
+
+impl AircraftDataBuilder {
+    /// Create a builder with a random model, serial number and
+    /// registration number
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a builder with a model, serial number and registration number
+    /// derived from `seed`
+    pub fn seeded(seed: u64) -> Self {
+        Self::default()
+            .vehicle_model_id(seeded_uuid(seed, 0).to_string())
+            .serial_number(format!("SN-{:08}", seed % 100_000_000))
+            .registration_number(format!("N{}AE", seed % 100_000))
+    }
+
+    /// Set the hangar and hangar bay
+    pub fn hangar(
+        mut self,
+        hangar_id: impl Into<String>,
+        hangar_bay_id: impl Into<String>,
+    ) -> Self {
+        self.data.hangar_id = Some(hangar_id.into());
+        self.data.hangar_bay_id = Some(hangar_bay_id.into());
+        self
+    }
+
+    /// Set the vehicle model
+    pub fn vehicle_model_id(mut self, vehicle_model_id: impl Into<String>) -> Self {
+        self.data.vehicle_model_id = vehicle_model_id.into();
+        self
+    }
+
+    /// Set the serial number
+    pub fn serial_number(mut self, serial_number: impl Into<String>) -> Self {
+        self.data.serial_number = serial_number.into();
+        self
+    }
+
+    /// Set the registration number
+    pub fn registration_number(mut self, registration_number: impl Into<String>) -> Self {
+        self.data.registration_number = registration_number.into();
+        self
+    }
+
+    /// Set the description
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.data.description = Some(description.into());
+        self
+    }
+
+    /// Set the asset group
+    pub fn asset_group_id(mut self, asset_group_id: impl Into<String>) -> Self {
+        self.data.asset_group_id = Some(asset_group_id.into());
+        self
+    }
+
+    /// Set the schedule
+    pub fn schedule(mut self, schedule: impl Into<String>) -> Self {
+        self.data.schedule = Some(schedule.into());
+        self
+    }
+
+    /// Set the last and next maintenance
+    pub fn maintenance(mut self, last: DateTime<Utc>, next: DateTime<Utc>) -> Self {
+        self.data.last_maintenance = Some(last.into());
+        self.data.next_maintenance = Some(next.into());
+        self
+    }
+
+    /// Set the creation and update time
+    pub fn timestamps(mut self, created_at: DateTime<Utc>, updated_at: DateTime<Utc>) -> Self {
+        self.data.created_at = Some(created_at.into());
+        self.data.updated_at = Some(updated_at.into());
+        self
+    }
+
+    /// Get the built data
+    pub fn build(self) -> vehicle::Data {
+        self.data
+    }
+}
+
+/// Builder of a valid [`vertiport::Data`]
+#[derive(Debug, Clone)]
+pub struct VertiportDataBuilder {
+    data: vertiport::Data,
+}
+
+impl Default for VertiportDataBuilder {
+    fn default() -> Self {
+        let point = |x: f64, y: f64| GeoPointZ { x, y, z: 0.0 };
+        Self {
+            data: vertiport::Data {
+                name: "Test Vertiport".to_string(),
+                description: "Test description".to_string(),
+                geo_location: Some(GeoPolygonZ {
+                    rings: vec![GeoLineStringZ {
+                        points: vec![
+                            point(4.0, 52.0),
+                            point(4.2, 52.0),
+                            point(4.2, 52.2),
+                            point(4.0, 52.2),
+                            point(4.0, 52.0),
+                        ],
+                    }],
+                }),
+                schedule: None,
+                created_at: None,
+                updated_at: None,
+            },
+        }
+    }
+}
+
+impl VertiportDataBuilder {
+    /// Create a builder of a vertiport with a square area
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a builder of a vertiport with a square area and a name and
+    /// description derived from `seed`
+    pub fn seeded(seed: u64) -> Self {
+        Self::default()
+            .name(format!("Vertiport {seed}"))
+            .description(format!("Vertiport number {seed}"))
+    }
+
+    /// Set the name
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.data.name = name.into();
+        self
+    }
+
+    /// Set the description
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.data.description = description.into();
+        self
+    }
+
+    /// Set the area
+    pub fn geo_location(mut self, geo_location: GeoPolygonZ) -> Self {
+        self.data.geo_location = Some(geo_location);
+        self
+    }
+
+    /// Set the schedule
+    pub fn schedule(mut self, schedule: impl Into<String>) -> Self {
+        self.data.schedule = Some(schedule.into());
+        self
+    }
+
+    /// Set the creation and update time
+    pub fn timestamps(mut self, created_at: DateTime<Utc>, updated_at: DateTime<Utc>) -> Self {
+        self.data.created_at = Some(created_at.into());
+        self.data.updated_at = Some(updated_at.into());
+        self
+    }
+
+    /// Get the built data
+    pub fn build(self) -> vertiport::Data {
+        self.data
+    }
+}
+
+/// Builder of a valid [`vertipad::Data`]
+#[derive(Debug, Clone)]
+pub struct VertipadDataBuilder {
+    data: vertipad::Data,
+}
+
+impl Default for VertipadDataBuilder {
+    fn default() -> Self {
+        Self {
+            data: vertipad::Data {
+                name: "Test Vertipad".to_string(),
+                vertiport_id: Uuid::new_v4().to_string(),
+                geo_location: Some(GeoPointZ {
+                    x: 4.1,
+                    y: 52.1,
+                    z: 0.0,
+                }),
+                enabled: true,
+                occupied: false,
+                schedule: None,
+                created_at: None,
+                updated_at: None,
+            },
+        }
+    }
+}
+
+impl VertipadDataBuilder {
+    /// Create a builder of an enabled, unoccupied vertipad at a random
+    /// vertiport
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a builder of an enabled, unoccupied vertipad with a name and
+    /// vertiport derived from `seed`
+    pub fn seeded(seed: u64) -> Self {
+        Self::default()
+            .name(format!("Vertipad {seed}"))
+            .vertiport_id(seeded_uuid(seed, 1).to_string())
+    }
+
+    /// Set the name
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.data.name = name.into();
+        self
+    }
+
+    /// Set the vertiport
+    pub fn vertiport_id(mut self, vertiport_id: impl Into<String>) -> Self {
+        self.data.vertiport_id = vertiport_id.into();
+        self
+    }
+
+    /// Set the location
+    pub fn geo_location(mut self, geo_location: GeoPointZ) -> Self {
+        self.data.geo_location = Some(geo_location);
+        self
+    }
+
+    /// Set if the vertipad is enabled
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.data.enabled = enabled;
+        self
+    }
+
+    /// Set if the vertipad is occupied
+    pub fn occupied(mut self, occupied: bool) -> Self {
+        self.data.occupied = occupied;
+        self
+    }
+
+    /// Set the schedule
+    pub fn schedule(mut self, schedule: impl Into<String>) -> Self {
+        self.data.schedule = Some(schedule.into());
+        self
+    }
+
+    /// Set the creation and update time
+    pub fn timestamps(mut self, created_at: DateTime<Utc>, updated_at: DateTime<Utc>) -> Self {
+        self.data.created_at = Some(created_at.into());
+        self.data.updated_at = Some(updated_at.into());
+        self
+    }
+
+    /// Get the built data
+    pub fn build(self) -> vertipad::Data {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo::point_in_polygon;
+    use crate::rest::api::aircraft::validate_vehicle_data;
+    use crate::rest::api::vertipad::validate_vertipad_data;
+    use crate::rest::api::vertiport::validate_vertiport_data;
+
+    #[test]
+    fn test_builders_are_valid() {
+        let now = Utc::now();
+        let aircraft = AircraftDataBuilder::new()
+            .hangar(Uuid::new_v4().to_string(), Uuid::new_v4().to_string())
+            .asset_group_id(Uuid::new_v4().to_string())
+            .maintenance(now, now)
+            .timestamps(now, now)
+            .build();
+        assert!(validate_vehicle_data(aircraft).is_ok());
+
+        let vertiport = VertiportDataBuilder::new().build();
+        let vertipad = VertipadDataBuilder::new().build();
+        assert!(point_in_polygon(
+            vertipad.geo_location.as_ref().unwrap(),
+            vertiport.geo_location.as_ref().unwrap()
+        ));
+        assert!(validate_vertiport_data(vertiport).is_ok());
+        assert!(validate_vertipad_data(vertipad).is_ok());
+    }
+
+    #[test]
+    fn test_builders_are_random() {
+        let first = AircraftDataBuilder::new().build();
+        let second = AircraftDataBuilder::new().build();
+        assert_ne!(first.registration_number, second.registration_number);
+        assert_ne!(
+            VertipadDataBuilder::new().build().vertiport_id,
+            VertipadDataBuilder::new().build().vertiport_id
+        );
+    }
+
+    #[test]
+    fn test_builders_are_seeded() {
+        let aircraft = AircraftDataBuilder::seeded(42).build();
+        assert_eq!(aircraft, AircraftDataBuilder::seeded(42).build());
+        assert_eq!(aircraft.serial_number, "SN-00000042");
+        assert_eq!(aircraft.registration_number, "N42AE");
+        assert_ne!(
+            aircraft.vehicle_model_id,
+            AircraftDataBuilder::seeded(43).build().vehicle_model_id
+        );
+        assert!(validate_vehicle_data(aircraft.clone()).is_ok());
+
+        let model = Uuid::parse_str(&aircraft.vehicle_model_id).unwrap();
+        assert_eq!(model.get_version_num(), 4);
+
+        let vertiport = VertiportDataBuilder::seeded(42).build();
+        assert_eq!(vertiport, VertiportDataBuilder::seeded(42).build());
+        assert_eq!(vertiport.name, "Vertiport 42");
+        assert_ne!(
+            vertiport.description,
+            VertiportDataBuilder::seeded(43).build().description
+        );
+        assert!(validate_vertiport_data(vertiport).is_ok());
+
+        let vertipad = VertipadDataBuilder::seeded(42).build();
+        assert_eq!(vertipad, VertipadDataBuilder::seeded(42).build());
+        assert_ne!(vertipad.vertiport_id, aircraft.vehicle_model_id);
+        assert!(validate_vertipad_data(vertipad).is_ok());
+    }
+}
@@ -0,0 +1,571 @@
+//! # TLS
+//!
+//! Optional TLS termination for the REST and gRPC servers, enabled by
+//! setting both `tls_cert_path` and `tls_key_path` in the [`Config`].
+//!
+//! With a `tls_client_ca_path` the servers request a client certificate
+//! signed by that CA (mTLS). Callers without a certificate are still
+//! accepted unless `tls_client_auth_required` is set; the gRPC mutations
+//! always require one, see [`crate::grpc::auth`].
+//!
+//! The certificate files are checked for changes at a fixed interval and
+//! reloaded without a restart, so rotated certificates are picked up by new
+//! connections. If the new files are invalid the current certificates are
+//! kept.
+
+use crate::Config;
+
+use futures::Stream;
+use rustls::server::{AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient};
+use rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::time::{interval, timeout, Duration, MissedTickBehavior};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+/// Interval at which the certificate files are checked for changes
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Time a client has to complete the TLS handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of established connections waiting to be served
+const ACCEPT_BACKLOG: usize = 128;
+
+/// Errors returned when loading the TLS configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TlsError {
+    /// TLS is partially configured, with the name of the missing setting
+    Incomplete(&'static str),
+    /// A certificate or key file could not be read
+    File {
+        /// Path of the file
+        path: String,
+        /// Why the file could not be read
+        reason: String,
+    },
+    /// The certificates or key were rejected
+    Invalid(String),
+}
+
+impl Display for TlsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsError::Incomplete(setting) => write!(f, "TLS requires '{}' to be set", setting),
+            TlsError::File { path, reason } => write!(f, "could not read {}: {}", path, reason),
+            TlsError::Invalid(reason) => write!(f, "invalid certificates: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for TlsError {}
+
+/// Protocol negotiated with clients through ALPN
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// HTTP/1.1, used by the REST server
+    Http1,
+    /// HTTP/2, required by gRPC
+    Http2,
+}
+
+impl Protocol {
+    fn alpn(self) -> Vec<Vec<u8>> {
+        match self {
+            Protocol::Http1 => vec![b"http/1.1".to_vec()],
+            Protocol::Http2 => vec![b"h2".to_vec()],
+        }
+    }
+}
+
+/// Certificate files and client authentication settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsSettings {
+    /// Path of the PEM encoded certificate chain of the server
+    pub cert_path: String,
+    /// Path of the PEM encoded private key of the server
+    pub key_path: String,
+    /// Path of the PEM encoded CA certificates client certificates are
+    /// verified with, client certificates are not requested if not set
+    pub client_ca_path: Option<String>,
+    /// Reject clients without a valid certificate
+    pub client_auth_required: bool,
+}
+
+impl TlsSettings {
+    /// Get the TLS settings of the configuration, `None` if TLS is disabled
+    pub fn from_config(config: &Config) -> Result<Option<Self>, TlsError> {
+        let (cert_path, key_path) = match (&config.tls_cert_path, &config.tls_key_path) {
+            (None, None) if config.tls_client_auth_required => {
+                return Err(TlsError::Incomplete("tls_cert_path"))
+            }
+            (None, None) => return Ok(None),
+            (None, Some(_)) => return Err(TlsError::Incomplete("tls_cert_path")),
+            (Some(_), None) => return Err(TlsError::Incomplete("tls_key_path")),
+            (Some(cert_path), Some(key_path)) => (cert_path.clone(), key_path.clone()),
+        };
+
+        if config.tls_client_auth_required && config.tls_client_ca_path.is_none() {
+            return Err(TlsError::Incomplete("tls_client_ca_path"));
+        }
+
+        Ok(Some(Self {
+            cert_path,
+            key_path,
+            client_ca_path: config.tls_client_ca_path.clone(),
+            client_auth_required: config.tls_client_auth_required,
+        }))
+    }
+
+    /// Paths of all files used by these settings
+    fn paths(&self) -> Vec<&str> {
+        let mut paths = vec![self.cert_path.as_str(), self.key_path.as_str()];
+        paths.extend(self.client_ca_path.as_deref());
+        paths
+    }
+
+    /// Modification times of the files, to detect changes
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        self.paths()
+            .into_iter()
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+
+    /// Load the certificates and build a server configuration
+    fn load(&self, protocol: Protocol) -> Result<ServerConfig, TlsError> {
+        let certs = read_certs(&self.cert_path)?;
+        let key = read_key(&self.key_path)?;
+
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = match &self.client_ca_path {
+            None => builder.with_no_client_auth(),
+            Some(path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in read_certs(path)? {
+                    roots
+                        .add(&cert)
+                        .map_err(|e| TlsError::Invalid(format!("{}: {}", path, e)))?;
+                }
+
+                match self.client_auth_required {
+                    true => builder
+                        .with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed()),
+                    false => builder.with_client_cert_verifier(
+                        AllowAnyAnonymousOrAuthenticatedClient::new(roots).boxed(),
+                    ),
+                }
+            }
+        };
+
+        let mut config = builder
+            .with_single_cert(certs, key)
+            .map_err(|e| TlsError::Invalid(e.to_string()))?;
+        config.alpn_protocols = protocol.alpn();
+        Ok(config)
+    }
+}
+
+/// Open a file, mapping errors to [`TlsError::File`]
+fn open(path: &str) -> Result<BufReader<File>, TlsError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| TlsError::File {
+            path: path.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Read all certificates of a PEM file
+fn read_certs(path: &str) -> Result<Vec<Certificate>, TlsError> {
+    let certs = rustls_pemfile::certs(&mut open(path)?).map_err(|e| TlsError::File {
+        path: path.to_string(),
+        reason: e.to_string(),
+    })?;
+    if certs.is_empty() {
+        return Err(TlsError::File {
+            path: path.to_string(),
+            reason: "no certificate found".to_string(),
+        });
+    }
+
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+/// Read the first private key of a PEM file
+fn read_key(path: &str) -> Result<PrivateKey, TlsError> {
+    let items = rustls_pemfile::read_all(&mut open(path)?).map_err(|e| TlsError::File {
+        path: path.to_string(),
+        reason: e.to_string(),
+    })?;
+
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| TlsError::File {
+            path: path.to_string(),
+            reason: "no private key found".to_string(),
+        })
+}
+
+/// TLS configuration of a server, reloaded when the certificate files
+/// change
+#[derive(Clone)]
+pub struct ServerTls {
+    settings: TlsSettings,
+    protocol: Protocol,
+    config: Arc<RwLock<Arc<ServerConfig>>>,
+}
+
+impl std::fmt::Debug for ServerTls {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerTls")
+            .field("settings", &self.settings)
+            .field("protocol", &self.protocol)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ServerTls {
+    /// Load the TLS configuration of a server, `None` if TLS is disabled
+    pub fn from_config(config: &Config, protocol: Protocol) -> Result<Option<Self>, TlsError> {
+        let Some(settings) = TlsSettings::from_config(config)? else {
+            return Ok(None);
+        };
+
+        let config = settings.load(protocol)?;
+        Ok(Some(Self {
+            settings,
+            protocol,
+            config: Arc::new(RwLock::new(Arc::new(config))),
+        }))
+    }
+
+    /// Get the TLS settings of this server
+    pub fn settings(&self) -> &TlsSettings {
+        &self.settings
+    }
+
+    /// Get an acceptor using the current certificates
+    fn acceptor(&self) -> TlsAcceptor {
+        let config = match self.config.read() {
+            Ok(config) => config.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        TlsAcceptor::from(config)
+    }
+
+    /// Load the certificate files again.
+    ///
+    /// Connections established before are not affected. If the files are
+    /// invalid, the current certificates are kept.
+    pub fn reload(&self) -> Result<(), TlsError> {
+        let config = Arc::new(self.settings.load(self.protocol)?);
+        match self.config.write() {
+            Ok(mut current) => *current = config,
+            Err(poisoned) => *poisoned.into_inner() = config,
+        }
+        Ok(())
+    }
+
+    /// Reload the certificates each time the certificate files change
+    pub async fn watch(self) {
+        let mut ticker = interval(RELOAD_CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut modified = self.settings.modified();
+
+        loop {
+            ticker.tick().await;
+
+            let current = self.settings.modified();
+            if current == modified {
+                continue;
+            }
+            modified = current;
+
+            match self.reload() {
+                Ok(()) => log::info!(
+                    "(ServerTls::watch) certificates reloaded from {}.",
+                    self.settings.cert_path
+                ),
+                Err(e) => log::error!(
+                    "(ServerTls::watch) could not reload certificates, keeping the current ones: {}",
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Accept connections from the listener, completing the TLS handshake of
+    /// each connection.
+    ///
+    /// Handshakes run concurrently, connections failing the handshake are
+    /// logged and dropped. Accepting stops when the stream is dropped.
+    pub fn incoming(
+        &self,
+        listener: TcpListener,
+    ) -> impl Stream<Item = Result<TlsStream<TcpStream>, io::Error>> + Send + 'static {
+        let (tx, rx) = mpsc::channel::<TlsStream<TcpStream>>(ACCEPT_BACKLOG);
+        let tls = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let accepted = tokio::select! {
+                    _ = tx.closed() => break,
+                    accepted = listener.accept() => accepted,
+                };
+                let (stream, addr) = match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        log::warn!("(ServerTls::incoming) could not accept connection: {}", e);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+
+                let acceptor = tls.acceptor();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    match timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => {
+                            let _ = tx.send(stream).await;
+                        }
+                        Ok(Err(e)) => {
+                            log::warn!(
+                                "(ServerTls::incoming) TLS handshake with {} failed: {}",
+                                addr,
+                                e
+                            )
+                        }
+                        Err(_) => {
+                            log::warn!(
+                                "(ServerTls::incoming) TLS handshake with {} timed out.",
+                                addr
+                            )
+                        }
+                    }
+                });
+            }
+        });
+
+        futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|stream| (Ok(stream), rx))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use openssl::asn1::Asn1Time;
+    use openssl::hash::MessageDigest;
+    use openssl::nid::Nid;
+    use openssl::pkey::{PKey, Private};
+    use openssl::rsa::Rsa;
+    use openssl::x509::extension::{BasicConstraints, SubjectAlternativeName};
+    use openssl::x509::{X509NameBuilder, X509};
+    use rustls::{ClientConfig, ServerName};
+    use std::path::PathBuf;
+    use tokio::io::AsyncReadExt;
+    use tokio_rustls::TlsConnector;
+
+    /// Create a certificate for `localhost`, signed by the issuer or self
+    /// signed
+    fn certificate(
+        common_name: &str,
+        issuer: Option<(&X509, &PKey<Private>)>,
+    ) -> (X509, PKey<Private>) {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, common_name)
+            .unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        match issuer {
+            Some((issuer, _)) => builder.set_issuer_name(issuer.subject_name()).unwrap(),
+            None => {
+                builder.set_issuer_name(&name).unwrap();
+                builder
+                    .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
+                    .unwrap();
+            }
+        }
+        let san = SubjectAlternativeName::new()
+            .dns("localhost")
+            .build(&builder.x509v3_context(issuer.map(|(cert, _)| &**cert), None))
+            .unwrap();
+        builder.append_extension(san).unwrap();
+        let signing_key = issuer.map_or(&key, |(_, key)| key);
+        builder.sign(signing_key, MessageDigest::sha256()).unwrap();
+
+        (builder.build(), key)
+    }
+
+    /// Write the files of a CA and a server certificate signed by it to a
+    /// new temporary directory
+    fn write_files(dir: &PathBuf) -> (X509, PKey<Private>) {
+        std::fs::create_dir_all(dir).unwrap();
+        let (ca, ca_key) = certificate("test-ca", None);
+        let (cert, key) = certificate("svc-assets", Some((&ca, &ca_key)));
+        std::fs::write(dir.join("ca.pem"), ca.to_pem().unwrap()).unwrap();
+        std::fs::write(dir.join("cert.pem"), cert.to_pem().unwrap()).unwrap();
+        std::fs::write(dir.join("key.pem"), key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        (ca, ca_key)
+    }
+
+    fn test_dir() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "svc-assets-tls-{}",
+            lib_common::uuid::Uuid::new_v4()
+        ))
+    }
+
+    fn tls_config(dir: &PathBuf, client_auth_required: bool) -> Config {
+        let path = |file: &str| Some(dir.join(file).to_string_lossy().to_string());
+        let mut config = Config::default();
+        config.tls_cert_path = path("cert.pem");
+        config.tls_key_path = path("key.pem");
+        config.tls_client_ca_path = path("ca.pem");
+        config.tls_client_auth_required = client_auth_required;
+        config
+    }
+
+    #[test]
+    fn test_tls_settings() {
+        let mut config = Config::default();
+        assert_eq!(TlsSettings::from_config(&config), Ok(None));
+
+        config.tls_cert_path = Some("cert.pem".to_string());
+        assert_eq!(
+            TlsSettings::from_config(&config),
+            Err(TlsError::Incomplete("tls_key_path"))
+        );
+
+        config.tls_key_path = Some("key.pem".to_string());
+        let settings = TlsSettings::from_config(&config).unwrap().unwrap();
+        assert_eq!(settings.client_ca_path, None);
+        assert!(!settings.client_auth_required);
+
+        config.tls_client_auth_required = true;
+        assert_eq!(
+            TlsSettings::from_config(&config),
+            Err(TlsError::Incomplete("tls_client_ca_path"))
+        );
+
+        let mut config = Config::default();
+        config.tls_client_auth_required = true;
+        assert_eq!(
+            TlsSettings::from_config(&config),
+            Err(TlsError::Incomplete("tls_cert_path"))
+        );
+    }
+
+    #[test]
+    fn test_server_tls_reload() {
+        let dir = test_dir();
+        write_files(&dir);
+        let config = tls_config(&dir, true);
+
+        let tls = ServerTls::from_config(&config, Protocol::Http2)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            tls.config.read().unwrap().alpn_protocols,
+            vec![b"h2".to_vec()]
+        );
+
+        // invalid files keep the current certificates
+        std::fs::write(dir.join("cert.pem"), "invalid").unwrap();
+        assert!(matches!(tls.reload(), Err(TlsError::File { .. })));
+
+        write_files(&dir);
+        assert!(tls.reload().is_ok());
+
+        std::fs::remove_file(dir.join("key.pem")).unwrap();
+        assert!(matches!(
+            ServerTls::from_config(&config, Protocol::Http2),
+            Err(TlsError::File { .. })
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_tls_client_auth() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("Start.");
+
+        let dir = test_dir();
+        let (ca, ca_key) = write_files(&dir);
+        let tls = ServerTls::from_config(&tls_config(&dir, true), Protocol::Http1)
+            .unwrap()
+            .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut incoming = Box::pin(tls.incoming(listener));
+
+        let mut roots = RootCertStore::empty();
+        roots.add(&Certificate(ca.to_der().unwrap())).unwrap();
+        let builder = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots);
+        let (client_cert, client_key) = certificate("svc-scheduler", Some((&ca, &ca_key)));
+        let with_cert = builder
+            .clone()
+            .with_client_auth_cert(
+                vec![Certificate(client_cert.to_der().unwrap())],
+                PrivateKey(client_key.private_key_to_der().unwrap()),
+            )
+            .unwrap();
+        let without_cert = builder.with_no_client_auth();
+        let connect = |config: ClientConfig| async move {
+            let stream = TcpStream::connect(addr).await.unwrap();
+            let connector = TlsConnector::from(Arc::new(config));
+            connector
+                .connect(ServerName::try_from("localhost").unwrap(), stream)
+                .await
+        };
+
+        // a client without a certificate is rejected; with TLS 1.3 the client
+        // finishes its side of the handshake first, so the rejection shows
+        // on the first read
+        if let Ok(mut stream) = connect(without_cert).await {
+            let mut buf = [0u8; 1];
+            assert!(stream.read(&mut buf).await.map_or(true, |read| read == 0));
+        }
+
+        connect(with_cert).await.unwrap();
+        let stream = incoming.next().await.unwrap().unwrap();
+        let (_, connection) = stream.get_ref();
+        assert_eq!(
+            connection.peer_certificates().map(|certs| certs.len()),
+            Some(1)
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+        ut_info!("Success.");
+    }
+}
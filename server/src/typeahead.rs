@@ -0,0 +1,239 @@
+//! # Typeahead
+//!
+//! In memory index of the vertiport names for the typeahead search of the
+//! dispatch UI.
+//!
+//! Searching svc-storage on every keystroke is too slow for autocomplete, so
+//! the names are kept in memory by [`VertiportIndex`], sorted for a prefix
+//! search. The index is refreshed at the configured interval, a registered
+//! or renamed vertiport is suggested after the next refresh.
+
+use crate::config::SharedConfig;
+use crate::geo::polygon_centroid;
+use crate::repo::Repositories;
+use std::sync::{Arc, RwLock};
+use svc_assets_client_rest::types::VertiportSuggestion;
+use svc_storage_client_grpc::prelude::vertiport;
+use svc_storage_client_grpc::prelude::*;
+use tokio::time::{interval_at, Duration, Instant, MissedTickBehavior};
+
+/// Interval at which a disabled refresh re-reads its configuration
+const DISABLED_RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A vertiport of the index with its lowercase name
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    key: String,
+    suggestion: VertiportSuggestion,
+}
+
+/// In memory index of the vertiport names, sorted by lowercase name
+#[derive(Debug, Clone, Default)]
+pub struct VertiportIndex {
+    entries: Arc<RwLock<Vec<IndexEntry>>>,
+}
+
+impl VertiportIndex {
+    /// Get up to `limit` vertiports with a name starting with `prefix`,
+    /// compared case insensitive and sorted by name
+    pub fn search(&self, prefix: &str, limit: usize) -> Vec<VertiportSuggestion> {
+        let entries = match self.entries.read() {
+            Ok(entries) => entries,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        search_entries(&entries, prefix, limit)
+    }
+
+    /// Replace the vertiports of the index
+    fn replace(&self, objects: Vec<vertiport::Object>) -> usize {
+        let entries = index_entries(objects);
+        let count = entries.len();
+        match self.entries.write() {
+            Ok(mut current) => *current = entries,
+            Err(poisoned) => *poisoned.into_inner() = entries,
+        }
+        count
+    }
+}
+
+/// Get the typeahead suggestion of a vertiport, `None` if it has no data
+pub fn vertiport_suggestion(object: &vertiport::Object) -> Option<VertiportSuggestion> {
+    let data = object.data.as_ref()?;
+    Some(VertiportSuggestion {
+        id: object.id.clone(),
+        name: data.name.clone(),
+        centroid: data
+            .geo_location
+            .as_ref()
+            .and_then(polygon_centroid)
+            .map(Into::into),
+    })
+}
+
+/// Get the index entries of the vertiports, sorted by lowercase name and id
+fn index_entries(objects: Vec<vertiport::Object>) -> Vec<IndexEntry> {
+    let mut entries: Vec<IndexEntry> = objects
+        .iter()
+        .filter_map(vertiport_suggestion)
+        .map(|suggestion| IndexEntry {
+            key: suggestion.name.trim().to_lowercase(),
+            suggestion,
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        a.key
+            .cmp(&b.key)
+            .then_with(|| a.suggestion.id.cmp(&b.suggestion.id))
+    });
+    entries
+}
+
+/// Get up to `limit` entries with a key starting with `prefix`
+fn search_entries(entries: &[IndexEntry], prefix: &str, limit: usize) -> Vec<VertiportSuggestion> {
+    let prefix = prefix.trim().to_lowercase();
+    let start = entries.partition_point(|entry| entry.key.as_str() < prefix.as_str());
+    entries[start..]
+        .iter()
+        .take_while(|entry| entry.key.starts_with(&prefix))
+        .take(limit)
+        .map(|entry| entry.suggestion.clone())
+        .collect()
+}
+
+/// Search the vertiports of the repositories directly, for repositories
+/// limited to a tenant which the shared index does not know about
+pub async fn search_vertiports(
+    repos: &Repositories,
+    prefix: &str,
+    limit: usize,
+) -> Result<Vec<VertiportSuggestion>, tonic::Status> {
+    let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+    let objects = repos.vertiports.search(filter).await?;
+    Ok(search_entries(&index_entries(objects), prefix, limit))
+}
+
+/// Read all vertiports from storage into the [`VertiportIndex`], returning
+/// the number of indexed vertiports
+pub async fn refresh_vertiport_index(
+    repos: &Repositories,
+    index: &VertiportIndex,
+) -> Result<usize, tonic::Status> {
+    let filter = AdvancedSearchFilter::search_is_null("deleted_at".to_string());
+    let objects = repos.vertiports.search(filter).await?;
+    Ok(index.replace(objects))
+}
+
+/// Get the configured interval between index refreshes, `None` if disabled
+fn refresh_interval(config: &SharedConfig) -> Option<Duration> {
+    match config.read(|config| config.vertiport_index_refresh_secs) {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+/// Refresh the [`VertiportIndex`] at the configured interval.
+///
+/// The interval is read from the [`SharedConfig`], so it can be changed
+/// (or the refresh disabled) at runtime.
+pub async fn vertiport_index_monitor(
+    config: SharedConfig,
+    repos: Repositories,
+    index: VertiportIndex,
+) {
+    log::info!("(vertiport_index_monitor) start.");
+
+    let mut current = refresh_interval(&config);
+    let mut ticker = interval_at(Instant::now(), current.unwrap_or(DISABLED_RECHECK_INTERVAL));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+
+        let configured = refresh_interval(&config);
+        if configured != current {
+            log::info!(
+                "(vertiport_index_monitor) refresh interval changed to {:?}.",
+                configured
+            );
+            current = configured;
+            let period = current.unwrap_or(DISABLED_RECHECK_INTERVAL);
+            ticker = interval_at(Instant::now() + period, period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        }
+
+        if current.is_none() {
+            continue;
+        }
+
+        match refresh_vertiport_index(&repos, &index).await {
+            Ok(count) => log::debug!("(vertiport_index_monitor) indexed {} vertiports.", count),
+            Err(e) => log::error!(
+                "(vertiport_index_monitor) could not refresh the index, keeping the previous one: {}",
+                e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::VertiportDataBuilder;
+
+    async fn insert(repos: &Repositories, name: &str) -> String {
+        repos
+            .vertiports
+            .insert(VertiportDataBuilder::new().name(name).build())
+            .await
+            .unwrap()
+            .id
+    }
+
+    #[tokio::test]
+    async fn test_vertiport_index() {
+        lib_common::logger::get_log_handle().await;
+        ut_info!("start");
+
+        let repos = Repositories::memory();
+        let amsterdam = insert(&repos, "Amsterdam Zuid").await;
+        let amstelveen = insert(&repos, "amstelveen").await;
+        insert(&repos, "Rotterdam").await;
+
+        let index = VertiportIndex::default();
+        assert!(index.search("ams", 10).is_empty());
+        assert_eq!(refresh_vertiport_index(&repos, &index).await.unwrap(), 3);
+
+        let names = |suggestions: Vec<VertiportSuggestion>| -> Vec<String> {
+            suggestions.into_iter().map(|s| s.name).collect()
+        };
+        assert_eq!(
+            names(index.search("AMS", 10)),
+            vec!["amstelveen", "Amsterdam Zuid"]
+        );
+        assert_eq!(
+            names(index.search(" amste", 10)),
+            vec!["amstelveen", "Amsterdam Zuid"]
+        );
+        assert_eq!(names(index.search("amsterdam", 10)), vec!["Amsterdam Zuid"]);
+        assert_eq!(names(index.search("ams", 1)), vec!["amstelveen"]);
+        assert!(index.search("utrecht", 10).is_empty());
+        assert_eq!(index.search("", 10).len(), 3);
+
+        let suggestion = &index.search("amsterdam", 1)[0];
+        assert_eq!(suggestion.id, amsterdam);
+        assert!(suggestion.centroid.is_some());
+
+        let direct = search_vertiports(&repos, "ams", 10).await.unwrap();
+        assert_eq!(direct[0].id, amstelveen);
+        assert_eq!(direct.len(), 2);
+
+        // a failed refresh keeps the previous index
+        let error = refresh_vertiport_index(&Repositories::unavailable(), &index)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unavailable);
+        assert_eq!(index.search("ams", 10).len(), 2);
+
+        ut_info!("success");
+    }
+}
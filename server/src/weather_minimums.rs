@@ -0,0 +1,189 @@
+//! # Vertiport Weather Minimums
+//!
+//! The operational weather minimums of a vertiport: the maximum wind speed,
+//! the minimum visibility and the maximum precipitation flights to and from
+//! it can operate in. svc-scheduler reads them through gRPC for its go/no-go
+//! decisions, instead of using constants of its own.
+//!
+//! svc-storage does not store weather minimums, so [`WeatherMinimums`]
+//! keeps them in the [`WEATHER_MINIMUMS_TABLE`] of the persisted state, see
+//! [`crate::state`]. The same store is used by the REST API to configure the
+//! minimums and by the gRPC API to expose them, which only works if
+//! svc-scheduler calls the instance the minimums were configured on.
+
+pub use crate::rest::{VertiportWeatherMinimums, WeatherMinimumsPayload};
+
+use crate::rest::api::validation::{field_error, into_result, FieldError};
+use crate::state::{StateError, StateTable};
+use chrono::{DateTime, Utc};
+
+/// Name of the table of the weather minimums in the state directory
+pub const WEATHER_MINIMUMS_TABLE: &str = "weather_minimums";
+
+/// Largest maximum wind speed accepted, in meters per second
+pub const MAX_WIND_SPEED_MPS: f64 = 100.0;
+
+/// Largest minimum visibility accepted, in meters
+pub const MAX_VISIBILITY_M: f64 = 100_000.0;
+
+/// Largest maximum precipitation accepted, in millimeters per hour
+pub const MAX_PRECIPITATION_MM_PER_HOUR: f64 = 500.0;
+
+/// Validate weather minimums, every limit must be between `0` and its
+/// largest accepted value.
+pub fn validate_weather_minimums(
+    payload: WeatherMinimumsPayload,
+) -> Result<WeatherMinimumsPayload, Vec<FieldError>> {
+    let errors = [
+        (
+            "max_wind_speed_mps",
+            payload.max_wind_speed_mps,
+            MAX_WIND_SPEED_MPS,
+        ),
+        (
+            "min_visibility_m",
+            payload.min_visibility_m,
+            MAX_VISIBILITY_M,
+        ),
+        (
+            "max_precipitation_mm_per_hour",
+            payload.max_precipitation_mm_per_hour,
+            MAX_PRECIPITATION_MM_PER_HOUR,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, value, max)| !(0.0..=*max).contains(value))
+    .map(|(field, _, max)| field_error(field, &format!("must be between 0 and {max}")))
+    .collect();
+
+    into_result(payload, errors)
+}
+
+/// Store of the weather minimums per vertiport id.
+///
+/// The default store is kept in memory, [`WeatherMinimums::open`] persists
+/// the minimums.
+#[derive(Debug, Clone, Default)]
+pub struct WeatherMinimums {
+    minimums: StateTable<VertiportWeatherMinimums>,
+}
+
+impl WeatherMinimums {
+    /// Open the minimums persisted in the state directory, kept in memory if
+    /// no directory is provided
+    pub fn open(dir: Option<&str>) -> Result<Self, StateError> {
+        Ok(Self {
+            minimums: StateTable::open(dir, WEATHER_MINIMUMS_TABLE)?,
+        })
+    }
+
+    /// Get the weather minimums of a vertiport, [`None`] if not configured
+    pub fn get(&self, vertiport_id: &str) -> Option<VertiportWeatherMinimums> {
+        self.minimums.get(vertiport_id)
+    }
+
+    /// Set the weather minimums of a vertiport, replacing the previous
+    /// minimums
+    pub fn set(
+        &self,
+        vertiport_id: &str,
+        payload: WeatherMinimumsPayload,
+        now: DateTime<Utc>,
+    ) -> Result<VertiportWeatherMinimums, StateError> {
+        let minimums = VertiportWeatherMinimums {
+            vertiport_id: vertiport_id.to_string(),
+            max_wind_speed_mps: payload.max_wind_speed_mps,
+            min_visibility_m: payload.min_visibility_m,
+            max_precipitation_mm_per_hour: payload.max_precipitation_mm_per_hour,
+            updated_at: now,
+        };
+        self.minimums.insert(vertiport_id, minimums.clone())?;
+        Ok(minimums)
+    }
+
+    /// Remove the weather minimums of a vertiport, returning `false` if none
+    /// were configured
+    pub fn remove(&self, vertiport_id: &str) -> Result<bool, StateError> {
+        Ok(self.minimums.remove(vertiport_id)?.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(wind: f64, visibility: f64, precipitation: f64) -> WeatherMinimumsPayload {
+        WeatherMinimumsPayload {
+            max_wind_speed_mps: wind,
+            min_visibility_m: visibility,
+            max_precipitation_mm_per_hour: precipitation,
+        }
+    }
+
+    #[test]
+    fn test_validate_weather_minimums() {
+        assert!(validate_weather_minimums(payload(12.5, 1500.0, 2.0)).is_ok());
+        assert!(validate_weather_minimums(payload(0.0, 0.0, 0.0)).is_ok());
+
+        let errors = validate_weather_minimums(payload(-1.0, f64::NAN, 501.0)).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "max_wind_speed_mps",
+                "min_visibility_m",
+                "max_precipitation_mm_per_hour"
+            ]
+        );
+        assert_eq!(errors[0].message, "must be between 0 and 100");
+
+        let errors = validate_weather_minimums(payload(f64::INFINITY, 1500.0, 2.0)).unwrap_err();
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_weather_minimums() {
+        let store = WeatherMinimums::default();
+        let now = Utc::now();
+        assert_eq!(store.get("vertiport"), None);
+        assert!(!store.remove("vertiport").unwrap());
+
+        let minimums = store
+            .set("vertiport", payload(12.5, 1500.0, 2.0), now)
+            .unwrap();
+        assert_eq!(minimums.vertiport_id, "vertiport");
+        assert_eq!(minimums.updated_at, now);
+        assert_eq!(store.get("vertiport"), Some(minimums));
+        assert_eq!(store.get("other"), None);
+
+        // shared between clones
+        let minimums = store
+            .clone()
+            .set("vertiport", payload(10.0, 3000.0, 0.5), now)
+            .unwrap();
+        assert_eq!(store.get("vertiport"), Some(minimums));
+
+        assert!(store.remove("vertiport").unwrap());
+        assert_eq!(store.get("vertiport"), None);
+    }
+
+    #[test]
+    fn test_weather_minimums_persisted() {
+        let dir = crate::state::test_state_dir();
+        let now = Utc::now();
+
+        let store = WeatherMinimums::open(Some(&dir)).unwrap();
+        let minimums = store
+            .set("vertiport", payload(12.5, 1500.0, 2.0), now)
+            .unwrap();
+        store.set("removed", payload(5.0, 800.0, 1.0), now).unwrap();
+        store.remove("removed").unwrap();
+
+        // the minimums survive a restart
+        let store = WeatherMinimums::open(Some(&dir)).unwrap();
+        assert_eq!(store.get("vertiport"), Some(minimums));
+        assert_eq!(store.get("removed"), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
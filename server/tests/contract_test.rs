@@ -0,0 +1,608 @@
+//! Storage Contract Tests
+//!
+//! Stores the mock objects of svc-storage (`mock::get_data_obj`) in the stub
+//! backends and gets them back through the REST API, comparing every field
+//! of the storage objects with the response.
+//!
+//! Every field of a storage object is either mapped to a field of the REST
+//! type, and its value must match, or listed as not exposed. Every field of
+//! the REST type is either mapped or derived by this service, like the
+//! `whitelist` and `status` of an asset. A field added to the storage client
+//! fails these tests until it is mapped or listed, and a renamed or removed
+//! field fails to compile, so upgrading the storage client crate can't
+//! silently drop a field.
+#![cfg(feature = "test_util")]
+
+use axum::body::Body;
+use axum::http::{header::CONTENT_TYPE, Method, Request, StatusCode};
+use axum::Router;
+use lib_common::log_macros;
+use lib_common::time::{DateTime, Utc};
+use lib_common::uuid::Uuid;
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use svc_assets::config::{Config, SharedConfig};
+use svc_assets::grpc::client::GrpcClients;
+use svc_assets::repo::memory::MemoryRepository;
+use svc_assets::repo::Repositories;
+use svc_assets::rest::api::rest_types::{
+    group, vehicle, vertipad, vertiport, GeoPoint, GeoPolygon,
+};
+use svc_assets::rest::server::{api_router, RestComponents};
+use svc_assets::rest::StartupState;
+use svc_assets::shared::SharedState;
+use svc_assets::testing::{AircraftDataBuilder, VertipadDataBuilder, VertiportDataBuilder};
+use tower::ServiceExt;
+
+log_macros!("it", "test");
+
+/// Create the REST API router with the stub storage backends, returning the
+/// router and its repositories
+fn app() -> (Router, Repositories) {
+    let mut config = Config::default();
+    // don't let the rate limit slow down the tests
+    config.rest_request_limit_per_second = u8::MAX;
+    let grpc_clients = GrpcClients::default(config.clone());
+    let repositories = Repositories::grpc(&grpc_clients);
+    (
+        with_repositories(config, grpc_clients, repositories.clone()),
+        repositories,
+    )
+}
+
+/// Create the REST API router using the provided repositories
+fn with_repositories(
+    config: Config,
+    grpc_clients: GrpcClients,
+    repositories: Repositories,
+) -> Router {
+    let shared = SharedState::default();
+    shared.startup.set(StartupState::Ready);
+
+    let components = RestComponents::with_repositories(
+        SharedConfig::from(config),
+        shared,
+        grpc_clients,
+        repositories,
+    )
+    .unwrap();
+    api_router(&components)
+}
+
+/// Call the router as an operator, returning the status and the JSON body
+/// of the response
+async fn call(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("x-operator-id", "8c5e3e0e-6f1c-4d8a-9f5e-2f1d7c0b9a41");
+    let request = match body {
+        Some(body) => request
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body = serde_json::from_slice(&body).unwrap_or_else(|_| {
+        // the register endpoints return the plain id
+        Value::String(String::from_utf8_lossy(&body).to_string())
+    });
+    (status, body)
+}
+
+/// Convert a storage timestamp
+fn time(timestamp: impl Into<DateTime<Utc>>) -> DateTime<Utc> {
+    timestamp.into()
+}
+
+/// Get the fields of a JSON object, the fields of `basics` prefixed by
+/// `basics/`
+fn fields(value: &Value) -> BTreeSet<String> {
+    let Some(object) = value.as_object() else {
+        panic!("expected an object, got {value}");
+    };
+
+    object
+        .iter()
+        .flat_map(|(field, value)| match (field.as_str(), value.as_object()) {
+            ("basics", Some(basics)) => basics
+                .keys()
+                .map(|field| format!("basics/{field}"))
+                .collect::<Vec<String>>(),
+            _ => vec![field.clone()],
+        })
+        .collect()
+}
+
+/// The fields of a storage object and their REST counterparts
+struct Contract {
+    /// Storage field, REST field and the expected REST value
+    mapped: Vec<(&'static str, &'static str, Value)>,
+    /// Storage fields which are not part of the REST type
+    not_exposed: &'static [&'static str],
+    /// REST fields which are not stored, but derived by this service
+    derived: &'static [&'static str],
+}
+
+impl Contract {
+    /// Check the REST response against the storage data, returning the
+    /// violations of the contract
+    fn violations(&self, storage: &Value, rest: &Value) -> Vec<String> {
+        let mut violations = vec![];
+
+        let known: BTreeSet<&str> = self
+            .mapped
+            .iter()
+            .map(|(field, _, _)| *field)
+            .chain(self.not_exposed.iter().copied())
+            .collect();
+        for field in fields(storage) {
+            if !known.contains(field.as_str()) {
+                violations.push(format!(
+                    "storage field '{field}' is neither mapped nor listed"
+                ));
+            }
+        }
+
+        let known: BTreeSet<&str> = self
+            .mapped
+            .iter()
+            .map(|(_, field, _)| *field)
+            .chain(self.derived.iter().copied())
+            .collect();
+        for field in fields(rest) {
+            if !known.contains(field.as_str()) {
+                violations.push(format!(
+                    "REST field '{field}' is neither mapped nor derived"
+                ));
+            }
+        }
+
+        for (storage_field, rest_field, expected) in &self.mapped {
+            let actual = rest.pointer(&format!("/{rest_field}"));
+            if actual != Some(expected) {
+                violations.push(format!(
+                    "'{storage_field}' is {expected}, REST field '{rest_field}' is {actual:?}"
+                ));
+            }
+        }
+
+        violations
+    }
+}
+
+/// Contract of the [`vehicle`] objects and the REST aircraft
+fn aircraft_contract(data: &vehicle::Data) -> Contract {
+    Contract {
+        mapped: vec![
+            ("vehicle_model_id", "model", json!(data.vehicle_model_id)),
+            ("serial_number", "serial_number", json!(data.serial_number)),
+            (
+                "registration_number",
+                "registration_number",
+                json!(data.registration_number),
+            ),
+            ("description", "description", json!(data.description)),
+            (
+                "asset_group_id",
+                "basics/group_id",
+                json!(data.asset_group_id),
+            ),
+            ("hangar_id", "hangar_id", json!(data.hangar_id)),
+            (
+                "last_maintenance",
+                "last_maintenance",
+                json!(data.last_maintenance.clone().map(time)),
+            ),
+            (
+                "next_maintenance",
+                "next_maintenance",
+                json!(data.next_maintenance.clone().map(time)),
+            ),
+            (
+                "created_at",
+                "basics/created_at",
+                json!(data.created_at.clone().map(time)),
+            ),
+            (
+                "updated_at",
+                "basics/updated_at",
+                json!(data.updated_at.clone().map(time)),
+            ),
+        ],
+        // the bay and the schedule of an aircraft are only used by the
+        // storage side checks and the availability
+        not_exposed: &["hangar_bay_id", "schedule"],
+        derived: &[
+            "basics/id",
+            "basics/name",
+            "basics/deleted_at",
+            "basics/owner_id",
+            "basics/whitelist",
+            "basics/status",
+            "manufacturer",
+            "max_payload_kg",
+            "max_range_km",
+        ],
+    }
+}
+
+/// Contract of the [`vertiport`] objects and the REST vertiports
+fn vertiport_contract(data: &vertiport::Data) -> Contract {
+    Contract {
+        mapped: vec![
+            ("name", "basics/name", json!(data.name)),
+            ("description", "description", json!(data.description)),
+            (
+                "geo_location",
+                "geo_location",
+                json!(data.geo_location.clone().map(GeoPolygon::from)),
+            ),
+            ("schedule", "schedule", json!(data.schedule)),
+            (
+                "created_at",
+                "basics/created_at",
+                json!(data.created_at.clone().map(time)),
+            ),
+            (
+                "updated_at",
+                "basics/updated_at",
+                json!(data.updated_at.clone().map(time)),
+            ),
+        ],
+        not_exposed: &[],
+        derived: &[
+            "basics/id",
+            "basics/group_id",
+            "basics/deleted_at",
+            "basics/owner_id",
+            "basics/whitelist",
+            "basics/status",
+            "centroid",
+            "bbox",
+            "localization",
+        ],
+    }
+}
+
+/// Contract of the [`vertipad`] objects and the REST vertipads
+fn vertipad_contract(data: &vertipad::Data) -> Contract {
+    Contract {
+        mapped: vec![
+            ("name", "basics/name", json!(data.name)),
+            ("vertiport_id", "vertiport_id", json!(data.vertiport_id)),
+            (
+                "geo_location",
+                "geo_location",
+                json!(data.geo_location.clone().map(GeoPoint::from)),
+            ),
+            ("enabled", "enabled", json!(data.enabled)),
+            ("occupied", "occupied", json!(data.occupied)),
+            ("schedule", "schedule", json!(data.schedule)),
+            (
+                "created_at",
+                "basics/created_at",
+                json!(data.created_at.clone().map(time)),
+            ),
+            (
+                "updated_at",
+                "basics/updated_at",
+                json!(data.updated_at.clone().map(time)),
+            ),
+        ],
+        not_exposed: &[],
+        derived: &[
+            "basics/id",
+            "basics/group_id",
+            "basics/deleted_at",
+            "basics/owner_id",
+            "basics/whitelist",
+            "basics/status",
+            "localization",
+            "classification",
+        ],
+    }
+}
+
+/// Assert the REST response fulfills the contract
+fn assert_contract(resource: &str, contract: &Contract, storage: &Value, rest: &Value) {
+    let violations = contract.violations(storage, rest);
+    assert!(
+        violations.is_empty(),
+        "{resource} contract violated: {violations:#?}"
+    );
+}
+
+#[tokio::test]
+async fn test_contract_of_stored_objects() {
+    lib_common::logger::get_log_handle().await;
+    it_info!("start");
+
+    let (app, repos) = app();
+
+    let aircraft = repos
+        .aircraft
+        .insert(vehicle::mock::get_data_obj())
+        .await
+        .unwrap();
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        &format!("/assets/aircraft/{}", aircraft.id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["basics"]["id"], json!(aircraft.id));
+    let data = aircraft.data.unwrap();
+    assert_contract(
+        "aircraft",
+        &aircraft_contract(&data),
+        &serde_json::to_value(&data).unwrap(),
+        &body,
+    );
+
+    let vertiport = repos
+        .vertiports
+        .insert(vertiport::mock::get_data_obj())
+        .await
+        .unwrap();
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        &format!("/assets/vertiports/{}", vertiport.id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["basics"]["id"], json!(vertiport.id));
+    let data = vertiport.data.unwrap();
+    assert_contract(
+        "vertiport",
+        &vertiport_contract(&data),
+        &serde_json::to_value(&data).unwrap(),
+        &body,
+    );
+
+    let mut data = vertipad::mock::get_data_obj();
+    data.vertiport_id = vertiport.id.clone();
+    let vertipad = repos.vertipads.insert(data).await.unwrap();
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        &format!("/assets/vertipads/{}", vertipad.id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["basics"]["id"], json!(vertipad.id));
+    let data = vertipad.data.unwrap();
+    assert_contract(
+        "vertipad",
+        &vertipad_contract(&data),
+        &serde_json::to_value(&data).unwrap(),
+        &body,
+    );
+
+    it_info!("success");
+}
+
+#[tokio::test]
+async fn test_contract_of_registered_objects() {
+    lib_common::logger::get_log_handle().await;
+    it_info!("start");
+
+    let (app, repos) = app();
+
+    // every optional field is set, so a field dropped on the way to the
+    // storage is caught. The timestamps are set by svc-storage, they are
+    // only provided for the stub backend.
+    let now = Utc::now();
+    let data = AircraftDataBuilder::new()
+        .hangar(Uuid::new_v4().to_string(), Uuid::new_v4().to_string())
+        .description("Contract test aircraft")
+        .maintenance(
+            now - chrono::Duration::days(1),
+            now + chrono::Duration::days(30),
+        )
+        .timestamps(now, now)
+        .build();
+    let (status, id) = call(
+        &app,
+        Method::POST,
+        "/assets/aircraft",
+        Some(serde_json::to_value(&data).unwrap()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let stored = repos
+        .aircraft
+        .get_by_id(id.as_str().unwrap().to_string())
+        .await
+        .unwrap()
+        .data
+        .unwrap();
+    assert_eq!(
+        vehicle::Data {
+            created_at: None,
+            updated_at: None,
+            ..stored.clone()
+        },
+        vehicle::Data {
+            created_at: None,
+            updated_at: None,
+            ..data
+        }
+    );
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        &format!("/assets/aircraft/{}", id.as_str().unwrap()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_contract(
+        "aircraft",
+        &aircraft_contract(&stored),
+        &serde_json::to_value(&stored).unwrap(),
+        &body,
+    );
+
+    let data = VertiportDataBuilder::new()
+        .schedule("DTSTART:20240101T000000Z;DURATION:PT24H\nRRULE:FREQ=DAILY")
+        .timestamps(now, now)
+        .build();
+    let (status, id) = call(
+        &app,
+        Method::POST,
+        "/assets/vertiports",
+        Some(serde_json::to_value(&data).unwrap()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let vertiport_id = id.as_str().unwrap().to_string();
+    let stored = repos
+        .vertiports
+        .get_by_id(vertiport_id.clone())
+        .await
+        .unwrap()
+        .data
+        .unwrap();
+    assert_eq!(
+        vertiport::Data {
+            created_at: None,
+            updated_at: None,
+            ..stored.clone()
+        },
+        vertiport::Data {
+            created_at: None,
+            updated_at: None,
+            ..data
+        }
+    );
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        &format!("/assets/vertiports/{vertiport_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_contract(
+        "vertiport",
+        &vertiport_contract(&stored),
+        &serde_json::to_value(&stored).unwrap(),
+        &body,
+    );
+
+    let data = VertipadDataBuilder::new()
+        .vertiport_id(vertiport_id)
+        .occupied(true)
+        .timestamps(now, now)
+        .build();
+    let (status, id) = call(
+        &app,
+        Method::POST,
+        "/assets/vertipads",
+        Some(serde_json::to_value(&data).unwrap()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let vertipad_id = id.as_str().unwrap().to_string();
+    let stored = repos
+        .vertipads
+        .get_by_id(vertipad_id.clone())
+        .await
+        .unwrap()
+        .data
+        .unwrap();
+    assert_eq!(
+        vertipad::Data {
+            created_at: None,
+            updated_at: None,
+            ..stored.clone()
+        },
+        vertipad::Data {
+            created_at: None,
+            updated_at: None,
+            ..data
+        }
+    );
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        &format!("/assets/vertipads/{vertipad_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_contract(
+        "vertipad",
+        &vertipad_contract(&stored),
+        &serde_json::to_value(&stored).unwrap(),
+        &body,
+    );
+
+    it_info!("success");
+}
+
+#[tokio::test]
+async fn test_contract_of_asset_groups() {
+    lib_common::logger::get_log_handle().await;
+    it_info!("start");
+
+    // asset groups are not registered by this service, so the stub backend
+    // can't be seeded with one
+    let config = Config::default();
+    let grpc_clients = GrpcClients::default(config.clone());
+    let group_id = Uuid::new_v4().to_string();
+    let repositories = Repositories {
+        groups: Arc::new(MemoryRepository::with_objects(HashMap::from([(
+            group_id.clone(),
+            group::Data {
+                name: "Contract Fleet".to_string(),
+                ..Default::default()
+            },
+        )]))),
+        ..Repositories::grpc(&grpc_clients)
+    };
+    let app = with_repositories(config, grpc_clients, repositories);
+
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        &format!("/assets/groups/{group_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["id"], json!(group_id));
+    assert_eq!(body["name"], json!("Contract Fleet"));
+
+    // only the name and the parent of a group are read from the storage
+    let known = [
+        "id",
+        "name",
+        "owner",
+        "created_at",
+        "updated_at",
+        "delegatee",
+        "assets",
+        "parent_group_id",
+        "child_group_ids",
+    ];
+    for field in fields(&body) {
+        assert!(
+            known.contains(&field.as_str()),
+            "REST field '{field}' is neither mapped nor derived"
+        );
+    }
+
+    it_info!("success");
+}
@@ -43,4 +43,59 @@ async fn test_server_requests_and_logs() {
             }
         }));
     }
+
+    //test_get_group_availability_request_logs
+    {
+        let imp = ServerImpl::default();
+        let from = std::time::SystemTime::now();
+        let result = imp
+            .get_group_availability(tonic::Request::new(GroupAvailabilityRequest {
+                group_id: "00000000-0000-0000-0000-000000000000".to_string(),
+                from: Some(from.into()),
+                to: Some((from + std::time::Duration::from_secs(3600)).into()),
+            }))
+            .await;
+        println!("{:?}", result);
+        assert!(result.is_ok());
+
+        // Search for the expected log message
+        let expected = get_log_string("get_group_availability", name);
+        println!("expected message: {}", expected);
+        assert!(logger.any(|log| {
+            if log.target().contains("app::") {
+                println!("{}", log.target());
+                let message = log.args();
+                println!("{:?}", message);
+                log.args() == expected
+            } else {
+                false
+            }
+        }));
+    }
+
+    //test_get_vertiport_weather_minimums_request_logs
+    {
+        let imp = ServerImpl::default();
+        let result = imp
+            .get_vertiport_weather_minimums(tonic::Request::new(VertiportWeatherMinimumsRequest {
+                vertiport_id: "00000000-0000-0000-0000-000000000000".to_string(),
+            }))
+            .await;
+        println!("{:?}", result);
+        assert!(result.is_ok());
+
+        // Search for the expected log message
+        let expected = get_log_string("get_vertiport_weather_minimums", name);
+        println!("expected message: {}", expected);
+        assert!(logger.any(|log| {
+            if log.target().contains("app::") {
+                println!("{}", log.target());
+                let message = log.args();
+                println!("{:?}", message);
+                log.args() == expected
+            } else {
+                false
+            }
+        }));
+    }
 }
@@ -0,0 +1,292 @@
+//! Performance Regression Tests
+//!
+//! Times the conversion of `svc-storage` objects into REST types, the list
+//! endpoints against the in-memory backend and the validation of
+//! registration payloads. Each measurement is compared with a baseline
+//! threshold for the mean duration of a single iteration, so payload and
+//! serialization regressions are caught before a release.
+//!
+//! The durations depend on the machine and its load, so the tests are
+//! ignored by default and run with `--ignored`. The thresholds are set for
+//! unoptimized test builds. Set `PERF_THRESHOLD_FACTOR` to scale all
+//! thresholds, for example when running with coverage instrumentation.
+#![cfg(feature = "test_util")]
+
+use axum::{extract::Query, Extension};
+use lib_common::log_macros;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use svc_assets::aircraft_status::AircraftStatuses;
+use svc_assets::archive::RemovedAssets;
+use svc_assets::config::{Config, SharedConfig};
+use svc_assets::hold::MaintenanceHolds;
+use svc_assets::maintenance::OverdueAircraft;
+use svc_assets::ops_status::VertiportOpsStatuses;
+use svc_assets::repo::Repositories;
+use svc_assets::rest::api::aircraft::{get_all_aircraft, validate_vehicle_data, Aircraft};
+use svc_assets::rest::api::list::ListAssetsQuery;
+use svc_assets::rest::api::vertipad::{
+    get_all_vertipads, validate_vertipad_data, Vertipad, VertipadClassificationQuery,
+};
+use svc_assets::rest::api::vertiport::{
+    get_all_vertiports, validate_vertiport_data, GeometryQuery, Vertiport,
+};
+use svc_assets::rest::chargers::{VertipadChargerQuery, VertipadChargers};
+use svc_assets::rest::classification::PadClassifications;
+use svc_assets::rest::quota::{AssetOwners, OperatorQuotas};
+use svc_storage_client_grpc::prelude::{vehicle, vertipad, vertiport};
+
+log_macros!("it", "test");
+
+/// Number of iterations for the single object measurements
+const ITERATIONS: u32 = 10_000;
+
+/// Number of iterations for the list endpoint measurements
+const LIST_ITERATIONS: u32 = 20;
+
+/// Number of assets of each type returned by the list endpoints
+const LIST_SIZE: usize = 1_000;
+
+/// Baseline for converting a single `svc-storage` object
+const CONVERSION_BASELINE: Duration = Duration::from_micros(100);
+
+/// Baseline for validating a single registration payload
+const VALIDATION_BASELINE: Duration = Duration::from_micros(50);
+
+/// Baseline for listing and serializing [`LIST_SIZE`] assets
+const LIST_BASELINE: Duration = Duration::from_millis(250);
+
+/// Quotas without limits and without recorded owners
+fn operator_quotas() -> OperatorQuotas {
+    OperatorQuotas::new(Config::default().into(), AssetOwners::default())
+}
+
+/// Scale applied to every baseline, read from `PERF_THRESHOLD_FACTOR`
+fn threshold_factor() -> f64 {
+    std::env::var("PERF_THRESHOLD_FACTOR")
+        .ok()
+        .and_then(|factor| factor.parse::<f64>().ok())
+        .filter(|factor| *factor > 0.0)
+        .unwrap_or(1.0)
+}
+
+/// Assert the mean duration of an iteration is within the scaled baseline.
+fn assert_within_baseline(name: &str, total: Duration, iterations: u32, baseline: Duration) {
+    let mean = total / iterations;
+    let threshold = baseline.mul_f64(threshold_factor());
+    it_info!(
+        "{}: {:?} per iteration ({} iterations, threshold {:?})",
+        name,
+        mean,
+        iterations,
+        threshold
+    );
+    assert!(
+        mean <= threshold,
+        "{name} regressed: {mean:?} per iteration exceeds the threshold of {threshold:?}"
+    );
+}
+
+/// Run `f` for the provided number of iterations and return the total duration.
+fn measure<F: FnMut()>(iterations: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    start.elapsed()
+}
+
+/// Fill in-memory repositories with [`LIST_SIZE`] assets of each type.
+async fn populated_repositories() -> Repositories {
+    let repos = Repositories::memory();
+    for _ in 0..LIST_SIZE {
+        repos
+            .aircraft
+            .insert(vehicle::mock::get_data_obj())
+            .await
+            .unwrap();
+        let vertiport = repos
+            .vertiports
+            .insert(vertiport::mock::get_data_obj())
+            .await
+            .unwrap();
+        let mut vertipad = vertipad::mock::get_data_obj();
+        vertipad.vertiport_id = vertiport.id;
+        repos.vertipads.insert(vertipad).await.unwrap();
+    }
+    repos
+}
+
+#[tokio::test]
+#[ignore = "depends on the machine, run with --ignored"]
+async fn test_conversion_performance() {
+    lib_common::logger::get_log_handle().await;
+    it_info!("start");
+
+    let repos = Repositories::memory();
+    let vehicle = repos
+        .aircraft
+        .insert(vehicle::mock::get_data_obj())
+        .await
+        .unwrap();
+    let vertiport = repos
+        .vertiports
+        .insert(vertiport::mock::get_data_obj())
+        .await
+        .unwrap();
+    let mut vertipad = vertipad::mock::get_data_obj();
+    vertipad.vertiport_id = vertiport.id.clone();
+    let vertipad = repos.vertipads.insert(vertipad).await.unwrap();
+
+    let total = measure(ITERATIONS, || {
+        black_box(Aircraft::try_from(black_box(vehicle.clone())).unwrap());
+    });
+    assert_within_baseline(
+        "vehicle to Aircraft",
+        total,
+        ITERATIONS,
+        CONVERSION_BASELINE,
+    );
+
+    let total = measure(ITERATIONS, || {
+        black_box(Vertiport::try_from(black_box(vertiport.clone())).unwrap());
+    });
+    assert_within_baseline(
+        "vertiport to Vertiport",
+        total,
+        ITERATIONS,
+        CONVERSION_BASELINE,
+    );
+
+    let total = measure(ITERATIONS, || {
+        black_box(Vertipad::try_from(black_box(vertipad.clone())).unwrap());
+    });
+    assert_within_baseline(
+        "vertipad to Vertipad",
+        total,
+        ITERATIONS,
+        CONVERSION_BASELINE,
+    );
+
+    it_info!("success");
+}
+
+#[tokio::test]
+#[ignore = "depends on the machine, run with --ignored"]
+async fn test_validation_performance() {
+    lib_common::logger::get_log_handle().await;
+    it_info!("start");
+
+    let vehicle = vehicle::mock::get_data_obj();
+    let total = measure(ITERATIONS, || {
+        let _ = black_box(validate_vehicle_data(black_box(vehicle.clone())));
+    });
+    assert_within_baseline("vehicle validation", total, ITERATIONS, VALIDATION_BASELINE);
+
+    let vertiport = vertiport::mock::get_data_obj();
+    let total = measure(ITERATIONS, || {
+        let _ = black_box(validate_vertiport_data(black_box(vertiport.clone())));
+    });
+    assert_within_baseline(
+        "vertiport validation",
+        total,
+        ITERATIONS,
+        VALIDATION_BASELINE,
+    );
+
+    let vertipad = vertipad::mock::get_data_obj();
+    let total = measure(ITERATIONS, || {
+        let _ = black_box(validate_vertipad_data(black_box(vertipad.clone())));
+    });
+    assert_within_baseline(
+        "vertipad validation",
+        total,
+        ITERATIONS,
+        VALIDATION_BASELINE,
+    );
+
+    it_info!("success");
+}
+
+#[tokio::test]
+#[ignore = "depends on the machine, run with --ignored"]
+async fn test_list_performance() {
+    lib_common::logger::get_log_handle().await;
+    it_info!("start");
+
+    let repos = populated_repositories().await;
+
+    let start = Instant::now();
+    for _ in 0..LIST_ITERATIONS {
+        let listed = get_all_aircraft(
+            Extension(repos.clone()),
+            Extension(OverdueAircraft::default()),
+            Extension(MaintenanceHolds::default()),
+            Extension(AircraftStatuses::default()),
+            Extension(SharedConfig::from(Config::default())),
+            Extension(RemovedAssets::default()),
+            Extension(operator_quotas()),
+            Query(ListAssetsQuery::default()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(listed.assets.len(), LIST_SIZE);
+        black_box(serde_json::to_vec(&listed.assets).unwrap());
+    }
+    assert_within_baseline(
+        "list aircraft",
+        start.elapsed(),
+        LIST_ITERATIONS,
+        LIST_BASELINE,
+    );
+
+    let start = Instant::now();
+    for _ in 0..LIST_ITERATIONS {
+        let listed = get_all_vertiports(
+            Extension(repos.clone()),
+            Extension(SharedConfig::from(Config::default())),
+            Extension(RemovedAssets::default()),
+            Extension(operator_quotas()),
+            Query(GeometryQuery::default()),
+            Query(ListAssetsQuery::default()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(listed.assets.len(), LIST_SIZE);
+        black_box(serde_json::to_vec(&listed.assets).unwrap());
+    }
+    assert_within_baseline(
+        "list vertiports",
+        start.elapsed(),
+        LIST_ITERATIONS,
+        LIST_BASELINE,
+    );
+
+    let start = Instant::now();
+    for _ in 0..LIST_ITERATIONS {
+        let listed = get_all_vertipads(
+            Extension(repos.clone()),
+            Extension(VertiportOpsStatuses::default()),
+            Extension(PadClassifications::default()),
+            Extension(VertipadChargers::default()),
+            Extension(SharedConfig::from(Config::default())),
+            Extension(RemovedAssets::default()),
+            Extension(operator_quotas()),
+            Query(VertipadClassificationQuery::default()),
+            Query(VertipadChargerQuery::default()),
+            Query(ListAssetsQuery::default()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(listed.assets.len(), LIST_SIZE);
+        black_box(serde_json::to_vec(&listed.assets).unwrap());
+    }
+    assert_within_baseline(
+        "list vertipads",
+        start.elapsed(),
+        LIST_ITERATIONS,
+        LIST_BASELINE,
+    );
+
+    it_info!("success");
+}
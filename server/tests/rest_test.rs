@@ -0,0 +1,412 @@
+//! REST Integration Tests
+//!
+//! Serves the router of the REST API with in-memory storage and calls it
+//! like a client would, through the middleware and the extractors of the
+//! handlers. Every route of the OpenAPI specification is called, so a route
+//! missing from the router, registered with the wrong method or missing one
+//! of the extensions of its handler is caught without a running svc-storage.
+#![cfg(feature = "test_util")]
+
+use axum::body::Body;
+use axum::http::{
+    header::{AUTHORIZATION, CONTENT_TYPE},
+    Method, Request, StatusCode,
+};
+use axum::Router;
+use lib_common::log_macros;
+use lib_common::uuid::Uuid;
+use serde_json::{json, Value};
+use svc_assets::config::{Config, SharedConfig};
+use svc_assets::grpc::client::GrpcClients;
+use svc_assets::repo::Repositories;
+use svc_assets::rest::server::{api_router, RestComponents};
+use svc_assets::rest::{ApiDoc, StartupState};
+use svc_assets::shared::SharedState;
+use svc_assets::testing::{AircraftDataBuilder, VertipadDataBuilder, VertiportDataBuilder};
+use tower::ServiceExt;
+use utoipa::openapi::path::PathItemType;
+use utoipa::OpenApi;
+
+log_macros!("it", "test");
+
+/// UUID of the operator sending the requests of the tests
+const OPERATOR_ID: &str = "8c5e3e0e-6f1c-4d8a-9f5e-2f1d7c0b9a41";
+
+/// Create the REST API router using the provided repositories
+fn app(repositories: Repositories) -> Router {
+    app_with_config(Config::default(), repositories)
+}
+
+/// Create the REST API router using the provided configuration and
+/// repositories
+fn app_with_config(mut config: Config, repositories: Repositories) -> Router {
+    // don't let the rate limit slow down the tests
+    config.rest_request_limit_per_second = u8::MAX;
+    let grpc_clients = GrpcClients::default(config.clone());
+
+    let shared = SharedState::default();
+    shared.startup.set(StartupState::Ready);
+
+    let components = RestComponents::with_repositories(
+        SharedConfig::from(config),
+        shared,
+        grpc_clients,
+        repositories,
+    )
+    .unwrap();
+    api_router(&components)
+}
+
+/// Call the router as [`OPERATOR_ID`], returning the status and the body of
+/// the response
+async fn call(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Vec<u8>) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("x-operator-id", OPERATOR_ID);
+    let request = match body {
+        Some(body) => request
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    send(app, request).await
+}
+
+/// Send a request to the router, returning the status and the body of the
+/// response
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Vec<u8>) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, body.to_vec())
+}
+
+/// Get the HTTP method of an OpenAPI operation
+fn method(operation: &PathItemType) -> Method {
+    match operation {
+        PathItemType::Get => Method::GET,
+        PathItemType::Post => Method::POST,
+        PathItemType::Put => Method::PUT,
+        PathItemType::Delete => Method::DELETE,
+        PathItemType::Options => Method::OPTIONS,
+        PathItemType::Head => Method::HEAD,
+        PathItemType::Patch => Method::PATCH,
+        PathItemType::Trace => Method::TRACE,
+        PathItemType::Connect => Method::CONNECT,
+    }
+}
+
+/// Replace the path parameters of an OpenAPI path by `value`
+fn with_params(path: &str, value: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.starts_with('{') {
+            true => value,
+            false => segment,
+        })
+        .collect::<Vec<&str>>()
+        .join("/")
+}
+
+#[tokio::test]
+async fn test_routes_are_wired() {
+    lib_common::logger::get_log_handle().await;
+    it_info!("start");
+
+    let app = app(Repositories::unavailable());
+
+    // Every handler taking an id checks it before anything else, so an
+    // invalid id reaches the handler without touching storage. A route
+    // missing from the router returns 404 or 405, a handler missing an
+    // extension returns 500.
+    let mut failures = vec![];
+    let mut calls = 0;
+    for (path, item) in ApiDoc::openapi().paths.paths {
+        // the admin endpoints are served by their own router
+        if path.starts_with("/admin") {
+            continue;
+        }
+
+        for operation in item.operations.keys() {
+            let method = method(operation);
+            let body = match method {
+                Method::POST | Method::PUT | Method::PATCH => Some(json!({})),
+                _ => None,
+            };
+
+            for uri in [
+                with_params(&path, "invalid"),
+                format!("/v1{}", with_params(&path, "invalid")),
+            ] {
+                let (status, _) = call(&app, method.clone(), &uri, body.clone()).await;
+                calls += 1;
+                if matches!(
+                    status,
+                    StatusCode::NOT_FOUND
+                        | StatusCode::METHOD_NOT_ALLOWED
+                        | StatusCode::UNSUPPORTED_MEDIA_TYPE
+                        | StatusCode::INTERNAL_SERVER_ERROR
+                ) {
+                    failures.push(format!("{method} {uri}: {status}"));
+                }
+            }
+        }
+    }
+
+    it_info!("called {} routes.", calls);
+    assert!(calls > 0);
+    assert!(failures.is_empty(), "routes not wired: {:#?}", failures);
+
+    // unknown routes are still rejected
+    let (status, _) = call(&app, Method::GET, "/assets/unknown/route", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = call(&app, Method::PATCH, "/assets/aircraft", Some(json!({}))).await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+
+    it_info!("success");
+}
+
+#[tokio::test]
+async fn test_aircraft_routes() {
+    lib_common::logger::get_log_handle().await;
+    it_info!("start");
+
+    let app = app(Repositories::memory());
+
+    let data = AircraftDataBuilder::new().build();
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        "/assets/aircraft",
+        Some(serde_json::to_value(&data).unwrap()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let id = String::from_utf8(body).unwrap();
+    let uri = format!("/assets/aircraft/{id}");
+
+    let (status, body) = call(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let aircraft: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        aircraft["registration_number"],
+        json!(data.registration_number)
+    );
+    assert_eq!(aircraft["basics"]["owner_id"], json!(OPERATOR_ID));
+
+    let (status, _) = call(&app, Method::HEAD, &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = call(&app, Method::GET, "/assets/demo/aircraft", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let list: Vec<Value> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(list.len(), 1);
+    let other_owner = format!("/assets/demo/aircraft?owner_id={}", Uuid::new_v4());
+    let (status, body) = call(&app, Method::GET, &other_owner, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let list: Vec<Value> = serde_json::from_slice(&body).unwrap();
+    assert!(list.is_empty());
+
+    // validation error
+    let (status, _) = call(&app, Method::POST, "/assets/aircraft", Some(json!({}))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    // not found
+    let unknown = format!("/assets/aircraft/{}", Uuid::new_v4());
+    let (status, _) = call(&app, Method::GET, &unknown, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = call(&app, Method::DELETE, &format!("{uri}?force=true"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = call(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    it_info!("success");
+}
+
+#[tokio::test]
+async fn test_tenant_assets_after_restart() {
+    lib_common::logger::get_log_handle().await;
+    it_info!("start");
+
+    let state_dir = std::env::temp_dir().join(format!("svc-assets-{}", Uuid::new_v4()));
+    let mut config = Config::default();
+    config.tenants = vec!["pilot".to_string()];
+    config.api_tokens = vec![
+        format!("pilot-token=tenant:pilot operator:{OPERATOR_ID} assets:read assets:write"),
+        "fleet-token=assets:read assets:write".to_string(),
+    ];
+    config.state_dir = Some(state_dir.to_string_lossy().into_owned());
+    let repositories = Repositories::memory();
+
+    let request = |method: Method, uri: &str, token: &str, tenant: Option<&str>| {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(AUTHORIZATION, format!("Bearer {token}"));
+        if let Some(tenant) = tenant {
+            request = request.header("x-tenant-id", tenant);
+        }
+        request
+    };
+
+    let app = app_with_config(config.clone(), repositories.clone());
+    let data = AircraftDataBuilder::new().build();
+    let (status, body) = send(
+        &app,
+        request(Method::POST, "/assets/aircraft", "pilot-token", None)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&data).unwrap()))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let uri = format!("/assets/aircraft/{}", String::from_utf8(body).unwrap());
+
+    // after a restart the aircraft still belongs to the tenant of the token
+    let app = app_with_config(config, repositories);
+    let (status, _) = send(
+        &app,
+        request(Method::GET, &uri, "fleet-token", None)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(
+        &app,
+        request(Method::GET, &uri, "fleet-token", Some("pilot"))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(
+        &app,
+        request(Method::GET, &uri, "pilot-token", None)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    std::fs::remove_dir_all(state_dir).unwrap();
+    it_info!("success");
+}
+
+#[tokio::test]
+async fn test_vertiport_and_vertipad_routes() {
+    lib_common::logger::get_log_handle().await;
+    it_info!("start");
+
+    let app = app(Repositories::memory());
+
+    let data = VertiportDataBuilder::new().build();
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        "/assets/vertiports",
+        Some(serde_json::to_value(&data).unwrap()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let vertiport_id = String::from_utf8(body).unwrap();
+
+    let data = VertipadDataBuilder::new()
+        .vertiport_id(vertiport_id.clone())
+        .build();
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        "/assets/vertipads",
+        Some(serde_json::to_value(&data).unwrap()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let vertipad_id = String::from_utf8(body).unwrap();
+
+    let (status, _) = call(
+        &app,
+        Method::GET,
+        &format!("/assets/vertiports/{vertiport_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        &format!("/assets/vertipads/{vertipad_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let vertipad: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(vertipad["vertiport_id"], json!(vertiport_id));
+
+    // validation errors
+    let (status, _) = call(&app, Method::POST, "/assets/vertiports", Some(json!({}))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let data = VertipadDataBuilder::new()
+        .vertiport_id(Uuid::new_v4().to_string())
+        .build();
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/assets/vertipads",
+        Some(serde_json::to_value(&data).unwrap()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    // not found
+    for uri in [
+        format!("/assets/vertiports/{}", Uuid::new_v4()),
+        format!("/assets/vertipads/{}", Uuid::new_v4()),
+    ] {
+        let (status, _) = call(&app, Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    it_info!("success");
+}
+
+#[tokio::test]
+async fn test_routes_dependency_down() {
+    lib_common::logger::get_log_handle().await;
+    it_info!("start");
+
+    let app = app(Repositories::unavailable());
+
+    let aircraft = serde_json::to_value(AircraftDataBuilder::new().build()).unwrap();
+    let vertiport = serde_json::to_value(VertiportDataBuilder::new().build()).unwrap();
+    let id = Uuid::new_v4();
+    let requests = [
+        (Method::POST, "/assets/aircraft".to_string(), Some(aircraft)),
+        (Method::GET, format!("/assets/aircraft/{id}"), None),
+        (Method::GET, "/assets/demo/aircraft".to_string(), None),
+        (
+            Method::POST,
+            "/assets/vertiports".to_string(),
+            Some(vertiport),
+        ),
+        (Method::GET, format!("/assets/vertiports/{id}"), None),
+        (Method::GET, format!("/assets/vertipads/{id}"), None),
+        (Method::GET, "/assets/demo/vertipads".to_string(), None),
+        (Method::GET, format!("/assets/{id}"), None),
+    ];
+
+    for (method, uri, body) in requests {
+        let (status, _) = call(&app, method.clone(), &uri, body).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE, "{method} {uri}");
+    }
+
+    it_info!("success");
+}